@@ -5,4 +5,6 @@ pub mod bip39;
 pub mod pointer_types;
 pub mod rng;
 pub mod salt;
+pub mod shamir;
+pub mod webauthn;
 pub mod zip32;
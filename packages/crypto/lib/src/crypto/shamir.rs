@@ -0,0 +1,326 @@
+//! Shamir secret sharing of the mnemonic entropy over GF(256), so the seed can be backed up as
+//! M-of-N paper shares. A share is encoded as `<threshold>-<index>-<fingerprint>-<hex>`, any
+//! `threshold` of them recover the mnemonic and fewer reveal nothing about it but the 4 byte
+//! fingerprint, which doesn't narrow down a 128 bit or longer entropy in practice. The hex data
+//! ends with a checksum of the share, so typos are caught per share, and the fingerprint of the
+//! secret tells shares of different backups apart and verifies the recovered secret.
+use bip39::{Language, Mnemonic as M};
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::crypto::pointer_types::{new_vec_string_pointer, StringPointer, VecStringPointer};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ShamirError {
+    #[error("Invalid threshold! Expected 2 <= threshold <= shares")]
+    InvalidThreshold,
+    #[error("Invalid share: {0}")]
+    InvalidShare(String),
+    #[error("Not enough shares! Expected {0}, received {1}")]
+    NotEnoughShares(u8, usize),
+    #[error("Shares don't belong to the same backup")]
+    MismatchedShares,
+    #[error("Checksum of share {0} doesn't match, it was entered incorrectly")]
+    InvalidChecksum(u8),
+}
+
+const CHECKSUM_LEN: usize = 4;
+const FINGERPRINT_LEN: usize = 4;
+
+// First `N` bytes of the BLAKE2b-256 hash of the data
+fn digest<const N: usize>(data: &[u8]) -> [u8; N] {
+    let digest = orion::hash::digest(data).expect("BLAKE2b-256 accepts any input");
+    let mut prefix = [0u8; N];
+    prefix.copy_from_slice(&digest.as_ref()[..N]);
+    prefix
+}
+
+// Tags the secret, so a wrong combination of shares is detected instead of recovering garbage
+fn fingerprint(secret: &[u8]) -> [u8; FINGERPRINT_LEN] {
+    let mut tagged = Zeroizing::new(b"namada-shamir-fingerprint".to_vec());
+    tagged.extend_from_slice(secret);
+    digest(&tagged)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Share {
+    threshold: u8,
+    // x coordinate, never 0 which is where the secret is
+    index: u8,
+    fingerprint: [u8; FINGERPRINT_LEN],
+    data: Vec<u8>,
+}
+
+impl Share {
+    fn checksum(&self) -> [u8; CHECKSUM_LEN] {
+        let mut bytes = Zeroizing::new(vec![self.threshold, self.index]);
+        bytes.extend_from_slice(&self.fingerprint);
+        bytes.extend_from_slice(&self.data);
+        digest(&bytes)
+    }
+
+    fn encode(&self) -> String {
+        let mut data = Zeroizing::new(self.data.clone());
+        data.extend_from_slice(&self.checksum());
+
+        format!(
+            "{}-{}-{}-{}",
+            self.threshold,
+            self.index,
+            hex::encode(self.fingerprint),
+            hex::encode(&*data)
+        )
+    }
+
+    fn decode(share: &str) -> Result<Share, ShamirError> {
+        let invalid = || ShamirError::InvalidShare(share.to_string());
+        let mut parts = share.trim().splitn(4, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let threshold = next()?.parse::<u8>().map_err(|_| invalid())?;
+        let index = next()?.parse::<u8>().map_err(|_| invalid())?;
+        let fingerprint = hex::decode(next()?)
+            .ok()
+            .and_then(|fingerprint| fingerprint.try_into().ok())
+            .ok_or_else(invalid)?;
+        let mut data = Zeroizing::new(hex::decode(next()?).map_err(|_| invalid())?);
+        if threshold < 2 || index == 0 || data.len() <= CHECKSUM_LEN {
+            return Err(invalid());
+        }
+        let checksum = data.split_off(data.len() - CHECKSUM_LEN);
+
+        let share = Share {
+            threshold,
+            index,
+            fingerprint,
+            data: data.to_vec(),
+        };
+        if share.checksum()[..] != checksum[..] {
+            return Err(ShamirError::InvalidChecksum(index));
+        }
+
+        Ok(share)
+    }
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+// Multiplication in GF(256) with the AES polynomial x^8 + x^4 + x^3 + x + 1. Branches and
+// loop bounds don't depend on the operands, so the timing doesn't leak the secret bytes.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        // All ones if the lowest bit is set, all zeros otherwise
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+// a^254 is the inverse of a, as a^255 = 1 for every non-zero a
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1;
+    for _ in 0..254 {
+        result = gf_mul(result, a);
+    }
+    result
+}
+
+fn split(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<Share>, String> {
+    if threshold < 2 || threshold > count {
+        return Err(ShamirError::InvalidThreshold.to_string());
+    }
+
+    // Random coefficients of degree 1..threshold for every byte of the secret
+    let fingerprint = fingerprint(secret);
+    let degree = threshold as usize - 1;
+    let mut coefficients = Zeroizing::new(vec![0u8; secret.len() * degree]);
+    getrandom::getrandom(&mut coefficients).map_err(|err| err.to_string())?;
+
+    let shares = (1..=count)
+        .map(|index| {
+            let data = secret
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| {
+                    let row = &coefficients[i * degree..(i + 1) * degree];
+                    // Horner's method from the highest coefficient down to the secret
+                    let acc = row
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, coefficient| gf_mul(acc, index) ^ coefficient);
+                    gf_mul(acc, index) ^ byte
+                })
+                .collect();
+
+            Share {
+                threshold,
+                index,
+                fingerprint,
+                data,
+            }
+        })
+        .collect();
+
+    Ok(shares)
+}
+
+fn combine(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>, ShamirError> {
+    let first = shares.first().ok_or(ShamirError::NotEnoughShares(2, 0))?;
+    let threshold = first.threshold;
+    if shares.iter().any(|share| {
+        share.threshold != threshold
+            || share.fingerprint != first.fingerprint
+            || share.data.len() != first.data.len()
+    }) {
+        return Err(ShamirError::MismatchedShares);
+    }
+
+    let mut unique: Vec<&Share> = vec![];
+    for share in shares {
+        match unique.iter().find(|other| other.index == share.index) {
+            Some(other) if other.data != share.data => return Err(ShamirError::MismatchedShares),
+            Some(_) => {}
+            None => unique.push(share),
+        }
+    }
+    if unique.len() < threshold as usize {
+        return Err(ShamirError::NotEnoughShares(threshold, unique.len()));
+    }
+    let shares = &unique[..threshold as usize];
+
+    // Lagrange interpolation at x = 0, subtraction and addition are both XOR in GF(256)
+    let mut secret = Zeroizing::new(vec![0u8; first.data.len()]);
+    for (i, share) in shares.iter().enumerate() {
+        let mut basis = 1;
+        for (j, other) in shares.iter().enumerate() {
+            if i != j {
+                basis = gf_mul(
+                    basis,
+                    gf_mul(other.index, gf_inv(other.index ^ share.index)),
+                );
+            }
+        }
+        for (byte, y) in secret.iter_mut().zip(share.data.iter()) {
+            *byte ^= gf_mul(basis, *y);
+        }
+    }
+    if fingerprint(&secret) != first.fingerprint {
+        return Err(ShamirError::MismatchedShares);
+    }
+
+    Ok(secret)
+}
+
+/// Splits the mnemonic into `n` shares, any `m` of which recover it
+///
+/// # Arguments
+///
+/// * `mnemonic` - BIP39 mnemonic phrase
+/// * `m` - number of shares required to recover the mnemonic, at least 2
+/// * `n` - number of shares created
+#[wasm_bindgen]
+pub fn split_seed(mut mnemonic: String, m: u8, n: u8) -> Result<VecStringPointer, String> {
+    let phrase = M::from_phrase(&mnemonic, Language::English).map_err(|err| err.to_string());
+    mnemonic.zeroize();
+
+    let shares = split(phrase?.entropy(), m, n)?;
+
+    Ok(new_vec_string_pointer(
+        shares.iter().map(Share::encode).collect(),
+    ))
+}
+
+/// Recovers the mnemonic from shares created by `split_seed`
+#[wasm_bindgen]
+pub fn recover_seed(shares: Vec<String>) -> Result<StringPointer, String> {
+    let shares = shares
+        .iter()
+        .map(|share| Share::decode(share))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    let entropy = combine(&shares).map_err(|err| err.to_string())?;
+    let mnemonic = M::from_entropy(&entropy, Language::English)
+        .map_err(|_| ShamirError::MismatchedShares.to_string())?;
+
+    Ok(StringPointer::new(String::from(mnemonic.phrase())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const PHRASE: &str = "caught pig embody hip goose like become worry face oval manual flame";
+
+    #[wasm_bindgen_test]
+    fn can_recover_from_any_threshold_shares() {
+        let shares = split_seed(String::from(PHRASE), 2, 3)
+            .unwrap()
+            .strings
+            .clone();
+
+        for pair in [[0, 1], [0, 2], [2, 1]] {
+            let subset = pair.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(recover_seed(subset).unwrap().string, PHRASE);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn cannot_recover_from_too_few_shares() {
+        let shares = split_seed(String::from(PHRASE), 3, 5)
+            .unwrap()
+            .strings
+            .clone();
+
+        assert!(recover_seed(shares[..2].to_vec()).is_err());
+        assert!(recover_seed(vec![
+            shares[0].clone(),
+            shares[0].clone(),
+            shares[1].clone()
+        ])
+        .is_err());
+        assert!(split_seed(String::from(PHRASE), 1, 3).is_err());
+        assert!(split_seed(String::from(PHRASE), 4, 3).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn detects_typos_and_other_backups() {
+        let shares = split_seed(String::from(PHRASE), 2, 3)
+            .unwrap()
+            .strings
+            .clone();
+        let other = split_seed(String::from(PHRASE), 2, 3)
+            .unwrap()
+            .strings
+            .clone();
+
+        // Flip the last hex digit of the data
+        let mut typo = shares[1].clone();
+        let last = if typo.ends_with('0') { "1" } else { "0" };
+        typo.replace_range(typo.len() - 1.., last);
+        assert_eq!(
+            recover_seed(vec![shares[0].clone(), typo]).err(),
+            Some(ShamirError::InvalidChecksum(2).to_string())
+        );
+
+        // Shares of the same secret split twice have the same fingerprint but don't combine
+        assert_eq!(
+            recover_seed(vec![shares[0].clone(), other[1].clone()]).err(),
+            Some(ShamirError::MismatchedShares.to_string())
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn inverts_in_gf256() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+}
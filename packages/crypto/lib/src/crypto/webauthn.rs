@@ -0,0 +1,149 @@
+//! WebAuthn unlock - wraps the wallet password with a key derived from the PRF extension output
+//! of a WebAuthn credential, so users can unlock with a passkey or biometrics instead of typing
+//! the password. The wrapped password is stored next to the vault and is useless without the
+//! authenticator.
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use orion::hazardous::kdf::hkdf;
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::crypto::pointer_types::{StringPointer, VecU8Pointer};
+
+const INFO: &[u8] = b"namada-interface/webauthn-unlock/v1";
+const NONCE_LENGTH: usize = 12;
+// PRF outputs are 32 bytes, shorter secrets can't be from the extension
+const MIN_PRF_OUTPUT_LENGTH: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum WebAuthnError {
+    #[error("Invalid PRF output! Expected at least {MIN_PRF_OUTPUT_LENGTH} bytes")]
+    PrfOutputLengthError,
+    #[error("Invalid wrapped password! Too short")]
+    WrappedLengthError,
+    #[error("Unlocking failed! Wrong credential or corrupted wrapped password")]
+    UnwrapError,
+}
+
+/// Result of a WebAuthn assertion with the PRF extension
+#[wasm_bindgen]
+pub struct WebAuthnAssertion {
+    credential_id: Vec<u8>,
+    prf_output: VecU8Pointer,
+}
+
+#[wasm_bindgen]
+impl WebAuthnAssertion {
+    /// # Arguments
+    ///
+    /// * `credential_id` - raw id of the credential
+    /// * `prf_output` - `getClientExtensionResults().prf.results.first` of the assertion
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        credential_id: Vec<u8>,
+        prf_output: VecU8Pointer,
+    ) -> Result<WebAuthnAssertion, String> {
+        if prf_output.length < MIN_PRF_OUTPUT_LENGTH {
+            return Err(WebAuthnError::PrfOutputLengthError.to_string());
+        }
+
+        Ok(WebAuthnAssertion {
+            credential_id,
+            prf_output,
+        })
+    }
+}
+
+impl WebAuthnAssertion {
+    // Key is bound to the credential, so PRF outputs of other credentials can't unwrap it
+    fn cipher(&self) -> Result<Aes256Gcm, String> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        hkdf::sha256::derive_key(
+            &self.credential_id,
+            &self.prf_output.vec,
+            Some(INFO),
+            key.as_mut(),
+        )
+        .map_err(|err| err.to_string())?;
+
+        Ok(Aes256Gcm::new(GenericArray::from_slice(key.as_ref())))
+    }
+}
+
+/// Encrypts the wallet password with the assertion, returns the nonce followed by the ciphertext
+#[wasm_bindgen]
+pub fn wrap_with_webauthn(
+    assertion: &WebAuthnAssertion,
+    mut password: String,
+) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    getrandom::getrandom(&mut nonce).map_err(|err| err.to_string())?;
+
+    let ciphertext = assertion
+        .cipher()?
+        .encrypt(Nonce::from_slice(&nonce), password.as_ref())
+        .map_err(|err| err.to_string());
+    password.zeroize();
+
+    Ok([nonce.to_vec(), ciphertext?].concat())
+}
+
+/// Decrypts the wallet password wrapped by `wrap_with_webauthn`
+#[wasm_bindgen]
+pub fn unlock_with_webauthn(
+    assertion: &WebAuthnAssertion,
+    wrapped: Vec<u8>,
+) -> Result<StringPointer, String> {
+    if wrapped.len() <= NONCE_LENGTH {
+        return Err(WebAuthnError::WrappedLengthError.to_string());
+    }
+    let (nonce, ciphertext) = wrapped.split_at(NONCE_LENGTH);
+
+    let plaintext = assertion
+        .cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| WebAuthnError::UnwrapError.to_string())?;
+    let password = String::from_utf8(plaintext).map_err(|err| {
+        let mut bytes = err.into_bytes();
+        bytes.zeroize();
+        WebAuthnError::UnwrapError.to_string()
+    })?;
+
+    Ok(StringPointer::new(password))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn assertion(credential_id: &[u8], prf_output: u8) -> WebAuthnAssertion {
+        WebAuthnAssertion::new(
+            credential_id.to_vec(),
+            VecU8Pointer::new(vec![prf_output; 32]),
+        )
+        .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn can_wrap_and_unlock() {
+        let assertion = assertion(b"credential", 1);
+        let wrapped = wrap_with_webauthn(&assertion, String::from("password")).unwrap();
+
+        let password = unlock_with_webauthn(&assertion, wrapped).unwrap();
+        assert_eq!(password.string, "password");
+    }
+
+    #[wasm_bindgen_test]
+    fn cannot_unlock_with_other_credential() {
+        let wrapped =
+            wrap_with_webauthn(&assertion(b"credential", 1), String::from("password")).unwrap();
+
+        assert!(unlock_with_webauthn(&assertion(b"credential", 2), wrapped.clone()).is_err());
+        assert!(unlock_with_webauthn(&assertion(b"other", 1), wrapped).is_err());
+        assert!(WebAuthnAssertion::new(vec![], VecU8Pointer::new(vec![1; 16])).is_err());
+    }
+}
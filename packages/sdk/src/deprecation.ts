@@ -0,0 +1,36 @@
+/**
+ * Receives a message for every deprecated entry point, once per entry point
+ */
+export type DeprecationHandler = (message: string) => void;
+
+const defaultHandler: DeprecationHandler = (message) => console.warn(message);
+
+let handler: DeprecationHandler = defaultHandler;
+const warned = new Set<string>();
+
+/**
+ * Route deprecation warnings somewhere other than the console, e.g. to telemetry
+ * @param [newHandler] - handler receiving the warnings, resets to console.warn if not provided
+ * @returns void
+ */
+export const setDeprecationHandler = (
+  newHandler?: DeprecationHandler
+): void => {
+  handler = newHandler || defaultHandler;
+};
+
+/**
+ * Warn that a deprecated entry point was used. Every entry point is reported only once
+ * @param name - deprecated entry point, e.g. "Sdk.getRpc"
+ * @param replacement - entry point to use instead
+ * @returns void
+ */
+export const warnDeprecated = (name: string, replacement: string): void => {
+  if (warned.has(name)) {
+    return;
+  }
+  warned.add(name);
+  handler(
+    `[@namada/sdk] ${name} is deprecated and will be removed in the next major version, use ${replacement} instead`
+  );
+};
@@ -18,7 +18,12 @@ export type {
   CryptoRecord,
   EncryptionParams,
 } from "./crypto";
-export type { Address, ShieldedKeys, TransparentKeys } from "./keys";
+export type {
+  Address,
+  ShieldedKeys,
+  SigningScheme,
+  TransparentKeys,
+} from "./keys";
 export type {
   Balance,
   Bonds,
@@ -34,6 +39,13 @@ export { TxType, TxTypeLabel } from "./tx";
 export type { SupportedTx } from "./tx";
 
 export { ProgressBarNames, Sdk, SdkEvents } from "./sdk";
+export { SdkV1 } from "./sdkV1";
+
+export { setDeprecationHandler } from "./deprecation";
+export type { DeprecationHandler } from "./deprecation";
+
+export { fieldError } from "./utils";
+export type { FieldError } from "@namada/shared";
 
 export { publicKeyToBech32 } from "./keys";
 
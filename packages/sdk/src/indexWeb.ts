@@ -32,6 +32,29 @@ export function getSdk(
   return new Sdk(sdk, query, cryptoMemory, url, token);
 }
 
+/**
+ * Get a read-only SDK instance, all signing and submitting calls are rejected
+ * @param cryptoMemory - WebAssembly.Memory of crypto package
+ * @param url - URL of the node
+ * @param maspIndexerUrl - optional URL of the MASP indexer
+ * @param token - Native token of the chain
+ * @returns - Sdk instance
+ */
+export function getReadOnlySdk(
+  cryptoMemory: WebAssembly.Memory,
+  url: string,
+  maspIndexerUrl: string,
+  token: string
+): Sdk {
+  // We change empty string to undefined so it "maps" to the Option<String> in Rust
+  const maspIndexerUrlOpt =
+    maspIndexerUrl.length === 0 ? undefined : maspIndexerUrl;
+  const query = new QueryWasm(url, maspIndexerUrlOpt);
+
+  const sdk = SdkWasm.new_read_only(url, token);
+  return new Sdk(sdk, query, cryptoMemory, url, token);
+}
+
 /**
  * Query native token from the node
  * @async
@@ -15,7 +15,12 @@ import {
 } from "@namada/shared";
 import { Bip44Path, Zip32Path } from "@namada/types";
 import { makeBip44PathArray, makeSaplingPathArray } from "../utils";
-import { Address, ShieldedKeys, TransparentKeys } from "./types";
+import {
+  Address,
+  ShieldedKeys,
+  SigningScheme,
+  TransparentKeys,
+} from "./types";
 
 const DEFAULT_BIP44_PATH: Bip44Path = {
   account: 0,
@@ -39,10 +44,11 @@ export class Keys {
   /**
    * Get address and public key from private key
    * @param privateKey - Private key
+   * @param [scheme] - Signing scheme of the key, defaults to "ed25519"
    * @returns Address and public key
    */
-  getAddress(privateKey: string): Address {
-    const addr = new AddressWasm(privateKey);
+  getAddress(privateKey: string, scheme?: SigningScheme): Address {
+    const addr = new AddressWasm(privateKey, scheme);
     const address = addr.implicit();
     const publicKey = addr.public();
 
@@ -55,11 +61,12 @@ export class Keys {
   /**
    * Get transparent keys and address from private key
    * @param privateKey - Private key
+   * @param [scheme] - Signing scheme of the key, defaults to "ed25519"
    * @returns Keys and address
    */
-  fromPrivateKey(privateKey: string): TransparentKeys {
+  fromPrivateKey(privateKey: string, scheme?: SigningScheme): TransparentKeys {
     return {
-      ...this.getAddress(privateKey),
+      ...this.getAddress(privateKey, scheme),
       privateKey,
     };
   }
@@ -1,3 +1,8 @@
+/**
+ * Signing scheme of transparent keys
+ */
+export type SigningScheme = "ed25519" | "secp256k1";
+
 /**
  * Address and public key type
  */
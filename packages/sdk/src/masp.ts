@@ -54,10 +54,15 @@ export class Masp {
    * @async
    * @param xvk - extended viewing key
    * @param alias - alias for the key
+   * @param birthday - optional height the key was created at, earlier blocks are not synced
    * @returns void
    */
-  async addViewingKey(xvk: string, alias: string): Promise<void> {
-    return await this.sdk.add_viewing_key(xvk, alias);
+  async addViewingKey(
+    xvk: string,
+    alias: string,
+    birthday?: bigint
+  ): Promise<void> {
+    return await this.sdk.add_viewing_key(xvk, alias, birthday);
   }
 
   /**
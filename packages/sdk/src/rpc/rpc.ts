@@ -1,10 +1,36 @@
 import { deserialize } from "@dao-xyz/borsh";
+import BigNumber from "bignumber.js";
 import {
+  AbciQueryResult,
+  BondPreflight,
+  BridgeFeeEstimate,
+  ChainCapabilities,
+  CompactScanResult,
+  Erc20Token,
+  EventFilter,
+  FailureExplanation,
+  KeySyncStatus,
+  MemoryUsage,
+  NamadaEvent,
+  NodeHealth,
+  NutBalance,
+  PortfolioEntry,
   Query as QueryWasm,
+  ScheduledTxInfo,
+  ScheduledTxResult,
   Sdk as SdkWasm,
+  ShieldedIntegrityReport,
+  StakingBatch,
+  SubmitResult,
   TransferToEthereum,
+  VestingSchedule,
+  VotingPowerStatement,
+  check_node,
+  get_memory_usage,
 } from "@namada/shared";
 import {
+  BondSplitMsgValue,
+  BondSplitProps,
   Message,
   TxResponseMsgValue,
   TxResponseProps,
@@ -38,17 +64,80 @@ export class Rpc {
     protected readonly query: QueryWasm
   ) { }
 
+  /**
+   * Return an Rpc whose queries send headers merged over the default ones,
+   * e.g. a different API key for a single request. Txs are still broadcast
+   * with the headers the Sdk was created with.
+   * @param headers - Header names mapped to values
+   * @returns Rpc using the overridden headers
+   */
+  withHeaders(headers: Record<string, string>): Rpc {
+    return new Rpc(this.sdk, this.query.with_headers(headers));
+  }
+
   /**
    * Query balances from chain
    * @async
    * @param owner - Owner address
    * @param tokens - Array of token addresses
-   * @returns [[tokenAddress, amount]]
+   * @returns [[tokenAddress, amount, status]], tokens outside the token
+   * allowlist are marked "unknown" or "look_alike"
    */
   async queryBalance(owner: string, tokens: string[]): Promise<Balance> {
     return await this.query.query_balance(owner, tokens);
   }
 
+  /**
+   * Query holdings of accounts and viewing keys summed per token
+   * @async
+   * @param addresses - Transparent account addresses
+   * @param viewingKeys - Extended viewing keys, requires shielded sync
+   * @param tokens - Array of token addresses
+   * @returns Transparent, shielded, bonded, unbonding and total amounts per
+   * token, tokens outside the token allowlist are marked "unknown" or
+   * "look_alike"
+   */
+  async getPortfolio(
+    addresses: string[],
+    viewingKeys: string[],
+    tokens: string[]
+  ): Promise<PortfolioEntry[]> {
+    return await this.query.get_portfolio(addresses, viewingKeys, tokens);
+  }
+
+  /**
+   * Set the price oracle valuing exported tx history in fiat
+   * @param urlTemplate - Endpoint URL with {token}, {currency} and optionally
+   * {timestamp} placeholders, returning { "price": "1.23" }. Pass undefined
+   * to remove the oracle
+   * @param currency - Fiat currency, e.g. "usd"
+   * @returns void
+   */
+  setPriceOracle(urlTemplate: string | undefined, currency: string): void {
+    return this.sdk.set_price_oracle(urlTemplate, currency);
+  }
+
+  /**
+   * Export tx history of addresses for tax and accounting
+   * @async
+   * @param addresses - Transparent account addresses
+   * @param range - Block range, to the latest block by default
+   * @param format - "csv" or "json"
+   * @returns CSV or JSON with one row per transfer, bond, unbond or fee
+   */
+  async exportHistory(
+    addresses: string[],
+    range: { fromHeight: bigint; toHeight?: bigint },
+    format: "csv" | "json"
+  ): Promise<string> {
+    return await this.sdk.export_history(
+      addresses,
+      range.fromHeight,
+      range.toHeight,
+      format
+    );
+  }
+
   /**
    * Query native token from chain
    * @async
@@ -167,6 +256,47 @@ export class Rpc {
     };
   }
 
+  /**
+   * Query native tokens of an address split into spendable and locked funds
+   * @async
+   * @param address - Owner address
+   * @returns Spendable, bonded, unbonding, withdrawable and slashed amounts and upcoming unlocks
+   */
+  async queryVestingSchedule(address: string): Promise<VestingSchedule> {
+    return await this.query.query_vesting_schedule(address);
+  }
+
+  /**
+   * Check a validator before bonding to it
+   * @async
+   * @param validator - Validator address
+   * @param amount - Amount of native tokens to bond
+   * @returns Warnings about the validator, the bond should be confirmed explicitly
+   * if requires_confirmation is true
+   */
+  async bondPreflight(
+    validator: string,
+    amount: BigNumber
+  ): Promise<BondPreflight> {
+    return await this.query.bond_preflight(validator, amount.toString());
+  }
+
+  /**
+   * Query a statement of the voting power of an address at the snapshot epoch
+   * of a proposal. Only the snapshot epoch is proven, not the voting power.
+   * @async
+   * @param address - Voter address
+   * @param proposalId - Id of the proposal
+   * @returns Voting power, its hash and the Merkle proof of the snapshot epoch
+   * if available
+   */
+  async votingPowerStatement(
+    address: string,
+    proposalId: bigint
+  ): Promise<VotingPowerStatement> {
+    return await this.query.voting_power_statement(address, proposalId);
+  }
+
   /**
    * Query total bonds by owner address
    * @param owner - Owner address
@@ -176,6 +306,136 @@ export class Rpc {
     return await this.query.query_total_bonds(owner);
   }
 
+  /**
+   * Query ERC20 tokens whitelisted by the Ethereum bridge. Symbols and decimals come from
+   * the mapping set with set_erc20_metadata, decimals fall back to the on-chain denomination
+   * @async
+   * @returns Promise resolving to whitelisted tokens with their bridge cap
+   */
+  async queryBridgeErc20Whitelist(): Promise<Erc20Token[]> {
+    return await this.query.query_bridge_erc20_whitelist();
+  }
+
+  /**
+   * Estimate the total cost of a transfer to Ethereum, combining the Namada fee with the
+   * relayer fee quoted by set_relayer_quotes
+   * @async
+   * @param asset - ERC20 address
+   * @param amount - Transferred amount
+   * @param [feeToken] - Token the Namada fee is paid in, native token by default
+   * @param [gasLimit] - Gas limit of the bridge pool tx
+   * @returns Promise resolving to the Namada and relayer fees and their sum by token
+   */
+  async estimateBridgeFee(
+    asset: string,
+    amount: BigNumber,
+    feeToken?: string,
+    gasLimit?: bigint
+  ): Promise<BridgeFeeEstimate> {
+    return await this.query.estimate_bridge_fee(
+      asset,
+      amount.toString(),
+      feeToken,
+      gasLimit
+    );
+  }
+
+  /**
+   * Query non-usable tokens (NUTs) of an owner, minted for bridged assets that could not be
+   * received as the ERC20 token. They can be recovered with Tx.buildNutRecovery
+   * @async
+   * @param owner - Owner address
+   * @returns Promise resolving to the non-zero NUT balances
+   */
+  async queryNutBalances(owner: string): Promise<NutBalance[]> {
+    return await this.query.query_nut_balances(owner);
+  }
+
+  /**
+   * Send a raw ABCI query, for storage keys and queries the SDK doesn't wrap
+   * @async
+   * @param path - ABCI query path
+   * @param [data] - query data
+   * @param [height] - height to query at, latest block by default
+   * @param [prove] - whether to request a Merkle proof of the response
+   * @returns Promise resolving to the hex encoded response, its height and proof
+   */
+  async abciQuery(
+    path: string,
+    data?: Uint8Array,
+    height?: bigint,
+    prove = false
+  ): Promise<AbciQueryResult> {
+    return await this.sdk.abci_query(path, data, height, prove);
+  }
+
+  /**
+   * Query the raw value stored under a storage key. Keys can be built with balance_key,
+   * bond_key and proposal_key and values decoded with decode_borsh
+   * @async
+   * @param key - Storage key
+   * @returns Promise resolving to the Borsh encoded value, undefined if the key is not set
+   */
+  async queryStorageValue(key: string): Promise<Uint8Array | undefined> {
+    return await this.query.query_storage_value(key);
+  }
+
+  /**
+   * Query events matching the filter with their Namada attributes decoded. A filter with
+   * only a height returns all events of the block
+   * @async
+   * @param filter - tx hash, event type, attributes and height range to match
+   * @param [page] - page number of the tx search, starting from 1
+   * @param [perPage] - number of txs per page
+   * @param [order] - "asc" or "desc"
+   * @returns Promise resolving to the matching events
+   */
+  async queryEvents(
+    filter: EventFilter,
+    page = 1,
+    perPage = 30,
+    order?: "asc" | "desc"
+  ): Promise<NamadaEvent[]> {
+    return await this.query.query_events(filter, page, perPage, order);
+  }
+
+  /**
+   * Set the RPC of an archival node, which serves block, validator and dry-run
+   * queries of heights the RPC node pruned, and the genesis block the Sdk
+   * checks the bound genesis hash against. Without it such queries throw a
+   * pruned height error naming the earliest height of the node.
+   * @param [url] - archival RPC url, undefined removes it
+   * @returns void
+   */
+  setArchivalRpc(url?: string): void {
+    this.query.set_archival_rpc(url);
+    this.sdk.set_archival_rpc(url);
+  }
+
+  /**
+   * Probe an RPC endpoint before switching to it, e.g. a custom RPC entered by
+   * the user. An unreachable node is reported in the result instead of throwing.
+   * @async
+   * @param url - RPC url to check
+   * @param [timeoutMs] - timeout of each request, defaults to 5 seconds
+   * @returns Promise resolving to reachability, latency, chain ID, sync status,
+   * earliest block and whether txs are indexed
+   */
+  async checkNode(url: string, timeoutMs?: number): Promise<NodeHealth> {
+    return await check_node(url, timeoutMs);
+  }
+
+  /**
+   * Explain why a tx failed, e.g. which VPs rejected it or whether it ran out of gas. The tx
+   * is also dry-run at the height before it was included, when the node keeps that state
+   * @async
+   * @param txHash - hash of the tx
+   * @returns Promise resolving to the reason, inner tx results and dry-run results
+   */
+  async explainFailure(txHash: string): Promise<FailureExplanation> {
+    return await this.query.explain_failure(txHash);
+  }
+
   /**
    * Query pending transactions in the signed bridge pool
    * @async
@@ -232,14 +492,609 @@ export class Rpc {
     return deserialize(Buffer.from(response), TxResponseMsgValue);
   }
 
+  /**
+   * Broadcast several Txs concurrently, Txs of the same fee payer are broadcast in order
+   * @async
+   * @param txs - Array of signed Tx bytes and the WrapperTxProps they were built with
+   * @returns Array with a TxResponseProps object or an error for every Tx, in order
+   */
+  async broadcastAll(
+    txs: { signedTxBytes: Uint8Array; args: WrapperTxProps }[]
+  ): Promise<{ response?: TxResponseProps; error?: string }[]> {
+    const requests = txs.map(({ signedTxBytes, args }) => ({
+      tx_bytes: Array.from(signedTxBytes),
+      wrapper_tx_msg: Array.from(
+        new Message<WrapperTxMsgValue>().encode(new WrapperTxMsgValue(args))
+      ),
+    }));
+
+    const results: SubmitResult[] = await this.sdk.submit_all(requests);
+    return results.map(({ response, error }) => ({
+      response:
+        response && deserialize(Buffer.from(response), TxResponseMsgValue),
+      error,
+    }));
+  }
+
+  /**
+   * Build, sign and broadcast a shielding transfer, revealing the public key of the source first
+   * if needed. The source pays the fees.
+   * @async
+   * @param args - WrapperTxProps, publicKey is set from privateKey
+   * @param amount - amount to shield
+   * @param token - address of the token
+   * @param fromTransparent - transparent source, owner of privateKey
+   * @param toPaymentAddress - shielded target
+   * @param privateKey - hex encoded private key of the source
+   * @returns TxResponseProps object
+   */
+  async submitShield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromTransparent: string,
+    toPaymentAddress: string,
+    privateKey: string
+  ): Promise<TxResponseProps> {
+    const response = await this.shield(
+      args,
+      amount,
+      token,
+      fromTransparent,
+      toPaymentAddress,
+      privateKey,
+      false
+    );
+    return deserialize(
+      Buffer.from(response as Uint8Array),
+      TxResponseMsgValue
+    );
+  }
+
+  /**
+   * Build and sign a shielding transfer without broadcasting it, see submitShield
+   * @async
+   * @param args - WrapperTxProps, publicKey is set from privateKey
+   * @param amount - amount to shield
+   * @param token - address of the token
+   * @param fromTransparent - transparent source, owner of privateKey
+   * @param toPaymentAddress - shielded target
+   * @param privateKey - hex encoded private key of the source
+   * @returns signed Txs in broadcast order, the reveal of the public key first if needed
+   */
+  async signShield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromTransparent: string,
+    toPaymentAddress: string,
+    privateKey: string
+  ): Promise<Uint8Array[]> {
+    const signedTxs = await this.shield(
+      args,
+      amount,
+      token,
+      fromTransparent,
+      toPaymentAddress,
+      privateKey,
+      true
+    );
+    return toSignedTxs(signedTxs as number[][]);
+  }
+
+  /**
+   * Build, sign and broadcast bonds to several validators in a single wrapper.
+   * The public key of the source is revealed first if needed.
+   * @async
+   * @param args - WrapperTxProps, publicKey is set from privateKey
+   * @param bondSplitProps - source and the validators with the amounts bonded to them
+   * @param privateKey - hex encoded key of the source, also paying the fees
+   * @returns TxResponseProps object
+   */
+  async submitBondSplit(
+    args: WrapperTxProps,
+    bondSplitProps: BondSplitProps,
+    privateKey: string
+  ): Promise<TxResponseProps> {
+    const response = await this.bondSplit(
+      args,
+      bondSplitProps,
+      privateKey,
+      false
+    );
+    return deserialize(
+      Buffer.from(response as Uint8Array),
+      TxResponseMsgValue
+    );
+  }
+
+  /**
+   * Build and sign bonds to several validators without broadcasting them, see submitBondSplit
+   * @async
+   * @param args - WrapperTxProps, publicKey is set from privateKey
+   * @param bondSplitProps - source and the validators with the amounts bonded to them
+   * @param privateKey - hex encoded key of the source, also paying the fees
+   * @returns signed Txs in broadcast order, the reveal of the public key first if needed
+   */
+  async signBondSplit(
+    args: WrapperTxProps,
+    bondSplitProps: BondSplitProps,
+    privateKey: string
+  ): Promise<Uint8Array[]> {
+    const signedTxs = await this.bondSplit(
+      args,
+      bondSplitProps,
+      privateKey,
+      true
+    );
+    return toSignedTxs(signedTxs as number[][]);
+  }
+
+  /**
+   * Build, sign and broadcast a batch unbonding everything the source has bonded
+   * @async
+   * @param args - WrapperTxProps
+   * @param source - address of the delegator
+   * @param privateKey - hex encoded key of the source
+   * @param [confirm] - called with the summary of the batch before signing, has to
+   * resolve to true for the batch to be submitted
+   * @returns TxResponseProps object
+   */
+  async submitUnbondAll(
+    args: WrapperTxProps,
+    source: string,
+    privateKey: string,
+    confirm?: (summary: StakingBatch) => boolean | Promise<boolean>
+  ): Promise<TxResponseProps> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    const response = await this.sdk.submit_unbond_all(
+      source,
+      privateKey,
+      encodedArgs,
+      confirm,
+      false
+    );
+    return deserialize(Buffer.from(response), TxResponseMsgValue);
+  }
+
+  /**
+   * Build and sign a batch unbonding everything the source has bonded without broadcasting it
+   * @async
+   * @param args - WrapperTxProps
+   * @param source - address of the delegator
+   * @param privateKey - hex encoded key of the source
+   * @param [confirm] - called with the summary of the batch before signing, has to
+   * resolve to true for the batch to be signed
+   * @returns signed Txs, the batch being the only one
+   */
+  async signUnbondAll(
+    args: WrapperTxProps,
+    source: string,
+    privateKey: string,
+    confirm?: (summary: StakingBatch) => boolean | Promise<boolean>
+  ): Promise<Uint8Array[]> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    const signedTxs = await this.sdk.submit_unbond_all(
+      source,
+      privateKey,
+      encodedArgs,
+      confirm,
+      true
+    );
+    return toSignedTxs(signedTxs);
+  }
+
+  /**
+   * Build, sign and broadcast a batch withdrawing every matured unbond of the source
+   * @async
+   * @param args - WrapperTxProps
+   * @param source - address of the delegator
+   * @param privateKey - hex encoded key of the source
+   * @param [confirm] - called with the summary of the batch before signing, has to
+   * resolve to true for the batch to be submitted
+   * @returns TxResponseProps object
+   */
+  async submitWithdrawAll(
+    args: WrapperTxProps,
+    source: string,
+    privateKey: string,
+    confirm?: (summary: StakingBatch) => boolean | Promise<boolean>
+  ): Promise<TxResponseProps> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    const response = await this.sdk.submit_withdraw_all(
+      source,
+      privateKey,
+      encodedArgs,
+      confirm,
+      false
+    );
+    return deserialize(Buffer.from(response), TxResponseMsgValue);
+  }
+
+  /**
+   * Build and sign a batch withdrawing every matured unbond of the source without broadcasting it
+   * @async
+   * @param args - WrapperTxProps
+   * @param source - address of the delegator
+   * @param privateKey - hex encoded key of the source
+   * @param [confirm] - called with the summary of the batch before signing, has to
+   * resolve to true for the batch to be signed
+   * @returns signed Txs, the batch being the only one
+   */
+  async signWithdrawAll(
+    args: WrapperTxProps,
+    source: string,
+    privateKey: string,
+    confirm?: (summary: StakingBatch) => boolean | Promise<boolean>
+  ): Promise<Uint8Array[]> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    const signedTxs = await this.sdk.submit_withdraw_all(
+      source,
+      privateKey,
+      encodedArgs,
+      confirm,
+      true
+    );
+    return toSignedTxs(signedTxs);
+  }
+
+  /**
+   * Build, sign and broadcast an unshielding transfer. Fees are paid from the shielded balance
+   * and the wrapper is signed with a disposable key.
+   * @async
+   * @param args - WrapperTxProps, publicKey is set to the disposable key
+   * @param amount - amount to unshield
+   * @param token - address of the token
+   * @param fromSpendingKey - shielded source, also paying the fees
+   * @param toTransparent - transparent target
+   * @returns TxResponseProps object
+   */
+  async submitUnshield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromSpendingKey: string,
+    toTransparent: string
+  ): Promise<TxResponseProps> {
+    const response = await this.unshield(
+      args,
+      amount,
+      token,
+      fromSpendingKey,
+      toTransparent,
+      false
+    );
+    return deserialize(
+      Buffer.from(response as Uint8Array),
+      TxResponseMsgValue
+    );
+  }
+
+  /**
+   * Build and sign an unshielding transfer without broadcasting it, see submitUnshield
+   * @async
+   * @param args - WrapperTxProps, publicKey is set to the disposable key
+   * @param amount - amount to unshield
+   * @param token - address of the token
+   * @param fromSpendingKey - shielded source, also paying the fees
+   * @param toTransparent - transparent target
+   * @returns signed Txs, the transfer being the only one
+   */
+  async signUnshield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromSpendingKey: string,
+    toTransparent: string
+  ): Promise<Uint8Array[]> {
+    const signedTxs = await this.unshield(
+      args,
+      amount,
+      token,
+      fromSpendingKey,
+      toTransparent,
+      true
+    );
+    return toSignedTxs(signedTxs as number[][]);
+  }
+
+  /**
+   * Set the store the outbox is persisted in and load the Txs saved there
+   * @async
+   * @param save - Function called with the encoded outbox
+   * @param load - Function returning the saved outbox or undefined
+   * @returns void
+   */
+  async setOutboxStore(
+    save: (outbox: Uint8Array) => void | Promise<void>,
+    load: () => Uint8Array | undefined | Promise<Uint8Array | undefined>
+  ): Promise<void> {
+    return await this.sdk.set_outbox_store(save, load);
+  }
+
+  /**
+   * Add a signed Tx to the outbox, it is broadcast by processOutbox once notBefore passes
+   * @async
+   * @param signedTxBytes - Transaction with signature
+   * @param args - WrapperTxProps
+   * @param notBefore - start of the window, in seconds since the Unix epoch
+   * @param notAfter - end of the window, in seconds since the Unix epoch
+   * @returns id of the scheduled Tx
+   */
+  async scheduleTx(
+    signedTxBytes: Uint8Array,
+    args: WrapperTxProps,
+    notBefore: number,
+    notAfter: number
+  ): Promise<number> {
+    const wrapperTxMsgValue = new WrapperTxMsgValue(args);
+    const msg = new Message<WrapperTxMsgValue>();
+    const encodedArgs = msg.encode(wrapperTxMsgValue);
+
+    return await this.sdk.schedule_tx(
+      signedTxBytes,
+      encodedArgs,
+      BigInt(notBefore),
+      BigInt(notAfter)
+    );
+  }
+
+  /**
+   * List Txs waiting in the outbox
+   * @returns Array of scheduled Txs and their windows
+   */
+  scheduledTxs(): ScheduledTxInfo[] {
+    return this.sdk.scheduled_txs();
+  }
+
+  /**
+   * Remove a Tx from the outbox
+   * @async
+   * @param id - id of the scheduled Tx
+   * @returns false if there was no such Tx
+   */
+  async cancelScheduledTx(id: number): Promise<boolean> {
+    return await this.sdk.cancel_scheduled_tx(id);
+  }
+
+  /**
+   * Broadcast outbox Txs whose window is open and drop expired ones
+   * @async
+   * @returns Array of { id, error } for every Tx taken from the outbox
+   */
+  async processOutbox(): Promise<ScheduledTxResult[]> {
+    return await this.sdk.process_outbox();
+  }
+
+  /**
+   * Periodically process the outbox
+   * @param intervalMs - polling interval in milliseconds
+   * @param onResults - called with the results of every non-empty run
+   * @returns function stopping the polling
+   */
+  watchOutbox(
+    intervalMs: number,
+    onResults: (results: ScheduledTxResult[]) => void
+  ): () => void {
+    const interval = setInterval(async () => {
+      const results = await this.processOutbox();
+      if (results.length > 0) {
+        onResults(results);
+      }
+    }, intervalMs);
+
+    return () => clearInterval(interval);
+  }
+
   /**
    * Sync the shielded context
    * @async
    * @param vks - Array of viewing keys
    * @param sks - Array of spending keys
+   * @param birthdays - optional heights the keys were created at, vks followed by sks.
+   * Blocks before the birthday of a key are not scanned for it, 0 if unknown
    * @returns
    */
-  async shieldedSync(vks: string[], sks: string[] = []): Promise<void> {
-    await this.query.shielded_sync(vks, sks);
+  async shieldedSync(
+    vks: string[],
+    sks: string[] = [],
+    birthdays?: bigint[]
+  ): Promise<void> {
+    await this.query.shielded_sync(
+      vks,
+      sks,
+      birthdays && BigUint64Array.from(birthdays)
+    );
+  }
+
+  /**
+   * Limit memory used by shielded sync, for hosts with little RAM
+   * @param maxCachedBlocks - optional maximum of blocks fetched and held at once
+   * @param pruneSpentWitnesses - drop witnesses of spent notes after every sync
+   */
+  setMemoryLimits(
+    maxCachedBlocks: number | undefined,
+    pruneSpentWitnesses: boolean
+  ): void {
+    this.query.set_memory_limits(maxCachedBlocks, pruneSpentWitnesses);
+  }
+
+  /**
+   * Get the size of the wasm heap, so the host can throttle work before it runs out of memory
+   * @returns bytes allocated by the wasm module
+   */
+  getMemoryUsage(): MemoryUsage {
+    return get_memory_usage();
+  }
+
+  /**
+   * Get the sync status of each viewing key, so accounts can show their own sync progress
+   * @async
+   * @param vks - Array of viewing keys
+   * @returns height each key was last synced to and its notes
+   */
+  async getSyncStatus(vks: string[]): Promise<KeySyncStatus[]> {
+    return await this.query.get_sync_status(vks);
+  }
+
+  /**
+   * Load a trusted commitment tree checkpoint, so shieldedSync of a new account starts at the
+   * checkpoint height instead of genesis. Notes received before the checkpoint are not found.
+   * @async
+   * @param checkpoint - Borsh encoded TreeCheckpoint bundled for the chain
+   * @returns height of the checkpoint
+   */
+  async loadTreeCheckpoint(checkpoint: Uint8Array): Promise<bigint> {
+    return await this.query.load_tree_checkpoint(checkpoint);
+  }
+
+  /**
+   * Check the stored shielded context for corruption, e.g. a commitment tree root unknown to
+   * the chain or witnesses which don't match the tree
+   * @async
+   * @returns issues found and whether rescanShielded is suggested
+   */
+  async checkShieldedState(): Promise<ShieldedIntegrityReport> {
+    return await this.query.check_shielded_state();
+  }
+
+  /**
+   * Drop decrypted notes and witnesses above fromHeight and sync again. The sync restarts from
+   * the newest snapshot taken while syncing below fromHeight. Without one it restarts from the
+   * loaded checkpoint if it is not above fromHeight, otherwise from genesis.
+   * @async
+   * @param fromHeight - first height which is rescanned
+   * @param vks - Array of viewing keys
+   * @param sks - Array of spending keys
+   * @returns height the sync restarted from
+   */
+  async rescanShielded(
+    fromHeight: bigint,
+    vks: string[],
+    sks: string[] = []
+  ): Promise<bigint> {
+    return await this.query.rescan_shielded(fromHeight, vks, sks);
+  }
+
+  /**
+   * Scan compact blocks for notes of the viewing keys. ingestCompactBlocks also applies the
+   * result to the shielded context.
+   * @param vks - Array of viewing keys
+   * @param blocks - Borsh encoded compact blocks
+   * @returns Highest scanned height and the outputs decrypted by the viewing keys
+   */
+  scanCompactBlocks(vks: string[], blocks: Uint8Array): CompactScanResult {
+    return this.query.scan_compact_blocks(vks, blocks);
+  }
+
+  /**
+   * Ingest compact blocks into the shielded context. The context is synced if the blocks hold
+   * notes of the keys or spend their notes, otherwise the keys are marked as synced up to the
+   * last block without downloading full blocks.
+   * @async
+   * @param vks - Array of viewing keys
+   * @param sks - Array of spending keys
+   * @param blocks - Borsh encoded compact blocks of every block with MASP txs since the last
+   * sync of the keys
+   * @returns true if the full sync ran
+   */
+  async ingestCompactBlocks(
+    vks: string[],
+    sks: string[],
+    blocks: Uint8Array
+  ): Promise<boolean> {
+    return await this.query.ingest_compact_blocks(vks, sks, blocks);
+  }
+
+  /**
+   * Query tx types and features supported by the chain
+   * @async
+   * @returns Supported tx types, MASP and bridge availability and governance version
+   */
+  async getChainCapabilities(): Promise<ChainCapabilities> {
+    return await this.sdk.get_chain_capabilities();
+  }
+
+  private async shield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromTransparent: string,
+    toPaymentAddress: string,
+    privateKey: string,
+    signOnly: boolean
+  ): Promise<unknown> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    return await this.sdk.submit_shield(
+      amount.toString(),
+      token,
+      fromTransparent,
+      toPaymentAddress,
+      privateKey,
+      encodedArgs,
+      signOnly
+    );
+  }
+
+  private async bondSplit(
+    args: WrapperTxProps,
+    bondSplitProps: BondSplitProps,
+    privateKey: string,
+    signOnly: boolean
+  ): Promise<unknown> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+    const encodedBondSplit = new Message<BondSplitMsgValue>().encode(
+      new BondSplitMsgValue(bondSplitProps)
+    );
+
+    return await this.sdk.submit_bond_split(
+      encodedBondSplit,
+      privateKey,
+      encodedArgs,
+      signOnly
+    );
+  }
+
+  private async unshield(
+    args: WrapperTxProps,
+    amount: BigNumber,
+    token: string,
+    fromSpendingKey: string,
+    toTransparent: string,
+    signOnly: boolean
+  ): Promise<unknown> {
+    const encodedArgs = new Message<WrapperTxMsgValue>().encode(
+      new WrapperTxMsgValue(args)
+    );
+
+    return await this.sdk.submit_unshield(
+      amount.toString(),
+      token,
+      fromSpendingKey,
+      toTransparent,
+      encodedArgs,
+      signOnly
+    );
   }
 }
+
+// Signed txs are returned by the wasm lib as arrays of bytes
+const toSignedTxs = (signedTxs: number[][]): Uint8Array[] =>
+  signedTxs.map((tx) => Uint8Array.from(tx));
@@ -1,3 +1,5 @@
+import type { BaseAmount } from "@namada/shared";
+
 /**
  * StakingTotalsResponse
  * [owner, validator, bonds, unbonds, withdrawable]
@@ -64,14 +66,13 @@ export type GasCosts = [string, string][];
 
 /**
  * Balance
- * [tokenAddress, amount][]
+ * [tokenAddress, amount, status][], amount is in base units of the token.
+ * Status is "known", "unknown" or "look_alike" when a token allowlist is set,
+ * "known" otherwise
  */
-export type Balance = [string, string][];
+export type Balance = [string, BaseAmount, string][];
 
 /**
  * Wasm checksum hashes returned from shared package
  */
-export type WasmHash = {
-  path: string;
-  hash: string;
-};
+export type { WasmHash } from "@namada/shared";
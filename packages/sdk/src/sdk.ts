@@ -1,12 +1,18 @@
 import Transport from "@ledgerhq/hw-transport";
-import { Query as QueryWasm, Sdk as SdkWasm } from "@namada/shared";
+import {
+  FlushResult,
+  Query as QueryWasm,
+  Sdk as SdkWasm,
+} from "@namada/shared";
 import packageJson from "../package.json";
 import { Crypto } from "./crypto";
+import { warnDeprecated } from "./deprecation";
 import { Keys } from "./keys";
 import { Ledger } from "./ledger";
 import { Masp } from "./masp";
 import { Mnemonic } from "./mnemonic";
 import { Rpc } from "./rpc";
+import { SdkV1 } from "./sdkV1";
 import { Signing } from "./signing";
 import { Tx } from "./tx";
 
@@ -31,6 +37,9 @@ export class Sdk {
     public readonly nativeToken: string
   ) {}
 
+  // Kept to apply it again when updateNetwork re-initializes the wasm instances
+  protected rpcTimeoutMs?: number;
+
   /**
    * Re-initialize wasm instances and return this instance
    * @param url - RPC url
@@ -45,62 +54,92 @@ export class Sdk {
     const sdk = new SdkWasm(url, nativeToken || this.nativeToken, "");
     this.sdk = sdk;
 
+    if (this.rpcTimeoutMs !== undefined) {
+      this.setRpcTimeout(this.rpcTimeoutMs);
+    }
+
     return this;
   }
 
+  /**
+   * Set the time after which RPC requests are aborted. A timed out request
+   * rejects with a timeout error instead of hanging, so the Sdk stays usable.
+   * @param timeoutMs - timeout in milliseconds, 0 disables it. Defaults to 30 seconds.
+   * @returns void
+   */
+  setRpcTimeout(timeoutMs: number): void {
+    this.rpcTimeoutMs = timeoutMs;
+    this.sdk.set_rpc_timeout(timeoutMs);
+    this.query.set_rpc_timeout(timeoutMs);
+  }
+
   /**
    * Return initialized Rpc class
+   * @deprecated use the rpc getter or SdkV1
    * @returns Namada RPC client
    */
   getRpc(): Rpc {
+    warnDeprecated("Sdk.getRpc", "Sdk.rpc");
     return new Rpc(this.sdk, this.query);
   }
 
   /**
    * Return initialized Tx class
+   * @deprecated use the tx getter or SdkV1
    * @returns Tx-related functionality
    */
   getTx(): Tx {
+    warnDeprecated("Sdk.getTx", "Sdk.tx");
     return new Tx(this.sdk);
   }
 
   /**
    * Return initialized Mnemonic class
+   * @deprecated use the mnemonic getter or SdkV1
    * @returns mnemonic-related functionality
    */
   getMnemonic(): Mnemonic {
+    warnDeprecated("Sdk.getMnemonic", "Sdk.mnemonic");
     return new Mnemonic(this.cryptoMemory);
   }
 
   /**
    * Return initialized Keys class
+   * @deprecated use the keys getter or SdkV1
    * @returns key-related functionality
    */
   getKeys(): Keys {
+    warnDeprecated("Sdk.getKeys", "Sdk.keys");
     return new Keys(this.cryptoMemory);
   }
 
   /**
    * Return initialized Signing class
+   * @deprecated use the signing getter or SdkV1
    * @returns Non-Tx signing functionality
    */
   getSigning(): Signing {
+    warnDeprecated("Sdk.getSigning", "Sdk.signing");
     return new Signing(this.sdk);
   }
 
   /**
    * Return initialized Masp class
+   * @deprecated use the masp getter or SdkV1
    * @returns Masp utilities for handling params
    */
   getMasp(): Masp {
+    warnDeprecated("Sdk.getMasp", "Sdk.masp");
     return new Masp(this.sdk);
   }
 
   /**
    * Return initialized Crypto class
+   * @deprecated use the crypto getter or SdkV1
    * @returns Utilities for encrypting and decrypting data
    */
   getCrypto(): Crypto {
+    warnDeprecated("Sdk.getCrypto", "Sdk.crypto");
     return new Crypto(this.cryptoMemory);
   }
 
@@ -114,11 +153,34 @@ export class Sdk {
     return await Ledger.init(transport);
   }
 
+  /**
+   * Persist unsaved wallet, shielded context and shielded sync changes, call
+   * from beforeunload or visibilitychange handlers so state is not lost when
+   * the tab is closed
+   * @async
+   * @param [budgetMs] - Optional time in milliseconds after which flushing gives up
+   * @returns which state was saved and whether the budget ran out
+   */
+  async flush(budgetMs?: number): Promise<FlushResult> {
+    const [sdkResult, queryResult]: FlushResult[] = await Promise.all([
+      this.sdk.flush(budgetMs),
+      this.query.flush(budgetMs),
+    ]);
+    return {
+      wallet_saved: sdkResult.wallet_saved,
+      shielded_saved: sdkResult.shielded_saved,
+      sync_state_saved: queryResult.sync_state_saved,
+      timed_out: sdkResult.timed_out || queryResult.timed_out,
+    };
+  }
+
   /**
    * Return SDK Package version
+   * @deprecated use the version getter or SdkV1
    * @returns SDK version
    */
   getVersion(): string {
+    warnDeprecated("Sdk.getVersion", "Sdk.version");
     return packageJson.version;
   }
 
@@ -127,7 +189,7 @@ export class Sdk {
    * @returns rpc client
    */
   get rpc(): Rpc {
-    return this.getRpc();
+    return new Rpc(this.sdk, this.query);
   }
 
   /**
@@ -135,7 +197,7 @@ export class Sdk {
    * @returns tx-related functionality
    */
   get tx(): Tx {
-    return this.getTx();
+    return new Tx(this.sdk);
   }
 
   /**
@@ -143,7 +205,7 @@ export class Sdk {
    * @returns mnemonic-related functionality
    */
   get mnemonic(): Mnemonic {
-    return this.getMnemonic();
+    return new Mnemonic(this.cryptoMemory);
   }
 
   /**
@@ -151,7 +213,7 @@ export class Sdk {
    * @returns key-related functionality
    */
   get keys(): Keys {
-    return this.getKeys();
+    return new Keys(this.cryptoMemory);
   }
 
   /**
@@ -159,7 +221,7 @@ export class Sdk {
    * @returns Non-Tx signing functionality
    */
   get signing(): Signing {
-    return this.getSigning();
+    return new Signing(this.sdk);
   }
 
   /**
@@ -167,7 +229,7 @@ export class Sdk {
    * @returns Masp utilities for handling params
    */
   get masp(): Masp {
-    return this.getMasp();
+    return new Masp(this.sdk);
   }
 
   /**
@@ -175,7 +237,7 @@ export class Sdk {
    * @returns Utilities for encrypting and decrypting data
    */
   get crypto(): Crypto {
-    return this.getCrypto();
+    return new Crypto(this.cryptoMemory);
   }
 
   /**
@@ -183,6 +245,14 @@ export class Sdk {
    * @returns Version from package.json
    */
   get version(): string {
-    return this.getVersion();
+    return packageJson.version;
+  }
+
+  /**
+   * Versioned facade of this instance, with members kept stable within the major version
+   * @returns SdkV1 wrapping this instance
+   */
+  get v1(): SdkV1 {
+    return new SdkV1(this);
   }
 }
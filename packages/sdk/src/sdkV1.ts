@@ -0,0 +1,121 @@
+import Transport from "@ledgerhq/hw-transport";
+import { FlushResult } from "@namada/shared";
+import { Crypto } from "./crypto";
+import { Keys } from "./keys";
+import { Ledger } from "./ledger";
+import { Masp } from "./masp";
+import { Mnemonic } from "./mnemonic";
+import { Rpc } from "./rpc";
+import type { Sdk } from "./sdk";
+import { Signing } from "./signing";
+import { Tx } from "./tx";
+
+/**
+ * Versioned facade of the SDK. Members of SdkV1 follow semver: they are only added within
+ * a major version, never removed or changed, so the wasm can be upgraded without breaking
+ * integrations. Breaking changes are introduced in a new facade, e.g. SdkV2
+ */
+export class SdkV1 {
+  /**
+   * Version of the facade API
+   */
+  static readonly apiVersion = 1;
+
+  /**
+   * @param sdk - Sdk instance the facade delegates to
+   */
+  constructor(private readonly sdk: Sdk) {}
+
+  /**
+   * RPC URL the SDK is connected to
+   * @returns RPC url
+   */
+  get url(): string {
+    return this.sdk.url;
+  }
+
+  /**
+   * Address of the chain's native token
+   * @returns native token address
+   */
+  get nativeToken(): string {
+    return this.sdk.nativeToken;
+  }
+
+  /**
+   * SDK package version
+   * @returns version from package.json
+   */
+  get version(): string {
+    return this.sdk.version;
+  }
+
+  /**
+   * @returns Namada RPC client
+   */
+  get rpc(): Rpc {
+    return this.sdk.rpc;
+  }
+
+  /**
+   * @returns Tx-related functionality
+   */
+  get tx(): Tx {
+    return this.sdk.tx;
+  }
+
+  /**
+   * @returns mnemonic-related functionality
+   */
+  get mnemonic(): Mnemonic {
+    return this.sdk.mnemonic;
+  }
+
+  /**
+   * @returns key-related functionality
+   */
+  get keys(): Keys {
+    return this.sdk.keys;
+  }
+
+  /**
+   * @returns Non-Tx signing functionality
+   */
+  get signing(): Signing {
+    return this.sdk.signing;
+  }
+
+  /**
+   * @returns Masp utilities for handling params
+   */
+  get masp(): Masp {
+    return this.sdk.masp;
+  }
+
+  /**
+   * @returns Utilities for encrypting and decrypting data
+   */
+  get crypto(): Crypto {
+    return this.sdk.crypto;
+  }
+
+  /**
+   * Intialize Ledger class for use with NamadaApp
+   * @async
+   * @param [transport] - Will default to USB transport if not specified
+   * @returns Class for interacting with NamadaApp for Ledger Hardware Wallets
+   */
+  async initLedger(transport?: Transport): Promise<Ledger> {
+    return await this.sdk.initLedger(transport);
+  }
+
+  /**
+   * Persist unsaved wallet, shielded context and shielded sync changes
+   * @async
+   * @param [budgetMs] - Optional time in milliseconds after which flushing gives up
+   * @returns which state was saved and whether the budget ran out
+   */
+  async flush(budgetMs?: number): Promise<FlushResult> {
+    return await this.sdk.flush(budgetMs);
+  }
+}
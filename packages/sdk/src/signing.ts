@@ -1,5 +1,19 @@
-import { Sdk as SdkWasm } from "@namada/shared";
-import { Message, TxMsgValue, TxProps } from "@namada/types";
+import { deserialize } from "@dao-xyz/borsh";
+import BigNumber from "bignumber.js";
+import {
+  OfflineBallot,
+  OwnershipProof,
+  Sdk as SdkWasm,
+  SessionInfo,
+  verify_offline_vote,
+} from "@namada/shared";
+import {
+  Message,
+  TxMsgValue,
+  TxProps,
+  WrapperTxMsgValue,
+  WrapperTxProps,
+} from "@namada/types";
 
 type Signature = [string, string];
 
@@ -38,6 +52,92 @@ export class Signing {
     return await this.sdk.sign_tx(txBytesFinal, signingKey, chainId);
   }
 
+  /**
+   * Make the sponsor the fee payer and sign only the inner tx, the sponsor signs the wrapper
+   * @async
+   * @param txProps - TxProps
+   * @param sponsorPublicKey - public key of the sponsor paying the fee
+   * @param signingKey - private key of the user
+   * @returns tx bytes signed by the user - Promise resolving to Uint8Array
+   */
+  async signSponsored(
+    txProps: TxProps,
+    sponsorPublicKey: string,
+    signingKey: string
+  ): Promise<Uint8Array> {
+    const txMsgValue = new TxMsgValue(txProps);
+    const msg = new Message<TxMsgValue>();
+    const txBytes = msg.encode(txMsgValue);
+
+    return await this.sdk.build_sponsored_tx(
+      txBytes,
+      sponsorPublicKey,
+      signingKey
+    );
+  }
+
+  /**
+   * Add the signature of a guardian to a recovery tx, the wrapper is signed later by the fee payer
+   * @async
+   * @param txProps - TxProps returned by initiateRecovery or approveRecovery
+   * @param guardianSigningKey - private key of the guardian
+   * @returns TxProps with the signature of the guardian
+   */
+  async approveRecovery(
+    txProps: TxProps,
+    guardianSigningKey: string
+  ): Promise<TxMsgValue> {
+    const txMsgValue = new TxMsgValue(txProps);
+    const msg = new Message<TxMsgValue>();
+    const txBytes = msg.encode(txMsgValue);
+
+    const approvedTx = await this.sdk.approve_recovery(
+      txBytes,
+      guardianSigningKey
+    );
+    return deserialize(Buffer.from(approvedTx), TxMsgValue);
+  }
+
+  /**
+   * Sign the wrapper of a sponsored tx with the key of the sponsor
+   * @async
+   * @param txBytes - tx bytes signed by the user
+   * @param sponsorSigningKey - private key of the sponsor
+   * @returns signed tx bytes
+   */
+  async attachSponsorSignature(
+    txBytes: Uint8Array,
+    sponsorSigningKey: string
+  ): Promise<Uint8Array> {
+    return await this.sdk.attach_sponsor_signature(txBytes, sponsorSigningKey);
+  }
+
+  /**
+   * Raise the fee of a signed tx stuck because of a low fee. Only the wrapper
+   * is signed again, the bumped tx has a new hash
+   * @async
+   * @param txBytes - signed tx bytes
+   * @param newFee - fee per gas unit, higher than the current one
+   * @param signingKey - private key of the fee payer
+   * @returns signed tx bytes
+   */
+  async bumpFee(
+    txBytes: Uint8Array,
+    newFee: BigNumber,
+    signingKey: string
+  ): Promise<Uint8Array> {
+    return await this.sdk.bump_fee(txBytes, newFee.toString(), signingKey);
+  }
+
+  /**
+   * Set the highest fee per gas unit bumpFee raises a fee to
+   * @param maxFee - fee per gas unit, undefined for ten times the current fee
+   * @returns void
+   */
+  setMaxBumpFee(maxFee?: BigNumber): void {
+    this.sdk.set_max_bump_fee(maxFee?.toString());
+  }
+
   /**
    * Sign arbitrary data
    * @param signingKey - private key
@@ -58,4 +158,168 @@ export class Signing {
   verifyArbitrary(publicKey: string, hash: string, signature: string): void {
     return this.sdk.verify_arbitrary(publicKey, hash, signature);
   }
+
+  /**
+   * Sign a governance vote off-chain with a key stored in the wallet
+   * @async
+   * @param chainId - chain the vote is cast on
+   * @param proposalId - id of the proposal
+   * @param vote - "yay", "nay" or "abstain"
+   * @param keyAlias - alias of the signing key
+   * @param [password] - password the key is encrypted with
+   * @returns JSON of the signed ballot
+   */
+  async signOfflineVote(
+    chainId: string,
+    proposalId: bigint,
+    vote: string,
+    keyAlias: string,
+    password?: string
+  ): Promise<string> {
+    return await this.sdk.sign_offline_vote(
+      chainId,
+      proposalId,
+      vote,
+      keyAlias,
+      password
+    );
+  }
+
+  /**
+   * Verify an off-chain signed ballot. Will throw an error if the ballot is invalid
+   * or cast on another chain
+   * @param ballot - JSON of the signed ballot
+   * @param chainId - chain the vote has to be cast on
+   * @returns the verified ballot
+   */
+  verifyOfflineVote(ballot: string, chainId: string): OfflineBallot {
+    return verify_offline_vote(ballot, chainId);
+  }
+
+  /**
+   * Prove the wallet controls an address, e.g. for an exchange before the
+   * first withdrawal
+   * @async
+   * @param address - implicit address or single key established account
+   * @param challenge - challenge chosen by the verifier
+   * @param [password] - password the key is encrypted with
+   * @returns JSON of the signed proof
+   */
+  async proveAddressOwnership(
+    address: string,
+    challenge: string,
+    password?: string
+  ): Promise<string> {
+    return await this.sdk.prove_address_ownership(
+      address,
+      challenge,
+      password
+    );
+  }
+
+  /**
+   * Verify an address ownership proof. Will throw an error if the proof is
+   * invalid or doesn't sign the challenge
+   * @async
+   * @param proof - JSON of the signed proof
+   * @param challenge - challenge the proof has to sign
+   * @returns the verified proof
+   */
+  async verifyAddressOwnership(
+    proof: string,
+    challenge: string
+  ): Promise<OwnershipProof> {
+    return await this.sdk.verify_address_ownership(proof, challenge);
+  }
+
+  /**
+   * Start a session of a dapp origin with a new in-memory key, which signs
+   * transparent transfers of the token without an approval popup. The user
+   * funds the returned address with the budget
+   * @param origin - dapp origin, e.g. "https://game.example"
+   * @param token - address of the only token the session key transfers
+   * @param maxPerTx - amount a single tx transfers at most
+   * @param budget - amount all txs of the session transfer at most
+   * @param ttlSecs - seconds until the session expires, at most a day
+   * @returns session with the address to fund
+   */
+  createSession(
+    origin: string,
+    token: string,
+    maxPerTx: BigNumber,
+    budget: BigNumber,
+    ttlSecs: number
+  ): SessionInfo {
+    return this.sdk.create_session(
+      origin,
+      token,
+      maxPerTx.toString(),
+      budget.toString(),
+      BigInt(ttlSecs)
+    );
+  }
+
+  /**
+   * Sign a transfer with the session key of the current dapp origin, without
+   * calling the approval callback
+   * @async
+   * @param txProps - TxProps of a transparent transfer from the session address,
+   * paying the fee in the session token
+   * @throws Error if the origin has no session or the amount plus the fee
+   * exceeds its limits
+   * @returns signed tx bytes - Promise resolving to Uint8Array
+   */
+  async signWithSession(txProps: TxProps): Promise<Uint8Array> {
+    const txMsgValue = new TxMsgValue(txProps);
+    const msg = new Message<TxMsgValue>();
+    const txBytes = msg.encode(txMsgValue);
+
+    return await this.sdk.sign_with_session(txBytes);
+  }
+
+  /**
+   * Add the signatures of a key to a partially signed Tx, see
+   * Tx.createPartialTx. Before finalization the key signs the inner txs of its
+   * accounts, after finalization the fee payer signs the wrapper.
+   * @async
+   * @param partialTx - partially signed tx bytes
+   * @param signingKey - private key of a signer
+   * @throws Error if the key is no signer of the tx
+   * @returns partially signed tx bytes - Promise resolving to Uint8Array
+   */
+  async signPartialTx(
+    partialTx: Uint8Array,
+    signingKey: string
+  ): Promise<Uint8Array> {
+    return await this.sdk.sign_partial_tx(partialTx, signingKey);
+  }
+
+  /**
+   * End the session of a dapp origin and sweep the funds left on the session
+   * address back to the owner. The key is dropped once the address is empty,
+   * call again after the sweep is applied
+   * @async
+   * @param origin - dapp origin
+   * @param owner - address the funds left are transferred to
+   * @param wrapperTxProps - properties of the sweep, paid by the session key
+   * @returns signed sweep tx bytes, or undefined if nothing is left
+   */
+  async revokeSession(
+    origin: string,
+    owner: string,
+    wrapperTxProps: WrapperTxProps
+  ): Promise<Uint8Array | undefined> {
+    const msg = new Message<WrapperTxMsgValue>();
+    const wrapperTxBytes = msg.encode(new WrapperTxMsgValue(wrapperTxProps));
+
+    return await this.sdk.revoke_session(origin, owner, wrapperTxBytes);
+  }
+
+  /**
+   * Return the sessions which didn't expire yet
+   * @returns sessions with their limits and spent amount
+   */
+  sessions(): SessionInfo[] {
+    return this.sdk.sessions();
+  }
 }
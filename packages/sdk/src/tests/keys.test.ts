@@ -53,4 +53,15 @@ describe("Keys", () => {
     expect(publicKey).toBe(account1.publicKey);
     expect(privateKey).toBe(account1.privateKey);
   });
+
+  it("should derive addresses of the selected signing scheme", () => {
+    const { keys } = initSdk();
+
+    const ed25519 = keys.getAddress(account1.privateKey);
+    const secp256k1 = keys.getAddress(account1.privateKey, "secp256k1");
+
+    expect(ed25519.address).toBe(account1.address);
+    expect(secp256k1.address).not.toBe(account1.address);
+    expect(secp256k1.publicKey).not.toBe(account1.publicKey);
+  });
 });
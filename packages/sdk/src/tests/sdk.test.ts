@@ -1,7 +1,9 @@
+import { setDeprecationHandler } from "deprecation";
 import { Keys } from "keys";
 import { Masp } from "masp";
 import { Mnemonic } from "mnemonic";
 import { Rpc } from "rpc";
+import { SdkV1 } from "sdkV1";
 import { Signing } from "signing";
 import { Tx } from "tx";
 import { initSdk } from "./initSdk";
@@ -16,4 +18,19 @@ describe("Sdk", () => {
     expect(masp).toBeInstanceOf(Masp);
     expect(signing).toBeInstanceOf(Signing);
   });
+
+  it("should warn once when a deprecated entry point is used", () => {
+    const handler = jest.fn();
+    setDeprecationHandler(handler);
+    const sdk = initSdk();
+
+    expect(sdk.getTx()).toBeInstanceOf(Tx);
+    sdk.getTx();
+    expect(sdk.v1.tx).toBeInstanceOf(Tx);
+    expect(handler).toHaveBeenCalledTimes(1);
+    expect(handler.mock.calls[0][0]).toContain("Sdk.getTx");
+    expect(SdkV1.apiVersion).toBe(1);
+
+    setDeprecationHandler();
+  });
 });
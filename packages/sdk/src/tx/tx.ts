@@ -1,20 +1,60 @@
 import { deserialize } from "@dao-xyz/borsh";
 import {
+  BorshSchemaInfo,
+  ChangeOutput,
+  CompoundPreview,
+  ExportedTx,
+  GasReport,
+  MultiTransferReport,
+  PartialTxInfo,
+  RecipientIssue,
   Sdk as SdkWasm,
+  SectionInfo,
+  SigningRequirements,
+  StakingBatch,
+  TxHashes,
   TxType,
+  append_tx_section,
+  append_tx_signature,
+  compute_tx_hash,
+  create_partial_tx,
   deserialize_tx,
+  export_signed_tx,
+  extract_partial_tx,
+  finalize_partial_tx,
+  get_borsh_schemas,
   get_inner_tx_hashes,
+  get_section_hash,
+  get_signature_hash,
+  get_tx_sections,
+  inspect_partial_tx,
+  merge_partial_txs,
+  register_tx_type,
+  replace_tx_section,
+  set_tx_code,
+  set_tx_data,
 } from "@namada/shared";
 import {
   BondMsgValue,
   BondProps,
+  BondSplitMsgValue,
+  BondSplitProps,
   ClaimRewardsMsgValue,
   ClaimRewardsProps,
+  CustomTxMsgValue,
   EthBridgeTransferMsgValue,
   EthBridgeTransferProps,
+  GuardianAccountMsgValue,
+  GuardianAccountProps,
   IbcTransferMsgValue,
   IbcTransferProps,
   Message,
+  MultiSourceTransferMsgValue,
+  MultiSourceTransferProps,
+  MultiTransferMsgValue,
+  MultiTransferProps,
+  RecoveryMsgValue,
+  RecoveryProps,
   RedelegateMsgValue,
   RedelegateProps,
   RevealPkMsgValue,
@@ -56,7 +96,8 @@ export class Tx {
   constructor(protected readonly sdk: SdkWasm) {}
 
   /**
-   * Build Transparent Transfer Tx
+   * Build Transparent Transfer Tx. Waits until an earlier transfer built for
+   * the same source is applied, or not signed or submitted in time.
    * @async
    * @param wrapperTxProps - properties of the transaction
    * @param transferProps -  properties of the transfer
@@ -80,6 +121,199 @@ export class Tx {
     return deserialize(Buffer.from(serializedTx), TxMsgValue);
   }
 
+  /**
+   * Build Multi Transfer Tx, a batch of transparent transfers from one source
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param multiTransferProps -  source and recipients of the transfers
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildMultiTransfer(
+    wrapperTxProps: WrapperTxProps,
+    multiTransferProps: MultiTransferProps
+  ): Promise<TxMsgValue> {
+    const multiTransferMsg = new Message<MultiTransferMsgValue>();
+
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const encodedMultiTransfer = multiTransferMsg.encode(
+      new MultiTransferMsgValue(multiTransferProps)
+    );
+
+    const serializedTx = await this.sdk.build_multi_transfer(
+      encodedMultiTransfer,
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Build Multi Source Transfer Tx, pulling the amount from the sources in priority order.
+   * Each used source becomes one transparent transfer of the batch. Duplicate sources are
+   * used once, and the fee stays in the balance of the source paying it.
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param multiSourceTransferProps - sources, target, token and amount of the transfer
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildMultiSourceTransfer(
+    wrapperTxProps: WrapperTxProps,
+    multiSourceTransferProps: MultiSourceTransferProps
+  ): Promise<TxMsgValue> {
+    const multiSourceTransferMsg = new Message<MultiSourceTransferMsgValue>();
+
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const encodedMultiSourceTransfer = multiSourceTransferMsg.encode(
+      new MultiSourceTransferMsgValue(multiSourceTransferProps)
+    );
+
+    const serializedTx = await this.sdk.build_multi_source_transfer(
+      encodedMultiSourceTransfer,
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Validate a CSV or JSON list of recipients and build a Multi Transfer Tx to them.
+   * The tx is built only if no issues were found.
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param source - address sending the transfers
+   * @param data - CSV(target,token,amount) or JSON array of { target, token, amount }
+   * @returns promise that resolves to the issues found and the TxMsgValue
+   */
+  async buildMultiTransferFromCsv(
+    wrapperTxProps: WrapperTxProps,
+    source: string,
+    data: string
+  ): Promise<{ issues: RecipientIssue[]; tx?: TxMsgValue }> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const report: MultiTransferReport =
+      await this.sdk.build_multi_transfer_from_csv(
+        source,
+        data,
+        encodedWrapperArgs
+      );
+
+    return {
+      issues: report.issues,
+      tx: report.tx
+        ? deserialize(Buffer.from(report.tx), TxMsgValue)
+        : undefined,
+    };
+  }
+
+  /**
+   * Build a Tx which can be broadcast only within a time window. The Tx expires at notAfter,
+   * pass the signed Tx to Rpc.scheduleTx to broadcast it once notBefore passes.
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param txType - tx type, e.g. "transparent_transfer" or "bond"
+   * @param txMsg - tx msg object, with the same field names as the Rust msg struct
+   * @param notBefore - start of the window, in seconds since the Unix epoch
+   * @param notAfter - end of the window, in seconds since the Unix epoch
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildScheduledTx(
+    wrapperTxProps: WrapperTxProps,
+    txType: string,
+    txMsg: Record<string, unknown>,
+    notBefore: number,
+    notAfter: number
+  ): Promise<TxMsgValue> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const serializedTx = await this.sdk.build_scheduled_tx(
+      txType,
+      JSON.stringify(txMsg),
+      encodedWrapperArgs,
+      BigInt(notBefore),
+      BigInt(notAfter)
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Return who has to sign a Tx and who pays its fee, without signing it, so
+   * multi-party coordination tools know which keys to collect up front
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param txType - tx type, e.g. "transparent_transfer" or "bond"
+   * @param txMsg - tx msg object, with the same field names as the Rust msg struct
+   * @returns signer keys and threshold per inner tx, fee payer and fee
+   */
+  async getSigningData(
+    wrapperTxProps: WrapperTxProps,
+    txType: string,
+    txMsg: Record<string, unknown>
+  ): Promise<SigningRequirements> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    return await this.sdk.get_signing_data(
+      txType,
+      JSON.stringify(txMsg),
+      encodedWrapperArgs
+    );
+  }
+
+  /**
+   * Register a custom Tx type, so its Txs can be built with buildCustomTx and are decoded by
+   * deserialize and included in sign docs like the built in ones
+   * @param name - name of the tx type
+   * @param codeHash - hex encoded hash of the tx code stored on chain
+   * @param encode - encodes the tx msg object into tx data
+   * @param [format] - returns the value shown to the user for tx data
+   * @returns void
+   */
+  registerTxType(
+    name: string,
+    codeHash: string,
+    encode: (txMsg: Record<string, unknown>) => Uint8Array,
+    format?: (data: Uint8Array) => unknown
+  ): void {
+    register_tx_type(name, codeHash, encode, format);
+  }
+
+  /**
+   * Build Tx of a custom type registered with registerTxType
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param txType - name of the registered tx type
+   * @param txMsg - tx msg object, passed to the encode callback of the type
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildCustomTx(
+    wrapperTxProps: WrapperTxProps,
+    txType: string,
+    txMsg: Record<string, unknown>
+  ): Promise<TxMsgValue> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const serializedTx = await this.sdk.build_custom_tx(
+      txType,
+      JSON.stringify(txMsg),
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Rebuild a signed Tx which expired before it was submitted, e.g. after the
+   * device slept. The rebuilt Tx does the same and has to be signed again,
+   * which asks the approval callback again. Shielded Txs can't be rebuilt.
+   * @async
+   * @param signedTx - bytes of the expired signed Tx
+   * @param builtTx - Tx the signed Tx was built as, for the owner of the Tx
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async rebuildExpired(
+    signedTx: Uint8Array,
+    builtTx: TxMsgValue
+  ): Promise<TxMsgValue> {
+    const serializedTx = await this.sdk.rebuild_expired(
+      signedTx,
+      new Message<TxMsgValue>().encode(builtTx)
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
   /**
    * Build Shielded Transfer Tx
    * @async
@@ -189,6 +423,103 @@ export class Tx {
     return deserialize(Buffer.from(serializedTx), TxMsgValue);
   }
 
+  /**
+   * Build bonds to several validators batched under a single wrapper
+   * @async
+   * @param wrapperTxProps - properties of the transaction, the gas limit covers the whole batch
+   * @param bondSplitProps - source and the validators with the amounts bonded to them
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildBondSplit(
+    wrapperTxProps: WrapperTxProps,
+    bondSplitProps: BondSplitProps
+  ): Promise<TxMsgValue> {
+    const bondSplitMsg = new Message<BondSplitMsgValue>();
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const encodedBondSplit = bondSplitMsg.encode(
+      new BondSplitMsgValue(bondSplitProps)
+    );
+    const serializedTx = await this.sdk.build_bond_split(
+      encodedBondSplit,
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Build a batch claiming the rewards of a delegation and bonding them back to the validator
+   * @async
+   * @param wrapperTxProps - properties of the transaction, the gas limit covers both txs
+   * @param delegator - address of the delegator
+   * @param validator - address of the validator
+   * @throws Error if there are no rewards to claim
+   * @returns promise that resolves to the compounded rewards and the TxMsgValue to sign
+   */
+  async compoundRewards(
+    wrapperTxProps: WrapperTxProps,
+    delegator: string,
+    validator: string
+  ): Promise<{ rewards: string; tx: TxMsgValue }> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const preview: CompoundPreview = await this.sdk.compound_rewards(
+      delegator,
+      validator,
+      encodedWrapperArgs
+    );
+
+    return {
+      rewards: preview.rewards,
+      tx: deserialize(Buffer.from(preview.tx), TxMsgValue),
+    };
+  }
+
+  /**
+   * Build a batch unbonding everything the source has bonded, one unbond per validator
+   * @async
+   * @param wrapperTxProps - properties of the transaction, the gas limit covers the whole batch
+   * @param source - address of the delegator
+   * @throws Error if the source has no bonds
+   * @returns promise that resolves to the summary of the batch and the TxMsgValue
+   */
+  async buildUnbondAll(
+    wrapperTxProps: WrapperTxProps,
+    source: string
+  ): Promise<{ summary: Omit<StakingBatch, "tx">; tx: TxMsgValue }> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const batch: StakingBatch = await this.sdk.build_unbond_all(
+      source,
+      encodedWrapperArgs
+    );
+    return this.toStakingBatch(batch);
+  }
+
+  /**
+   * Build a batch withdrawing every matured unbond of the source, one withdrawal per validator
+   * @async
+   * @param wrapperTxProps - properties of the transaction, the gas limit covers the whole batch
+   * @param source - address of the delegator
+   * @throws Error if nothing can be withdrawn
+   * @returns promise that resolves to the summary of the batch and the TxMsgValue
+   */
+  async buildWithdrawAll(
+    wrapperTxProps: WrapperTxProps,
+    source: string
+  ): Promise<{ summary: Omit<StakingBatch, "tx">; tx: TxMsgValue }> {
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const batch: StakingBatch = await this.sdk.build_withdraw_all(
+      source,
+      encodedWrapperArgs
+    );
+    return this.toStakingBatch(batch);
+  }
+
+  private toStakingBatch({ tx, ...summary }: StakingBatch): {
+    summary: Omit<StakingBatch, "tx">;
+    tx: TxMsgValue;
+  } {
+    return { summary, tx: deserialize(Buffer.from(tx), TxMsgValue) };
+  }
+
   /**
    * Build Unbond Tx
    * @async
@@ -303,6 +634,25 @@ export class Tx {
     return deserialize(Buffer.from(serializedTx), TxMsgValue);
   }
 
+  /**
+   * Build a transfer sending non-usable tokens (NUTs) back to Ethereum, which is the only
+   * way to recover them
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param ethBridgeTransferProps - properties of the eth bridge transfer tx, asset is the
+   * ERC20 address the NUT was minted for
+   * @returns promise that resolves to an TxMsgValue
+   */
+  async buildNutRecovery(
+    wrapperTxProps: WrapperTxProps,
+    ethBridgeTransferProps: Omit<EthBridgeTransferProps, "nut">
+  ): Promise<TxMsgValue> {
+    return await this.buildEthBridgeTransfer(wrapperTxProps, {
+      ...ethBridgeTransferProps,
+      nut: true,
+    });
+  }
+
   /**
    * Build Vote Proposal Tx
    * @async
@@ -350,6 +700,54 @@ export class Tx {
     return deserialize(Buffer.from(serializedTx), TxMsgValue);
   }
 
+  /**
+   * Build a tx creating an account recoverable by guardians
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param guardianAccountProps - owner and guardian keys with their
+   * thresholds, the guardians alone can't meet the owner threshold
+   * @returns a serialized TxMsgValue type
+   */
+  async buildGuardianAccount(
+    wrapperTxProps: WrapperTxProps,
+    guardianAccountProps: GuardianAccountProps
+  ): Promise<TxMsgValue> {
+    const guardianAccountMsg = new Message<GuardianAccountProps>();
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const encodedGuardianAccount = guardianAccountMsg.encode(
+      new GuardianAccountMsgValue(guardianAccountProps)
+    );
+    const serializedTx = await this.sdk.build_guardian_account(
+      encodedGuardianAccount,
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
+  /**
+   * Build a tx replacing the keys of a guardian account, to be approved by guardians
+   * @async
+   * @param wrapperTxProps - properties of the transaction
+   * @param recoveryProps - account, new owner and guardian keys with their
+   * thresholds
+   * @returns a serialized TxMsgValue type
+   */
+  async initiateRecovery(
+    wrapperTxProps: WrapperTxProps,
+    recoveryProps: RecoveryProps
+  ): Promise<TxMsgValue> {
+    const recoveryMsg = new Message<RecoveryProps>();
+    const encodedWrapperArgs = this.encodeTxArgs(wrapperTxProps);
+    const encodedRecovery = recoveryMsg.encode(
+      new RecoveryMsgValue(recoveryProps)
+    );
+    const serializedTx = await this.sdk.initiate_recovery(
+      encodedRecovery,
+      encodedWrapperArgs
+    );
+    return deserialize(Buffer.from(serializedTx), TxMsgValue);
+  }
+
   /**
    * Build a batched transaction
    * @param txs - array of TxProp
@@ -459,6 +857,8 @@ export class Tx {
           return deserialize(data, RevealPkMsgValue);
         case TxType.IBCTransfer:
           return deserialize(data, IbcTransferMsgValue);
+        case TxType.Custom:
+          return deserialize(data, CustomTxMsgValue);
         default:
           throw "Unsupported Tx type!";
       }
@@ -502,6 +902,29 @@ export class Tx {
     );
   }
 
+  /**
+   * Return the shielded outputs a built tx sends back to its shielded sources. Change always goes
+   * to the default payment address of the source.
+   * @param tx - TxMsgValue returned by the build functions
+   * @returns change outputs with their payment address, asset type and value
+   */
+  getTxChange(tx: TxMsgValue): ChangeOutput[] {
+    const bytes = new Message<TxMsgValue>().encode(tx);
+    return this.sdk.tx_change(bytes);
+  }
+
+  /**
+   * Return the gas used by each inner tx of a built tx, and the sizes of its sections. The
+   * batch is dry-run once per inner tx, the first inner tx also carries the wrapper cost
+   * @async
+   * @param tx - TxMsgValue returned by the build functions
+   * @returns total gas, gas per inner tx and the inner tx which used the most
+   */
+  async getGasReport(tx: TxMsgValue): Promise<GasReport> {
+    const bytes = new Message<TxMsgValue>().encode(tx);
+    return await this.sdk.gas_report(bytes);
+  }
+
   /**
    * Return the inner tx hashes from the provided tx bytes
    * @param bytes - Uint8Array
@@ -510,4 +933,177 @@ export class Tx {
   getInnerTxHashes(bytes: Uint8Array): string[] {
     return get_inner_tx_hashes(bytes);
   }
+
+  /**
+   * Compute the wrapper and inner tx hashes the node will report, before broadcasting
+   * @param bytes - Uint8Array of tx, signed or not
+   * @returns wrapper hash and inner tx hashes
+   */
+  computeTxHash(bytes: Uint8Array): TxHashes {
+    return compute_tx_hash(bytes);
+  }
+
+  /**
+   * Encode a signed tx with its metadata, so it can be broadcast through any node
+   * @param bytes - Uint8Array of signed tx
+   * @returns hex and base64 encoded tx, hash, chain_id and expiration
+   */
+  exportSignedTx(bytes: Uint8Array): ExportedTx {
+    return export_signed_tx(bytes);
+  }
+
+  /**
+   * Return Borsh schemas of the types passed to and returned from the SDK, for tools
+   * decoding the binary payloads
+   * @returns schemas keyed by type name, Namada types are prefixed with "namada::"
+   */
+  getBorshSchemas(): Record<string, BorshSchemaInfo> {
+    return get_borsh_schemas();
+  }
+
+  /**
+   * Return the sections of a tx with their hashes, for assembling tx kinds the SDK doesn't wrap
+   * @param bytes - Uint8Array of tx
+   * @returns index, kind and hash of every section
+   */
+  getTxSections(bytes: Uint8Array): SectionInfo[] {
+    return get_tx_sections(bytes);
+  }
+
+  /**
+   * Set the data of the first commitment, signatures made before become invalid
+   * @param bytes - Uint8Array of tx
+   * @param data - Borsh serialized tx data
+   * @returns tx bytes
+   */
+  setTxData(bytes: Uint8Array, data: Uint8Array): Uint8Array {
+    return set_tx_data(bytes, data);
+  }
+
+  /**
+   * Set the wasm code of the first commitment, signatures made before become invalid
+   * @param bytes - Uint8Array of tx
+   * @param code - wasm code
+   * @param [tag] - optional code tag, e.g. "tx_transfer.wasm"
+   * @returns tx bytes
+   */
+  setTxCode(bytes: Uint8Array, code: Uint8Array, tag?: string): Uint8Array {
+    return set_tx_code(bytes, code, tag);
+  }
+
+  /**
+   * Append a Borsh serialized section
+   * @param bytes - Uint8Array of tx
+   * @param section - Borsh serialized section
+   * @returns tx bytes
+   */
+  appendTxSection(bytes: Uint8Array, section: Uint8Array): Uint8Array {
+    return append_tx_section(bytes, section);
+  }
+
+  /**
+   * Replace the section at the index with a Borsh serialized section, the
+   * header commitments referring to the old section are updated
+   * @param bytes - Uint8Array of tx
+   * @param index - index of the section, as returned by getTxSections
+   * @param section - Borsh serialized section
+   * @returns tx bytes
+   */
+  replaceTxSection(
+    bytes: Uint8Array,
+    index: number,
+    section: Uint8Array
+  ): Uint8Array {
+    return replace_tx_section(bytes, index, section);
+  }
+
+  /**
+   * Append a signature of the target sections made outside of the SDK
+   * @param bytes - Uint8Array of tx
+   * @param targets - hashes of the signed sections
+   * @param pubkey - public key of the signer
+   * @param signature - signature of the hash returned by getSignatureHash
+   * @returns tx bytes
+   */
+  appendTxSignature(
+    bytes: Uint8Array,
+    targets: string[],
+    pubkey: string,
+    signature: string
+  ): Uint8Array {
+    return append_tx_signature(bytes, targets, pubkey, signature);
+  }
+
+  /**
+   * Wrap a built Tx into a partially signed Tx, which the signers of a multisig
+   * account sign with Signing.signPartialTx, on the same or different devices
+   * @param txProps - TxProps returned by the build functions
+   * @param [description] - note for the other signers, e.g. what the tx is for
+   * @returns partially signed tx bytes
+   */
+  createPartialTx(txProps: TxProps, description?: string): Uint8Array {
+    const bytes = new Message<TxMsgValue>().encode(new TxMsgValue(txProps));
+    return create_partial_tx(bytes, description);
+  }
+
+  /**
+   * Merge the signatures of partially signed Txs of the same Tx, e.g. signed
+   * on different devices
+   * @param partialTxs - partially signed tx bytes
+   * @throws Error if the partial txs are of different txs or finalized
+   * @returns merged partially signed tx bytes
+   */
+  mergePartialTxs(partialTxs: Uint8Array[]): Uint8Array {
+    return merge_partial_txs(partialTxs);
+  }
+
+  /**
+   * Add the collected signatures to the Tx once every account reached its
+   * threshold. Afterwards the fee payer signs the wrapper with
+   * Signing.signPartialTx.
+   * @param partialTx - partially signed tx bytes
+   * @throws Error if an account misses signatures
+   * @returns finalized partially signed tx bytes
+   */
+  finalizePartialTx(partialTx: Uint8Array): Uint8Array {
+    return finalize_partial_tx(partialTx);
+  }
+
+  /**
+   * Return the signed Tx of a finalized partially signed Tx, for broadcast
+   * @param partialTx - partially signed tx bytes
+   * @throws Error if the partial tx is not finalized or the wrapper is not signed
+   * @returns signed tx bytes
+   */
+  extractPartialTx(partialTx: Uint8Array): Uint8Array {
+    return extract_partial_tx(partialTx);
+  }
+
+  /**
+   * Return the signers, collected signatures and state of a partially signed Tx
+   * @param partialTx - partially signed tx bytes
+   * @returns info of the partial tx
+   */
+  inspectPartialTx(partialTx: Uint8Array): PartialTxInfo {
+    return inspect_partial_tx(partialTx);
+  }
+
+  /**
+   * Return the hash a signer of the target sections signs
+   * @param targets - hashes of the signed sections
+   * @param pubkey - public key of the signer
+   * @returns hex encoded hash
+   */
+  getSignatureHash(targets: string[], pubkey: string): string {
+    return get_signature_hash(targets, pubkey);
+  }
+
+  /**
+   * Return the hash of a Borsh serialized section
+   * @param section - Borsh serialized section
+   * @returns hex encoded hash
+   */
+  getSectionHash(section: Uint8Array): string {
+    return get_section_hash(section);
+  }
 }
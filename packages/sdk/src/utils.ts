@@ -1,3 +1,4 @@
+import type { FieldError } from "@namada/shared";
 import { Bip44Path, Zip32Path } from "@namada/types";
 
 /**
@@ -67,3 +68,18 @@ export const makeSaplingPathArray = (
   if (typeof index === "number") pathArray.push(index);
   return new Uint32Array(pathArray);
 };
+
+const FIELD_ERROR = /^Invalid ([a-z_]+): ([\s\S]*)$/;
+
+/**
+ * Return the invalid field of an error thrown by the SDK when validating input
+ * @param error - error thrown by an SDK method
+ * @returns field and reason, or undefined if the error is not a field error
+ */
+export const fieldError = (error: unknown): FieldError | undefined => {
+  const message = error instanceof Error ? error.message : String(error);
+  const match = FIELD_ERROR.exec(message);
+  if (!match) return undefined;
+  const [, field, reason] = match;
+  return { field, reason };
+};
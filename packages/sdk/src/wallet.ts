@@ -1,4 +1,5 @@
-import { Sdk as SdkWasm } from "@namada/shared";
+import { EthKey, ImportSummary, Sdk as SdkWasm } from "@namada/shared";
+import { SigningScheme } from "./keys";
 
 /**
  * Class representing utilities related to Wallet
@@ -33,13 +34,351 @@ export class Wallet {
    * @param secretKey - Serialized namada secret key
    * @param alias - Alias for the key
    * @param [password] - Optional password to encrypt the secret key
+   * @param [path] - Optional BIP44 path the key was derived from, e.g. "m/44'/877'/0'/0'/0'",
+   * or "m/44'/60'/0'/0/0" for secp256k1 keys
+   * @throws Error if a key was already derived from the path
    * @returns void
    */
   async addKeypair(
     secretKey: string,
     alias: string,
+    password?: string,
+    path?: string
+  ): Promise<void> {
+    return await this.sdk.add_keypair(secretKey, alias, password, path);
+  }
+
+  /**
+   * Generate a keypair and add it to the wallet
+   * @async
+   * @param alias - Alias for the key
+   * @param [extraEntropy] - Optional host entropy mixed into the key, e.g. WebAuthn PRF output
+   * @param [password] - Optional password to encrypt the secret key
+   * @param [scheme] - Signing scheme of the key, defaults to "ed25519"
+   * @throws Error if the extra entropy is shorter than 16 bytes
+   * @returns Implicit address of the generated key
+   */
+  async generateKey(
+    alias: string,
+    extraEntropy?: Uint8Array,
+    password?: string,
+    scheme?: SigningScheme
+  ): Promise<string> {
+    return await this.sdk.generate_key(alias, extraEntropy, password, scheme);
+  }
+
+  /**
+   * Import the secret key of an Ethereum account as a secp256k1 keypair
+   * @async
+   * @param hexPrivkey - Hex encoded secret key, with or without the 0x prefix
+   * @param alias - Alias for the key
+   * @param [password] - Optional password to encrypt the secret key
+   * @throws Error if the key is not a valid secp256k1 key
+   * @returns Namada address and public key, and the Ethereum address of the key
+   */
+  async importEthKey(
+    hexPrivkey: string,
+    alias: string,
     password?: string
+  ): Promise<EthKey> {
+    return await this.sdk.import_eth_key(hexPrivkey, alias, password);
+  }
+
+  /**
+   * Return the message an external wallet, e.g. Keplr or MetaMask, signs to derive a key
+   * @returns message to sign
+   */
+  externalSignatureMessage(): string {
+    return this.sdk.external_signature_message();
+  }
+
+  /**
+   * Derive a keypair from the signature of an external wallet over the message returned by
+   * externalSignatureMessage and add it to the wallet. Signing again restores the same key
+   * @async
+   * @param signature - ECDSA signature as r, s and optionally the recovery byte
+   * @param signer - Address of the external wallet, "0x" prefixed for
+   * Ethereum wallets or bech32 for Cosmos wallets
+   * @param alias - Alias for the key, must not be taken
+   * @param [password] - Optional password to encrypt the secret key
+   * @param [scheme] - Signing scheme of the key, defaults to "ed25519"
+   * @throws Error if the signature is not of the message by the signer or
+   * the alias is taken
+   * @returns Implicit address of the derived key
+   */
+  async deriveFromExternalSignature(
+    signature: Uint8Array,
+    signer: string,
+    alias: string,
+    password?: string,
+    scheme?: SigningScheme
+  ): Promise<string> {
+    return await this.sdk.derive_from_external_signature(
+      signature,
+      signer,
+      alias,
+      password,
+      scheme
+    );
+  }
+
+  /**
+   * Scan accounts derived from the mnemonic and return the ones with on-chain activity
+   * @async
+   * @param mnemonic - BIP39 mnemonic phrase
+   * @param gapLimit - Number of consecutive unused indices after which scanning stops
+   * @param [coinType] - Optional BIP44 coin type, defaults to 877
+   * @param [scheme] - Signing scheme of the derived keys, defaults to "ed25519"
+   * @returns discovered accounts with their path, address, public key and balance
+   */
+  async discoverAccounts(
+    mnemonic: string,
+    gapLimit: number,
+    coinType?: number,
+    scheme?: SigningScheme
+  ): Promise<
+    { path: string; address: string; publicKey: string; balance: string }[]
+  > {
+    const accounts: {
+      path: string;
+      address: string;
+      public_key: string;
+      balance: string;
+    }[] = await this.sdk.discover_accounts(
+      mnemonic,
+      gapLimit,
+      coinType,
+      scheme
+    );
+
+    return accounts.map(({ path, address, public_key, balance }) => ({
+      path,
+      address,
+      publicKey: public_key,
+      balance,
+    }));
+  }
+
+  /**
+   * Derive shielded keys from the same mnemonic as the transparent keys
+   * @async
+   * @param mnemonic - BIP39 mnemonic phrase
+   * @param account - ZIP32 account index
+   * @param [alias] - Optional alias, if provided the keys are added to the wallet
+   * @param [coinType] - Optional BIP44 coin type, defaults to 877
+   * @returns derived path, spending key, viewing key and default payment address
+   */
+  async restoreShieldedAccount(
+    mnemonic: string,
+    account: number,
+    alias?: string,
+    coinType?: number
+  ): Promise<{
+    path: string;
+    spendingKey: string;
+    viewingKey: string;
+    paymentAddress: string;
+  }> {
+    const {
+      path,
+      spending_key: spendingKey,
+      viewing_key: viewingKey,
+      payment_address: paymentAddress,
+    } = await this.sdk.restore_shielded_account(
+      mnemonic,
+      account,
+      alias,
+      coinType
+    );
+
+    return { path, spendingKey, viewingKey, paymentAddress };
+  }
+
+  /**
+   * Set the store tx annotations are persisted in and load the ones saved there
+   * @async
+   * @param save - Function called with the encoded annotations
+   * @param load - Function returning the saved annotations or undefined
+   * @returns void
+   */
+  async setAnnotationStore(
+    save: (annotations: Uint8Array) => void | Promise<void>,
+    load: () => Uint8Array | undefined | Promise<Uint8Array | undefined>
+  ): Promise<void> {
+    return await this.sdk.set_annotation_store(save, load);
+  }
+
+  /**
+   * Label and tag a tx locally, an empty label and no tags remove the annotation
+   * @async
+   * @param hash - Hash of the tx
+   * @param label - Label, e.g. "rent", at most 64 characters
+   * @param [tags] - Case insensitive tags, at most 16
+   * @returns void
+   */
+  async annotateTx(
+    hash: string,
+    label: string,
+    tags: string[] = []
   ): Promise<void> {
-    return await this.sdk.add_keypair(secretKey, alias, password);
+    return await this.sdk.annotate_tx(hash, label, tags);
+  }
+
+  /**
+   * Return tx annotations
+   * @param [tag] - Optional tag the annotations have to have
+   * @returns annotations with their hash, label, tags and time of the last change
+   */
+  getAnnotations(
+    tag?: string
+  ): { hash: string; label: string; tags: string[]; updatedAt: number }[] {
+    const annotations: {
+      hash: string;
+      label: string;
+      tags: string[];
+      updated_at: number;
+    }[] = this.sdk.get_annotations(tag);
+
+    return annotations.map(({ hash, label, tags, updated_at }) => ({
+      hash,
+      label,
+      tags,
+      updatedAt: updated_at,
+    }));
+  }
+
+  /**
+   * Add the annotation of every tx to the tx history
+   * @param history - Result of tx search, or an array of txs with a hash
+   * @returns history with an annotation added to the annotated txs
+   */
+  withAnnotations<T>(history: T): T {
+    return this.sdk.with_annotations(history);
+  }
+
+  /**
+   * Export tx annotations to be included in backups
+   * @returns encoded annotations
+   */
+  exportAnnotations(): Uint8Array {
+    return this.sdk.export_annotations();
+  }
+
+  /**
+   * Import tx annotations from a backup, the most recently changed annotation
+   * wins if a tx is annotated in both
+   * @async
+   * @param annotations - Annotations returned by exportAnnotations
+   * @returns void
+   */
+  async importAnnotations(annotations: Uint8Array): Promise<void> {
+    return await this.sdk.import_annotations(annotations);
+  }
+
+  /**
+   * Return the lowest BIP44 index not derived yet for the coin type and account
+   * @param coinType - BIP44 coin type
+   * @param account - BIP44 account
+   * @returns index
+   */
+  nextUnusedIndex(coinType: number, account: number): number {
+    return this.sdk.next_unused_index(coinType, account);
+  }
+
+  /**
+   * Set the storage wallet metadata is synced to across devices, a WebDAV
+   * server or an S3 bucket with presigned URLs. Blobs are encrypted on the
+   * device with a key derived from syncSecret. Pass neither urlTemplate nor
+   * presign to stop syncing
+   * @param syncSecret - at least 32 bytes shared by the devices of the user
+   * @param [urlTemplate] - URL of the blobs with a {name} placeholder
+   * @param [presign] - returns the URL of a blob for "GET" or "PUT"
+   * @param [headers] - headers sent with every request, e.g. credentials
+   * @returns void
+   */
+  setSyncBackend(
+    syncSecret: Uint8Array,
+    urlTemplate?: string,
+    presign?: (name: string, method: "GET" | "PUT") => Promise<string>,
+    headers?: Record<string, string>
+  ): void {
+    return this.sdk.set_sync_backend(
+      urlTemplate,
+      presign,
+      syncSecret,
+      headers
+    );
+  }
+
+  /**
+   * Upload tx annotations and registered names to the sync backend, public
+   * and viewing keys only with includeKeys
+   * @async
+   * @param [includeKeys] - also upload the watch-only wallet descriptor
+   * @returns names of the uploaded blobs
+   */
+  async syncPush(includeKeys = false): Promise<string[]> {
+    return await this.sdk.sync_push(includeKeys);
+  }
+
+  /**
+   * Download and merge the metadata uploaded by other devices
+   * @async
+   * @returns names of the merged blobs
+   */
+  async syncPull(): Promise<string[]> {
+    return await this.sdk.sync_pull();
+  }
+
+  /**
+   * Upload an encrypted blob of the app, e.g. settings
+   * @async
+   * @param name - blob name of [a-z0-9_-] characters
+   * @param data - blob content
+   * @returns void
+   */
+  async syncPut(name: string, data: Uint8Array): Promise<void> {
+    return await this.sdk.sync_put(name, data);
+  }
+
+  /**
+   * Download a blob uploaded with syncPut
+   * @async
+   * @param name - blob name
+   * @returns blob content, undefined if it doesn't exist
+   */
+  async syncGet(name: string): Promise<Uint8Array | undefined> {
+    return await this.sdk.sync_get(name);
+  }
+
+  /**
+   * List the blobs stored by the sync backend
+   * @async
+   * @returns blob names
+   */
+  async syncList(): Promise<string[]> {
+    return await this.sdk.sync_list();
+  }
+
+  /**
+   * Export the watch-only descriptor of the wallet: public keys, derivation
+   * paths, viewing keys and labels, without any secrets
+   * @async
+   * @returns JSON of the descriptor
+   */
+  async exportDescriptor(): Promise<string> {
+    return await this.sdk.export_wallet_descriptor();
+  }
+
+  /**
+   * Import a descriptor exported by another instance to reconstruct its
+   * watch-only view. Entries already in the wallet are skipped, taken labels
+   * are suffixed deterministically
+   * @async
+   * @param descriptor - JSON returned by exportDescriptor
+   * @returns labels of the imported and skipped entries
+   */
+  async importDescriptor(descriptor: string): Promise<ImportSummary> {
+    return await this.sdk.import_wallet_descriptor(descriptor);
   }
 }
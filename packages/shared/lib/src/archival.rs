@@ -0,0 +1,137 @@
+//! Archival routing - pruned nodes only keep blocks and state above their earliest height.
+//! Queries of older heights are sent to the archival RPC when one is set, otherwise they fail
+//! with `ArchivalError::PrunedHeight` instead of a generic RPC error.
+use thiserror::Error;
+use wasm_bindgen::JsError;
+
+// Earliest height only grows as the node prunes, a recent value is good enough for routing
+pub const EARLIEST_HEIGHT_TTL_SECS: u64 = 60;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ArchivalError {
+    #[error(
+        "Height {height} is pruned, the earliest height of the node is {earliest}. Set an \
+         archival RPC to query it."
+    )]
+    PrunedHeight { height: u64, earliest: u64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Route {
+    Primary,
+    Archival,
+}
+
+/// Returns the node a query of the height is sent to
+///
+/// # Arguments
+///
+/// * `height` - queried height
+/// * `earliest` - earliest height of the primary node
+/// * `has_archival` - whether an archival RPC is set
+///
+/// # Errors
+///
+/// Returns `PrunedHeight` if the height is pruned and there is no archival RPC
+pub fn route(height: u64, earliest: u64, has_archival: bool) -> Result<Route, ArchivalError> {
+    match (height >= earliest, has_archival) {
+        (true, _) => Ok(Route::Primary),
+        (false, true) => Ok(Route::Archival),
+        (false, false) => Err(ArchivalError::PrunedHeight { height, earliest }),
+    }
+}
+
+/// Recognizes the error CometBFT returns for pruned heights, e.g. "height 5 is not available,
+/// lowest height is 100", which the cached earliest height may have missed
+pub fn pruned_height_error(error: &str) -> Option<ArchivalError> {
+    let number_after = |marker: &str| -> Option<u64> {
+        let start = error.find(marker)? + marker.len();
+        error[start..]
+            .trim_start()
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()
+    };
+
+    Some(ArchivalError::PrunedHeight {
+        height: number_after("height ")?,
+        earliest: number_after("lowest height is")?,
+    })
+}
+
+/// Turns the error CometBFT returns for pruned heights into `ArchivalError::PrunedHeight`, e.g.
+/// when the node pruned further since its earliest height was cached
+pub fn with_pruned_height<T, E>(result: Result<T, E>) -> Result<T, JsError>
+where
+    E: std::error::Error,
+{
+    result.map_err(|err| match pruned_height_error(&err.to_string()) {
+        Some(pruned) => pruned.into(),
+        None => err.into(),
+    })
+}
+
+/// Earliest height of the primary node, refreshed after `EARLIEST_HEIGHT_TTL_SECS`
+#[derive(Clone, Debug, Default)]
+pub struct EarliestHeight {
+    cached: Option<(u64, u64)>,
+}
+
+impl EarliestHeight {
+    /// Returns the cached height unless it is older than the ttl
+    pub fn get(&self, now_secs: u64) -> Option<u64> {
+        self.cached
+            .filter(|(_, checked_at)| {
+                now_secs.saturating_sub(*checked_at) < EARLIEST_HEIGHT_TTL_SECS
+            })
+            .map(|(height, _)| height)
+    }
+
+    pub fn set(&mut self, height: u64, now_secs: u64) {
+        self.cached = Some((height, now_secs));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn routes_pruned_heights() {
+        assert_eq!(route(150, 100, false), Ok(Route::Primary));
+        assert_eq!(route(50, 100, true), Ok(Route::Archival));
+        assert_eq!(
+            route(50, 100, false),
+            Err(ArchivalError::PrunedHeight {
+                height: 50,
+                earliest: 100
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_pruned_height_errors() {
+        assert_eq!(
+            pruned_height_error(
+                r#"{"code":-32603,"data":"height 5 is not available, lowest height is 100"}"#
+            ),
+            Some(ArchivalError::PrunedHeight {
+                height: 5,
+                earliest: 100
+            })
+        );
+        assert_eq!(pruned_height_error("connection refused"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn caches_earliest_height() {
+        let mut earliest = EarliestHeight::default();
+        assert_eq!(earliest.get(1000), None);
+
+        earliest.set(100, 1000);
+        assert_eq!(earliest.get(1000 + EARLIEST_HEIGHT_TTL_SECS - 1), Some(100));
+        assert_eq!(earliest.get(1000 + EARLIEST_HEIGHT_TTL_SECS), None);
+    }
+}
@@ -0,0 +1,437 @@
+//! Ethereum bridge - ERC20 tokens whitelisted by the bridge and their metadata. Symbols and
+//! decimals are not stored on chain for every asset, so the host configures a mapping which
+//! takes precedence over the on-chain denomination. Relayers quote the fee they take for
+//! relaying a transfer to Ethereum, the host configures those quotes as well.
+//! Transfers from Ethereum which can't be minted as the ERC20 token end up as non-usable tokens
+//! (NUTs). They don't show up in regular balances and can only be sent back to Ethereum.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use gloo_utils::format::JsValueSerdeExt;
+use namada_sdk::address::{Address, InternalAddress};
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::ethereum_events::EthAddress;
+use namada_sdk::token::{Amount, Denomination};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::types::amount::{BaseAmount, StringAmount};
+
+// Storage sub-key of the bridge whitelist and its key types
+pub const WHITELIST_SEGMENT: &str = "whitelist";
+const WHITELISTED: &str = "whitelisted";
+const CAP: &str = "cap";
+const DENOMINATION: &str = "denomination";
+// Gas limit of a bridge pool tx used when the caller doesn't provide one
+pub const BRIDGE_POOL_GAS_LIMIT: u64 = 50_000;
+
+thread_local! {
+    static ERC20_METADATA: RefCell<BTreeMap<String, Erc20Metadata>> = RefCell::new(BTreeMap::new());
+    static RELAYER_QUOTES: RefCell<Vec<RelayerQuote>> = RefCell::new(vec![]);
+}
+
+/// Metadata of an ERC20 token configured by the host
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Erc20Metadata {
+    // Hex encoded Ethereum address, e.g. "0x6b17...1d0f"
+    pub address: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// ERC20 token whitelisted by the bridge
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Tsify)]
+pub struct Erc20Token {
+    pub address: String,
+    // None if the token is not in the configured mapping
+    pub symbol: Option<String>,
+    // Configured decimals, or the on-chain denomination if not configured
+    pub decimals: Option<u8>,
+    // Max amount that can be bridged, in base units
+    pub cap: Option<BaseAmount>,
+}
+
+// Ethereum addresses are case insensitive, checksummed ones included
+fn normalize_address(address: &str) -> String {
+    let address = address.trim().to_lowercase();
+    match address.strip_prefix("0x") {
+        Some(_) => address,
+        None => format!("0x{}", address),
+    }
+}
+
+/// Collects whitelisted tokens from the storage values under the whitelist prefix. Keys end
+/// with `whitelist/<asset>/<key type>`, values are borsh encoded.
+///
+/// # Arguments
+///
+/// * `values` - storage keys and their values
+/// * `metadata` - configured metadata by normalized address
+pub fn erc20_whitelist(
+    values: Vec<(String, Vec<u8>)>,
+    metadata: &BTreeMap<String, Erc20Metadata>,
+) -> Vec<Erc20Token> {
+    let mut tokens: BTreeMap<String, (bool, Erc20Token)> = BTreeMap::new();
+
+    for (key, value) in values {
+        let segments: Vec<&str> = key.split('/').collect();
+        let [.., WHITELIST_SEGMENT, asset, key_type] = segments[..] else {
+            continue;
+        };
+        let address = normalize_address(asset);
+        let (whitelisted, token) = tokens.entry(address.clone()).or_insert_with(|| {
+            let token = Erc20Token {
+                address,
+                ..Erc20Token::default()
+            };
+            (false, token)
+        });
+
+        match key_type {
+            WHITELISTED => *whitelisted = bool::try_from_slice(&value).unwrap_or_default(),
+            CAP => token.cap = Amount::try_from_slice(&value).ok().map(BaseAmount::from),
+            DENOMINATION => {
+                let denom = Denomination::try_from_slice(&value).ok();
+                token.decimals = denom.map(|denom| denom.0);
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+        .into_values()
+        .filter(|(whitelisted, _)| *whitelisted)
+        .map(|(_, token)| match metadata.get(&token.address) {
+            Some(metadata) => Erc20Token {
+                symbol: Some(metadata.symbol.clone()),
+                decimals: Some(metadata.decimals),
+                ..token
+            },
+            None => token,
+        })
+        .collect()
+}
+
+/// Returns the configured ERC20 metadata by normalized address
+pub fn erc20_metadata() -> BTreeMap<String, Erc20Metadata> {
+    ERC20_METADATA.with(|metadata| metadata.borrow().clone())
+}
+
+/// Sets symbols and decimals of ERC20 tokens, replacing the previous mapping
+///
+/// # Arguments
+///
+/// * `metadata` - array of `{ address, symbol, decimals }`
+///
+/// # Errors
+///
+/// Returns an error if the metadata can't be deserialized
+#[wasm_bindgen]
+pub fn set_erc20_metadata(metadata: JsValue) -> Result<(), JsError> {
+    let metadata: Vec<Erc20Metadata> = metadata.into_serde()?;
+    let metadata = metadata
+        .into_iter()
+        .map(|token| {
+            let address = normalize_address(&token.address);
+            (address.clone(), Erc20Metadata { address, ..token })
+        })
+        .collect();
+
+    ERC20_METADATA.with(|current| *current.borrow_mut() = metadata);
+
+    Ok(())
+}
+
+/// Returns the configured metadata of an ERC20 token, or undefined
+#[wasm_bindgen]
+pub fn get_erc20_metadata(address: &str) -> Result<JsValue, JsError> {
+    let metadata =
+        ERC20_METADATA.with(|metadata| metadata.borrow().get(&normalize_address(address)).cloned());
+
+    Ok(JsValue::from_serde(&metadata)?)
+}
+
+/// NUT balance of an owner, recoverable with a bridge transfer having `nut` set
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct NutBalance {
+    // ERC20 address the NUT was minted for
+    pub asset: String,
+    // Namada address of the NUT
+    pub token: String,
+    // Amount in base units
+    pub amount: BaseAmount,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+/// Returns the Namada address of the NUT of an ERC20 asset
+///
+/// # Errors
+///
+/// Returns an error if the asset is not a valid Ethereum address
+pub fn nut_address(asset: &str) -> Result<Address, JsError> {
+    let asset = EthAddress::from_str(&normalize_address(asset))
+        .map_err(|e| JsError::new(&format!("Invalid ERC20 address {}: {}", asset, e)))?;
+
+    Ok(Address::Internal(InternalAddress::Nut(asset)))
+}
+
+/// Returns the assets NUT balances are looked up for, the whitelisted tokens and the ones
+/// with configured metadata, as NUTs of delisted tokens are still recoverable
+pub fn nut_candidates(
+    whitelist: Vec<Erc20Token>,
+    metadata: &BTreeMap<String, Erc20Metadata>,
+) -> Vec<Erc20Token> {
+    let mut tokens: BTreeMap<String, Erc20Token> = whitelist
+        .into_iter()
+        .map(|token| (token.address.clone(), token))
+        .collect();
+    for (address, metadata) in metadata {
+        tokens.entry(address.clone()).or_insert_with(|| Erc20Token {
+            address: address.clone(),
+            symbol: Some(metadata.symbol.clone()),
+            decimals: Some(metadata.decimals),
+            cap: None,
+        });
+    }
+
+    tokens.into_values().collect()
+}
+
+/// Fee a relayer takes for relaying a transfer to Ethereum, paid on Namada
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct RelayerQuote {
+    // Quote for a single ERC20 asset, or for all assets if not set
+    pub asset: Option<String>,
+    // Namada token the relayer is paid in
+    pub fee_token: String,
+    // Amount in base units of the fee token
+    pub amount: BaseAmount,
+}
+
+/// Fee in base units of a token
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct TokenFee {
+    pub token: String,
+    pub amount: BaseAmount,
+}
+
+/// Total cost of a transfer to Ethereum, paid on top of the transferred amount
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct BridgeFeeEstimate {
+    pub asset: String,
+    pub amount: StringAmount,
+    // Wrapper fee of the bridge pool tx
+    pub namada_fee: TokenFee,
+    // None if no relayer quote is configured for the asset
+    pub relayer_fee: Option<TokenFee>,
+    // Sum of the fees by token
+    pub total: Vec<TokenFee>,
+}
+
+/// Returns the quote of the asset, falling back to the quote for all assets
+pub fn relayer_quote(quotes: &[RelayerQuote], asset: &str) -> Option<RelayerQuote> {
+    let asset = normalize_address(asset);
+    quotes
+        .iter()
+        .find(|quote| quote.asset.as_deref().map(normalize_address) == Some(asset.clone()))
+        .or_else(|| quotes.iter().find(|quote| quote.asset.is_none()))
+        .cloned()
+}
+
+/// Combines the wrapper fee and the relayer fee of a transfer to Ethereum.
+/// Returns None on arithmetic overflow or if the quoted amount is invalid.
+///
+/// # Arguments
+///
+/// * `asset` - ERC20 address
+/// * `amount` - transferred amount
+/// * `fee_token` - token the wrapper fee is paid in
+/// * `gas_price` - min gas price of the fee token, in base units
+/// * `gas_limit` - gas limit of the bridge pool tx
+/// * `quote` - relayer quote of the asset
+pub fn bridge_fee(
+    asset: &str,
+    amount: StringAmount,
+    fee_token: String,
+    gas_price: Amount,
+    gas_limit: u64,
+    quote: Option<RelayerQuote>,
+) -> Option<BridgeFeeEstimate> {
+    let namada_fee = gas_price.checked_mul(Amount::from_u64(gas_limit))?;
+    let relayer_fee = match quote {
+        Some(quote) => Some((quote.fee_token, quote.amount.to_amount().ok()?)),
+        None => None,
+    };
+
+    let mut total: BTreeMap<String, Amount> = BTreeMap::new();
+    for (token, fee) in std::iter::once((fee_token.clone(), namada_fee)).chain(relayer_fee.clone())
+    {
+        let sum = total.entry(token).or_default();
+        *sum = sum.checked_add(fee)?;
+    }
+
+    let token_fee = |token: String, amount: Amount| TokenFee {
+        token,
+        amount: BaseAmount::from(amount),
+    };
+
+    Some(BridgeFeeEstimate {
+        asset: normalize_address(asset),
+        amount,
+        namada_fee: token_fee(fee_token, namada_fee),
+        relayer_fee: relayer_fee.map(|(token, fee)| token_fee(token, fee)),
+        total: total
+            .into_iter()
+            .map(|(token, fee)| token_fee(token, fee))
+            .collect(),
+    })
+}
+
+/// Returns the configured relayer quotes
+pub fn relayer_quotes() -> Vec<RelayerQuote> {
+    RELAYER_QUOTES.with(|quotes| quotes.borrow().clone())
+}
+
+/// Sets the fees relayers quote for transfers to Ethereum, replacing the previous quotes
+///
+/// # Arguments
+///
+/// * `quotes` - array of `{ asset?, fee_token, amount }`, a quote without an asset applies to
+///   all assets without their own quote
+///
+/// # Errors
+///
+/// Returns an error if the quotes can't be deserialized or an amount is invalid
+#[wasm_bindgen]
+pub fn set_relayer_quotes(quotes: JsValue) -> Result<(), JsError> {
+    let quotes: Vec<RelayerQuote> = quotes.into_serde()?;
+    for quote in quotes.iter() {
+        quote.amount.to_amount(0)?;
+    }
+
+    RELAYER_QUOTES.with(|current| *current.borrow_mut() = quotes);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::borsh;
+    use wasm_bindgen_test::*;
+
+    const DAI: &str = "0x6B175474E89094C44Da98b954EedeAC495271d0F";
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    fn key(asset: &str, key_type: &str) -> String {
+        format!("#tnam1qyz/whitelist/{}/{}", asset, key_type)
+    }
+
+    #[wasm_bindgen_test]
+    fn resolves_whitelisted_tokens() {
+        let values = vec![
+            (key(DAI, WHITELISTED), borsh::to_vec(&true).unwrap()),
+            (
+                key(DAI, DENOMINATION),
+                borsh::to_vec(&Denomination(18)).unwrap(),
+            ),
+            (
+                key(DAI, CAP),
+                borsh::to_vec(&Amount::from_u64(100)).unwrap(),
+            ),
+            (key(USDC, WHITELISTED), borsh::to_vec(&true).unwrap()),
+            (
+                key(USDC, DENOMINATION),
+                borsh::to_vec(&Denomination(0)).unwrap(),
+            ),
+            (
+                key("0x0000000000000000000000000000000000000001", WHITELISTED),
+                borsh::to_vec(&false).unwrap(),
+            ),
+        ];
+        let metadata = BTreeMap::from([(
+            normalize_address(USDC),
+            Erc20Metadata {
+                address: normalize_address(USDC),
+                symbol: String::from("USDC"),
+                decimals: 6,
+            },
+        )]);
+
+        let tokens = erc20_whitelist(values, &metadata);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].address, normalize_address(DAI));
+        assert_eq!(tokens[0].symbol, None);
+        assert_eq!(tokens[0].decimals, Some(18));
+        assert_eq!(tokens[0].cap, Some(BaseAmount::new(String::from("100"))));
+        assert_eq!(tokens[1].symbol, Some(String::from("USDC")));
+        assert_eq!(tokens[1].decimals, Some(6));
+    }
+
+    #[wasm_bindgen_test]
+    fn can_estimate_bridge_fee() {
+        let quotes = vec![
+            RelayerQuote {
+                asset: None,
+                fee_token: String::from("nam"),
+                amount: BaseAmount::new(String::from("1000")),
+            },
+            RelayerQuote {
+                asset: Some(USDC.to_uppercase().replace("0X", "0x")),
+                fee_token: String::from("nam"),
+                amount: BaseAmount::new(String::from("3000")),
+            },
+        ];
+        assert_eq!(
+            relayer_quote(&quotes, USDC).unwrap().amount.to_string(),
+            "3000"
+        );
+        assert_eq!(
+            relayer_quote(&quotes, DAI).unwrap().amount.to_string(),
+            "1000"
+        );
+
+        let estimate = bridge_fee(
+            DAI,
+            StringAmount::new(String::from("5")),
+            String::from("nam"),
+            Amount::from_u64(2),
+            BRIDGE_POOL_GAS_LIMIT,
+            relayer_quote(&quotes, DAI),
+        )
+        .unwrap();
+
+        assert_eq!(estimate.namada_fee.amount.to_string(), "100000");
+        assert_eq!(estimate.total.len(), 1);
+        assert_eq!(estimate.total[0].amount.to_string(), "101000");
+    }
+
+    #[wasm_bindgen_test]
+    fn nut_candidates_include_configured_tokens() {
+        let whitelist = vec![Erc20Token {
+            address: normalize_address(DAI),
+            ..Erc20Token::default()
+        }];
+        let metadata = BTreeMap::from([(
+            normalize_address(USDC),
+            Erc20Metadata {
+                address: normalize_address(USDC),
+                symbol: String::from("USDC"),
+                decimals: 6,
+            },
+        )]);
+
+        let candidates = nut_candidates(whitelist, &metadata);
+
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[1].symbol, Some(String::from("USDC")));
+        assert!(matches!(
+            nut_address(DAI),
+            Ok(Address::Internal(InternalAddress::Nut(_)))
+        ));
+    }
+}
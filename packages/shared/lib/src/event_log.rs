@@ -0,0 +1,199 @@
+//! Event log - typed filters over CometBFT tx_search and block_results, and decoding of the
+//! attributes of Namada events, e.g. "tx/applied" with its code, gas and inner tx results.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Filter of `Query::query_events`. Events of txs are searched with tx_search, `height`
+/// alone returns all events of the block, including the ones not emitted by txs.
+#[derive(Clone, Debug, Default, Deserialize, Tsify)]
+#[serde(default)]
+pub struct EventFilter {
+    pub tx_hash: Option<String>,
+    // Event type, e.g. "tx/applied", required to filter by attributes on the node
+    pub event_type: Option<String>,
+    // Vec of (attribute key, value) the event has to have
+    pub attributes: Vec<(String, String)>,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub height: Option<u64>,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub from_height: Option<u64>,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub to_height: Option<u64>,
+}
+
+impl EventFilter {
+    /// Returns true if only the block at `height` has to be read
+    pub fn is_block_query(&self) -> bool {
+        self.height.is_some()
+            && self.tx_hash.is_none()
+            && self.from_height.is_none()
+            && self.to_height.is_none()
+    }
+
+    /// Returns the CometBFT query of the filter
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filter has no conditions, attributes are given without an
+    /// event type or a value contains a quote
+    pub fn to_query(&self) -> Result<String, String> {
+        let quoted = |value: &str| -> Result<String, String> {
+            if value.contains('\'') {
+                return Err(format!("Filter values can't contain quotes: {}", value));
+            }
+            Ok(format!("'{}'", value))
+        };
+        let mut conditions = vec![];
+
+        if let Some(hash) = &self.tx_hash {
+            conditions.push(format!("tx.hash = {}", quoted(&hash.to_uppercase())?));
+        }
+        if let Some(height) = self.height {
+            conditions.push(format!("tx.height = {}", height));
+        }
+        if let Some(from_height) = self.from_height {
+            conditions.push(format!("tx.height >= {}", from_height));
+        }
+        if let Some(to_height) = self.to_height {
+            conditions.push(format!("tx.height <= {}", to_height));
+        }
+        match &self.event_type {
+            Some(event_type) => {
+                if self.attributes.is_empty() {
+                    conditions.push(format!("{} EXISTS", event_type));
+                }
+                for (key, value) in self.attributes.iter() {
+                    conditions.push(format!("{}.{} = {}", event_type, key, quoted(value)?));
+                }
+            }
+            None if !self.attributes.is_empty() => {
+                return Err(String::from(
+                    "Event type is required to filter by attributes",
+                ))
+            }
+            None => {}
+        }
+
+        if conditions.is_empty() {
+            return Err(String::from("Filter has no conditions"));
+        }
+
+        Ok(conditions.join(" AND "))
+    }
+
+    /// Returns true if the event matches the event type and attributes of the filter
+    pub fn matches(&self, kind: &str, attributes: &BTreeMap<String, String>) -> bool {
+        self.event_type
+            .as_ref()
+            .map_or(true, |event_type| event_type == kind)
+            && self
+                .attributes
+                .iter()
+                .all(|(key, value)| attributes.get(key) == Some(value))
+    }
+}
+
+/// Event with the attributes Namada emits decoded
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct NamadaEvent {
+    // Event type, e.g. "tx/applied"
+    pub kind: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    // None for events not emitted by a tx
+    pub tx_hash: Option<String>,
+    // Result code of the tx, "0" on success
+    pub code: Option<String>,
+    pub gas_used: Option<String>,
+    // Results of the inner txs of the batch
+    #[tsify(type = "unknown")]
+    pub batch: Option<serde_json::Value>,
+    pub info: Option<String>,
+    // All attributes, including the decoded ones
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Decodes an event from its type and attributes
+///
+/// # Arguments
+///
+/// * `kind` - event type
+/// * `attributes` - attribute keys and values
+/// * `height` - height of the block the event was emitted in
+/// * `tx_hash` - hash of the tx which emitted the event
+pub fn decode_event(
+    kind: String,
+    attributes: BTreeMap<String, String>,
+    height: u64,
+    tx_hash: Option<String>,
+) -> NamadaEvent {
+    let attribute = |key: &str| attributes.get(key).cloned();
+
+    NamadaEvent {
+        height,
+        tx_hash: tx_hash.or_else(|| attribute("hash")),
+        code: attribute("code"),
+        gas_used: attribute("gas_used"),
+        batch: attribute("batch").and_then(|batch| serde_json::from_str(&batch).ok()),
+        info: attribute("info").filter(|info| !info.is_empty()),
+        kind,
+        attributes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn can_build_event_query() {
+        let filter = EventFilter {
+            event_type: Some(String::from("tx/applied")),
+            attributes: vec![(String::from("code"), String::from("0"))],
+            from_height: Some(10),
+            ..EventFilter::default()
+        };
+        assert_eq!(
+            filter.to_query().unwrap(),
+            "tx.height >= 10 AND tx/applied.code = '0'"
+        );
+
+        let filter = EventFilter {
+            attributes: vec![(String::from("code"), String::from("0"))],
+            ..EventFilter::default()
+        };
+        assert!(filter.to_query().is_err());
+        assert!(EventFilter::default().to_query().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_decode_event() {
+        let attributes = BTreeMap::from([
+            (String::from("code"), String::from("0")),
+            (String::from("gas_used"), String::from("1200")),
+            (String::from("batch"), String::from(r#"{"0xab":{"Ok":{}}}"#)),
+            (String::from("info"), String::new()),
+        ]);
+        let filter = EventFilter {
+            event_type: Some(String::from("tx/applied")),
+            attributes: vec![(String::from("code"), String::from("0"))],
+            ..EventFilter::default()
+        };
+        assert!(filter.matches("tx/applied", &attributes));
+        assert!(!filter.matches("tx/rejected", &attributes));
+
+        let event = decode_event(String::from("tx/applied"), attributes, 5, None);
+
+        assert_eq!(event.code.as_deref(), Some("0"));
+        assert_eq!(event.gas_used.as_deref(), Some("1200"));
+        assert!(event.batch.is_some());
+        assert_eq!(event.info, None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Explorer registry - URL templates of the block explorer of each chain, so deep links to txs
+//! and addresses are built the same way across the interface.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use wasm_bindgen::prelude::*;
+
+const HASH_PLACEHOLDER: &str = "{hash}";
+const ADDRESS_PLACEHOLDER: &str = "{address}";
+
+thread_local! {
+    static EXPLORERS: RefCell<BTreeMap<String, Explorer>> = RefCell::new(BTreeMap::new());
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explorer {
+    // e.g. "https://explorer.example/tx/{hash}"
+    tx_template: String,
+    // e.g. "https://explorer.example/account/{address}"
+    address_template: String,
+}
+
+impl Explorer {
+    pub fn new(tx_template: String, address_template: String) -> Result<Explorer, String> {
+        if !tx_template.contains(HASH_PLACEHOLDER) {
+            return Err(format!("Tx URL template has no {}", HASH_PLACEHOLDER));
+        }
+        if !address_template.contains(ADDRESS_PLACEHOLDER) {
+            return Err(format!(
+                "Address URL template has no {}",
+                ADDRESS_PLACEHOLDER
+            ));
+        }
+
+        Ok(Explorer {
+            tx_template,
+            address_template,
+        })
+    }
+
+    pub fn tx_url(&self, hash: &str) -> String {
+        // Nodes report hashes in upper case, explorers match them in lower case
+        self.tx_template
+            .replace(HASH_PLACEHOLDER, &hash.to_lowercase())
+    }
+
+    pub fn address_url(&self, address: &str) -> String {
+        self.address_template.replace(ADDRESS_PLACEHOLDER, address)
+    }
+}
+
+/// Sets the explorer of the chain, replacing the previous one
+///
+/// # Arguments
+///
+/// * `chain_id` - chain the explorer indexes
+/// * `tx_template` - URL of a tx with a `{hash}` placeholder
+/// * `address_template` - URL of an address with an `{address}` placeholder
+///
+/// # Errors
+///
+/// Returns an error if a template has no placeholder
+#[wasm_bindgen]
+pub fn set_explorer(
+    chain_id: String,
+    tx_template: String,
+    address_template: String,
+) -> Result<(), JsError> {
+    let explorer = Explorer::new(tx_template, address_template).map_err(|e| JsError::new(&e))?;
+    EXPLORERS.with(|explorers| explorers.borrow_mut().insert(chain_id, explorer));
+
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn remove_explorer(chain_id: &str) -> bool {
+    EXPLORERS.with(|explorers| explorers.borrow_mut().remove(chain_id).is_some())
+}
+
+/// Returns the explorer URL of the tx, or undefined if no explorer is set for the chain
+#[wasm_bindgen]
+pub fn explorer_tx_url(chain_id: &str, hash: &str) -> Option<String> {
+    EXPLORERS.with(|explorers| {
+        explorers
+            .borrow()
+            .get(chain_id)
+            .map(|explorer| explorer.tx_url(hash))
+    })
+}
+
+/// Returns the explorer URL of the address, or undefined if no explorer is set for the chain
+#[wasm_bindgen]
+pub fn explorer_address_url(chain_id: &str, address: &str) -> Option<String> {
+    EXPLORERS.with(|explorers| {
+        explorers
+            .borrow()
+            .get(chain_id)
+            .map(|explorer| explorer.address_url(address))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn builds_urls_from_templates() {
+        let explorer = Explorer::new(
+            String::from("https://explorer.example/tx/{hash}"),
+            String::from("https://explorer.example/account/{address}"),
+        )
+        .unwrap();
+
+        assert_eq!(explorer.tx_url("ABCD"), "https://explorer.example/tx/abcd");
+        assert_eq!(
+            explorer.address_url("tnam1abc"),
+            "https://explorer.example/account/tnam1abc"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_templates_without_placeholder() {
+        assert!(Explorer::new(
+            String::from("https://explorer.example/tx"),
+            String::from("https://explorer.example/account/{address}"),
+        )
+        .is_err());
+    }
+}
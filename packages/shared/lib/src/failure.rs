@@ -0,0 +1,254 @@
+//! Failure explanation - turns the result of a failed tx into a structured reason, e.g. the VPs
+//! which rejected it or the gas limit it ran out of, so support doesn't have to read raw logs.
+use namada_sdk::tx::Tx;
+use serde::Serialize;
+use tsify::Tsify;
+
+/// Result of an inner tx of the batch
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct InnerTxResult {
+    pub hash: String,
+    // One of "applied", "rejected" or "error"
+    pub status: String,
+    // Addresses of the VPs which rejected the inner tx
+    pub rejected_vps: Vec<String>,
+    // VP errors as "address: message", or the error of the tx code
+    pub errors: Vec<String>,
+}
+
+/// Explanation returned by `Query::explain_failure`
+#[derive(Clone, Debug, Serialize, Tsify)]
+pub struct FailureExplanation {
+    pub hash: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    // Result code of the tx, 0 if it was applied
+    pub code: u32,
+    // One of "applied", "vp_rejected", "gas_exhausted", "invalid_section", "invalid_signature",
+    // "wasm_error", "fee_error", "expired", "replay", "not_allowlisted", "unknown"
+    pub reason: String,
+    pub message: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub gas_used: u64,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub gas_limit: Option<u64>,
+    pub inner_txs: Vec<InnerTxResult>,
+    // Commitments of the tx referring to sections it doesn't contain
+    pub invalid_sections: Vec<String>,
+    // Inner tx results of the dry-run at the height before the tx, None if the node can't
+    // dry-run at past heights
+    pub dry_run: Option<Vec<InnerTxResult>>,
+    pub log: String,
+}
+
+/// Decodes the results of the inner txs, the "batch" attribute of "tx/applied" events or a
+/// serialized dry-run result
+pub fn inner_tx_results(batch: &serde_json::Value) -> Vec<InnerTxResult> {
+    // Dry-run results are serialized with the gas used after the batch
+    let batch = match batch {
+        serde_json::Value::Array(values) => values.first().unwrap_or(batch),
+        _ => batch,
+    };
+    let Some(results) = batch.as_object() else {
+        return vec![];
+    };
+
+    results
+        .iter()
+        .map(|(hash, result)| {
+            if let Some(err) = result.get("Err") {
+                let error = err
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| err.to_string());
+                return InnerTxResult {
+                    hash: hash.clone(),
+                    status: String::from("error"),
+                    rejected_vps: vec![],
+                    errors: vec![error],
+                };
+            }
+
+            let vps_result = result.pointer("/Ok/vps_result");
+            let rejected_vps: Vec<String> = vps_result
+                .and_then(|vps| vps.get("rejected_vps"))
+                .and_then(|vps| vps.as_array())
+                .map(|vps| vps.iter().map(json_string).collect())
+                .unwrap_or_default();
+            let errors: Vec<String> = vps_result
+                .and_then(|vps| vps.get("errors"))
+                .and_then(|errors| errors.as_array())
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .map(|error| match error.as_array().map(Vec::as_slice) {
+                            Some([address, message]) => {
+                                format!("{}: {}", json_string(address), json_string(message))
+                            }
+                            _ => json_string(error),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            InnerTxResult {
+                hash: hash.clone(),
+                status: String::from(if rejected_vps.is_empty() {
+                    "applied"
+                } else {
+                    "rejected"
+                }),
+                rejected_vps,
+                errors,
+            }
+        })
+        .collect()
+}
+
+fn json_string(value: &serde_json::Value) -> String {
+    value
+        .as_str()
+        .map(String::from)
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Returns the commitments of the tx whose code or data section is missing
+pub fn missing_sections(tx: &Tx) -> Vec<String> {
+    let mut missing = vec![];
+
+    for commitments in tx.commitments() {
+        let hash = commitments.get_hash().to_string();
+        if tx.get_section(commitments.code_sechash()).is_none() {
+            missing.push(format!("{}: code", hash));
+        }
+        if tx.get_section(commitments.data_sechash()).is_none() {
+            missing.push(format!("{}: data", hash));
+        }
+    }
+
+    missing
+}
+
+/// Returns the reason and message of the failure
+///
+/// # Arguments
+///
+/// * `code` - result code of the tx, see `namada_sdk::tx::data::ResultCode`
+/// * `gas_used` - gas used by the tx
+/// * `gas_limit` - gas limit of the wrapper
+/// * `inner_txs` - results of the inner txs
+/// * `invalid_sections` - commitments with missing sections
+pub fn explain(
+    code: u32,
+    gas_used: u64,
+    gas_limit: Option<u64>,
+    inner_txs: &[InnerTxResult],
+    invalid_sections: &[String],
+) -> (&'static str, String) {
+    let out_of_gas = gas_limit.map_or(false, |gas_limit| gas_used >= gas_limit)
+        || inner_txs
+            .iter()
+            .flat_map(|inner_tx| inner_tx.errors.iter())
+            .any(|error| error.to_lowercase().contains("gas"));
+
+    if code == 8 || (out_of_gas && inner_txs.iter().any(|tx| tx.status != "applied")) {
+        return (
+            "gas_exhausted",
+            format!(
+                "Tx ran out of gas, {} used of the limit {}",
+                gas_used,
+                gas_limit.map_or_else(|| String::from("unknown"), |limit| limit.to_string())
+            ),
+        );
+    }
+    if let Some(inner_tx) = inner_txs.iter().find(|tx| tx.status == "rejected") {
+        return (
+            "vp_rejected",
+            format!(
+                "Inner tx {} was rejected by {}",
+                inner_tx.hash,
+                inner_tx.rejected_vps.join(", ")
+            ),
+        );
+    }
+    if !invalid_sections.is_empty() {
+        return (
+            "invalid_section",
+            format!("Tx is missing sections: {}", invalid_sections.join(", ")),
+        );
+    }
+    if let Some(inner_tx) = inner_txs.iter().find(|tx| tx.status == "error") {
+        return (
+            "wasm_error",
+            format!(
+                "Inner tx {} failed: {}",
+                inner_tx.hash,
+                inner_tx.errors.join(", ")
+            ),
+        );
+    }
+
+    let (reason, message) = match code {
+        0 => ("applied", "Tx was applied"),
+        1 => ("wasm_error", "Tx code failed to run"),
+        2 => (
+            "invalid_section",
+            "Tx is malformed or its sections don't match",
+        ),
+        3 => ("invalid_signature", "Tx has a missing or invalid signature"),
+        5 => ("replay", "Tx was already applied"),
+        6 => ("invalid_section", "Tx was built for another chain"),
+        7 => ("expired", "Tx expired before it was included in a block"),
+        9 => ("fee_error", "Fee payer couldn't pay the fee"),
+        12 => ("not_allowlisted", "Tx code is not allowed on the chain"),
+        _ => ("unknown", "Tx failed for an unknown reason"),
+    };
+
+    (reason, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    #[wasm_bindgen_test]
+    fn explains_rejected_vps() {
+        let batch: serde_json::Value = serde_json::from_str(&format!(
+            r#"{{"0xab":{{"Ok":{{"vps_result":{{"rejected_vps":["{0}"],"errors":[["{0}","Insufficient balance"]]}}}}}}}}"#,
+            ALICE
+        ))
+        .unwrap();
+        let inner_txs = inner_tx_results(&batch);
+
+        assert_eq!(inner_txs[0].rejected_vps, vec![ALICE.to_string()]);
+        assert_eq!(
+            inner_txs[0].errors,
+            vec![format!("{}: Insufficient balance", ALICE)]
+        );
+        assert_eq!(
+            explain(1, 100, Some(1000), &inner_txs, &[]).0,
+            "vp_rejected"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn explains_gas_exhaustion() {
+        let batch: serde_json::Value =
+            serde_json::from_str(r#"{"0xab":{"Err":"Gas error: Transaction gas exceeded"}}"#)
+                .unwrap();
+        let inner_txs = inner_tx_results(&batch);
+
+        assert_eq!(inner_txs[0].status, "error");
+        assert_eq!(
+            explain(1, 1000, Some(1000), &inner_txs, &[]).0,
+            "gas_exhausted"
+        );
+        assert_eq!(explain(7, 10, Some(1000), &[], &[]).0, "expired");
+    }
+}
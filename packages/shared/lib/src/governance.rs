@@ -0,0 +1,544 @@
+//! Governance - proposal JSON builders and validation of the on-chain proposal rules,
+//! so invalid proposals are rejected before the deposit is spent.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use gloo_utils::format::JsValueSerdeExt;
+use namada_sdk::address::Address;
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::governance::parameters::GovernanceParameters;
+use namada_sdk::hash::Hash;
+use namada_sdk::key::{common, RefTo, SigScheme};
+use namada_sdk::token;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProposalError {
+    #[error("Invalid proposal JSON: {0}")]
+    InvalidJson(String),
+    #[error("Unknown proposal type: {0}")]
+    UnknownType(String),
+    #[error("Invalid author address: {0}")]
+    InvalidAuthor(String),
+    #[error("Proposal content is missing required field: {0}")]
+    MissingContentField(String),
+    #[error("Proposal content is {0} bytes, maximum is {1}")]
+    ContentTooLarge(u64, u64),
+    #[error("Proposal code is {0} bytes, maximum is {1}")]
+    CodeTooLarge(u64, u64),
+    #[error("Voting start epoch {0} has to be greater than the current epoch {1}")]
+    StartEpochInPast(u64, u64),
+    #[error("Voting start epoch {0} is more than {1} epochs after the current epoch")]
+    StartEpochTooLate(u64, u64),
+    #[error("Voting period has to be at least {0} epochs")]
+    VotingPeriodTooShort(u64),
+    #[error("Activation epoch can be at most {0} epochs after the voting start epoch")]
+    ProposalPeriodTooLong(u64),
+    #[error("Activation epoch has to be at least {0} epochs after the voting end epoch")]
+    GracePeriodTooShort(u64),
+    #[error("Author balance {0} is lower than the minimum proposal fund {1}")]
+    InsufficientFunds(String, String),
+    #[error("PGF steward proposal has to add or remove at least one steward")]
+    EmptyStewardsUpdate,
+    #[error("PGF funding proposal has to contain at least one target")]
+    EmptyFunding,
+    #[error("Invalid vote: {0}, expected yay, nay or abstain")]
+    InvalidVote(String),
+    #[error("Invalid ballot: {0}")]
+    InvalidBallot(String),
+}
+
+/// Content fields every proposal has to define
+const REQUIRED_CONTENT_FIELDS: [&str; 2] = ["title", "authors"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProposalKind {
+    Default,
+    PgfSteward,
+    PgfFunding,
+}
+
+impl FromStr for ProposalKind {
+    type Err = ProposalError;
+
+    // Uses the same names as Query::query_proposal_by_id
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(ProposalKind::Default),
+            "pgf_steward" => Ok(ProposalKind::PgfSteward),
+            "pgf_payment" => Ok(ProposalKind::PgfFunding),
+            _ => Err(ProposalError::UnknownType(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct OnChainProposal {
+    pub content: BTreeMap<String, String>,
+    pub author: String,
+    // Epochs are numbers in the Namada proposal JSON, they are far below 2^53
+    #[tsify(type = "number")]
+    pub voting_start_epoch: u64,
+    #[tsify(type = "number")]
+    pub voting_end_epoch: u64,
+    #[tsify(type = "number")]
+    pub activation_epoch: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Tsify)]
+pub struct StewardsUpdate {
+    pub add: Option<String>,
+    pub remove: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Tsify)]
+pub struct PgfFunding {
+    #[tsify(type = "unknown[]")]
+    pub continuous: Vec<serde_json::Value>,
+    #[tsify(type = "unknown[]")]
+    pub retro: Vec<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct Proposal<T> {
+    pub proposal: OnChainProposal,
+    pub data: T,
+}
+
+#[derive(Debug)]
+pub enum ParsedProposal {
+    // Data of the default proposal is the optional wasm code
+    Default(Proposal<Option<Vec<u8>>>),
+    PgfSteward(Proposal<StewardsUpdate>),
+    PgfFunding(Proposal<PgfFunding>),
+}
+
+impl ParsedProposal {
+    pub fn proposal(&self) -> &OnChainProposal {
+        match self {
+            ParsedProposal::Default(p) => &p.proposal,
+            ParsedProposal::PgfSteward(p) => &p.proposal,
+            ParsedProposal::PgfFunding(p) => &p.proposal,
+        }
+    }
+}
+
+/// Parses proposal JSON of the given kind
+///
+/// # Arguments
+///
+/// * `json` - proposal JSON
+/// * `kind` - proposal kind
+///
+/// # Errors
+///
+/// Returns an error if the JSON does not match the proposal kind
+pub fn parse_proposal(json: &str, kind: ProposalKind) -> Result<ParsedProposal, ProposalError> {
+    let to_err = |e: serde_json::Error| ProposalError::InvalidJson(e.to_string());
+
+    let parsed = match kind {
+        ProposalKind::Default => {
+            ParsedProposal::Default(serde_json::from_str(json).map_err(to_err)?)
+        }
+        ProposalKind::PgfSteward => {
+            ParsedProposal::PgfSteward(serde_json::from_str(json).map_err(to_err)?)
+        }
+        ProposalKind::PgfFunding => {
+            ParsedProposal::PgfFunding(serde_json::from_str(json).map_err(to_err)?)
+        }
+    };
+
+    Address::from_str(&parsed.proposal().author)
+        .map_err(|e| ProposalError::InvalidAuthor(e.to_string()))?;
+
+    Ok(parsed)
+}
+
+/// Checks the proposal against the on-chain rules and returns all violations
+///
+/// # Arguments
+///
+/// * `proposal` - parsed proposal
+/// * `params` - governance parameters
+/// * `current_epoch` - current epoch
+/// * `author_balance` - native token balance of the author
+pub fn validate_proposal(
+    proposal: &ParsedProposal,
+    params: &GovernanceParameters,
+    current_epoch: u64,
+    author_balance: token::Amount,
+) -> Vec<ProposalError> {
+    let mut errors = vec![];
+    let p = proposal.proposal();
+
+    for field in REQUIRED_CONTENT_FIELDS {
+        if p.content.get(field).map_or(true, |v| v.trim().is_empty()) {
+            errors.push(ProposalError::MissingContentField(field.to_string()));
+        }
+    }
+
+    let content_size = serde_json::to_vec(&p.content).map_or(0, |c| c.len() as u64);
+    if content_size > params.max_proposal_content_size {
+        errors.push(ProposalError::ContentTooLarge(
+            content_size,
+            params.max_proposal_content_size,
+        ));
+    }
+
+    if p.voting_start_epoch <= current_epoch {
+        errors.push(ProposalError::StartEpochInPast(
+            p.voting_start_epoch,
+            current_epoch,
+        ));
+    } else if p.voting_start_epoch - current_epoch > params.max_proposal_latency {
+        errors.push(ProposalError::StartEpochTooLate(
+            p.voting_start_epoch,
+            params.max_proposal_latency,
+        ));
+    }
+
+    if p.voting_end_epoch < p.voting_start_epoch
+        || p.voting_end_epoch - p.voting_start_epoch < params.min_proposal_voting_period
+    {
+        errors.push(ProposalError::VotingPeriodTooShort(
+            params.min_proposal_voting_period,
+        ));
+    }
+
+    if p.activation_epoch.saturating_sub(p.voting_start_epoch) > params.max_proposal_period {
+        errors.push(ProposalError::ProposalPeriodTooLong(
+            params.max_proposal_period,
+        ));
+    }
+
+    if p.activation_epoch < p.voting_end_epoch
+        || p.activation_epoch - p.voting_end_epoch < params.min_proposal_grace_epochs
+    {
+        errors.push(ProposalError::GracePeriodTooShort(
+            params.min_proposal_grace_epochs,
+        ));
+    }
+
+    if author_balance < params.min_proposal_fund {
+        errors.push(ProposalError::InsufficientFunds(
+            author_balance.to_string_native(),
+            params.min_proposal_fund.to_string_native(),
+        ));
+    }
+
+    match proposal {
+        ParsedProposal::Default(p) => {
+            let code_size = p.data.as_ref().map_or(0, |code| code.len() as u64);
+            if code_size > params.max_proposal_code_size {
+                errors.push(ProposalError::CodeTooLarge(
+                    code_size,
+                    params.max_proposal_code_size,
+                ));
+            }
+        }
+        ParsedProposal::PgfSteward(p) => {
+            if p.data.add.is_none() && p.data.remove.is_empty() {
+                errors.push(ProposalError::EmptyStewardsUpdate);
+            }
+        }
+        ParsedProposal::PgfFunding(p) => {
+            if p.data.continuous.is_empty() && p.data.retro.is_empty() {
+                errors.push(ProposalError::EmptyFunding);
+            }
+        }
+    }
+
+    errors
+}
+
+/// Builds proposal JSON template of the given type
+///
+/// # Arguments
+///
+/// * `proposal_type` - "default", "pgf_steward" or "pgf_payment"
+/// * `author` - author address
+/// * `content` - `{ [field]: value }` object of proposal content
+/// * `voting_start_epoch` - voting start epoch
+/// * `voting_end_epoch` - voting end epoch
+/// * `activation_epoch` - activation epoch
+/// * `data` - optional proposal data: wasm code bytes for default proposals,
+///   `{ add, remove }` for steward proposals and `{ continuous, retro }` for funding proposals
+///
+/// # Errors
+///
+/// Returns an error if the content or data don't match the proposal type
+#[wasm_bindgen]
+pub fn build_proposal_json(
+    proposal_type: &str,
+    author: String,
+    content: JsValue,
+    voting_start_epoch: u64,
+    voting_end_epoch: u64,
+    activation_epoch: u64,
+    data: JsValue,
+) -> Result<String, JsError> {
+    let kind = ProposalKind::from_str(proposal_type)?;
+    let proposal = OnChainProposal {
+        content: content.into_serde()?,
+        author,
+        voting_start_epoch,
+        voting_end_epoch,
+        activation_epoch,
+    };
+    let has_data = !data.is_undefined() && !data.is_null();
+
+    let json = match kind {
+        ProposalKind::Default => {
+            let data: Option<Vec<u8>> = if has_data {
+                Some(crate::utils::to_bytes(data))
+            } else {
+                None
+            };
+            serde_json::to_string(&Proposal { proposal, data })?
+        }
+        ProposalKind::PgfSteward => {
+            let data: StewardsUpdate = if has_data {
+                data.into_serde()?
+            } else {
+                StewardsUpdate::default()
+            };
+            serde_json::to_string(&Proposal { proposal, data })?
+        }
+        ProposalKind::PgfFunding => {
+            let data: PgfFunding = if has_data {
+                data.into_serde()?
+            } else {
+                PgfFunding::default()
+            };
+            serde_json::to_string(&Proposal { proposal, data })?
+        }
+    };
+
+    // Make sure the template can be parsed back
+    parse_proposal(&json, kind)?;
+
+    Ok(json)
+}
+
+/// Prefix of the signed ballot payload, so the signature can't be reused as anything else
+const BALLOT_DOMAIN: &str = "namada-offline-vote";
+
+/// Governance vote signed off-chain, exchanged as JSON text and verifiable without the chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct OfflineBallot {
+    // Chain the vote is cast on, so the ballot can't be replayed on another chain
+    pub chain_id: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub proposal_id: u64,
+    // One of "yay", "nay", "abstain"
+    pub vote: String,
+    // Implicit address of the signing key
+    pub voter: String,
+    pub public_key: String,
+    // Hex encoded signature of the ballot payload
+    pub signature: String,
+}
+
+impl OfflineBallot {
+    /// Signs the vote with the secret key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chain id is invalid or the vote is not yay, nay or abstain
+    pub fn sign(
+        chain_id: &str,
+        proposal_id: u64,
+        vote: &str,
+        secret_key: &common::SecretKey,
+    ) -> Result<OfflineBallot, ProposalError> {
+        let chain_id = chain_id.trim().to_string();
+        if chain_id.is_empty() {
+            return Err(ProposalError::InvalidBallot(String::from(
+                "chain id is empty",
+            )));
+        }
+        let vote = parse_vote(vote)?;
+        let public_key = secret_key.ref_to();
+        let voter = Address::from(&public_key).to_string();
+        let signature = common::SigScheme::sign(
+            secret_key,
+            ballot_hash(&chain_id, proposal_id, &vote, &voter),
+        );
+
+        Ok(OfflineBallot {
+            chain_id,
+            proposal_id,
+            vote,
+            voter,
+            public_key: public_key.to_string(),
+            signature: hex::encode(signature.to_bytes()),
+        })
+    }
+
+    /// Checks that the ballot is for the chain, the vote is valid, the voter is the address of
+    /// the public key and the signature matches the ballot
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first failed check
+    pub fn verify(&self, chain_id: &str) -> Result<(), ProposalError> {
+        let invalid = |reason: &str| ProposalError::InvalidBallot(reason.to_string());
+
+        if self.chain_id != chain_id.trim() {
+            return Err(invalid("chain id does not match"));
+        }
+        if parse_vote(&self.vote)? != self.vote {
+            return Err(invalid("vote is not in canonical form"));
+        }
+        let public_key =
+            common::PublicKey::from_str(&self.public_key).map_err(|_| invalid("public key"))?;
+        if Address::from(&public_key).to_string() != self.voter {
+            return Err(invalid("voter does not match the public key"));
+        }
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| common::Signature::try_from_slice(&bytes).ok())
+            .ok_or_else(|| invalid("signature encoding"))?;
+
+        common::SigScheme::verify_signature(
+            &public_key,
+            &ballot_hash(&self.chain_id, self.proposal_id, &self.vote, &self.voter),
+            &signature,
+        )
+        .map_err(|_| invalid("signature does not match"))
+    }
+}
+
+fn parse_vote(vote: &str) -> Result<String, ProposalError> {
+    let normalized = vote.trim().to_lowercase();
+    match normalized.as_str() {
+        "yay" | "nay" | "abstain" => Ok(normalized),
+        _ => Err(ProposalError::InvalidVote(vote.to_string())),
+    }
+}
+
+fn ballot_hash(chain_id: &str, proposal_id: u64, vote: &str, voter: &str) -> Hash {
+    Hash::sha256(format!(
+        "{}\nchain_id:{}\nproposal_id:{}\nvote:{}\nvoter:{}",
+        BALLOT_DOMAIN, chain_id, proposal_id, vote, voter
+    ))
+}
+
+/// Verifies a ballot created by `Sdk::sign_offline_vote` and returns it as an object
+///
+/// # Arguments
+///
+/// * `ballot` - JSON of the signed ballot
+/// * `chain_id` - chain the vote has to be cast on
+///
+/// # Errors
+///
+/// Returns an error if the JSON is invalid, the ballot is for another chain or the signature
+/// does not match
+#[wasm_bindgen]
+pub fn verify_offline_vote(ballot: &str, chain_id: &str) -> Result<JsValue, JsError> {
+    let ballot: OfflineBallot =
+        serde_json::from_str(ballot).map_err(|e| ProposalError::InvalidBallot(e.to_string()))?;
+    ballot.verify(chain_id)?;
+
+    Ok(JsValue::from_serde(&ballot)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const AUTHOR: &str = "tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp";
+    const CHAIN_ID: &str = "namada-test.0";
+
+    fn params() -> GovernanceParameters {
+        GovernanceParameters {
+            min_proposal_fund: token::Amount::native_whole(500),
+            max_proposal_code_size: 10,
+            min_proposal_voting_period: 3,
+            max_proposal_period: 27,
+            max_proposal_content_size: 10_000,
+            min_proposal_grace_epochs: 6,
+            max_proposal_latency: 30,
+        }
+    }
+
+    fn proposal_json(start: u64, end: u64, activation: u64) -> String {
+        format!(
+            r#"{{"proposal":{{"content":{{"title":"Title","authors":"me"}},"author":"{}","voting_start_epoch":{},"voting_end_epoch":{},"activation_epoch":{}}},"data":{{"add":"{}","remove":[]}}}}"#,
+            AUTHOR, start, end, activation, AUTHOR
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn can_validate_proposal() {
+        let proposal = parse_proposal(&proposal_json(3, 6, 12), ProposalKind::PgfSteward).unwrap();
+        let errors = validate_proposal(&proposal, &params(), 1, token::Amount::native_whole(500));
+
+        assert!(errors.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_detect_invalid_epochs_and_funds() {
+        let proposal = parse_proposal(&proposal_json(1, 2, 3), ProposalKind::PgfSteward).unwrap();
+        let errors = validate_proposal(&proposal, &params(), 1, token::Amount::native_whole(1));
+
+        assert_eq!(
+            errors,
+            vec![
+                ProposalError::StartEpochInPast(1, 1),
+                ProposalError::VotingPeriodTooShort(3),
+                ProposalError::GracePeriodTooShort(6),
+                ProposalError::InsufficientFunds(
+                    token::Amount::native_whole(1).to_string_native(),
+                    token::Amount::native_whole(500).to_string_native()
+                ),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_reject_unknown_proposal_type() {
+        assert_eq!(
+            ProposalKind::from_str("unknown"),
+            Err(ProposalError::UnknownType("unknown".to_string()))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_sign_and_verify_offline_ballot() {
+        let secret_key = common::SecretKey::Ed25519(
+            namada_sdk::key::ed25519::SecretKey::from_str(
+                "0000000000000000000000000000000000000000000000000000000000000001",
+            )
+            .unwrap(),
+        );
+
+        let ballot = OfflineBallot::sign(CHAIN_ID, 7, " Yay ", &secret_key).unwrap();
+        assert_eq!(ballot.vote, "yay");
+        assert!(ballot.verify(CHAIN_ID).is_ok());
+
+        let tampered = OfflineBallot {
+            vote: String::from("nay"),
+            ..ballot.clone()
+        };
+        assert!(tampered.verify(CHAIN_ID).is_err());
+
+        // Ballots can't be replayed on another chain, even with the chain id rewritten
+        assert!(ballot.verify("other-chain.0").is_err());
+        let replayed = OfflineBallot {
+            chain_id: String::from("other-chain.0"),
+            ..ballot.clone()
+        };
+        assert!(replayed.verify("other-chain.0").is_err());
+        assert_eq!(
+            OfflineBallot::sign(CHAIN_ID, 7, "maybe", &secret_key),
+            Err(ProposalError::InvalidVote(String::from("maybe")))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Node health check - probes an RPC endpoint before switching to it, e.g. a custom RPC entered
+//! by the user, so an unreachable, lagging or non-Namada node is rejected up front.
+use namada_sdk::io::Client;
+use namada_sdk::rpc::query_native_token;
+use namada_sdk::tendermint::node::info::TxIndexStatus;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use crate::rpc_client::HttpClient;
+use crate::utils::to_js_result;
+
+// Probes fail fast, a node that needs longer is not usable for the wallet either
+const DEFAULT_CHECK_TIMEOUT_MS: u32 = 5_000;
+
+#[derive(Clone, Debug, Default, Serialize, Tsify)]
+pub struct NodeHealth {
+    pub url: String,
+    pub reachable: bool,
+    // Round trip of the status request in milliseconds
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub latency_ms: Option<u64>,
+    pub chain_id: Option<String>,
+    pub node_version: Option<String>,
+    // True while the node is still syncing blocks and serves stale state
+    pub catching_up: Option<bool>,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub latest_block_height: Option<u64>,
+    pub latest_block_time: Option<String>,
+    // Earliest block the node keeps, above 1 for pruned nodes or nodes started from a snapshot
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub earliest_block_height: Option<u64>,
+    // Txs can be looked up by hash, required for tx status and history
+    pub tx_index: Option<bool>,
+    // True if the node answers Namada queries, i.e. is not a plain CometBFT node
+    pub namada: bool,
+    pub error: Option<String>,
+}
+
+/// Probes the RPC endpoint. An unreachable node is reported in the result, not as an error.
+///
+/// # Arguments
+///
+/// * `url` - RPC url
+/// * `timeout_ms` - timeout of each request, defaults to 5 seconds
+///
+/// # Errors
+///
+/// Returns an error if the result can't be serialized
+#[wasm_bindgen]
+pub async fn check_node(url: String, timeout_ms: Option<u32>) -> Result<JsValue, JsError> {
+    let client = HttpClient::new(url.clone());
+    client.set_timeout(timeout_ms.unwrap_or(DEFAULT_CHECK_TIMEOUT_MS));
+
+    let started = js_sys::Date::now();
+    let status = match client.status().await {
+        Ok(status) => status,
+        Err(err) => {
+            return to_js_result(NodeHealth {
+                url,
+                error: Some(err.to_string()),
+                ..NodeHealth::default()
+            })
+        }
+    };
+    let latency_ms = (js_sys::Date::now() - started).max(0.0) as u64;
+    let namada = query_native_token(&client).await.is_ok();
+
+    to_js_result(NodeHealth {
+        url,
+        reachable: true,
+        latency_ms: Some(latency_ms),
+        chain_id: Some(status.node_info.network.to_string()),
+        node_version: Some(status.node_info.version.to_string()),
+        catching_up: Some(status.sync_info.catching_up),
+        latest_block_height: Some(status.sync_info.latest_block_height.value()),
+        latest_block_time: Some(status.sync_info.latest_block_time.to_rfc3339()),
+        earliest_block_height: Some(status.sync_info.earliest_block_height.value()),
+        tx_index: Some(status.node_info.other.tx_index == TxIndexStatus::On),
+        namada,
+        error: (!namada).then(|| String::from("Node doesn't answer Namada queries")),
+    })
+}
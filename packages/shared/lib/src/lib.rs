@@ -2,11 +2,27 @@
 //!
 //! A library of functions to integrate shared functionality from the Namada ecosystem
 
+pub mod archival;
+pub mod bridge;
+pub mod event_log;
+pub mod explorer;
+pub mod failure;
+pub mod governance;
+pub mod health;
+pub mod pagination;
+pub mod portfolio;
+// Not public, the price provider trait uses async fn
+mod prices;
 pub mod query;
 pub mod rpc_client;
+pub mod scheduler;
 pub mod sdk;
+pub mod staking;
+pub mod storage;
+pub mod tokens;
 pub mod types;
 mod utils;
+pub mod watcher;
 
 #[cfg(feature = "multicore")]
 pub use wasm_bindgen_rayon::init_thread_pool;
@@ -0,0 +1,139 @@
+//! Paginator - cursor over queries returning large sets of data.
+//! Pages are fetched lazily with `next_page`, so the whole set never has to be kept in memory.
+use std::cell::Cell;
+
+use js_sys::Uint8Array;
+use namada_sdk::borsh;
+use namada_sdk::io::Client;
+use namada_sdk::tendermint::block::Height;
+use namada_sdk::tendermint_rpc::{
+    paging::{PageNumber, PerPage},
+    query::Query as TmQuery,
+    Order, Paging,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::query::query_proposal_info;
+use crate::rpc_client::HttpClient;
+use crate::types::query::Proposals;
+use crate::utils::to_js_result;
+
+pub(crate) enum PageSource {
+    TxSearch { query: TmQuery, order: Order },
+    Validators { height: Height },
+    Proposals { count: u64 },
+}
+
+#[wasm_bindgen]
+pub struct Paginator {
+    client: HttpClient,
+    source: PageSource,
+    per_page: u8,
+    page: Cell<u32>,
+    total: Cell<Option<u64>>,
+}
+
+impl Paginator {
+    /// Creates a paginator before the first page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `per_page` is 0, the paginator would never reach the total
+    pub(crate) fn new(
+        client: HttpClient,
+        source: PageSource,
+        per_page: u8,
+    ) -> Result<Paginator, JsError> {
+        if per_page == 0 {
+            return Err(JsError::new("per_page must be at least 1"));
+        }
+        // Proposals count is known upfront
+        let total = match source {
+            PageSource::Proposals { count } => Some(count),
+            _ => None,
+        };
+
+        Ok(Paginator {
+            client,
+            source,
+            per_page,
+            page: Cell::new(0),
+            total: Cell::new(total),
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl Paginator {
+    /// Fetches the next page. Returns undefined if there are no more pages.
+    ///
+    /// Pages of transactions and validators are returned as JS objects, pages of proposals
+    /// as borsh serialized `Proposals`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn next_page(&self) -> Result<JsValue, JsError> {
+        if !self.has_next_page() {
+            return Ok(JsValue::UNDEFINED);
+        }
+
+        let page = self.page.get() + 1;
+        let per_page = self.per_page;
+
+        let (result, total) = match &self.source {
+            PageSource::TxSearch { query, order } => {
+                let response = self
+                    .client
+                    .tx_search(query.clone(), false, page, per_page, *order)
+                    .await?;
+
+                (to_js_result(response.txs)?, u64::from(response.total_count))
+            }
+            PageSource::Validators { height } => {
+                let paging = Paging::Specific {
+                    page_number: PageNumber::from(page as usize),
+                    per_page: PerPage::from(per_page),
+                };
+                let response = self.client.validators(*height, paging).await?;
+
+                (to_js_result(response.validators)?, response.total as u64)
+            }
+            PageSource::Proposals { count } => {
+                let start = u64::from(page - 1) * u64::from(per_page);
+                let end = (start + u64::from(per_page)).min(*count);
+
+                let mut proposals = vec![];
+                for id in start..end {
+                    proposals.push(query_proposal_info(&self.client, id).await?);
+                }
+                let bytes = borsh::to_vec(&Proposals::new(proposals))?;
+
+                (Uint8Array::from(bytes.as_slice()).into(), *count)
+            }
+        };
+
+        self.page.set(page);
+        self.total.set(Some(total));
+
+        Ok(result)
+    }
+
+    /// Returns true if there are more pages to fetch
+    pub fn has_next_page(&self) -> bool {
+        match self.total.get() {
+            Some(total) => u64::from(self.page.get()) * u64::from(self.per_page) < total,
+            None => true,
+        }
+    }
+
+    /// Number of the last fetched page, 0 if no page was fetched yet
+    pub fn page(&self) -> u32 {
+        self.page.get()
+    }
+
+    /// Total number of items, known after the first page is fetched
+    pub fn total(&self) -> Option<u64> {
+        self.total.get()
+    }
+}
@@ -0,0 +1,122 @@
+//! Portfolio - balances of several accounts and viewing keys summed per token, split into
+//! transparent, shielded, bonded and unbonding funds. Kept free of RPC calls, the query only
+//! feeds the balances in.
+use std::collections::BTreeMap;
+
+use namada_sdk::address::Address;
+use namada_sdk::token::Amount;
+
+use crate::tokens;
+use crate::types::amount::BaseAmount;
+use crate::types::query::PortfolioEntry;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Holdings {
+    pub transparent: Amount,
+    pub shielded: Amount,
+    pub bonded: Amount,
+    pub unbonding: Amount,
+}
+
+impl Holdings {
+    pub fn total(&self) -> Amount {
+        sum(
+            sum(self.transparent, self.shielded),
+            sum(self.bonded, self.unbonding),
+        )
+    }
+}
+
+// Token supplies fit into an Amount, so sums of balances can't overflow
+fn sum(a: Amount, b: Amount) -> Amount {
+    a.checked_add(b)
+        .expect("Sum of balances should not overflow")
+}
+
+#[derive(Debug, Default)]
+pub struct Portfolio {
+    tokens: BTreeMap<Address, Holdings>,
+}
+
+impl Portfolio {
+    fn holdings(&mut self, token: Address) -> &mut Holdings {
+        self.tokens.entry(token).or_default()
+    }
+
+    pub fn add_transparent(&mut self, token: Address, amount: Amount) {
+        let holdings = self.holdings(token);
+        holdings.transparent = sum(holdings.transparent, amount);
+    }
+
+    pub fn add_shielded(&mut self, token: Address, amount: Amount) {
+        let holdings = self.holdings(token);
+        holdings.shielded = sum(holdings.shielded, amount);
+    }
+
+    pub fn add_bonded(&mut self, token: Address, amount: Amount) {
+        let holdings = self.holdings(token);
+        holdings.bonded = sum(holdings.bonded, amount);
+    }
+
+    pub fn add_unbonding(&mut self, token: Address, amount: Amount) {
+        let holdings = self.holdings(token);
+        holdings.unbonding = sum(holdings.unbonding, amount);
+    }
+
+    pub fn get(&self, token: &Address) -> Option<Holdings> {
+        self.tokens.get(token).copied()
+    }
+
+    /// Returns tokens with non-zero holdings, amounts in base units. Tokens outside the token
+    /// allowlist are kept and marked with their status.
+    pub fn entries(&self) -> Vec<PortfolioEntry> {
+        self.tokens
+            .iter()
+            .filter(|(_, holdings)| !holdings.total().is_zero())
+            .map(|(token, holdings)| PortfolioEntry {
+                token: token.to_string(),
+                status: tokens::token_status(token).to_string(),
+                transparent: BaseAmount::from(holdings.transparent),
+                shielded: BaseAmount::from(holdings.shielded),
+                bonded: BaseAmount::from(holdings.bonded),
+                unbonding: BaseAmount::from(holdings.unbonding),
+                total: BaseAmount::from(holdings.total()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wasm_bindgen_test::*;
+
+    fn nam() -> Address {
+        Address::from_str("tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7").unwrap()
+    }
+
+    fn btc() -> Address {
+        Address::from_str("tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e").unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn sums_holdings_per_token() {
+        let mut portfolio = Portfolio::default();
+        portfolio.add_transparent(nam(), Amount::from_u64(10));
+        portfolio.add_transparent(nam(), Amount::from_u64(5));
+        portfolio.add_shielded(nam(), Amount::from_u64(3));
+        portfolio.add_bonded(nam(), Amount::from_u64(100));
+        portfolio.add_unbonding(nam(), Amount::from_u64(7));
+        portfolio.add_transparent(btc(), Amount::zero());
+
+        let holdings = portfolio.get(&nam()).unwrap();
+        assert_eq!(holdings.transparent, Amount::from_u64(15));
+        assert_eq!(holdings.total(), Amount::from_u64(125));
+
+        let entries = portfolio.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].total.to_string(), "125");
+        assert_eq!(entries[0].status, "known");
+    }
+}
@@ -0,0 +1,482 @@
+//! Fiat prices - values of token amounts in fiat currencies. Prices come from a price provider,
+//! by default an HTTP endpoint, and are cached, so balance views showing fiat equivalents don't
+//! call external APIs for every component and stay within the rate limits of the endpoint.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::str::FromStr;
+
+use namada_sdk::dec::Dec;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::rpc_client::{with_timeout, DEFAULT_TIMEOUT_MS};
+use crate::utils::sleep;
+
+const TOKEN_PLACEHOLDER: &str = "{token}";
+const CURRENCY_PLACEHOLDER: &str = "{currency}";
+const TIMESTAMP_PLACEHOLDER: &str = "{timestamp}";
+// Historical prices are looked up once per day
+const DAY_SECS: u64 = 86_400;
+// How long prices are cached by default
+const DEFAULT_TTL_SECS: u64 = 60;
+// Requests made to the provider per minute by default
+const DEFAULT_MAX_REQUESTS: u32 = 30;
+const RATE_WINDOW_SECS: u64 = 60;
+// Delay after the first failure of a price, doubled on every further failure
+const BACKOFF_SECS: u64 = 5;
+const MAX_BACKOFF_SECS: u64 = 300;
+// How often calls waiting for a request of the same price check if it is done
+const IN_FLIGHT_POLL_MS: u32 = 50;
+
+/// Source of token prices in fiat currencies
+pub trait PriceProvider {
+    /// Returns the price of one whole token in the currency
+    async fn price(&self, token: &str, currency: &str) -> Result<Dec, JsError>;
+
+    /// Returns the price at the time in seconds, providers without historical prices return
+    /// the current one
+    async fn price_at(&self, token: &str, currency: &str, _timestamp: u64) -> Result<Dec, JsError> {
+        self.price(token, currency).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    price: String,
+}
+
+/// Fetches `{ "price": "1.23" }` from an endpoint, the URL template has `{token}` and
+/// `{currency}` placeholders, e.g. "https://prices.example/v1/{token}?currency={currency}", and
+/// optionally a `{timestamp}` placeholder for historical prices
+pub struct HttpPriceProvider {
+    url_template: String,
+}
+
+impl HttpPriceProvider {
+    pub fn new(url_template: String) -> Result<HttpPriceProvider, JsError> {
+        if !url_template.contains(TOKEN_PLACEHOLDER) {
+            return Err(JsError::new(&format!(
+                "Price URL template has no {}",
+                TOKEN_PLACEHOLDER
+            )));
+        }
+
+        Ok(HttpPriceProvider { url_template })
+    }
+
+    pub fn has_history(&self) -> bool {
+        self.url_template.contains(TIMESTAMP_PLACEHOLDER)
+    }
+
+    async fn fetch(&self, token: &str, currency: &str, timestamp: u64) -> Result<Dec, JsError> {
+        let url = self
+            .url_template
+            .replace(TOKEN_PLACEHOLDER, token)
+            .replace(CURRENCY_PLACEHOLDER, currency)
+            .replace(TIMESTAMP_PLACEHOLDER, &timestamp.to_string());
+        let fetch = async { reqwest::get(url).await?.text().await };
+        let body = with_timeout(fetch, DEFAULT_TIMEOUT_MS).await??;
+        let response: PriceResponse = serde_json::from_str(&body)?;
+
+        Ok(Dec::from_str(&response.price)?)
+    }
+}
+
+impl PriceProvider for HttpPriceProvider {
+    async fn price(&self, token: &str, currency: &str) -> Result<Dec, JsError> {
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        self.fetch(token, currency, now).await
+    }
+
+    async fn price_at(&self, token: &str, currency: &str, timestamp: u64) -> Result<Dec, JsError> {
+        self.fetch(token, currency, timestamp).await
+    }
+}
+
+#[derive(Debug)]
+pub struct PriceCache {
+    // Price and the time in seconds it was fetched at, keyed by (token, currency)
+    prices: BTreeMap<(String, String), (Dec, u64)>,
+    ttl_secs: u64,
+}
+
+impl PriceCache {
+    pub fn new(ttl_secs: u64) -> PriceCache {
+        PriceCache {
+            prices: BTreeMap::new(),
+            ttl_secs,
+        }
+    }
+
+    /// Returns the cached price if it is not older than the ttl
+    pub fn fresh(&self, token: &str, currency: &str, now: u64) -> Option<Dec> {
+        self.prices
+            .get(&(token.to_string(), currency.to_string()))
+            .filter(|(_, fetched_at)| now < fetched_at + self.ttl_secs)
+            .map(|(price, _)| *price)
+    }
+
+    /// Returns the cached price regardless of its age
+    pub fn any(&self, token: &str, currency: &str) -> Option<Dec> {
+        self.prices
+            .get(&(token.to_string(), currency.to_string()))
+            .map(|(price, _)| *price)
+    }
+
+    pub fn insert(&mut self, token: &str, currency: &str, price: Dec, now: u64) {
+        self.prices
+            .insert((token.to_string(), currency.to_string()), (price, now));
+    }
+}
+
+// Token, currency and, for historical prices, the day
+type PriceKey = (String, String, Option<u64>);
+
+/// Limits the requests made to the price provider. At most `max_requests` are made per minute,
+/// only one request of a price is in flight at a time and prices which failed are not requested
+/// again until their backoff ends.
+#[derive(Debug)]
+pub struct RequestLimiter {
+    max_requests: usize,
+    // Times in seconds of the requests of the last minute
+    requests: VecDeque<u64>,
+    in_flight: BTreeSet<PriceKey>,
+    // Failures in a row and the time in seconds the price can be requested again
+    failures: BTreeMap<PriceKey, (u32, u64)>,
+}
+
+impl RequestLimiter {
+    pub fn new(max_requests: u32) -> RequestLimiter {
+        RequestLimiter {
+            max_requests: max_requests as usize,
+            requests: VecDeque::new(),
+            in_flight: BTreeSet::new(),
+            failures: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_in_flight(&self, key: &PriceKey) -> bool {
+        self.in_flight.contains(key)
+    }
+
+    /// Records a request of the price
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the price is backing off after a failure or the requests of the last
+    /// minute reached the limit
+    pub fn start(&mut self, key: &PriceKey, now: u64) -> Result<(), JsError> {
+        if let Some((_, retry_at)) = self.failures.get(key).filter(|(_, at)| now < *at) {
+            return Err(JsError::new(&format!(
+                "Price of {} failed, retrying in {}s",
+                key.0,
+                retry_at - now
+            )));
+        }
+        while self
+            .requests
+            .front()
+            .is_some_and(|requested_at| requested_at + RATE_WINDOW_SECS <= now)
+        {
+            self.requests.pop_front();
+        }
+        if self.requests.len() >= self.max_requests {
+            return Err(JsError::new("Too many price requests, retry later"));
+        }
+
+        self.requests.push_back(now);
+        self.in_flight.insert(key.clone());
+        Ok(())
+    }
+
+    /// Ends the request of the price, backing it off if it failed
+    pub fn finish(&mut self, key: &PriceKey, succeeded: bool, now: u64) {
+        self.in_flight.remove(key);
+        if succeeded {
+            self.failures.remove(key);
+            return;
+        }
+
+        let failures = self.failures.get(key).map_or(0, |(failures, _)| *failures) + 1;
+        let backoff_secs = BACKOFF_SECS
+            .saturating_mul(2u64.saturating_pow(failures - 1))
+            .min(MAX_BACKOFF_SECS);
+        self.failures
+            .insert(key.clone(), (failures, now + backoff_secs));
+    }
+}
+
+// Waits until no request of the price is in flight, so concurrent calls share one request
+async fn wait_in_flight(limiter: &RefCell<RequestLimiter>, key: &PriceKey) {
+    while limiter.borrow().is_in_flight(key) {
+        if sleep(IN_FLIGHT_POLL_MS).await.is_err() {
+            break;
+        }
+    }
+}
+
+// Requests the price through the limiter
+async fn limited<F: std::future::Future<Output = Result<Dec, JsError>>>(
+    limiter: &RefCell<RequestLimiter>,
+    key: &PriceKey,
+    now: u64,
+    request: F,
+) -> Result<Dec, JsError> {
+    limiter.borrow_mut().start(key, now)?;
+    let result = request.await;
+    limiter.borrow_mut().finish(key, result.is_ok(), now);
+
+    result
+}
+
+/// Returns the price from the cache, fetching it at most once per ttl and within the limits of
+/// the limiter. If fetching fails or is not allowed, the last known price is returned.
+pub async fn cached_price<P: PriceProvider>(
+    provider: &P,
+    cache: &RefCell<PriceCache>,
+    limiter: &RefCell<RequestLimiter>,
+    token: &str,
+    currency: &str,
+    now: u64,
+) -> Result<Dec, JsError> {
+    let key = (token.to_string(), currency.to_string(), None);
+    wait_in_flight(limiter, &key).await;
+    if let Some(price) = cache.borrow().fresh(token, currency, now) {
+        return Ok(price);
+    }
+
+    match limited(limiter, &key, now, provider.price(token, currency)).await {
+        Ok(price) => {
+            cache.borrow_mut().insert(token, currency, price, now);
+            Ok(price)
+        }
+        Err(err) => cache.borrow().any(token, currency).ok_or(err),
+    }
+}
+
+/// Values token amounts in fiat currencies
+#[wasm_bindgen]
+pub struct PriceOracle {
+    provider: HttpPriceProvider,
+    cache: RefCell<PriceCache>,
+    limiter: RefCell<RequestLimiter>,
+    // Historical prices don't change, so they are kept by (token, currency, day)
+    history: RefCell<BTreeMap<(String, String, u64), Dec>>,
+}
+
+#[wasm_bindgen]
+impl PriceOracle {
+    /// Creates a price oracle fetching prices from an HTTP endpoint
+    ///
+    /// # Arguments
+    ///
+    /// * `url_template` - endpoint URL with `{token}` and `{currency}` placeholders, returning
+    ///   `{ "price": "1.23" }`
+    /// * `ttl_secs` - optional time prices are cached for, 60 seconds by default
+    /// * `max_requests_per_minute` - optional limit of requests to the endpoint, 30 by default
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL template has no `{token}` placeholder
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        url_template: String,
+        ttl_secs: Option<u32>,
+        max_requests_per_minute: Option<u32>,
+    ) -> Result<PriceOracle, JsError> {
+        Ok(PriceOracle {
+            provider: HttpPriceProvider::new(url_template)?,
+            cache: RefCell::new(PriceCache::new(
+                ttl_secs.map_or(DEFAULT_TTL_SECS, u64::from),
+            )),
+            limiter: RefCell::new(RequestLimiter::new(
+                max_requests_per_minute.unwrap_or(DEFAULT_MAX_REQUESTS),
+            )),
+            history: RefCell::new(BTreeMap::new()),
+        })
+    }
+
+    /// Returns the value of the amount in the currency as a decimal string. Requests of the price
+    /// are shared by concurrent calls, rate limited, and backed off after failures, in which
+    /// cases the last known price is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - address of the token
+    /// * `amount` - decimal amount of the token
+    /// * `currency` - fiat currency, e.g. "usd"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the amount is invalid or no price is known for the token
+    pub async fn get_fiat_value(
+        &self,
+        token: String,
+        amount: String,
+        currency: String,
+    ) -> Result<String, JsError> {
+        let amount = Dec::from_str(&amount)?;
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        let price = cached_price(
+            &self.provider,
+            &self.cache,
+            &self.limiter,
+            &token,
+            &currency,
+            now,
+        )
+        .await?;
+
+        Ok(fiat_value(amount, price)?.to_string())
+    }
+}
+
+impl PriceOracle {
+    /// Returns the value of the amount at the time in seconds. Without a `{timestamp}`
+    /// placeholder in the URL template the current price is used.
+    pub async fn value_at(
+        &self,
+        token: &str,
+        amount: Dec,
+        currency: &str,
+        timestamp: u64,
+    ) -> Result<Dec, JsError> {
+        if !self.provider.has_history() {
+            let now = (js_sys::Date::now() / 1000.0) as u64;
+            let price = cached_price(
+                &self.provider,
+                &self.cache,
+                &self.limiter,
+                token,
+                currency,
+                now,
+            )
+            .await?;
+            return fiat_value(amount, price);
+        }
+
+        let day = timestamp - timestamp % DAY_SECS;
+        let request_key = (token.to_string(), currency.to_string(), Some(day));
+        wait_in_flight(&self.limiter, &request_key).await;
+        let key = (token.to_string(), currency.to_string(), day);
+        let cached = self.history.borrow().get(&key).copied();
+        let price = match cached {
+            Some(price) => price,
+            None => {
+                let now = (js_sys::Date::now() / 1000.0) as u64;
+                let request = self.provider.price_at(token, currency, day);
+                let price = limited(&self.limiter, &request_key, now, request).await?;
+                self.history.borrow_mut().insert(key, price);
+                price
+            }
+        };
+
+        fiat_value(amount, price)
+    }
+}
+
+fn fiat_value(amount: Dec, price: Dec) -> Result<Dec, JsError> {
+    amount
+        .checked_mul(price)
+        .ok_or_else(|| JsError::new("Overflow when computing fiat value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    struct CountingProvider {
+        calls: Cell<u32>,
+        fail: bool,
+    }
+
+    impl PriceProvider for CountingProvider {
+        async fn price(&self, _token: &str, _currency: &str) -> Result<Dec, JsError> {
+            self.calls.set(self.calls.get() + 1);
+            if self.fail {
+                return Err(JsError::new("Unavailable"));
+            }
+            Ok(Dec::from(2))
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn fetches_once_per_ttl() {
+        let provider = CountingProvider {
+            calls: Cell::new(0),
+            fail: false,
+        };
+        let cache = RefCell::new(PriceCache::new(60));
+        let limiter = RefCell::new(RequestLimiter::new(30));
+
+        cached_price(&provider, &cache, &limiter, "nam", "usd", 0)
+            .await
+            .unwrap();
+        cached_price(&provider, &cache, &limiter, "nam", "usd", 59)
+            .await
+            .unwrap();
+        assert_eq!(provider.calls.get(), 1);
+
+        cached_price(&provider, &cache, &limiter, "nam", "usd", 60)
+            .await
+            .unwrap();
+        assert_eq!(provider.calls.get(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    async fn falls_back_to_stale_price() {
+        let provider = CountingProvider {
+            calls: Cell::new(0),
+            fail: true,
+        };
+        let cache = RefCell::new(PriceCache::new(60));
+        let limiter = RefCell::new(RequestLimiter::new(30));
+        cache.borrow_mut().insert("nam", "usd", Dec::from(3), 0);
+
+        let price = cached_price(&provider, &cache, &limiter, "nam", "usd", 120).await;
+        assert_eq!(price.ok(), Some(Dec::from(3)));
+        assert!(cached_price(&provider, &cache, &limiter, "btc", "usd", 120)
+            .await
+            .is_err());
+        assert_eq!(provider.calls.get(), 2);
+
+        // Failed prices are not requested again until the backoff ends
+        assert!(cached_price(&provider, &cache, &limiter, "btc", "usd", 121)
+            .await
+            .is_err());
+        assert_eq!(provider.calls.get(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn limits_requests() {
+        let key = |token: &str| (token.to_string(), String::from("usd"), None);
+        let mut limiter = RequestLimiter::new(2);
+
+        limiter.start(&key("nam"), 0).unwrap();
+        assert!(limiter.is_in_flight(&key("nam")));
+        limiter.finish(&key("nam"), true, 0);
+        assert!(!limiter.is_in_flight(&key("nam")));
+        limiter.start(&key("btc"), 10).unwrap();
+        limiter.finish(&key("btc"), true, 10);
+        assert!(limiter.start(&key("eth"), 59).is_err());
+        limiter.start(&key("eth"), 60).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn backs_off_failed_prices() {
+        let key = (String::from("nam"), String::from("usd"), None);
+        let mut limiter = RequestLimiter::new(100);
+
+        limiter.start(&key, 0).unwrap();
+        limiter.finish(&key, false, 0);
+        assert!(limiter.start(&key, BACKOFF_SECS - 1).is_err());
+        limiter.start(&key, BACKOFF_SECS).unwrap();
+        limiter.finish(&key, false, BACKOFF_SECS);
+        assert!(limiter.start(&key, 3 * BACKOFF_SECS - 1).is_err());
+        limiter.start(&key, 3 * BACKOFF_SECS).unwrap();
+        limiter.finish(&key, true, 3 * BACKOFF_SECS);
+        limiter.start(&key, 3 * BACKOFF_SECS).unwrap();
+    }
+}
@@ -1,8 +1,9 @@
 use gloo_utils::format::JsValueSerdeExt;
 use js_sys::Uint8Array;
-use namada_sdk::address::Address;
-use namada_sdk::borsh::BorshSerialize;
+use namada_sdk::address::{Address, ETH_BRIDGE};
+use namada_sdk::borsh::{self, BorshSerialize};
 use namada_sdk::collections::{HashMap, HashSet};
+use namada_sdk::dec::Dec;
 use namada_sdk::eth_bridge::bridge_pool::query_signed_bridge_pool;
 use namada_sdk::eth_bridge_pool::TransferToEthereum;
 use namada_sdk::governance::storage::keys as governance_storage;
@@ -11,47 +12,78 @@ use namada_sdk::governance::utils::{
 };
 use namada_sdk::governance::{ProposalType, ProposalVote};
 use namada_sdk::hash::Hash;
+use namada_sdk::io::Client;
 use namada_sdk::masp::shielded_wallet::ShieldedApi;
-use namada_sdk::masp::utils::MaspClient as NamadaMaspClient;
 use namada_sdk::masp::utils::RetryStrategy;
-use namada_sdk::masp::{IndexerMaspClient, LedgerMaspClient};
 use namada_sdk::masp::{ShieldedContext, ShieldedSyncConfig};
 use namada_sdk::masp_primitives::asset_type::AssetType;
 use namada_sdk::masp_primitives::sapling::ViewingKey;
 use namada_sdk::masp_primitives::transaction::components::ValueSum;
-use namada_sdk::masp_primitives::zip32::ExtendedFullViewingKey;
-use namada_sdk::parameters::storage;
+use namada_sdk::masp_primitives::zip32::{DiversifierIndex, ExtendedFullViewingKey};
+use namada_sdk::parameters::{storage, EpochDuration};
 use namada_sdk::proof_of_stake::Epoch;
 use namada_sdk::queries::RPC;
 use namada_sdk::rpc::{
-    self, get_public_key_at, get_token_balance, get_total_staked_tokens, is_steward, query_epoch,
-    query_masp_epoch, query_native_token, query_proposal_by_id, query_proposal_votes,
-    query_storage_value,
+    self, get_public_key_at, get_staking_rewards_rate, get_token_balance, get_total_staked_tokens,
+    get_validator_stake, get_validator_state, is_steward, is_validator, query_commission_rate,
+    query_denom, query_epoch, query_governance_parameters, query_masp_epoch, query_native_token,
+    query_pos_parameters, query_proposal_by_id, query_proposal_votes, query_storage_value,
 };
 use namada_sdk::state::BlockHeight;
 use namada_sdk::state::Key;
+use namada_sdk::storage::KeySeg;
+use namada_sdk::tendermint::abci;
+use namada_sdk::tendermint::block::Height;
+use namada_sdk::tendermint_rpc::{query::Query as TmQuery, Order, Paging};
 use namada_sdk::token;
 use namada_sdk::tx::{
-    TX_BOND_WASM, TX_CLAIM_REWARDS_WASM, TX_IBC_WASM, TX_REDELEGATE_WASM, TX_REVEAL_PK,
-    TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
+    Tx, TX_BOND_WASM, TX_BRIDGE_POOL_WASM, TX_CLAIM_REWARDS_WASM, TX_IBC_WASM, TX_REDELEGATE_WASM,
+    TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
 };
 use namada_sdk::uint::I256;
 use namada_sdk::wallet::DatedKeypair;
 use namada_sdk::ExtendedSpendingKey;
 use namada_sdk::ExtendedViewingKey;
+use namada_sdk::PaymentAddress;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::str::FromStr;
-use std::time::Duration;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsError;
 
-use crate::rpc_client::HttpClient;
+use crate::archival;
+use crate::bridge;
+use crate::event_log::{decode_event, EventFilter, NamadaEvent};
+use crate::failure;
+use crate::governance::{parse_proposal, validate_proposal, ProposalKind};
+use crate::pagination::{PageSource, Paginator};
+use crate::portfolio::Portfolio;
+use crate::rpc_client::{headers_from_js, HttpClient};
 use crate::sdk::{
+    flush,
     io::WebIo,
+    masp::block_source::{BlockSource, IndexerBlockSource, RpcBlockSource},
+    masp::checkpoint::TreeCheckpoint,
+    masp::compact::{self, CompactBlock},
+    masp::integrity,
+    masp::memory::{self, MemoryLimits},
+    masp::resume::{SyncCursor, WorkStore, CHUNK_BLOCKS},
+    masp::snapshot::{Snapshot, Snapshots},
     masp::{sync, JSShieldedUtils},
+    payment_request::PaymentRequest,
+};
+use crate::staking;
+use crate::tokens;
+use crate::types::amount::{BaseAmount, StringAmount};
+use crate::types::query::{
+    BondPreflight, KeySyncStatus, ProofOp, ProposalInfo, ProtocolParameters, ScannedPaymentAddress,
+    StakingReturn, TokenUnlock, VestingSchedule, VotingPower, VotingPowerStatement, WasmHash,
 };
-use crate::types::query::{ProposalInfo, WasmHash};
 use crate::utils::{set_panic_hook, to_js_result};
+use crate::watcher::{
+    start_balance_watcher, start_incoming_watcher, start_validator_set_watcher,
+    start_validator_watcher, WatcherHandle,
+};
 
 /// Progress bar names
 pub const SDK_SCANNED_PROGRESS_BAR: &str = "namada_sdk::progress_bar::scanned";
@@ -82,44 +114,147 @@ impl ProgressBarNames {
     }
 }
 
-enum MaspClient {
-    Ledger(LedgerMaspClient<HttpClient>),
-    Indexer(IndexerMaspClient),
-}
-
 #[wasm_bindgen]
 /// Represents an API for querying the ledger
 pub struct Query {
     client: HttpClient,
-    masp_client: MaspClient,
+    rpc_source: RpcBlockSource,
+    // Preferred for shielded sync when set, falls back to the RPC
+    indexer_source: Option<IndexerBlockSource>,
+    // Protocol parameters can only change at epoch boundaries, so we cache them per epoch
+    protocol_parameters: RefCell<Option<ProtocolParameters>>,
+    // Loaded commitment tree checkpoint, shielded sync starts after its height
+    sync_checkpoint: RefCell<Option<TreeCheckpoint>>,
+    // Height each viewing key was last synced to, None until loaded from the storage of the
+    // shielded context
+    key_sync_heights: RefCell<Option<HashMap<ViewingKey, u64>>>,
+    memory_limits: RefCell<MemoryLimits>,
+    // Set when shielded sync has to survive restarts of the worker
+    work_store: RefCell<Option<WorkStore>>,
+    // Sync state `flush` still has to save
+    dirty: RefCell<flush::DirtySyncState>,
+    // Serves heights the RPC node pruned, see `set_archival_rpc`
+    archival_client: RefCell<Option<HttpClient>>,
+    earliest_height: RefCell<archival::EarliestHeight>,
 }
 
 #[wasm_bindgen]
 impl Query {
+    /// Creates a new Query instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - RPC url
+    /// * `masp_url` - optional MASP indexer url, ledger is used for syncing if not provided or
+    ///   the indexer is unavailable
+    /// * `headers` - optional `{ [name]: value }` object of headers sent with every request
+    /// * `auth_token` - optional bearer token sent with every request
+    /// * `transport` - optional `fetch`-like function RPC requests are routed through
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the headers can't be deserialized
     #[wasm_bindgen(constructor)]
-    pub fn new(url: String, masp_url: Option<String>) -> Query {
+    pub fn new(
+        url: String,
+        masp_url: Option<String>,
+        headers: Option<JsValue>,
+        auth_token: Option<String>,
+        transport: Option<js_sys::Function>,
+    ) -> Result<Query, JsError> {
         set_panic_hook();
-        let client = HttpClient::new(url);
+        let headers = headers
+            .map(headers_from_js)
+            .transpose()?
+            .unwrap_or_default();
+        let mut client = HttpClient::new_with_headers(url, headers, auth_token);
+        if let Some(transport) = transport {
+            client = client.with_transport(transport);
+        }
 
-        let masp_client = if let Some(url) = masp_url {
-            let client = reqwest::Client::builder().build().unwrap();
+        let indexer_source = if let Some(url) = masp_url {
+            let mut default_headers = reqwest::header::HeaderMap::new();
+            for (name, value) in client.headers() {
+                default_headers.insert(
+                    reqwest::header::HeaderName::from_str(name)?,
+                    reqwest::header::HeaderValue::from_str(value)?,
+                );
+            }
+            let client = reqwest::Client::builder()
+                .default_headers(default_headers)
+                .build()
+                .unwrap();
             // TODO: for now we just concatenate the v1 api path
             let url = reqwest::Url::parse(&format!("{}/api/v1", url)).unwrap();
 
-            MaspClient::Indexer(IndexerMaspClient::new(client, url, true, 10))
+            Some(IndexerBlockSource::new(client, url))
         } else {
-            MaspClient::Ledger(LedgerMaspClient::new(
-                client.clone(),
-                // Using one does not break the progress indicators
-                1,
-                Duration::from_millis(5),
-            ))
+            None
         };
 
-        Query {
+        Ok(Query {
+            rpc_source: RpcBlockSource::new(client.clone()),
             client,
-            masp_client,
-        }
+            indexer_source,
+            protocol_parameters: RefCell::new(None),
+            sync_checkpoint: RefCell::new(None),
+            key_sync_heights: RefCell::new(None),
+            memory_limits: RefCell::new(MemoryLimits::default()),
+            work_store: RefCell::new(None),
+            dirty: RefCell::new(flush::DirtySyncState::default()),
+            archival_client: RefCell::new(None),
+            earliest_height: RefCell::new(archival::EarliestHeight::default()),
+        })
+    }
+
+    /// Returns a copy of Query with `headers` merged over the default ones,
+    /// allowing headers to be overridden for a single request.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - `{ [name]: value }` object of headers
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the headers can't be deserialized
+    pub fn with_headers(&self, headers: JsValue) -> Result<Query, JsError> {
+        let headers = headers_from_js(headers)?;
+
+        Ok(Query {
+            client: self.client.with_headers(headers),
+            rpc_source: self.rpc_source.clone(),
+            indexer_source: self.indexer_source.clone(),
+            protocol_parameters: RefCell::new(self.protocol_parameters.borrow().clone()),
+            sync_checkpoint: RefCell::new(self.sync_checkpoint.borrow().clone()),
+            key_sync_heights: RefCell::new(self.key_sync_heights.borrow().clone()),
+            memory_limits: RefCell::new(self.memory_limits.borrow().clone()),
+            work_store: RefCell::new(self.work_store.borrow().clone()),
+            dirty: RefCell::new(flush::DirtySyncState::default()),
+            archival_client: RefCell::new(self.archival_client.borrow().clone()),
+            earliest_height: RefCell::new(self.earliest_height.borrow().clone()),
+        })
+    }
+
+    /// Sets the time after which RPC requests are aborted and fail with a timeout error, for this
+    /// Query, the copies returned by `with_headers` and the archival RPC
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_ms` - timeout in milliseconds, 0 disables it. Defaults to 30 seconds.
+    pub fn set_rpc_timeout(&self, timeout_ms: u32) {
+        self.client.set_timeout(timeout_ms);
+    }
+
+    /// Sets the RPC of an archival node, which serves the block, validator and dry-run queries of
+    /// heights the RPC node pruned. Without it such queries fail with a pruned height error.
+    /// Headers of the RPC are not sent to it, as it's usually another provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - archival RPC url, undefined removes it
+    pub fn set_archival_rpc(&self, url: Option<String>) {
+        *self.archival_client.borrow_mut() =
+            url.map(|url| HttpClient::new(url).with_timeout_handle(self.client.timeout_handle()));
     }
 
     /// Gets current epoch
@@ -203,7 +338,8 @@ impl Query {
             validators_per_address.insert(address, validators);
         }
 
-        let mut result: Vec<(Address, Address, String, String, String)> = Vec::new();
+        let mut result: Vec<(Address, Address, StringAmount, StringAmount, StringAmount)> =
+            Vec::new();
 
         let epoch = query_epoch(&self.client).await?;
         for (owner, validators) in validators_per_address.into_iter() {
@@ -220,9 +356,9 @@ impl Query {
                 result.push((
                     owner.clone(),
                     validator,
-                    enriched.bonds_total.to_string_native(),
-                    enriched.unbonds_total.to_string_native(),
-                    enriched.total_withdrawable.to_string_native(),
+                    StringAmount::native(enriched.bonds_total),
+                    StringAmount::native(enriched.unbonds_total),
+                    StringAmount::native(enriched.total_withdrawable),
                 ));
             }
         }
@@ -285,7 +421,7 @@ impl Query {
                         bonds.push((
                             bond_id.source.clone(),
                             bond_id.validator.clone(),
-                            bond.amount.to_string_native(),
+                            StringAmount::native(bond.amount),
                             bond.start.to_string(),
                         ));
                     }
@@ -294,7 +430,7 @@ impl Query {
                         unbonds.push((
                             bond_id.source.clone(),
                             bond_id.validator.clone(),
-                            unbond.amount.to_string_native(),
+                            StringAmount::native(unbond.amount),
                             unbond.start.to_string(),
                             unbond.withdraw.to_string(),
                         ));
@@ -325,16 +461,37 @@ impl Query {
         Ok(result)
     }
 
+    // Returns the decimal places of the token
+    async fn token_denom(&self, token: &Address) -> Result<u8, JsError> {
+        query_denom(&self.client, token)
+            .await
+            .map(|denom| denom.0)
+            .ok_or_else(|| JsError::new("Unknown token denomination"))
+    }
+
+    /// Syncs the shielded context with the chain
+    ///
+    /// # Arguments
+    ///
+    /// * `vks` - Extended viewing keys
+    /// * `sks` - Extended spending keys
+    /// * `birthdays` - optional heights the keys were created at, `vks` followed by `sks`. Blocks
+    ///   before the birthday of a key are not scanned for it, 0 if unknown
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if birthdays don't match the keys or the sync fails
     pub async fn shielded_sync(
         &self,
         vks: Box<[JsValue]>,
         sks: Box<[JsValue]>,
+        birthdays: Option<Vec<u64>>,
     ) -> Result<(), JsError> {
-        let vks: Vec<ViewingKey> = vks
+        let xvks: Vec<String> = vks.iter().filter_map(|owner| owner.as_string()).collect();
+        let vks: Vec<ViewingKey> = xvks
             .iter()
-            .filter_map(|owner| owner.as_string())
             .map(|o| {
-                ExtendedFullViewingKey::from(ExtendedViewingKey::from_str(&o).unwrap())
+                ExtendedFullViewingKey::from(ExtendedViewingKey::from_str(o).unwrap())
                     .fvk
                     .vk
             })
@@ -346,63 +503,419 @@ impl Query {
             .map(|sk| ExtendedSpendingKey::from_str(&sk).unwrap())
             .collect::<Vec<_>>();
 
+        let birthdays = birthdays.unwrap_or_else(|| vec![0; vks.len() + sks.len()]);
+        if birthdays.len() != vks.len() + sks.len() {
+            return Err(JsError::new("Expected a birthday for every key"));
+        }
+        // Nothing before the loaded checkpoint can be synced anyway
+        let checkpoint_height = self
+            .sync_checkpoint()
+            .await?
+            .map(|checkpoint| checkpoint.height)
+            .unwrap_or_default();
+        let (vk_birthdays, sk_birthdays) = birthdays.split_at(vks.len());
+        let synced_vks: Vec<ViewingKey> = vks
+            .iter()
+            .cloned()
+            .chain(
+                sks.iter()
+                    .map(|sk| ExtendedFullViewingKey::from(sk.to_viewing_key()).fvk.vk),
+            )
+            .collect();
+        // The sync waits for the current height, so all keys are synced at least up to it
+        let target_height = self
+            .client
+            .latest_block()
+            .await?
+            .block
+            .header
+            .height
+            .value();
+        let birthday = |height: &u64| BlockHeight::from(std::cmp::max(*height, checkpoint_height));
+
+        let work_store = self.work_store.borrow().clone();
+        if let Some(work_store) = work_store {
+            // Spending keys are only needed for their viewing keys, so those are stored instead
+            let xvks: Vec<String> = xvks
+                .into_iter()
+                .chain(sks.iter().map(|sk| sk.to_viewing_key().to_string()))
+                .collect();
+            let cursor = match work_store.load::<SyncCursor>().await? {
+                Some(mut cursor) if cursor.has_keys(&xvks) => {
+                    cursor.target_height = std::cmp::max(cursor.target_height, target_height);
+                    cursor
+                }
+                _ => SyncCursor {
+                    synced_to: birthdays
+                        .iter()
+                        .map(|height| birthday(height).0)
+                        .min()
+                        .unwrap_or(checkpoint_height),
+                    vks: xvks,
+                    birthdays,
+                    target_height,
+                },
+            };
+
+            return self.sync_cursor(&work_store, cursor).await;
+        }
+
         let dated_keypairs = vks
             .into_iter()
-            .map(|vk| DatedKeypair {
+            .zip(vk_birthdays)
+            .map(|(vk, height)| DatedKeypair {
                 key: vk,
-                birthday: BlockHeight::from(0),
+                birthday: birthday(height),
             })
             .collect::<Vec<_>>();
 
         let dated_sks = sks
             .into_iter()
-            .map(|sk| DatedKeypair {
+            .zip(sk_birthdays)
+            .map(|(sk, height)| DatedKeypair {
                 key: sk,
-                birthday: BlockHeight::from(0),
+                birthday: birthday(height),
             })
             .collect::<Vec<_>>();
 
-        match &self.masp_client {
-            MaspClient::Indexer(client) => {
-                web_sys::console::log_1(&"Syncing using IndexerMaspClient".into());
-                self.sync(client.clone(), dated_keypairs, dated_sks).await?
-            }
-            MaspClient::Ledger(client) => {
-                web_sys::console::log_1(&"Syncing using LedgerMaspClient".into());
-                self.sync(client.clone(), dated_keypairs, dated_sks).await?
+        self.sync_with_fallback(
+            &dated_keypairs,
+            &dated_sks,
+            Some(BlockHeight::from(target_height)),
+        )
+        .await?;
+        self.save_snapshot(target_height).await?;
+
+        let mut key_sync_heights = self.key_sync_heights().await?;
+        for vk in synced_vks {
+            key_sync_heights.insert(vk, target_height);
+        }
+
+        self.save_key_sync_heights(key_sync_heights).await
+    }
+
+    /// Returns the sync status of each viewing key: the height it was last synced to and its
+    /// decrypted notes, so accounts can show their own sync progress and
+    /// balances of keys which are not synced yet can be hidden.
+    ///
+    /// # Arguments
+    ///
+    /// * `vks` - Extended viewing keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a viewing key is invalid or the latest block can't be queried
+    pub async fn get_sync_status(&self, vks: Vec<String>) -> Result<JsValue, JsError> {
+        let latest_height = self
+            .client
+            .latest_block()
+            .await?
+            .block
+            .header
+            .height
+            .value();
+
+        let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+        // Nothing is stored before the first sync
+        let _ = shielded.load().await;
+
+        let key_sync_heights = self.key_sync_heights().await?;
+        let mut statuses: Vec<KeySyncStatus> = vec![];
+        for xvk in vks {
+            let vk = ExtendedViewingKey::from_str(&xvk)
+                .map(|xvk| ExtendedFullViewingKey::from(xvk).fvk.vk)
+                .map_err(|err| JsError::new(&format!("Invalid viewing key {}: {}", xvk, err)))?;
+            let notes = shielded.pos_map.get(&vk);
+
+            statuses.push(KeySyncStatus {
+                synced_height: key_sync_heights.get(&vk).copied(),
+                latest_height,
+                notes: notes.map(|notes| notes.len() as u32).unwrap_or_default(),
+                unspent_notes: notes
+                    .map(|notes| {
+                        notes
+                            .iter()
+                            .filter(|note_pos| !shielded.spents.contains(*note_pos))
+                            .count() as u32
+                    })
+                    .unwrap_or_default(),
+                viewing_key: xvk,
+            });
+        }
+
+        to_js_result(statuses)
+    }
+
+    /// Persists the shielded sync state which is not saved yet, the heights the keys were synced
+    /// to and the cursor of a running sync. Meant to be called next to `Sdk::flush` from
+    /// `beforeunload` or `visibilitychange` handlers.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget_ms` - optional time in milliseconds after which flushing gives up
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if saving fails
+    pub async fn flush(&self, budget_ms: Option<u32>) -> Result<JsValue, JsError> {
+        let mut result = flush::FlushResult::default();
+        if self.dirty.borrow().is_clean() {
+            return to_js_result(result);
+        }
+
+        let saved = flush::with_budget(
+            async {
+                if self.dirty.borrow().key_sync_heights {
+                    let key_sync_heights = self.key_sync_heights().await?;
+                    self.save_key_sync_heights(key_sync_heights).await?;
+                }
+
+                let cursor = self.dirty.borrow().sync_cursor.clone();
+                let work_store = self.work_store.borrow().clone();
+                if let (Some(cursor), Some(work_store)) = (cursor, work_store) {
+                    self.save_sync_cursor(&work_store, &cursor).await?;
+                }
+                result.sync_state_saved = true;
+
+                Ok::<_, JsError>(())
+            },
+            budget_ms,
+        )
+        .await;
+        match saved {
+            Some(saved) => saved?,
+            None => result.timed_out = true,
+        }
+
+        to_js_result(result)
+    }
+
+    /// Sets the storage shielded sync writes its cursor to after every chunk of blocks, so
+    /// that `resume_pending_work` can continue an interrupted sync, e.g. in an MV3 service
+    /// worker which was stopped mid-way
+    ///
+    /// # Arguments
+    ///
+    /// * `save` - function called with a Uint8Array to store, or null to clear it
+    /// * `load` - function returning the stored Uint8Array or undefined
+    pub fn set_work_store(&self, save: js_sys::Function, load: js_sys::Function) {
+        *self.work_store.borrow_mut() = Some(WorkStore::new(save, load));
+    }
+
+    /// Continues a shielded sync interrupted before reaching its target height. Returns true if
+    /// there was pending work.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no work store is set, the cursor can't be loaded or the sync fails
+    pub async fn resume_pending_work(&self) -> Result<bool, JsError> {
+        let work_store = self
+            .work_store
+            .borrow()
+            .clone()
+            .ok_or_else(|| JsError::new("No work store set"))?;
+
+        match work_store.load::<SyncCursor>().await? {
+            Some(cursor) if cursor.is_pending() => {
+                self.sync_cursor(&work_store, cursor).await?;
+                Ok(true)
             }
+            _ => Ok(false),
+        }
+    }
+
+    /// Syncs the keys of the cursor chunk by chunk up to its target height, storing the cursor
+    /// after every chunk
+    async fn sync_cursor(
+        &self,
+        work_store: &WorkStore,
+        mut cursor: SyncCursor,
+    ) -> Result<(), JsError> {
+        if cursor.vks.len() != cursor.birthdays.len() {
+            return Err(JsError::new("Expected a birthday for every key"));
+        }
+        let vks = cursor
+            .vks
+            .iter()
+            .map(|xvk| {
+                ExtendedViewingKey::from_str(xvk)
+                    .map(|xvk| ExtendedFullViewingKey::from(xvk).fvk.vk)
+                    .map_err(|err| JsError::new(&format!("Invalid viewing key {}: {}", xvk, err)))
+            })
+            .collect::<Result<Vec<ViewingKey>, JsError>>()?;
+        // A restarted worker continues from the stored cursor even if the first chunk is cut
+        self.save_sync_cursor(work_store, &cursor).await?;
+
+        while let Some(height) = cursor.next_height(CHUNK_BLOCKS) {
+            let dated_keypairs = vks
+                .iter()
+                .cloned()
+                .zip(cursor.scan_heights())
+                .map(|(vk, height)| DatedKeypair {
+                    key: vk,
+                    birthday: BlockHeight::from(height),
+                })
+                .collect::<Vec<_>>();
+            self.sync_with_fallback(&dated_keypairs, &[], Some(BlockHeight::from(height)))
+                .await?;
+            self.save_snapshot(height).await?;
+            cursor.advance(height);
+            self.save_sync_cursor(work_store, &cursor).await?;
+        }
+
+        let mut key_sync_heights = self.key_sync_heights().await?;
+        for vk in vks {
+            key_sync_heights.insert(vk, cursor.synced_to);
+        }
+
+        self.save_key_sync_heights(key_sync_heights).await
+    }
+
+    /// Stores the synced shielded context as a snapshot `rescan_shielded` can restore
+    async fn save_snapshot(&self, height: u64) -> Result<(), JsError> {
+        let utils = ShieldedContext::<JSShieldedUtils>::default().utils;
+        let Some(context) = utils.load_context_bytes().await? else {
+            return Ok(());
         };
+        let mut snapshots: Snapshots = utils
+            .load_snapshots()
+            .await?
+            .map(|bytes| borsh::from_slice(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        snapshots.push(Snapshot { height, context });
+        utils
+            .save_snapshots(Some(&borsh::to_vec(&snapshots)?))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the checkpoint the shielded context was started from, restoring it from the
+    /// storage of the context if it was loaded by an earlier instance
+    async fn sync_checkpoint(&self) -> Result<Option<TreeCheckpoint>, JsError> {
+        if let Some(checkpoint) = self.sync_checkpoint.borrow().clone() {
+            return Ok(Some(checkpoint));
+        }
+
+        let utils = ShieldedContext::<JSShieldedUtils>::default().utils;
+        let checkpoint = utils
+            .load_checkpoint()
+            .await?
+            .map(|bytes| borsh::from_slice::<TreeCheckpoint>(&bytes))
+            .transpose()?;
+        self.sync_checkpoint.borrow_mut().clone_from(&checkpoint);
+
+        Ok(checkpoint)
+    }
+
+    /// Returns the heights the viewing keys were synced to, restoring them from the storage of
+    /// the context if they were saved by an earlier instance
+    async fn key_sync_heights(&self) -> Result<HashMap<ViewingKey, u64>, JsError> {
+        if let Some(key_sync_heights) = self.key_sync_heights.borrow().clone() {
+            return Ok(key_sync_heights);
+        }
+
+        let utils = ShieldedContext::<JSShieldedUtils>::default().utils;
+        let key_sync_heights: HashMap<ViewingKey, u64> = utils
+            .load_key_sync_heights()
+            .await?
+            .map(|bytes| borsh::from_slice::<Vec<(ViewingKey, u64)>>(&bytes))
+            .transpose()?
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        *self.key_sync_heights.borrow_mut() = Some(key_sync_heights.clone());
+
+        Ok(key_sync_heights)
+    }
+
+    /// Stores the heights the viewing keys were synced to next to the shielded context, so they
+    /// survive reloads
+    async fn save_key_sync_heights(
+        &self,
+        key_sync_heights: HashMap<ViewingKey, u64>,
+    ) -> Result<(), JsError> {
+        let bytes = borsh::to_vec(&key_sync_heights.iter().collect::<Vec<_>>())?;
+        *self.key_sync_heights.borrow_mut() = Some(key_sync_heights);
+        // Kept dirty until saved, so that `flush` retries an interrupted save
+        self.dirty.borrow_mut().key_sync_heights = true;
+        let utils = ShieldedContext::<JSShieldedUtils>::default().utils;
+        utils.save_key_sync_heights(Some(&bytes)).await?;
+        self.dirty.borrow_mut().key_sync_heights = false;
+
+        Ok(())
+    }
+
+    /// Writes the cursor to the work store, keeping it for `flush` until it is written
+    async fn save_sync_cursor(
+        &self,
+        work_store: &WorkStore,
+        cursor: &SyncCursor,
+    ) -> Result<(), JsError> {
+        self.dirty.borrow_mut().sync_cursor = Some(cursor.clone());
+        work_store.save(Some(cursor)).await?;
+        let mut dirty = self.dirty.borrow_mut();
+        // A newer cursor may have been set while saving
+        if dirty.sync_cursor.as_ref() == Some(cursor) {
+            dirty.sync_cursor = None;
+        }
 
         Ok(())
     }
 
-    async fn sync<C>(
+    /// Syncs using the indexer if it is available, falling back to the ledger
+    async fn sync_with_fallback(
+        &self,
+        dated_keypairs: &[DatedKeypair<ViewingKey>],
+        dated_sks: &[DatedKeypair<ExtendedSpendingKey>],
+        last_query_height: Option<BlockHeight>,
+    ) -> Result<(), JsError> {
+        if let Some(indexer_source) = &self.indexer_source {
+            if indexer_source.is_available().await {
+                match self
+                    .sync(indexer_source, dated_keypairs, dated_sks, last_query_height)
+                    .await
+                {
+                    Ok(()) => return Ok(()),
+                    Err(_) => web_sys::console::warn_1(
+                        &"Syncing using IndexerMaspClient failed, falling back to the ledger"
+                            .into(),
+                    ),
+                }
+            }
+        }
+
+        self.sync(
+            &self.rpc_source,
+            dated_keypairs,
+            dated_sks,
+            last_query_height,
+        )
+        .await
+    }
+
+    async fn sync<S: BlockSource>(
         &self,
-        client: C,
-        dated_keypairs: Vec<DatedKeypair<ViewingKey>>,
-        dated_sks: Vec<DatedKeypair<ExtendedSpendingKey>>,
-    ) -> Result<(), JsError>
-    where
-        C: NamadaMaspClient + Send + Sync + Unpin + 'static,
-    {
+        source: &S,
+        dated_keypairs: &[DatedKeypair<ViewingKey>],
+        dated_sks: &[DatedKeypair<ExtendedSpendingKey>],
+        last_query_height: Option<BlockHeight>,
+    ) -> Result<(), JsError> {
+        web_sys::console::log_1(&format!("Syncing using {}", source.name()).into());
+        let limits = self.memory_limits.borrow().clone();
         let progress_bar_scanned = sync::ProgressBarWeb::new(SDK_SCANNED_PROGRESS_BAR);
         let progress_bar_fetched = sync::ProgressBarWeb::new(SDK_FETCHED_PROGRESS_BAR);
         let progress_bar_applied = sync::ProgressBarWeb::new(SDK_APPLIED_PROGRESS_BAR);
         let shutdown_signal_web = sync::ShutdownSignalWeb {};
-        // batch size does not matter for masp ledger client, and if we set to sth else than 1 it breaks
-        // progress bar
-        let batch_size = match self.masp_client {
-            MaspClient::Ledger(_) => 1,
-            MaspClient::Indexer(_) => 100,
-        };
 
         let config = ShieldedSyncConfig::builder()
-            .client(client)
+            .client(source.client())
             .scanned_tracker(progress_bar_scanned)
             .fetched_tracker(progress_bar_fetched)
             .applied_tracker(progress_bar_applied)
             .shutdown_signal(shutdown_signal_web)
-            .block_batch_size(batch_size)
+            .block_batch_size(limits.batch_size(source.batch_size()))
             .wait_for_last_query_height(true)
             .retry_strategy(RetryStrategy::Times(10))
             .build();
@@ -412,114 +925,711 @@ impl Query {
         let mut shielded_context: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
 
         shielded_context
-            .sync(
-                env,
-                config,
-                None,
-                dated_sks.as_slice(),
-                dated_keypairs.as_slice(),
-            )
+            .sync(env, config, last_query_height, dated_sks, dated_keypairs)
             .await
             .map_err(|e| JsError::new(&format!("{:?}", e)))?;
 
+        if limits.prune_spent_witnesses {
+            let spents = shielded_context.spents.clone();
+            let pruned = memory::prune_spent_witnesses(&mut shielded_context.witness_map, &spents);
+            if pruned > 0 {
+                shielded_context.save().await?;
+            }
+        }
+
         Ok(())
     }
 
-    /// Queries shielded balance for a given extended viewing key
+    /// Limits memory used by shielded sync, for hosts with little RAM
     ///
     /// # Arguments
     ///
-    /// * `xvk` - Extended viewing key
-    async fn query_shielded_balance(
-        &self,
-        xvk: ExtendedViewingKey,
-        tokens: Vec<Address>,
-    ) -> Result<Vec<(Address, token::Amount)>, JsError> {
-        let viewing_key = ExtendedFullViewingKey::from(xvk).fvk.vk;
+    /// * `max_cached_blocks` - optional maximum of blocks fetched and held at once
+    /// * `prune_spent_witnesses` - drop witnesses of spent notes after every sync
+    pub fn set_memory_limits(&self, max_cached_blocks: Option<u32>, prune_spent_witnesses: bool) {
+        *self.memory_limits.borrow_mut() = MemoryLimits {
+            max_cached_blocks: max_cached_blocks.map(|max| max as usize),
+            prune_spent_witnesses,
+        };
+    }
+
+    /// Loads a trusted commitment tree checkpoint, so that `shielded_sync` of a new account
+    /// starts at the checkpoint height instead of genesis. The checkpoint anchor is verified
+    /// against the chain. Notes received before the checkpoint are not found. Returns the
+    /// checkpoint height.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpoint` - Borsh encoded TreeCheckpoint
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint is for another chain, its tree does not match the
+    /// anchor, the anchor is unknown to the chain or the shielded context was already synced
+    pub async fn load_tree_checkpoint(&self, checkpoint: &[u8]) -> Result<u64, JsError> {
+        let checkpoint: TreeCheckpoint = borsh::from_slice(checkpoint)?;
+        let tree = checkpoint.tree()?;
+
+        let chain_id = self.client.latest_block().await?.block.header.chain_id;
+        if chain_id.as_str() != checkpoint.chain_id {
+            return Err(JsError::new(&format!(
+                "Checkpoint is for chain {}, connected to {}",
+                checkpoint.chain_id, chain_id
+            )));
+        }
+
+        let anchor_key = token::storage_key::masp_commitment_anchor_key(tree.root());
+        if !rpc::query_has_storage_key(&self.client, &anchor_key).await? {
+            return Err(JsError::new(&format!(
+                "Checkpoint anchor {} is unknown to the chain",
+                checkpoint.anchor
+            )));
+        }
 
-        // We are recreating shielded context to avoid multiple mutable borrows
         let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
-        shielded.load().await?;
+        // Nothing is stored before the first sync
+        let _ = shielded.load().await;
+        if shielded.tree.size() > 0 {
+            return Err(JsError::new("Shielded context is already synced"));
+        }
+        shielded.tree = tree;
+        shielded.save().await?;
+        // The tree is only usable together with its height, so both are stored
         shielded
-            .precompute_asset_types(&self.client, tokens.iter().collect())
-            .await
-            .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+            .utils
+            .save_checkpoint(Some(&borsh::to_vec(&checkpoint)?))
+            .await?;
 
-        let epoch = query_masp_epoch(&self.client).await?;
-        let balance = shielded
-            .compute_exchanged_balance(&self.client, &WebIo, &viewing_key, epoch)
-            .await
-            .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+        let height = checkpoint.height;
+        *self.sync_checkpoint.borrow_mut() = Some(checkpoint);
 
-        let res = match balance {
-            Some(balance) => {
-                let decoded_balance = shielded
-                    .decode_combine_sum_to_epoch(&self.client, balance, epoch)
-                    .await;
+        Ok(height)
+    }
+
+    /// Checks the stored shielded context for corruption: the commitment tree root has to be an
+    /// anchor known to the chain, witnesses have to match the tree and every note of a viewing
+    /// key has to be decrypted. Issues found suggest calling `rescan_shielded`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shielded context can't be loaded or the anchor can't be queried
+    pub async fn check_shielded_state(&self) -> Result<JsValue, JsError> {
+        let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+        shielded.load().await?;
+
+        let root = shielded.tree.root();
+        let mut issues = integrity::check_witnesses(&shielded.tree, &shielded.witness_map);
 
-                Self::get_decoded_balance(decoded_balance)
+        if shielded.tree.size() > 0 {
+            let anchor_key = token::storage_key::masp_commitment_anchor_key(root);
+            if !rpc::query_has_storage_key(&self.client, &anchor_key).await? {
+                issues.push(String::from(
+                    "Commitment tree root is not an anchor known to the chain",
+                ));
             }
-            None => vec![],
-        };
+        }
+
+        let missing_notes = shielded
+            .pos_map
+            .values()
+            .flatten()
+            .filter(|note_pos| !shielded.note_map.contains_key(*note_pos))
+            .count();
+        if missing_notes > 0 {
+            issues.push(format!("{} notes are not decrypted", missing_notes));
+        }
 
-        Ok(res)
+        to_js_result(integrity::ShieldedIntegrityReport::new(
+            shielded.tree.size() as u64,
+            hex::encode(borsh::to_vec(&root)?),
+            issues,
+        ))
     }
 
-    pub async fn query_balance(
+    /// Drops decrypted notes and witnesses above `from_height` and syncs again. The commitment
+    /// tree can't be rolled back to an arbitrary height, so the context is restored from the
+    /// newest snapshot taken while syncing at or below `from_height`. Without one it restarts
+    /// from the loaded checkpoint if it is not above `from_height`, otherwise from genesis.
+    /// Returns the height the sync restarted from.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_height` - first height which is rescanned
+    /// * `vks` - Extended viewing keys to sync
+    /// * `sks` - Extended spending keys to sync
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context can't be reset or the sync fails
+    pub async fn rescan_shielded(
         &self,
-        owner: String,
-        tokens: Box<[JsValue]>,
-    ) -> Result<JsValue, JsError> {
-        let tokens: Vec<Address> = tokens
-            .iter()
-            .map(|address| {
-                let address_str = address.as_string().unwrap();
-                Address::from_str(&address_str).unwrap()
-            })
-            .collect();
-
-        let result = match Address::from_str(&owner) {
-            Ok(addr) => self.query_transparent_balance(addr, tokens).await,
-            Err(e1) => match ExtendedViewingKey::from_str(&owner) {
-                Ok(xvk) => self.query_shielded_balance(xvk, tokens).await,
-                Err(e2) => return Err(JsError::new(&format!("{} {}", e1, e2))),
-            },
-        }?;
+        from_height: u64,
+        vks: Box<[JsValue]>,
+        sks: Box<[JsValue]>,
+    ) -> Result<u64, JsError> {
+        let utils = ShieldedContext::<JSShieldedUtils>::default().utils;
+        let mut snapshots: Snapshots = utils
+            .load_snapshots()
+            .await?
+            .map(|bytes| borsh::from_slice(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        // Blocks up to the snapshot height were synced, the rescan starts after it
+        let snapshot = snapshots.rollback(from_height.saturating_sub(1)).cloned();
+        utils
+            .save_snapshots(Some(&borsh::to_vec(&snapshots)?))
+            .await?;
 
-        let mut mapped_result: Vec<(Address, String)> = vec![];
-        for (token, amount) in result {
-            mapped_result.push((token.clone(), amount.to_string()))
+        let start_height = match snapshot {
+            Some(snapshot) => {
+                utils.save_context_bytes(&snapshot.context).await?;
+                snapshot.height
+            }
+            None => self.reset_shielded(from_height).await?,
+        };
+        self.save_key_sync_heights(HashMap::new()).await?;
+        // A cursor of an earlier sync would skip the rescanned blocks
+        self.dirty.borrow_mut().sync_cursor = None;
+        let work_store = self.work_store.borrow().clone();
+        if let Some(work_store) = work_store {
+            work_store.save::<SyncCursor>(None).await?;
         }
 
-        to_js_result(mapped_result)
+        self.shielded_sync(vks, sks, None).await?;
+
+        Ok(start_height)
     }
 
-    pub async fn query_public_key(&self, address: &str) -> Result<JsValue, JsError> {
-        let addr = Address::from_str(address).map_err(JsError::from)?;
-        let pk = get_public_key_at(&self.client, &addr, 0).await?;
+    /// Resets the shielded context to the loaded checkpoint if it is not above `from_height`,
+    /// otherwise to genesis, and returns the height the context starts from
+    async fn reset_shielded(&self, from_height: u64) -> Result<u64, JsError> {
+        let checkpoint = self
+            .sync_checkpoint()
+            .await?
+            .filter(|checkpoint| checkpoint.height <= from_height);
 
-        let result = pk.map(|v| v.to_string());
+        let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+        let start_height = match &checkpoint {
+            Some(checkpoint) => {
+                shielded.tree = checkpoint.tree()?;
+                checkpoint.height
+            }
+            None => 0,
+        };
+        shielded.save().await?;
+        shielded
+            .utils
+            .save_checkpoint(
+                checkpoint
+                    .as_ref()
+                    .map(borsh::to_vec)
+                    .transpose()?
+                    .as_deref(),
+            )
+            .await?;
+        *self.sync_checkpoint.borrow_mut() = checkpoint;
 
-        to_js_result(result)
+        Ok(start_height)
     }
 
-    pub async fn query_signed_bridge_pool(
-        &self,
-        owner_addresses: Box<[JsValue]>,
-    ) -> Result<JsValue, JsError> {
-        let bridge_pool = query_signed_bridge_pool(&self.client, &WebIo).await?;
-
-        let owner_addresses: Vec<Address> = owner_addresses
+    /// Scans compact blocks for notes of the viewing keys, which downloads far less than
+    /// `shielded_sync`. `ingest_compact_blocks` also applies the result to the shielded context.
+    ///
+    /// # Arguments
+    ///
+    /// * `vks` - Extended viewing keys
+    /// * `blocks` - Borsh encoded Vec<CompactBlock>
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a viewing key is invalid or the blocks can't be decoded
+    pub fn scan_compact_blocks(&self, vks: Vec<String>, blocks: &[u8]) -> Result<JsValue, JsError> {
+        let vks = vks
             .iter()
-            .filter_map(|address| address.as_string())
-            .filter_map(|address| Address::from_str(&address).ok())
-            .collect();
+            .map(|vk| {
+                ExtendedViewingKey::from_str(vk)
+                    .map(|xvk| ExtendedFullViewingKey::from(xvk).fvk.vk)
+                    .map_err(|err| JsError::new(&format!("Invalid viewing key {}: {}", vk, err)))
+            })
+            .collect::<Result<Vec<ViewingKey>, JsError>>()?;
+        let blocks: Vec<CompactBlock> = borsh::from_slice(blocks)?;
 
-        let result: Vec<TransferToEthereum> = bridge_pool
-            .into_iter()
-            .filter_map(|(_hash, pending_transfer)| {
-                if owner_addresses.contains(&pending_transfer.transfer.sender) {
-                    Some(pending_transfer.transfer)
+        to_js_result(compact::scan_compact_blocks(&blocks, &vks))
+    }
+
+    /// Ingests compact blocks into the shielded context. If the blocks hold notes of the keys or
+    /// spend their notes, the context is synced so the notes, memos and witnesses are stored.
+    /// Otherwise nothing changed for the keys and they are marked as synced up to the last
+    /// block without downloading full blocks. Keys which were never synced are always synced.
+    /// Returns true if the full sync ran.
+    ///
+    /// # Arguments
+    ///
+    /// * `vks` - Extended viewing keys
+    /// * `sks` - Extended spending keys
+    /// * `blocks` - Borsh encoded Vec<CompactBlock> of every block with MASP txs after the
+    ///   last sync of the keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is invalid, the blocks can't be decoded or the sync fails
+    pub async fn ingest_compact_blocks(
+        &self,
+        vks: Box<[JsValue]>,
+        sks: Box<[JsValue]>,
+        blocks: &[u8],
+    ) -> Result<bool, JsError> {
+        let compact_blocks: Vec<CompactBlock> = borsh::from_slice(blocks)?;
+        let Some(last_height) = compact_blocks.iter().map(|block| block.height).max() else {
+            return Ok(false);
+        };
+        let mut synced_vks = vec![];
+        for xvk in vks.iter().filter_map(|vk| vk.as_string()) {
+            let xvk = ExtendedViewingKey::from_str(&xvk)
+                .map_err(|err| JsError::new(&format!("Invalid viewing key {}: {}", xvk, err)))?;
+            synced_vks.push(ExtendedFullViewingKey::from(xvk).fvk.vk);
+        }
+        for xsk in sks.iter().filter_map(|sk| sk.as_string()) {
+            let xsk = ExtendedSpendingKey::from_str(&xsk)
+                .map_err(|err| JsError::new(&format!("Invalid spending key: {}", err)))?;
+            synced_vks.push(ExtendedFullViewingKey::from(xsk.to_viewing_key()).fvk.vk);
+        }
+
+        let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+        let loaded = shielded.load().await.is_ok();
+        let nullifiers: std::collections::HashSet<[u8; 32]> = shielded
+            .nf_map
+            .keys()
+            .map(|nullifier| nullifier.0)
+            .collect();
+        let mut key_sync_heights = self.key_sync_heights().await?;
+        let all_synced = synced_vks
+            .iter()
+            .all(|vk| key_sync_heights.contains_key(vk));
+
+        if !loaded
+            || !all_synced
+            || !compact::scan_compact_blocks(&compact_blocks, &synced_vks)
+                .matches
+                .is_empty()
+            || compact::spends_any(&compact_blocks, &nullifiers)
+        {
+            self.shielded_sync(vks, sks, None).await?;
+            return Ok(true);
+        }
+
+        for vk in synced_vks {
+            key_sync_heights
+                .entry(vk)
+                .and_modify(|height| *height = std::cmp::max(*height, last_height));
+        }
+        self.save_key_sync_heights(key_sync_heights).await?;
+
+        Ok(false)
+    }
+
+    /// Queries shielded balance for a given extended viewing key
+    ///
+    /// # Arguments
+    ///
+    /// * `xvk` - Extended viewing key
+    async fn query_shielded_balance(
+        &self,
+        xvk: ExtendedViewingKey,
+        tokens: Vec<Address>,
+    ) -> Result<Vec<(Address, token::Amount)>, JsError> {
+        let viewing_key = ExtendedFullViewingKey::from(xvk).fvk.vk;
+
+        shielded_balance(&self.client, &viewing_key, &tokens).await
+    }
+
+    /// Scans diversifier indices of the viewing key against the synced notes to recover
+    /// previously used payment addresses. Scanning stops after `gap_limit` consecutive
+    /// diversifier indices without notes. Requires `shielded_sync` to be called first.
+    ///
+    /// # Arguments
+    ///
+    /// * `xvk` - Extended viewing key
+    /// * `gap_limit` - number of consecutive unused addresses after which scanning stops
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the viewing key is invalid or the shielded context can't be loaded
+    pub async fn scan_payment_addresses(
+        &self,
+        xvk: String,
+        gap_limit: u32,
+    ) -> Result<JsValue, JsError> {
+        let xfvk = ExtendedFullViewingKey::from(ExtendedViewingKey::from_str(&xvk)?);
+
+        let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+        shielded.load().await?;
+
+        let used_diversifiers = shielded
+            .pos_map
+            .get(&xfvk.fvk.vk)
+            .map(|note_indices| {
+                note_indices
+                    .iter()
+                    .filter_map(|idx| shielded.div_map.get(idx).copied())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut addresses: Vec<ScannedPaymentAddress> = vec![];
+        let mut gap = 0;
+        let mut diversifier_index = DiversifierIndex::new();
+        while gap < gap_limit {
+            // Not every index produces a valid diversifier, find_address skips to the next valid
+            let Some((index, pa)) = xfvk.find_address(diversifier_index) else {
+                break;
+            };
+
+            if used_diversifiers.contains(pa.diversifier()) {
+                addresses.push(ScannedPaymentAddress {
+                    diversifier_index: hex::encode(index.0),
+                    payment_address: PaymentAddress::from(pa).to_string(),
+                });
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+
+            diversifier_index = index;
+            if diversifier_index.increment().is_err() {
+                break;
+            }
+        }
+
+        to_js_result(addresses)
+    }
+
+    /// Returns the balances of the owner, `[token, amount, status]` with the amount in base
+    /// units. Convert it with `amount_from_base_units` before passing it to the decimal
+    /// arithmetic.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - address or extended viewing key
+    /// * `tokens` - addresses of the tokens
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the owner is invalid or the RPC call fails
+    pub async fn query_balance(
+        &self,
+        owner: String,
+        tokens: Box<[JsValue]>,
+    ) -> Result<JsValue, JsError> {
+        let tokens: Vec<Address> = tokens
+            .iter()
+            .map(|address| {
+                let address_str = address.as_string().unwrap();
+                Address::from_str(&address_str).unwrap()
+            })
+            .collect();
+
+        let result = match Address::from_str(&owner) {
+            Ok(addr) => self.query_transparent_balance(addr, tokens).await,
+            Err(e1) => match ExtendedViewingKey::from_str(&owner) {
+                Ok(xvk) => self.query_shielded_balance(xvk, tokens).await,
+                Err(e2) => return Err(JsError::new(&format!("{} {}", e1, e2))),
+            },
+        }?;
+
+        // Tokens outside the allowlist are kept, marked so the interface can flag them
+        let mut mapped_result: Vec<(Address, BaseAmount, String)> = vec![];
+        for (token, amount) in result {
+            let status = tokens::token_status(&token).to_string();
+            mapped_result.push((token.clone(), BaseAmount::from(amount), status))
+        }
+
+        to_js_result(mapped_result)
+    }
+
+    /// Returns the holdings of the accounts and viewing keys summed per token, e.g.
+    /// `[{ token, status, transparent, shielded, bonded, unbonding, total }]` in base units.
+    /// Bonds and unbonds are in the native token, unbonding includes withdrawable tokens. Tokens
+    /// outside the token allowlist have status "unknown" or "look_alike". Shielded balances
+    /// require `shielded_sync` to be called first.
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - transparent account addresses
+    /// * `viewing_keys` - extended viewing keys
+    /// * `tokens` - token addresses to include
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an address or key is invalid or a query fails
+    pub async fn get_portfolio(
+        &self,
+        addresses: Vec<String>,
+        viewing_keys: Vec<String>,
+        tokens: Vec<String>,
+    ) -> Result<JsValue, JsError> {
+        let tokens = tokens
+            .iter()
+            .map(|token| Address::from_str(token))
+            .collect::<Result<Vec<Address>, _>>()?;
+        let native_token = query_native_token(&self.client).await?;
+        let epoch = query_epoch(&self.client).await?;
+        let mut portfolio = Portfolio::default();
+
+        for address in addresses {
+            let owner = Address::from_str(&address)?;
+            for (token, amount) in self
+                .query_transparent_balance(owner.clone(), tokens.clone())
+                .await?
+            {
+                portfolio.add_transparent(token, amount);
+            }
+
+            let enriched = RPC
+                .vp()
+                .pos()
+                .enriched_bonds_and_unbonds(&self.client, epoch, &Some(owner), &None)
+                .await?;
+            let net = |amount: token::Amount, slashed: token::Amount| {
+                amount.checked_sub(slashed).unwrap_or_default()
+            };
+            portfolio.add_bonded(
+                native_token.clone(),
+                net(enriched.bonds_total, enriched.bonds_total_slashed),
+            );
+            portfolio.add_unbonding(
+                native_token.clone(),
+                net(enriched.unbonds_total, enriched.unbonds_total_slashed),
+            );
+        }
+
+        for xvk in viewing_keys {
+            let xvk = ExtendedViewingKey::from_str(&xvk)?;
+            for (token, amount) in self.query_shielded_balance(xvk, tokens.clone()).await? {
+                portfolio.add_shielded(token, amount);
+            }
+        }
+
+        to_js_result(portfolio.entries())
+    }
+
+    /// Creates a payment request URI, e.g. `namada:tnam1...?token=tnam1...&amount=10`
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - transparent or shielded address to be paid
+    /// * `token` - address of the token
+    /// * `amount` - optional decimal amount, the payer chooses it if not set
+    /// * `memo` - optional memo of the transfer
+    /// * `expiry` - optional seconds since the Unix epoch after which the request must not be paid
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recipient or token is invalid, or the amount has more decimal
+    /// places than the token
+    pub async fn create_payment_request(
+        &self,
+        recipient: String,
+        token: String,
+        amount: Option<String>,
+        memo: Option<String>,
+        expiry: Option<u64>,
+    ) -> Result<String, JsError> {
+        let request = PaymentRequest {
+            recipient,
+            token,
+            amount,
+            memo,
+            expiry,
+        };
+        let denom = self.token_denom(&request.token()?).await?;
+        request.validate(denom)?;
+
+        Ok(request.to_uri())
+    }
+
+    /// Parses and validates a payment request URI, e.g.
+    /// `{ recipient, token, amount: "10", memo: undefined, expiry: undefined }`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI is malformed, a field is invalid, the amount has more decimal
+    /// places than the token or the request expired
+    pub async fn parse_payment_request(&self, uri: String) -> Result<JsValue, JsError> {
+        let request = PaymentRequest::from_uri(&uri)?;
+        let denom = self.token_denom(&request.token()?).await?;
+        request.validate(denom)?;
+        request.ensure_not_expired((js_sys::Date::now() / 1000.0) as u64)?;
+
+        to_js_result(request)
+    }
+
+    /// Starts polling transparent balances in the background.
+    /// `SdkEvents.BalanceChanged` is dispatched only when a balance changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `owners` - Account addresses in form of bech32 encoded strings
+    /// * `tokens` - Token addresses to watch
+    /// * `interval_ms` - polling interval in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the addresses is invalid
+    pub fn start_balance_watcher(
+        &self,
+        owners: Box<[JsValue]>,
+        tokens: Box<[JsValue]>,
+        interval_ms: u32,
+    ) -> Result<WatcherHandle, JsError> {
+        let parse = |addresses: Box<[JsValue]>| -> Result<Vec<Address>, JsError> {
+            addresses
+                .iter()
+                .map(|address| {
+                    let address = address
+                        .as_string()
+                        .ok_or_else(|| JsError::new("Address has to be a string"))?;
+                    Address::from_str(&address).map_err(JsError::from)
+                })
+                .collect()
+        };
+
+        Ok(start_balance_watcher(
+            self.client.clone(),
+            parse(owners)?,
+            parse(tokens)?,
+            interval_ms,
+        ))
+    }
+
+    /// Starts polling commission rate and state of a validator in the background.
+    /// `callback` is called with the change when the commission changes, the validator gets
+    /// jailed or becomes inactive.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - validator address
+    /// * `callback` - function called with `{ validator, kind, previous, current }`
+    /// * `interval_ms` - polling interval in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the validator address is invalid
+    pub fn watch_validator(
+        &self,
+        validator: String,
+        callback: js_sys::Function,
+        interval_ms: u32,
+    ) -> Result<WatcherHandle, JsError> {
+        let validator = Address::from_str(&validator)?;
+
+        Ok(start_validator_watcher(
+            self.client.clone(),
+            validator,
+            callback,
+            interval_ms,
+        ))
+    }
+
+    /// Starts polling the consensus validator set in the background. `callback` is called
+    /// with `{ epoch, entered, left }` at every epoch boundary at which validators entered or
+    /// left the consensus set.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - function called with the change
+    /// * `interval_ms` - polling interval in milliseconds
+    pub fn watch_validator_set(
+        &self,
+        callback: js_sys::Function,
+        interval_ms: u32,
+    ) -> WatcherHandle {
+        start_validator_set_watcher(self.client.clone(), callback, interval_ms)
+    }
+
+    /// Starts watching for funds received by the accounts and viewing keys in the background.
+    /// `callback` is called with `{ owner, token, amount, kind, hash }` whenever funds are
+    /// received, `kind` being "transparent" or "shielded". Transparent funds are found in the txs
+    /// of new blocks, shielded funds are detected from the balance after `shielded_sync`.
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - account addresses to watch
+    /// * `viewing_keys` - extended viewing keys to watch
+    /// * `tokens` - token addresses to watch
+    /// * `min_amount` - optional smallest amount reported, in base units, smaller ones are ignored
+    /// * `callback` - function called with the incoming transfer
+    /// * `interval_ms` - polling interval in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the addresses, keys or the minimum amount is invalid
+    pub fn watch_incoming(
+        &self,
+        addresses: Vec<String>,
+        viewing_keys: Vec<String>,
+        tokens: Vec<String>,
+        min_amount: Option<String>,
+        callback: js_sys::Function,
+        interval_ms: u32,
+    ) -> Result<WatcherHandle, JsError> {
+        let parse = |addresses: Vec<String>| -> Result<Vec<Address>, JsError> {
+            addresses
+                .iter()
+                .map(|address| Address::from_str(address).map_err(JsError::from))
+                .collect()
+        };
+        let viewing_keys = viewing_keys
+            .into_iter()
+            .map(|xvk| {
+                let viewing_key = ExtendedFullViewingKey::from(ExtendedViewingKey::from_str(&xvk)?)
+                    .fvk
+                    .vk;
+                Ok((xvk, viewing_key))
+            })
+            .collect::<Result<Vec<_>, JsError>>()?;
+        let min_amount = match min_amount {
+            Some(amount) => StringAmount::new(amount).to_amount(0)?,
+            None => token::Amount::zero(),
+        };
+
+        Ok(start_incoming_watcher(
+            self.client.clone(),
+            parse(addresses)?,
+            viewing_keys,
+            parse(tokens)?,
+            min_amount,
+            callback,
+            interval_ms,
+        ))
+    }
+
+    pub async fn query_public_key(&self, address: &str) -> Result<JsValue, JsError> {
+        let addr = Address::from_str(address).map_err(JsError::from)?;
+        let pk = get_public_key_at(&self.client, &addr, 0).await?;
+
+        let result = pk.map(|v| v.to_string());
+
+        to_js_result(result)
+    }
+
+    pub async fn query_signed_bridge_pool(
+        &self,
+        owner_addresses: Box<[JsValue]>,
+    ) -> Result<JsValue, JsError> {
+        let bridge_pool = query_signed_bridge_pool(&self.client, &WebIo).await?;
+
+        let owner_addresses: Vec<Address> = owner_addresses
+            .iter()
+            .filter_map(|address| address.as_string())
+            .filter_map(|address| Address::from_str(&address).ok())
+            .collect();
+
+        let result: Vec<TransferToEthereum> = bridge_pool
+            .into_iter()
+            .filter_map(|(_hash, pending_transfer)| {
+                if owner_addresses.contains(&pending_transfer.transfer.sender) {
+                    Some(pending_transfer.transfer)
                 } else {
                     None
                 }
@@ -529,6 +1639,135 @@ impl Query {
         to_js_result(result)
     }
 
+    /// Estimates the total cost of a transfer to Ethereum, combining the wrapper fee of the
+    /// bridge pool tx with the relayer fee set by `set_relayer_quotes`
+    ///
+    /// # Arguments
+    ///
+    /// * `asset` - ERC20 address
+    /// * `amount` - transferred amount
+    /// * `fee_token` - optional token the wrapper fee is paid in, native token if not provided
+    /// * `gas_limit` - optional gas limit of the bridge pool tx
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fee token is not accepted for gas or the RPC call fails
+    pub async fn estimate_bridge_fee(
+        &self,
+        asset: String,
+        amount: String,
+        fee_token: Option<String>,
+        gas_limit: Option<u64>,
+    ) -> Result<JsValue, JsError> {
+        let fee_token = match fee_token {
+            Some(fee_token) => Address::from_str(&fee_token)?,
+            None => query_native_token(&self.client).await?,
+        };
+        let gas_costs = query_storage_value::<HttpClient, BTreeMap<Address, token::Amount>>(
+            &self.client,
+            &storage::get_gas_cost_key(),
+        )
+        .await?;
+        let gas_price = gas_costs
+            .get(&fee_token)
+            .copied()
+            .ok_or_else(|| JsError::new(&format!("Token {} is not accepted for gas", fee_token)))?;
+
+        let quote = bridge::relayer_quote(&bridge::relayer_quotes(), &asset);
+        let estimate = bridge::bridge_fee(
+            &asset,
+            StringAmount::new(amount),
+            fee_token.to_string(),
+            gas_price,
+            gas_limit.unwrap_or(bridge::BRIDGE_POOL_GAS_LIMIT),
+            quote,
+        )
+        .ok_or_else(|| JsError::new("Overflow when estimating the bridge fee"))?;
+
+        to_js_result(estimate)
+    }
+
+    /// Returns ERC20 tokens whitelisted by the Ethereum bridge with their bridge cap. Symbols
+    /// and decimals are resolved from the mapping set by `set_erc20_metadata`, decimals fall
+    /// back to the on-chain denomination.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn query_bridge_erc20_whitelist(&self) -> Result<JsValue, JsError> {
+        to_js_result(self.bridge_erc20_whitelist().await?)
+    }
+
+    /// Returns the non-zero NUT balances of an owner. NUTs are looked up for the whitelisted
+    /// tokens and the ones set by `set_erc20_metadata`, and can be recovered with an Ethereum
+    /// bridge transfer of the asset with `nut` set.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - account address
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is invalid or the RPC call fails
+    pub async fn query_nut_balances(&self, owner: String) -> Result<JsValue, JsError> {
+        let owner = Address::from_str(&owner)?;
+        let candidates = bridge::nut_candidates(
+            self.bridge_erc20_whitelist().await?,
+            &bridge::erc20_metadata(),
+        );
+
+        let mut balances = vec![];
+        for token in candidates {
+            let nut = bridge::nut_address(&token.address)?;
+            let amount = get_token_balance(&self.client, &nut, &owner, None).await?;
+            if amount.is_zero() {
+                continue;
+            }
+            balances.push(bridge::NutBalance {
+                asset: token.address,
+                token: nut.to_string(),
+                amount: BaseAmount::from(amount),
+                symbol: token.symbol,
+                decimals: token.decimals,
+            });
+        }
+
+        to_js_result(balances)
+    }
+
+    async fn bridge_erc20_whitelist(&self) -> Result<Vec<bridge::Erc20Token>, JsError> {
+        let prefix = Key::from(ETH_BRIDGE.to_db_key())
+            .push(&bridge::WHITELIST_SEGMENT.to_owned())
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        let values = RPC
+            .shell()
+            .storage_prefix(&self.client, None, None, false, &prefix)
+            .await?
+            .data
+            .into_iter()
+            .map(|value| (value.key.to_string(), value.value))
+            .collect();
+
+        Ok(bridge::erc20_whitelist(values, &bridge::erc20_metadata()))
+    }
+
+    /// Returns the raw value stored under a key, keys can be built with `balance_key`,
+    /// `bond_key` and `proposal_key` and values decoded with `decode_borsh`
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - storage key, e.g. "#tnam1.../balance/#tnam1..."
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is invalid or the RPC call fails
+    pub async fn query_storage_value(&self, key: String) -> Result<Option<Vec<u8>>, JsError> {
+        let key = Key::parse(&key).map_err(|e| JsError::new(&e.to_string()))?;
+        let (value, _) = rpc::query_storage_value_bytes(&self.client, &key, None, false).await?;
+
+        Ok(value)
+    }
+
     pub async fn query_total_staked_tokens(&self, epoch: u64) -> Result<JsValue, JsError> {
         let total_staked_tokens = get_total_staked_tokens(&self.client, Epoch(epoch)).await?;
 
@@ -546,46 +1785,7 @@ impl Query {
     }
 
     pub async fn query_proposal_by_id(&self, id: u64) -> Result<Uint8Array, JsError> {
-        let proposal = query_proposal_by_id(&self.client, id)
-            .await
-            .unwrap()
-            .expect("Proposal should be written to storage.");
-
-        let content = serde_json::to_string(&proposal.content)?;
-
-        let is_steward = is_steward(&self.client, &proposal.author).await;
-        let tally_type = proposal.get_tally_type(is_steward);
-        let tally_type_string = match tally_type {
-            // TODO: Change in interface
-            TallyType::TwoFifths => "two-fifths",
-            TallyType::OneHalfOverOneThird => "one-half-over-one-third",
-            TallyType::LessOneHalfOverOneThirdNay => "less-one-half-over-one-third-nay",
-        };
-
-        let (proposal_type, data) = match proposal.r#type {
-            ProposalType::Default => ("default", None),
-            ProposalType::DefaultWithWasm(hash) => ("default", Some(hash.to_string())),
-            ProposalType::PGFSteward(data) => {
-                let data_string = serde_json::to_string(&data)?;
-                ("pgf_steward", Some(data_string))
-            }
-            ProposalType::PGFPayment(data) => {
-                let data_string = serde_json::to_string(&data)?;
-                ("pgf_payment", Some(data_string))
-            }
-        };
-
-        let proposal_info = ProposalInfo {
-            id: proposal.id,
-            author: proposal.author.to_string(),
-            start_epoch: proposal.voting_start_epoch.0,
-            end_epoch: proposal.voting_end_epoch.0,
-            grace_epoch: proposal.activation_epoch.0,
-            content,
-            tally_type: String::from(tally_type_string),
-            proposal_type: String::from(proposal_type),
-            data,
-        };
+        let proposal_info = query_proposal_info(&self.client, id).await?;
 
         let mut writer = vec![];
         BorshSerialize::serialize(&proposal_info, &mut writer)?;
@@ -680,46 +1880,233 @@ impl Query {
         let code =
             query_storage_value::<HttpClient, Vec<u8>>(&self.client, &proposal_code_key).await?;
 
-        Ok(Uint8Array::from(code.as_slice()))
+        Ok(Uint8Array::from(code.as_slice()))
+    }
+
+    /// Returns a list of all delegations for given addresses and epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - delegators addresses
+    /// * `epoch` - epoch in which we want to query delegations
+    pub async fn get_total_delegations(
+        &self,
+        addresses: Box<[JsValue]>,
+        epoch: Option<u64>,
+    ) -> Result<JsValue, JsError> {
+        let addresses: Vec<Address> = addresses
+            .iter()
+            .filter_map(|address| address.as_string())
+            .filter_map(|address| Address::from_str(&address).ok())
+            .collect();
+
+        let epoch = epoch.map(Epoch);
+
+        let mut delegations: HashMap<Address, token::Amount> = HashMap::new();
+
+        for address in addresses.into_iter() {
+            let validators: HashMap<Address, token::Amount> = RPC
+                .vp()
+                .pos()
+                .delegations(&self.client, &address, &epoch)
+                .await?;
+            let sum_of_delegations = validators
+                .into_values()
+                .fold(token::Amount::zero(), |acc, curr| {
+                    acc.checked_add(curr).expect("Amount overflow")
+                });
+
+            delegations.insert(address, sum_of_delegations);
+        }
+
+        to_js_result(delegations)
+    }
+
+    /// Returns the voting power of an address on a proposal, split into the power it has as
+    /// a validator and the power of its delegations, which is what counts when voting as a
+    /// delegator
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - voter address
+    /// * `proposal_id` - id of the proposal
+    /// * `epoch` - epoch at which the power is computed, current epoch if not provided
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal does not exist or the RPC call fails
+    pub async fn query_voting_power(
+        &self,
+        address: String,
+        proposal_id: u64,
+        epoch: Option<u64>,
+    ) -> Result<JsValue, JsError> {
+        let address = Address::from_str(&address)?;
+        query_proposal_by_id(&self.client, proposal_id)
+            .await?
+            .ok_or_else(|| JsError::new(&format!("Proposal {} does not exist", proposal_id)))?;
+
+        let epoch = match epoch {
+            Some(epoch) => Epoch(epoch),
+            None => query_epoch(&self.client).await?,
+        };
+
+        to_js_result(self.voting_power(&address, proposal_id, epoch).await?)
+    }
+
+    /// Returns a statement of the voting power of an address at the snapshot epoch of a
+    /// proposal, i.e. its voting start epoch, for off-chain tally tools. Only the snapshot epoch
+    /// is backed by a Merkle proof at the returned height, when the node provides one. The
+    /// bonds making up the voting power are not proven, tally tools have to trust the node or
+    /// query them again.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - voter address
+    /// * `proposal_id` - id of the proposal
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal does not exist or the RPC call fails
+    pub async fn voting_power_statement(
+        &self,
+        address: String,
+        proposal_id: u64,
+    ) -> Result<JsValue, JsError> {
+        let address = Address::from_str(&address)?;
+        let proposal = query_proposal_by_id(&self.client, proposal_id)
+            .await?
+            .ok_or_else(|| JsError::new(&format!("Proposal {} does not exist", proposal_id)))?;
+
+        let voting_power = self
+            .voting_power(&address, proposal_id, proposal.voting_start_epoch)
+            .await?;
+        let statement_hash = Hash::sha256(serde_json::to_vec(&voting_power)?)
+            .to_string()
+            .to_lowercase();
+
+        let snapshot_key = governance_storage::get_voting_start_epoch_key(proposal_id);
+        let response = RPC
+            .shell()
+            .storage_value(&self.client, None, None, true, &snapshot_key)
+            .await?;
+        let snapshot_proof = response.proof.map(ProofOp::from_proof);
+
+        to_js_result(VotingPowerStatement {
+            voting_power,
+            statement_hash,
+            height: response.height.0,
+            snapshot_key: snapshot_key.to_string(),
+            snapshot_value: hex::encode(response.data),
+            snapshot_proof,
+        })
+    }
+
+    // Returns the client serving the height, the archival one if the RPC node pruned it
+    async fn client_at(&self, height: u64) -> Result<HttpClient, JsError> {
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        let cached = self.earliest_height.borrow().get(now);
+        let earliest = match cached {
+            Some(earliest) => earliest,
+            None => {
+                let earliest = self
+                    .client
+                    .status()
+                    .await?
+                    .sync_info
+                    .earliest_block_height
+                    .value();
+                self.earliest_height.borrow_mut().set(earliest, now);
+                earliest
+            }
+        };
+
+        let archival_client = self.archival_client.borrow().clone();
+        match archival::route(height, earliest, archival_client.is_some())? {
+            archival::Route::Primary => Ok(self.client.clone()),
+            archival::Route::Archival => Ok(archival_client.expect("archival client to be set")),
+        }
+    }
+
+    async fn voting_power(
+        &self,
+        address: &Address,
+        proposal_id: u64,
+        epoch: Epoch,
+    ) -> Result<VotingPower, JsError> {
+        let is_validator = is_validator(&self.client, address).await?;
+        let validator_power = if is_validator {
+            RPC.vp()
+                .pos()
+                .validator_stake(&self.client, address, &Some(epoch))
+                .await?
+                .unwrap_or_default()
+        } else {
+            token::Amount::zero()
+        };
+
+        let delegations: HashMap<Address, token::Amount> = RPC
+            .vp()
+            .pos()
+            .delegations(&self.client, address, &Some(epoch))
+            .await?;
+
+        let mut total = validator_power;
+        let mut delegated_power = vec![];
+        for (validator, amount) in delegations {
+            // The self-bond of a validator is already part of its stake
+            if &validator == address {
+                continue;
+            }
+            total = total
+                .checked_add(amount)
+                .ok_or_else(|| JsError::new("Amount overflow"))?;
+            delegated_power.push((validator.to_string(), StringAmount::native(amount)));
+        }
+
+        Ok(VotingPower {
+            address: address.to_string(),
+            proposal_id,
+            epoch: epoch.0,
+            is_validator,
+            validator_power: StringAmount::native(validator_power),
+            delegated_power,
+            total: StringAmount::native(total),
+        })
     }
 
-    /// Returns a list of all delegations for given addresses and epoch
+    /// Validates proposal JSON against the on-chain rules: required fields, content and code
+    /// size caps, epoch constraints and the minimum proposal fund.
+    /// Returns a list of violations, empty if the proposal is valid.
     ///
     /// # Arguments
     ///
-    /// * `addresses` - delegators addresses
-    /// * `epoch` - epoch in which we want to query delegations
-    pub async fn get_total_delegations(
+    /// * `json` - proposal JSON
+    /// * `proposal_type` - "default", "pgf_steward" or "pgf_payment"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON can't be parsed or the RPC call fails
+    pub async fn validate_proposal_json(
         &self,
-        addresses: Box<[JsValue]>,
-        epoch: Option<u64>,
+        json: String,
+        proposal_type: String,
     ) -> Result<JsValue, JsError> {
-        let addresses: Vec<Address> = addresses
-            .iter()
-            .filter_map(|address| address.as_string())
-            .filter_map(|address| Address::from_str(&address).ok())
-            .collect();
-
-        let epoch = epoch.map(Epoch);
+        let kind = ProposalKind::from_str(&proposal_type)?;
+        let proposal = parse_proposal(&json, kind)?;
 
-        let mut delegations: HashMap<Address, token::Amount> = HashMap::new();
-
-        for address in addresses.into_iter() {
-            let validators: HashMap<Address, token::Amount> = RPC
-                .vp()
-                .pos()
-                .delegations(&self.client, &address, &epoch)
-                .await?;
-            let sum_of_delegations = validators
-                .into_values()
-                .fold(token::Amount::zero(), |acc, curr| {
-                    acc.checked_add(curr).expect("Amount overflow")
-                });
+        let params = query_governance_parameters(&self.client).await;
+        let epoch = query_epoch(&self.client).await?;
+        let native_token = query_native_token(&self.client).await?;
+        let author = Address::from_str(&proposal.proposal().author)?;
+        let balance = get_token_balance(&self.client, &native_token, &author, None).await?;
 
-            delegations.insert(address, sum_of_delegations);
-        }
+        let errors: Vec<String> = validate_proposal(&proposal, &params, epoch.0, balance)
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect();
 
-        to_js_result(delegations)
+        to_js_result(errors)
     }
 
     /// Returns list of delegators that already voted on a proposal
@@ -751,10 +2138,10 @@ impl Query {
                 .await
                 .expect("Parameter should be defined.");
 
-        let mut result: Vec<(String, String)> = Vec::new();
+        let mut result: Vec<(String, StringAmount)> = Vec::new();
 
         for (token, gas_cost) in gas_cost_table {
-            result.push((token.to_string(), gas_cost.to_string_native()));
+            result.push((token.to_string(), StringAmount::native(gas_cost)));
         }
 
         to_js_result(result)
@@ -765,6 +2152,625 @@ impl Query {
         to_js_result(address)
     }
 
+    /// Gets a block at a given height, latest block if height is not provided
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - block height
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn query_block(&self, height: Option<u64>) -> Result<JsValue, JsError> {
+        let block = match height {
+            Some(height) => {
+                let client = self.client_at(height).await?;
+                archival::with_pruned_height(client.block(Height::try_from(height)?).await)?
+            }
+            None => self.client.latest_block().await?,
+        };
+
+        to_js_result(block)
+    }
+
+    /// Gets results of the block at a given height, latest block if height is not provided
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - block height
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn query_block_results(&self, height: Option<u64>) -> Result<JsValue, JsError> {
+        let block_results = match height {
+            Some(height) => {
+                let client = self.client_at(height).await?;
+                archival::with_pruned_height(client.block_results(Height::try_from(height)?).await)?
+            }
+            None => self.client.latest_block_results().await?,
+        };
+
+        to_js_result(block_results)
+    }
+
+    /// Searches for transactions matching CometBFT query, e.g. `tx.height > 100`
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - CometBFT event query
+    /// * `page` - page number, starting from 1
+    /// * `per_page` - number of transactions per page
+    /// * `order` - "asc" or "desc", defaults to "asc"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query is invalid or the RPC call fails
+    pub async fn query_tx_search(
+        &self,
+        query: String,
+        page: u32,
+        per_page: u8,
+        order: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        let query = TmQuery::from_str(&query)?;
+        let order = parse_order(order)?;
+
+        let txs = self
+            .client
+            .tx_search(query, false, page, per_page, order)
+            .await?;
+
+        to_js_result(txs)
+    }
+
+    /// Returns events matching the filter with their Namada attributes decoded, e.g.
+    /// `{ event_type: "tx/applied", attributes: [["code", "0"]], from_height: 100 }`
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - `EventFilter`, a filter with only `height` returns all events of the block
+    /// * `page` - page number of the tx_search, starting from 1
+    /// * `per_page` - number of txs per page
+    /// * `order` - "asc" or "desc", defaults to "asc"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filter is invalid or the RPC call fails
+    pub async fn query_events(
+        &self,
+        filter: JsValue,
+        page: u32,
+        per_page: u8,
+        order: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        let filter: EventFilter = filter.into_serde()?;
+        let to_events = |events: Vec<abci::Event>, height: u64, tx_hash: Option<String>| {
+            events
+                .into_iter()
+                .map(|event| {
+                    let attributes: BTreeMap<String, String> = event
+                        .attributes
+                        .iter()
+                        .filter_map(|attribute| {
+                            let key = attribute.key_str().ok()?;
+                            let value = attribute.value_str().ok()?;
+                            Some((key.to_string(), value.to_string()))
+                        })
+                        .collect();
+                    (event.kind, attributes, height, tx_hash.clone())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let events = if filter.is_block_query() {
+            let height = filter.height.unwrap_or_default();
+            let client = self.client_at(height).await?;
+            let results = archival::with_pruned_height(
+                client.block_results(Height::try_from(height)?).await,
+            )?;
+            let mut events = vec![];
+            for tx_result in results.txs_results.unwrap_or_default() {
+                events.extend(to_events(tx_result.events, height, None));
+            }
+            events.extend(to_events(results.finalize_block_events, height, None));
+            events.extend(to_events(
+                results.end_block_events.unwrap_or_default(),
+                height,
+                None,
+            ));
+            events
+        } else {
+            let query = TmQuery::from_str(&filter.to_query().map_err(|e| JsError::new(&e))?)?;
+            let txs = self
+                .client
+                .tx_search(query, false, page, per_page, parse_order(order)?)
+                .await?;
+            txs.txs
+                .into_iter()
+                .flat_map(|tx| {
+                    to_events(
+                        tx.tx_result.events,
+                        tx.height.value(),
+                        Some(tx.hash.to_string()),
+                    )
+                })
+                .collect()
+        };
+
+        let events: Vec<NamadaEvent> = events
+            .into_iter()
+            .filter(|(kind, attributes, _, _)| filter.matches(kind, attributes))
+            .map(|(kind, attributes, height, tx_hash)| {
+                decode_event(kind, attributes, height, tx_hash)
+            })
+            .collect();
+
+        to_js_result(events)
+    }
+
+    /// Explains why a tx failed, e.g. which VPs rejected it or whether it ran out of gas. The tx
+    /// is also dry-run at the height before it was included, so failures caused by the state the
+    /// tx was applied to can be told apart from ones caused by the tx itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - hash of the tx, as returned by broadcast
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx is not found or can't be decoded
+    pub async fn explain_failure(&self, tx_hash: String) -> Result<JsValue, JsError> {
+        let hash = namada_sdk::tendermint::Hash::from_str(&tx_hash.to_uppercase())?;
+        let response = self.client.tx(hash, false).await?;
+        let tx: Tx = borsh::from_slice(&response.tx)?;
+        let height = response.height.value();
+
+        let attributes: BTreeMap<String, String> = response
+            .tx_result
+            .events
+            .iter()
+            .filter(|event| event.kind == "tx/applied")
+            .flat_map(|event| event.attributes.iter())
+            .filter_map(|attribute| {
+                let key = attribute.key_str().ok()?;
+                let value = attribute.value_str().ok()?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect();
+        let inner_txs = attributes
+            .get("batch")
+            .and_then(|batch| serde_json::from_str(batch).ok())
+            .map(|batch| failure::inner_tx_results(&batch))
+            .unwrap_or_default();
+        let gas_used = attributes
+            .get("gas_used")
+            .and_then(|gas_used| gas_used.parse().ok())
+            .unwrap_or_else(|| u64::try_from(response.tx_result.gas_used).unwrap_or_default());
+        let gas_limit = tx
+            .header
+            .wrapper()
+            .map(|wrapper| u64::from(wrapper.gas_limit));
+        let code = attributes
+            .get("code")
+            .and_then(|code| code.parse().ok())
+            .unwrap_or_else(|| response.tx_result.code.value());
+        let invalid_sections = failure::missing_sections(&tx);
+
+        // Nodes without the state of past heights can't dry-run, the explanation is still useful
+        // without it
+        let pre_state = match height.checked_sub(1).filter(|height| *height > 0) {
+            Some(pre_state_height) => self
+                .client_at(pre_state_height)
+                .await
+                .ok()
+                .map(|client| (client, pre_state_height)),
+            None => None,
+        };
+        let dry_run = match pre_state {
+            Some((client, pre_state_height)) => RPC
+                .shell()
+                .dry_run_tx(
+                    &client,
+                    Some(response.tx.clone()),
+                    Some(BlockHeight(pre_state_height)),
+                    false,
+                )
+                .await
+                .ok()
+                .and_then(|result| serde_json::to_value(result.data).ok())
+                .map(|result| failure::inner_tx_results(&result)),
+            None => None,
+        };
+
+        let (reason, message) =
+            failure::explain(code, gas_used, gas_limit, &inner_txs, &invalid_sections);
+
+        to_js_result(failure::FailureExplanation {
+            hash: tx_hash,
+            height,
+            code,
+            reason: reason.to_string(),
+            message,
+            gas_used,
+            gas_limit,
+            inner_txs,
+            invalid_sections,
+            dry_run,
+            log: response.tx_result.log,
+        })
+    }
+
+    /// Returns a paginator over transactions matching CometBFT query
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - CometBFT event query
+    /// * `per_page` - number of transactions per page
+    /// * `order` - "asc" or "desc", defaults to "asc"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query or order is invalid or `per_page` is 0
+    pub fn paginate_tx_search(
+        &self,
+        query: String,
+        per_page: u8,
+        order: Option<String>,
+    ) -> Result<Paginator, JsError> {
+        let query = TmQuery::from_str(&query)?;
+        let order = parse_order(order)?;
+
+        Paginator::new(
+            self.client.clone(),
+            PageSource::TxSearch { query, order },
+            per_page,
+        )
+    }
+
+    /// Returns a paginator over the validator set at a given height,
+    /// latest block if height is not provided
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - block height
+    /// * `per_page` - number of validators per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails or `per_page` is 0
+    pub async fn paginate_validators(
+        &self,
+        height: Option<u64>,
+        per_page: u8,
+    ) -> Result<Paginator, JsError> {
+        let (client, height) = match height {
+            Some(height) => (self.client_at(height).await?, Height::try_from(height)?),
+            None => (
+                self.client.clone(),
+                self.client.latest_block().await?.block.header.height,
+            ),
+        };
+
+        Paginator::new(client, PageSource::Validators { height }, per_page)
+    }
+
+    /// Returns a paginator over all proposals, ordered by id
+    ///
+    /// # Arguments
+    ///
+    /// * `per_page` - number of proposals per page
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails or `per_page` is 0
+    pub async fn paginate_proposals(&self, per_page: u8) -> Result<Paginator, JsError> {
+        let proposal_counter_key = governance_storage::get_counter_key();
+        let count =
+            query_storage_value::<HttpClient, u64>(&self.client, &proposal_counter_key).await?;
+
+        Paginator::new(
+            self.client.clone(),
+            PageSource::Proposals { count },
+            per_page,
+        )
+    }
+
+    /// Gets the validator set at a given height, latest block if height is not provided
+    ///
+    /// # Arguments
+    ///
+    /// * `height` - block height
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn query_validators(&self, height: Option<u64>) -> Result<JsValue, JsError> {
+        let (client, height) = match height {
+            Some(height) => (self.client_at(height).await?, Height::try_from(height)?),
+            None => (
+                self.client.clone(),
+                self.client.latest_block().await?.block.header.height,
+            ),
+        };
+        let validators =
+            archival::with_pruned_height(client.validators(height, Paging::All).await)?;
+
+        to_js_result(validators)
+    }
+
+    /// Gets protocol parameters snapshot, cached until the epoch changes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn query_protocol_parameters(&self) -> Result<JsValue, JsError> {
+        let parameters = self.protocol_parameters().await?;
+
+        to_js_result(parameters)
+    }
+
+    /// Returns the epoch in which tokens unbonded at `unbond_epoch` become withdrawable
+    ///
+    /// # Arguments
+    ///
+    /// * `unbond_epoch` - epoch in which the unbond was submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn compute_withdraw_epoch(&self, unbond_epoch: u64) -> Result<u64, JsError> {
+        let parameters = self.protocol_parameters().await?;
+
+        Ok(staking::withdraw_epoch(&parameters, unbond_epoch))
+    }
+
+    /// Returns the estimated unix timestamp(in seconds) at which tokens unbonded at
+    /// `unbond_epoch` become withdrawable
+    ///
+    /// # Arguments
+    ///
+    /// * `unbond_epoch` - epoch in which the unbond was submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn compute_withdraw_time(&self, unbond_epoch: u64) -> Result<u64, JsError> {
+        let parameters = self.protocol_parameters().await?;
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+
+        Ok(staking::withdraw_time(&parameters, unbond_epoch, now))
+    }
+
+    /// Returns native tokens of an address split into spendable and locked funds, with the
+    /// epochs in which unbonded tokens unlock. Builders should not offer more than `spendable`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - owner address
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is invalid or the RPC call fails
+    pub async fn query_vesting_schedule(&self, address: String) -> Result<JsValue, JsError> {
+        let owner = Address::from_str(&address)?;
+        let native_token = query_native_token(&self.client).await?;
+        let spendable = get_token_balance(&self.client, &native_token, &owner, None).await?;
+        let parameters = self.protocol_parameters().await?;
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+
+        let enriched = RPC
+            .vp()
+            .pos()
+            .enriched_bonds_and_unbonds(&self.client, Epoch(parameters.epoch), &Some(owner), &None)
+            .await?;
+
+        let net = |amount: token::Amount, slashed: token::Amount| {
+            amount.checked_sub(slashed).unwrap_or_default()
+        };
+        let bonded = net(enriched.bonds_total, enriched.bonds_total_slashed);
+        let unbonded = net(enriched.unbonds_total, enriched.unbonds_total_slashed);
+        let unbonding = net(unbonded, enriched.total_withdrawable);
+        let slashed = enriched
+            .bonds_total_slashed
+            .checked_add(enriched.unbonds_total_slashed)
+            .unwrap_or_default();
+        let locked = bonded.checked_add(unbonded).unwrap_or_default();
+
+        let mut unlocks: Vec<TokenUnlock> = enriched
+            .data
+            .iter()
+            .flat_map(|(bond_id, details)| {
+                details
+                    .data
+                    .unbonds
+                    .iter()
+                    .map(move |unbond| (bond_id, unbond))
+            })
+            .filter(|(_, unbond)| unbond.withdraw.0 > parameters.epoch)
+            .map(|(bond_id, unbond)| TokenUnlock {
+                validator: bond_id.validator.to_string(),
+                amount: StringAmount::native(net(
+                    unbond.amount,
+                    unbond.slashed_amount.unwrap_or_default(),
+                )),
+                epoch: unbond.withdraw.0,
+                time: staking::epoch_time(&parameters, unbond.withdraw.0, now),
+            })
+            .collect();
+        unlocks.sort_by_key(|unlock| unlock.epoch);
+
+        to_js_result(VestingSchedule {
+            address,
+            spendable: StringAmount::native(spendable),
+            locked: StringAmount::native(locked),
+            bonded: StringAmount::native(bonded),
+            unbonding: StringAmount::native(unbonding),
+            withdrawable: StringAmount::native(enriched.total_withdrawable),
+            slashed: StringAmount::native(slashed),
+            unlocks,
+        })
+    }
+
+    /// Estimates the return of staking `amount` with `validator`, based on the current
+    /// staking rewards rate and the validator commission
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - amount of native tokens to stake
+    /// * `validator` - validator address
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments are invalid or the RPC call fails
+    pub async fn estimate_staking_return(
+        &self,
+        amount: String,
+        validator: String,
+    ) -> Result<JsValue, JsError> {
+        let amount = Dec::from_str(&amount)?;
+        let validator = Address::from_str(&validator)?;
+
+        let parameters = self.protocol_parameters().await?;
+        let rates = get_staking_rewards_rate(&self.client).await?;
+        let commission_rate = query_commission_rate(&self.client, &validator, None)
+            .await?
+            .commission_rate
+            .unwrap_or_default();
+
+        let (apr, reward_per_epoch) = staking::staking_return(
+            amount,
+            rates.staking_rewards_rate,
+            commission_rate,
+            parameters.epochs_per_year,
+        )
+        .ok_or_else(|| JsError::new("Overflow when estimating staking return"))?;
+
+        to_js_result(StakingReturn {
+            validator: validator.to_string(),
+            staking_rewards_rate: rates.staking_rewards_rate.to_string(),
+            inflation_rate: rates.inflation_rate.to_string(),
+            commission_rate: commission_rate.to_string(),
+            apr: apr.to_string(),
+            reward_per_epoch: StringAmount::new(reward_per_epoch.to_string()),
+        })
+    }
+
+    /// Checks the validator before bonding `amount` to it. Returns a `BondPreflight` with
+    /// warnings if the validator is jailed or inactive, is about to raise its commission by
+    /// close to the max change per epoch, or would hold too large a share of the total stake.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - validator address
+    /// * `amount` - amount of native tokens to bond
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is not a validator or the RPC call fails
+    pub async fn bond_preflight(
+        &self,
+        validator: String,
+        amount: String,
+    ) -> Result<JsValue, JsError> {
+        let validator = Address::from_str(&validator)?;
+        let amount = StringAmount::new(amount).to_amount(token::NATIVE_MAX_DECIMAL_PLACES)?;
+        if !is_validator(&self.client, &validator).await? {
+            return Err(JsError::new(&format!("{} is not a validator", validator)));
+        }
+
+        let parameters = self.protocol_parameters().await?;
+        let epoch = Epoch(parameters.epoch);
+        let pipeline_epoch = Epoch(parameters.epoch + parameters.pipeline_len);
+
+        let (state, _) = get_validator_state(&self.client, &validator, None).await?;
+        let commission = query_commission_rate(&self.client, &validator, None).await?;
+        let pipeline_commission =
+            query_commission_rate(&self.client, &validator, Some(pipeline_epoch)).await?;
+        let stake = get_validator_stake(&self.client, epoch, &validator).await?;
+        let total_stake = get_total_staked_tokens(&self.client, epoch).await?;
+
+        let commission_rate = commission.commission_rate.unwrap_or_default();
+        let risk = staking::ValidatorRisk {
+            state,
+            commission_rate,
+            pipeline_commission_rate: pipeline_commission
+                .commission_rate
+                .unwrap_or(commission_rate),
+            max_commission_change_per_epoch: commission
+                .max_commission_change_per_epoch
+                .unwrap_or_default(),
+            stake,
+            total_stake,
+        };
+        let warnings = staking::bond_warnings(&risk, amount)
+            .ok_or_else(|| JsError::new("Overflow when checking the validator"))?;
+
+        to_js_result(BondPreflight {
+            validator: validator.to_string(),
+            requires_confirmation: !warnings.is_empty(),
+            warnings,
+        })
+    }
+
+    pub(crate) async fn protocol_parameters(&self) -> Result<ProtocolParameters, JsError> {
+        let epoch = query_epoch(&self.client).await?;
+
+        if let Some(parameters) = self.protocol_parameters.borrow().as_ref() {
+            if parameters.epoch == epoch.0 {
+                return Ok(parameters.clone());
+            }
+        }
+
+        let epochs_per_year = query_storage_value::<HttpClient, u64>(
+            &self.client,
+            &storage::get_epochs_per_year_key(),
+        )
+        .await?;
+        let epoch_duration = query_storage_value::<HttpClient, EpochDuration>(
+            &self.client,
+            &storage::get_epoch_duration_storage_key(),
+        )
+        .await?;
+        let max_block_gas =
+            query_storage_value::<HttpClient, u64>(&self.client, &storage::get_max_block_gas_key())
+                .await?;
+        let masp_epoch_multiplier = query_storage_value::<HttpClient, u64>(
+            &self.client,
+            &storage::get_masp_epoch_multiplier_key(),
+        )
+        .await?;
+        let gas_cost = query_storage_value::<HttpClient, BTreeMap<Address, token::Amount>>(
+            &self.client,
+            &storage::get_gas_cost_key(),
+        )
+        .await?
+        .into_iter()
+        .map(|(token, gas_cost)| (token.to_string(), StringAmount::native(gas_cost)))
+        .collect();
+        let pos_params = query_pos_parameters(&self.client).await?;
+
+        let parameters = ProtocolParameters {
+            epoch: epoch.0,
+            epochs_per_year,
+            min_epoch_duration: epoch_duration.min_duration.0,
+            max_block_gas,
+            masp_epoch_multiplier,
+            gas_cost,
+            pipeline_len: pos_params.owned.pipeline_len,
+            unbonding_len: pos_params.owned.unbonding_len,
+            cubic_slashing_window_length: pos_params.owned.cubic_slashing_window_length,
+            max_validator_slots: pos_params.owned.max_validator_slots,
+            max_inflation_rate: pos_params.owned.max_inflation_rate.to_string(),
+            target_staked_ratio: pos_params.owned.target_staked_ratio.to_string(),
+            max_proposal_period: pos_params.max_proposal_period,
+        };
+
+        *self.protocol_parameters.borrow_mut() = Some(parameters.clone());
+
+        Ok(parameters)
+    }
+
     // Vec of code paths of supported transactions
     pub fn code_paths() -> Vec<String> {
         vec![
@@ -777,39 +2783,120 @@ impl Query {
             TX_REVEAL_PK.to_string(),
             TX_VOTE_PROPOSAL.to_string(),
             TX_IBC_WASM.to_string(),
+            TX_BRIDGE_POOL_WASM.to_string(),
         ]
     }
 
     // Query supported wasm code-paths, and return a serialized vec of object containing path and hash
     pub async fn query_wasm_hashes(&self) -> Result<JsValue, JsError> {
-        let mut results: Vec<WasmHash> = vec![];
-        let code_paths = Query::code_paths();
+        let results = query_wasm_hashes(&self.client).await?;
 
-        for path in code_paths {
-            let hash = self.query_wasm_hash(&path).await;
+        to_js_result(results)
+    }
 
-            if hash.is_some() {
-                let wasm_hash = WasmHash::new(path, hash.unwrap());
-                results.push(wasm_hash);
-            }
-        }
+    // Query hash of wasm code on chain
+    pub async fn query_wasm_hash(&self, tx_code_path: &str) -> Result<Option<String>, JsError> {
+        query_wasm_hash(&self.client, tx_code_path).await
+    }
+}
 
-        Ok(JsValue::from_serde(&results).unwrap())
+/// Maps "asc" or "desc" to tx_search Order, defaults to ascending
+fn parse_order(order: Option<String>) -> Result<Order, JsError> {
+    match order.as_deref() {
+        None | Some("asc") => Ok(Order::Ascending),
+        Some("desc") => Ok(Order::Descending),
+        Some(order) => Err(JsError::new(&format!("Invalid order: {}", order))),
     }
+}
 
-    // Query hash of wasm code on chain
-    pub async fn query_wasm_hash(&self, tx_code_path: &str) -> Option<String> {
-        let hash_key = Key::wasm_hash(tx_code_path);
-        let (tx_code_res, _) = rpc::query_storage_value_bytes(&self.client, &hash_key, None, false)
-            .await
-            .ok()?;
-        if let Some(tx_code_bytes) = tx_code_res {
-            let tx_code = Hash::try_from(&tx_code_bytes[..]).expect("Invalid code hash");
-            Some(tx_code.to_string())
-        } else {
-            None
+/// Queries hashes of the wasm codes of supported transactions, code paths without a hash in
+/// storage are left out
+///
+/// # Errors
+///
+/// Returns an error if an RPC call fails or a stored hash is invalid
+pub async fn query_wasm_hashes(client: &HttpClient) -> Result<Vec<WasmHash>, JsError> {
+    let mut results: Vec<WasmHash> = vec![];
+    let code_paths = Query::code_paths();
+
+    for path in code_paths {
+        if let Some(hash) = query_wasm_hash(client, &path).await? {
+            results.push(WasmHash::new(path, hash));
         }
     }
+
+    Ok(results)
+}
+
+// Returns None if the code path has no hash in storage
+async fn query_wasm_hash(
+    client: &HttpClient,
+    tx_code_path: &str,
+) -> Result<Option<String>, JsError> {
+    let hash_key = Key::wasm_hash(tx_code_path);
+    let (tx_code_res, _) = rpc::query_storage_value_bytes(client, &hash_key, None, false).await?;
+
+    tx_code_res
+        .map(|tx_code_bytes| {
+            Hash::try_from(&tx_code_bytes[..])
+                .map(|tx_code| tx_code.to_string())
+                .map_err(|err| {
+                    JsError::new(&format!("Invalid code hash of {}: {}", tx_code_path, err))
+                })
+        })
+        .transpose()
+}
+
+/// Queries proposal by id and maps it to the serializable ProposalInfo
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `id` - proposal id
+///
+/// # Errors
+///
+/// Returns an error if the RPC call fails or the proposal does not exist
+pub async fn query_proposal_info(client: &HttpClient, id: u64) -> Result<ProposalInfo, JsError> {
+    let proposal = query_proposal_by_id(client, id)
+        .await?
+        .ok_or_else(|| JsError::new(&format!("Proposal {} is not written to storage.", id)))?;
+
+    let content = serde_json::to_string(&proposal.content)?;
+
+    let is_steward = is_steward(client, &proposal.author).await;
+    let tally_type = proposal.get_tally_type(is_steward);
+    let tally_type_string = match tally_type {
+        // TODO: Change in interface
+        TallyType::TwoFifths => "two-fifths",
+        TallyType::OneHalfOverOneThird => "one-half-over-one-third",
+        TallyType::LessOneHalfOverOneThirdNay => "less-one-half-over-one-third-nay",
+    };
+
+    let (proposal_type, data) = match proposal.r#type {
+        ProposalType::Default => ("default", None),
+        ProposalType::DefaultWithWasm(hash) => ("default", Some(hash.to_string())),
+        ProposalType::PGFSteward(data) => {
+            let data_string = serde_json::to_string(&data)?;
+            ("pgf_steward", Some(data_string))
+        }
+        ProposalType::PGFPayment(data) => {
+            let data_string = serde_json::to_string(&data)?;
+            ("pgf_payment", Some(data_string))
+        }
+    };
+
+    Ok(ProposalInfo {
+        id: proposal.id,
+        author: proposal.author.to_string(),
+        start_epoch: proposal.voting_start_epoch.0,
+        end_epoch: proposal.voting_end_epoch.0,
+        grace_epoch: proposal.activation_epoch.0,
+        content,
+        tally_type: String::from(tally_type_string),
+        proposal_type: String::from(proposal_type),
+        data,
+    })
 }
 
 //TODO: remove after moving this fn from apps to shared
@@ -861,3 +2948,43 @@ pub async fn compute_proposal_votes(
         delegator_voting_power,
     }
 }
+
+/// Computes the shielded balance of the viewing key from the stored shielded context
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `viewing_key` - viewing key to compute the balance of
+/// * `tokens` - tokens whose asset types are precomputed for decoding
+pub(crate) async fn shielded_balance(
+    client: &HttpClient,
+    viewing_key: &ViewingKey,
+    tokens: &[Address],
+) -> Result<Vec<(Address, token::Amount)>, JsError> {
+    // We are recreating shielded context to avoid multiple mutable borrows
+    let mut shielded: ShieldedContext<JSShieldedUtils> = ShieldedContext::default();
+    shielded.load().await?;
+    shielded
+        .precompute_asset_types(client, tokens.iter().collect())
+        .await
+        .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+
+    let epoch = query_masp_epoch(client).await?;
+    let balance = shielded
+        .compute_exchanged_balance(client, &WebIo, viewing_key, epoch)
+        .await
+        .map_err(|e| JsError::new(&format!("{:?}", e)))?;
+
+    let res = match balance {
+        Some(balance) => {
+            let decoded_balance = shielded
+                .decode_combine_sum_to_epoch(client, balance, epoch)
+                .await;
+
+            Query::get_decoded_balance(decoded_balance)
+        }
+        None => vec![],
+    };
+
+    Ok(res)
+}
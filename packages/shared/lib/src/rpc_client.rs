@@ -1,7 +1,14 @@
+use gloo_utils::format::JsValueSerdeExt;
 use js_sys::JSON::stringify;
 use namada_sdk::storage::BlockHeight;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
-use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
 use thiserror::Error;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -18,21 +25,39 @@ use namada_sdk::tendermint_rpc::{
 #[wasm_bindgen(module = "/src/rpc_client.js")]
 extern "C" {
     #[wasm_bindgen(catch, js_name = "wasmFetch")]
-    async fn wasmFetch(url: JsValue, method: JsValue, body: JsValue) -> Result<JsValue, JsValue>;
+    async fn wasmFetch(
+        url: JsValue,
+        method: JsValue,
+        body: JsValue,
+        headers: JsValue,
+        transport: JsValue,
+        timeout_ms: JsValue,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = "wasmSleep")]
+    fn wasmSleep(ms: u32) -> js_sys::Promise;
 }
 
+/// Timeout of RPC requests unless set with `HttpClient::set_timeout`
+pub const DEFAULT_TIMEOUT_MS: u32 = 30_000;
+// Name of the error wasmFetch rejects with when a request times out
+const TIMEOUT_ERROR_NAME: &str = "TimeoutError";
+
 #[derive(Clone, Error, Debug)]
-pub struct RpcError(String);
+pub enum RpcError {
+    #[error("{0}")]
+    Other(String),
+    #[error("RPC request timed out after {0} ms")]
+    Timeout(u64),
+}
 
 impl RpcError {
     pub fn new(msg: &str) -> Self {
-        RpcError(String::from(msg))
+        RpcError::Other(String::from(msg))
     }
-}
 
-impl Display for RpcError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.0, f)
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, RpcError::Timeout(_))
     }
 }
 
@@ -50,34 +75,239 @@ impl From<namada_sdk::tendermint::Error> for RpcError {
 
 impl From<namada_sdk::tendermint_rpc::Error> for RpcError {
     fn from(error: namada_sdk::tendermint_rpc::Error) -> Self {
-        RpcError::new(&error.to_string())
+        match error.detail() {
+            namada_sdk::tendermint_rpc::error::ErrorDetail::Timeout(timeout) => {
+                RpcError::Timeout(timeout.duration.as_millis() as u64)
+            }
+            _ => RpcError::new(&error.to_string()),
+        }
+    }
+}
+
+/// Additional HTTP headers sent with every request, e.g. API keys required by RPC providers
+pub type Headers = BTreeMap<String, String>;
+
+/// Name of the header used to pass bearer tokens
+const AUTHORIZATION_HEADER: &str = "Authorization";
+
+/// Deserializes headers passed from JS as a plain `{ [name]: value }` object
+///
+/// # Arguments
+///
+/// * `headers` - JS object mapping header names to values
+///
+/// # Errors
+///
+/// Returns an error if `headers` is not an object of strings
+pub fn headers_from_js(headers: JsValue) -> Result<Headers, JsError> {
+    if headers.is_undefined() || headers.is_null() {
+        return Ok(Headers::new());
     }
+
+    headers
+        .into_serde()
+        .map_err(|e| JsError::new(&format!("Invalid headers: {}", e)))
+}
+
+thread_local! {
+    // JS functions are not Send, so HttpClient only keeps the id of the registered transport
+    static TRANSPORTS: RefCell<BTreeMap<u32, js_sys::Function>> = RefCell::new(BTreeMap::new());
+    static NEXT_TRANSPORT_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Id of a registered transport, the transport is removed once the last clone of the client
+/// holding it is dropped
+#[derive(Debug)]
+struct TransportHandle(u32);
+
+impl Drop for TransportHandle {
+    fn drop(&mut self) {
+        // Fails only while the thread exits, when TRANSPORTS was destroyed with the transport
+        TRANSPORTS
+            .try_with(|transports| transports.borrow_mut().remove(&self.0))
+            .ok();
+    }
+}
+
+/// Registers a `fetch`-like function and returns the handle of its id
+fn register_transport(transport: js_sys::Function) -> TransportHandle {
+    let id = NEXT_TRANSPORT_ID.with(|next| {
+        let id = next.get();
+        next.set(id.wrapping_add(1));
+        id
+    });
+    TRANSPORTS.with(|transports| transports.borrow_mut().insert(id, transport));
+
+    TransportHandle(id)
 }
 
 #[derive(Clone)]
 pub struct HttpClient {
     url: String,
+    headers: Headers,
+    // Shared between the clones of the client, the transport is registered until all are dropped
+    transport: Option<Arc<TransportHandle>>,
+    // Shared between the clones of the client, so the timeout can be changed after the client
+    // was handed to the Sdk. 0 disables the timeout.
+    timeout_ms: Arc<AtomicU32>,
 }
 
 /// HttpClient implementation using `window.fetch` API.
 impl HttpClient {
     pub fn new(url: String) -> HttpClient {
-        HttpClient { url }
+        HttpClient {
+            url,
+            headers: Headers::new(),
+            transport: None,
+            timeout_ms: Arc::new(AtomicU32::new(DEFAULT_TIMEOUT_MS)),
+        }
+    }
+
+    /// Creates a client that sends `headers` with every request.
+    /// If `auth_token` is provided, it is sent as a bearer token in the `Authorization` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - RPC url
+    /// * `headers` - headers applied to all requests
+    /// * `auth_token` - optional bearer token
+    pub fn new_with_headers(
+        url: String,
+        headers: Headers,
+        auth_token: Option<String>,
+    ) -> HttpClient {
+        let mut headers = headers;
+        if let Some(token) = auth_token {
+            headers.insert(
+                String::from(AUTHORIZATION_HEADER),
+                format!("Bearer {}", token),
+            );
+        }
+
+        HttpClient {
+            url,
+            headers,
+            transport: None,
+            timeout_ms: Arc::new(AtomicU32::new(DEFAULT_TIMEOUT_MS)),
+        }
+    }
+
+    /// Routes all requests through `transport` instead of the global `fetch`.
+    /// The function is called as `transport(url, { method, body, headers })` and has to resolve
+    /// to an object with a `json()` method, like `Response`. Useful when the RPC can't be reached
+    /// directly, e.g. from MV3 content scripts which have to proxy through the background script.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - `fetch`-like function
+    pub fn with_transport(mut self, transport: js_sys::Function) -> HttpClient {
+        self.transport = Some(Arc::new(register_transport(transport)));
+        self
+    }
+
+    /// Returns a copy of the client with `headers` merged over the default ones.
+    /// Used to override headers for a single request.
+    pub fn with_headers(&self, headers: Headers) -> HttpClient {
+        let mut client = self.clone();
+        client.headers.extend(headers);
+
+        client
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Sets the time after which requests are aborted and fail with `RpcError::Timeout`, also for
+    /// the clones of the client
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_ms` - timeout in milliseconds, 0 disables it
+    pub fn set_timeout(&self, timeout_ms: u32) {
+        self.timeout_ms.store(timeout_ms, Ordering::Relaxed);
+    }
+
+    pub fn timeout(&self) -> u32 {
+        self.timeout_ms.load(Ordering::Relaxed)
+    }
+
+    /// Returns the timeout shared by the clones of the client, for other clients and requests
+    /// which follow the timeout set with `set_timeout`
+    pub fn timeout_handle(&self) -> Arc<AtomicU32> {
+        self.timeout_ms.clone()
+    }
+
+    /// Makes the client follow the timeout of another one, e.g. of the primary RPC
+    pub fn with_timeout_handle(mut self, timeout_ms: Arc<AtomicU32>) -> HttpClient {
+        self.timeout_ms = timeout_ms;
+        self
     }
 
     async fn fetch(&self, url: &str, method: &str, body: &str) -> Result<JsValue, JsValue> {
+        let headers = JsValue::from_serde(&self.headers)
+            .map_err(|e| JsValue::from_str(&format!("Invalid headers: {}", e)))?;
+
+        let transport = self
+            .transport
+            .as_ref()
+            .and_then(|handle| {
+                TRANSPORTS.with(|transports| transports.borrow().get(&handle.0).cloned())
+            })
+            .map(JsValue::from)
+            .unwrap_or(JsValue::UNDEFINED);
+
         let resp_value = wasmFetch(
             JsValue::from_str(url),
             JsValue::from_str(method),
             JsValue::from_str(body),
+            headers,
+            transport,
+            JsValue::from(self.timeout()),
         )
         .await?;
 
-        let resp: Response = resp_value.dyn_into()?;
+        // Custom transports might not return a real Response, we only rely on json() being there
+        let resp: Response = resp_value.unchecked_into();
         JsFuture::from(resp.json().unwrap()).await
     }
 }
 
+/// Runs a request which doesn't go through `wasmFetch`, e.g. of reqwest, and fails with
+/// `RpcError::Timeout` if it doesn't finish in time. The request is dropped then.
+///
+/// # Arguments
+///
+/// * `request` - future of the request
+/// * `timeout_ms` - timeout in milliseconds, 0 disables it
+pub async fn with_timeout<F: Future>(request: F, timeout_ms: u32) -> Result<F::Output, RpcError> {
+    if timeout_ms == 0 {
+        return Ok(request.await);
+    }
+
+    let mut request = std::pin::pin!(request);
+    let mut timer = JsFuture::from(wasmSleep(timeout_ms));
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = request.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        std::pin::Pin::new(&mut timer)
+            .poll(cx)
+            .map(|_| Err(RpcError::Timeout(u64::from(timeout_ms))))
+    })
+    .await
+}
+
+fn is_timeout_error(error: &JsValue) -> bool {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map_or(false, |error| error.name() == TIMEOUT_ERROR_NAME)
+}
+
 #[async_trait::async_trait(?Send)]
 impl Client for HttpClient {
     /// Implementation of the `Client` trait for the `HttpClient` struct.
@@ -141,6 +371,11 @@ impl Client for HttpClient {
             .fetch(&self.url[..], "POST", &request_body)
             .await
             .map_err(|e| {
+                if is_timeout_error(&e) {
+                    return TendermintRpcError::timeout(Duration::from_millis(u64::from(
+                        self.timeout(),
+                    )));
+                }
                 let e = stringify(&e).expect("Error to be serializable");
                 let e_str: String = e.into();
                 // There is no "generic" RpcError, so we have to pick
@@ -1,12 +1,78 @@
 /* eslint-disable @typescript-eslint/explicit-function-return-type */
+type Transport = (
+  url: string,
+  init: {
+    method: string;
+    body: string;
+    headers: Record<string, string>;
+    signal?: AbortSignal;
+  }
+) => Promise<{ json: () => Promise<unknown> }>;
+
+/**
+ * Error the request rejects with when it doesn't finish in time, checked by name in wasm
+ */
+class TimeoutError extends Error {
+  name = "TimeoutError";
+
+  constructor(timeoutMs: number) {
+    super(`RPC request timed out after ${timeoutMs} ms`);
+  }
+}
+
 /**
  * Small wrapper for fetch to make it easier to pass props
  * Called wasmFetch to avoid naming conflict
+ * If transport is provided, it is used instead of the global fetch
+ * If timeoutMs is set, the request and reading its body are aborted after it,
+ * so a hanging RPC can't block the caller forever
  */
-export async function wasmFetch(url: string, method: string, body: string) {
-  const res = await fetch(url, {
-    method,
-    body,
+export async function wasmFetch(
+  url: string,
+  method: string,
+  body: string,
+  headers: Record<string, string>,
+  transport?: Transport,
+  timeoutMs?: number
+) {
+  const fetchFn: Transport = transport ?? fetch;
+  if (!timeoutMs) {
+    const res = await fetchFn(url, { method, body, headers });
+    return res;
+  }
+
+  const controller = new AbortController();
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const timeout = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => {
+      // Rejected before aborting, so the race settles with the TimeoutError and
+      // not the AbortError of the request. The reason covers transports which
+      // reject with signal.reason.
+      const error = new TimeoutError(timeoutMs);
+      reject(error);
+      controller.abort(error);
+    }, timeoutMs);
   });
-  return res;
+
+  try {
+    const res = await Promise.race([
+      fetchFn(url, { method, body, headers, signal: controller.signal }),
+      timeout,
+    ]);
+    const json = await Promise.race([res.json(), timeout]);
+    return { json: () => Promise.resolve(json) };
+  } catch (error) {
+    // Transports rejecting with their own AbortError still time out
+    throw controller.signal.aborted ? controller.signal.reason : error;
+  } finally {
+    clearTimeout(timer);
+  }
+}
+
+/**
+ * Resolves after ms, used to time out requests which don't go through
+ * wasmFetch
+ */
+export function wasmSleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
 }
@@ -0,0 +1,233 @@
+//! Background sync scheduler - runs shielded sync, balance refresh and header sync one at a time
+//! at their own intervals, so the extension background worker has a single loop to start instead
+//! of several timers competing for the RPC. Failing tasks are retried with exponential backoff
+//! and nothing runs while the document is hidden.
+use gloo_utils::format::JsValueSerdeExt;
+use serde::Deserialize;
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::utils::sleep;
+use crate::watcher::WatcherHandle;
+
+// Longest time the loop sleeps, so stopping and visibility changes are noticed quickly
+const MAX_SLEEP_MS: u32 = 1000;
+
+#[derive(Debug, Deserialize, Tsify)]
+#[serde(default)]
+pub struct SyncOptions {
+    pub shielded_interval_ms: u32,
+    pub balance_interval_ms: u32,
+    pub header_interval_ms: u32,
+    // Delay after the first failure, doubled on every further failure
+    pub backoff_ms: u32,
+    pub max_backoff_ms: u32,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            shielded_interval_ms: 60_000,
+            balance_interval_ms: 15_000,
+            header_interval_ms: 5_000,
+            backoff_ms: 5_000,
+            max_backoff_ms: 300_000,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TaskState {
+    interval_ms: u32,
+    // Time in milliseconds the task is due at
+    due_at: f64,
+    failures: u32,
+}
+
+/// Decides which task runs next. Tasks never overlap, the most overdue one runs first.
+#[derive(Debug)]
+pub struct Schedule {
+    tasks: Vec<TaskState>,
+    backoff_ms: u32,
+    max_backoff_ms: u32,
+}
+
+impl Schedule {
+    /// Creates a schedule with all tasks due at `now`
+    pub fn new(intervals_ms: &[u32], backoff_ms: u32, max_backoff_ms: u32, now: f64) -> Schedule {
+        let tasks = intervals_ms
+            .iter()
+            .map(|interval_ms| TaskState {
+                interval_ms: *interval_ms,
+                due_at: now,
+                failures: 0,
+            })
+            .collect();
+
+        Schedule {
+            tasks,
+            backoff_ms,
+            max_backoff_ms,
+        }
+    }
+
+    /// Returns the index of the most overdue task, if any is due
+    pub fn next_due(&self, now: f64) -> Option<usize> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.due_at <= now)
+            .min_by(|(_, a), (_, b)| a.due_at.total_cmp(&b.due_at))
+            .map(|(index, _)| index)
+    }
+
+    /// Schedules the next run of the task, backing off if it failed
+    pub fn complete(&mut self, index: usize, succeeded: bool, now: f64) {
+        let task = &mut self.tasks[index];
+        let delay_ms = if succeeded {
+            task.failures = 0;
+            task.interval_ms
+        } else {
+            task.failures += 1;
+            let backoff_ms = self
+                .backoff_ms
+                .saturating_mul(2u32.saturating_pow(task.failures - 1));
+            backoff_ms.min(self.max_backoff_ms)
+        };
+        task.due_at = now + f64::from(delay_ms);
+    }
+
+    /// Returns how long to wait until the next task is due
+    pub fn wait_ms(&self, now: f64) -> u32 {
+        self.tasks
+            .iter()
+            .map(|task| (task.due_at - now).max(0.0))
+            .min_by(f64::total_cmp)
+            .map_or(MAX_SLEEP_MS, |wait_ms| (wait_ms as u32).min(MAX_SLEEP_MS))
+    }
+}
+
+// Workers have no document, so they are never considered hidden
+fn document_hidden() -> bool {
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("document"))
+        .ok()
+        .filter(|document| document.is_object())
+        .and_then(|document| js_sys::Reflect::get(&document, &JsValue::from_str("hidden")).ok())
+        .and_then(|hidden| hidden.as_bool())
+        .unwrap_or(false)
+}
+
+// Calls the task and waits for the returned promise, if any
+async fn run_task(task: &js_sys::Function) -> bool {
+    match task.call0(&JsValue::NULL) {
+        Ok(result) => JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Starts the background sync loop. Each task is a function returning a promise, e.g.
+/// `() => query.shielded_sync(vks, sks)`, and runs at its interval, one task at a time.
+/// Returns a handle to stop the loop.
+///
+/// # Arguments
+///
+/// * `shielded_sync` - optional shielded sync task
+/// * `balance_refresh` - optional balance refresh task
+/// * `header_sync` - optional header sync task
+/// * `options` - optional `{ shielded_interval_ms, balance_interval_ms, header_interval_ms,
+///   backoff_ms, max_backoff_ms }`, missing fields use the defaults
+///
+/// # Errors
+///
+/// Returns an error if the options can't be deserialized
+#[wasm_bindgen]
+pub fn start_background_sync(
+    shielded_sync: Option<js_sys::Function>,
+    balance_refresh: Option<js_sys::Function>,
+    header_sync: Option<js_sys::Function>,
+    options: JsValue,
+) -> Result<WatcherHandle, JsError> {
+    let options: SyncOptions = if options.is_undefined() || options.is_null() {
+        SyncOptions::default()
+    } else {
+        options.into_serde()?
+    };
+    let (tasks, intervals_ms): (Vec<_>, Vec<_>) = [
+        (shielded_sync, options.shielded_interval_ms),
+        (balance_refresh, options.balance_interval_ms),
+        (header_sync, options.header_interval_ms),
+    ]
+    .into_iter()
+    .filter_map(|(task, interval_ms)| task.map(|task| (task, interval_ms)))
+    .unzip();
+
+    let (handle, stopped) = WatcherHandle::new();
+    let mut schedule = Schedule::new(
+        &intervals_ms,
+        options.backoff_ms,
+        options.max_backoff_ms,
+        js_sys::Date::now(),
+    );
+
+    wasm_bindgen_futures::spawn_local(async move {
+        while !stopped.get() {
+            let now = js_sys::Date::now();
+            let hidden = document_hidden();
+
+            let wait_ms = match schedule.next_due(now) {
+                Some(index) if !hidden => {
+                    let succeeded = run_task(&tasks[index]).await;
+                    schedule.complete(index, succeeded, js_sys::Date::now());
+                    continue;
+                }
+                _ if hidden => MAX_SLEEP_MS,
+                _ => schedule.wait_ms(now),
+            };
+
+            if sleep(wait_ms).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn runs_most_overdue_task_first() {
+        let mut schedule = Schedule::new(&[1000, 100], 50, 400, 0.0);
+
+        assert_eq!(schedule.next_due(0.0), Some(0));
+        schedule.complete(0, true, 0.0);
+        assert_eq!(schedule.next_due(0.0), Some(1));
+        schedule.complete(1, true, 10.0);
+
+        assert_eq!(schedule.next_due(10.0), None);
+        assert_eq!(schedule.wait_ms(10.0), 100);
+        assert_eq!(schedule.next_due(110.0), Some(1));
+    }
+
+    #[wasm_bindgen_test]
+    fn backs_off_failing_tasks() {
+        let mut schedule = Schedule::new(&[1000], 50, 150, 0.0);
+
+        schedule.complete(0, false, 0.0);
+        assert_eq!(schedule.wait_ms(0.0), 50);
+        schedule.complete(0, false, 0.0);
+        assert_eq!(schedule.wait_ms(0.0), 100);
+        schedule.complete(0, false, 0.0);
+        assert_eq!(schedule.wait_ms(0.0), 150);
+
+        schedule.complete(0, true, 0.0);
+        assert_eq!(schedule.wait_ms(0.0), MAX_SLEEP_MS);
+        assert_eq!(schedule.next_due(1000.0), Some(0));
+    }
+}
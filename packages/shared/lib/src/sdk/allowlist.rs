@@ -0,0 +1,140 @@
+//! Tx allowlist - wasm hashes of the tx codes and the chain's tx allowlist, used to reject
+//! building txs which are not enabled on the target chain.
+use std::collections::{BTreeMap, BTreeSet};
+
+use thiserror::Error;
+
+use super::tx::{code_path_to_tx_type, TxType};
+use crate::types::query::WasmHash;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AllowlistError {
+    #[error("Tx {0} is not available on the target chain")]
+    Unavailable(String),
+    #[error("Tx {0} is not enabled in the allowlist of the target chain")]
+    NotAllowed(String),
+}
+
+#[derive(Debug, Default)]
+pub struct TxAllowlist {
+    // Tx code path to lowercase hex encoded hash
+    hashes: BTreeMap<String, String>,
+    // Lowercase hex encoded hashes, empty if the chain allows all txs, which is how the ledger
+    // reads an empty `tx_allowlist` parameter
+    allowed: BTreeSet<String>,
+}
+
+impl TxAllowlist {
+    /// Creates the registry from wasm hashes and the `tx_allowlist` protocol parameter
+    pub fn new(wasm_hashes: Vec<WasmHash>, allowlist: Vec<String>) -> TxAllowlist {
+        TxAllowlist {
+            hashes: wasm_hashes
+                .into_iter()
+                .map(|wasm_hash| (wasm_hash.path(), wasm_hash.hash().to_lowercase()))
+                .collect(),
+            allowed: allowlist
+                .into_iter()
+                .map(|hash| hash.to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Returns an error if the tx code is missing on the chain or not in its allowlist
+    ///
+    /// # Arguments
+    ///
+    /// * `code_path` - tx code path, e.g. "tx_bond.wasm"
+    pub fn ensure_allowed(&self, code_path: &str) -> Result<(), AllowlistError> {
+        let hash = self
+            .hashes
+            .get(code_path)
+            .ok_or_else(|| AllowlistError::Unavailable(code_path.to_string()))?;
+
+        if !self.allowed.is_empty() && !self.allowed.contains(hash) {
+            return Err(AllowlistError::NotAllowed(code_path.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if the chain has an allowlist without the tx code hash, used for codes
+    /// which are not referenced by path, e.g. custom tx types
+    pub fn ensure_hash_allowed(&self, code_hash: &str) -> Result<(), AllowlistError> {
+        let code_hash = code_hash.to_lowercase();
+        if !self.allowed.is_empty() && !self.allowed.contains(&code_hash) {
+            return Err(AllowlistError::NotAllowed(code_hash));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_allowed(&self, code_path: &str) -> bool {
+        self.ensure_allowed(code_path).is_ok()
+    }
+
+    /// Returns hash of the tx code, if the tx is allowed
+    pub fn allowed_hash(&self, code_path: &str) -> Option<String> {
+        self.is_allowed(code_path)
+            .then(|| self.hashes.get(code_path).cloned())
+            .flatten()
+    }
+
+    /// Returns types of the allowed txs
+    pub fn tx_types(&self) -> Vec<TxType> {
+        self.hashes
+            .keys()
+            .filter(|code_path| self.is_allowed(code_path))
+            .filter_map(|code_path| code_path_to_tx_type(code_path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn wasm_hashes() -> Vec<WasmHash> {
+        vec![
+            WasmHash::new(String::from("tx_bond.wasm"), String::from("AA")),
+            WasmHash::new(String::from("tx_unbond.wasm"), String::from("BB")),
+        ]
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_allowlist_allows_all_available_txs() {
+        let allowlist = TxAllowlist::new(wasm_hashes(), vec![]);
+
+        assert!(allowlist.ensure_allowed("tx_bond.wasm").is_ok());
+        assert_eq!(
+            allowlist.ensure_allowed("tx_withdraw.wasm"),
+            Err(AllowlistError::Unavailable(String::from(
+                "tx_withdraw.wasm"
+            )))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_txs_not_in_allowlist() {
+        let allowlist = TxAllowlist::new(wasm_hashes(), vec![String::from("aa")]);
+
+        assert!(allowlist.ensure_allowed("tx_bond.wasm").is_ok());
+        assert_eq!(
+            allowlist.ensure_allowed("tx_unbond.wasm"),
+            Err(AllowlistError::NotAllowed(String::from("tx_unbond.wasm")))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn lists_allowed_tx_types() {
+        let allowlist = TxAllowlist::new(wasm_hashes(), vec![String::from("bb")]);
+        let tx_types: Vec<&str> = allowlist.tx_types().iter().map(|t| t.name()).collect();
+
+        assert_eq!(tx_types, vec!["unbond"]);
+        assert_eq!(
+            allowlist.allowed_hash("tx_unbond.wasm"),
+            Some(String::from("bb"))
+        );
+        assert_eq!(allowlist.allowed_hash("tx_bond.wasm"), None);
+    }
+}
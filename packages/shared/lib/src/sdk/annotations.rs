@@ -0,0 +1,252 @@
+//! Tx annotations - local labels and tags users attach to their txs, e.g. "rent" or "exchange
+//! deposit". They never leave the device, are kept in the store provided by the host and can be
+//! exported with wallet backups.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use namada_sdk::hash::Hash;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::{JsError, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+// Limits keeping the store small enough to be saved on every change
+pub const MAX_LABEL_LEN: usize = 64;
+pub const MAX_TAGS: usize = 16;
+
+#[derive(
+    Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize, Tsify,
+)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct TxAnnotation {
+    pub hash: String,
+    pub label: String,
+    pub tags: Vec<String>,
+    // Time in seconds the annotation was last changed at
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub updated_at: u64,
+}
+
+impl TxAnnotation {
+    // Removed annotations are kept without label and tags, so the removal wins over older
+    // copies when merging backups or synced annotations
+    fn is_tombstone(&self) -> bool {
+        self.label.is_empty() && self.tags.is_empty()
+    }
+}
+
+#[derive(Debug, Default, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct Annotations {
+    // Keyed by upper case tx hash, the way CometBFT returns them, including tombstones
+    annotations: BTreeMap<String, TxAnnotation>,
+}
+
+impl Annotations {
+    /// Sets the label and tags of the tx, an empty label and no tags remove the annotation by
+    /// leaving a tombstone
+    pub fn annotate(
+        &mut self,
+        hash: &str,
+        label: String,
+        tags: Vec<String>,
+        now: u64,
+    ) -> Result<(), JsError> {
+        let hash = normalize_hash(hash)?;
+        let label = label.trim().to_string();
+        if label.chars().count() > MAX_LABEL_LEN {
+            return Err(JsError::new(&format!(
+                "Label must be at most {} characters",
+                MAX_LABEL_LEN
+            )));
+        }
+        let mut tags: Vec<String> = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        if tags.len() > MAX_TAGS {
+            return Err(JsError::new(&format!(
+                "At most {} tags are allowed",
+                MAX_TAGS
+            )));
+        }
+
+        self.annotations.insert(
+            hash.clone(),
+            TxAnnotation {
+                hash,
+                label,
+                tags,
+                updated_at: now,
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn get(&self, hash: &str) -> Option<&TxAnnotation> {
+        normalize_hash(hash)
+            .ok()
+            .and_then(|hash| self.annotations.get(&hash))
+            .filter(|annotation| !annotation.is_tombstone())
+    }
+
+    /// Returns the annotations having the tag, or all of them without a tag
+    pub fn list(&self, tag: Option<&str>) -> Vec<TxAnnotation> {
+        let tag = tag.map(|tag| tag.trim().to_lowercase());
+        self.annotations
+            .values()
+            .filter(|annotation| !annotation.is_tombstone())
+            .filter(|annotation| {
+                tag.as_ref()
+                    .map_or(true, |tag| annotation.tags.contains(tag))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Merges imported annotations, the most recently changed one wins on conflicts, so
+    /// removals propagate as well
+    pub fn merge(&mut self, other: Annotations) {
+        for (hash, annotation) in other.annotations {
+            match self.annotations.get(&hash) {
+                Some(existing) if existing.updated_at > annotation.updated_at => {}
+                _ => {
+                    self.annotations.insert(hash, annotation);
+                }
+            }
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, JsError> {
+        Ok(borsh::to_vec(self)?)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Annotations, JsError> {
+        Annotations::try_from_slice(bytes)
+            .map_err(|err| JsError::new(&format!("Invalid annotations: {}", err)))
+    }
+}
+
+/// Tx hashes are case insensitive hex
+pub fn normalize_hash(hash: &str) -> Result<String, JsError> {
+    Hash::from_str(hash.trim())
+        .map(|hash| hash.to_string())
+        .map_err(|_| JsError::new(&format!("Invalid tx hash: {}", hash)))
+}
+
+/// Store of the host the annotations are persisted in
+#[derive(Clone)]
+pub struct AnnotationStore {
+    // Called with a Uint8Array, may return a promise
+    save: js_sys::Function,
+    // Returns the saved Uint8Array or undefined, may return a promise
+    load: js_sys::Function,
+}
+
+impl AnnotationStore {
+    pub fn new(save: js_sys::Function, load: js_sys::Function) -> AnnotationStore {
+        AnnotationStore { save, load }
+    }
+
+    /// Saves annotations encoded by `Annotations::encode`
+    pub async fn save(&self, bytes: Vec<u8>) -> Result<(), JsError> {
+        let value: JsValue = js_sys::Uint8Array::from(&bytes[..]).into();
+        let result = self
+            .save
+            .call1(&JsValue::NULL, &value)
+            .map_err(|_| JsError::new("Saving annotations failed"))?;
+        JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Saving annotations failed"))?;
+
+        Ok(())
+    }
+
+    pub async fn load(&self) -> Result<Annotations, JsError> {
+        let result = self
+            .load
+            .call0(&JsValue::NULL)
+            .map_err(|_| JsError::new("Loading annotations failed"))?;
+        let value = JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Loading annotations failed"))?;
+        if value.is_undefined() || value.is_null() {
+            return Ok(Annotations::default());
+        }
+
+        Annotations::decode(&js_sys::Uint8Array::new(&value).to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const HASH: &str = "8a5e2ee1b0e5a5c6e5c0f2c1d0d9e8b7a6f5e4d3c2b1a09f8e7d6c5b4a392817";
+
+    #[wasm_bindgen_test]
+    fn annotations_are_case_insensitive() {
+        let mut annotations = Annotations::default();
+        annotations
+            .annotate(
+                HASH,
+                String::from(" rent "),
+                vec![String::from("Home"), String::from("home")],
+                1,
+            )
+            .unwrap();
+
+        let annotation = annotations.get(&HASH.to_uppercase()).unwrap();
+        assert_eq!(annotation.label, "rent");
+        assert_eq!(annotation.tags, vec![String::from("home")]);
+        assert_eq!(annotations.list(Some("HOME")).len(), 1);
+        assert!(annotations.list(Some("exchange")).is_empty());
+
+        annotations
+            .annotate(HASH, String::new(), vec![], 2)
+            .unwrap();
+        assert!(annotations.get(HASH).is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn merge_keeps_latest_annotation() {
+        let mut local = Annotations::default();
+        local
+            .annotate(HASH, String::from("rent"), vec![], 5)
+            .unwrap();
+        let mut backup = Annotations::default();
+        backup
+            .annotate(HASH, String::from("exchange deposit"), vec![], 3)
+            .unwrap();
+
+        let backup = Annotations::decode(&backup.encode().unwrap()).unwrap();
+        local.merge(backup);
+
+        assert_eq!(local.get(HASH).unwrap().label, "rent");
+    }
+
+    #[wasm_bindgen_test]
+    fn merge_keeps_removals() {
+        let mut local = Annotations::default();
+        local
+            .annotate(HASH, String::from("rent"), vec![], 3)
+            .unwrap();
+        let backup = Annotations::decode(&local.encode().unwrap()).unwrap();
+        local.annotate(HASH, String::new(), vec![], 5).unwrap();
+
+        local.merge(backup);
+        assert!(local.get(HASH).is_none());
+        assert!(local.list(None).is_empty());
+
+        let mut other = Annotations::default();
+        other.merge(local);
+        assert!(other.get(HASH).is_none());
+    }
+}
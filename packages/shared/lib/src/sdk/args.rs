@@ -1,7 +1,8 @@
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::{path::PathBuf, str::FromStr};
 
-use namada_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_sdk::collections::HashMap;
 use namada_sdk::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_sdk::ibc::IbcShieldingData;
@@ -16,25 +17,47 @@ use namada_sdk::masp_primitives::transaction::sighash::{signature_hash, Signable
 use namada_sdk::masp_primitives::transaction::txid::TxIdDigester;
 use namada_sdk::masp_primitives::zip32;
 use namada_sdk::signing::SigningTxData;
+use namada_sdk::time::DateTimeUtc;
 use namada_sdk::tx::data::GasLimit;
 use namada_sdk::tx::{Section, Tx};
+use namada_sdk::ExtendedSpendingKey;
 use namada_sdk::{
     address::Address,
     args::{self, InputAmount, TxExpiration},
-    chain::ChainId,
     ethereum_events::EthAddress,
     key::common::PublicKey,
-    token::{Amount, DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES},
+    token::{Amount, DenominatedAmount, Denomination, NATIVE_MAX_DECIMAL_PLACES},
     TransferSource,
 };
 use namada_sdk::{error, masp_primitives, tendermint_rpc};
-use namada_sdk::{ExtendedSpendingKey, PaymentAddress};
 use rand::rngs::OsRng;
+use serde::{de::DeserializeOwned, Deserialize};
+use tsify::Tsify;
 use wasm_bindgen::JsError;
 
+use super::recovery;
+use super::validate;
+use crate::types::amount::StringAmount;
 use crate::types::masp::PseudoExtendedKey;
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+/// Converts JSON encoded msg to its Borsh representation, so JSON inputs can be passed to the
+/// same functions as Borsh slices
+///
+/// # Arguments
+///
+/// * `json` - JSON encoded msg, field names are the same as in the msg struct
+///
+/// # Errors
+///
+/// Returns an error if the JSON does not match the msg struct
+pub fn json_to_borsh<T: DeserializeOwned + BorshSerialize>(json: &str) -> Result<Vec<u8>, JsError> {
+    let msg: T = serde_json::from_str(json)
+        .map_err(|err| JsError::new(&format!("Invalid tx msg JSON: {}", err)))?;
+
+    Ok(borsh::to_vec(&msg)?)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct RevealPkMsg {
     public_key: String,
@@ -46,27 +69,30 @@ impl RevealPkMsg {
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Clone, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct WrapperTxMsg {
     token: String,
-    fee_amount: String,
+    fee_amount: StringAmount,
     gas_limit: String,
     chain_id: String,
     public_key: Option<String>,
     memo: Option<String>,
     force: Option<bool>,
+    // RFC3339 datetime, defaults to the SDK expiration
+    expiration: Option<String>,
 }
 
 impl WrapperTxMsg {
     pub fn new(
         token: String,
-        fee_amount: String,
+        fee_amount: StringAmount,
         gas_limit: String,
         chain_id: String,
         public_key: Option<String>,
         memo: Option<String>,
         force: Option<bool>,
+        expiration: Option<String>,
     ) -> WrapperTxMsg {
         WrapperTxMsg {
             token,
@@ -76,20 +102,32 @@ impl WrapperTxMsg {
             public_key,
             memo,
             force,
+            expiration,
+        }
+    }
+
+    pub fn with_expiration(self, expiration: Option<String>) -> WrapperTxMsg {
+        WrapperTxMsg { expiration, ..self }
+    }
+
+    pub fn with_public_key(self, public_key: String) -> WrapperTxMsg {
+        WrapperTxMsg {
+            public_key: Some(public_key),
+            ..self
         }
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct BondMsg {
     source: String,
     validator: String,
-    amount: String,
+    amount: StringAmount,
 }
 
 impl BondMsg {
-    pub fn new(source: String, validator: String, amount: String) -> BondMsg {
+    pub fn new(source: String, validator: String, amount: StringAmount) -> BondMsg {
         BondMsg {
             source,
             validator,
@@ -118,9 +156,9 @@ pub fn bond_tx_args(bond_msg: &[u8], tx_msg: &[u8]) -> Result<args::Bond, JsErro
         amount,
     } = bond_msg;
 
-    let source = Address::from_str(&source)?;
-    let validator = Address::from_str(&validator)?;
-    let amount = Amount::from_str(&amount, NATIVE_MAX_DECIMAL_PLACES)?;
+    let source = validate::address("source", &source)?;
+    let validator = validate::address("validator", &validator)?;
+    let amount = amount.to_amount(NATIVE_MAX_DECIMAL_PLACES)?;
     let tx = tx_msg_into_args(tx_msg)?;
 
     let args = args::Bond {
@@ -134,16 +172,90 @@ pub fn bond_tx_args(bond_msg: &[u8], tx_msg: &[u8]) -> Result<args::Bond, JsErro
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct BondSplitDataMsg {
+    validator: String,
+    amount: StringAmount,
+}
+
+impl BondSplitDataMsg {
+    pub fn new(validator: String, amount: StringAmount) -> BondSplitDataMsg {
+        BondSplitDataMsg { validator, amount }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct BondSplitMsg {
+    source: String,
+    bonds: Vec<BondSplitDataMsg>,
+}
+
+impl BondSplitMsg {
+    pub fn new(source: String, bonds: Vec<BondSplitDataMsg>) -> BondSplitMsg {
+        BondSplitMsg { source, bonds }
+    }
+}
+
+/// Maps serialized tx_msg into BondTx args, one for each validator.
+///
+/// # Arguments
+///
+/// * `bond_split_msg` - Borsh serialized BondSplitMsg.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized, there are no bonds, a validator is
+/// listed more than once or Rust structs can't be created.
+pub fn bond_split_tx_args(
+    bond_split_msg: &[u8],
+    tx_msg: &[u8],
+) -> Result<Vec<args::Bond>, JsError> {
+    let BondSplitMsg { source, bonds } = BondSplitMsg::try_from_slice(bond_split_msg)?;
+
+    if bonds.is_empty() {
+        return Err(JsError::new("At least one validator is required"));
+    }
+
+    let source = validate::address("source", &source)?;
+    let tx = tx_msg_into_args(tx_msg)?;
+    let mut validators = BTreeSet::new();
+    let mut args: Vec<args::Bond> = vec![];
+
+    for bond in bonds {
+        let validator = validate::address("validator", &bond.validator)?;
+        if !validators.insert(validator.clone()) {
+            return Err(JsError::new(&format!(
+                "Validator {} is listed more than once",
+                validator
+            )));
+        }
+        let amount = bond.amount.to_amount(NATIVE_MAX_DECIMAL_PLACES)?;
+
+        args.push(args::Bond {
+            tx: tx.clone(),
+            validator,
+            amount,
+            source: Some(source.clone()),
+            tx_code_path: PathBuf::from("tx_bond.wasm"),
+        });
+    }
+
+    Ok(args)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct UnbondMsg {
     source: String,
     validator: String,
-    amount: String,
+    amount: StringAmount,
 }
 
 impl UnbondMsg {
-    pub fn new(source: String, validator: String, amount: String) -> UnbondMsg {
+    pub fn new(source: String, validator: String, amount: StringAmount) -> UnbondMsg {
         UnbondMsg {
             source,
             validator,
@@ -172,10 +284,10 @@ pub fn unbond_tx_args(unbond_msg: &[u8], tx_msg: &[u8]) -> Result<args::Unbond,
         amount,
     } = unbond_msg;
 
-    let source = Address::from_str(&source)?;
-    let validator = Address::from_str(&validator)?;
+    let source = validate::address("source", &source)?;
+    let validator = validate::address("validator", &validator)?;
 
-    let amount = Amount::from_str(&amount, NATIVE_MAX_DECIMAL_PLACES)?;
+    let amount = amount.to_amount(NATIVE_MAX_DECIMAL_PLACES)?;
     let tx = tx_msg_into_args(tx_msg)?;
 
     let args = args::Unbond {
@@ -189,7 +301,7 @@ pub fn unbond_tx_args(unbond_msg: &[u8], tx_msg: &[u8]) -> Result<args::Unbond,
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct WithdrawMsg {
     source: String,
@@ -218,8 +330,8 @@ pub fn withdraw_tx_args(withdraw_msg: &[u8], tx_msg: &[u8]) -> Result<args::With
 
     let WithdrawMsg { source, validator } = withdraw_msg;
 
-    let source = Address::from_str(&source)?;
-    let validator = Address::from_str(&validator)?;
+    let source = validate::address("source", &source)?;
+    let validator = validate::address("validator", &validator)?;
     let tx = tx_msg_into_args(tx_msg)?;
 
     let args = args::Withdraw {
@@ -232,13 +344,13 @@ pub fn withdraw_tx_args(withdraw_msg: &[u8], tx_msg: &[u8]) -> Result<args::With
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct RedelegateMsg {
     owner: String,
     source_validator: String,
     destination_validator: String,
-    amount: String,
+    amount: StringAmount,
 }
 
 impl RedelegateMsg {
@@ -246,7 +358,7 @@ impl RedelegateMsg {
         owner: String,
         source_validator: String,
         destination_validator: String,
-        amount: String,
+        amount: StringAmount,
     ) -> RedelegateMsg {
         RedelegateMsg {
             owner,
@@ -281,10 +393,10 @@ pub fn redelegate_tx_args(
         amount,
     } = redelegate_msg;
 
-    let owner = Address::from_str(&owner)?;
-    let src_validator = Address::from_str(&source_validator)?;
-    let dest_validator = Address::from_str(&destination_validator)?;
-    let amount = Amount::from_str(&amount, NATIVE_MAX_DECIMAL_PLACES)?;
+    let owner = validate::address("owner", &owner)?;
+    let src_validator = validate::address("source_validator", &source_validator)?;
+    let dest_validator = validate::address("destination_validator", &destination_validator)?;
+    let amount = amount.to_amount(NATIVE_MAX_DECIMAL_PLACES)?;
     let tx = tx_msg_into_args(tx_msg)?;
 
     let args = args::Redelegate {
@@ -299,10 +411,12 @@ pub fn redelegate_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct VoteProposalMsg {
     signer: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
     proposal_id: u64,
     vote: String,
 }
@@ -340,7 +454,7 @@ pub fn vote_proposal_tx_args(
         vote,
     } = vote_proposal_msg;
     let tx = tx_msg_into_args(tx_msg)?;
-    let voter_address = Address::from_str(&signer)?;
+    let voter_address = validate::address("signer", &signer)?;
 
     let args = args::VoteProposal {
         tx,
@@ -353,7 +467,7 @@ pub fn vote_proposal_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ClaimRewardsMsg {
     validator: String,
@@ -386,8 +500,10 @@ pub fn claim_rewards_tx_args(
     let ClaimRewardsMsg { validator, source } = claim_rewards_msg;
     let tx = tx_msg_into_args(tx_msg)?;
 
-    let validator_address = Address::from_str(&validator)?;
-    let source_address = source.map(|str| Address::from_str(&str).expect("valid address"));
+    let validator_address = validate::address("validator", &validator)?;
+    let source_address = source
+        .map(|source| validate::address("source", &source))
+        .transpose()?;
 
     let args = args::ClaimRewards {
         tx,
@@ -399,16 +515,280 @@ pub fn claim_rewards_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+/// Maps the delegation and its claimable rewards into ClaimRewardsTx and BondTx args, the
+/// rewards being bonded back to the same validator.
+///
+/// # Arguments
+///
+/// * `delegator` - address of the delegator.
+/// * `validator` - address of the validator.
+/// * `rewards` - claimable rewards, in base units of the native token.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized or an address is invalid.
+pub fn compound_rewards_tx_args(
+    delegator: &str,
+    validator: &str,
+    rewards: Amount,
+    tx_msg: &[u8],
+) -> Result<(args::ClaimRewards, args::Bond), JsError> {
+    let delegator = validate::address("delegator", delegator)?;
+    let validator = validate::address("validator", validator)?;
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    let claim = args::ClaimRewards {
+        tx: tx.clone(),
+        validator: validator.clone(),
+        source: Some(delegator.clone()),
+        tx_code_path: PathBuf::from("tx_claim_rewards.wasm"),
+    };
+    let bond = args::Bond {
+        tx,
+        validator,
+        amount: rewards,
+        source: Some(delegator),
+        tx_code_path: PathBuf::from("tx_bond.wasm"),
+    };
+
+    Ok((claim, bond))
+}
+
+/// Maps the bonds of the source into UnbondTx args, one for each validator.
+///
+/// # Arguments
+///
+/// * `source` - address of the delegator.
+/// * `bonds` - validators and the amounts bonded to them.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized.
+pub fn unbond_all_tx_args(
+    source: &Address,
+    bonds: Vec<(Address, Amount)>,
+    tx_msg: &[u8],
+) -> Result<Vec<args::Unbond>, JsError> {
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    Ok(bonds
+        .into_iter()
+        .map(|(validator, amount)| args::Unbond {
+            tx: tx.clone(),
+            validator,
+            amount,
+            source: Some(source.clone()),
+            tx_code_path: PathBuf::from("tx_unbond.wasm"),
+        })
+        .collect())
+}
+
+/// Maps the validators with withdrawable unbonds of the source into WithdrawTx args.
+///
+/// # Arguments
+///
+/// * `source` - address of the delegator.
+/// * `validators` - validators to withdraw from.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized.
+pub fn withdraw_all_tx_args(
+    source: &Address,
+    validators: Vec<Address>,
+    tx_msg: &[u8],
+) -> Result<Vec<args::Withdraw>, JsError> {
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    Ok(validators
+        .into_iter()
+        .map(|validator| args::Withdraw {
+            tx: tx.clone(),
+            validator,
+            source: Some(source.clone()),
+            tx_code_path: PathBuf::from("tx_withdraw.wasm"),
+        })
+        .collect())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct GuardianAccountMsg {
+    owner_public_keys: Vec<String>,
+    // Owner signatures required for any tx of the account, the threshold of the account
+    owner_threshold: u8,
+    guardian_public_keys: Vec<String>,
+    // Owner keys which can be lost and replaced by guardian signatures in a recovery
+    guardian_threshold: u8,
+}
+
+impl GuardianAccountMsg {
+    pub fn new(
+        owner_public_keys: Vec<String>,
+        owner_threshold: u8,
+        guardian_public_keys: Vec<String>,
+        guardian_threshold: u8,
+    ) -> GuardianAccountMsg {
+        GuardianAccountMsg {
+            owner_public_keys,
+            owner_threshold,
+            guardian_public_keys,
+            guardian_threshold,
+        }
+    }
+}
+
+// Parses the owner and guardian keys and checks the thresholds
+fn guardian_account_keys(
+    owner_public_keys: &[String],
+    owner_threshold: u8,
+    guardian_public_keys: &[String],
+    guardian_threshold: u8,
+) -> Result<recovery::AccountKeys, JsError> {
+    let owners = owner_public_keys
+        .iter()
+        .map(|key| validate::public_key("owner_public_keys", key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let guardians = guardian_public_keys
+        .iter()
+        .map(|key| validate::public_key("guardian_public_keys", key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(recovery::account_keys(
+        owners,
+        owner_threshold,
+        guardians,
+        guardian_threshold,
+    )?)
+}
+
+/// Maps serialized tx_msg into TxInitAccount args of an account recoverable by guardians.
+///
+/// # Arguments
+///
+/// * `guardian_account_msg` - Borsh serialized guardian_account_msg.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized, a key is invalid, a threshold can't be
+/// met or the guardians alone meet the owner threshold.
+pub fn guardian_account_tx_args(
+    guardian_account_msg: &[u8],
+    tx_msg: &[u8],
+) -> Result<args::TxInitAccount, JsError> {
+    let GuardianAccountMsg {
+        owner_public_keys,
+        owner_threshold,
+        guardian_public_keys,
+        guardian_threshold,
+    } = GuardianAccountMsg::try_from_slice(guardian_account_msg)?;
+
+    let keys = guardian_account_keys(
+        &owner_public_keys,
+        owner_threshold,
+        &guardian_public_keys,
+        guardian_threshold,
+    )?;
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    let args = args::TxInitAccount {
+        tx,
+        vp_code_path: PathBuf::from("vp_user.wasm"),
+        tx_code_path: PathBuf::from("tx_init_account.wasm"),
+        public_keys: keys.public_keys,
+        threshold: Some(keys.threshold),
+    };
+
+    Ok(args)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct RecoveryMsg {
+    account: String,
+    // Keys replacing the keys of the account, e.g. new owner keys and the same guardians
+    owner_public_keys: Vec<String>,
+    owner_threshold: u8,
+    guardian_public_keys: Vec<String>,
+    guardian_threshold: u8,
+}
+
+impl RecoveryMsg {
+    pub fn new(
+        account: String,
+        owner_public_keys: Vec<String>,
+        owner_threshold: u8,
+        guardian_public_keys: Vec<String>,
+        guardian_threshold: u8,
+    ) -> RecoveryMsg {
+        RecoveryMsg {
+            account,
+            owner_public_keys,
+            owner_threshold,
+            guardian_public_keys,
+            guardian_threshold,
+        }
+    }
+}
+
+/// Maps serialized tx_msg into TxUpdateAccount args replacing the keys of the account.
+///
+/// # Arguments
+///
+/// * `recovery_msg` - Borsh serialized recovery_msg.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized, a key is invalid, a threshold can't be
+/// met or the guardians alone meet the owner threshold.
+pub fn recovery_tx_args(
+    recovery_msg: &[u8],
+    tx_msg: &[u8],
+) -> Result<args::TxUpdateAccount, JsError> {
+    let RecoveryMsg {
+        account,
+        owner_public_keys,
+        owner_threshold,
+        guardian_public_keys,
+        guardian_threshold,
+    } = RecoveryMsg::try_from_slice(recovery_msg)?;
+
+    let addr = validate::address("account", &account)?;
+    let keys = guardian_account_keys(
+        &owner_public_keys,
+        owner_threshold,
+        &guardian_public_keys,
+        guardian_threshold,
+    )?;
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    let args = args::TxUpdateAccount {
+        tx,
+        vp_code_path: None,
+        tx_code_path: PathBuf::from("tx_update_account.wasm"),
+        addr,
+        public_keys: keys.public_keys,
+        threshold: Some(keys.threshold),
+    };
+
+    Ok(args)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TransferDataMsg {
     owner: String,
     token: String,
-    amount: String,
+    amount: StringAmount,
 }
 
 impl TransferDataMsg {
-    pub fn new(owner: String, token: String, amount: String) -> TransferDataMsg {
+    pub fn new(owner: String, token: String, amount: StringAmount) -> TransferDataMsg {
         TransferDataMsg {
             owner,
             token,
@@ -417,7 +797,7 @@ impl TransferDataMsg {
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TransferMsg {
     sources: Vec<TransferDataMsg>,
@@ -439,16 +819,16 @@ impl TransferMsg {
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TransparentTransferDataMsg {
     source: String,
     target: String,
     token: String,
-    amount: String,
+    amount: StringAmount,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TransparentTransferMsg {
     data: Vec<TransparentTransferDataMsg>,
@@ -475,11 +855,10 @@ pub fn transparent_transfer_tx_args(
     let mut transfer_data: Vec<args::TxTransparentTransferData> = vec![];
 
     for transfer in data {
-        let source = Address::from_str(&transfer.source)?;
-        let target = Address::from_str(&transfer.target)?;
-        let token = Address::from_str(&transfer.token)?;
-        let denom_amount =
-            DenominatedAmount::from_str(&transfer.amount).expect("Amount to be valid.");
+        let source = validate::address("source", &transfer.source)?;
+        let target = validate::address("target", &transfer.target)?;
+        let token = validate::address("token", &transfer.token)?;
+        let denom_amount = transfer.amount.to_denominated()?;
         let amount = InputAmount::Unvalidated(denom_amount);
 
         transfer_data.push(args::TxTransparentTransferData {
@@ -501,16 +880,222 @@ pub fn transparent_transfer_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct MultiTransferDataMsg {
+    target: String,
+    token: String,
+    amount: StringAmount,
+}
+
+impl MultiTransferDataMsg {
+    pub fn new(target: String, token: String, amount: StringAmount) -> MultiTransferDataMsg {
+        MultiTransferDataMsg {
+            target,
+            token,
+            amount,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct MultiTransferMsg {
+    source: String,
+    data: Vec<MultiTransferDataMsg>,
+}
+
+impl MultiTransferMsg {
+    pub fn new(source: String, data: Vec<MultiTransferDataMsg>) -> MultiTransferMsg {
+        MultiTransferMsg { source, data }
+    }
+}
+
+/// Maps serialized tx_msg into TransferTx args, one for each recipient.
+///
+/// # Arguments
+///
+/// * `multi_transfer_msg` - Borsh serialized MultiTransferMsg.
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized or
+/// Rust structs can't be created.
+pub fn multi_transfer_tx_args(
+    multi_transfer_msg: &[u8],
+    tx_msg: &[u8],
+) -> Result<Vec<args::TxTransparentTransfer>, JsError> {
+    let multi_transfer_msg = MultiTransferMsg::try_from_slice(multi_transfer_msg)?;
+    let MultiTransferMsg { source, data } = multi_transfer_msg;
+
+    if data.is_empty() {
+        return Err(JsError::new("At least one recipient is required"));
+    }
+
+    let source = validate::address("source", &source)?;
+    let tx = tx_msg_into_args(tx_msg)?;
+    let mut transfers: Vec<args::TxTransparentTransfer> = vec![];
+
+    for transfer in data {
+        let target = validate::address("target", &transfer.target)?;
+        let token = validate::address("token", &transfer.token)?;
+        let amount = InputAmount::Unvalidated(transfer.amount.to_denominated()?);
+
+        transfers.push(args::TxTransparentTransfer {
+            tx: tx.clone(),
+            data: vec![args::TxTransparentTransferData {
+                source: source.clone(),
+                target,
+                token,
+                amount,
+            }],
+            tx_code_path: PathBuf::from("tx_transfer.wasm"),
+        });
+    }
+
+    Ok(transfers)
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct MultiSourceTransferMsg {
+    // Source accounts in priority order
+    sources: Vec<String>,
+    target: String,
+    token: String,
+    amount: StringAmount,
+}
+
+/// Parsed MultiSourceTransferMsg
+pub struct MultiSourceTransfer {
+    pub sources: Vec<Address>,
+    pub target: Address,
+    pub token: Address,
+    pub amount: StringAmount,
+}
+
+/// Deserializes and validates MultiSourceTransferMsg. Sources listed more than once are kept at
+/// their first position only.
+///
+/// # Arguments
+///
+/// * `transfer_msg` - Borsh serialized MultiSourceTransferMsg.
+///
+/// # Errors
+///
+/// Returns JsError if the msg can't be deserialized, has no sources or an address is invalid
+pub fn multi_source_transfer_from_slice(
+    transfer_msg: &[u8],
+) -> Result<MultiSourceTransfer, JsError> {
+    let MultiSourceTransferMsg {
+        sources,
+        target,
+        token,
+        amount,
+    } = MultiSourceTransferMsg::try_from_slice(transfer_msg)?;
+
+    if sources.is_empty() {
+        return Err(JsError::new("At least one source is required"));
+    }
+
+    let mut unique_sources: Vec<Address> = vec![];
+    for source in sources.iter() {
+        let source = validate::address("sources", source)?;
+        if !unique_sources.contains(&source) {
+            unique_sources.push(source);
+        }
+    }
+
+    Ok(MultiSourceTransfer {
+        sources: unique_sources,
+        target: validate::address("target", &target)?,
+        token: validate::address("token", &token)?,
+        amount,
+    })
+}
+
+/// Maps the amounts pulled from each source into TransferTx args, one for each source.
+///
+/// # Arguments
+///
+/// * `transfer` - parsed MultiSourceTransferMsg
+/// * `parts` - (source, amount) pairs, amounts denominated by the token
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized.
+pub fn split_transfer_tx_args(
+    transfer: &MultiSourceTransfer,
+    parts: Vec<(Address, DenominatedAmount)>,
+    tx_msg: &[u8],
+) -> Result<Vec<args::TxTransparentTransfer>, JsError> {
+    let tx = tx_msg_into_args(tx_msg)?;
+
+    Ok(parts
+        .into_iter()
+        .map(|(source, amount)| args::TxTransparentTransfer {
+            tx: tx.clone(),
+            data: vec![args::TxTransparentTransferData {
+                source,
+                target: transfer.target.clone(),
+                token: transfer.token.clone(),
+                amount: InputAmount::Unvalidated(amount),
+            }],
+            tx_code_path: PathBuf::from("tx_transfer.wasm"),
+        })
+        .collect())
+}
+
+/// Maps the sweep of a session address into TransferTx args, signed by the session key, which
+/// also pays the fee.
+///
+/// # Arguments
+///
+/// * `source` - session address
+/// * `target` - address the funds are swept to
+/// * `token` - swept token
+/// * `amount` - swept amount, denominated by the token
+/// * `public_key` - public key of the session key
+/// * `tx_msg` - Borsh serialized tx_msg.
+///
+/// # Errors
+///
+/// Returns JsError if the tx_msg can't be deserialized.
+pub fn sweep_transfer_tx_args(
+    source: Address,
+    target: Address,
+    token: Address,
+    amount: DenominatedAmount,
+    public_key: PublicKey,
+    tx_msg: &[u8],
+) -> Result<args::TxTransparentTransfer, JsError> {
+    let mut tx = tx_msg_into_args(tx_msg)?;
+    tx.signing_keys = vec![public_key];
+
+    Ok(args::TxTransparentTransfer {
+        tx,
+        data: vec![args::TxTransparentTransferData {
+            source,
+            target,
+            token,
+            amount: InputAmount::Unvalidated(amount),
+        }],
+        tx_code_path: PathBuf::from("tx_transfer.wasm"),
+    })
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ShieldedTransferDataMsg {
     source: String,
     target: String,
     token: String,
-    amount: String,
+    amount: StringAmount,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ShieldedTransferMsg {
     data: Vec<ShieldedTransferDataMsg>,
@@ -544,10 +1129,9 @@ pub fn shielded_transfer_tx_args(
 
     for shielded_transfer in data {
         let source = PseudoExtendedKey::decode(shielded_transfer.source).0;
-        let target = PaymentAddress::from_str(&shielded_transfer.target)?;
-        let token = Address::from_str(&shielded_transfer.token)?;
-        let denom_amount =
-            DenominatedAmount::from_str(&shielded_transfer.amount).expect("Amount to be valid.");
+        let target = validate::payment_address("target", &shielded_transfer.target)?;
+        let token = validate::address("token", &shielded_transfer.token)?;
+        let denom_amount = shielded_transfer.amount.to_denominated()?;
         let amount = InputAmount::Unvalidated(denom_amount);
 
         shielded_transfer_data.push(args::TxShieldedTransferData {
@@ -572,21 +1156,37 @@ pub fn shielded_transfer_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ShieldingTransferDataMsg {
     source: String,
     token: String,
-    amount: String,
+    amount: StringAmount,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ShieldingTransferMsg {
     target: String,
     data: Vec<ShieldingTransferDataMsg>,
 }
 
+impl ShieldingTransferDataMsg {
+    pub fn new(source: String, token: String, amount: StringAmount) -> ShieldingTransferDataMsg {
+        ShieldingTransferDataMsg {
+            source,
+            token,
+            amount,
+        }
+    }
+}
+
+impl ShieldingTransferMsg {
+    pub fn new(target: String, data: Vec<ShieldingTransferDataMsg>) -> ShieldingTransferMsg {
+        ShieldingTransferMsg { target, data }
+    }
+}
+
 /// Maps serialized tx_msg into TxShieldingTransfer args.
 ///
 /// # Arguments
@@ -604,15 +1204,14 @@ pub fn shielding_transfer_tx_args(
 ) -> Result<args::TxShieldingTransfer, JsError> {
     let shielding_transfer_msg = ShieldingTransferMsg::try_from_slice(shielding_transfer_msg)?;
     let ShieldingTransferMsg { target, data } = shielding_transfer_msg;
-    let target = PaymentAddress::from_str(&target)?;
+    let target = validate::payment_address("target", &target)?;
 
     let mut shielding_transfer_data: Vec<args::TxShieldingTransferData> = vec![];
 
     for shielding_transfer in data {
-        let source = Address::from_str(&shielding_transfer.source)?;
-        let token = Address::from_str(&shielding_transfer.token)?;
-        let denom_amount =
-            DenominatedAmount::from_str(&shielding_transfer.amount).expect("Amount to be valid.");
+        let source = validate::address("source", &shielding_transfer.source)?;
+        let token = validate::address("token", &shielding_transfer.token)?;
+        let denom_amount = shielding_transfer.amount.to_denominated()?;
         let amount = InputAmount::Unvalidated(denom_amount);
 
         shielding_transfer_data.push(args::TxShieldingTransferData {
@@ -634,15 +1233,15 @@ pub fn shielding_transfer_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct UnshieldingTransferDataMsg {
     target: String,
     token: String,
-    amount: String,
+    amount: StringAmount,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct UnshieldingTransferMsg {
     source: String,
@@ -650,6 +1249,30 @@ pub struct UnshieldingTransferMsg {
     gas_spending_key: Option<String>,
 }
 
+impl UnshieldingTransferDataMsg {
+    pub fn new(target: String, token: String, amount: StringAmount) -> UnshieldingTransferDataMsg {
+        UnshieldingTransferDataMsg {
+            target,
+            token,
+            amount,
+        }
+    }
+}
+
+impl UnshieldingTransferMsg {
+    pub fn new(
+        source: String,
+        data: Vec<UnshieldingTransferDataMsg>,
+        gas_spending_key: Option<String>,
+    ) -> UnshieldingTransferMsg {
+        UnshieldingTransferMsg {
+            source,
+            data,
+            gas_spending_key,
+        }
+    }
+}
+
 /// Maps serialized tx_msg into TxUnshieldingTransfer args.
 ///
 /// # Arguments
@@ -677,10 +1300,9 @@ pub fn unshielding_transfer_tx_args(
     let mut unshielding_transfer_data: Vec<args::TxUnshieldingTransferData> = vec![];
 
     for unshielding_transfer in data {
-        let target = Address::from_str(&unshielding_transfer.target)?;
-        let token = Address::from_str(&unshielding_transfer.token)?;
-        let denom_amount =
-            DenominatedAmount::from_str(&unshielding_transfer.amount).expect("Amount to be valid.");
+        let target = validate::address("target", &unshielding_transfer.target)?;
+        let token = validate::address("token", &unshielding_transfer.token)?;
+        let denom_amount = unshielding_transfer.amount.to_denominated()?;
         let amount = InputAmount::Unvalidated(denom_amount);
 
         unshielding_transfer_data.push(args::TxUnshieldingTransferData {
@@ -705,16 +1327,20 @@ pub fn unshielding_transfer_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct IbcTransferMsg {
     source: String,
     receiver: String,
     token: String,
-    amount_in_base_denom: String,
+    amount_in_base_denom: StringAmount,
     port_id: String,
     channel_id: String,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
     timeout_height: Option<u64>,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
     timeout_sec_offset: Option<u64>,
     memo: Option<String>,
     shielding_data: Option<Vec<u8>>,
@@ -725,7 +1351,7 @@ impl IbcTransferMsg {
         source: String,
         receiver: String,
         token: String,
-        amount_in_base_denom: String,
+        amount_in_base_denom: StringAmount,
         port_id: String,
         channel_id: String,
         timeout_height: Option<u64>,
@@ -777,16 +1403,18 @@ pub fn ibc_transfer_tx_args(
         shielding_data,
     } = ibc_transfer_msg;
 
-    let source_address = Address::from_str(&source)?;
+    let source_address = validate::address("source", &source)?;
     let source = TransferSource::Address(source_address);
-    let token = Address::from_str(&token)?;
-    let amount = Amount::from_str(&amount_in_base_denom, 0u8).expect("Amount to be valid.");
+    let token = validate::address("token", &token)?;
+    let amount = amount_in_base_denom.to_amount(0)?;
     // Using InputAmount::Validated because the amount is already in the base
     // denom. If Unvalidated is used, the SDK will change the denom based on the
     // token address, which complicates knowing which amount to pass to this function.
     let amount = InputAmount::Validated(amount.into());
-    let port_id = PortId::from_str(&port_id).expect("Port id to be valid");
-    let channel_id = ChannelId::from_str(&channel_id).expect("Channel id to be valid");
+    let receiver = validate::string("receiver", &receiver, validate::MAX_ADDRESS_LEN)?;
+    let port_id: PortId = validate::parse("port_id", &port_id, validate::MAX_IBC_ID_LEN)?;
+    let channel_id: ChannelId =
+        validate::parse("channel_id", &channel_id, validate::MAX_IBC_ID_LEN)?;
     let ibc_shielding_data = match shielding_data {
         Some(v) => Some(IbcShieldingData::try_from_slice(&v)?),
         None => None,
@@ -817,15 +1445,15 @@ pub fn ibc_transfer_tx_args(
     Ok(args)
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Deserialize, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct EthBridgeTransferMsg {
     nut: bool,
     asset: String,
     recipient: String,
     sender: String,
-    amount: String,
-    fee_amount: String,
+    amount: StringAmount,
+    fee_amount: StringAmount,
     fee_payer: Option<String>,
     fee_token: String,
 }
@@ -847,16 +1475,18 @@ pub fn eth_bridge_transfer_tx_args(
     } = eth_bridge_transfer_msg;
 
     let tx = tx_msg_into_args(tx_msg)?;
-    let asset = EthAddress::from_str(&asset).map_err(|e| JsError::new(&format!("{}", e)))?;
-    let recipient =
-        EthAddress::from_str(&recipient).map_err(|e| JsError::new(&format!("{}", e)))?;
-    let sender = Address::from_str(&sender)?;
-    let denom_amount = DenominatedAmount::from_str(&amount).expect("Amount to be valid.");
+    let asset: EthAddress = validate::parse("asset", &asset, validate::MAX_ADDRESS_LEN)?;
+    let recipient: EthAddress =
+        validate::parse("recipient", &recipient, validate::MAX_ADDRESS_LEN)?;
+    let sender = validate::address("sender", &sender)?;
+    let denom_amount = amount.to_denominated()?;
     let amount = InputAmount::Unvalidated(denom_amount);
-    let denom_amount = DenominatedAmount::from_str(&fee_amount).expect("Amount to be valid.");
+    let denom_amount = fee_amount.to_denominated()?;
     let fee_amount = InputAmount::Unvalidated(denom_amount);
-    let fee_payer = fee_payer.map(|v| Address::from_str(&v)).transpose()?;
-    let fee_token = Address::from_str(&fee_token)?;
+    let fee_payer = fee_payer
+        .map(|fee_payer| validate::address("fee_payer", &fee_payer))
+        .transpose()?;
+    let fee_token = validate::address("fee_token", &fee_token)?;
     let code_path = PathBuf::from("tx_bridge_pool.wasm");
 
     let args = args::EthereumBridgePool {
@@ -881,6 +1511,43 @@ pub fn tx_args_from_slice(tx_msg_bytes: &[u8]) -> Result<args::Tx, JsError> {
     Ok(args)
 }
 
+/// Returns the address paying the fee of the wrapper, the fee payer if set, else the signer
+pub fn fee_payer(tx: &args::Tx) -> Option<Address> {
+    tx.wrapper_fee_payer
+        .as_ref()
+        .or(tx.signing_keys.first())
+        .map(Address::from)
+}
+
+/// Returns the fee of the wrapper, gas limit times the gas price, if it is paid in the token
+///
+/// # Arguments
+///
+/// * `tx` - args of the wrapper
+/// * `token` - token the fee could be paid in
+/// * `denom` - denomination of the token
+///
+/// # Errors
+///
+/// Returns JsError if the gas price has more decimal places than the token or the fee overflows
+pub fn fee_in_token(
+    tx: &args::Tx,
+    token: &Address,
+    denom: Denomination,
+) -> Result<Option<Amount>, JsError> {
+    match &tx.fee_amount {
+        Some(InputAmount::Unvalidated(fee_per_gas)) if tx.fee_token == *token => Ok(Some(
+            fee_per_gas
+                .increase_precision(denom)
+                .map_err(|err| JsError::new(&format!("Invalid fee: {}", err)))?
+                .amount()
+                .checked_mul(Amount::from_u64(u64::from(tx.gas_limit)))
+                .ok_or_else(|| JsError::new("Invalid fee: overflow"))?,
+        )),
+        _ => Ok(None),
+    }
+}
+
 /// Maps serialized tx_msg into Tx args.
 /// This is common for all tx types.
 ///
@@ -901,22 +1568,29 @@ fn tx_msg_into_args(tx_msg: &[u8]) -> Result<args::Tx, JsError> {
         public_key,
         memo,
         force,
+        expiration,
     } = tx_msg;
 
-    let token = Address::from_str(&token)?;
+    let token = validate::address("token", &token)?;
+    let chain_id = validate::chain_id("chain_id", &chain_id)?;
+    // At most the 20 digits of u64::MAX
+    let gas_limit: GasLimit = validate::parse("gas_limit", &gas_limit, 20)?;
 
-    let fee_amount = DenominatedAmount::from_str(&fee_amount)
-        .unwrap_or_else(|_| panic!("Fee amount has to be valid. Received {}", fee_amount));
+    let fee_amount = fee_amount.to_denominated()?;
     let fee_input_amount = InputAmount::Unvalidated(fee_amount);
 
-    let public_key = match public_key {
-        Some(v) => {
-            let pk = PublicKey::from_str(&v)?;
-            Some(pk)
-        }
-        _ => None,
+    let expiration = match expiration {
+        Some(expiration) => TxExpiration::Custom(
+            DateTimeUtc::from_str(&expiration)
+                .map_err(|_| JsError::new(&format!("Invalid expiration: {}", expiration)))?,
+        ),
+        None => TxExpiration::Default,
     };
 
+    let public_key = public_key
+        .map(|public_key| validate::public_key("public_key", &public_key))
+        .transpose()?;
+
     let signing_keys: Vec<PublicKey> = match public_key {
         Some(v) => vec![v.clone()],
         _ => vec![],
@@ -926,7 +1600,10 @@ fn tx_msg_into_args(tx_msg: &[u8]) -> Result<args::Tx, JsError> {
     // We can leave it as whatever as long as it's valid url.
     let ledger_address = tendermint_rpc::Url::from_str("http://notinuse:13337").unwrap();
 
-    let memo = memo.map(|v| v.as_bytes().to_vec());
+    let memo = memo
+        .map(|memo| validate::memo("memo", &memo))
+        .transpose()?
+        .map(|memo| memo.as_bytes().to_vec());
 
     let force = force.unwrap_or(false);
 
@@ -942,11 +1619,11 @@ fn tx_msg_into_args(tx_msg: &[u8]) -> Result<args::Tx, JsError> {
         initialized_account_alias: None,
         fee_amount: Some(fee_input_amount),
         fee_token: token.clone(),
-        gas_limit: GasLimit::from_str(&gas_limit).expect("Gas limit to be valid"),
+        gas_limit,
         wrapper_fee_payer: None,
         output_folder: None,
-        expiration: TxExpiration::Default,
-        chain_id: Some(ChainId(chain_id)),
+        expiration,
+        chain_id: Some(chain_id),
         signatures: vec![],
         wrapper_signature: None,
         signing_keys,
@@ -0,0 +1,145 @@
+//! Timing benches of the wasm critical paths, enabled by the `bench` feature:
+//! `wasm-pack test --node --release -- --features nodejs,bench`
+//! Compare the reported times before and after bumping namada to catch perf regressions.
+use std::str::FromStr;
+
+use namada_sdk::borsh;
+use namada_sdk::chain::ChainId;
+use namada_sdk::key::{common, ed25519, RefTo, SigScheme};
+use namada_sdk::masp_primitives::asset_type::AssetType;
+use namada_sdk::masp_primitives::consensus::{BlockHeight, MainNetwork};
+use namada_sdk::masp_primitives::memo::MemoBytes;
+use namada_sdk::masp_primitives::merkle_tree::{CommitmentTree, IncrementalWitness};
+use namada_sdk::masp_primitives::sapling::note_encryption::{
+    sapling_note_encryption, try_sapling_compact_note_decryption, PreparedIncomingViewingKey,
+    COMPACT_NOTE_SIZE,
+};
+use namada_sdk::masp_primitives::sapling::{Node, Rseed};
+use namada_sdk::masp_primitives::transaction::components::sapling::CompactOutputDescription;
+use namada_sdk::masp_primitives::zip32::{ExtendedFullViewingKey, ExtendedSpendingKey};
+use namada_sdk::tx::Tx;
+use wasm_bindgen_test::*;
+
+use super::args;
+use super::signature::{append_signature, SignatureMsg};
+
+const SECRET_KEY: &str = "1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93";
+const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+// Runs `f` `iterations` times and logs the average time per iteration
+fn bench<T>(name: &str, iterations: u32, mut f: impl FnMut() -> T) {
+    // Warm up, so lazily initialized statics are not measured
+    std::hint::black_box(f());
+
+    let start = js_sys::Date::now();
+    for _ in 0..iterations {
+        std::hint::black_box(f());
+    }
+    let micros = (js_sys::Date::now() - start) * 1000.0 / iterations as f64;
+
+    console_log!(
+        "bench {}: {:.1}µs/iter ({} iterations)",
+        name,
+        micros,
+        iterations
+    );
+}
+
+fn wrapper_tx_msg() -> Vec<u8> {
+    let json = format!(
+        r#"{{ "token": "{}", "fee_amount": "0.000001", "gas_limit": "50000", "chain_id": "bench" }}"#,
+        NAM
+    );
+    args::json_to_borsh::<args::WrapperTxMsg>(&json).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn bench_tx_msg_decode() {
+    let wrapper_tx_msg = wrapper_tx_msg();
+    let data = format!(
+        r#"{{ "source": "{alice}", "target": "{alice}", "token": "{nam}", "amount": "1.5" }}"#,
+        alice = ALICE,
+        nam = NAM
+    );
+    let json = format!(r#"{{ "data": [{}] }}"#, vec![data; 32].join(","));
+    let transfer_msg = args::json_to_borsh::<args::TransparentTransferMsg>(&json).unwrap();
+
+    bench("wrapper_tx_msg_decode", 10_000, || {
+        args::tx_args_from_slice(&wrapper_tx_msg).unwrap()
+    });
+    bench("transparent_transfer_msg_decode", 1_000, || {
+        args::transparent_transfer_tx_args(&transfer_msg, &wrapper_tx_msg).unwrap()
+    });
+}
+
+#[wasm_bindgen_test]
+fn bench_signature_construction() {
+    let secret = common::SecretKey::Ed25519(ed25519::SecretKey::from_str(SECRET_KEY).unwrap());
+    let mut tx = Tx::new(ChainId::default(), None);
+    tx.add_serialized_data(vec![0; 1024]);
+    let signature = common::SigScheme::sign(&secret, tx.raw_header_hash());
+
+    let pubkey = borsh::to_vec(&secret.ref_to()).unwrap();
+    let signature = borsh::to_vec(&signature).unwrap();
+
+    bench("append_signature", 1_000, || {
+        let mut tx = tx.clone();
+        let sig_msg = SignatureMsg {
+            pubkey: pubkey.clone(),
+            raw_indices: vec![0, 1],
+            raw_signature: signature.clone(),
+            wrapper_indices: vec![0, 1],
+            wrapper_signature: signature.clone(),
+        };
+        append_signature(&mut tx, sig_msg).unwrap();
+        tx
+    });
+}
+
+#[wasm_bindgen_test]
+fn bench_note_decryption() {
+    let xsk = ExtendedSpendingKey::master(b"bench");
+    let xfvk = ExtendedFullViewingKey::from(&xsk);
+    let payment_address = xfvk.default_address().1;
+    let ivk = PreparedIncomingViewingKey::new(&xfvk.fvk.vk.ivk());
+
+    let asset_type = AssetType::new(b"bench").unwrap();
+    let note = payment_address
+        .create_note(asset_type, 1_000_000, Rseed::AfterZip212([7; 32]))
+        .unwrap();
+    let cmu = note.cmu();
+    let encryption =
+        sapling_note_encryption::<MainNetwork>(None, note, payment_address, MemoBytes::empty());
+    let output = CompactOutputDescription {
+        ephemeral_key: encryption.epk().to_bytes().into(),
+        cmu,
+        enc_ciphertext: encryption.encrypt_note_plaintext()[..COMPACT_NOTE_SIZE]
+            .try_into()
+            .unwrap(),
+    };
+
+    bench("note_decryption", 100, || {
+        try_sapling_compact_note_decryption(&MainNetwork, BlockHeight::from(1), &ivk, &output)
+            .unwrap()
+    });
+}
+
+#[wasm_bindgen_test]
+fn bench_witness_updates() {
+    let node = |i: u64| {
+        let mut repr = [0u8; 32];
+        repr[..8].copy_from_slice(&i.to_le_bytes());
+        Node::new(repr)
+    };
+    let mut tree = CommitmentTree::<Node>::empty();
+    tree.append(node(0)).unwrap();
+    let mut witness = IncrementalWitness::from_tree(&tree);
+    let mut i = 0;
+
+    bench("witness_update", 1_000, || {
+        i += 1;
+        witness.append(node(i)).unwrap();
+        witness.root()
+    });
+}
@@ -0,0 +1,225 @@
+//! Custom tx types - registry of app specific txs. An embedding application registers the hash of
+//! its tx code with a codec and a display formatter, and `build_tx_json`, `process_tx` and
+//! `deserialize_tx` handle its txs like the built in ones.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use gloo_utils::format::JsValueSerdeExt;
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSchema, BorshSerialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::utils::to_js_result;
+
+// Tx types of `Sdk::build_tx_json`, custom types can't shadow them
+const BUILT_IN_TX_TYPES: [&str; 15] = [
+    "bond",
+    "unbond",
+    "withdraw",
+    "redelegate",
+    "claim_rewards",
+    "vote_proposal",
+    "transparent_transfer",
+    "multi_transfer",
+    "multi_source_transfer",
+    "shielded_transfer",
+    "shielding_transfer",
+    "unshielding_transfer",
+    "ibc_transfer",
+    "eth_bridge_transfer",
+    "reveal_pk",
+];
+
+thread_local! {
+    // Shared by the Sdk tx builders and tx deserialization
+    static CUSTOM_TX_TYPES: RefCell<BTreeMap<String, CustomTxType>> =
+        RefCell::new(BTreeMap::new());
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomTxType {
+    pub name: String,
+    // Lowercase hex encoded hash of the tx code
+    pub code_hash: String,
+    // Called with the parsed JSON msg, returns the tx data as Uint8Array
+    encode: js_sys::Function,
+    // Called with the tx data, returns a JSON serializable value shown to the user
+    format: Option<js_sys::Function>,
+}
+
+impl CustomTxType {
+    /// Encodes a JSON msg into tx data with the codec callback
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is invalid, or the callback throws or doesn't return bytes
+    pub fn encode(&self, tx_msg: &str) -> Result<Vec<u8>, JsError> {
+        let msg = js_sys::JSON::parse(tx_msg)
+            .map_err(|_| JsError::new(&format!("Invalid {} msg JSON", self.name)))?;
+        let data = self
+            .encode
+            .call1(&JsValue::NULL, &msg)
+            .map_err(|_| JsError::new(&format!("Encoding {} msg failed", self.name)))?;
+
+        data.dyn_into::<js_sys::Uint8Array>()
+            .map(|data| data.to_vec())
+            .map_err(|_| JsError::new("Tx codec has to return a Uint8Array"))
+    }
+
+    /// Returns the display value of the tx data, null if the type has no formatter
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the formatter throws or its result is not JSON serializable
+    pub fn format(&self, data: &[u8]) -> Result<serde_json::Value, JsError> {
+        let format = match &self.format {
+            Some(format) => format,
+            None => return Ok(serde_json::Value::Null),
+        };
+        let display = format
+            .call1(&JsValue::NULL, &js_sys::Uint8Array::from(data))
+            .map_err(|_| JsError::new(&format!("Formatting {} data failed", self.name)))?;
+
+        Ok(display.into_serde()?)
+    }
+
+    /// Returns the decoded inner tx, as stored in `deserialize_tx` commitments
+    pub fn decode(&self, data: &[u8]) -> Result<CustomTxMsg, JsError> {
+        Ok(CustomTxMsg {
+            name: self.name.clone(),
+            data: data.to_vec(),
+            display: self.format(data)?.to_string(),
+        })
+    }
+}
+
+/// Inner tx of a custom type, Borsh serialized as data of `TxType::Custom` commitments
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Debug)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct CustomTxMsg {
+    name: String,
+    data: Vec<u8>,
+    // JSON returned by the formatter
+    display: String,
+}
+
+impl CustomTxMsg {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsError> {
+        Ok(borsh::to_vec(self)?)
+    }
+}
+
+/// Returns the lowercase hex encoded code hash, validating the name of the type
+///
+/// # Errors
+///
+/// Returns an error if the name is empty or taken by a built in tx type, or the hash is not a hex
+/// encoded 32 bytes hash
+pub fn validate_tx_type(name: &str, code_hash: &str) -> Result<String, String> {
+    if name.is_empty() || BUILT_IN_TX_TYPES.contains(&name) {
+        return Err(format!("Invalid custom tx type name: {}", name));
+    }
+
+    let code_hash = code_hash.trim_start_matches("0x").to_lowercase();
+    if code_hash.len() != 64 || !code_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid code hash: {}", code_hash));
+    }
+
+    Ok(code_hash)
+}
+
+/// Registers a custom tx type, replacing a type of the same name
+///
+/// # Arguments
+///
+/// * `name` - tx type passed to `build_tx_json`
+/// * `code_hash` - hex encoded hash of the tx code, the code has to be stored on chain
+/// * `encode` - called with the JSON msg, returns the tx data as Uint8Array
+/// * `format` - called with the tx data, returns the value shown in sign docs and tx details
+///
+/// # Errors
+///
+/// Returns an error if the name or hash is invalid, or the hash is registered by another type
+#[wasm_bindgen]
+pub fn register_tx_type(
+    name: String,
+    code_hash: String,
+    encode: js_sys::Function,
+    format: Option<js_sys::Function>,
+) -> Result<(), JsError> {
+    let code_hash = validate_tx_type(&name, &code_hash).map_err(|err| JsError::new(&err))?;
+
+    CUSTOM_TX_TYPES.with(|tx_types| {
+        let mut tx_types = tx_types.borrow_mut();
+        if let Some(other) = tx_types
+            .values()
+            .find(|tx_type| tx_type.code_hash == code_hash && tx_type.name != name)
+        {
+            return Err(JsError::new(&format!(
+                "Code hash is already registered by {}",
+                other.name
+            )));
+        }
+
+        tx_types.insert(
+            name.clone(),
+            CustomTxType {
+                name,
+                code_hash,
+                encode,
+                format,
+            },
+        );
+        Ok(())
+    })
+}
+
+/// Removes a custom tx type, returns false if it was not registered
+#[wasm_bindgen]
+pub fn unregister_tx_type(name: &str) -> bool {
+    CUSTOM_TX_TYPES.with(|tx_types| tx_types.borrow_mut().remove(name).is_some())
+}
+
+/// Returns names of the registered custom tx types
+#[wasm_bindgen]
+pub fn registered_tx_types() -> Result<JsValue, JsError> {
+    let names: Vec<String> =
+        CUSTOM_TX_TYPES.with(|tx_types| tx_types.borrow().keys().cloned().collect());
+
+    to_js_result(names)
+}
+
+pub fn custom_tx_type(name: &str) -> Option<CustomTxType> {
+    CUSTOM_TX_TYPES.with(|tx_types| tx_types.borrow().get(name).cloned())
+}
+
+pub fn custom_tx_type_by_hash(code_hash: &str) -> Option<CustomTxType> {
+    let code_hash = code_hash.to_lowercase();
+
+    CUSTOM_TX_TYPES.with(|tx_types| {
+        tx_types
+            .borrow()
+            .values()
+            .find(|tx_type| tx_type.code_hash == code_hash)
+            .cloned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const CODE_HASH: &str = "0x8F3C6B2E9A1D4F7B0C5E8A2D6F9B3E1C7A4D0F8B2E5C9A6D3F1B7E4C0A8D2F6B";
+
+    #[wasm_bindgen_test]
+    fn validates_custom_tx_types() {
+        assert_eq!(
+            validate_tx_type("app_swap", CODE_HASH).unwrap(),
+            CODE_HASH[2..].to_lowercase()
+        );
+        assert!(validate_tx_type("bond", CODE_HASH).is_err());
+        assert!(validate_tx_type("", CODE_HASH).is_err());
+        assert!(validate_tx_type("app_swap", "0xabc").is_err());
+    }
+}
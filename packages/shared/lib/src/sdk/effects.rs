@@ -0,0 +1,183 @@
+//! Tx effects - balance and bond changes a tx would cause.
+//! Changes are taken from the tx data and kept only if the dry-run on the node reports the
+//! corresponding storage key as changed.
+use std::collections::BTreeSet;
+
+use namada_sdk::address::Address;
+use namada_sdk::proof_of_stake::storage_key::bond_key;
+use namada_sdk::proof_of_stake::types::BondId;
+use namada_sdk::state::Key;
+use namada_sdk::token::storage_key::balance_key;
+use namada_sdk::token::Transfer;
+use namada_sdk::tx::data::pos::{Bond, Redelegation, Unbond};
+use serde::Serialize;
+use tsify::Tsify;
+
+use super::transaction::TransactionKind;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct TxEffect {
+    pub address: String,
+    // One of "balance", "bond"
+    pub kind: String,
+    // Token for balance changes, validator for bond changes
+    pub target: String,
+    // Signed amount, e.g. "-10"
+    pub delta: String,
+}
+
+struct Change {
+    key: Key,
+    effect: TxEffect,
+}
+
+fn balance_change(token: &Address, owner: &Address, sign: &str, amount: String) -> Change {
+    Change {
+        key: balance_key(token, owner),
+        effect: TxEffect {
+            address: owner.to_string(),
+            kind: String::from("balance"),
+            target: token.to_string(),
+            delta: format!("{}{}", sign, amount),
+        },
+    }
+}
+
+fn bond_change(source: &Address, validator: &Address, sign: &str, amount: String) -> Change {
+    let bond_id = BondId {
+        source: source.clone(),
+        validator: validator.clone(),
+    };
+
+    Change {
+        key: bond_key(&bond_id),
+        effect: TxEffect {
+            address: source.to_string(),
+            kind: String::from("bond"),
+            target: validator.to_string(),
+            delta: format!("{}{}", sign, amount),
+        },
+    }
+}
+
+fn changes(kind: &TransactionKind, native_token: &Address) -> Vec<Change> {
+    match kind {
+        TransactionKind::Transfer(Transfer {
+            sources, targets, ..
+        }) => sources
+            .iter()
+            .map(|(account, amount)| {
+                balance_change(&account.token, &account.owner, "-", amount.to_string())
+            })
+            .chain(targets.iter().map(|(account, amount)| {
+                balance_change(&account.token, &account.owner, "", amount.to_string())
+            }))
+            .collect(),
+        TransactionKind::Bond(Bond {
+            amount,
+            validator,
+            source,
+        }) => {
+            // Validator self-bond if source is not provided
+            let source = source.as_ref().unwrap_or(validator);
+            let amount = amount.to_string_native();
+
+            vec![
+                balance_change(native_token, source, "-", amount.clone()),
+                bond_change(source, validator, "", amount),
+            ]
+        }
+        TransactionKind::Unbond(Unbond {
+            amount,
+            validator,
+            source,
+        }) => {
+            let source = source.as_ref().unwrap_or(validator);
+
+            vec![bond_change(
+                source,
+                validator,
+                "-",
+                amount.to_string_native(),
+            )]
+        }
+        TransactionKind::Redelegation(Redelegation {
+            owner,
+            src_validator,
+            dest_validator,
+            amount,
+        }) => {
+            let amount = amount.to_string_native();
+
+            vec![
+                bond_change(owner, src_validator, "-", amount.clone()),
+                bond_change(owner, dest_validator, "", amount),
+            ]
+        }
+        _ => vec![],
+    }
+}
+
+/// Returns effects of the tx whose storage keys were changed by the dry-run.
+/// Bonds are stored per epoch, so bond keys are matched by prefix.
+///
+/// # Arguments
+///
+/// * `kinds` - decoded inner txs
+/// * `changed_keys` - keys changed by the dry-run
+/// * `native_token` - address of the native token
+pub fn tx_effects(
+    kinds: &[TransactionKind],
+    changed_keys: &BTreeSet<Key>,
+    native_token: &Address,
+) -> Vec<TxEffect> {
+    kinds
+        .iter()
+        .flat_map(|kind| changes(kind, native_token))
+        .filter(|change| {
+            let prefix = change.key.to_string();
+            changed_keys
+                .iter()
+                .any(|key| key == &change.key || key.to_string().starts_with(&prefix))
+        })
+        .map(|change| change.effect)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::address::{GOV, PGF};
+    use namada_sdk::token::Amount;
+    use std::str::FromStr;
+    use wasm_bindgen_test::*;
+
+    // Any addresses will do, the effects are not validated against the chain
+    fn nam() -> Address {
+        GOV
+    }
+
+    #[wasm_bindgen_test]
+    fn keeps_only_changed_effects() {
+        let source = Address::from_str("tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp").unwrap();
+        let validator = PGF;
+        let bond = TransactionKind::Bond(Bond {
+            amount: Amount::native_whole(5),
+            validator: validator.clone(),
+            source: Some(source.clone()),
+        });
+        let changed_keys = BTreeSet::from([balance_key(&nam(), &source)]);
+
+        let effects = tx_effects(&[bond], &changed_keys, &nam());
+
+        assert_eq!(
+            effects,
+            vec![TxEffect {
+                address: source.to_string(),
+                kind: String::from("balance"),
+                target: nam().to_string(),
+                delta: format!("-{}", Amount::native_whole(5).to_string_native()),
+            }]
+        );
+    }
+}
@@ -1,10 +1,11 @@
 use crate::utils::to_js_result;
 use serde::Serialize;
+use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 use web_sys::{CustomEvent, CustomEventInit, WorkerGlobalScope};
 
-#[wasm_bindgen]
-#[derive(Debug, Serialize)]
+/// Detail of the progress bar started event, passed as JSON text
+#[derive(Debug, Serialize, Tsify)]
 pub struct ProgressStart {
     name: String,
 }
@@ -16,8 +17,8 @@ impl ProgressStart {
     }
 }
 
-#[wasm_bindgen]
-#[derive(Debug, Serialize)]
+/// Detail of the progress bar finished event
+#[derive(Debug, Serialize, Tsify)]
 pub struct ProgressFinish {
     name: String,
 }
@@ -29,8 +30,8 @@ impl ProgressFinish {
     }
 }
 
-#[wasm_bindgen]
-#[derive(Debug, Serialize)]
+/// Detail of the progress bar incremented event
+#[derive(Debug, Serialize, Tsify)]
 pub struct ProgressIncrement {
     name: String,
     current: usize,
@@ -44,10 +45,27 @@ impl ProgressIncrement {
     }
 }
 
+/// Detail of the balance changed event
+#[derive(Debug, Serialize, Tsify)]
+pub struct BalanceChanged {
+    owner: String,
+    token: String,
+    previous: Option<String>,
+    current: String,
+}
+
+impl BalanceChanged {
+    pub fn to_json(&self) -> JsValue {
+        let json = serde_json::to_value(&self).unwrap();
+        JsValue::from_str(&json.to_string())
+    }
+}
+
 /// Event constants for use in Rust
 pub const SDK_EVENT_PROGRESS_BAR_STARTED: &str = "namada_sdk::progress_bar::started";
 pub const SDK_EVENT_PROGRESS_BAR_INCREMENTED: &str = "namada_sdk::progress_bar::incremented";
 pub const SDK_EVENT_PROGRESS_BAR_FINISHED: &str = "namada_sdk::progress_bar::finished";
+pub const SDK_EVENT_BALANCE_CHANGED: &str = "namada_sdk::balance::changed";
 
 // This will generate proper enum in TypeScript, the downisde is that we need to copy the values.
 // Unfortunately we can't use macros here.
@@ -57,6 +75,7 @@ export enum SdkEvents {
     ProgressBarStarted = "namada_sdk::progress_bar::started",
     ProgressBarIncremented = "namada_sdk::progress_bar::incremented",
     ProgressBarFinished = "namada_sdk::progress_bar::finished",
+    BalanceChanged = "namada_sdk::balance::changed",
 }
 "#;
 
@@ -82,6 +101,12 @@ impl SdkEvents {
     pub fn ProgressBarFinished() -> String {
         SDK_EVENT_PROGRESS_BAR_FINISHED.to_string()
     }
+
+    #[allow(non_snake_case)]
+    #[wasm_bindgen(getter)]
+    pub fn BalanceChanged() -> String {
+        SDK_EVENT_BALANCE_CHANGED.to_string()
+    }
 }
 
 pub struct EventDispatcher {
@@ -148,4 +173,26 @@ impl EventDispatcher {
 
         self.dispatch_custom_event(event)
     }
+
+    pub fn balance_changed(
+        &self,
+        owner: String,
+        token: String,
+        previous: Option<String>,
+        current: String,
+    ) -> Result<JsValue, JsError> {
+        let changed = BalanceChanged {
+            owner,
+            token,
+            previous,
+            current,
+        };
+        let mut options = CustomEventInit::new();
+        options.detail(&changed.to_json());
+
+        let event =
+            CustomEvent::new_with_event_init_dict(SDK_EVENT_BALANCE_CHANGED, &options).unwrap();
+
+        self.dispatch_custom_event(event)
+    }
 }
@@ -0,0 +1,142 @@
+//! Expired txs - rebuilds a signed tx rejected because of its expiration, e.g. after the device
+//! slept between signing and submitting. The code and data sections are kept as they are, so the
+//! rebuilt tx does the same as the expired one.
+use namada_sdk::time::DateTimeUtc;
+use namada_sdk::tx::{data::TxType, Section, Tx};
+use thiserror::Error;
+
+use super::args::WrapperTxMsg;
+use crate::types::amount::StringAmount;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpiredTxError {
+    #[error("Invalid transaction type!")]
+    NotWrapper,
+    #[error("Tx has no expiration")]
+    NoExpiration,
+    #[error("Tx expires at {0}, it can still be submitted")]
+    NotExpired(String),
+    #[error("Shielded txs can't be rebuilt, build them again from the msg")]
+    Shielded,
+}
+
+/// Returns the tx with a new timestamp and expiration and without signatures. The expiration is
+/// moved by the time passed since the tx was built, so the tx is valid for as long as before.
+///
+/// # Arguments
+///
+/// * `tx` - signed tx
+/// * `now` - current time
+///
+/// # Errors
+///
+/// Returns an error if the tx is not an expired wrapper, or has MASP sections bound to a note
+/// commitment tree which is outdated by now
+pub fn rebuild(tx: &Tx, now: DateTimeUtc) -> Result<Tx, ExpiredTxError> {
+    if !matches!(tx.header.tx_type, TxType::Wrapper(_)) {
+        return Err(ExpiredTxError::NotWrapper);
+    }
+    let expiration = tx.header.expiration.ok_or(ExpiredTxError::NoExpiration)?;
+    if expiration.0 > now.0 {
+        return Err(ExpiredTxError::NotExpired(expiration.to_string()));
+    }
+    if tx
+        .sections
+        .iter()
+        .any(|section| matches!(section, Section::MaspTx(_)))
+    {
+        return Err(ExpiredTxError::Shielded);
+    }
+
+    let mut rebuilt = tx.clone();
+    // Both the inner and wrapper signatures cover the header, none of them stays valid
+    rebuilt
+        .sections
+        .retain(|section| !matches!(section, Section::Authorization(_)));
+    rebuilt.header.timestamp = now;
+    rebuilt.header.expiration = Some(DateTimeUtc(now.0 + (expiration.0 - tx.header.timestamp.0)));
+
+    Ok(rebuilt)
+}
+
+/// Returns the wrapper msg the tx would be built with, for the signing data of the rebuilt tx
+///
+/// # Errors
+///
+/// Returns an error if the tx is not a wrapper
+pub fn wrapper_tx_msg(tx: &Tx) -> Result<WrapperTxMsg, ExpiredTxError> {
+    let wrapper = match &tx.header.tx_type {
+        TxType::Wrapper(wrapper) => wrapper,
+        _ => return Err(ExpiredTxError::NotWrapper),
+    };
+
+    Ok(WrapperTxMsg::new(
+        wrapper.fee.token.to_string(),
+        StringAmount::from(wrapper.fee.amount_per_gas_unit),
+        u64::from(wrapper.gas_limit).to_string(),
+        tx.header.chain_id.to_string(),
+        Some(wrapper.pk.to_string()),
+        None,
+        None,
+        tx.header
+            .expiration
+            .map(|expiration| expiration.to_string()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use namada_sdk::account::AccountPublicKeysMap;
+    use namada_sdk::address::Address;
+    use namada_sdk::chain::ChainId;
+    use namada_sdk::key::{common, ed25519, RefTo};
+    use namada_sdk::token::DenominatedAmount;
+    use namada_sdk::tx::data::{Fee, GasLimit};
+    use namada_sdk::tx::Data;
+    use wasm_bindgen_test::*;
+
+    const SECRET_KEY: &str = "1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    fn time(time: &str) -> DateTimeUtc {
+        DateTimeUtc::from_str(time).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn rebuilds_expired_txs() {
+        let key = common::SecretKey::Ed25519(ed25519::SecretKey::from_str(SECRET_KEY).unwrap());
+        let mut tx = Tx::new(ChainId::default(), Some(time("2024-01-01T10:00:00Z")));
+        tx.header.timestamp = time("2024-01-01T09:00:00Z");
+        tx.set_data(Data::new(vec![1]));
+        tx.add_wrapper(
+            Fee {
+                amount_per_gas_unit: DenominatedAmount::from_str("0.000001").unwrap(),
+                token: Address::from_str(NAM).unwrap(),
+            },
+            key.ref_to(),
+            GasLimit::from(50_000),
+        );
+        tx.sign_raw(
+            vec![key.clone()],
+            AccountPublicKeysMap::from_iter(vec![key.ref_to()]),
+            None,
+        );
+        tx.sign_wrapper(key.clone());
+
+        assert_eq!(
+            rebuild(&tx, time("2024-01-01T09:30:00Z")).unwrap_err(),
+            ExpiredTxError::NotExpired(String::from("2024-01-01T10:00:00+00:00"))
+        );
+
+        let rebuilt = rebuild(&tx, time("2024-01-02T08:00:00Z")).unwrap();
+        assert_eq!(
+            rebuilt.header.expiration,
+            Some(time("2024-01-02T09:00:00Z"))
+        );
+        // Only the inner and wrapper signatures are removed
+        assert_eq!(rebuilt.sections.len(), tx.sections.len() - 2);
+    }
+}
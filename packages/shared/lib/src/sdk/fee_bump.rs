@@ -0,0 +1,169 @@
+//! Fee bumping - raises the gas price of a signed tx stuck in the mempool. Only the wrapper is
+//! signed again, signatures of the inner txs don't sign the wrapper header and stay valid.
+use namada_sdk::key::common::PublicKey;
+use namada_sdk::token::{Amount, DenominatedAmount};
+use namada_sdk::tx::{data::TxType, Section, Tx};
+use thiserror::Error;
+
+// Without a configured maximum a bump can raise the fee at most this many times
+pub const DEFAULT_MAX_BUMP_FACTOR: u64 = 10;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FeeBumpError {
+    #[error("Invalid transaction type!")]
+    NotWrapper,
+    #[error("Fee payer is {0}, not the owner of the signing key")]
+    WrongFeePayer(String),
+    #[error("New fee {new} per gas unit has to be higher than {current}")]
+    FeeNotHigher { current: String, new: String },
+    #[error("New fee {new} per gas unit is higher than the maximum {max}")]
+    FeeTooHigh { max: String, new: String },
+    #[error("Invalid fee amount: {0}")]
+    InvalidAmount(String),
+}
+
+/// Sets the fee per gas unit of the wrapper and removes its signatures. The tx has to be signed
+/// with `sign_wrapper` by the fee payer afterwards.
+///
+/// # Arguments
+///
+/// * `tx` - signed tx
+/// * `amount_per_gas_unit` - new fee per gas unit, in the fee token
+/// * `fee_payer` - public key of the key the wrapper is signed with
+/// * `max_per_gas_unit` - highest allowed fee per gas unit, `DEFAULT_MAX_BUMP_FACTOR` times the
+///   current fee if None
+///
+/// # Errors
+///
+/// Returns an error if the tx is not a wrapper, the key is not the fee payer or the fee is not
+/// higher than the current one or higher than the maximum
+pub fn bump_fee(
+    tx: &mut Tx,
+    amount_per_gas_unit: DenominatedAmount,
+    fee_payer: &PublicKey,
+    max_per_gas_unit: Option<DenominatedAmount>,
+) -> Result<(), FeeBumpError> {
+    let header_hash = tx.header_hash();
+    let wrapper = match &mut tx.header.tx_type {
+        TxType::Wrapper(wrapper) => wrapper,
+        _ => return Err(FeeBumpError::NotWrapper),
+    };
+    if &wrapper.pk != fee_payer {
+        return Err(FeeBumpError::WrongFeePayer(wrapper.pk.to_string()));
+    }
+
+    let current = wrapper.fee.amount_per_gas_unit;
+    if !is_higher(&current, &amount_per_gas_unit)? {
+        return Err(FeeBumpError::FeeNotHigher {
+            current: current.to_string(),
+            new: amount_per_gas_unit.to_string(),
+        });
+    }
+    let max = match max_per_gas_unit {
+        Some(max) => max,
+        None => default_max(&current)?,
+    };
+    if is_higher(&max, &amount_per_gas_unit)? {
+        return Err(FeeBumpError::FeeTooHigh {
+            max: max.to_string(),
+            new: amount_per_gas_unit.to_string(),
+        });
+    }
+    wrapper.fee.amount_per_gas_unit = amount_per_gas_unit;
+
+    // Wrapper signatures cover the header, inner tx signatures only the raw header
+    tx.sections.retain(|section| match section {
+        Section::Authorization(authorization) => !authorization.targets.contains(&header_hash),
+        _ => true,
+    });
+
+    Ok(())
+}
+
+// Compares amounts of possibly different denominations
+fn is_higher(current: &DenominatedAmount, new: &DenominatedAmount) -> Result<bool, FeeBumpError> {
+    let denom = current.denom().max(new.denom());
+    let scaled = |amount: &DenominatedAmount| {
+        amount
+            .increase_precision(denom)
+            .map(|amount| amount.amount())
+            .map_err(|err| FeeBumpError::InvalidAmount(err.to_string()))
+    };
+
+    Ok(scaled(new)? > scaled(current)?)
+}
+
+fn default_max(current: &DenominatedAmount) -> Result<DenominatedAmount, FeeBumpError> {
+    let amount = current
+        .amount()
+        .checked_mul(Amount::from_u64(DEFAULT_MAX_BUMP_FACTOR))
+        .ok_or_else(|| FeeBumpError::InvalidAmount(current.to_string()))?;
+
+    Ok(DenominatedAmount::new(amount, current.denom()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use namada_sdk::account::AccountPublicKeysMap;
+    use namada_sdk::address::Address;
+    use namada_sdk::chain::ChainId;
+    use namada_sdk::key::{common, ed25519, RefTo};
+    use namada_sdk::tx::data::{Fee, GasLimit};
+    use namada_sdk::tx::Data;
+    use wasm_bindgen_test::*;
+
+    const SECRET_KEY: &str = "1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    fn amount(amount: &str) -> DenominatedAmount {
+        DenominatedAmount::from_str(amount).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn replaces_only_the_wrapper_signature() {
+        let key = common::SecretKey::Ed25519(ed25519::SecretKey::from_str(SECRET_KEY).unwrap());
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.set_data(Data::new(vec![1]));
+        tx.add_wrapper(
+            Fee {
+                amount_per_gas_unit: amount("0.000001"),
+                token: Address::from_str(NAM).unwrap(),
+            },
+            key.ref_to(),
+            GasLimit::from(50_000),
+        );
+        tx.sign_raw(
+            vec![key.clone()],
+            AccountPublicKeysMap::from_iter(vec![key.ref_to()]),
+            None,
+        );
+        tx.sign_wrapper(key.clone());
+        let signatures = tx.sections.len();
+
+        assert_eq!(
+            bump_fee(&mut tx, amount("0.000001"), &key.ref_to(), None),
+            Err(FeeBumpError::FeeNotHigher {
+                current: String::from("0.000001"),
+                new: String::from("0.000001"),
+            })
+        );
+        assert!(matches!(
+            bump_fee(&mut tx, amount("0.00002"), &key.ref_to(), None),
+            Err(FeeBumpError::FeeTooHigh { .. })
+        ));
+        bump_fee(
+            &mut tx,
+            amount("0.00002"),
+            &key.ref_to(),
+            Some(amount("0.0001")),
+        )
+        .unwrap();
+        assert_eq!(tx.sections.len(), signatures - 1);
+
+        tx.sign_wrapper(key);
+        assert_eq!(tx.sections.len(), signatures);
+    }
+}
@@ -0,0 +1,104 @@
+//! Persistence of unsaved state on page unload. Wallet changes are only written on `save_wallet`
+//! and shielded context updates can be interrupted mid-save, so the Sdk tracks what is dirty and
+//! `flush` writes it from `beforeunload`/`visibilitychange` handlers, where the browser only
+//! grants a short time before the tab is gone. `Query` does the same for the shielded sync
+//! state, the heights the keys were synced to and the cursor of a running sync.
+use std::future::Future;
+use std::pin::pin;
+use std::task::Poll;
+
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::sdk::masp::resume::SyncCursor;
+use crate::utils::sleep;
+
+#[derive(Debug, Default)]
+pub struct DirtyState {
+    // Keys or addresses were added since the wallet was last saved
+    pub wallet: bool,
+    // Spent notes were marked but the shielded context was not saved yet
+    pub shielded: bool,
+}
+
+impl DirtyState {
+    pub fn is_clean(&self) -> bool {
+        !self.wallet && !self.shielded
+    }
+}
+
+/// Shielded sync state of `Query` which is not saved yet
+#[derive(Debug, Default)]
+pub struct DirtySyncState {
+    // Heights the keys were synced to changed but were not stored yet
+    pub key_sync_heights: bool,
+    // Cursor of the running sync which was not written to the work store yet
+    pub sync_cursor: Option<SyncCursor>,
+}
+
+impl DirtySyncState {
+    pub fn is_clean(&self) -> bool {
+        !self.key_sync_heights && self.sync_cursor.is_none()
+    }
+}
+
+/// Outcome of `Sdk::flush` and `Query::flush`
+#[derive(Debug, Default, Serialize, Tsify)]
+pub struct FlushResult {
+    pub wallet_saved: bool,
+    pub shielded_saved: bool,
+    // Key sync heights and the sync cursor, saved by `Query::flush`
+    pub sync_state_saved: bool,
+    // The budget ran out before everything dirty was saved
+    pub timed_out: bool,
+}
+
+/// Runs the future until it resolves or the budget in milliseconds runs out, in which case None
+/// is returned. Without a budget the future is awaited to completion.
+pub async fn with_budget<F: Future>(fut: F, budget_ms: Option<u32>) -> Option<F::Output> {
+    let budget_ms = match budget_ms {
+        Some(budget_ms) => budget_ms,
+        None => return Some(fut.await),
+    };
+
+    let mut fut = pin!(fut);
+    let mut timer = pin!(sleep(budget_ms));
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        match timer.as_mut().poll(cx) {
+            Poll::Ready(_) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn tracks_dirty_state() {
+        let mut dirty = DirtyState::default();
+        assert!(dirty.is_clean());
+
+        dirty.shielded = true;
+        assert!(!dirty.is_clean());
+
+        let mut sync = DirtySyncState::default();
+        assert!(sync.is_clean());
+
+        sync.key_sync_heights = true;
+        assert!(!sync.is_clean());
+    }
+
+    #[wasm_bindgen_test]
+    async fn stops_at_budget() {
+        assert_eq!(with_budget(async { 1 }, Some(10)).await, Some(1));
+        assert_eq!(with_budget(sleep(1_000), Some(10)).await, None);
+        assert_eq!(with_budget(async { 1 }, None).await, Some(1));
+    }
+}
@@ -0,0 +1,137 @@
+//! Gas reports - splits the gas of a dry-run across the inner txs of a batch, so developers can
+//! see which inner tx dominates the cost and set the gas limit of the wrapper precisely.
+use namada_sdk::borsh;
+use namada_sdk::tx::{Section, Tx, TxCommitments};
+use serde::Serialize;
+use tsify::Tsify;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct SectionSize {
+    pub hash: String,
+    // "code", "data", "extra_data", "authorization", "masp_tx", "masp_builder" or "header"
+    pub kind: String,
+    // Size of the borsh serialized section in bytes
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Tsify)]
+pub struct InnerTxGas {
+    pub hash: String,
+    // Tag of the tx code, e.g. "tx_transfer.wasm"
+    pub code: Option<String>,
+    // Gas the inner tx added to the batch. The first inner tx also carries the cost of the
+    // wrapper, i.e. fee payment and the size of the whole tx.
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub gas_used: u64,
+    pub accepted: bool,
+    // Sections the inner tx commits to
+    pub sections: Vec<SectionSize>,
+}
+
+#[derive(Clone, Debug, Serialize, Tsify)]
+pub struct GasReport {
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub total: u64,
+    pub inner_txs: Vec<InnerTxGas>,
+    // All sections of the tx, MASP sections are referenced from the data of inner txs
+    pub sections: Vec<SectionSize>,
+    // Hash of the inner tx which used the most gas
+    pub dominant: Option<String>,
+}
+
+fn section_kind(section: &Section) -> &'static str {
+    match section {
+        Section::Data(_) => "data",
+        Section::ExtraData(_) => "extra_data",
+        Section::Code(_) => "code",
+        Section::Authorization(_) => "authorization",
+        Section::MaspTx(_) => "masp_tx",
+        Section::MaspBuilder(_) => "masp_builder",
+        Section::Header(_) => "header",
+    }
+}
+
+fn section_size(section: &Section) -> SectionSize {
+    SectionSize {
+        hash: section.get_hash().to_string(),
+        kind: section_kind(section).to_string(),
+        size: borsh::to_vec(section).map_or(0, |bytes| bytes.len() as u64),
+    }
+}
+
+/// Returns the sizes of all sections of the tx
+pub fn section_sizes(tx: &Tx) -> Vec<SectionSize> {
+    tx.sections.iter().map(section_size).collect()
+}
+
+/// Returns the sizes of the code, data and memo sections the inner tx commits to
+pub fn inner_tx_sections(tx: &Tx, cmt: &TxCommitments) -> Vec<SectionSize> {
+    [cmt.code_sechash(), cmt.data_sechash(), cmt.memo_sechash()]
+        .into_iter()
+        .filter_map(|hash| tx.get_section(hash))
+        .map(|section| section_size(&section))
+        .collect()
+}
+
+/// Returns the tx with only the first `len` inner txs of its batch. The sections are kept, so
+/// the size cost of the tx is the same for every prefix.
+pub fn batch_prefix(tx: &Tx, len: usize) -> Tx {
+    let mut prefix = tx.clone();
+    prefix.header.batch = tx.header.batch.iter().take(len).cloned().collect();
+
+    prefix
+}
+
+/// Returns the gas each inner tx added, from the gas used by the growing prefixes of the batch
+pub fn marginal_gas(cumulative: &[u64]) -> Vec<u64> {
+    cumulative
+        .iter()
+        .scan(0, |previous, gas| {
+            let marginal = gas.saturating_sub(*previous);
+            *previous = *gas;
+            Some(marginal)
+        })
+        .collect()
+}
+
+/// Returns the hash of the inner tx which used the most gas
+pub fn dominant(inner_txs: &[InnerTxGas]) -> Option<String> {
+    inner_txs
+        .iter()
+        .max_by_key(|inner_tx| inner_tx.gas_used)
+        .map(|inner_tx| inner_tx.hash.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::chain::ChainId;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn splits_gas_across_inner_txs() {
+        assert_eq!(marginal_gas(&[120, 150, 400]), vec![120, 30, 250]);
+        // A prefix using less gas than the previous one, e.g. after a failed dry-run, adds none
+        assert_eq!(marginal_gas(&[120, 100]), vec![120, 0]);
+    }
+
+    #[wasm_bindgen_test]
+    fn reports_sections_of_inner_txs() {
+        let mut tx = Tx::new(ChainId::default(), None);
+        tx.add_code(vec![1; 16], Some(String::from("tx_transfer.wasm")));
+        tx.add_serialized_data(vec![2; 8]);
+        let cmt = tx.first_commitments().unwrap().clone();
+
+        let kinds: Vec<String> = inner_tx_sections(&tx, &cmt)
+            .into_iter()
+            .map(|section| section.kind)
+            .collect();
+        assert_eq!(kinds, vec!["code", "data"]);
+        assert_eq!(batch_prefix(&tx, 0).header.batch.len(), 0);
+        assert_eq!(section_sizes(&tx).len(), tx.sections.len());
+    }
+}
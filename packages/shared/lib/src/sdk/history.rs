@@ -0,0 +1,484 @@
+//! Tx history export - turns the txs of a block range into one row per movement of funds of the
+//! exported addresses, as CSV or JSON for tax and accounting tools. Fees are separate rows, so
+//! summing the amounts per direction gives the balance change.
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use chrono::DateTime;
+use namada_sdk::address::Address;
+use namada_sdk::dec::Dec;
+use namada_sdk::ibc::MsgTransfer;
+use namada_sdk::token::{Account, Amount, DenominatedAmount, Transfer};
+use namada_sdk::tx::data::compute_inner_tx_hash;
+use namada_sdk::tx::data::pos::{Bond, Unbond};
+use namada_sdk::tx::either::Either;
+use namada_sdk::tx::{self, data::TxType as NamadaTxType};
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::JsError;
+
+use super::transaction::TransactionKind;
+use super::tx::{wasm_hash_to_tx_type, TxType};
+use crate::failure;
+use crate::prices::PriceOracle;
+use crate::types::amount::StringAmount;
+use crate::types::query::WasmHash;
+
+// Columns of the CSV export, in the order of the HistoryEntry fields
+const CSV_HEADER: &str =
+    "hash,height,epoch,timestamp,kind,direction,address,counterparty,token,amount,fiat_value,memo,label";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistoryFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for HistoryFormat {
+    type Err = JsError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(HistoryFormat::Csv),
+            "json" => Ok(HistoryFormat::Json),
+            _ => Err(JsError::new(&format!(
+                "Invalid format: {}, expected \"csv\" or \"json\"",
+                format
+            ))),
+        }
+    }
+}
+
+/// Details of the tx shared by all of its rows
+#[derive(Clone, Debug, Default)]
+pub struct TxContext {
+    // CometBFT hash of the tx, the one annotations are keyed by
+    pub hash: String,
+    pub height: u64,
+    pub epoch: Option<u64>,
+    // RFC 3339 time of the block the tx was applied in
+    pub timestamp: String,
+    pub memo: Option<String>,
+    pub label: Option<String>,
+}
+
+/// Movement of funds of one of the exported addresses
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct HistoryEntry {
+    pub hash: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub epoch: Option<u64>,
+    pub timestamp: String,
+    // "transfer", "ibc_transfer", "bond", "unbond" or "fee"
+    pub kind: String,
+    // "in" or "out" of the spendable balance of the address
+    pub direction: String,
+    pub address: String,
+    // Other parties of the movement separated by ";", the validator for bonds, empty for fees
+    pub counterparty: String,
+    pub token: String,
+    pub amount: StringAmount,
+    // Value in the currency of the price oracle, if one is set
+    pub fiat_value: Option<String>,
+    pub memo: Option<String>,
+    // Label of the tx annotation
+    pub label: Option<String>,
+}
+
+impl HistoryEntry {
+    fn new(ctx: &TxContext, kind: &str, direction: &str, address: String) -> HistoryEntry {
+        HistoryEntry {
+            hash: ctx.hash.clone(),
+            height: ctx.height,
+            epoch: ctx.epoch,
+            timestamp: ctx.timestamp.clone(),
+            kind: kind.to_string(),
+            direction: direction.to_string(),
+            address,
+            counterparty: String::new(),
+            token: String::new(),
+            amount: StringAmount::new(String::new()),
+            fiat_value: None,
+            memo: ctx.memo.clone(),
+            label: ctx.label.clone(),
+        }
+    }
+
+    fn with_funds(self, counterparty: String, token: String, amount: String) -> HistoryEntry {
+        HistoryEntry {
+            counterparty,
+            token,
+            amount: StringAmount::new(amount),
+            ..self
+        }
+    }
+}
+
+/// Returns the rows of a tx concerning the addresses, the fee row first. Inner txs which were
+/// not applied, or whose data can't be decoded, move no funds and have no rows.
+///
+/// # Arguments
+///
+/// * `tx` - applied wrapper tx
+/// * `ctx` - details of the tx, the memo is taken from the inner txs
+/// * `applied` - hashes of the inner txs which were applied, see `applied_inner_txs`
+/// * `addresses` - exported addresses
+/// * `native_token` - address of the native token, staked by bonds
+/// * `wasm_hashes` - hashes of the tx codes, identifying the type of the inner txs
+pub fn tx_entries(
+    tx: &tx::Tx,
+    ctx: &TxContext,
+    applied: &BTreeSet<String>,
+    addresses: &BTreeSet<Address>,
+    native_token: &Address,
+    wasm_hashes: &Vec<WasmHash>,
+) -> Vec<HistoryEntry> {
+    let wrapper = match &tx.header().tx_type {
+        NamadaTxType::Wrapper(wrapper) => wrapper,
+        _ => return vec![],
+    };
+
+    let mut entries = vec![];
+    let payer = Address::from(&wrapper.pk);
+    // The fee of the whole gas limit is charged, whatever the tx used
+    let fee = wrapper
+        .get_tx_fee()
+        .ok()
+        .filter(|_| addresses.contains(&payer));
+    if let Some(fee) = fee {
+        entries.push(fee_entry(ctx, &payer, &wrapper.fee.token, fee));
+    }
+
+    for (tx_type, kind, memo) in applied_kinds(tx, applied, wasm_hashes) {
+        let ctx = TxContext {
+            memo,
+            ..ctx.clone()
+        };
+        entries.extend(kind_entries(&kind, tx_type, &ctx, addresses, native_token));
+    }
+
+    entries
+}
+
+/// Returns the type, decoded data and memo of the applied inner txs of a wrapper tx. Inner txs
+/// of unknown types, or whose data can't be decoded, are left out.
+///
+/// # Arguments
+///
+/// * `tx` - applied wrapper tx
+/// * `applied` - hashes of the inner txs which were applied, see `applied_inner_txs`
+/// * `wasm_hashes` - hashes of the tx codes, identifying the type of the inner txs
+pub fn applied_kinds(
+    tx: &tx::Tx,
+    applied: &BTreeSet<String>,
+    wasm_hashes: &Vec<WasmHash>,
+) -> Vec<(TxType, TransactionKind, Option<String>)> {
+    let wrapper_hash = tx.wrapper_hash();
+    let mut kinds = vec![];
+    for cmt in tx.commitments() {
+        let inner_tx_hash = compute_inner_tx_hash(wrapper_hash.as_ref(), Either::Right(cmt));
+        if !applied.contains(&inner_tx_hash.to_string()) {
+            continue;
+        }
+        let tx_type = tx
+            .get_section(cmt.code_sechash())
+            .and_then(|s| s.code_sec())
+            .and_then(|s| String::from_utf8(subtle_encoding::hex::encode(s.code.hash().0)).ok())
+            .and_then(|tx_code_id| wasm_hash_to_tx_type(&tx_code_id, wasm_hashes));
+        let tx_type = match tx_type {
+            Some(tx_type) => tx_type,
+            None => continue,
+        };
+        let memo = tx
+            .memo(cmt)
+            .map(|memo_bytes| String::from_utf8_lossy(&memo_bytes).to_string());
+        let tx_data = tx.data(cmt).unwrap_or_default();
+        let Ok(kind) = TransactionKind::decode(tx_type, &tx_data) else {
+            continue;
+        };
+
+        kinds.push((tx_type, kind, memo));
+    }
+
+    kinds
+}
+
+/// Returns the hashes of the applied inner txs, from the "batch" attribute of the "tx/applied"
+/// event of the tx
+pub fn applied_inner_txs(batch: &str) -> BTreeSet<String> {
+    serde_json::from_str(batch)
+        .map(|batch| failure::inner_tx_results(&batch))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|result| result.status == "applied")
+        .map(|result| result.hash.trim_start_matches("0x").to_uppercase())
+        .collect()
+}
+
+/// Returns the rows of an inner tx concerning the addresses
+pub fn kind_entries(
+    kind: &TransactionKind,
+    tx_type: TxType,
+    ctx: &TxContext,
+    addresses: &BTreeSet<Address>,
+    native_token: &Address,
+) -> Vec<HistoryEntry> {
+    let name = tx_type.name();
+    match kind {
+        TransactionKind::Transfer(Transfer {
+            sources, targets, ..
+        }) => {
+            let outgoing = account_entries(ctx, name, "out", sources, targets, addresses);
+            let incoming = account_entries(ctx, name, "in", targets, sources, addresses);
+            outgoing.into_iter().chain(incoming).collect()
+        }
+        TransactionKind::IbcTransfer(MsgTransfer { message, .. }) => {
+            let data = &message.packet_data;
+            addresses
+                .iter()
+                .filter(|address| address.to_string() == data.sender.to_string())
+                .map(|address| {
+                    HistoryEntry::new(ctx, name, "out", address.to_string()).with_funds(
+                        data.receiver.to_string(),
+                        data.token.denom.to_string(),
+                        data.token.amount.to_string(),
+                    )
+                })
+                .collect()
+        }
+        TransactionKind::Bond(Bond {
+            amount,
+            validator,
+            source,
+        }) => staking_entries(
+            ctx,
+            name,
+            "out",
+            (source, validator),
+            *amount,
+            addresses,
+            native_token,
+        ),
+        TransactionKind::Unbond(Unbond {
+            amount,
+            validator,
+            source,
+        }) => staking_entries(
+            ctx,
+            name,
+            "in",
+            (source, validator),
+            *amount,
+            addresses,
+            native_token,
+        ),
+        _ => vec![],
+    }
+}
+
+// Rows of the accounts of the addresses on one side of a transfer, the owners on the other side
+// moving the same token are the counterparties
+fn account_entries(
+    ctx: &TxContext,
+    kind: &str,
+    direction: &str,
+    accounts: &BTreeMap<Account, DenominatedAmount>,
+    others: &BTreeMap<Account, DenominatedAmount>,
+    addresses: &BTreeSet<Address>,
+) -> Vec<HistoryEntry> {
+    accounts
+        .iter()
+        .filter(|(account, _)| addresses.contains(&account.owner))
+        .map(|(account, amount)| {
+            let counterparty = others
+                .keys()
+                .filter(|other| other.token == account.token && other.owner != account.owner)
+                .map(|other| other.owner.to_string())
+                .collect::<Vec<_>>()
+                .join(";");
+
+            HistoryEntry::new(ctx, kind, direction, account.owner.to_string()).with_funds(
+                counterparty,
+                account.token.to_string(),
+                amount.to_string(),
+            )
+        })
+        .collect()
+}
+
+// Bonds without a source are self-bonds of the validator
+fn staking_entries(
+    ctx: &TxContext,
+    kind: &str,
+    direction: &str,
+    (source, validator): (&Option<Address>, &Address),
+    amount: Amount,
+    addresses: &BTreeSet<Address>,
+    native_token: &Address,
+) -> Vec<HistoryEntry> {
+    let owner = source.as_ref().unwrap_or(validator);
+    if !addresses.contains(owner) {
+        return vec![];
+    }
+
+    vec![
+        HistoryEntry::new(ctx, kind, direction, owner.to_string()).with_funds(
+            validator.to_string(),
+            native_token.to_string(),
+            amount.to_string_native(),
+        ),
+    ]
+}
+
+/// Returns the row of the fee paid by the wrapper tx
+pub fn fee_entry(
+    ctx: &TxContext,
+    payer: &Address,
+    token: &Address,
+    fee: DenominatedAmount,
+) -> HistoryEntry {
+    HistoryEntry::new(ctx, "fee", "out", payer.to_string()).with_funds(
+        String::new(),
+        token.to_string(),
+        fee.to_string(),
+    )
+}
+
+/// Returns the value of the row at the time of the tx, None if the token can't be valued
+pub async fn fiat_value(
+    oracle: &PriceOracle,
+    currency: &str,
+    entry: &HistoryEntry,
+) -> Option<String> {
+    // IBC amounts are in base units of denominations the oracle doesn't know
+    if entry.kind == TxType::IBCTransfer.name() || Address::from_str(&entry.token).is_err() {
+        return None;
+    }
+    let amount = Dec::from_str(&entry.amount.to_string()).ok()?;
+    let timestamp = DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+    let timestamp = u64::try_from(timestamp.timestamp()).ok()?;
+
+    oracle
+        .value_at(&entry.token, amount, currency, timestamp)
+        .await
+        .ok()
+        .map(|value| value.to_string())
+}
+
+/// Encodes the rows as CSV with a header row
+pub fn to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for entry in entries {
+        let fields = [
+            entry.hash.clone(),
+            entry.height.to_string(),
+            entry
+                .epoch
+                .map(|epoch| epoch.to_string())
+                .unwrap_or_default(),
+            entry.timestamp.clone(),
+            entry.kind.clone(),
+            entry.direction.clone(),
+            entry.address.clone(),
+            entry.counterparty.clone(),
+            entry.token.clone(),
+            entry.amount.to_string(),
+            entry.fiat_value.clone().unwrap_or_default(),
+            entry.memo.clone().unwrap_or_default(),
+            entry.label.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+// Quotes fields containing separators, quotes or line breaks, as memos and labels can
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+    const BOB: &str = "tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    fn address(address: &str) -> Address {
+        Address::from_str(address).unwrap()
+    }
+
+    fn ctx() -> TxContext {
+        TxContext {
+            hash: String::from("ABCD"),
+            height: 10,
+            memo: Some(String::from("rent, march")),
+            ..TxContext::default()
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn transfer_rows_have_counterparties() {
+        let account = |owner: &str| Account {
+            owner: address(owner),
+            token: address(NAM),
+        };
+        let amount = DenominatedAmount::native(Amount::from_u64(1_500_000));
+        let transfer = TransactionKind::Transfer(Transfer {
+            sources: BTreeMap::from([(account(ALICE), amount)]),
+            targets: BTreeMap::from([(account(BOB), amount)]),
+            shielded_section_hash: None,
+        });
+        let addresses = BTreeSet::from([address(ALICE)]);
+
+        let entries = kind_entries(
+            &transfer,
+            TxType::Transfer,
+            &ctx(),
+            &addresses,
+            &address(NAM),
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].direction, "out");
+        assert_eq!(entries[0].counterparty, BOB);
+        assert_eq!(entries[0].amount.to_string(), amount.to_string());
+    }
+
+    #[wasm_bindgen_test]
+    fn skips_failed_inner_txs() {
+        let applied = applied_inner_txs(&format!(
+            r#"{{"ab":{{"Ok":{{}}}},"cd":{{"Err":"Gas error"}},"ef":{{"Ok":{{"vps_result":{{"rejected_vps":["{}"]}}}}}}}}"#,
+            ALICE
+        ));
+
+        assert_eq!(applied, BTreeSet::from([String::from("AB")]));
+        assert!(applied_inner_txs("not json").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn csv_escapes_fields() {
+        let fee = DenominatedAmount::native(Amount::from_u64(10));
+        let csv = to_csv(&[fee_entry(&ctx(), &address(ALICE), &address(NAM), fee)]);
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows[0], CSV_HEADER);
+        assert!(rows[1].starts_with("ABCD,10,,,fee,out,"));
+        assert!(rows[1].ends_with(",\"rent, march\","));
+        assert!(HistoryFormat::from_str("xml").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Ledger - helpers for signing with the Namada Ledger app.
+//! APDU framing and response parsing happen here, so the TS transport layer only shuttles bytes.
+use namada_sdk::borsh::{self, BorshDeserialize};
+use namada_sdk::tx::Tx;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use super::signature::{append_signature, SignatureMsg};
+use crate::utils::to_js_result;
+
+const CLA: u8 = 0x57;
+const INS_SIGN: u8 = 0x02;
+const P1_INIT: u8 = 0x00;
+const P1_ADD: u8 = 0x01;
+const P1_LAST: u8 = 0x02;
+const CHUNK_SIZE: usize = 250;
+const HARDENED: u32 = 0x8000_0000;
+const PATH_LEN: usize = 5;
+
+// Lengths of the tagged public key and signatures in the sign response
+const PK_LEN_PLUS_TAG: usize = 33;
+const SIG_LEN_PLUS_TAG: usize = 65;
+
+/// Single APDU command to be sent to the device
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct LedgerChunk {
+    cla: u8,
+    ins: u8,
+    p1: u8,
+    p2: u8,
+    data: Vec<u8>,
+}
+
+/// Serializes BIP44 path, e.g. "m/44'/877'/0'/0'/0'", to the format expected by the app
+fn serialize_path(path: &str) -> Result<Vec<u8>, JsError> {
+    let components = path
+        .strip_prefix("m/")
+        .ok_or_else(|| JsError::new("Path has to start with \"m/\""))?
+        .split('/')
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index = index
+                .parse::<u32>()
+                .map_err(|_| JsError::new(&format!("Invalid path component: {}", component)))?;
+            if index >= HARDENED {
+                return Err(JsError::new(&format!(
+                    "Path component too large: {}",
+                    index
+                )));
+            }
+
+            Ok(if hardened { index + HARDENED } else { index })
+        })
+        .collect::<Result<Vec<u32>, JsError>>()?;
+
+    if components.len() != PATH_LEN {
+        return Err(JsError::new(&format!(
+            "Path has to have {} components",
+            PATH_LEN
+        )));
+    }
+
+    Ok(components
+        .iter()
+        .flat_map(|component| component.to_le_bytes())
+        .collect())
+}
+
+/// Splits the tx into sign APDUs, the first one carrying the BIP44 path
+fn ledger_payloads(tx_bytes: &[u8], path: &str) -> Result<Vec<LedgerChunk>, JsError> {
+    let mut chunks = vec![serialize_path(path)?];
+    chunks.extend(tx_bytes.chunks(CHUNK_SIZE).map(|chunk| chunk.to_vec()));
+
+    let last = chunks.len() - 1;
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, data)| LedgerChunk {
+            cla: CLA,
+            ins: INS_SIGN,
+            p1: match i {
+                0 => P1_INIT,
+                i if i == last => P1_LAST,
+                _ => P1_ADD,
+            },
+            p2: 0,
+            data,
+        })
+        .collect())
+}
+
+/// Parses the sign response of the app:
+/// [pubkey(33) | raw_signature(65) | wrapper_signature(65) | raw_indices_len(1) | raw_indices |
+/// wrapper_indices_len(1) | wrapper_indices]
+fn parse_sign_response(response: &[u8]) -> Result<SignatureMsg, JsError> {
+    let mut rest = response;
+    let mut take = |len: usize| -> Result<Vec<u8>, JsError> {
+        if rest.len() < len {
+            return Err(JsError::new("Ledger sign response is too short"));
+        }
+        let (taken, remaining) = rest.split_at(len);
+        rest = remaining;
+        Ok(taken.to_vec())
+    };
+
+    let pubkey = take(PK_LEN_PLUS_TAG)?;
+    let raw_signature = take(SIG_LEN_PLUS_TAG)?;
+    let wrapper_signature = take(SIG_LEN_PLUS_TAG)?;
+    let raw_indices_len = take(1)?[0] as usize;
+    let raw_indices = take(raw_indices_len)?;
+    let wrapper_indices_len = take(1)?[0] as usize;
+    let wrapper_indices = take(wrapper_indices_len)?;
+
+    Ok(SignatureMsg {
+        pubkey,
+        raw_indices,
+        raw_signature,
+        wrapper_indices,
+        wrapper_signature,
+    })
+}
+
+/// Returns APDU commands which have to be sent, in order, to the Namada Ledger app to sign the tx
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized tx
+/// * `path` - BIP44 path of the signing key, e.g. "m/44'/877'/0'/0'/0'"
+///
+/// # Errors
+///
+/// Returns an error if the path is invalid
+#[wasm_bindgen]
+pub fn get_ledger_payloads(tx_bytes: &[u8], path: String) -> Result<JsValue, JsError> {
+    to_js_result(ledger_payloads(tx_bytes, &path)?)
+}
+
+/// Appends signatures from the Ledger sign response to the tx and returns the signed tx bytes
+///
+/// # Arguments
+///
+/// * `response_bytes` - response of the last sign APDU, without the return code
+/// * `tx_bytes` - borsh serialized tx which was signed
+///
+/// # Errors
+///
+/// Returns an error if the response is malformed or the tx can't be deserialized
+#[wasm_bindgen]
+pub fn assemble_ledger_signature(
+    response_bytes: &[u8],
+    tx_bytes: &[u8],
+) -> Result<JsValue, JsError> {
+    let mut tx: Tx = Tx::try_from_slice(tx_bytes)?;
+    let sig_msg = parse_sign_response(response_bytes)?;
+    append_signature(&mut tx, sig_msg)?;
+
+    to_js_result(borsh::to_vec(&tx)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn can_serialize_path() {
+        let bytes = serialize_path("m/44'/877'/0'/0'/1").unwrap();
+
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(bytes[0..4], (44 + HARDENED).to_le_bytes());
+        assert_eq!(bytes[4..8], (877 + HARDENED).to_le_bytes());
+        assert_eq!(bytes[16..20], 1u32.to_le_bytes());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_split_tx_into_chunks() {
+        let tx_bytes = vec![1u8; CHUNK_SIZE * 2 + 1];
+        let chunks = ledger_payloads(&tx_bytes, "m/44'/877'/0'/0'/0'").unwrap();
+        let p1s: Vec<u8> = chunks.iter().map(|chunk| chunk.p1).collect();
+
+        assert_eq!(p1s, vec![P1_INIT, P1_ADD, P1_ADD, P1_LAST]);
+        assert_eq!(chunks[1].data.len(), CHUNK_SIZE);
+        assert_eq!(chunks[3].data.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn can_parse_sign_response() {
+        let mut response = vec![0u8; PK_LEN_PLUS_TAG];
+        response.extend(vec![1u8; SIG_LEN_PLUS_TAG]);
+        response.extend(vec![2u8; SIG_LEN_PLUS_TAG]);
+        response.extend([2, 0, 1]);
+        response.extend([1, 3]);
+
+        let sig_msg = parse_sign_response(&response).unwrap();
+
+        assert_eq!(sig_msg.raw_signature, vec![1u8; SIG_LEN_PLUS_TAG]);
+        assert_eq!(sig_msg.wrapper_signature, vec![2u8; SIG_LEN_PLUS_TAG]);
+        assert_eq!(sig_msg.raw_indices, vec![0, 1]);
+        assert_eq!(sig_msg.wrapper_indices, vec![3]);
+        assert!(parse_sign_response(&response[..response.len() - 1]).is_err());
+    }
+}
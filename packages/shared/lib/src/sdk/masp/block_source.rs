@@ -0,0 +1,102 @@
+//! Block sources shielded sync pulls MASP data from. The indexer serves pre-indexed data and is
+//! preferred when available, the ledger RPC is the fallback which can always be used.
+//! Blocks which were already synced are not fetched again, as the shielded context saved
+//! in storage remembers the last synced height.
+use std::time::Duration;
+
+use namada_sdk::masp::utils::MaspClient as NamadaMaspClient;
+use namada_sdk::masp::{IndexerMaspClient, LedgerMaspClient};
+
+use crate::rpc_client::HttpClient;
+
+/// Source of the blocks scanned by shielded sync
+pub trait BlockSource {
+    type Client: NamadaMaspClient + Send + Sync + Unpin + 'static;
+
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Number of blocks fetched per request
+    fn batch_size(&self) -> usize;
+
+    /// Returns false if the source can't serve blocks at the moment
+    async fn is_available(&self) -> bool;
+
+    fn client(&self) -> Self::Client;
+}
+
+/// Fetches blocks from the ledger RPC
+#[derive(Clone)]
+pub struct RpcBlockSource {
+    client: LedgerMaspClient<HttpClient>,
+}
+
+impl RpcBlockSource {
+    pub fn new(client: HttpClient) -> RpcBlockSource {
+        RpcBlockSource {
+            client: LedgerMaspClient::new(
+                client,
+                // Using one does not break the progress indicators
+                1,
+                Duration::from_millis(5),
+            ),
+        }
+    }
+}
+
+impl BlockSource for RpcBlockSource {
+    type Client = LedgerMaspClient<HttpClient>;
+
+    fn name(&self) -> &'static str {
+        "LedgerMaspClient"
+    }
+
+    // Batch size does not matter for the ledger client, and anything else than 1 breaks the
+    // progress bar
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    fn client(&self) -> Self::Client {
+        self.client.clone()
+    }
+}
+
+/// Fetches pre-indexed blocks from a MASP indexer
+#[derive(Clone)]
+pub struct IndexerBlockSource {
+    client: IndexerMaspClient,
+}
+
+impl IndexerBlockSource {
+    pub fn new(client: reqwest::Client, url: reqwest::Url) -> IndexerBlockSource {
+        IndexerBlockSource {
+            client: IndexerMaspClient::new(client, url, true, 10),
+        }
+    }
+}
+
+impl BlockSource for IndexerBlockSource {
+    type Client = IndexerMaspClient;
+
+    fn name(&self) -> &'static str {
+        "IndexerMaspClient"
+    }
+
+    fn batch_size(&self) -> usize {
+        100
+    }
+
+    // The indexer is usable once it has indexed at least one block
+    async fn is_available(&self) -> bool {
+        matches!(self.client.last_block_height().await, Ok(Some(_)))
+    }
+
+    fn client(&self) -> Self::Client {
+        self.client.clone()
+    }
+}
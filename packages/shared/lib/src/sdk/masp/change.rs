@@ -0,0 +1,57 @@
+//! Change of shielded transfers. The MASP builder returns what is left of the spent notes to the
+//! default payment address of the source, so the change address is always derived from the
+//! spending key and can't be configured. Change notes are unknown to the local shielded context
+//! until the next shielded sync, while the notes they came from still look unspent, so spends
+//! are marked locally as soon as the tx is applied.
+use namada_sdk::masp_primitives::consensus::{BlockHeight, MainNetwork};
+use namada_sdk::masp_primitives::sapling::note_encryption::{
+    try_sapling_note_decryption, PreparedIncomingViewingKey,
+};
+use namada_sdk::masp_primitives::sapling::ViewingKey;
+use namada_sdk::masp_primitives::transaction::Transaction;
+use namada_sdk::PaymentAddress;
+use serde::Serialize;
+use tsify::Tsify;
+
+/// Shielded output sent back to the source of the transfer
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct ChangeOutput {
+    pub output_index: u32,
+    pub payment_address: String,
+    // Hex encoded MASP asset type
+    pub asset_type: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub value: u64,
+}
+
+/// Returns outputs of the MASP tx which decrypt with the viewing key of the source
+///
+/// # Arguments
+///
+/// * `masp_tx` - MASP transaction
+/// * `vk` - viewing key of the source
+pub fn find_change(masp_tx: &Transaction, vk: &ViewingKey) -> Vec<ChangeOutput> {
+    let Some(bundle) = masp_tx.sapling_bundle() else {
+        return vec![];
+    };
+    let ivk = PreparedIncomingViewingKey::new(&vk.ivk());
+
+    bundle
+        .shielded_outputs
+        .iter()
+        .enumerate()
+        .filter_map(|(output_index, output)| {
+            // Height only matters for ZIP-212 activation, which is active from the first block
+            let (note, payment_address, _) =
+                try_sapling_note_decryption(&MainNetwork, BlockHeight::from(1), &ivk, output)?;
+
+            Some(ChangeOutput {
+                output_index: output_index as u32,
+                payment_address: PaymentAddress::from(payment_address).to_string(),
+                asset_type: hex::encode(note.asset_type.get_identifier()),
+                value: note.value,
+            })
+        })
+        .collect()
+}
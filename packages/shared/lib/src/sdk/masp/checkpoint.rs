@@ -0,0 +1,79 @@
+//! Commitment tree checkpoints - trusted snapshots of the MASP commitment tree bundled per chain,
+//! so shielded sync of new accounts starts from a recent height instead of genesis. Notes
+//! received before the checkpoint height are not found, so checkpoints are only meant for
+//! accounts created after it.
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_sdk::masp_primitives::merkle_tree::CommitmentTree;
+use namada_sdk::masp_primitives::sapling::Node;
+use wasm_bindgen::JsError;
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct TreeCheckpoint {
+    pub chain_id: String,
+    // Height of the last block included in the tree
+    pub height: u64,
+    // Borsh serialized CommitmentTree<Node>
+    pub tree: Vec<u8>,
+    // Hex encoded root of the tree, a MASP anchor on chain
+    pub anchor: String,
+}
+
+impl TreeCheckpoint {
+    pub fn new(chain_id: String, height: u64, tree: &CommitmentTree<Node>) -> TreeCheckpoint {
+        TreeCheckpoint {
+            chain_id,
+            height,
+            tree: borsh::to_vec(tree).expect("Serializing commitment tree should not fail"),
+            anchor: hex::encode(
+                borsh::to_vec(&tree.root()).expect("Serializing tree root should not fail"),
+            ),
+        }
+    }
+
+    /// Decodes the tree and checks that its root is the checkpoint anchor
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree can't be decoded or its root is not the anchor
+    pub fn tree(&self) -> Result<CommitmentTree<Node>, JsError> {
+        let tree: CommitmentTree<Node> = borsh::from_slice(&self.tree)?;
+        let root = hex::encode(borsh::to_vec(&tree.root())?);
+
+        if root != self.anchor.to_lowercase() {
+            return Err(JsError::new(&format!(
+                "Checkpoint tree root {} does not match the anchor {}",
+                root, self.anchor
+            )));
+        }
+
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn tree() -> CommitmentTree<Node> {
+        let mut tree = CommitmentTree::<Node>::empty();
+        tree.append(Node::new([1; 32])).unwrap();
+        tree
+    }
+
+    #[wasm_bindgen_test]
+    fn verifies_tree_root() {
+        let checkpoint = TreeCheckpoint::new(String::from("namada.test"), 10, &tree());
+
+        assert_eq!(checkpoint.tree().unwrap().root(), tree().root());
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_tampered_tree() {
+        let mut checkpoint = TreeCheckpoint::new(String::from("namada.test"), 10, &tree());
+        checkpoint.tree = borsh::to_vec(&CommitmentTree::<Node>::empty()).unwrap();
+
+        assert!(checkpoint.tree().is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Compact blocks - only the shielded outputs and nullifiers of each block, which is enough to
+//! find the blocks holding notes of a viewing key. Scanning compact blocks downloads a fraction
+//! of the data of full blocks, so the full shielded sync can be skipped when there is nothing new.
+//!
+//! The format is Borsh encoded `Vec<CompactBlock>`, servers build it with
+//! `CompactTx::from_masp_tx` and the schema is exported by `get_borsh_schemas`.
+use namada_sdk::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_sdk::masp_primitives::bls12_381;
+use namada_sdk::masp_primitives::consensus::{BlockHeight, MainNetwork};
+use namada_sdk::masp_primitives::ff::PrimeField;
+use namada_sdk::masp_primitives::sapling::note_encryption::{
+    try_sapling_compact_note_decryption, PreparedIncomingViewingKey, COMPACT_NOTE_SIZE,
+};
+use namada_sdk::masp_primitives::sapling::ViewingKey;
+use namada_sdk::masp_primitives::transaction::components::sapling::CompactOutputDescription;
+use namada_sdk::masp_primitives::transaction::Transaction;
+use serde::Serialize;
+use std::collections::HashSet;
+use tsify::Tsify;
+
+/// Shielded output without the proof, value commitment and outgoing ciphertext
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct CompactOutput {
+    pub cmu: [u8; 32],
+    pub ephemeral_key: [u8; 32],
+    // First COMPACT_NOTE_SIZE bytes of the note ciphertext
+    pub ciphertext: [u8; COMPACT_NOTE_SIZE],
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct CompactTx {
+    // Index of the tx in the block
+    pub index: u32,
+    pub nullifiers: Vec<[u8; 32]>,
+    pub outputs: Vec<CompactOutput>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct CompactBlock {
+    pub height: u64,
+    // Only txs with a MASP section
+    pub txs: Vec<CompactTx>,
+}
+
+/// Output of a compact block which decrypted with one of the scanned viewing keys
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct CompactMatch {
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    pub tx_index: u32,
+    pub output_index: u32,
+    // Index of the viewing key in the scanned keys
+    pub viewing_key_index: u32,
+}
+
+/// Result of scanning compact blocks
+#[derive(Debug, Serialize, Tsify)]
+pub struct CompactScanResult {
+    // Highest scanned block, None if no blocks were passed
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub last_height: Option<u64>,
+    pub matches: Vec<CompactMatch>,
+}
+
+impl CompactTx {
+    /// Extracts the compact data of a MASP tx
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - index of the tx in the block
+    /// * `masp_tx` - MASP transaction
+    pub fn from_masp_tx(index: u32, masp_tx: &Transaction) -> CompactTx {
+        let (nullifiers, outputs) = match masp_tx.sapling_bundle() {
+            Some(bundle) => (
+                bundle
+                    .shielded_spends
+                    .iter()
+                    .map(|spend| spend.nullifier.0)
+                    .collect(),
+                bundle
+                    .shielded_outputs
+                    .iter()
+                    .map(|output| CompactOutput {
+                        cmu: output.cmu.to_repr(),
+                        ephemeral_key: output.ephemeral_key.0,
+                        ciphertext: output.enc_ciphertext[..COMPACT_NOTE_SIZE]
+                            .try_into()
+                            .expect("Ciphertext to be longer than the compact note"),
+                    })
+                    .collect(),
+            ),
+            None => (vec![], vec![]),
+        };
+
+        CompactTx {
+            index,
+            nullifiers,
+            outputs,
+        }
+    }
+}
+
+impl CompactOutput {
+    // Returns None if the note commitment is not a valid field element
+    fn to_output_description(&self) -> Option<CompactOutputDescription> {
+        let cmu = Option::from(bls12_381::Scalar::from_repr(self.cmu))?;
+
+        Some(CompactOutputDescription {
+            ephemeral_key: self.ephemeral_key.into(),
+            cmu,
+            enc_ciphertext: self.ciphertext,
+        })
+    }
+}
+
+/// Trial decrypts all outputs of the blocks with every viewing key
+///
+/// # Arguments
+///
+/// * `blocks` - compact blocks, in any order
+/// * `vks` - viewing keys to scan for
+pub fn scan_compact_blocks(blocks: &[CompactBlock], vks: &[ViewingKey]) -> CompactScanResult {
+    let ivks: Vec<PreparedIncomingViewingKey> = vks
+        .iter()
+        .map(|vk| PreparedIncomingViewingKey::new(&vk.ivk()))
+        .collect();
+    let mut matches = vec![];
+
+    for block in blocks {
+        for tx in &block.txs {
+            for (output_index, output) in tx.outputs.iter().enumerate() {
+                let Some(description) = output.to_output_description() else {
+                    continue;
+                };
+                let viewing_key_index = ivks.iter().position(|ivk| {
+                    try_sapling_compact_note_decryption(
+                        &MainNetwork,
+                        BlockHeight::from(block.height as u32),
+                        ivk,
+                        &description,
+                    )
+                    .is_some()
+                });
+
+                if let Some(viewing_key_index) = viewing_key_index {
+                    matches.push(CompactMatch {
+                        height: block.height,
+                        tx_index: tx.index,
+                        output_index: output_index as u32,
+                        viewing_key_index: viewing_key_index as u32,
+                    });
+                }
+            }
+        }
+    }
+
+    CompactScanResult {
+        last_height: blocks.iter().map(|block| block.height).max(),
+        matches,
+    }
+}
+
+/// Returns true if a tx of the blocks spends one of the nullifiers
+pub fn spends_any(blocks: &[CompactBlock], nullifiers: &HashSet<[u8; 32]>) -> bool {
+    blocks
+        .iter()
+        .flat_map(|block| &block.txs)
+        .flat_map(|tx| &tx.nullifiers)
+        .any(|nullifier| nullifiers.contains(nullifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::borsh;
+    use namada_sdk::masp_primitives::zip32::{ExtendedFullViewingKey, ExtendedSpendingKey};
+    use wasm_bindgen_test::*;
+
+    fn block(height: u64) -> CompactBlock {
+        CompactBlock {
+            height,
+            txs: vec![CompactTx {
+                index: 0,
+                nullifiers: vec![[1; 32]],
+                outputs: vec![CompactOutput {
+                    cmu: [0; 32],
+                    ephemeral_key: [2; 32],
+                    ciphertext: [3; COMPACT_NOTE_SIZE],
+                }],
+            }],
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn encodes_compact_blocks() {
+        let blocks = vec![block(1), block(2)];
+        let bytes = borsh::to_vec(&blocks).unwrap();
+
+        assert_eq!(Vec::<CompactBlock>::try_from_slice(&bytes).unwrap(), blocks);
+    }
+
+    #[wasm_bindgen_test]
+    fn skips_outputs_of_other_keys() {
+        let xsk = ExtendedSpendingKey::master(b"compact");
+        let vk = ExtendedFullViewingKey::from(&xsk).fvk.vk;
+        let result = scan_compact_blocks(&[block(5), block(3)], &[vk]);
+
+        assert_eq!(result.last_height, Some(5));
+        assert!(result.matches.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn finds_spent_nullifiers() {
+        let blocks = [block(1), block(2)];
+
+        assert!(spends_any(&blocks, &HashSet::from([[1; 32]])));
+        assert!(!spends_any(&blocks, &HashSet::from([[2; 32]])));
+    }
+}
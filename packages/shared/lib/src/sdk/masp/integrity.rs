@@ -0,0 +1,75 @@
+//! Integrity checks of the stored shielded context. A commitment tree whose root the chain does
+//! not know, or witnesses which drifted from the tree, mean the state was corrupted or written
+//! by an interrupted sync, and notes can't be spent until the context is rescanned.
+use namada_sdk::masp_primitives::merkle_tree::{CommitmentTree, IncrementalWitness};
+use namada_sdk::masp_primitives::sapling::Node;
+use serde::Serialize;
+use tsify::Tsify;
+
+/// Result of checking the shielded context, rescan is suggested if any issue was found
+#[derive(Debug, Serialize, Tsify)]
+pub struct ShieldedIntegrityReport {
+    // Number of notes in the commitment tree
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub tree_size: u64,
+    // Hex encoded root of the commitment tree
+    pub anchor: String,
+    pub issues: Vec<String>,
+    pub rescan_suggested: bool,
+}
+
+impl ShieldedIntegrityReport {
+    pub fn new(tree_size: u64, anchor: String, issues: Vec<String>) -> ShieldedIntegrityReport {
+        ShieldedIntegrityReport {
+            tree_size,
+            anchor,
+            rescan_suggested: !issues.is_empty(),
+            issues,
+        }
+    }
+}
+
+/// Returns an issue for every witness whose root differs from the tree root
+///
+/// # Arguments
+///
+/// * `tree` - commitment tree of the shielded context
+/// * `witnesses` - (note position, witness) pairs
+pub fn check_witnesses<'a>(
+    tree: &CommitmentTree<Node>,
+    witnesses: impl IntoIterator<Item = (&'a usize, &'a IncrementalWitness<Node>)>,
+) -> Vec<String> {
+    let root = tree.root();
+    let mut issues: Vec<String> = witnesses
+        .into_iter()
+        .filter(|(_, witness)| witness.root() != root)
+        .map(|(note_pos, _)| format!("Witness of note {} does not match the tree", note_pos))
+        .collect();
+    issues.sort();
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn detects_outdated_witnesses() {
+        let mut tree = CommitmentTree::<Node>::empty();
+        tree.append(Node::new([1; 32])).unwrap();
+        let witness = IncrementalWitness::from_tree(&tree);
+        let witnesses = BTreeMap::from([(0usize, witness)]);
+
+        assert!(check_witnesses(&tree, &witnesses).is_empty());
+
+        tree.append(Node::new([2; 32])).unwrap();
+        assert_eq!(
+            check_witnesses(&tree, &witnesses),
+            vec![String::from("Witness of note 0 does not match the tree")]
+        );
+    }
+}
@@ -24,6 +24,9 @@ const SPECULATIVE_FILE_NAME: &str = "speculative_shielded.dat";
 const SPECULATIVE_TMP_FILE_NAME: &str = "speculative_shielded.tmp";
 const CACHE_FILE_NAME: &str = "shielded_sync.cache";
 const CACHE_FILE_TMP_PREFIX: &str = "shielded_sync.cache.tmp";
+const CHECKPOINT_FILE_NAME: &str = "tree_checkpoint.dat";
+const SNAPSHOTS_FILE_NAME: &str = "context_snapshots.dat";
+const KEY_SYNC_HEIGHTS_FILE_NAME: &str = "key_sync_heights.dat";
 
 /// Mostly copied from the Namada CLI
 
@@ -78,6 +81,81 @@ impl NodeShieldedUtils {
         let uint8_array = js_sys::Uint8Array::from(&content[..]);
         write_file_sync(JsValue::from_str(path), uint8_array.into()).unwrap();
     }
+
+    /// Stores the Borsh encoded tree checkpoint the context was started from, None removes it
+    pub async fn save_checkpoint(&self, checkpoint: Option<&[u8]>) -> std::io::Result<()> {
+        self.save_file(CHECKPOINT_FILE_NAME, checkpoint)
+    }
+
+    /// Returns the stored Borsh encoded tree checkpoint, None if there is none
+    pub async fn load_checkpoint(&self) -> std::io::Result<Option<Vec<u8>>> {
+        self.load_file(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Stores the Borsh encoded context snapshots, None removes them
+    pub async fn save_snapshots(&self, snapshots: Option<&[u8]>) -> std::io::Result<()> {
+        self.save_file(SNAPSHOTS_FILE_NAME, snapshots)
+    }
+
+    /// Returns the stored Borsh encoded context snapshots, None if there are none
+    pub async fn load_snapshots(&self) -> std::io::Result<Option<Vec<u8>>> {
+        self.load_file(SNAPSHOTS_FILE_NAME)
+    }
+
+    /// Stores the Borsh encoded heights the viewing keys were synced to, None removes them
+    pub async fn save_key_sync_heights(&self, heights: Option<&[u8]>) -> std::io::Result<()> {
+        self.save_file(KEY_SYNC_HEIGHTS_FILE_NAME, heights)
+    }
+
+    /// Returns the stored Borsh encoded key sync heights, None if there are none
+    pub async fn load_key_sync_heights(&self) -> std::io::Result<Option<Vec<u8>>> {
+        self.load_file(KEY_SYNC_HEIGHTS_FILE_NAME)
+    }
+
+    /// Returns the stored Borsh encoded confirmed context, None if nothing was synced yet
+    pub async fn load_context_bytes(&self) -> std::io::Result<Option<Vec<u8>>> {
+        self.load_file(FILE_NAME)
+    }
+
+    /// Replaces the confirmed context with a Borsh encoded one and drops the speculative context
+    pub async fn save_context_bytes(&self, context: &[u8]) -> std::io::Result<()> {
+        let tmp_path = path_buf_to_js_value(self.context_dir.join(TMP_FILE_NAME));
+        write_file_sync(tmp_path.clone(), js_sys::Uint8Array::from(context).into())
+            .map_err(to_io_err)?;
+        renameSync(
+            tmp_path,
+            path_buf_to_js_value(self.context_dir.join(FILE_NAME)),
+        )
+        .map_err(to_io_err)?;
+
+        self.save_file(SPECULATIVE_FILE_NAME, None)
+    }
+
+    fn save_file(&self, file_name: &str, bytes: Option<&[u8]>) -> std::io::Result<()> {
+        let path = self.context_dir.join(file_name);
+
+        let result = match bytes {
+            Some(bytes) => write_file_sync(
+                path_buf_to_js_value(path),
+                js_sys::Uint8Array::from(bytes).into(),
+            ),
+            None if file_exists(path.clone()) => unlinkSync(path_buf_to_js_value(path)),
+            None => return Ok(()),
+        };
+
+        result.map(|_| ()).map_err(to_io_err)
+    }
+
+    fn load_file(&self, file_name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let path = self.context_dir.join(file_name);
+        if !file_exists(path.clone()) {
+            return Ok(None);
+        }
+
+        let bytes = read_file_sync(path_buf_to_js_value(path)).map_err(to_io_err)?;
+
+        Ok(Some(to_bytes(bytes)))
+    }
 }
 
 #[async_trait(?Send)]
@@ -170,6 +248,10 @@ fn path_buf_to_js_value(path: PathBuf) -> JsValue {
     JsValue::from_str(path.to_str().unwrap())
 }
 
+fn to_io_err(err: JsValue) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", err))
+}
+
 fn file_exists(path: PathBuf) -> bool {
     exists_sync(path_buf_to_js_value(path))
         .unwrap()
@@ -14,6 +14,9 @@ const SHIELDED_CONTEXT_TABLE: &str = "ShieldedContext";
 const SHIELDED_CONTEXT_KEY_CONFIRMED: &str = "shielded-context-confirmed";
 const SHIELDED_CONTEXT_KEY_SPECULATIVE: &str = "shielded-context-speculative";
 const SHIELDED_CONTEXT_KEY_TEMP: &str = "shielded-context-temp";
+const TREE_CHECKPOINT_KEY: &str = "tree-checkpoint";
+const SNAPSHOTS_KEY: &str = "context-snapshots";
+const KEY_SYNC_HEIGHTS_KEY: &str = "key-sync-heights";
 
 #[derive(Default, Debug, BorshSerialize, BorshDeserialize, Clone)]
 #[borsh(crate = "namada_sdk::borsh")]
@@ -111,6 +114,90 @@ impl WebShieldedUtils {
         Ok(())
     }
 
+    /// Stores the Borsh encoded tree checkpoint the context was started from, None removes it
+    pub async fn save_checkpoint(&self, checkpoint: Option<&[u8]>) -> std::io::Result<()> {
+        Self::save_entry(TREE_CHECKPOINT_KEY, checkpoint).await
+    }
+
+    /// Returns the stored Borsh encoded tree checkpoint, None if there is none
+    pub async fn load_checkpoint(&self) -> std::io::Result<Option<Vec<u8>>> {
+        Self::load_entry(TREE_CHECKPOINT_KEY).await
+    }
+
+    /// Stores the Borsh encoded context snapshots, None removes them
+    pub async fn save_snapshots(&self, snapshots: Option<&[u8]>) -> std::io::Result<()> {
+        Self::save_entry(SNAPSHOTS_KEY, snapshots).await
+    }
+
+    /// Returns the stored Borsh encoded context snapshots, None if there are none
+    pub async fn load_snapshots(&self) -> std::io::Result<Option<Vec<u8>>> {
+        Self::load_entry(SNAPSHOTS_KEY).await
+    }
+
+    /// Stores the Borsh encoded heights the viewing keys were synced to, None removes them
+    pub async fn save_key_sync_heights(&self, heights: Option<&[u8]>) -> std::io::Result<()> {
+        Self::save_entry(KEY_SYNC_HEIGHTS_KEY, heights).await
+    }
+
+    /// Returns the stored Borsh encoded key sync heights, None if there are none
+    pub async fn load_key_sync_heights(&self) -> std::io::Result<Option<Vec<u8>>> {
+        Self::load_entry(KEY_SYNC_HEIGHTS_KEY).await
+    }
+
+    /// Returns the stored Borsh encoded confirmed context, None if nothing was synced yet
+    pub async fn load_context_bytes(&self) -> std::io::Result<Option<Vec<u8>>> {
+        Self::load_entry(SHIELDED_CONTEXT_KEY_CONFIRMED).await
+    }
+
+    /// Replaces the confirmed context with a Borsh encoded one and drops the speculative context
+    pub async fn save_context_bytes(&self, context: &[u8]) -> std::io::Result<()> {
+        Self::save_entry(SHIELDED_CONTEXT_KEY_CONFIRMED, Some(context)).await?;
+        let db = Self::build_database().await.map_err(Self::to_io_err)?;
+
+        Self::remove_speculative_context(&db)
+            .await
+            .map_err(Self::to_io_err)
+    }
+
+    async fn save_entry(key: &str, bytes: Option<&[u8]>) -> std::io::Result<()> {
+        let db = Self::build_database().await.map_err(Self::to_io_err)?;
+        let transaction = db
+            .transaction(&[SHIELDED_CONTEXT_TABLE], TransactionMode::ReadWrite)
+            .map_err(Self::to_io_err)?;
+        let context_store = transaction
+            .store(SHIELDED_CONTEXT_TABLE)
+            .map_err(Self::to_io_err)?;
+        let key = JsValue::from_str(key);
+
+        match bytes {
+            Some(bytes) => context_store
+                .put(&JsValue::from_serde(&bytes).unwrap(), Some(&key))
+                .await
+                .map_err(Self::to_io_err)?,
+            None => context_store.delete(&key).await.map_err(Self::to_io_err)?,
+        };
+
+        Ok(())
+    }
+
+    async fn load_entry(key: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let db = Self::build_database().await.map_err(Self::to_io_err)?;
+        let transaction = db
+            .transaction(&[SHIELDED_CONTEXT_TABLE], TransactionMode::ReadOnly)
+            .map_err(Self::to_io_err)?;
+        let context_store = transaction
+            .store(SHIELDED_CONTEXT_TABLE)
+            .map_err(Self::to_io_err)?;
+
+        let entry = context_store
+            .get(&JsValue::from_str(key))
+            .await
+            .map_err(Self::to_io_err)?;
+        let bytes = to_bytes(entry);
+
+        Ok((!bytes.is_empty()).then_some(bytes))
+    }
+
     fn get_key(force_confirmed: bool, cache: bool) -> &'static str {
         if cache {
             SHIELDED_CONTEXT_KEY_TEMP
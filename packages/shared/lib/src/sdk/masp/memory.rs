@@ -0,0 +1,98 @@
+//! Memory budget of shielded sync. Low-RAM mobile browsers kill the tab once the wasm heap grows
+//! too large, and the heap never shrinks back, so hosts cap the fetched blocks held at once,
+//! prune witnesses which are never needed again and probe the heap size to throttle work.
+use namada_sdk::collections::{HashMap, HashSet};
+use namada_sdk::masp_primitives::merkle_tree::IncrementalWitness;
+use namada_sdk::masp_primitives::sapling::Node;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsCast, JsError, JsValue};
+
+use crate::utils::to_js_result;
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryLimits {
+    // Blocks fetched per request, None keeps the default of the block source
+    pub max_cached_blocks: Option<usize>,
+    // Drop witnesses of spent notes after every sync
+    pub prune_spent_witnesses: bool,
+}
+
+impl MemoryLimits {
+    /// Caps the batch size of a block source
+    pub fn batch_size(&self, default: usize) -> usize {
+        self.max_cached_blocks
+            .map_or(default, |max| std::cmp::min(default, max.max(1)))
+    }
+}
+
+/// Size of the wasm heap
+#[derive(Debug, Serialize, Tsify)]
+pub struct MemoryUsage {
+    // Bytes of linear memory allocated by the module, it only ever grows
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub heap_bytes: u64,
+}
+
+/// Removes witnesses of spent notes, which can't be spent again. Returns the number of removed
+/// witnesses.
+pub fn prune_spent_witnesses(
+    witness_map: &mut HashMap<usize, IncrementalWitness<Node>>,
+    spents: &HashSet<usize>,
+) -> usize {
+    let before = witness_map.len();
+    witness_map.retain(|note_pos, _| !spents.contains(note_pos));
+
+    before - witness_map.len()
+}
+
+/// Returns the size of the wasm heap, e.g. `{ heap_bytes: 67108864 }`
+#[wasm_bindgen]
+pub fn get_memory_usage() -> Result<JsValue, JsError> {
+    let memory = wasm_bindgen::memory()
+        .dyn_into::<js_sys::WebAssembly::Memory>()
+        .map_err(|_| JsError::new("Wasm memory is not available"))?;
+    let buffer = memory
+        .buffer()
+        .dyn_into::<js_sys::ArrayBuffer>()
+        .map_err(|_| JsError::new("Wasm memory is not available"))?;
+
+    to_js_result(MemoryUsage {
+        heap_bytes: buffer.byte_length() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::masp_primitives::merkle_tree::CommitmentTree;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn caps_batch_size() {
+        let limits = MemoryLimits {
+            max_cached_blocks: Some(10),
+            prune_spent_witnesses: false,
+        };
+
+        assert_eq!(limits.batch_size(100), 10);
+        assert_eq!(limits.batch_size(1), 1);
+        assert_eq!(MemoryLimits::default().batch_size(100), 100);
+    }
+
+    #[wasm_bindgen_test]
+    fn prunes_only_spent_witnesses() {
+        let mut tree = CommitmentTree::<Node>::empty();
+        tree.append(Node::new([1; 32])).unwrap();
+        let witness = IncrementalWitness::from_tree(&tree);
+        let mut witness_map = HashMap::new();
+        witness_map.insert(0, witness.clone());
+        witness_map.insert(1, witness);
+        let mut spents = HashSet::new();
+        spents.insert(1);
+
+        assert_eq!(prune_spent_witnesses(&mut witness_map, &spents), 1);
+        assert!(witness_map.contains_key(&0));
+    }
+}
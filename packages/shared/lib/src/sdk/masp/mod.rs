@@ -10,4 +10,12 @@ mod masp_node;
 #[cfg(feature = "nodejs")]
 pub use masp_node::NodeShieldedUtils as JSShieldedUtils;
 
+pub(crate) mod block_source;
+pub(crate) mod change;
+pub(crate) mod checkpoint;
+pub(crate) mod compact;
+pub(crate) mod integrity;
+pub(crate) mod memory;
+pub(crate) mod resume;
+pub(crate) mod snapshot;
 pub mod sync;
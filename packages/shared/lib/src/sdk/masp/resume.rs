@@ -0,0 +1,185 @@
+//! Resumable MASP work - MV3 service workers are killed after ~30s of work, so with a work
+//! store set, shielded sync advances in chunks of blocks and writes a cursor to the store after
+//! each of them, and MASP tx builds write their inputs until the proofs are built. A restarted
+//! worker calls `resume_pending_work` and continues from the cursor instead of starting over,
+//! or `resume_pending_build` to build the tx again. Only viewing keys and pseudo extended keys,
+//! which can't authorize spends, are written, never spending keys.
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use wasm_bindgen::{JsError, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+// Blocks synced between two cursor writes
+pub const CHUNK_BLOCKS: u64 = 2_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct SyncCursor {
+    // Extended viewing keys, spending keys are stored as their viewing keys
+    pub vks: Vec<String>,
+    pub birthdays: Vec<u64>,
+    // Height the shielded context is synced to
+    pub synced_to: u64,
+    // Height the sync has to reach
+    pub target_height: u64,
+}
+
+impl SyncCursor {
+    pub fn is_pending(&self) -> bool {
+        self.synced_to < self.target_height
+    }
+
+    /// Returns the height the next chunk syncs to, None if the target is reached
+    pub fn next_height(&self, chunk_blocks: u64) -> Option<u64> {
+        self.is_pending()
+            .then(|| std::cmp::min(self.synced_to + chunk_blocks, self.target_height))
+    }
+
+    /// Returns the heights the keys are scanned from, blocks up to the cursor were scanned
+    /// already for every key
+    pub fn scan_heights(&self) -> Vec<u64> {
+        self.birthdays
+            .iter()
+            .map(|birthday| std::cmp::max(*birthday, self.synced_to))
+            .collect()
+    }
+
+    pub fn advance(&mut self, height: u64) {
+        self.synced_to = std::cmp::max(self.synced_to, height);
+    }
+
+    /// Returns true if the cursor was written for the same keys, so it can be continued
+    pub fn has_keys(&self, vks: &[String]) -> bool {
+        let mut stored = self.vks.clone();
+        let mut vks = vks.to_vec();
+        stored.sort();
+        vks.sort();
+
+        stored == vks
+    }
+}
+
+/// MASP tx kinds whose build can be resumed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub enum BuildKind {
+    ShieldedTransfer,
+    UnshieldingTransfer,
+    ShieldingTransfer,
+}
+
+/// Inputs of a MASP tx build, stored until its proofs are built
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct PendingBuild {
+    pub kind: BuildKind,
+    // Borsh serialized transfer msg of the kind
+    pub msg: Vec<u8>,
+    // Borsh serialized WrapperTxMsg
+    pub wrapper_tx_msg: Vec<u8>,
+}
+
+/// Storage of the sync cursor or pending build provided by the host, e.g. backed by `chrome.storage.local`
+#[derive(Clone)]
+pub struct WorkStore {
+    // Called with a Uint8Array, or null to clear, may return a promise
+    save: js_sys::Function,
+    // Returns the saved Uint8Array or undefined, may return a promise
+    load: js_sys::Function,
+}
+
+impl WorkStore {
+    pub fn new(save: js_sys::Function, load: js_sys::Function) -> WorkStore {
+        WorkStore { save, load }
+    }
+
+    pub async fn save<T: BorshSerialize>(&self, work: Option<&T>) -> Result<(), JsError> {
+        let value = match work {
+            Some(work) => js_sys::Uint8Array::from(&borsh::to_vec(work)?[..]).into(),
+            None => JsValue::NULL,
+        };
+        let result = self
+            .save
+            .call1(&JsValue::NULL, &value)
+            .map_err(|_| JsError::new("Saving pending work failed"))?;
+        JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Saving pending work failed"))?;
+
+        Ok(())
+    }
+
+    pub async fn load<T: BorshDeserialize>(&self) -> Result<Option<T>, JsError> {
+        let result = self
+            .load
+            .call0(&JsValue::NULL)
+            .map_err(|_| JsError::new("Loading pending work failed"))?;
+        let value = JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Loading pending work failed"))?;
+        if value.is_undefined() || value.is_null() {
+            return Ok(None);
+        }
+
+        let bytes = js_sys::Uint8Array::new(&value).to_vec();
+        Ok(Some(borsh::from_slice(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn cursor() -> SyncCursor {
+        SyncCursor {
+            vks: vec![String::from("b"), String::from("a")],
+            birthdays: vec![0, 0],
+            synced_to: 100,
+            target_height: 4_500,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn advances_in_chunks() {
+        let mut cursor = cursor();
+
+        assert_eq!(cursor.next_height(CHUNK_BLOCKS), Some(2_100));
+        cursor.advance(2_100);
+        assert_eq!(cursor.next_height(CHUNK_BLOCKS), Some(4_100));
+        cursor.advance(4_100);
+        assert_eq!(cursor.next_height(CHUNK_BLOCKS), Some(4_500));
+        cursor.advance(4_500);
+        assert!(!cursor.is_pending());
+        assert_eq!(cursor.next_height(CHUNK_BLOCKS), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn scans_from_the_cursor() {
+        let mut cursor = cursor();
+        cursor.birthdays = vec![0, 3_000];
+
+        assert_eq!(cursor.scan_heights(), vec![100, 3_000]);
+        cursor.advance(4_100);
+        assert_eq!(cursor.scan_heights(), vec![4_100, 4_100]);
+    }
+
+    #[wasm_bindgen_test]
+    fn encodes_pending_builds() {
+        let pending = PendingBuild {
+            kind: BuildKind::UnshieldingTransfer,
+            msg: vec![1, 2],
+            wrapper_tx_msg: vec![3],
+        };
+        let bytes = borsh::to_vec(&pending).unwrap();
+
+        assert_eq!(borsh::from_slice::<PendingBuild>(&bytes).unwrap(), pending);
+    }
+
+    #[wasm_bindgen_test]
+    fn matches_keys_in_any_order() {
+        let cursor = cursor();
+
+        assert!(cursor.has_keys(&[String::from("a"), String::from("b")]));
+        assert!(!cursor.has_keys(&[String::from("a")]));
+    }
+}
@@ -0,0 +1,89 @@
+//! Snapshots of the shielded context taken while syncing. The commitment tree and witnesses can
+//! only be appended to, so `rescan_shielded` restores the newest snapshot at or below the height
+//! to rescan from and syncs again from there.
+use namada_sdk::borsh::{BorshDeserialize, BorshSerialize};
+
+// Snapshots kept, the oldest is dropped first
+pub const MAX_SNAPSHOTS: usize = 8;
+// Blocks between two kept snapshots, the newest snapshot is replaced until it is this far ahead
+pub const SNAPSHOT_INTERVAL: u64 = 10_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct Snapshot {
+    // Height the context was synced to
+    pub height: u64,
+    // Borsh encoded shielded context, as stored by the shielded utils
+    pub context: Vec<u8>,
+}
+
+/// Snapshots ordered by height
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct Snapshots(Vec<Snapshot>);
+
+impl Snapshots {
+    /// Adds a snapshot, dropping the ones at or above its height, e.g. taken before a rescan
+    pub fn push(&mut self, snapshot: Snapshot) {
+        self.0.retain(|kept| kept.height < snapshot.height);
+        if let [.., before, newest] = self.0.as_slice() {
+            if newest.height - before.height < SNAPSHOT_INTERVAL {
+                self.0.pop();
+            }
+        }
+        self.0.push(snapshot);
+        if self.0.len() > MAX_SNAPSHOTS {
+            self.0.remove(0);
+        }
+    }
+
+    /// Drops the snapshots above the height and returns the newest remaining one
+    pub fn rollback(&mut self, height: u64) -> Option<&Snapshot> {
+        self.0.retain(|kept| kept.height <= height);
+        self.0.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn snapshot(height: u64) -> Snapshot {
+        Snapshot {
+            height,
+            context: height.to_le_bytes().to_vec(),
+        }
+    }
+
+    fn heights(snapshots: &Snapshots) -> Vec<u64> {
+        snapshots.0.iter().map(|snapshot| snapshot.height).collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn keeps_snapshots_apart() {
+        let mut snapshots = Snapshots::default();
+        for height in [100, 2_100, 4_100, 12_100, 14_100] {
+            snapshots.push(snapshot(height));
+        }
+        assert_eq!(heights(&snapshots), vec![100, 12_100, 14_100]);
+
+        for height in 1..=MAX_SNAPSHOTS as u64 * 2 {
+            snapshots.push(snapshot(14_100 + height * SNAPSHOT_INTERVAL));
+        }
+        assert_eq!(snapshots.0.len(), MAX_SNAPSHOTS);
+    }
+
+    #[wasm_bindgen_test]
+    fn rolls_back_to_height() {
+        let mut snapshots = Snapshots::default();
+        for height in [100, 20_000, 40_000] {
+            snapshots.push(snapshot(height));
+        }
+
+        assert_eq!(snapshots.rollback(30_000), Some(&snapshot(20_000)));
+        assert_eq!(heights(&snapshots), vec![100, 20_000]);
+        assert_eq!(snapshots.rollback(50), None);
+        assert_eq!(heights(&snapshots), Vec::<u64>::new());
+    }
+}
@@ -1,51 +1,110 @@
+mod allowlist;
+mod annotations;
 mod args;
+#[cfg(all(test, feature = "bench"))]
+mod bench;
+mod custom_tx;
+mod effects;
 pub mod events;
+mod expired;
+mod fee_bump;
+pub(crate) mod flush;
+mod gas;
+pub(crate) mod history;
 pub mod io;
+mod ledger;
 pub mod masp;
+mod names;
+mod outbox;
+mod ownership;
+mod partial;
+pub(crate) mod payment_request;
+mod pending;
+mod permissions;
+mod queue;
+mod recipients;
+mod recovery;
+mod schema;
+mod sections;
+mod session;
+mod sign_doc;
 mod signature;
-mod transaction;
+mod signers;
+mod sources;
+mod sponsor;
+mod submit;
+mod sync;
+pub(crate) mod transaction;
 mod tx;
+mod validate;
 mod wallet;
 
 use self::io::WebIo;
-use crate::rpc_client::HttpClient;
+use crate::archival;
+use crate::governance::OfflineBallot;
+use crate::prices::PriceOracle;
+use crate::query::query_wasm_hashes;
+use crate::rpc_client::{headers_from_js, HttpClient};
+use crate::tokens;
+use crate::types::amount::StringAmount;
+use crate::types::key;
+use crate::types::query::{AbciQueryResult, ChainCapabilities, ProofOp};
 use crate::utils::set_panic_hook;
 #[cfg(feature = "web")]
 use crate::utils::to_bytes;
 use crate::utils::to_js_result;
 use args::{generate_masp_build_params, masp_sign, BuildParams};
+use bip39::{Language, Mnemonic, Seed};
 use gloo_utils::format::JsValueSerdeExt;
 use namada_sdk::address::{Address, MASP};
 use namada_sdk::args::{GenIbcShieldingTransfer, InputAmount, Query, TxExpiration};
 use namada_sdk::borsh::{self, BorshDeserialize};
+use namada_sdk::chain::BlockHeight;
 use namada_sdk::eth_bridge::bridge_pool::build_bridge_pool_tx;
+use namada_sdk::eth_bridge::{self, storage::eth_bridge_queries::EthBridgeStatus};
 use namada_sdk::hash::Hash;
 use namada_sdk::ibc::convert_masp_tx_to_ibc_memo;
 use namada_sdk::ibc::core::host::types::identifiers::{ChannelId, PortId};
-use namada_sdk::io::NamadaIo;
-use namada_sdk::key::{common, ed25519, SigScheme};
+use namada_sdk::io::{Client, NamadaIo};
+use namada_sdk::key::{common, ed25519, RefTo, SchemeType, SigScheme};
 use namada_sdk::masp::ShieldedContext;
+use namada_sdk::masp_primitives::sapling::Nullifier;
 use namada_sdk::masp_primitives::transaction::components::sapling::fees::InputView;
 use namada_sdk::masp_primitives::zip32::{ExtendedFullViewingKey, ExtendedKey};
-use namada_sdk::rpc::{query_epoch, InnerTxResult};
-use namada_sdk::signing::SigningTxData;
+use namada_sdk::parameters;
+use namada_sdk::queries::RPC;
+use namada_sdk::rpc::{
+    dry_run_tx, enriched_bonds_and_unbonds, get_account_info, get_token_balance,
+    is_public_key_revealed, query_denom, query_epoch, query_epoch_at_height, query_rewards,
+    query_storage_value, query_storage_value_bytes, InnerTxResult,
+};
+use namada_sdk::signing::{self, SigningTxData};
 use namada_sdk::string_encoding::Format;
-use namada_sdk::tendermint_rpc::Url;
-use namada_sdk::token::DenominatedAmount;
-use namada_sdk::token::{MaspTxId, OptionExt};
+use namada_sdk::tendermint::block::Height;
+use namada_sdk::tendermint_rpc::{query::Query as TmQuery, Order, Url};
+use namada_sdk::time::DateTimeUtc;
+use namada_sdk::token::{Amount, DenominatedAmount, MaspTxId, OptionExt};
 use namada_sdk::tx::{
-    build_batch, build_bond, build_claim_rewards, build_ibc_transfer, build_redelegation,
-    build_reveal_pk, build_shielded_transfer, build_shielding_transfer, build_transparent_transfer,
-    build_unbond, build_unshielding_transfer, build_vote_proposal, build_withdraw,
-    data::compute_inner_tx_hash, either::Either, gen_ibc_shielding_transfer, process_tx,
-    ProcessTxResponse, Tx,
+    build_batch, build_bond, build_claim_rewards, build_ibc_transfer, build_init_account,
+    build_redelegation, build_reveal_pk, build_shielded_transfer, build_shielding_transfer,
+    build_transparent_transfer, build_unbond, build_unshielding_transfer, build_update_account,
+    build_vote_proposal, build_withdraw, data::compute_inner_tx_hash, either::Either,
+    gen_ibc_shielding_transfer, prepare_tx, process_tx, ProcessTxResponse, Tx, TX_BOND_WASM,
+    TX_BRIDGE_POOL_WASM, TX_CLAIM_REWARDS_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
+    TX_REDELEGATE_WASM, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_UPDATE_ACCOUNT_WASM,
+    TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
 };
 use namada_sdk::wallet::{Store, Wallet};
 use namada_sdk::{Namada, NamadaImpl, PaymentAddress, TransferTarget};
-use std::collections::BTreeMap;
+use rand::rngs::OsRng;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
 use std::str::FromStr;
 use tx::MaspSigningData;
 use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use zeroize::Zeroizing;
 
 // Maximum number of spend description randomness parameters that can be
 // generated on the hardware wallet. It is hard to compute the exact required
@@ -62,22 +121,85 @@ const MAX_HW_CONVERT: usize = 15;
 // number because the number of outputs depends on the number of dummy outputs
 // introduced.
 const MAX_HW_OUTPUT: usize = 15;
+// BIP44 coin type registered for Namada
+const NAMADA_COIN_TYPE: u32 = 877;
+// Number of txs fetched per tx_search request of the history export
+const HISTORY_PAGE_SIZE: u8 = 100;
 
 /// Represents the Sdk public API.
 #[wasm_bindgen]
 pub struct Sdk {
     namada: NamadaImpl<HttpClient, wallet::JSWalletUtils, masp::JSShieldedUtils, WebIo>,
     rpc_url: String,
+    derived_paths: RefCell<wallet::DerivedPaths>,
+    read_only: bool,
+    permissions: RefCell<permissions::PermissionRegistry>,
+    sessions: RefCell<session::SessionRegistry>,
+    // Origin of the dapp the Sdk currently acts for, None in the extension's own context
+    origin: RefCell<Option<String>>,
+    approval_callback: RefCell<Option<js_sys::Function>>,
+    genesis_hash: RefCell<Option<String>>,
+    // Genesis hash of the chain the Sdk is connected to, queried once
+    chain_genesis_hash: RefCell<Option<String>>,
+    // Serves the genesis block if the RPC node pruned it, see `set_archival_rpc`
+    archival_client: RefCell<Option<HttpClient>>,
+    address_denylist: RefCell<BTreeSet<String>>,
+    screening_callback: RefCell<Option<js_sys::Function>>,
+    tx_allowlist: RefCell<Option<allowlist::TxAllowlist>>,
+    outbox: RefCell<outbox::Outbox>,
+    outbox_store: RefCell<Option<outbox::OutboxStore>>,
+    pending_spends: RefCell<pending::PendingSpends>,
+    dirty: RefCell<flush::DirtyState>,
+    account_queue: queue::AccountQueue,
+    names: RefCell<names::NameRegistry>,
+    name_resolver: RefCell<Option<js_sys::Function>>,
+    annotations: RefCell<annotations::Annotations>,
+    annotation_store: RefCell<Option<annotations::AnnotationStore>>,
+    // Oracle valuing exported history and the currency it values in
+    price_oracle: RefCell<Option<(Rc<PriceOracle>, String)>>,
+    sync: RefCell<Option<Rc<sync::EncryptedSync<sync::HttpSyncBackend>>>>,
+    // Highest fee per gas unit `bump_fee` raises to, a multiple of the current fee if None
+    max_bump_fee: RefCell<Option<DenominatedAmount>>,
+    // Set when MASP tx builds have to survive restarts of the worker
+    work_store: RefCell<Option<masp::resume::WorkStore>>,
 }
 
 #[wasm_bindgen]
 /// Sdk mostly wraps the logic of the Sdk struct members, making it a part of public API.
 /// For more details, navigate to the corresponding modules.
 impl Sdk {
+    /// Creates a new Sdk instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - RPC url
+    /// * `native_token` - address of the native token
+    /// * `path_or_db_name` - wallet storage path (nodejs) or database name (web)
+    /// * `headers` - optional `{ [name]: value }` object of headers sent with every RPC request
+    /// * `auth_token` - optional bearer token sent with every RPC request
+    /// * `transport` - optional `fetch`-like function all RPC requests are routed through
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the headers can't be deserialized
     #[wasm_bindgen(constructor)]
-    pub fn new(url: String, native_token: String, path_or_db_name: String) -> Self {
+    pub fn new(
+        url: String,
+        native_token: String,
+        path_or_db_name: String,
+        headers: Option<JsValue>,
+        auth_token: Option<String>,
+        transport: Option<js_sys::Function>,
+    ) -> Result<Sdk, JsError> {
         set_panic_hook();
-        let client: HttpClient = HttpClient::new(url.clone());
+        let headers = headers
+            .map(headers_from_js)
+            .transpose()?
+            .unwrap_or_default();
+        let mut client: HttpClient = HttpClient::new_with_headers(url.clone(), headers, auth_token);
+        if let Some(transport) = transport {
+            client = client.with_transport(transport);
+        }
         let wallet: Wallet<wallet::JSWalletUtils> = Wallet::new(
             wallet::JSWalletUtils::new_utils(&path_or_db_name),
             Store::default(),
@@ -93,284 +215,1899 @@ impl Sdk {
             Address::from_str(&native_token).unwrap(),
         );
 
-        Sdk {
+        Ok(Sdk {
             namada,
             rpc_url: url,
-        }
+            derived_paths: RefCell::new(wallet::DerivedPaths::default()),
+            read_only: false,
+            permissions: RefCell::new(permissions::PermissionRegistry::default()),
+            sessions: RefCell::new(session::SessionRegistry::default()),
+            origin: RefCell::new(None),
+            approval_callback: RefCell::new(None),
+            genesis_hash: RefCell::new(None),
+            chain_genesis_hash: RefCell::new(None),
+            archival_client: RefCell::new(None),
+            address_denylist: RefCell::new(BTreeSet::new()),
+            screening_callback: RefCell::new(None),
+            tx_allowlist: RefCell::new(None),
+            outbox: RefCell::new(outbox::Outbox::default()),
+            outbox_store: RefCell::new(None),
+            pending_spends: RefCell::new(pending::PendingSpends::default()),
+            dirty: RefCell::new(flush::DirtyState::default()),
+            account_queue: queue::AccountQueue::default(),
+            names: RefCell::new(names::NameRegistry::default()),
+            name_resolver: RefCell::new(None),
+            annotations: RefCell::new(annotations::Annotations::default()),
+            annotation_store: RefCell::new(None),
+            price_oracle: RefCell::new(None),
+            sync: RefCell::new(None),
+            max_bump_fee: RefCell::new(None),
+            work_store: RefCell::new(None),
+        })
     }
 
-    pub async fn has_masp_params() -> Result<JsValue, JsValue> {
-        let has = has_masp_params().await?;
+    /// Creates a new read-only Sdk instance. All calls that add keys, sign or submit
+    /// transactions return an error, which makes it safe to embed in dapps that must never
+    /// hold or request keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - RPC url
+    /// * `native_token` - address of the native token
+    /// * `headers` - optional `{ [name]: value }` object of headers sent with every RPC request
+    /// * `auth_token` - optional bearer token sent with every RPC request
+    /// * `transport` - optional `fetch`-like function all RPC requests are routed through
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the headers can't be deserialized
+    pub fn new_read_only(
+        url: String,
+        native_token: String,
+        headers: Option<JsValue>,
+        auth_token: Option<String>,
+        transport: Option<js_sys::Function>,
+    ) -> Result<Sdk, JsError> {
+        // Wallet is never persisted, so the storage name is irrelevant
+        let sdk = Sdk::new(
+            url,
+            native_token,
+            String::new(),
+            headers,
+            auth_token,
+            transport,
+        )?;
 
-        Ok(js_sys::Boolean::from(has.as_bool().unwrap()).into())
+        Ok(Sdk {
+            read_only: true,
+            ..sdk
+        })
     }
 
-    pub async fn fetch_and_store_masp_params(url: Option<String>) -> Result<(), JsValue> {
-        fetch_and_store_masp_params(url).await?;
-        Ok(())
+    /// Sets the time after which RPC requests are aborted. A timed out request fails with a
+    /// timeout error instead of hanging, so the call awaiting it returns and releases the account
+    /// queue and other state it holds.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_ms` - timeout in milliseconds, 0 disables it. Defaults to 30 seconds.
+    pub fn set_rpc_timeout(&self, timeout_ms: u32) {
+        self.namada.client().set_timeout(timeout_ms);
     }
 
-    #[cfg(feature = "web")]
-    pub async fn load_masp_params(&self, _db_name: JsValue) -> Result<(), JsValue> {
-        // _dn_name is not used in the web version for a time being
-        let params = get_masp_params().await?;
-        let params_iter = js_sys::try_iter(&params)?.ok_or("Can't iterate over JsValue")?;
-        let mut params_bytes = params_iter.map(|p| to_bytes(p.unwrap()));
-
-        let spend = params_bytes.next().unwrap();
-        let output = params_bytes.next().unwrap();
-        let convert = params_bytes.next().unwrap();
-
-        // We are making sure that there are no more params left
-        assert_eq!(params_bytes.next(), None);
-
-        let mut shielded = self.namada.shielded_mut().await;
-        *shielded = ShieldedContext::new(masp::JSShieldedUtils::new(spend, output, convert).await?);
-
-        Ok(())
+    /// Sets the RPC of an archival node, which serves the genesis block if the RPC node pruned
+    /// it, see `query_genesis_hash`. Headers of the RPC are not sent to it, as it's usually
+    /// another provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - archival RPC url, undefined removes it
+    pub fn set_archival_rpc(&self, url: Option<String>) {
+        let client = self.namada.client();
+        *self.archival_client.borrow_mut() =
+            url.map(|url| HttpClient::new(url).with_timeout_handle(client.timeout_handle()));
+        *self.chain_genesis_hash.borrow_mut() = None;
     }
 
-    #[cfg(feature = "nodejs")]
-    pub async fn load_masp_params(&self, context_dir: JsValue) -> Result<(), JsValue> {
-        let context_dir = context_dir.as_string().unwrap();
-
-        let mut shielded = self.namada.shielded_mut().await;
-        *shielded = ShieldedContext::new(masp::JSShieldedUtils::new(&context_dir).await);
+    /// Grants capabilities to the dapp origin
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - dapp origin, e.g. "https://dapp.example"
+    /// * `capabilities` - any of "read_addresses", "sign", "submit"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a capability is unknown
+    pub fn grant(&self, origin: String, capabilities: Vec<String>) -> Result<(), JsError> {
+        let capabilities = capabilities
+            .iter()
+            .map(|capability| permissions::Capability::from_str(capability))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.permissions.borrow_mut().grant(origin, capabilities);
 
         Ok(())
     }
 
-    pub async fn add_spending_key(&self, xsk: String, alias: String) {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet::add_spending_key(&mut wallet, xsk, alias)
+    /// Revokes all capabilities of the dapp origin and ends its session, the funds left on the
+    /// session address are swept with `revoke_session`
+    pub fn revoke(&self, origin: String) {
+        self.permissions.borrow_mut().revoke(&origin);
+        self.sessions.borrow_mut().end(&origin, DateTimeUtc::now());
     }
 
-    pub async fn add_viewing_key(&self, xvk: String, alias: String) {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet::add_viewing_key(&mut wallet, xvk, alias)
-    }
+    /// Returns true if the dapp origin was granted the capability
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capability is unknown
+    pub fn check(&self, origin: String, capability: String) -> Result<bool, JsError> {
+        let capability = permissions::Capability::from_str(&capability)?;
 
-    pub async fn add_payment_address(&self, pa: String, alias: String) {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet::add_payment_address(&mut wallet, pa, alias)
+        Ok(self.permissions.borrow().check(&origin, capability))
     }
 
-    pub async fn add_default_payment_address(&self, xvk: String, alias: String) {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet::add_default_payment_address(&mut wallet, xvk, alias)
-    }
+    /// Starts a session of the dapp origin with a new in-memory key, which signs transparent
+    /// transfers of the token from its implicit address without the approval callback. The user
+    /// funds the returned address with the budget, e.g.
+    /// `{ address: "tnam1...", budget: "10", expires_at: "2024-01-01T10:00:00+00:00", ... }`.
+    /// A new session replaces the previous one of the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - dapp origin, e.g. "https://game.example"
+    /// * `token` - address of the only token the session key transfers
+    /// * `max_per_tx` - decimal amount a single tx transfers at most
+    /// * `budget` - decimal amount all txs of the session transfer at most
+    /// * `ttl_secs` - seconds until the session expires, at most a day
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if called for a dapp origin, an argument is invalid or the Sdk is
+    /// read-only
+    pub fn create_session(
+        &self,
+        origin: String,
+        token: String,
+        max_per_tx: String,
+        budget: String,
+        ttl_secs: u64,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        if self.origin.borrow().is_some() {
+            return Err(JsError::new(
+                "Sessions can only be created in the extension's own context",
+            ));
+        }
+        let token = Address::from_str(&token)?;
+        let max_per_tx = StringAmount::new(max_per_tx).to_denominated()?;
+        let budget = StringAmount::new(budget).to_denominated()?;
+        let key = wallet::generate_secret_key(None, SchemeType::Ed25519)?;
 
-    pub async fn add_keypair(&self, secret_key: String, alias: String, password: Option<String>) {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet::add_keypair(&mut wallet, secret_key, alias, password)
+        to_js_result(self.sessions.borrow_mut().create(
+            origin,
+            key,
+            token,
+            max_per_tx,
+            budget,
+            ttl_secs,
+            DateTimeUtc::now(),
+        )?)
     }
 
-    pub async fn save_wallet(&self) -> Result<(), JsValue> {
-        let wallet = self.namada.wallet_mut().await;
-        wallet.save().map_err(JsError::from)?;
+    /// Ends the session of the dapp origin and returns a transfer, signed by the session key, of
+    /// the funds left on the session address back to the owner. The session key pays the fee of
+    /// the transfer. Expired sessions are swept the same way. The key is dropped once the session
+    /// address is empty, so call again after the sweep is applied; undefined is returned then.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - dapp origin, e.g. "https://game.example"
+    /// * `owner` - address the funds left are transferred to
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg of the sweep
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if called for a dapp origin, the origin has no session, the Sdk is
+    /// read-only or the sweep can't be built
+    pub async fn revoke_session(
+        &self,
+        origin: String,
+        owner: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        if self.origin.borrow().is_some() {
+            return Err(JsError::new(
+                "Sessions can only be revoked in the extension's own context",
+            ));
+        }
+        let owner = Address::from_str(&owner)?;
+        self.sessions.borrow_mut().end(&origin, DateTimeUtc::now());
+        let (key, token) = self.sessions.borrow().sweep_key(&origin)?;
+        let public_key = key.ref_to();
+        let address = Address::from(&public_key);
 
-        Ok(())
-    }
+        let client = self.namada.client();
+        let denom = query_denom(client, &token)
+            .await
+            .ok_or_else(|| JsError::new("Unknown token denomination"))?;
+        let balance = get_token_balance(client, &token, &address, None).await?;
+        let tx_args = args::tx_args_from_slice(wrapper_tx_msg)?;
+        // The fee is paid out of the swept token if it's the fee token
+        let fee = args::fee_in_token(&tx_args, &token, denom)?;
+        let Some(amount) = session::sweep_amount(balance, fee) else {
+            self.sessions.borrow_mut().remove(&origin);
+            return Ok(JsValue::UNDEFINED);
+        };
 
-    pub async fn load_wallet(&self) -> Result<(), JsValue> {
-        let mut wallet = self.namada.wallet_mut().await;
-        wallet.load().map_err(JsError::from)?;
+        let mut args = args::sweep_transfer_tx_args(
+            address,
+            owner,
+            token,
+            DenominatedAmount::new(amount, denom),
+            public_key,
+            wrapper_tx_msg,
+        )?;
+        let (mut namada_tx, signing_data) =
+            build_transparent_transfer(&self.namada, &mut args).await?;
+        if let Some(account_public_keys_map) = signing_data.account_public_keys_map {
+            namada_tx.sign_raw(
+                vec![key.clone()],
+                account_public_keys_map,
+                signing_data.owner,
+            );
+        }
+        namada_tx.sign_wrapper(key);
 
-        Ok(())
+        to_js_result(borsh::to_vec(&namada_tx)?)
     }
 
-    pub async fn sign_masp(&self, xsks: Box<[String]>, tx: Vec<u8>) -> Result<JsValue, JsError> {
+    /// Returns the sessions which didn't expire yet
+    pub fn sessions(&self) -> Result<JsValue, JsError> {
+        to_js_result(self.sessions.borrow().sessions(DateTimeUtc::now()))
+    }
+
+    /// Signs a tx with the session key of the current dapp origin, without calling the approval
+    /// callback. The tx has to be a transparent transfer from the session address, with the fee
+    /// paid by the session key in the session token. The amount plus the fee of the whole gas
+    /// limit has to be within the limits of the session and counts against the budget once it's
+    /// signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Borsh serialized TxMsg, as returned by the build functions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no dapp origin is set, the origin has no session or the tx is not
+    /// allowed by the session
+    pub async fn sign_with_session(&self, tx: Vec<u8>) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        let origin = self
+            .origin
+            .borrow()
+            .clone()
+            .ok_or_else(|| JsError::new("Session keys only sign for a dapp origin"))?;
         let tx: tx::Tx = borsh::from_slice(&tx)?;
         let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        self.ensure_genesis().await?;
 
-        // Use keys_map to easily map xfvk to xsk
-        let mut keys_map = BTreeMap::new();
-        for xsk_s in xsks.iter() {
-            let xsk = namada_sdk::ExtendedSpendingKey::from_str(xsk_s)?;
-            let xvk = xsk.to_viewing_key();
-            let xfvk = ExtendedFullViewingKey::from(xvk);
+        let kinds = self.tx_kinds(&namada_tx).await?;
+        // Inner txs with unknown code are not decoded, they can't be checked against the policy
+        if kinds.len() != namada_tx.commitments().len() {
+            return Err(session::SessionError::NotTransfer.into());
+        }
+        let wrapper = namada_tx
+            .header
+            .wrapper()
+            .ok_or(session::SessionError::WrongFeePayer)?;
+        let key =
+            self.sessions
+                .borrow_mut()
+                .authorize(&origin, &kinds, &wrapper, DateTimeUtc::now())?;
 
-            keys_map.insert(xfvk, xsk);
+        for signing_tx_data in tx.signing_tx_data()? {
+            if let Some(account_public_keys_map) = signing_tx_data.account_public_keys_map {
+                namada_tx.sign_raw(
+                    vec![key.clone()],
+                    account_public_keys_map,
+                    signing_tx_data.owner,
+                );
+            }
         }
+        namada_tx.sign_wrapper(key);
 
-        for signing_data in tx.signing_data() {
-            if let Some(masp_signing_data) = signing_data.masp() {
-                let masp_signing_data =
-                    borsh::from_slice::<MaspSigningData>(&masp_signing_data).ok();
+        to_js_result(borsh::to_vec(&namada_tx)?)
+    }
 
-                if let Some(masp_signing_data) = masp_signing_data {
-                    let signing_tx_data = signing_data.to_signing_tx_data()?;
-                    let bparams = masp_signing_data.bparams();
+    /// Sets the dapp origin subsequent calls are made for. While set, listing addresses,
+    /// signing and submitting txs require the corresponding capability to be granted.
+    /// Pass undefined to act in the extension's own context.
+    pub fn set_origin(&self, origin: Option<String>) {
+        *self.origin.borrow_mut() = origin;
+    }
 
-                    for xfvk in masp_signing_data.xfvks() {
-                        let xsk = keys_map.get(&xfvk).ok_or_err_msg("Can't map xfvk to xsk")?;
-                        masp_sign(&mut namada_tx, &signing_tx_data, bparams.clone(), *xsk).await?;
-                    }
-                }
-            }
+    /// Sets the approval callback, which is called with the sign doc(`{ doc, hash }`) before
+    /// every tx signature and has to resolve to true for the signing to proceed. Off-chain
+    /// statements, e.g. ownership proofs, are passed as a `{ statement }` doc. The callback
+    /// can be set only once. If it's not set, signing is denied while acting for a dapp origin.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the callback was already set
+    pub fn set_approval_callback(&self, callback: js_sys::Function) -> Result<(), JsError> {
+        let mut approval_callback = self.approval_callback.borrow_mut();
+        if approval_callback.is_some() {
+            return Err(JsError::new("Approval callback can be set only once"));
         }
+        *approval_callback = Some(callback);
 
-        let signing_data = tx
-            .signing_tx_data()?
-            .iter()
-            .cloned()
-            .map(|std| (std, None))
-            .collect::<Vec<(SigningTxData, Option<MaspSigningData>)>>();
+        Ok(())
+    }
 
-        // Recreate the tx with the new signatures, we can pass None for masp_signing_data as it
-        // was already used
-        let tx = tx::Tx::new(namada_tx, &borsh::to_vec(&tx.args())?, signing_data)?;
+    /// Sets addresses which can't receive transfers, checked before every broadcast
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - flagged addresses, including payment addresses and IBC receivers on other
+    ///   chains
+    pub fn set_address_denylist(&self, addresses: Vec<String>) {
+        *self.address_denylist.borrow_mut() = addresses.into_iter().collect();
+    }
 
-        to_js_result(borsh::to_vec(&tx)?)
+    /// Sets the screening callback, which is called with the recipients of transfers in the tx
+    /// before every broadcast, and with the payment addresses of shielded transfers before they
+    /// are built, and has to resolve to true for the tx to be submitted. Txs are blocked if the
+    /// callback fails or their inner txs can't be decoded. Pass undefined to remove the callback.
+    pub fn set_screening_callback(&self, callback: Option<js_sys::Function>) {
+        *self.screening_callback.borrow_mut() = callback;
     }
 
-    pub async fn sign_tx(
-        &self,
-        tx: Vec<u8>,
-        private_key: Option<String>,
-        chain_id: Option<String>,
-    ) -> Result<JsValue, JsError> {
-        let tx: tx::Tx = borsh::from_slice(&tx)?;
-        let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+    /// Sets the highest fee per gas unit `bump_fee` raises a fee to. Pass undefined to go back to
+    /// the default, which allows bumps up to ten times the current fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_fee` - decimal fee per gas unit, in the fee token
+    pub fn set_max_bump_fee(&self, max_fee: Option<String>) -> Result<(), JsError> {
+        let max_fee = max_fee
+            .map(|fee| DenominatedAmount::from_str(&fee))
+            .transpose()
+            .map_err(|err| JsError::new(&format!("Invalid fee: {}", err)))?;
+        *self.max_bump_fee.borrow_mut() = max_fee;
+        Ok(())
+    }
 
-        // If chain_id is provided, validate this against value in Tx header
-        if let Some(c) = chain_id {
-            if c != namada_tx.header.chain_id.to_string() {
-                return Err(JsError::new(&format!(
-                    "chain_id {} does not match Tx header chain_id {}",
-                    &c,
-                    namada_tx.header.chain_id.as_str()
-                )));
-            }
-        }
+    /// Sets the name resolver, which is called with names like "alice.namada" and has to resolve
+    /// to the address or undefined. Pass undefined to remove the resolver.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - function resolving a name to an address
+    /// * `ttl_secs` - optional time resolved names are cached for, 300 seconds by default
+    pub fn set_name_resolver(&self, resolver: Option<js_sys::Function>, ttl_secs: Option<u32>) {
+        *self.name_resolver.borrow_mut() = resolver;
+        self.names
+            .borrow_mut()
+            .set_ttl(ttl_secs.map_or(names::DEFAULT_TTL_SECS, u64::from));
+    }
 
-        let signing_keys = match private_key.clone() {
-            Some(private_key) => vec![common::SecretKey::Ed25519(ed25519::SecretKey::from_str(
-                &private_key,
-            )?)],
-            // If no private key is provided, we assume masp source and return empty vec
-            None => vec![],
-        };
+    /// Registers a name locally, registered names take precedence over the resolver
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address is not a transparent or shielded address
+    pub fn register_name(&self, name: String, address: String) -> Result<(), JsError> {
+        self.names.borrow_mut().register(&name, address)
+    }
 
-        for signing_tx_data in tx.signing_tx_data()? {
-            if let Some(account_public_keys_map) = signing_tx_data.account_public_keys_map.clone() {
-                // We only sign the raw header for transfers from transparent source
-                if !signing_keys.is_empty() {
-                    // Sign the raw header
-                    namada_tx.sign_raw(
-                        signing_keys.clone(),
-                        account_public_keys_map,
-                        signing_tx_data.owner.clone(),
-                    );
-                }
-            }
+    /// Resolves a recipient name to an address the transfer builders accept. Addresses are
+    /// returned as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - name like "alice.namada" or an address
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name can't be resolved or resolves to an invalid address
+    pub async fn resolve_recipient(&self, recipient: String) -> Result<String, JsError> {
+        if names::is_address(&recipient) {
+            return Ok(recipient);
         }
 
-        // The key is either passed private key for transparent sources or the disposable signing
-        // key for shielded sources
-        let key = signing_keys[0].clone();
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        if let Some(address) = self.names.borrow().get(&recipient, now) {
+            return Ok(address);
+        }
 
-        // Sign the fee header
-        namada_tx.sign_wrapper(key);
+        // Function is cloned so the RefCell is not borrowed across the await
+        let resolver = self
+            .name_resolver
+            .borrow()
+            .clone()
+            .ok_or_else(|| JsError::new(&format!("Unknown name {}", recipient)))?;
+        let result = resolver
+            .call1(&JsValue::NULL, &JsValue::from(&recipient))
+            .map_err(|_| JsError::new("Name resolver failed"))?;
+        let address = JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Name resolver failed"))?
+            .as_string()
+            .ok_or_else(|| JsError::new(&format!("Unknown name {}", recipient)))?;
 
-        to_js_result(borsh::to_vec(&namada_tx)?)
+        names::verify_address(&address)?;
+        self.names
+            .borrow_mut()
+            .cache(&recipient, address.clone(), now);
+
+        Ok(address)
     }
 
-    // Broadcast Tx
-    pub async fn process_tx(&self, tx_bytes: &[u8], tx_msg: &[u8]) -> Result<JsValue, JsError> {
-        let args = args::tx_args_from_slice(tx_msg)?;
-        let tx = Tx::try_from_slice(tx_bytes)?;
-        let cmts = tx.commitments().clone();
-        let wrapper_hash = tx.wrapper_hash();
-        let resp = process_tx(&self.namada, &args, tx.clone()).await?;
+    /// Sets the store tx annotations are persisted in and loads the annotations saved there
+    ///
+    /// # Arguments
+    ///
+    /// * `save` - function called with the encoded annotations as Uint8Array
+    /// * `load` - function returning the saved Uint8Array or undefined
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading fails or the saved annotations are invalid
+    pub async fn set_annotation_store(
+        &self,
+        save: js_sys::Function,
+        load: js_sys::Function,
+    ) -> Result<(), JsError> {
+        let store = annotations::AnnotationStore::new(save, load);
+        let saved = store.load().await?;
+        self.annotations.borrow_mut().merge(saved);
+        *self.annotation_store.borrow_mut() = Some(store);
 
-        let mut batch_tx_results: Vec<tx::BatchTxResult> = vec![];
+        Ok(())
+    }
 
-        // Collect results and return
-        match resp {
-            ProcessTxResponse::Applied(tx_response) => {
-                let code = tx_response.code.to_string();
-                let gas_used = tx_response.gas_used.to_string();
-                let height = tx_response.height.to_string();
-                let info = tx_response.info.to_string();
-                let log = tx_response.log.to_string();
+    /// Labels and tags a tx locally, e.g. `annotate_tx(hash, "rent", ["home"])`. An empty label
+    /// and no tags remove the annotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - hash of the tx
+    /// * `label` - label of the tx, at most 64 characters
+    /// * `tags` - case insensitive tags, at most 16
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hash, label or tags are invalid or saving fails
+    pub async fn annotate_tx(
+        &self,
+        hash: String,
+        label: String,
+        tags: Vec<String>,
+    ) -> Result<(), JsError> {
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        self.annotations
+            .borrow_mut()
+            .annotate(&hash, label, tags, now)?;
 
-                for cmt in cmts {
-                    let hash = compute_inner_tx_hash(wrapper_hash.as_ref(), Either::Right(&cmt));
+        self.save_annotations().await
+    }
 
-                    if let Some(InnerTxResult::Success(_)) = tx_response.batch_result().get(&hash) {
-                        batch_tx_results.push(tx::BatchTxResult::new(hash.to_string(), true));
-                    } else {
-                        batch_tx_results.push(tx::BatchTxResult::new(hash.to_string(), false));
-                    }
-                }
+    /// Returns tx annotations, only the ones having the tag if provided
+    pub fn get_annotations(&self, tag: Option<String>) -> Result<JsValue, JsError> {
+        to_js_result(self.annotations.borrow().list(tag.as_deref()))
+    }
 
-                let response = tx::TxResponse::new(
-                    code,
-                    batch_tx_results,
-                    gas_used,
-                    wrapper_hash.unwrap().to_string(),
-                    height,
-                    info,
-                    log,
+    /// Adds the `annotation` of every tx to the history returned by `Query::query_tx_search`
+    ///
+    /// # Arguments
+    ///
+    /// * `history` - tx search result, or an array of txs with a `hash` field
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history can't be deserialized
+    pub fn with_annotations(&self, history: JsValue) -> Result<JsValue, JsError> {
+        let mut history: serde_json::Value = history.into_serde()?;
+        let annotations = self.annotations.borrow();
+
+        let txs = match &mut history {
+            serde_json::Value::Array(txs) => Some(txs),
+            serde_json::Value::Object(result) => {
+                result.get_mut("txs").and_then(|txs| txs.as_array_mut())
+            }
+            _ => None,
+        };
+        for tx in txs.into_iter().flatten() {
+            let annotation = tx
+                .get("hash")
+                .and_then(|hash| hash.as_str())
+                .and_then(|hash| annotations.get(hash));
+            if let (Some(tx), Some(annotation)) = (tx.as_object_mut(), annotation) {
+                tx.insert(
+                    String::from("annotation"),
+                    serde_json::to_value(annotation)?,
                 );
-                to_js_result(borsh::to_vec(&response)?)
             }
-            _ => Err(JsError::new(&format!(
-                "Tx not applied: {}",
-                &wrapper_hash.unwrap().to_string()
-            ))),
         }
+
+        to_js_result(history)
     }
 
-    /// Build a batch Tx from built transactions and return the bytes
-    pub fn build_batch(txs: JsValue) -> Result<JsValue, JsError> {
-        let mut built_txs: Vec<tx::Tx> = vec![];
-        let built_txs_bytes: Vec<Vec<u8>> = txs.into_serde().unwrap();
+    /// Returns the encoded tx annotations to be included in backups
+    pub fn export_annotations(&self) -> Result<Vec<u8>, JsError> {
+        self.annotations.borrow().encode()
+    }
 
-        for bytes in built_txs_bytes.iter() {
-            let tx: tx::Tx = borsh::from_slice(bytes)?;
-            built_txs.push(tx);
+    /// Merges annotations exported by `export_annotations`, the most recently changed one wins
+    /// if a tx is annotated in both
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the annotations are invalid or saving fails
+    pub async fn import_annotations(&self, bytes: Vec<u8>) -> Result<(), JsError> {
+        let imported = annotations::Annotations::decode(&bytes)?;
+        self.annotations.borrow_mut().merge(imported);
+
+        self.save_annotations().await
+    }
+
+    /// Sets the storage wallet metadata is synced to across devices, e.g. a WebDAV server or
+    /// an S3 bucket with presigned URLs. Blobs are encrypted with a key derived from
+    /// `sync_secret` before they leave the device. Pass undefined as both the template and the
+    /// callback to stop syncing.
+    ///
+    /// # Arguments
+    ///
+    /// * `url_template` - URL of the blobs with a `{name}` placeholder
+    /// * `presign` - function called with the blob name and "GET" or "PUT", returning the URL
+    /// * `sync_secret` - at least 32 bytes shared by the devices of the user, e.g. WebAuthn PRF
+    ///   output
+    /// * `headers` - optional `{ [name]: value }` object of headers, e.g. WebDAV credentials
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret is too short, or the template or headers are invalid
+    pub fn set_sync_backend(
+        &self,
+        url_template: Option<String>,
+        presign: Option<js_sys::Function>,
+        sync_secret: Vec<u8>,
+        headers: Option<JsValue>,
+    ) -> Result<(), JsError> {
+        self.ensure_writable()?;
+        if url_template.is_none() && presign.is_none() {
+            *self.sync.borrow_mut() = None;
+            return Ok(());
         }
 
-        // Get wrapper args
-        let first_tx = built_txs
-            .first()
-            .expect("At least one Tx is required for building batches!");
+        let sync_secret = Zeroizing::new(sync_secret);
+        let headers = headers
+            .map(headers_from_js)
+            .transpose()?
+            .unwrap_or_default();
+        let backend = sync::HttpSyncBackend::new(
+            url_template,
+            presign,
+            headers,
+            self.namada.client().timeout_handle(),
+        )?;
+        let cipher = sync::SyncCipher::new(&sync_secret)?;
+        *self.sync.borrow_mut() = Some(Rc::new(sync::EncryptedSync::new(backend, cipher)));
 
-        let args = first_tx.args();
+        Ok(())
+    }
 
-        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
-        let mut masp_map: BTreeMap<MaspTxId, MaspSigningData> = BTreeMap::new();
+    /// Uploads tx annotations and registered names to the sync backend. Public and viewing keys
+    /// are only uploaded with `include_keys`, spending keys never. Returns the uploaded blobs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no sync backend is set or uploading fails
+    pub async fn sync_push(&self, include_keys: bool) -> Result<JsValue, JsError> {
+        let sync = self.sync_backend()?;
+        let mut pushed = vec![];
 
-        // Iterate through provided tx::Tx and deserialize bytes to Namada Tx
-        for built_tx in built_txs.into_iter() {
-            let tx_bytes = built_tx.tx_bytes();
-            let signing_data = built_tx.signing_data();
-            let first_signing_data = signing_data
-                .first()
-                .expect("At least one signing data should be present on a Tx");
+        let annotations = self.annotations.borrow().encode()?;
+        sync.put(sync::ANNOTATIONS_BLOB, &annotations).await?;
+        pushed.push(sync::ANNOTATIONS_BLOB);
 
-            let signing_tx_data = first_signing_data.to_signing_tx_data()?;
+        let names = serde_json::to_vec(self.names.borrow().registered())?;
+        sync.put(sync::NAMES_BLOB, &names).await?;
+        pushed.push(sync::NAMES_BLOB);
 
-            if let Some(sh) = signing_tx_data.shielded_hash {
-                let masp_signing_data = first_signing_data.masp();
+        if include_keys {
+            self.ensure_permitted(permissions::Capability::ReadAddresses)?;
+            let descriptor = wallet::export_descriptor(&*self.namada.wallet().await);
+            sync.put(sync::WALLET_BLOB, &serde_json::to_vec(&descriptor)?)
+                .await?;
+            pushed.push(sync::WALLET_BLOB);
+        }
 
-                // We do not need to insert masp_signing_data when we shield
-                if let Some(masp_signing_data) = masp_signing_data {
-                    let masp_signing_data = <MaspSigningData>::try_from_slice(&masp_signing_data)?;
-                    masp_map.insert(sh, masp_signing_data);
-                }
-            }
+        to_js_result(pushed)
+    }
 
-            let tx: Tx = Tx::try_from_slice(&tx_bytes)?;
+    /// Downloads the metadata uploaded by `sync_push` on other devices and merges it. The most
+    /// recently changed annotation wins, keys already in the wallet are skipped. Returns the
+    /// merged blobs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no sync backend is set, downloading fails or a blob can't be
+    /// decrypted
+    pub async fn sync_pull(&self) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        let sync = self.sync_backend()?;
+        let mut pulled = vec![];
 
-            txs.push((tx, signing_tx_data.to_owned()));
+        if let Some(annotations) = sync.get(sync::ANNOTATIONS_BLOB).await? {
+            let annotations = annotations::Annotations::decode(&annotations)?;
+            self.annotations.borrow_mut().merge(annotations);
+            self.save_annotations().await?;
+            pulled.push(sync::ANNOTATIONS_BLOB);
         }
 
-        let (tx, signing_data) = build_batch(txs.clone())?;
+        if let Some(names) = sync.get(sync::NAMES_BLOB).await? {
+            let names: BTreeMap<String, String> = serde_json::from_slice(&names)?;
+            let mut registry = self.names.borrow_mut();
+            for (name, address) in names {
+                registry.register(&name, address)?;
+            }
+            pulled.push(sync::NAMES_BLOB);
+        }
 
-        let signing_data = signing_data
+        if let Some(descriptor) = sync.get(sync::WALLET_BLOB).await? {
+            let descriptor: wallet::WalletDescriptor = serde_json::from_slice(&descriptor)?;
+            let mut wallet = self.namada.wallet_mut().await;
+            if !wallet::import_descriptor(&mut wallet, descriptor)?
+                .imported
+                .is_empty()
+            {
+                self.dirty.borrow_mut().wallet = true;
+                *self.derived_paths.borrow_mut() = wallet::derived_paths(&wallet);
+            }
+            pulled.push(sync::WALLET_BLOB);
+        }
+
+        to_js_result(pulled)
+    }
+
+    /// Uploads an encrypted blob of the host, e.g. app settings
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - blob name of `[a-z0-9_-]` characters, not used by `sync_push`
+    /// * `data` - blob content
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no sync backend is set, the name is invalid or uploading fails
+    pub async fn sync_put(&self, name: String, data: Vec<u8>) -> Result<(), JsError> {
+        self.ensure_writable()?;
+        if [sync::ANNOTATIONS_BLOB, sync::NAMES_BLOB, sync::WALLET_BLOB].contains(&name.as_str()) {
+            return Err(JsError::new(&format!("Blob name {} is reserved", name)));
+        }
+
+        self.sync_backend()?.put(&name, &data).await
+    }
+
+    /// Downloads and decrypts a blob uploaded with `sync_put`, undefined if it doesn't exist
+    pub async fn sync_get(&self, name: String) -> Result<Option<Vec<u8>>, JsError> {
+        self.sync_backend()?.get(&name).await
+    }
+
+    /// Returns names of the blobs stored by the sync backend
+    pub async fn sync_list(&self) -> Result<Vec<String>, JsError> {
+        self.sync_backend()?.list().await
+    }
+
+    /// Sets the price oracle valuing the exported tx history in fiat. If the URL template has a
+    /// `{timestamp}` placeholder, txs are valued at the price of their day, otherwise at the
+    /// current price. Pass undefined to remove the oracle.
+    ///
+    /// # Arguments
+    ///
+    /// * `url_template` - endpoint URL with `{token}`, `{currency}` and optionally `{timestamp}`
+    ///   placeholders, returning `{ "price": "1.23" }`
+    /// * `currency` - fiat currency, e.g. "usd"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL template has no `{token}` placeholder
+    pub fn set_price_oracle(
+        &self,
+        url_template: Option<String>,
+        currency: String,
+    ) -> Result<(), JsError> {
+        *self.price_oracle.borrow_mut() = match url_template {
+            Some(url_template) => Some((
+                Rc::new(PriceOracle::new(url_template, None, None)?),
+                currency,
+            )),
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    /// Exports the tx history of the addresses for tax and accounting, one row per transfer,
+    /// bond, unbond or fee payment with its epoch, time, counterparties, annotation label and,
+    /// if a price oracle is set, fiat value
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - transparent addresses to export the history of
+    /// * `from_height` - first block of the range
+    /// * `to_height` - optional last block of the range, the latest block by default
+    /// * `format` - "csv" or "json"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an address, the range or the format is invalid or the RPC calls fail
+    pub async fn export_history(
+        &self,
+        addresses: Vec<String>,
+        from_height: u64,
+        to_height: Option<u64>,
+        format: String,
+    ) -> Result<String, JsError> {
+        let format = history::HistoryFormat::from_str(&format)?;
+        let addresses = addresses
+            .iter()
+            .map(|address| Address::from_str(address))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        if from_height == 0 || to_height.is_some_and(|to_height| to_height < from_height) {
+            return Err(JsError::new(&format!(
+                "Invalid height range: {} to {:?}",
+                from_height, to_height
+            )));
+        }
+        let mut query = format!("tx.height >= {}", from_height);
+        if let Some(to_height) = to_height {
+            query.push_str(&format!(" AND tx.height <= {}", to_height));
+        }
+        let query = TmQuery::from_str(&query)?;
+
+        let client = self.namada.client();
+        // Pruned heights are left out of the search instead of failing it
+        let earliest = client
+            .status()
+            .await?
+            .sync_info
+            .earliest_block_height
+            .value();
+        archival::route(from_height, earliest, false)?;
+        let native_token = self.namada.native_token();
+        let wasm_hashes = query_wasm_hashes(client).await?;
+        // Epoch and block time of the heights
+        let mut blocks: BTreeMap<u64, (Option<u64>, String)> = BTreeMap::new();
+        let mut entries = vec![];
+        let mut page = 1;
+        loop {
+            let response = client
+                .tx_search(
+                    query.clone(),
+                    false,
+                    page,
+                    HISTORY_PAGE_SIZE,
+                    Order::Ascending,
+                )
+                .await?;
+
+            for tx_response in &response.txs {
+                let tx = match Tx::try_from_slice(&tx_response.tx) {
+                    Ok(tx) if tx_response.tx_result.code.is_ok() => tx,
+                    _ => continue,
+                };
+                let height = tx_response.height.value();
+                let (epoch, timestamp) = match blocks.get(&height) {
+                    Some(block) => block.clone(),
+                    None => {
+                        let epoch = archival::with_pruned_height(
+                            query_epoch_at_height(client, BlockHeight(height)).await,
+                        )?
+                        .map(|epoch| epoch.0);
+                        let block =
+                            archival::with_pruned_height(client.block(tx_response.height).await)?;
+                        let block = (epoch, block.block.header.time.to_rfc3339());
+                        blocks.insert(height, block.clone());
+                        block
+                    }
+                };
+                let applied = tx_response
+                    .tx_result
+                    .events
+                    .iter()
+                    .filter(|event| event.kind == "tx/applied")
+                    .flat_map(|event| event.attributes.iter())
+                    .find(|attribute| attribute.key_str().ok() == Some("batch"))
+                    .and_then(|attribute| attribute.value_str().ok())
+                    .map(history::applied_inner_txs)
+                    .unwrap_or_default();
+                let hash = tx_response.hash.to_string();
+                let ctx = history::TxContext {
+                    label: self
+                        .annotations
+                        .borrow()
+                        .get(&hash)
+                        .map(|annotation| annotation.label.clone()),
+                    hash,
+                    height,
+                    epoch,
+                    timestamp,
+                    memo: None,
+                };
+
+                entries.extend(history::tx_entries(
+                    &tx,
+                    &ctx,
+                    &applied,
+                    &addresses,
+                    &native_token,
+                    &wasm_hashes,
+                ));
+            }
+
+            let fetched = page as u64 * u64::from(HISTORY_PAGE_SIZE);
+            if response.txs.is_empty() || fetched >= u64::from(response.total_count) {
+                break;
+            }
+            page += 1;
+        }
+
+        // Oracle is cloned so the RefCell is not borrowed across the awaits
+        let price_oracle = self.price_oracle.borrow().clone();
+        if let Some((oracle, currency)) = price_oracle {
+            for entry in entries.iter_mut() {
+                entry.fiat_value = history::fiat_value(&oracle, &currency, entry).await;
+            }
+        }
+
+        match format {
+            history::HistoryFormat::Csv => Ok(history::to_csv(&entries)),
+            history::HistoryFormat::Json => Ok(serde_json::to_string(&entries)?),
+        }
+    }
+
+    /// Binds signing and submission to the chain with the given genesis hash. They fail while
+    /// the RPC node serves another chain, e.g. a relaunched network reusing the chain_id. The
+    /// hash is not part of the signed tx, so this checks which chain the node serves and doesn't
+    /// keep a signed tx from being replayed on another chain with the same chain_id. The hash is
+    /// also included in the sign doc.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - hash of the first block of the chain, see `query_genesis_hash`
+    pub fn bind_to_genesis(&self, hash: String) {
+        *self.genesis_hash.borrow_mut() = Some(hash.to_uppercase());
+    }
+
+    /// Returns the hash of the first block of the chain the Sdk is connected to. It's queried
+    /// once, from the archival RPC if the RPC node pruned the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails or the RPC node pruned the block and no archival
+    /// RPC is set
+    pub async fn query_genesis_hash(&self) -> Result<String, JsError> {
+        if let Some(hash) = self.chain_genesis_hash.borrow().clone() {
+            return Ok(hash);
+        }
+
+        let client = self.namada.client();
+        let earliest = client
+            .status()
+            .await?
+            .sync_info
+            .earliest_block_height
+            .value();
+        let archival_client = self.archival_client.borrow().clone();
+        let response = match archival::route(1, earliest, archival_client.is_some())? {
+            archival::Route::Primary => client.block(Height::from(1u32)).await,
+            archival::Route::Archival => {
+                let archival_client = archival_client.expect("archival client to be set");
+                archival_client.block(Height::from(1u32)).await
+            }
+        };
+        let hash = archival::with_pruned_height(response)?
+            .block_id
+            .hash
+            .to_string()
+            .to_uppercase();
+        *self.chain_genesis_hash.borrow_mut() = Some(hash.clone());
+
+        Ok(hash)
+    }
+
+    /// Refreshes wasm hashes of the tx codes and the tx allowlist of the chain. Build functions
+    /// load them on the first call and reject txs which are not enabled on the chain. While the
+    /// allowlist can't be loaded, all txs are rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails or the chain has no allowlist parameter
+    pub async fn refresh_tx_allowlist(&self) -> Result<(), JsError> {
+        // A failed refresh doesn't keep allowing txs by the previous allowlist
+        *self.tx_allowlist.borrow_mut() = None;
+
+        let client = self.namada.client();
+        let wasm_hashes = query_wasm_hashes(client).await?;
+        let (allowlist, _) = query_storage_value_bytes(
+            client,
+            &parameters::storage::get_tx_allowlist_storage_key(),
+            None,
+            false,
+        )
+        .await?;
+        let allowlist =
+            allowlist.ok_or_else(|| JsError::new("Tx allowlist is not set on the target chain"))?;
+        let allowlist = Vec::<String>::try_from_slice(&allowlist)
+            .map_err(|err| JsError::new(&format!("Invalid tx allowlist: {}", err)))?;
+
+        *self.tx_allowlist.borrow_mut() = Some(allowlist::TxAllowlist::new(wasm_hashes, allowlist));
+
+        Ok(())
+    }
+
+    /// Returns tx types and features supported by the target chain, so unsupported features can
+    /// be hidden instead of failing at submit time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC call fails
+    pub async fn get_chain_capabilities(&self) -> Result<JsValue, JsError> {
+        let (tx_types, transfer_allowed, bridge_pool_allowed, governance_version) = self
+            .with_tx_allowlist(|tx_allowlist| {
+                (
+                    tx_allowlist
+                        .tx_types()
+                        .iter()
+                        .map(|tx_type| tx_type.name().to_string())
+                        .collect::<Vec<String>>(),
+                    tx_allowlist.is_allowed(TX_TRANSFER_WASM),
+                    tx_allowlist.is_allowed(TX_BRIDGE_POOL_WASM),
+                    tx_allowlist.allowed_hash(TX_VOTE_PROPOSAL),
+                )
+            })
+            .await?;
+
+        let masp = transfer_allowed
+            && query_storage_value::<_, u64>(
+                self.namada.client(),
+                &parameters::storage::get_masp_epoch_multiplier_key(),
+            )
+            .await
+            .is_ok();
+        let eth_bridge = bridge_pool_allowed
+            && matches!(
+                query_storage_value::<_, EthBridgeStatus>(
+                    self.namada.client(),
+                    &eth_bridge::storage::active_key(),
+                )
+                .await,
+                Ok(EthBridgeStatus::Enabled(_))
+            );
+
+        to_js_result(ChainCapabilities {
+            tx_types,
+            masp,
+            eth_bridge,
+            governance_version,
+        })
+    }
+
+    /// Returns aliases and addresses stored in the wallet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current origin was not granted "read_addresses"
+    pub async fn list_addresses(&self) -> Result<JsValue, JsError> {
+        self.ensure_permitted(permissions::Capability::ReadAddresses)?;
+        let wallet = self.namada.wallet().await;
+        let addresses: BTreeMap<String, String> = wallet
+            .get_addresses()
+            .into_iter()
+            .map(|(alias, address)| (alias, address.to_string()))
+            .collect();
+
+        to_js_result(addresses)
+    }
+
+    /// Returns the watch-only descriptor of the wallet as JSON: public keys, derivation paths,
+    /// viewing keys and labels, without any secrets
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current origin was not granted "read_addresses"
+    pub async fn export_wallet_descriptor(&self) -> Result<String, JsError> {
+        self.ensure_permitted(permissions::Capability::ReadAddresses)?;
+        let wallet = self.namada.wallet().await;
+
+        Ok(serde_json::to_string(&wallet::export_descriptor(&wallet))?)
+    }
+
+    /// Imports a descriptor exported by `export_wallet_descriptor`, reconstructing the watch-only
+    /// view of the other wallet. Returns the labels of the imported and skipped entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - JSON of the descriptor
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the descriptor is invalid, nothing is imported then
+    pub async fn import_wallet_descriptor(&self, descriptor: String) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        let descriptor: wallet::WalletDescriptor = serde_json::from_str(&descriptor)
+            .map_err(|e| JsError::new(&format!("Invalid descriptor: {}", e)))?;
+
+        let mut wallet = self.namada.wallet_mut().await;
+        let summary = wallet::import_descriptor(&mut wallet, descriptor)?;
+        if !summary.imported.is_empty() {
+            self.dirty.borrow_mut().wallet = true;
+            *self.derived_paths.borrow_mut() = wallet::derived_paths(&wallet);
+        }
+
+        to_js_result(summary)
+    }
+
+    /// Returns true if the Sdk was created with `new_read_only`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub async fn has_masp_params() -> Result<JsValue, JsValue> {
+        let has = has_masp_params().await?;
+
+        Ok(js_sys::Boolean::from(has.as_bool().unwrap()).into())
+    }
+
+    pub async fn fetch_and_store_masp_params(url: Option<String>) -> Result<(), JsValue> {
+        fetch_and_store_masp_params(url).await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "web")]
+    pub async fn load_masp_params(&self, _db_name: JsValue) -> Result<(), JsValue> {
+        // _dn_name is not used in the web version for a time being
+        let params = get_masp_params().await?;
+        let params_iter = js_sys::try_iter(&params)?.ok_or("Can't iterate over JsValue")?;
+        let mut params_bytes = params_iter.map(|p| to_bytes(p.unwrap()));
+
+        let spend = params_bytes.next().unwrap();
+        let output = params_bytes.next().unwrap();
+        let convert = params_bytes.next().unwrap();
+
+        // We are making sure that there are no more params left
+        assert_eq!(params_bytes.next(), None);
+
+        let mut shielded = self.namada.shielded_mut().await;
+        *shielded = ShieldedContext::new(masp::JSShieldedUtils::new(spend, output, convert).await?);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "nodejs")]
+    pub async fn load_masp_params(&self, context_dir: JsValue) -> Result<(), JsValue> {
+        let context_dir = context_dir.as_string().unwrap();
+
+        let mut shielded = self.namada.shielded_mut().await;
+        *shielded = ShieldedContext::new(masp::JSShieldedUtils::new(&context_dir).await);
+
+        Ok(())
+    }
+
+    pub async fn add_spending_key(&self, xsk: String, alias: String) -> Result<(), JsError> {
+        self.ensure_writable()?;
+        let alias = validate::alias("alias", &alias)?;
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::add_spending_key(&mut wallet, xsk, alias);
+        self.dirty.borrow_mut().wallet = true;
+
+        Ok(())
+    }
+
+    /// Adds viewing key to the wallet
+    ///
+    /// # Arguments
+    ///
+    /// * `xvk` - extended viewing key
+    /// * `alias` - key alias
+    /// * `birthday` - optional height the key was created at, blocks before it are not synced
+    pub async fn add_viewing_key(&self, xvk: String, alias: String, birthday: Option<u64>) {
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::add_viewing_key(&mut wallet, xvk, alias, birthday);
+        self.dirty.borrow_mut().wallet = true;
+    }
+
+    pub async fn add_payment_address(&self, pa: String, alias: String) {
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::add_payment_address(&mut wallet, pa, alias);
+        self.dirty.borrow_mut().wallet = true;
+    }
+
+    pub async fn add_default_payment_address(&self, xvk: String, alias: String) {
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::add_default_payment_address(&mut wallet, xvk, alias);
+        self.dirty.borrow_mut().wallet = true;
+    }
+
+    /// Adds keypair to the wallet
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - serialized secret key
+    /// * `alias` - keypair alias, must not be taken
+    /// * `password` - optional password used to encrypt the secret key
+    /// * `path` - optional BIP44 path the key was derived from, e.g. "m/44'/877'/0'/0'/0'", or
+    ///   "m/44'/60'/0'/0/0" for secp256k1 keys. Hardened components may also be written with
+    ///   "h" or "H".
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret key or the path is invalid, the alias is taken or a key was
+    /// already derived from the path
+    pub async fn add_keypair(
+        &self,
+        secret_key: String,
+        alias: String,
+        password: Option<String>,
+        path: Option<String>,
+    ) -> Result<(), JsError> {
+        self.ensure_writable()?;
+        let alias = validate::alias("alias", &alias)?;
+        let secret_key = common::SecretKey::from_str(&secret_key)
+            .map_err(|err| JsError::new(&format!("Invalid secret key: {}", err)))?;
+        let path = path
+            .map(|path| wallet::Bip44Path::from_str(&path))
+            .transpose()?;
+        if let Some(path) = &path {
+            self.derived_paths.borrow().ensure_not_derived(path)?;
+        }
+
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::ensure_alias_free(&wallet, &alias)?;
+        wallet::add_keypair(&mut wallet, secret_key, alias, password, path)?;
+        self.dirty.borrow_mut().wallet = true;
+        // Recorded once the key is stored, so a failed insert doesn't burn the path
+        if let Some(path) = path {
+            self.derived_paths.borrow_mut().insert(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates a keypair, adds it to the wallet and returns its implicit address.
+    /// Host entropy, e.g. WebAuthn PRF output, is mixed into the platform randomness with HKDF.
+    ///
+    /// # Arguments
+    ///
+    /// * `alias` - keypair alias, must not be taken
+    /// * `extra_entropy` - optional entropy provided by the host, at least 16 bytes
+    /// * `password` - optional password used to encrypt the secret key
+    /// * `scheme` - "ed25519" or "secp256k1", defaults to "ed25519"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extra entropy is too short, the scheme is not supported, the alias
+    /// is taken or the wallet is read-only
+    pub async fn generate_key(
+        &self,
+        alias: String,
+        extra_entropy: Option<Vec<u8>>,
+        password: Option<String>,
+        scheme: Option<String>,
+    ) -> Result<String, JsError> {
+        self.ensure_writable()?;
+        let alias = validate::alias("alias", &alias)?;
+        let scheme = key::scheme(scheme.as_deref())?;
+        let extra_entropy = extra_entropy.map(Zeroizing::new);
+        let secret_key =
+            wallet::generate_secret_key(extra_entropy.as_ref().map(|e| e.as_slice()), scheme)?;
+        let address = Address::from(&secret_key.ref_to()).to_string();
+
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::ensure_alias_free(&wallet, &alias)?;
+        wallet::add_keypair(&mut wallet, secret_key, alias, password, None)?;
+        self.dirty.borrow_mut().wallet = true;
+
+        Ok(address)
+    }
+
+    /// Imports the secret key of an Ethereum account, e.g. exported from an EVM wallet, as a
+    /// secp256k1 keypair and returns its Namada and Ethereum addresses, e.g.
+    /// `{ address: "tnam1...", public_key: "tpknam1...", eth_address: "0x..." }`
+    ///
+    /// # Arguments
+    ///
+    /// * `hex_privkey` - hex encoded secret key of either case, with or without the 0x prefix
+    /// * `alias` - keypair alias, must not be taken
+    /// * `password` - optional password used to encrypt the secret key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is invalid, the alias is taken or the wallet is read-only
+    pub async fn import_eth_key(
+        &self,
+        hex_privkey: String,
+        alias: String,
+        password: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        let alias = validate::alias("alias", &alias)?;
+        let hex_privkey = Zeroizing::new(hex_privkey);
+        let (secret_key, imported) = key::eth_key(&hex_privkey)?;
+
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::ensure_alias_free(&wallet, &alias)?;
+        wallet::add_keypair(&mut wallet, secret_key, alias, password, None)?;
+        self.dirty.borrow_mut().wallet = true;
+
+        to_js_result(imported)
+    }
+
+    /// Returns the message an external wallet, e.g. Keplr or MetaMask, signs for
+    /// `derive_from_external_signature`
+    pub fn external_signature_message(&self) -> String {
+        wallet::EXTERNAL_SIGNATURE_MESSAGE.to_string()
+    }
+
+    /// Derives a keypair from the signature of an external wallet over
+    /// `external_signature_message`, adds it to the wallet and returns its implicit address.
+    /// Signing the message again with the same wallet restores the same keypair.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - ECDSA signature as r, s and optionally the recovery byte
+    /// * `signer` - address of the external wallet, "0x" prefixed for Ethereum wallets (EIP-191
+    ///   signature) or bech32 for Cosmos wallets (ADR-36 signature)
+    /// * `alias` - keypair alias, must not be taken
+    /// * `password` - optional password used to encrypt the secret key
+    /// * `scheme` - "ed25519" or "secp256k1", defaults to "ed25519"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is not of the message by the signer, the scheme is
+    /// invalid, the alias is taken or the wallet is read-only
+    pub async fn derive_from_external_signature(
+        &self,
+        signature: Vec<u8>,
+        signer: String,
+        alias: String,
+        password: Option<String>,
+        scheme: Option<String>,
+    ) -> Result<String, JsError> {
+        self.ensure_writable()?;
+        let alias = validate::alias("alias", &alias)?;
+        let scheme = key::scheme(scheme.as_deref())?;
+        let signature = Zeroizing::new(signature);
+        let secret_key = wallet::derive_from_external_signature(&signature, &signer, scheme)?;
+        let address = Address::from(&secret_key.ref_to()).to_string();
+
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet::ensure_alias_free(&wallet, &alias)?;
+        wallet::add_keypair(&mut wallet, secret_key, alias, password, None)?;
+        self.dirty.borrow_mut().wallet = true;
+
+        Ok(address)
+    }
+
+    /// Scans BIP44 paths m/44'/coin_type'/0'/0'/index' derived from the mnemonic and returns
+    /// the accounts with on-chain activity, i.e. revealed public key or non-zero native token
+    /// balance. Scanning stops after `gap_limit` consecutive unused indices.
+    ///
+    /// # Arguments
+    ///
+    /// * `mnemonic` - BIP39 mnemonic phrase
+    /// * `gap_limit` - number of consecutive unused indices after which scanning stops
+    /// * `coin_type` - optional BIP44 coin type, defaults to 877
+    /// * `scheme` - "ed25519" or "secp256k1", defaults to "ed25519"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mnemonic or the scheme is invalid or an RPC call fails
+    pub async fn discover_accounts(
+        &self,
+        mnemonic: String,
+        gap_limit: u32,
+        coin_type: Option<u32>,
+        scheme: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        let scheme = key::scheme(scheme.as_deref())?;
+        let mnemonic = Mnemonic::from_phrase(&mnemonic, Language::English)
+            .map_err(|err| JsError::new(&format!("Invalid mnemonic: {}", err)))?;
+        let seed = Seed::new(&mnemonic, "");
+        let native_token = self.namada.native_token();
+
+        let mut accounts: Vec<wallet::DiscoveredAccount> = vec![];
+        let mut gap = 0;
+        let mut index = 0;
+        while gap < gap_limit {
+            let path = wallet::Bip44Path {
+                coin_type: coin_type.unwrap_or(NAMADA_COIN_TYPE),
+                account: 0,
+                change: 0,
+                index,
+            };
+            let public_key = wallet::derive_bip44_key(seed.as_bytes(), &path, scheme)?.to_public();
+            let address = Address::from(&public_key);
+
+            let balance =
+                get_token_balance(self.namada.client(), &native_token, &address, None).await?;
+            let revealed = is_public_key_revealed(self.namada.client(), &address).await?;
+
+            if revealed || !balance.is_zero() {
+                accounts.push(wallet::DiscoveredAccount {
+                    path: path.to_scheme_string(scheme),
+                    address: address.to_string(),
+                    public_key: public_key.to_string(),
+                    balance: StringAmount::native(balance),
+                });
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+            index += 1;
+        }
+
+        to_js_result(accounts)
+    }
+
+    /// Derives ZIP32 shielded keys from the same mnemonic as the transparent keys, so a single
+    /// mnemonic restores both transparent and shielded funds. If `alias` is provided, the
+    /// spending key and its default payment address are added to the wallet.
+    ///
+    /// # Arguments
+    ///
+    /// * `mnemonic` - BIP39 mnemonic phrase
+    /// * `account` - ZIP32 account index
+    /// * `alias` - optional alias under which the keys are stored in the wallet
+    /// * `coin_type` - optional BIP44 coin type, defaults to 877
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mnemonic is invalid
+    pub async fn restore_shielded_account(
+        &self,
+        mnemonic: String,
+        account: u32,
+        alias: Option<String>,
+        coin_type: Option<u32>,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        let alias = alias
+            .map(|alias| validate::alias("alias", &alias))
+            .transpose()?;
+        let mnemonic = Mnemonic::from_phrase(&mnemonic, Language::English)
+            .map_err(|err| JsError::new(&format!("Invalid mnemonic: {}", err)))?;
+        let seed = Seed::new(&mnemonic, "");
+        // Shielded keys are derived from the first transparent key of the mnemonic
+        let transparent_path = wallet::Bip44Path {
+            coin_type: coin_type.unwrap_or(NAMADA_COIN_TYPE),
+            account: 0,
+            change: 0,
+            index: 0,
+        };
+        let shielded_account =
+            wallet::derive_zip32_key(seed.as_bytes(), &transparent_path, account)?;
+
+        if let Some(alias) = alias {
+            let mut wallet = self.namada.wallet_mut().await;
+            wallet::add_spending_key(
+                &mut wallet,
+                shielded_account.spending_key.clone(),
+                alias.clone(),
+            );
+            wallet::add_payment_address(
+                &mut wallet,
+                shielded_account.payment_address.clone(),
+                alias,
+            );
+            self.dirty.borrow_mut().wallet = true;
+        }
+
+        to_js_result(shielded_account)
+    }
+
+    /// Returns the lowest BIP44 index not derived yet for the given coin type and account. Paths
+    /// of the stored keys are known once the wallet is loaded.
+    pub fn next_unused_index(&self, coin_type: u32, account: u32) -> u32 {
+        self.derived_paths
+            .borrow()
+            .next_unused_index(coin_type, account)
+    }
+
+    pub async fn save_wallet(&self) -> Result<(), JsValue> {
+        let wallet = self.namada.wallet_mut().await;
+        wallet.save().map_err(JsError::from)?;
+        self.dirty.borrow_mut().wallet = false;
+
+        Ok(())
+    }
+
+    pub async fn load_wallet(&self) -> Result<(), JsValue> {
+        let mut wallet = self.namada.wallet_mut().await;
+        wallet.load().map_err(JsError::from)?;
+        self.dirty.borrow_mut().wallet = false;
+        *self.derived_paths.borrow_mut() = wallet::derived_paths(&wallet);
+
+        Ok(())
+    }
+
+    /// Persists the wallet and shielded context changes which are not saved yet, meant to be
+    /// called from `beforeunload` or `visibilitychange` handlers so that state is not lost when
+    /// the tab is closed. The web wallet is persisted by the host, so only the shielded context
+    /// is saved there.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget_ms` - optional time in milliseconds after which flushing gives up
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if saving fails
+    pub async fn flush(&self, budget_ms: Option<u32>) -> Result<JsValue, JsError> {
+        let mut result = flush::FlushResult::default();
+        if self.read_only || self.dirty.borrow().is_clean() {
+            return to_js_result(result);
+        }
+
+        let saved = flush::with_budget(
+            async {
+                #[cfg(feature = "nodejs")]
+                if self.dirty.borrow().wallet {
+                    self.namada.wallet().await.save().map_err(JsError::from)?;
+                    self.dirty.borrow_mut().wallet = false;
+                    result.wallet_saved = true;
+                }
+
+                if self.dirty.borrow().shielded {
+                    self.namada.shielded().await.save().await?;
+                    self.dirty.borrow_mut().shielded = false;
+                    result.shielded_saved = true;
+                }
+
+                Ok::<_, JsError>(())
+            },
+            budget_ms,
+        )
+        .await;
+        match saved {
+            Some(saved) => saved?,
+            None => result.timed_out = true,
+        }
+
+        to_js_result(result)
+    }
+
+    pub async fn sign_masp(&self, xsks: Box<[String]>, tx: Vec<u8>) -> Result<JsValue, JsError> {
+        self.ensure_signing_approved(&tx).await?;
+
+        self.sign_masp_approved(xsks, tx).await
+    }
+
+    // Adds the MASP signatures, the caller has to get the tx approved first
+    async fn sign_masp_approved(
+        &self,
+        xsks: Box<[String]>,
+        tx: Vec<u8>,
+    ) -> Result<JsValue, JsError> {
+        let tx: tx::Tx = borsh::from_slice(&tx)?;
+        let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+
+        // Use keys_map to easily map xfvk to xsk
+        let mut keys_map = BTreeMap::new();
+        for xsk_s in xsks.iter() {
+            let xsk = namada_sdk::ExtendedSpendingKey::from_str(xsk_s)?;
+            let xvk = xsk.to_viewing_key();
+            let xfvk = ExtendedFullViewingKey::from(xvk);
+
+            keys_map.insert(xfvk, xsk);
+        }
+
+        for signing_data in tx.signing_data() {
+            if let Some(masp_signing_data) = signing_data.masp() {
+                let masp_signing_data =
+                    borsh::from_slice::<MaspSigningData>(&masp_signing_data).ok();
+
+                if let Some(masp_signing_data) = masp_signing_data {
+                    let signing_tx_data = signing_data.to_signing_tx_data()?;
+                    let bparams = masp_signing_data.bparams();
+
+                    for xfvk in masp_signing_data.xfvks() {
+                        let xsk = keys_map.get(&xfvk).ok_or_err_msg("Can't map xfvk to xsk")?;
+                        masp_sign(&mut namada_tx, &signing_tx_data, bparams.clone(), *xsk).await?;
+                    }
+                }
+            }
+        }
+
+        let signing_data = tx
+            .signing_tx_data()?
+            .iter()
+            .cloned()
+            .map(|std| (std, None))
+            .collect::<Vec<(SigningTxData, Option<MaspSigningData>)>>();
+
+        // Recreate the tx with the new signatures, we can pass None for masp_signing_data as it
+        // was already used
+        let tx = tx::Tx::new(namada_tx, &borsh::to_vec(&tx.args())?, signing_data)?;
+
+        to_js_result(borsh::to_vec(&tx)?)
+    }
+
+    pub async fn sign_tx(
+        &self,
+        tx: Vec<u8>,
+        private_key: Option<String>,
+        chain_id: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        if let Err(err) = self.ensure_signing_approved(&tx).await {
+            // The tx won't be submitted, the next txs of its sources don't wait for it
+            if let Ok(tx_hash) = built_tx_hash(&tx) {
+                self.account_queue.take(&tx_hash);
+            }
+            return Err(err);
+        }
+
+        self.sign_tx_approved(tx, private_key, chain_id).await
+    }
+
+    // Signs the tx, the caller has to get it approved first
+    async fn sign_tx_approved(
+        &self,
+        tx: Vec<u8>,
+        private_key: Option<String>,
+        chain_id: Option<String>,
+    ) -> Result<JsValue, JsError> {
+        let tx: tx::Tx = borsh::from_slice(&tx)?;
+        let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+
+        // If chain_id is provided, validate this against value in Tx header
+        if let Some(c) = chain_id {
+            if c != namada_tx.header.chain_id.to_string() {
+                return Err(JsError::new(&format!(
+                    "chain_id {} does not match Tx header chain_id {}",
+                    &c,
+                    namada_tx.header.chain_id.as_str()
+                )));
+            }
+        }
+
+        let signing_keys = match private_key.clone() {
+            Some(private_key) => vec![key::signing_key(&private_key)?],
+            // If no private key is provided, we assume masp source and return empty vec
+            None => vec![],
+        };
+
+        for signing_tx_data in tx.signing_tx_data()? {
+            if let Some(account_public_keys_map) = signing_tx_data.account_public_keys_map.clone() {
+                // We only sign the raw header for transfers from transparent source
+                if !signing_keys.is_empty() {
+                    // Sign the raw header
+                    namada_tx.sign_raw(
+                        signing_keys.clone(),
+                        account_public_keys_map,
+                        signing_tx_data.owner.clone(),
+                    );
+                }
+            }
+        }
+
+        // The key is either passed private key for transparent sources or the disposable signing
+        // key for shielded sources
+        let key = signing_keys[0].clone();
+
+        // Sign the fee header
+        namada_tx.sign_wrapper(key);
+
+        to_js_result(borsh::to_vec(&namada_tx)?)
+    }
+
+    /// Adds the signatures of the key to a partially signed tx, see `create_partial_tx`. Before
+    /// finalization the key signs the inner txs of the accounts it belongs to, after it the fee
+    /// payer signs the wrapper.
+    ///
+    /// # Arguments
+    ///
+    /// * `partial_tx` - partially signed tx
+    /// * `private_key` - hex encoded secret key of a signer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is invalid or the key is no signer of the tx
+    pub async fn sign_partial_tx(
+        &self,
+        partial_tx: Vec<u8>,
+        private_key: String,
+    ) -> Result<Vec<u8>, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let mut partial_tx = partial::PartiallySignedTx::decode(&partial_tx)?;
+        self.ensure_genesis().await?;
+        self.ensure_approved(&partial_tx.namada_tx()?).await?;
+
+        partial_tx.sign(&key::signing_key(&private_key)?)?;
+
+        partial_tx.encode()
+    }
+
+    /// Makes the sponsor the fee payer of the built tx and signs only the inner tx with the key
+    /// of the user. The returned tx is passed to the sponsor, who signs the wrapper with
+    /// `attach_sponsor_signature`. Build the tx with `force` if the user can't pay the fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Borsh serialized tx, as returned by the build functions
+    /// * `sponsor_public_key` - public key of the sponsor paying the fee
+    /// * `private_key` - hex encoded secret key of the user
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx is not a wrapper or a key is invalid
+    pub async fn build_sponsored_tx(
+        &self,
+        tx: Vec<u8>,
+        sponsor_public_key: String,
+        private_key: String,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let tx: tx::Tx = borsh::from_slice(&tx)?;
+        let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        self.ensure_genesis().await?;
+        self.ensure_approved(&namada_tx).await?;
+
+        let sponsor = common::PublicKey::from_str(&sponsor_public_key)?;
+        sponsor::set_fee_payer(&mut namada_tx, sponsor)?;
+
+        let signing_keys = vec![key::signing_key(&private_key)?];
+        for signing_tx_data in tx.signing_tx_data()? {
+            if let Some(account_public_keys_map) = signing_tx_data.account_public_keys_map {
+                namada_tx.sign_raw(
+                    signing_keys.clone(),
+                    account_public_keys_map,
+                    signing_tx_data.owner,
+                );
+            }
+        }
+
+        to_js_result(borsh::to_vec(&namada_tx)?)
+    }
+
+    /// Signs the wrapper of a sponsored tx with the key of the sponsor, after which the tx can be
+    /// submitted with `process_tx`
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - Borsh serialized tx, as returned by `build_sponsored_tx`
+    /// * `sponsor_private_key` - hex encoded secret key of the sponsor
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is invalid, the sponsor is not the fee payer of the tx or the
+    /// tx is not approved
+    pub async fn attach_sponsor_signature(
+        &self,
+        tx_bytes: Vec<u8>,
+        sponsor_private_key: String,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let mut namada_tx = Tx::try_from_slice(&tx_bytes)?;
+        let key = key::signing_key(&sponsor_private_key)?;
+        sponsor::ensure_fee_payer(&namada_tx, &key.ref_to())?;
+        self.ensure_genesis().await?;
+        self.ensure_approved(&namada_tx).await?;
+
+        namada_tx.sign_wrapper(key);
+
+        to_js_result(borsh::to_vec(&namada_tx)?)
+    }
+
+    /// Raises the fee of a signed tx which is stuck because of a low fee. Only the wrapper is
+    /// signed again, signatures of the inner txs stay valid. The bumped tx has a new hash, the
+    /// stuck one can't be applied anymore once the bumped one is.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - Borsh serialized signed tx
+    /// * `new_fee` - decimal fee per gas unit, higher than the current one
+    /// * `private_key` - hex encoded secret key of the fee payer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not the fee payer, the fee is not higher or above the
+    /// maximum set with `set_max_bump_fee`, or the bumped tx is not approved
+    pub async fn bump_fee(
+        &self,
+        tx_bytes: Vec<u8>,
+        new_fee: String,
+        private_key: String,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let mut namada_tx = Tx::try_from_slice(&tx_bytes)?;
+        let key = key::signing_key(&private_key)?;
+        let new_fee = DenominatedAmount::from_str(&new_fee)
+            .map_err(|err| JsError::new(&format!("Invalid fee: {}", err)))?;
+        self.ensure_genesis().await?;
+
+        let max_fee = *self.max_bump_fee.borrow();
+        fee_bump::bump_fee(&mut namada_tx, new_fee, &key.ref_to(), max_fee)?;
+        // The fee is what changed, the approval callback sees the bumped tx
+        self.ensure_approved(&namada_tx).await?;
+        namada_tx.sign_wrapper(key);
+
+        to_js_result(borsh::to_vec(&namada_tx)?)
+    }
+
+    /// Rebuilds a signed tx rejected because it expired, with a new expiration and the signing
+    /// data of the current chain state. The rebuilt tx is signed with `sign_tx`, which asks the
+    /// approval callback again.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - Borsh serialized signed tx
+    /// * `built_tx` - Borsh serialized tx the signed tx was built as, for the owner of the inner tx
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx has not expired, is shielded or its fee can't be paid anymore
+    pub async fn rebuild_expired(
+        &self,
+        tx_bytes: Vec<u8>,
+        built_tx: Vec<u8>,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let namada_tx = Tx::try_from_slice(&tx_bytes)?;
+        let built_tx: tx::Tx = borsh::from_slice(&built_tx)?;
+        if Tx::try_from_slice(&built_tx.tx_bytes())?.raw_header_hash()
+            != namada_tx.raw_header_hash()
+        {
+            return Err(JsError::new("Signed tx was not built as the given tx"));
+        }
+        // Signatures only name the keys, established and multisig owners come from the build
+        let owner = built_tx
+            .signing_tx_data()?
+            .into_iter()
+            .find_map(|signing_tx_data| signing_tx_data.owner);
+        self.ensure_genesis().await?;
+
+        let tx = expired::rebuild(&namada_tx, DateTimeUtc::now())?;
+        let wrapper_tx_msg = borsh::to_vec(&expired::wrapper_tx_msg(&tx)?)?;
+        let args = args::tx_args_from_slice(&wrapper_tx_msg)?;
+
+        // Keys and threshold are queried again, the account could have changed since signing
+        let signing_data =
+            signing::aux_signing_data(&self.namada, &args, owner, None, vec![], false).await?;
+        signing::validate_fee(&self.namada, &args).await?;
+
+        self.serialize_tx_result(tx, &wrapper_tx_msg, signing_data, None)
+    }
+
+    // Broadcast Tx
+    pub async fn process_tx(&self, tx_bytes: &[u8], tx_msg: &[u8]) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Submit)?;
+        self.ensure_genesis().await?;
+        let args = args::tx_args_from_slice(tx_msg)?;
+        let tx = Tx::try_from_slice(tx_bytes)?;
+        // Txs of the same sources built after this one wait until it's applied or failed
+        let _turns = self.account_queue.take(&tx.raw_header_hash());
+        self.ensure_screened(&tx).await?;
+        let cmts = tx.commitments().clone();
+        let wrapper_hash = tx.wrapper_hash();
+        let tx_hash = wrapper_hash
+            .map(|hash| hash.to_string())
+            .unwrap_or_default();
+
+        // Notes spent by the tx are pending until it lands, so txs built meanwhile don't pick them
+        self.pending_spends
+            .borrow_mut()
+            .insert(tx_hash.clone(), pending::spent_nullifiers(&tx));
+        let resp = process_tx(&self.namada, &args, tx.clone()).await;
+        self.pending_spends.borrow_mut().remove(&tx_hash);
+        let resp = resp?;
+
+        let mut batch_tx_results: Vec<tx::BatchTxResult> = vec![];
+
+        // Collect results and return
+        match resp {
+            ProcessTxResponse::Applied(tx_response) => {
+                let code = tx_response.code.to_string();
+                let gas_used = tx_response.gas_used.to_string();
+                let height = tx_response.height.to_string();
+                let info = tx_response.info.to_string();
+                let log = tx_response.log.to_string();
+
+                let mut applied_cmts = vec![];
+                for cmt in cmts {
+                    let hash = compute_inner_tx_hash(wrapper_hash.as_ref(), Either::Right(&cmt));
+
+                    if let Some(InnerTxResult::Success(_)) = tx_response.batch_result().get(&hash) {
+                        batch_tx_results.push(tx::BatchTxResult::new(hash.to_string(), true));
+                        applied_cmts.push(cmt);
+                    } else {
+                        batch_tx_results.push(tx::BatchTxResult::new(hash.to_string(), false));
+                    }
+                }
+
+                // Notes of failed inner txs stay unspent. The tx is applied regardless, spent
+                // notes just show until the next sync.
+                let spent = pending::inner_tx_nullifiers(&tx, &applied_cmts);
+                if let Err(err) = self.mark_spent(spent).await {
+                    web_sys::console::warn_1(&JsValue::from(err));
+                }
+
+                let response = tx::TxResponse::new(
+                    code,
+                    batch_tx_results,
+                    gas_used,
+                    wrapper_hash.unwrap().to_string(),
+                    height,
+                    info,
+                    log,
+                );
+                to_js_result(borsh::to_vec(&response)?)
+            }
+            _ => Err(JsError::new(&format!(
+                "Tx not applied: {}",
+                &wrapper_hash.unwrap().to_string()
+            ))),
+        }
+    }
+
+    /// Build a batch Tx from built transactions and return the bytes
+    pub fn build_batch(txs: JsValue) -> Result<JsValue, JsError> {
+        let mut built_txs: Vec<tx::Tx> = vec![];
+        let built_txs_bytes: Vec<Vec<u8>> = txs.into_serde().unwrap();
+
+        for bytes in built_txs_bytes.iter() {
+            let tx: tx::Tx = borsh::from_slice(bytes)?;
+            built_txs.push(tx);
+        }
+
+        // Get wrapper args
+        let first_tx = built_txs
+            .first()
+            .expect("At least one Tx is required for building batches!");
+
+        let args = first_tx.args();
+
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        let mut masp_map: BTreeMap<MaspTxId, MaspSigningData> = BTreeMap::new();
+
+        // Iterate through provided tx::Tx and deserialize bytes to Namada Tx
+        for built_tx in built_txs.into_iter() {
+            let tx_bytes = built_tx.tx_bytes();
+            let signing_data = built_tx.signing_data();
+            let first_signing_data = signing_data
+                .first()
+                .expect("At least one signing data should be present on a Tx");
+
+            let signing_tx_data = first_signing_data.to_signing_tx_data()?;
+
+            if let Some(sh) = signing_tx_data.shielded_hash {
+                let masp_signing_data = first_signing_data.masp();
+
+                // We do not need to insert masp_signing_data when we shield
+                if let Some(masp_signing_data) = masp_signing_data {
+                    let masp_signing_data = <MaspSigningData>::try_from_slice(&masp_signing_data)?;
+                    masp_map.insert(sh, masp_signing_data);
+                }
+            }
+
+            let tx: Tx = Tx::try_from_slice(&tx_bytes)?;
+
+            txs.push((tx, signing_tx_data.to_owned()));
+        }
+
+        let (tx, signing_data) = build_batch(txs.clone())?;
+
+        let signing_data = signing_data
             .iter()
             .cloned()
             .map(|sd| {
@@ -380,13 +2117,175 @@ impl Sdk {
                     (sd, None)
                 }
             })
-            .collect::<Vec<_>>();
+            .collect::<Vec<_>>();
+
+        to_js_result(borsh::to_vec(&tx::Tx::new(
+            tx,
+            &borsh::to_vec(&args)?,
+            signing_data,
+        )?)?)
+    }
+
+    /// Dry-runs the tx on the node and returns balance and bond changes it would cause, e.g.
+    /// `[{ address, kind: "balance", target: token, delta: "-10" }]`
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - borsh serialized tx, as returned by the build functions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx can't be deserialized or the dry-run fails
+    pub async fn preview_tx_effects(&self, tx_bytes: Vec<u8>) -> Result<JsValue, JsError> {
+        let tx: tx::Tx = borsh::from_slice(&tx_bytes)?;
+        let namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        let kinds = self.tx_kinds(&namada_tx).await?;
+
+        let result = dry_run_tx(&self.namada, tx.tx_bytes()).await?;
+        let mut changed_keys = BTreeSet::new();
+        for (hash, inner_result) in result.iter() {
+            match inner_result {
+                Ok(batched_result) => {
+                    changed_keys.extend(batched_result.changed_keys.iter().cloned())
+                }
+                Err(err) => {
+                    return Err(JsError::new(&format!(
+                        "Dry-run of inner tx {:?} failed: {}",
+                        hash, err
+                    )))
+                }
+            }
+        }
+
+        to_js_result(effects::tx_effects(
+            &kinds,
+            &changed_keys,
+            &self.namada.native_token(),
+        ))
+    }
+
+    /// Returns the gas used by each inner tx of a built tx, e.g.
+    /// `{ total: 400, inner_txs: [{ hash, code: "tx_transfer.wasm", gas_used: 250, ... }] }`.
+    /// The batch is dry-run once per inner tx, each run adding the next inner tx.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - borsh serialized tx, as returned by the build functions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx can't be deserialized or the dry-run of the whole batch fails
+    pub async fn gas_report(&self, tx_bytes: Vec<u8>) -> Result<JsValue, JsError> {
+        let tx: tx::Tx = borsh::from_slice(&tx_bytes)?;
+        let namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        let commitments: Vec<_> = namada_tx.commitments().iter().cloned().collect();
+
+        let mut cumulative = vec![];
+        let mut accepted = vec![];
+        for len in 1..=commitments.len() {
+            let prefix = gas::batch_prefix(&namada_tx, len);
+            let result = RPC
+                .shell()
+                .dry_run_tx(
+                    self.namada.client(),
+                    Some(borsh::to_vec(&prefix)?),
+                    None,
+                    false,
+                )
+                .await;
+            match result {
+                Ok(response) => {
+                    let (tx_result, gas) = (response.data.0, response.data.1);
+                    cumulative.push(u64::from(gas));
+                    // Results are keyed by inner tx hash, not stored in batch order
+                    let hash = compute_inner_tx_hash(
+                        prefix.wrapper_hash().as_ref(),
+                        Either::Right(&commitments[len - 1]),
+                    );
+                    accepted.push(
+                        tx_result
+                            .iter()
+                            .find(|(inner_hash, _)| **inner_hash == hash)
+                            .map_or(false, |(_, result)| {
+                                matches!(
+                                    result,
+                                    Ok(result) if result.vps_result.rejected_vps.is_empty()
+                                )
+                            }),
+                    );
+                }
+                // The whole batch has to run, a failed prefix only hides the split
+                Err(err) if len == commitments.len() => {
+                    return Err(JsError::new(&format!("Dry-run failed: {}", err)))
+                }
+                Err(_) => {
+                    cumulative.push(cumulative.last().copied().unwrap_or_default());
+                    accepted.push(false);
+                }
+            }
+        }
+
+        let inner_txs: Vec<gas::InnerTxGas> = commitments
+            .iter()
+            .zip(gas::marginal_gas(&cumulative))
+            .zip(accepted)
+            .map(|((cmt, gas_used), accepted)| gas::InnerTxGas {
+                hash: cmt.get_hash().to_string(),
+                code: namada_tx
+                    .get_section(cmt.code_sechash())
+                    .and_then(|section| section.code_sec())
+                    .and_then(|code| code.tag),
+                gas_used,
+                accepted,
+                sections: gas::inner_tx_sections(&namada_tx, cmt),
+            })
+            .collect();
 
-        to_js_result(borsh::to_vec(&tx::Tx::new(
-            tx,
-            &borsh::to_vec(&args)?,
-            signing_data,
-        )?)?)
+        to_js_result(gas::GasReport {
+            total: cumulative.last().copied().unwrap_or_default(),
+            dominant: gas::dominant(&inner_txs),
+            sections: gas::section_sizes(&namada_tx),
+            inner_txs,
+        })
+    }
+
+    /// Returns the shielded outputs a built tx sends back to its shielded sources, e.g.
+    /// `[{ output_index: 1, payment_address: "znam...", asset_type: "...", value: 5 }]`. Change
+    /// always goes to the default payment address of the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - borsh serialized tx, as returned by the build functions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx can't be deserialized
+    pub fn tx_change(&self, tx_bytes: Vec<u8>) -> Result<JsValue, JsError> {
+        let tx: tx::Tx = borsh::from_slice(&tx_bytes)?;
+        let namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        let mut change = vec![];
+
+        for signing_data in tx.signing_data() {
+            let Some(masp_signing_data) = signing_data
+                .masp()
+                .and_then(|bytes| borsh::from_slice::<MaspSigningData>(&bytes).ok())
+            else {
+                continue;
+            };
+            let Some(masp_tx) = signing_data
+                .to_signing_tx_data()?
+                .shielded_hash
+                .and_then(|hash| namada_tx.get_masp_section(&hash))
+            else {
+                continue;
+            };
+
+            for xfvk in masp_signing_data.xfvks() {
+                change.extend(masp::change::find_change(masp_tx, &xfvk.fvk.vk));
+            }
+        }
+
+        to_js_result(change)
     }
 
     // Append signatures and return tx bytes
@@ -395,53 +2294,186 @@ impl Sdk {
         tx_bytes: &[u8],
         sig_msg_bytes: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
         let mut tx: Tx = Tx::try_from_slice(tx_bytes)?;
-        let signature::SignatureMsg {
-            pubkey,
-            raw_indices,
-            raw_signature,
-            wrapper_indices,
-            wrapper_signature,
-        } = signature::SignatureMsg::try_from_slice(sig_msg_bytes)?;
-
-        let raw_sig_section =
-            signature::construct_signature_section(&pubkey, &raw_indices, &raw_signature, &tx)?;
-        tx.add_section(raw_sig_section);
-
-        let wrapper_sig_section = signature::construct_signature_section(
-            &pubkey,
-            &wrapper_indices,
-            &wrapper_signature,
-            &tx,
-        )?;
-        tx.add_section(wrapper_sig_section);
-
-        tx.protocol_filter();
+        let sig_msg = signature::SignatureMsg::try_from_slice(sig_msg_bytes)?;
+        signature::append_signature(&mut tx, sig_msg)?;
 
         to_js_result(borsh::to_vec(&tx)?)
     }
 
+    /// Builds a transparent transfer. While an earlier transfer built for one of the sources is
+    /// not applied yet, the build waits for it, so it doesn't see stale balances and revealed
+    /// keys. The wait ends early if the earlier transfer is not signed or submitted in time.
     pub async fn build_transparent_transfer(
         &self,
         transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
         let mut args = args::transparent_transfer_tx_args(transfer_msg, wrapper_tx_msg)?;
+        for data in &args.data {
+            tokens::ensure_token_allowed(&data.token)?;
+        }
+        let turns = self
+            .account_queue
+            .enter(transparent_accounts(
+                args.data.iter().map(|data| &data.source),
+            ))
+            .await;
         let (tx, signing_data) = build_transparent_transfer(&self.namada, &mut args).await?;
+        self.account_queue.hold(tx.raw_header_hash(), turns);
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
     }
 
+    /// Builds a transparent transfer to each recipient and batches them under a single wrapper
+    ///
+    /// # Arguments
+    ///
+    /// * `multi_transfer_msg` - Borsh serialized MultiTransferMsg
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers the whole batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the msg is invalid or any of the transfers can't be built
+    pub async fn build_multi_transfer(
+        &self,
+        multi_transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        let tx = self
+            .multi_transfer_tx(multi_transfer_msg, wrapper_tx_msg)
+            .await?;
+
+        to_js_result(tx)
+    }
+
+    /// Builds a transfer pulling the amount from several sources in priority order. Each source is
+    /// drained before the next one is used, and every used source becomes one inner transfer of
+    /// the batch. If the fee is paid in the transferred token by one of the sources, the fee is
+    /// left in its balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `transfer_msg` - Borsh serialized MultiSourceTransferMsg
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers the whole batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the msg is invalid or the sources don't hold the amount and the fee
+    pub async fn build_multi_source_transfer(
+        &self,
+        transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
+        let transfer = args::multi_source_transfer_from_slice(transfer_msg)?;
+        tokens::ensure_token_allowed(&transfer.token)?;
+        let turns = self
+            .account_queue
+            .enter(transparent_accounts(&transfer.sources))
+            .await;
+
+        let client = self.namada.client();
+        let denom = query_denom(client, &transfer.token)
+            .await
+            .ok_or_else(|| JsError::new("Unknown token denomination"))?;
+        let amount = transfer.amount.to_amount(denom.0)?;
+
+        let mut balances = vec![];
+        for source in &transfer.sources {
+            let balance = get_token_balance(client, &transfer.token, source, None).await?;
+            balances.push((source.clone(), balance));
+        }
+        let tx_args = args::tx_args_from_slice(wrapper_tx_msg)?;
+        let fee_reserve =
+            args::fee_payer(&tx_args).zip(args::fee_in_token(&tx_args, &transfer.token, denom)?);
+
+        let parts = sources::select_sources(balances, amount, fee_reserve)?
+            .into_iter()
+            .map(|(source, amount)| (source, DenominatedAmount::new(amount, denom)))
+            .collect();
+        let transfers = args::split_transfer_tx_args(&transfer, parts, wrapper_tx_msg)?;
+
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        for mut args in transfers {
+            txs.push(build_transparent_transfer(&self.namada, &mut args).await?);
+        }
+        let tx = self.batch_txs(txs, wrapper_tx_msg)?;
+        self.account_queue.hold(built_tx_hash(&tx)?, turns);
+
+        to_js_result(tx)
+    }
+
+    /// Validates a CSV or JSON list of recipients and builds a multi transfer to them. The tx is
+    /// built only if no issues were found, e.g. `{ issues: [], tx: [...] }`
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - address sending the transfers
+    /// * `data` - CSV(`target,token,amount`) or JSON array of `{ target, token, amount }`
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transfers can't be built
+    pub async fn build_multi_transfer_from_csv(
+        &self,
+        source: String,
+        data: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        let (transfers, issues) = recipients::parse_recipients(&data);
+
+        let tx = if issues.is_empty() {
+            let msg = borsh::to_vec(&args::MultiTransferMsg::new(source, transfers))?;
+            Some(self.multi_transfer_tx(&msg, wrapper_tx_msg).await?)
+        } else {
+            None
+        };
+
+        to_js_result(recipients::MultiTransferReport { issues, tx })
+    }
+
     pub async fn build_shielded_transfer(
         &self,
         shielded_transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        let pending = masp::resume::PendingBuild {
+            kind: masp::resume::BuildKind::ShieldedTransfer,
+            msg: shielded_transfer_msg.to_vec(),
+            wrapper_tx_msg: wrapper_tx_msg.to_vec(),
+        };
+
+        self.build_masp_tx(pending).await
+    }
+
+    async fn shielded_transfer_tx(
+        &self,
+        shielded_transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
         let mut args = args::shielded_transfer_tx_args(shielded_transfer_msg, wrapper_tx_msg)?;
+        for data in &args.data {
+            tokens::ensure_token_allowed(&data.token)?;
+        }
+        // Payment addresses are encrypted in the built tx, they are screened before building
+        if self.is_screening() {
+            let targets: Vec<String> = args
+                .data
+                .iter()
+                .map(|data| data.target.to_string())
+                .collect();
+            self.screen_recipients(&targets).await?;
+        }
         let bparams =
             generate_masp_build_params(MAX_HW_SPEND, MAX_HW_CONVERT, MAX_HW_OUTPUT, &args.tx)
                 .await?;
 
-        let _ = &self.namada.shielded_mut().await.load().await?;
+        self.load_shielded_context().await?;
 
         let xfvks = args
             .data
@@ -477,13 +2509,31 @@ impl Sdk {
         unshielding_transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        let pending = masp::resume::PendingBuild {
+            kind: masp::resume::BuildKind::UnshieldingTransfer,
+            msg: unshielding_transfer_msg.to_vec(),
+            wrapper_tx_msg: wrapper_tx_msg.to_vec(),
+        };
+
+        self.build_masp_tx(pending).await
+    }
+
+    async fn unshielding_transfer_tx(
+        &self,
+        unshielding_transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
         let mut args =
             args::unshielding_transfer_tx_args(unshielding_transfer_msg, wrapper_tx_msg)?;
+        for data in &args.data {
+            tokens::ensure_token_allowed(&data.token)?;
+        }
         let bparams =
             generate_masp_build_params(MAX_HW_SPEND, MAX_HW_CONVERT, MAX_HW_OUTPUT, &args.tx)
                 .await?;
 
-        let _ = &self.namada.shielded_mut().await.load().await?;
+        self.load_shielded_context().await?;
 
         let xfvks = vec![args.source.to_viewing_key()];
 
@@ -515,7 +2565,35 @@ impl Sdk {
         shielding_transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        let pending = masp::resume::PendingBuild {
+            kind: masp::resume::BuildKind::ShieldingTransfer,
+            msg: shielding_transfer_msg.to_vec(),
+            wrapper_tx_msg: wrapper_tx_msg.to_vec(),
+        };
+
+        self.build_masp_tx(pending).await
+    }
+
+    async fn shielding_transfer_tx(
+        &self,
+        shielding_transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
         let mut args = args::shielding_transfer_tx_args(shielding_transfer_msg, wrapper_tx_msg)?;
+        for data in &args.data {
+            tokens::ensure_token_allowed(&data.token)?;
+        }
+        // Payment addresses are encrypted in the built tx, they are screened before building
+        if self.is_screening() {
+            self.screen_recipients(&[args.target.to_string()]).await?;
+        }
+        let turns = self
+            .account_queue
+            .enter(transparent_accounts(
+                args.data.iter().map(|data| &data.source),
+            ))
+            .await;
         let bparams =
             generate_masp_build_params(MAX_HW_SPEND, MAX_HW_CONVERT, MAX_HW_OUTPUT, &args.tx)
                 .await?;
@@ -528,16 +2606,149 @@ impl Sdk {
                 build_shielding_transfer(&self.namada, &mut args, &mut bparams).await?
             }
         };
+        self.account_queue.hold(tx.raw_header_hash(), turns);
 
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
     }
 
+    /// Builds, signs and submits a shielding transfer. The public key of the source is revealed
+    /// first if needed, the source also pays the fees. Like `build_shielding_transfer`, the
+    /// transfer is built only once the previous tx of the source is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - decimal amount to shield
+    /// * `token` - address of the token
+    /// * `from_transparent` - transparent source, owner of `private_key`
+    /// * `to_payment_address` - shielded target
+    /// * `private_key` - hex encoded secret key of the source
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the public key is set from `private_key`
+    /// * `sign_only` - if true, the signed txs are returned in broadcast order instead of being
+    ///   submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the txs can't be built, signed or is not applied
+    pub async fn submit_shield(
+        &self,
+        amount: String,
+        token: String,
+        from_transparent: String,
+        to_payment_address: String,
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        let mut signed = sign_only.then(Vec::new);
+        let public_key = key::signing_key(&private_key)?.ref_to();
+        let wrapper_tx_msg = borsh::to_vec(
+            &args::WrapperTxMsg::try_from_slice(wrapper_tx_msg)?
+                .with_public_key(public_key.to_string()),
+        )?;
+
+        let source = Address::from_str(&from_transparent)?;
+        if !is_public_key_revealed(self.namada.client(), &source).await? {
+            let tx = self.build_reveal_pk(&wrapper_tx_msg).await?.into_serde()?;
+            self.sign_and_process(tx, private_key.clone(), &wrapper_tx_msg, &mut signed)
+                .await?;
+        }
+
+        let shielding_transfer_msg = borsh::to_vec(&args::ShieldingTransferMsg::new(
+            to_payment_address,
+            vec![args::ShieldingTransferDataMsg::new(
+                from_transparent,
+                token,
+                StringAmount::new(amount),
+            )],
+        ))?;
+        let tx = self
+            .build_shielding_transfer(&shielding_transfer_msg, &wrapper_tx_msg)
+            .await?
+            .into_serde()?;
+
+        self.sign_and_process(tx, private_key, &wrapper_tx_msg, &mut signed)
+            .await
+    }
+
+    /// Builds, signs and submits an unshielding transfer. Fees are paid from the shielded
+    /// balance of the spending key, the wrapper is signed with a disposable key. Transfers of the
+    /// same spending key are built only once the previous one is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - decimal amount to unshield
+    /// * `token` - address of the token
+    /// * `from_spending_key` - shielded source, also paying the fees
+    /// * `to_transparent` - transparent target
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the public key is set to the disposable
+    ///   key
+    /// * `sign_only` - if true, the signed tx is returned instead of being submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx can't be built, signed or is not applied
+    pub async fn submit_unshield(
+        &self,
+        amount: String,
+        token: String,
+        from_spending_key: String,
+        to_transparent: String,
+        wrapper_tx_msg: &[u8],
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        let disposable_key = ed25519::SigScheme::generate(&mut OsRng);
+        let public_key = common::PublicKey::Ed25519(disposable_key.ref_to());
+        let wrapper_tx_msg = borsh::to_vec(
+            &args::WrapperTxMsg::try_from_slice(wrapper_tx_msg)?
+                .with_public_key(public_key.to_string()),
+        )?;
+
+        let xsk = namada_sdk::ExtendedSpendingKey::from_str(&from_spending_key)?;
+        let source = crate::types::masp::ExtendedSpendingKey(xsk)
+            .to_pseudo_extended_key()
+            .encode();
+        let _turns = self
+            .account_queue
+            .enter([queue::Account::Shielded(source.clone())])
+            .await;
+        let unshielding_transfer_msg = borsh::to_vec(&args::UnshieldingTransferMsg::new(
+            source.clone(),
+            vec![args::UnshieldingTransferDataMsg::new(
+                to_transparent,
+                token,
+                StringAmount::new(amount),
+            )],
+            Some(source),
+        ))?;
+        let tx: Vec<u8> = self
+            .build_unshielding_transfer(&unshielding_transfer_msg, &wrapper_tx_msg)
+            .await?
+            .into_serde()?;
+        // Approved once for both the MASP and the wrapper signatures
+        self.ensure_signing_approved(&tx).await?;
+        let tx = self
+            .sign_masp_approved(Box::new([from_spending_key]), tx)
+            .await?
+            .into_serde()?;
+        let tx: Vec<u8> = self
+            .sign_tx_approved(tx, Some(disposable_key.to_string()), None)
+            .await?
+            .into_serde()?;
+
+        if sign_only {
+            return to_js_result(vec![tx]);
+        }
+        self.process_tx(&tx, &wrapper_tx_msg).await
+    }
+
     pub async fn build_ibc_transfer(
         &self,
         ibc_transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_IBC_WASM).await?;
         let args = args::ibc_transfer_tx_args(ibc_transfer_msg, wrapper_tx_msg)?;
+        tokens::ensure_token_allowed(&args.token)?;
         let bparams =
             generate_masp_build_params(MAX_HW_SPEND, MAX_HW_CONVERT, MAX_HW_OUTPUT, &args.tx)
                 .await?;
@@ -587,6 +2798,7 @@ impl Sdk {
         eth_bridge_transfer_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_BRIDGE_POOL_WASM).await?;
         let args = args::eth_bridge_transfer_tx_args(eth_bridge_transfer_msg, wrapper_tx_msg)?;
         let (tx, signing_data) = build_bridge_pool_tx(&self.namada, args.clone()).await?;
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
@@ -597,6 +2809,7 @@ impl Sdk {
         vote_proposal_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_VOTE_PROPOSAL).await?;
         let args = args::vote_proposal_tx_args(vote_proposal_msg, wrapper_tx_msg)?;
         let epoch = query_epoch(self.namada.client()).await?;
         let (tx, signing_data) = build_vote_proposal(&self.namada, &args, epoch)
@@ -610,6 +2823,7 @@ impl Sdk {
         claim_rewards_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_CLAIM_REWARDS_WASM).await?;
         let args = args::claim_rewards_tx_args(claim_rewards_msg, wrapper_tx_msg)?;
         let (tx, signing_data) = build_claim_rewards(&self.namada, &args)
             .await
@@ -617,21 +2831,252 @@ impl Sdk {
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
     }
 
+    /// Builds a batch claiming the rewards of the delegation and bonding them back to the same
+    /// validator. The returned preview shows the compounded amount before the tx is signed, e.g.
+    /// `{ delegator, validator, rewards: "1.5", tx: [...] }`.
+    ///
+    /// # Arguments
+    ///
+    /// * `delegator` - address of the delegator
+    /// * `validator` - address of the validator
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers both txs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no rewards to claim or the txs can't be built
+    pub async fn compound_rewards(
+        &self,
+        delegator: String,
+        validator: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_CLAIM_REWARDS_WASM).await?;
+        self.ensure_tx_allowed(TX_BOND_WASM).await?;
+
+        let rewards = query_rewards(
+            self.namada.client(),
+            &Some(Address::from_str(&delegator)?),
+            &Address::from_str(&validator)?,
+            &None,
+        )
+        .await?;
+        if rewards.is_zero() {
+            return Err(JsError::new(&format!(
+                "No rewards to claim from {}",
+                validator
+            )));
+        }
+
+        let (claim, bond) =
+            args::compound_rewards_tx_args(&delegator, &validator, rewards, wrapper_tx_msg)?;
+        let txs = vec![
+            build_claim_rewards(&self.namada, &claim).await?,
+            build_bond(&self.namada, &bond).await?,
+        ];
+
+        to_js_result(tx::CompoundPreview {
+            delegator,
+            validator,
+            rewards: StringAmount::native(rewards),
+            tx: self.batch_txs(txs, wrapper_tx_msg)?,
+        })
+    }
+
+    /// Builds a batch unbonding everything the source has bonded, one unbond per validator,
+    /// e.g. `{ source, kind: "unbond", items: [{ validator, amount }], total, tx: [...] }`
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - address of the delegator
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers the whole batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source has no bonds or the txs can't be built
+    pub async fn build_unbond_all(
+        &self,
+        source: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        to_js_result(self.unbond_all_batch(source, wrapper_tx_msg).await?)
+    }
+
+    /// Builds a batch withdrawing every matured unbond of the source, one withdrawal per
+    /// validator, e.g. `{ source, kind: "withdraw", items: [{ validator, amount }], total, tx }`
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - address of the delegator
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers the whole batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing can be withdrawn or the txs can't be built
+    pub async fn build_withdraw_all(
+        &self,
+        source: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        to_js_result(self.withdraw_all_batch(source, wrapper_tx_msg).await?)
+    }
+
+    /// Unbonds everything the source has bonded in a single batch. The summary of the batch is
+    /// passed to `confirm` before signing, which has to resolve to true for it to be submitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - address of the delegator
+    /// * `private_key` - hex encoded secret key of the source
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg
+    /// * `confirm` - optional function called with the summary of the batch
+    /// * `sign_only` - if true, the signed batch is returned instead of being submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch can't be built, is not confirmed or is not applied
+    pub async fn submit_unbond_all(
+        &self,
+        source: String,
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        confirm: Option<js_sys::Function>,
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        let _turns = self
+            .account_queue
+            .enter([queue::Account::Transparent(Address::from_str(&source)?)])
+            .await;
+        let batch = self.unbond_all_batch(source, wrapper_tx_msg).await?;
+
+        self.submit_staking_batch(batch, private_key, wrapper_tx_msg, confirm, sign_only)
+            .await
+    }
+
+    /// Withdraws every matured unbond of the source in a single batch. The summary of the batch
+    /// is passed to `confirm` before signing, which has to resolve to true for it to be
+    /// submitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - address of the delegator
+    /// * `private_key` - hex encoded secret key of the source
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg
+    /// * `confirm` - optional function called with the summary of the batch
+    /// * `sign_only` - if true, the signed batch is returned instead of being submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch can't be built, is not confirmed or is not applied
+    pub async fn submit_withdraw_all(
+        &self,
+        source: String,
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        confirm: Option<js_sys::Function>,
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        let _turns = self
+            .account_queue
+            .enter([queue::Account::Transparent(Address::from_str(&source)?)])
+            .await;
+        let batch = self.withdraw_all_batch(source, wrapper_tx_msg).await?;
+
+        self.submit_staking_batch(batch, private_key, wrapper_tx_msg, confirm, sign_only)
+            .await
+    }
+
     pub async fn build_bond(
         &self,
         bond_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_BOND_WASM).await?;
         let args = args::bond_tx_args(bond_msg, wrapper_tx_msg)?;
         let (tx, signing_data) = build_bond(&self.namada, &args).await?;
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
     }
 
+    /// Builds a bond to each validator and batches them under a single wrapper, so stake can be
+    /// spread over several validators paying the fee once
+    ///
+    /// # Arguments
+    ///
+    /// * `bond_split_msg` - Borsh serialized BondSplitMsg
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the gas limit covers the whole batch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the msg is invalid or any of the bonds can't be built
+    pub async fn build_bond_split(
+        &self,
+        bond_split_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_BOND_WASM).await?;
+        let bonds = args::bond_split_tx_args(bond_split_msg, wrapper_tx_msg)?;
+
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        for args in bonds {
+            txs.push(build_bond(&self.namada, &args).await?);
+        }
+
+        to_js_result(self.batch_txs(txs, wrapper_tx_msg)?)
+    }
+
+    /// Builds, signs and submits bonds to several validators in a single wrapper. The public key
+    /// of the source is revealed first if needed, the source also pays the fees.
+    ///
+    /// # Arguments
+    ///
+    /// * `bond_split_msg` - Borsh serialized BondSplitMsg
+    /// * `private_key` - hex encoded secret key of the source
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg, the public key is set from `private_key`
+    /// * `sign_only` - if true, the signed txs are returned in broadcast order instead of being
+    ///   submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the txs can't be built, signed or is not applied
+    pub async fn submit_bond_split(
+        &self,
+        bond_split_msg: &[u8],
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        let mut signed = sign_only.then(Vec::new);
+        let public_key = key::signing_key(&private_key)?.ref_to();
+        let source = Address::from(&public_key);
+        let _turns = self
+            .account_queue
+            .enter([queue::Account::Transparent(source.clone())])
+            .await;
+        let wrapper_tx_msg = borsh::to_vec(
+            &args::WrapperTxMsg::try_from_slice(wrapper_tx_msg)?
+                .with_public_key(public_key.to_string()),
+        )?;
+
+        if !is_public_key_revealed(self.namada.client(), &source).await? {
+            let tx = self.build_reveal_pk(&wrapper_tx_msg).await?.into_serde()?;
+            self.sign_and_process(tx, private_key.clone(), &wrapper_tx_msg, &mut signed)
+                .await?;
+        }
+
+        let tx = self
+            .build_bond_split(bond_split_msg, &wrapper_tx_msg)
+            .await?
+            .into_serde()?;
+
+        self.sign_and_process(tx, private_key, &wrapper_tx_msg, &mut signed)
+            .await
+    }
+
     pub async fn build_unbond(
         &self,
         unbond_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_UNBOND_WASM).await?;
         let args = args::unbond_tx_args(unbond_msg, wrapper_tx_msg)?;
         let (tx, signing_data, _) = build_unbond(&self.namada, &args).await?;
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
@@ -642,6 +3087,7 @@ impl Sdk {
         withdraw_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_WITHDRAW_WASM).await?;
         let args = args::withdraw_tx_args(withdraw_msg, wrapper_tx_msg)?;
         let (tx, signing_data) = build_withdraw(&self.namada, &args).await?;
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
@@ -652,22 +3098,454 @@ impl Sdk {
         redelegate_msg: &[u8],
         wrapper_tx_msg: &[u8],
     ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_REDELEGATE_WASM).await?;
         let args = args::redelegate_tx_args(redelegate_msg, wrapper_tx_msg)?;
         let (tx, signing_data) = build_redelegation(&self.namada, &args).await?;
         self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
     }
 
-    pub async fn build_reveal_pk(&self, wrapper_tx_msg: &[u8]) -> Result<JsValue, JsError> {
-        let args = args::tx_args_from_slice(wrapper_tx_msg)?;
-        let public_key = args.signing_keys[0].clone();
-        let (tx, signing_data) = build_reveal_pk(&self.namada, &args.clone(), &public_key).await?;
-        self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
+    /// Builds a tx creating an account whose keys are the owner keys and the guardian keys. The
+    /// owner threshold is the threshold of the account, which the guardians alone can't meet,
+    /// and guardian signatures stand in for up to `guardian_threshold` lost owner keys
+    ///
+    /// # Arguments
+    ///
+    /// * `guardian_account_msg` - Borsh serialized GuardianAccountMsg
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is invalid, a threshold can't be met, the guardians alone meet
+    /// the owner threshold or building fails
+    pub async fn build_guardian_account(
+        &self,
+        guardian_account_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_INIT_ACCOUNT_WASM).await?;
+        let args = args::guardian_account_tx_args(guardian_account_msg, wrapper_tx_msg)?;
+        let (tx, signing_data) = build_init_account(&self.namada, &args).await?;
+        self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
+    }
+
+    /// Builds a tx replacing the keys of a guardian account, e.g. after the owner lost a key. The
+    /// tx is signed by the remaining owner keys and passed to the guardians, who sign it with
+    /// `approve_recovery`.
+    ///
+    /// # Arguments
+    ///
+    /// * `recovery_msg` - Borsh serialized RecoveryMsg
+    /// * `wrapper_tx_msg` - Borsh serialized WrapperTxMsg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is invalid, a threshold can't be met, the guardians alone meet
+    /// the owner threshold or building fails
+    pub async fn initiate_recovery(
+        &self,
+        recovery_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_UPDATE_ACCOUNT_WASM).await?;
+        let args = args::recovery_tx_args(recovery_msg, wrapper_tx_msg)?;
+        let (tx, signing_data) = build_update_account(&self.namada, &args).await?;
+        self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
+    }
+
+    /// Adds the signature of a guardian to a recovery tx. Only the inner tx is signed, once enough
+    /// guardians approved, the fee payer signs the tx with `sign_tx` and submits it.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - Borsh serialized tx, as returned by `initiate_recovery` or `approve_recovery`
+    /// * `private_key` - hex encoded secret key of the guardian
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is invalid or not a key of the account
+    pub async fn approve_recovery(
+        &self,
+        tx: Vec<u8>,
+        private_key: String,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let mut tx: tx::Tx = borsh::from_slice(&tx)?;
+        let mut namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        self.ensure_genesis().await?;
+        self.ensure_approved(&namada_tx).await?;
+
+        let key = key::signing_key(&private_key)?;
+        let public_key = key.ref_to();
+        for signing_tx_data in tx.signing_tx_data()? {
+            let account_public_keys_map = signing_tx_data
+                .account_public_keys_map
+                .filter(|map| map.get_index_from_public_key(&public_key).is_some())
+                .ok_or_else(|| JsError::new("Key is not a guardian of the account"))?;
+            namada_tx.sign_raw(
+                vec![key.clone()],
+                account_public_keys_map,
+                signing_tx_data.owner,
+            );
+        }
+        tx.set_tx(&namada_tx)?;
+
+        to_js_result(borsh::to_vec(&tx)?)
+    }
+
+    pub async fn build_reveal_pk(&self, wrapper_tx_msg: &[u8]) -> Result<JsValue, JsError> {
+        self.ensure_tx_allowed(TX_REVEAL_PK).await?;
+        let args = args::tx_args_from_slice(wrapper_tx_msg)?;
+        let public_key = args.signing_keys[0].clone();
+        let (tx, signing_data) = build_reveal_pk(&self.namada, &args.clone(), &public_key).await?;
+        self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
+    }
+
+    /// Builds a tx from JSON encoded msgs instead of Borsh slices. Field names of the JSON objects
+    /// are the same as in the corresponding msg structs.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_type` - one of "bond", "unbond", "withdraw", "redelegate", "claim_rewards",
+    ///   "vote_proposal", "transparent_transfer", "multi_transfer", "multi_source_transfer",
+    ///   "shielded_transfer", "shielding_transfer", "unshielding_transfer", "ibc_transfer",
+    ///   "eth_bridge_transfer", "reveal_pk", or the name of a type registered with
+    ///   `register_tx_type`
+    /// * `tx_msg` - JSON encoded tx msg, ignored for "reveal_pk"
+    /// * `wrapper_tx_msg` - JSON encoded wrapper tx msg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tx type is unknown, JSON is invalid or building the tx fails
+    pub async fn build_tx_json(
+        &self,
+        tx_type: String,
+        tx_msg: String,
+        wrapper_tx_msg: String,
+    ) -> Result<JsValue, JsError> {
+        let wrapper_tx_msg = args::json_to_borsh::<args::WrapperTxMsg>(&wrapper_tx_msg)?;
+
+        self.build_tx_from_json(&tx_type, &tx_msg, &wrapper_tx_msg)
+            .await
+    }
+
+    /// Returns who has to sign a tx and who pays its fee, without signing it, so coordination
+    /// tools can gather the keys of all parties before the tx to sign is built
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_type` - tx type, see `build_tx_json`
+    /// * `tx_msg` - JSON encoded tx msg
+    /// * `wrapper_tx_msg` - Borsh serialized wrapper tx msg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if building the tx fails
+    pub async fn get_signing_data(
+        &self,
+        tx_type: String,
+        tx_msg: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.ensure_permitted(permissions::Capability::ReadAddresses)?;
+        let tx: tx::Tx = self
+            .build_tx_from_json(&tx_type, &tx_msg, wrapper_tx_msg)
+            .await?
+            .into_serde()?;
+        let namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        let wrapper = namada_tx
+            .header
+            .wrapper()
+            .ok_or_else(|| JsError::new("Tx is not a wrapper"))?;
+
+        to_js_result(signers::requirements(&tx.signing_tx_data()?, &wrapper))
+    }
+
+    /// Builds a tx of a custom type registered with `register_tx_type`
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_type` - name of the registered type
+    /// * `tx_msg` - JSON encoded msg, passed to the codec of the type
+    /// * `wrapper_tx_msg` - Borsh serialized wrapper tx msg
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type is not registered, the chain doesn't allow its code or the
+    /// codec fails
+    pub async fn build_custom_tx(
+        &self,
+        tx_type: String,
+        tx_msg: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        let custom_tx = custom_tx::custom_tx_type(&tx_type)
+            .ok_or_else(|| JsError::new(&format!("Unknown custom tx type: {}", tx_type)))?;
+
+        self.build_registered_tx(&custom_tx, &tx_msg, wrapper_tx_msg)
+            .await
+    }
+
+    /// Builds a tx which can be broadcast only within a time window. The tx expires at
+    /// `not_after`, pass the signed tx to `schedule_tx` to broadcast it once `not_before` passes.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_type` - tx type, same as in `build_tx_json`
+    /// * `tx_msg` - JSON encoded tx msg
+    /// * `wrapper_tx_msg` - Borsh serialized wrapper tx msg
+    /// * `not_before` - start of the window, in seconds since the Unix epoch
+    /// * `not_after` - end of the window, in seconds since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window is empty or already closed, or building the tx fails
+    pub async fn build_scheduled_tx(
+        &self,
+        tx_type: String,
+        tx_msg: String,
+        wrapper_tx_msg: &[u8],
+        not_before: u64,
+        not_after: u64,
+    ) -> Result<JsValue, JsError> {
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        if not_before >= not_after {
+            return Err(JsError::new("not_before must be earlier than not_after"));
+        }
+        if not_after <= now {
+            return Err(JsError::new("Schedule window has already closed"));
+        }
+
+        let expiration = DateTimeUtc::from_unix_timestamp(not_after as i64)
+            .ok_or_else(|| JsError::new(&format!("Invalid timestamp: {}", not_after)))?;
+        let wrapper_tx_msg = args::WrapperTxMsg::try_from_slice(wrapper_tx_msg)?
+            .with_expiration(Some(expiration.to_string()));
+
+        self.build_tx_from_json(&tx_type, &tx_msg, &borsh::to_vec(&wrapper_tx_msg)?)
+            .await
+    }
+
+    /// Sets the store the outbox is persisted in and loads the txs saved there. Txs scheduled
+    /// before the store was set are kept and saved with them.
+    ///
+    /// # Arguments
+    ///
+    /// * `save` - function called with the encoded outbox as Uint8Array
+    /// * `load` - function returning the saved Uint8Array or undefined
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if loading or saving fails or the saved outbox is invalid
+    pub async fn set_outbox_store(
+        &self,
+        save: js_sys::Function,
+        load: js_sys::Function,
+    ) -> Result<(), JsError> {
+        let store = outbox::OutboxStore::new(save, load);
+        let mut saved = store.load().await?;
+        saved.append(std::mem::take(&mut *self.outbox.borrow_mut()));
+        *self.outbox.borrow_mut() = saved;
+        *self.outbox_store.borrow_mut() = Some(store);
+
+        self.save_outbox().await
+    }
+
+    /// Adds a signed tx to the outbox, it is broadcast by `process_outbox` once `not_before`
+    /// passes. Returns the id of the scheduled tx.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_bytes` - Borsh serialized signed tx
+    /// * `wrapper_tx_msg` - Borsh serialized wrapper tx msg, as passed to `process_tx`
+    /// * `not_before` - start of the window, in seconds since the Unix epoch
+    /// * `not_after` - end of the window, in seconds since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window is empty or saving the outbox fails
+    pub async fn schedule_tx(
+        &self,
+        tx_bytes: Vec<u8>,
+        wrapper_tx_msg: Vec<u8>,
+        not_before: u64,
+        not_after: u64,
+    ) -> Result<u32, JsError> {
+        self.ensure_writable()?;
+        if not_before >= not_after {
+            return Err(JsError::new("not_before must be earlier than not_after"));
+        }
+        let scheduled_tx = outbox::ScheduledTx {
+            tx_bytes,
+            wrapper_tx_msg,
+            not_before,
+            not_after,
+        };
+
+        let id = self.outbox.borrow_mut().push(scheduled_tx);
+        self.save_outbox().await?;
+
+        Ok(id)
+    }
+
+    /// Returns txs waiting in the outbox, e.g. `[{ id: 0, not_before: 1700000000, not_after: 1700003600 }]`
+    pub fn scheduled_txs(&self) -> Result<JsValue, JsError> {
+        to_js_result(self.outbox.borrow().list())
+    }
+
+    /// Removes a tx from the outbox, returns false if there is no such tx
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if saving the outbox fails
+    pub async fn cancel_scheduled_tx(&self, id: u32) -> Result<bool, JsError> {
+        let cancelled = self.outbox.borrow_mut().cancel(id);
+        if cancelled {
+            self.save_outbox().await?;
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Broadcasts outbox txs whose window is open and drops expired ones. Should be called
+    /// periodically, returns `[{ id, error }]` for every tx taken from the outbox. The outbox is
+    /// saved once all of them were broadcast, so a restart in between broadcasts them again,
+    /// which the ledger rejects as replays.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if saving the outbox fails
+    pub async fn process_outbox(&self) -> Result<JsValue, JsError> {
+        let now = (js_sys::Date::now() / 1000.0) as u64;
+        let (due, expired) = self.outbox.borrow_mut().take_due(now);
+
+        let mut results: Vec<outbox::ScheduledTxResult> = expired
+            .into_iter()
+            .map(|id| outbox::ScheduledTxResult::new(id, Some(String::from("Expired"))))
+            .collect();
+
+        for (id, scheduled_tx) in due {
+            let error = self
+                .process_tx(&scheduled_tx.tx_bytes, &scheduled_tx.wrapper_tx_msg)
+                .await
+                .err()
+                .map(|err| String::from(js_sys::Error::from(JsValue::from(err)).message()));
+            results.push(outbox::ScheduledTxResult::new(id, error));
+        }
+        if !results.is_empty() {
+            self.save_outbox().await?;
+        }
+
+        to_js_result(results)
+    }
+
+    /// Submits signed txs concurrently and returns a result for each of them, e.g.
+    /// `[{ index: 0, response: [...], error: undefined }]`. Txs of different fee payers are
+    /// broadcast concurrently, txs of the same fee payer one after the other in the given order.
+    /// Like with `process_tx`, transfers built afterwards for the sources of a tx wait until it's
+    /// applied or failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - array of `{ tx_bytes, wrapper_tx_msg }`, the signed tx and the Borsh
+    ///   serialized WrapperTxMsg it was built with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the requests can't be deserialized, failures of single txs are
+    /// returned in their results
+    pub async fn submit_all(&self, requests: JsValue) -> Result<JsValue, JsError> {
+        let requests: Vec<submit::SubmitRequest> = requests.into_serde()?;
+        let fee_payers: Vec<Option<String>> = requests
+            .iter()
+            .map(|request| {
+                Tx::try_from_slice(&request.tx_bytes)
+                    .ok()
+                    .and_then(|tx| tx.header.wrapper())
+                    .map(|wrapper| wrapper.pk.to_string())
+            })
+            .collect();
+
+        let groups: Vec<_> = submit::group_by_fee_payer(&fee_payers)
+            .into_iter()
+            .map(|indices| {
+                let requests = &requests;
+                async move {
+                    let mut results = vec![];
+                    for index in indices {
+                        let request = &requests[index];
+                        let result = self
+                            .process_tx(&request.tx_bytes, &request.wrapper_tx_msg)
+                            .await
+                            .and_then(|response| Ok(response.into_serde::<Vec<u8>>()?))
+                            .map_err(|err| {
+                                String::from(js_sys::Error::from(JsValue::from(err)).message())
+                            });
+                        results.push(submit::SubmitResult::new(index, result));
+                    }
+                    results
+                }
+            })
+            .collect();
+
+        let mut results: Vec<submit::SubmitResult> = submit::join_all(groups)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+        results.sort_by_key(|result| result.index);
+
+        to_js_result(results)
+    }
+
+    /// Sends a raw ABCI query to the node, for storage keys and queries this crate doesn't wrap
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - ABCI query path, e.g. "/shell/value/#tnam1.../balance"
+    /// * `data` - optional query data
+    /// * `height` - optional height to query at, latest block if not provided
+    /// * `prove` - whether to request a Merkle proof of the response
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the height is pruned, the RPC call fails or the node returns an error code
+    pub async fn abci_query(
+        &self,
+        path: String,
+        data: Option<Vec<u8>>,
+        height: Option<u64>,
+        prove: bool,
+    ) -> Result<JsValue, JsError> {
+        let client = self.namada.client();
+        // Queries of pruned heights fail with a bare error code, without the height
+        if let Some(height) = height {
+            let earliest = client
+                .status()
+                .await?
+                .sync_info
+                .earliest_block_height
+                .value();
+            archival::route(height, earliest, false)?;
+        }
+        let response = client
+            .request(path, data, height.map(BlockHeight), prove)
+            .await;
+        let response = archival::with_pruned_height(response)?;
+
+        to_js_result(AbciQueryResult {
+            data: hex::encode(response.data),
+            info: response.info,
+            height: response.height.0,
+            proof: response.proof.map(ProofOp::from_proof),
+        })
     }
 
     // Sign arbitrary data with the provided signing key
     pub fn sign_arbitrary(&self, signing_key: String, data: String) -> Result<JsValue, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
         let hash = Hash::sha256(data);
-        let secret = common::SecretKey::Ed25519(ed25519::SecretKey::from_str(&signing_key)?);
+        let secret = key::signing_key(&signing_key)?;
         let signature = common::SigScheme::sign(&secret, hash);
         let sig_bytes = signature.to_bytes();
 
@@ -688,6 +3566,121 @@ impl Sdk {
         common::SigScheme::verify_signature(&public_key, &signed_hash, &sig).map_err(JsError::from)
     }
 
+    /// Signs a governance vote off-chain with a key stored in the wallet. Returns the ballot
+    /// as JSON, which can be collected and checked with `verify_offline_vote` before submission.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - chain the vote is cast on
+    /// * `proposal_id` - id of the proposal
+    /// * `vote` - "yay", "nay" or "abstain"
+    /// * `key_alias` - alias of the signing key in the wallet
+    /// * `password` - optional password the key is encrypted with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vote is invalid, the key can't be found or decrypted, or the
+    /// current origin was not granted "sign"
+    pub async fn sign_offline_vote(
+        &self,
+        chain_id: String,
+        proposal_id: u64,
+        vote: String,
+        key_alias: String,
+        password: Option<String>,
+    ) -> Result<String, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let secret_key = self
+            .namada
+            .wallet_mut()
+            .await
+            .find_secret_key(&key_alias, password.map(Zeroizing::new))?;
+        let ballot = OfflineBallot::sign(&chain_id, proposal_id, &vote, &secret_key)?;
+
+        Ok(serde_json::to_string(&ballot)?)
+    }
+
+    /// Signs a statement that the wallet controls the address, as required by some exchanges
+    /// before the first withdrawal. Returns the proof as JSON, which is checked with
+    /// `verify_address_ownership`.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - implicit address, or established account with a threshold of one
+    /// * `challenge` - challenge chosen by the verifier
+    /// * `password` - optional password the key is encrypted with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no key of the address is in the wallet or the current origin was not
+    /// granted "sign"
+    pub async fn prove_address_ownership(
+        &self,
+        address: String,
+        challenge: String,
+        password: Option<String>,
+    ) -> Result<String, JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let address = validate::address("address", &address)?;
+        let challenge = ownership::challenge(&challenge)?;
+        let password = password.map(Zeroizing::new);
+        self.ensure_statement_approved(ownership::statement(&address.to_string(), &challenge))
+            .await?;
+
+        let secret_key = match &address {
+            Address::Implicit(implicit) => self
+                .namada
+                .wallet_mut()
+                .await
+                .find_key_by_pkh(&implicit.0, password)?,
+            _ => {
+                let account = self.query_account(&address).await?;
+                ownership::ensure_single_signer(&account)?;
+                let mut wallet = self.namada.wallet_mut().await;
+                account
+                    .public_keys_map
+                    .pk_to_idx
+                    .keys()
+                    .find_map(|public_key| wallet.find_key_by_pk(public_key, password.clone()).ok())
+                    .ok_or_else(|| JsError::new("No key of the account is in the wallet"))?
+            }
+        };
+        let proof = ownership::OwnershipProof::sign(&address, &challenge, &secret_key);
+
+        Ok(serde_json::to_string(&proof)?)
+    }
+
+    /// Verifies an ownership proof created by `prove_address_ownership` and returns it as an
+    /// object. Keys of established accounts are checked against the current chain state.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - JSON of the proof
+    /// * `challenge` - challenge the proof has to sign
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof is invalid or signed with a key the address doesn't accept
+    pub async fn verify_address_ownership(
+        &self,
+        proof: String,
+        challenge: String,
+    ) -> Result<JsValue, JsError> {
+        let proof: ownership::OwnershipProof = serde_json::from_str(&proof)
+            .map_err(|e| ownership::OwnershipError::InvalidProof(e.to_string()))?;
+        let challenge = ownership::challenge(&challenge)?;
+        let (address, public_key) = proof.verify(&challenge)?;
+
+        if !matches!(address, Address::Implicit(_)) {
+            let account = self.query_account(&address).await?;
+            ownership::ensure_account_key(&account, &public_key)?;
+        }
+
+        to_js_result(proof)
+    }
+
     pub async fn generate_ibc_shielding_memo(
         &self,
         target: &str,
@@ -700,7 +3693,7 @@ impl Sdk {
             PaymentAddress::from_str(target).expect("target is a valid shielded address"),
         );
         let amount =
-            InputAmount::Unvalidated(DenominatedAmount::from_str(amount).expect("amount is valid"));
+            InputAmount::Unvalidated(StringAmount::new(amount.to_string()).to_denominated()?);
         let channel_id = ChannelId::from_str(channel_id).expect("channel ID is valid");
 
         let args = GenIbcShieldingTransfer {
@@ -728,6 +3721,435 @@ impl Sdk {
         MASP.to_string()
     }
 
+    /// Sets the storage MASP tx builds write their inputs to until the proofs are built, so
+    /// that `resume_pending_build` can build a tx again, e.g. in an MV3 service worker which
+    /// was stopped while proving. The store must not be shared with `Query.set_work_store`.
+    ///
+    /// # Arguments
+    ///
+    /// * `save` - function called with a Uint8Array to store, or null to clear it
+    /// * `load` - function returning the stored Uint8Array or undefined
+    pub fn set_work_store(&self, save: js_sys::Function, load: js_sys::Function) {
+        *self.work_store.borrow_mut() = Some(masp::resume::WorkStore::new(save, load));
+    }
+
+    /// Builds the MASP tx whose build was interrupted again and returns it like the build
+    /// methods, or null if there was none
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no work store is set, the inputs can't be loaded or the build fails
+    pub async fn resume_pending_build(&self) -> Result<JsValue, JsError> {
+        let work_store = self
+            .work_store
+            .borrow()
+            .clone()
+            .ok_or_else(|| JsError::new("No work store set"))?;
+
+        match work_store.load::<masp::resume::PendingBuild>().await? {
+            Some(pending) => self.build_masp_tx(pending).await,
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    // Builds the MASP tx, storing its inputs in the work store until the build is done
+    async fn build_masp_tx(&self, pending: masp::resume::PendingBuild) -> Result<JsValue, JsError> {
+        let work_store = self.work_store.borrow().clone();
+        if let Some(work_store) = &work_store {
+            work_store.save(Some(&pending)).await?;
+        }
+
+        let masp::resume::PendingBuild {
+            kind,
+            msg,
+            wrapper_tx_msg,
+        } = pending;
+        let result = match kind {
+            masp::resume::BuildKind::ShieldedTransfer => {
+                self.shielded_transfer_tx(&msg, &wrapper_tx_msg).await
+            }
+            masp::resume::BuildKind::UnshieldingTransfer => {
+                self.unshielding_transfer_tx(&msg, &wrapper_tx_msg).await
+            }
+            masp::resume::BuildKind::ShieldingTransfer => {
+                self.shielding_transfer_tx(&msg, &wrapper_tx_msg).await
+            }
+        };
+        // Failed builds are not resumed either, they would fail again
+        if let Some(work_store) = &work_store {
+            work_store.save::<masp::resume::PendingBuild>(None).await?;
+        }
+
+        result
+    }
+
+    // Returns the borsh serialized batch of transfers
+    async fn multi_transfer_tx(
+        &self,
+        multi_transfer_msg: &[u8],
+        wrapper_tx_msg: &[u8],
+    ) -> Result<Vec<u8>, JsError> {
+        self.ensure_tx_allowed(TX_TRANSFER_WASM).await?;
+        let transfers = args::multi_transfer_tx_args(multi_transfer_msg, wrapper_tx_msg)?;
+        let sources = transfers
+            .iter()
+            .flat_map(|args| args.data.iter().map(|data| &data.source));
+        let turns = self
+            .account_queue
+            .enter(transparent_accounts(sources))
+            .await;
+
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        for mut args in transfers {
+            for data in &args.data {
+                tokens::ensure_token_allowed(&data.token)?;
+            }
+            txs.push(build_transparent_transfer(&self.namada, &mut args).await?);
+        }
+        let tx = self.batch_txs(txs, wrapper_tx_msg)?;
+        self.account_queue.hold(built_tx_hash(&tx)?, turns);
+
+        Ok(tx)
+    }
+
+    // Returns the borsh serialized batch of the built txs
+    fn batch_txs(
+        &self,
+        txs: Vec<(Tx, SigningTxData)>,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<Vec<u8>, JsError> {
+        let (tx, signing_data) = build_batch(txs)?;
+        let signing_data = signing_data.into_iter().map(|sd| (sd, None)).collect();
+
+        Ok(borsh::to_vec(&tx::Tx::new(
+            tx,
+            wrapper_tx_msg,
+            signing_data,
+        )?)?)
+    }
+
+    // Dispatches JSON encoded tx msgs to the corresponding build method
+    async fn build_tx_from_json(
+        &self,
+        tx_type: &str,
+        tx_msg: &str,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        match tx_type {
+            "bond" => {
+                let msg = args::json_to_borsh::<args::BondMsg>(tx_msg)?;
+                self.build_bond(&msg, wrapper_tx_msg).await
+            }
+            "unbond" => {
+                let msg = args::json_to_borsh::<args::UnbondMsg>(tx_msg)?;
+                self.build_unbond(&msg, wrapper_tx_msg).await
+            }
+            "withdraw" => {
+                let msg = args::json_to_borsh::<args::WithdrawMsg>(tx_msg)?;
+                self.build_withdraw(&msg, wrapper_tx_msg).await
+            }
+            "redelegate" => {
+                let msg = args::json_to_borsh::<args::RedelegateMsg>(tx_msg)?;
+                self.build_redelegate(&msg, wrapper_tx_msg).await
+            }
+            "claim_rewards" => {
+                let msg = args::json_to_borsh::<args::ClaimRewardsMsg>(tx_msg)?;
+                self.build_claim_rewards(&msg, wrapper_tx_msg).await
+            }
+            "vote_proposal" => {
+                let msg = args::json_to_borsh::<args::VoteProposalMsg>(tx_msg)?;
+                self.build_vote_proposal(&msg, wrapper_tx_msg).await
+            }
+            "transparent_transfer" => {
+                let msg = args::json_to_borsh::<args::TransparentTransferMsg>(tx_msg)?;
+                self.build_transparent_transfer(&msg, wrapper_tx_msg).await
+            }
+            "multi_transfer" => {
+                let msg = args::json_to_borsh::<args::MultiTransferMsg>(tx_msg)?;
+                self.build_multi_transfer(&msg, wrapper_tx_msg).await
+            }
+            "multi_source_transfer" => {
+                let msg = args::json_to_borsh::<args::MultiSourceTransferMsg>(tx_msg)?;
+                self.build_multi_source_transfer(&msg, wrapper_tx_msg).await
+            }
+            "shielded_transfer" => {
+                let msg = args::json_to_borsh::<args::ShieldedTransferMsg>(tx_msg)?;
+                self.build_shielded_transfer(&msg, wrapper_tx_msg).await
+            }
+            "shielding_transfer" => {
+                let msg = args::json_to_borsh::<args::ShieldingTransferMsg>(tx_msg)?;
+                self.build_shielding_transfer(&msg, wrapper_tx_msg).await
+            }
+            "unshielding_transfer" => {
+                let msg = args::json_to_borsh::<args::UnshieldingTransferMsg>(tx_msg)?;
+                self.build_unshielding_transfer(&msg, wrapper_tx_msg).await
+            }
+            "ibc_transfer" => {
+                let msg = args::json_to_borsh::<args::IbcTransferMsg>(tx_msg)?;
+                self.build_ibc_transfer(&msg, wrapper_tx_msg).await
+            }
+            "eth_bridge_transfer" => {
+                let msg = args::json_to_borsh::<args::EthBridgeTransferMsg>(tx_msg)?;
+                self.build_eth_bridge_transfer(&msg, wrapper_tx_msg).await
+            }
+            "reveal_pk" => self.build_reveal_pk(wrapper_tx_msg).await,
+            _ => match custom_tx::custom_tx_type(tx_type) {
+                Some(custom_tx) => {
+                    self.build_registered_tx(&custom_tx, tx_msg, wrapper_tx_msg)
+                        .await
+                }
+                None => Err(JsError::new(&format!("Unsupported tx type: {}", tx_type))),
+            },
+        }
+    }
+
+    // Builds a tx of a registered custom type, its code is referenced by hash
+    async fn build_registered_tx(
+        &self,
+        custom_tx: &custom_tx::CustomTxType,
+        tx_msg: &str,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<JsValue, JsError> {
+        self.with_tx_allowlist(|tx_allowlist| {
+            tx_allowlist.ensure_hash_allowed(&custom_tx.code_hash)
+        })
+        .await??;
+        let data = custom_tx.encode(tx_msg)?;
+        let args = args::tx_args_from_slice(wrapper_tx_msg)?;
+        let chain_id = args
+            .chain_id
+            .clone()
+            .ok_or_else(|| JsError::new("Chain id is required"))?;
+
+        let signing_data =
+            signing::aux_signing_data(&self.namada, &args, None, None, vec![], false).await?;
+        let fee_amount = signing::validate_fee(&self.namada, &args).await?;
+
+        let mut tx = Tx::new(chain_id, args.expiration.to_datetime());
+        if let Some(memo) = &args.memo {
+            tx.add_memo(memo);
+        }
+        tx.add_code_from_hash(
+            Hash::from_str(&custom_tx.code_hash)?,
+            Some(custom_tx.name.clone()),
+        );
+        tx.add_serialized_data(data);
+        prepare_tx(&args, &mut tx, fee_amount, signing_data.fee_payer.clone()).await?;
+
+        self.serialize_tx_result(tx, wrapper_tx_msg, signing_data, None)
+    }
+
+    async fn query_account(
+        &self,
+        address: &Address,
+    ) -> Result<namada_sdk::account::Account, JsError> {
+        get_account_info(self.namada.client(), address)
+            .await?
+            .ok_or_else(|| JsError::new(&format!("Account {} does not exist", address)))
+    }
+
+    // Marks notes spent by an applied tx in the local shielded context, so they are not selected
+    // again before the next shielded sync
+    async fn mark_spent(&self, nullifiers: Vec<Nullifier>) -> Result<(), JsError> {
+        if nullifiers.is_empty() {
+            return Ok(());
+        }
+
+        let mut shielded = self.namada.shielded_mut().await;
+        shielded.load().await?;
+        for nullifier in nullifiers {
+            if let Some(note_pos) = shielded.nf_map.get(&nullifier).copied() {
+                shielded.spents.insert(note_pos);
+            }
+        }
+        // Kept dirty until saved, so that `flush` retries an interrupted save
+        self.dirty.borrow_mut().shielded = true;
+        shielded.save().await?;
+        self.dirty.borrow_mut().shielded = false;
+
+        Ok(())
+    }
+
+    // Loads the shielded context and marks notes spent by pending txs
+    async fn load_shielded_context(&self) -> Result<(), JsError> {
+        let mut shielded = self.namada.shielded_mut().await;
+        shielded.load().await?;
+        // Unsaved spent notes are gone once reloaded, and pending spends must not be saved
+        self.dirty.borrow_mut().shielded = false;
+
+        let pending_spends = self.pending_spends.borrow();
+        for nullifier in pending_spends.nullifiers() {
+            if let Some(note_pos) = shielded.nf_map.get(nullifier).copied() {
+                shielded.spents.insert(note_pos);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Persists the outbox in the outbox store, if there is one
+    async fn save_outbox(&self) -> Result<(), JsError> {
+        // Store is cloned and the outbox encoded so the RefCells are not borrowed across the await
+        let store = self.outbox_store.borrow().clone();
+        match store {
+            Some(store) => {
+                let bytes = self.outbox.borrow().encode()?;
+                store.save(bytes).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Persists the annotations in the annotation store, if there is one
+    async fn save_annotations(&self) -> Result<(), JsError> {
+        // Store is cloned and annotations encoded so the RefCells are not borrowed across the await
+        let store = self.annotation_store.borrow().clone();
+        match store {
+            Some(store) => {
+                let bytes = self.annotations.borrow().encode()?;
+                store.save(bytes).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Returns the bonds of the source per validator and the withdrawable amounts per validator.
+    // Bonds are the raw bonded amounts, unbond txs take them before slashes and the ledger
+    // applies the slashes itself.
+    async fn staking_totals(
+        &self,
+        source: &Address,
+    ) -> Result<(Vec<(Address, Amount)>, Vec<(Address, Amount)>), JsError> {
+        let client = self.namada.client();
+        let epoch = query_epoch(client).await?;
+        let enriched =
+            enriched_bonds_and_unbonds(client, epoch, &Some(source.clone()), &None).await?;
+
+        let mut bonded = vec![];
+        let mut withdrawable = vec![];
+        for (bond_id, details) in enriched.data {
+            if !details.bonds_total.is_zero() {
+                bonded.push((bond_id.validator.clone(), details.bonds_total));
+            }
+            if !details.total_withdrawable.is_zero() {
+                withdrawable.push((bond_id.validator, details.total_withdrawable));
+            }
+        }
+
+        Ok((bonded, withdrawable))
+    }
+
+    async fn unbond_all_batch(
+        &self,
+        source: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<tx::StakingBatch, JsError> {
+        self.ensure_tx_allowed(TX_UNBOND_WASM).await?;
+        let source_address = Address::from_str(&source)?;
+        let (bonded, _) = self.staking_totals(&source_address).await?;
+        if bonded.is_empty() {
+            return Err(JsError::new(&format!("{} has no bonds", source)));
+        }
+
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        for args in args::unbond_all_tx_args(&source_address, bonded.clone(), wrapper_tx_msg)? {
+            let (tx, signing_data, _) = build_unbond(&self.namada, &args).await?;
+            txs.push((tx, signing_data));
+        }
+
+        tx::StakingBatch::new(
+            source,
+            "unbond",
+            bonded,
+            self.batch_txs(txs, wrapper_tx_msg)?,
+        )
+    }
+
+    async fn withdraw_all_batch(
+        &self,
+        source: String,
+        wrapper_tx_msg: &[u8],
+    ) -> Result<tx::StakingBatch, JsError> {
+        self.ensure_tx_allowed(TX_WITHDRAW_WASM).await?;
+        let source_address = Address::from_str(&source)?;
+        let (_, withdrawable) = self.staking_totals(&source_address).await?;
+        if withdrawable.is_empty() {
+            return Err(JsError::new(&format!("{} has nothing to withdraw", source)));
+        }
+
+        let validators = withdrawable.iter().map(|(validator, _)| validator.clone());
+        let mut txs: Vec<(Tx, SigningTxData)> = vec![];
+        for args in
+            args::withdraw_all_tx_args(&source_address, validators.collect(), wrapper_tx_msg)?
+        {
+            txs.push(build_withdraw(&self.namada, &args).await?);
+        }
+
+        tx::StakingBatch::new(
+            source,
+            "withdraw",
+            withdrawable,
+            self.batch_txs(txs, wrapper_tx_msg)?,
+        )
+    }
+
+    // Asks for confirmation of the batch summary, then signs and submits the batch
+    async fn submit_staking_batch(
+        &self,
+        batch: tx::StakingBatch,
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        confirm: Option<js_sys::Function>,
+        sign_only: bool,
+    ) -> Result<JsValue, JsError> {
+        if let Some(confirm) = confirm {
+            let result = confirm
+                .call1(&JsValue::NULL, &to_js_result(&batch)?)
+                .map_err(|_| JsError::new("Confirm callback failed"))?;
+            let confirmed = JsFuture::from(js_sys::Promise::resolve(&result))
+                .await
+                .map_err(|_| JsError::new("Confirm callback failed"))?;
+
+            if confirmed.as_bool() != Some(true) {
+                return Err(JsError::new(&format!(
+                    "Submitting {} all was cancelled",
+                    batch.kind
+                )));
+            }
+        }
+
+        self.sign_and_process(
+            batch.tx,
+            private_key,
+            wrapper_tx_msg,
+            &mut sign_only.then(Vec::new),
+        )
+        .await
+    }
+
+    // Signs the built tx with the key and submits it. In sign only mode, i.e. when `signed` is
+    // Some, the tx is appended to `signed` instead and all txs signed so far are returned.
+    async fn sign_and_process(
+        &self,
+        tx: Vec<u8>,
+        private_key: String,
+        wrapper_tx_msg: &[u8],
+        signed: &mut Option<Vec<Vec<u8>>>,
+    ) -> Result<JsValue, JsError> {
+        let tx: Vec<u8> = self
+            .sign_tx(tx, Some(private_key), None)
+            .await?
+            .into_serde()?;
+
+        match signed {
+            Some(signed) => {
+                signed.push(tx);
+                to_js_result(&signed)
+            }
+            None => self.process_tx(&tx, wrapper_tx_msg).await,
+        }
+    }
+
     fn serialize_tx_result(
         &self,
         tx: Tx,
@@ -739,6 +4161,243 @@ impl Sdk {
 
         to_js_result(borsh::to_vec(&tx)?)
     }
+
+    // Cloned so the RefCell is not borrowed across awaits
+    fn sync_backend(&self) -> Result<Rc<sync::EncryptedSync<sync::HttpSyncBackend>>, JsError> {
+        self.sync
+            .borrow()
+            .clone()
+            .ok_or_else(|| JsError::new("No sync backend is set"))
+    }
+
+    // Loads the tx allowlist on the first call
+    async fn with_tx_allowlist<T>(
+        &self,
+        f: impl FnOnce(&allowlist::TxAllowlist) -> T,
+    ) -> Result<T, JsError> {
+        if self.tx_allowlist.borrow().is_none() {
+            self.refresh_tx_allowlist().await?;
+        }
+
+        let tx_allowlist = self.tx_allowlist.borrow();
+        let tx_allowlist = tx_allowlist
+            .as_ref()
+            .ok_or_else(|| JsError::new("Tx allowlist is not loaded"))?;
+
+        Ok(f(tx_allowlist))
+    }
+
+    async fn ensure_tx_allowed(&self, code_path: &str) -> Result<(), JsError> {
+        self.with_tx_allowlist(|tx_allowlist| tx_allowlist.ensure_allowed(code_path))
+            .await??;
+
+        Ok(())
+    }
+
+    async fn ensure_genesis(&self) -> Result<(), JsError> {
+        let expected = self.genesis_hash.borrow().clone();
+        if let Some(expected) = expected {
+            let genesis_hash = self.query_genesis_hash().await?;
+            if genesis_hash != expected {
+                return Err(JsError::new(&format!(
+                    "Genesis hash {} does not match the bound genesis hash {}",
+                    genesis_hash, expected
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Decodes inner txs of known types
+    async fn tx_kinds(&self, namada_tx: &Tx) -> Result<Vec<transaction::TransactionKind>, JsError> {
+        let wasm_hashes = query_wasm_hashes(self.namada.client()).await?;
+
+        Ok(namada_tx
+            .commitments()
+            .iter()
+            .filter_map(|cmt| {
+                let tx_code_id = namada_tx
+                    .get_section(cmt.code_sechash())
+                    .and_then(|s| s.code_sec())
+                    .map(|s| subtle_encoding::hex::encode(s.code.hash().0))
+                    .and_then(|bytes| String::from_utf8(bytes).ok())?;
+                let tx_type = tx::wasm_hash_to_tx_type(&tx_code_id, &wasm_hashes)?;
+                let tx_data = namada_tx.data(cmt).unwrap_or_default();
+
+                // Undecodable inner txs are left out, like unknown ones
+                transaction::TransactionKind::decode(tx_type, &tx_data).ok()
+            })
+            .collect())
+    }
+
+    // Recipients of the inner txs, failing on inner txs which can't be identified or decoded, as
+    // their recipients can't be screened
+    async fn tx_recipients(&self, namada_tx: &Tx) -> Result<Vec<String>, JsError> {
+        let wasm_hashes = query_wasm_hashes(self.namada.client()).await?;
+        let mut recipients = vec![];
+
+        for cmt in namada_tx.commitments() {
+            let tx_type = namada_tx
+                .get_section(cmt.code_sechash())
+                .and_then(|s| s.code_sec())
+                .map(|s| subtle_encoding::hex::encode(s.code.hash().0))
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .and_then(|tx_code_id| tx::wasm_hash_to_tx_type(&tx_code_id, &wasm_hashes))
+                .ok_or_else(|| JsError::new("Tx can't be screened, an inner tx is unknown"))?;
+            let tx_data = namada_tx.data(cmt).unwrap_or_default();
+            let kind = transaction::TransactionKind::decode(tx_type, &tx_data).map_err(|err| {
+                JsError::new(&format!("Tx can't be screened, invalid inner tx: {}", err))
+            })?;
+            recipients.extend(kind.recipients());
+        }
+
+        Ok(recipients)
+    }
+
+    async fn ensure_screened(&self, tx: &Tx) -> Result<(), JsError> {
+        if !self.is_screening() {
+            return Ok(());
+        }
+
+        let recipients = self.tx_recipients(tx).await?;
+        self.screen_recipients(&recipients).await
+    }
+
+    fn is_screening(&self) -> bool {
+        self.screening_callback.borrow().is_some() || !self.address_denylist.borrow().is_empty()
+    }
+
+    // Checks the recipients, transparent or payment addresses, against the denylist and the
+    // screening callback. Errors of the callback block the tx like a rejection does.
+    async fn screen_recipients(&self, recipients: &[String]) -> Result<(), JsError> {
+        if let Some(recipient) = recipients
+            .iter()
+            .find(|recipient| self.address_denylist.borrow().contains(*recipient))
+        {
+            return Err(JsError::new(&format!(
+                "Transfers to {} are blocked",
+                recipient
+            )));
+        }
+
+        let callback = self.screening_callback.borrow().clone();
+        if let Some(callback) = callback {
+            let result = callback
+                .call1(&JsValue::NULL, &to_js_result(recipients)?)
+                .map_err(|_| JsError::new("Screening callback failed, tx was blocked"))?;
+            let allowed = JsFuture::from(js_sys::Promise::resolve(&result))
+                .await
+                .map_err(|_| JsError::new("Screening callback failed, tx was blocked"))?;
+
+            if allowed.as_bool() != Some(true) {
+                return Err(JsError::new("Tx was blocked by address screening"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the Sdk may sign and the built tx is approved, see `ensure_approved`
+    async fn ensure_signing_approved(&self, tx: &[u8]) -> Result<(), JsError> {
+        self.ensure_writable()?;
+        self.ensure_permitted(permissions::Capability::Sign)?;
+        let tx: tx::Tx = borsh::from_slice(tx)?;
+        let namada_tx: Tx = borsh::from_slice(&tx.tx_bytes())?;
+        self.ensure_genesis().await?;
+
+        self.ensure_approved(&namada_tx).await
+    }
+
+    async fn ensure_approved(&self, tx: &Tx) -> Result<(), JsError> {
+        let Some(callback) = self.approval_callback()? else {
+            return Ok(());
+        };
+
+        let wasm_hashes = query_wasm_hashes(self.namada.client()).await?;
+        let genesis_hash = self.genesis_hash.borrow().clone();
+        let sign_doc = sign_doc::SignDoc::from_tx(tx, &wasm_hashes)?
+            .with_genesis_hash(genesis_hash)
+            .canonical()?;
+
+        Self::approve(callback, sign_doc).await
+    }
+
+    /// Asks the approval callback to approve an off-chain statement before it is signed
+    async fn ensure_statement_approved(&self, statement: String) -> Result<(), JsError> {
+        let Some(callback) = self.approval_callback()? else {
+            return Ok(());
+        };
+
+        Self::approve(
+            callback,
+            sign_doc::StatementSignDoc::new(statement).canonical()?,
+        )
+        .await
+    }
+
+    /// Returns the approval callback, None if signing needs no approval
+    fn approval_callback(&self) -> Result<Option<js_sys::Function>, JsError> {
+        // Function is cloned so the RefCell is not borrowed across the await
+        let callback = self.approval_callback.borrow().clone();
+        if callback.is_none() && self.origin.borrow().is_some() {
+            return Err(JsError::new("Signing denied, no approval callback is set"));
+        }
+
+        Ok(callback)
+    }
+
+    async fn approve(
+        callback: js_sys::Function,
+        sign_doc: sign_doc::SignDocResult,
+    ) -> Result<(), JsError> {
+        let result = callback
+            .call1(&JsValue::NULL, &to_js_result(sign_doc)?)
+            .map_err(|_| JsError::new("Approval callback failed"))?;
+        let approved = JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Approval callback failed"))?;
+
+        if approved.as_bool() != Some(true) {
+            return Err(JsError::new("Signing was not approved"));
+        }
+
+        Ok(())
+    }
+
+    fn ensure_permitted(&self, capability: permissions::Capability) -> Result<(), JsError> {
+        if let Some(origin) = self.origin.borrow().as_ref() {
+            self.permissions.borrow().ensure(origin, capability)?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_writable(&self) -> Result<(), JsError> {
+        if self.read_only {
+            return Err(JsError::new(
+                "Sdk is read-only, adding keys, signing and submitting txs is disabled",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Queued accounts of transparent sources
+fn transparent_accounts<'a>(sources: impl IntoIterator<Item = &'a Address>) -> Vec<queue::Account> {
+    sources
+        .into_iter()
+        .cloned()
+        .map(queue::Account::Transparent)
+        .collect()
+}
+
+// Raw header hash of the Borsh serialized tx, as returned by the build functions
+fn built_tx_hash(tx: &[u8]) -> Result<Hash, JsError> {
+    let tx: tx::Tx = borsh::from_slice(tx)?;
+
+    Ok(Tx::try_from_slice(&tx.tx_bytes())?.raw_header_hash())
 }
 
 #[wasm_bindgen(module = "/src/sdk/mod.js")]
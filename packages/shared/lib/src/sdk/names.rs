@@ -0,0 +1,120 @@
+//! Recipient names - resolves human readable names like "alice.namada" to addresses accepted by
+//! the transfer builders. Names registered locally take precedence, other names are passed to
+//! the resolver callback, e.g. an on-chain alias registry or an external naming service, and
+//! cached for a while.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::PaymentAddress;
+use wasm_bindgen::JsError;
+
+// How long resolved names are cached by default
+pub const DEFAULT_TTL_SECS: u64 = 300;
+
+#[derive(Debug)]
+pub struct NameRegistry {
+    // Registered names, never expire
+    names: BTreeMap<String, String>,
+    // Names resolved by the callback with the time in seconds they expire at
+    cache: BTreeMap<String, (String, u64)>,
+    ttl_secs: u64,
+}
+
+impl Default for NameRegistry {
+    fn default() -> Self {
+        NameRegistry {
+            names: BTreeMap::new(),
+            cache: BTreeMap::new(),
+            ttl_secs: DEFAULT_TTL_SECS,
+        }
+    }
+}
+
+impl NameRegistry {
+    pub fn register(&mut self, name: &str, address: String) -> Result<(), JsError> {
+        verify_address(&address)?;
+        self.names.insert(normalize(name), address);
+
+        Ok(())
+    }
+
+    /// Returns the registered names and their addresses
+    pub fn registered(&self) -> &BTreeMap<String, String> {
+        &self.names
+    }
+
+    /// Sets how long resolved names are cached and drops the cached names
+    pub fn set_ttl(&mut self, ttl_secs: u64) {
+        self.ttl_secs = ttl_secs;
+        self.cache.clear();
+    }
+
+    /// Returns the registered or cached address of the name
+    pub fn get(&self, name: &str, now: u64) -> Option<String> {
+        let name = normalize(name);
+        self.names.get(&name).cloned().or_else(|| {
+            self.cache
+                .get(&name)
+                .filter(|(_, expires_at)| *expires_at > now)
+                .map(|(address, _)| address.clone())
+        })
+    }
+
+    pub fn cache(&mut self, name: &str, address: String, now: u64) {
+        self.cache
+            .insert(normalize(name), (address, now + self.ttl_secs));
+    }
+}
+
+/// Names are case insensitive
+pub fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Returns true if the recipient is already an address, which needs no resolution
+pub fn is_address(recipient: &str) -> bool {
+    verify_address(recipient).is_ok()
+}
+
+/// Ensures the resolved value is a transparent or shielded address
+pub fn verify_address(address: &str) -> Result<(), JsError> {
+    if Address::from_str(address).is_ok() || PaymentAddress::from_str(address).is_ok() {
+        return Ok(());
+    }
+
+    Err(JsError::new(&format!("Invalid address: {}", address)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+    const BOB: &str = "tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp";
+
+    #[wasm_bindgen_test]
+    fn registered_names_take_precedence() {
+        let mut registry = NameRegistry::default();
+        registry.cache("alice.namada", BOB.to_string(), 0);
+        registry
+            .register("Alice.Namada", ALICE.to_string())
+            .unwrap();
+
+        assert_eq!(registry.get("alice.namada", 0), Some(ALICE.to_string()));
+        assert!(registry
+            .register("bob.namada", String::from("bob"))
+            .is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn cached_names_expire() {
+        let mut registry = NameRegistry::default();
+        registry.set_ttl(10);
+        registry.cache("bob.namada", BOB.to_string(), 100);
+
+        assert_eq!(registry.get("bob.namada", 105), Some(BOB.to_string()));
+        assert_eq!(registry.get("bob.namada", 110), None);
+    }
+}
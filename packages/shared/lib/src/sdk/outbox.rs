@@ -0,0 +1,229 @@
+//! Outbox - signed txs waiting for their broadcast window. Txs are broadcast by
+//! `Sdk::process_outbox` once `not_before` passes, and dropped after `not_after`, which is also
+//! the expiration of the built tx. The outbox is kept in the store provided by the host, so
+//! scheduled txs survive restarts.
+use std::collections::BTreeMap;
+
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::{JsError, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct ScheduledTx {
+    // Borsh serialized signed tx
+    pub tx_bytes: Vec<u8>,
+    // Borsh serialized WrapperTxMsg
+    pub wrapper_tx_msg: Vec<u8>,
+    // Window in seconds since the Unix epoch
+    pub not_before: u64,
+    pub not_after: u64,
+}
+
+/// Scheduled tx as listed to JS, without the tx bytes
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct ScheduledTxInfo {
+    pub id: u32,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub not_before: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub not_after: u64,
+}
+
+/// Outcome of broadcasting a scheduled tx, `error` is None if the tx was applied
+#[derive(Debug, Serialize, Tsify)]
+pub struct ScheduledTxResult {
+    pub id: u32,
+    pub error: Option<String>,
+}
+
+impl ScheduledTxResult {
+    pub fn new(id: u32, error: Option<String>) -> ScheduledTxResult {
+        ScheduledTxResult { id, error }
+    }
+}
+
+#[derive(Debug, Default, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct Outbox {
+    next_id: u32,
+    txs: BTreeMap<u32, ScheduledTx>,
+}
+
+impl Outbox {
+    /// Adds the tx and returns its id
+    pub fn push(&mut self, tx: ScheduledTx) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.txs.insert(id, tx);
+        id
+    }
+
+    pub fn cancel(&mut self, id: u32) -> bool {
+        self.txs.remove(&id).is_some()
+    }
+
+    pub fn list(&self) -> Vec<ScheduledTxInfo> {
+        self.txs
+            .iter()
+            .map(|(id, tx)| ScheduledTxInfo {
+                id: *id,
+                not_before: tx.not_before,
+                not_after: tx.not_after,
+            })
+            .collect()
+    }
+
+    /// Removes and returns txs whose window is open at `now`, and ids of expired txs
+    pub fn take_due(&mut self, now: u64) -> (Vec<(u32, ScheduledTx)>, Vec<u32>) {
+        let closed: Vec<u32> = self
+            .txs
+            .iter()
+            .filter(|(_, tx)| tx.not_before <= now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut due = vec![];
+        let mut expired = vec![];
+        for id in closed {
+            let tx = self.txs.remove(&id).expect("Scheduled tx to exist");
+            if tx.not_after <= now {
+                expired.push(id);
+            } else {
+                due.push((id, tx));
+            }
+        }
+
+        (due, expired)
+    }
+
+    /// Adds the txs of the other outbox, which get new ids
+    pub fn append(&mut self, other: Outbox) {
+        for tx in other.txs.into_values() {
+            self.push(tx);
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, JsError> {
+        Ok(borsh::to_vec(self)?)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Outbox, JsError> {
+        Outbox::try_from_slice(bytes)
+            .map_err(|err| JsError::new(&format!("Invalid outbox: {}", err)))
+    }
+}
+
+/// Store of the host the outbox is persisted in
+#[derive(Clone)]
+pub struct OutboxStore {
+    // Called with a Uint8Array, may return a promise
+    save: js_sys::Function,
+    // Returns the saved Uint8Array or undefined, may return a promise
+    load: js_sys::Function,
+}
+
+impl OutboxStore {
+    pub fn new(save: js_sys::Function, load: js_sys::Function) -> OutboxStore {
+        OutboxStore { save, load }
+    }
+
+    /// Saves the outbox encoded by `Outbox::encode`
+    pub async fn save(&self, bytes: Vec<u8>) -> Result<(), JsError> {
+        let value: JsValue = js_sys::Uint8Array::from(&bytes[..]).into();
+        let result = self
+            .save
+            .call1(&JsValue::NULL, &value)
+            .map_err(|_| JsError::new("Saving outbox failed"))?;
+        JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Saving outbox failed"))?;
+
+        Ok(())
+    }
+
+    pub async fn load(&self) -> Result<Outbox, JsError> {
+        let result = self
+            .load
+            .call0(&JsValue::NULL)
+            .map_err(|_| JsError::new("Loading outbox failed"))?;
+        let value = JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Loading outbox failed"))?;
+        if value.is_undefined() || value.is_null() {
+            return Ok(Outbox::default());
+        }
+
+        Outbox::decode(&js_sys::Uint8Array::new(&value).to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn scheduled_tx(not_before: u64, not_after: u64) -> ScheduledTx {
+        ScheduledTx {
+            tx_bytes: vec![],
+            wrapper_tx_msg: vec![],
+            not_before,
+            not_after,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn takes_due_and_expired_txs() {
+        let mut outbox = Outbox::default();
+        let early = outbox.push(scheduled_tx(10, 20));
+        let late = outbox.push(scheduled_tx(30, 40));
+        let expired = outbox.push(scheduled_tx(0, 15));
+
+        let (due, dropped) = outbox.take_due(15);
+        let due: Vec<u32> = due.into_iter().map(|(id, _)| id).collect();
+
+        assert_eq!(due, vec![early]);
+        assert_eq!(dropped, vec![expired]);
+        assert_eq!(
+            outbox.list(),
+            vec![ScheduledTxInfo {
+                id: late,
+                not_before: 30,
+                not_after: 40
+            }]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn cancels_txs() {
+        let mut outbox = Outbox::default();
+        let id = outbox.push(scheduled_tx(10, 20));
+
+        assert!(outbox.cancel(id));
+        assert!(!outbox.cancel(id));
+        assert!(outbox.take_due(15).0.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn survives_encoding() {
+        let mut outbox = Outbox::default();
+        outbox.push(scheduled_tx(10, 20));
+        let id = outbox.push(scheduled_tx(30, 40));
+        outbox.cancel(id);
+
+        let mut decoded = Outbox::decode(&outbox.encode().unwrap()).unwrap();
+        assert_eq!(decoded.list(), outbox.list());
+        // Ids are not reused after a restart
+        assert_eq!(decoded.push(scheduled_tx(50, 60)), id + 1);
+
+        let mut appended = Outbox::default();
+        appended.push(scheduled_tx(70, 80));
+        decoded.append(appended);
+        assert_eq!(decoded.list().len(), 3);
+        assert!(Outbox::decode(&[1, 2]).is_err());
+    }
+}
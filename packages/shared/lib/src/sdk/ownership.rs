@@ -0,0 +1,160 @@
+//! Address ownership proofs - signed statements exchanges request before the first withdrawal to
+//! an address. The challenge is chosen by the verifier, so a proof can't be replayed to another
+//! exchange or reused after the challenge expired.
+use std::str::FromStr;
+
+use namada_sdk::account::Account;
+use namada_sdk::address::Address;
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::hash::Hash;
+use namada_sdk::key::{common, RefTo, SigScheme};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tsify::Tsify;
+
+use super::validate;
+
+const OWNERSHIP_DOMAIN: &str = "namada-address-ownership";
+pub const MAX_CHALLENGE_LEN: usize = 256;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum OwnershipError {
+    #[error("Invalid ownership proof: {0}")]
+    InvalidProof(String),
+    #[error("Account {0} requires {1} signatures, ownership can't be proven with one key")]
+    Multisig(String, u8),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct OwnershipProof {
+    pub address: String,
+    // Key of the implicit address, or one of the keys of an established account
+    pub public_key: String,
+    pub challenge: String,
+    // Hex encoded signature of the statement
+    pub signature: String,
+}
+
+impl OwnershipProof {
+    /// Signs the statement that the owner of the key controls the address
+    pub fn sign(address: &Address, challenge: &str, secret_key: &common::SecretKey) -> Self {
+        let signature =
+            common::SigScheme::sign(secret_key, statement_hash(&address.to_string(), challenge));
+
+        OwnershipProof {
+            address: address.to_string(),
+            public_key: secret_key.ref_to().to_string(),
+            challenge: challenge.to_string(),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+
+    /// Checks the challenge and the signature of the statement. For implicit addresses also
+    /// checks the address is the one of the public key, keys of established accounts have to be
+    /// checked with `ensure_account_key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first failed check
+    pub fn verify(&self, challenge: &str) -> Result<(Address, common::PublicKey), OwnershipError> {
+        let invalid = |reason: &str| OwnershipError::InvalidProof(reason.to_string());
+
+        if self.challenge != challenge {
+            return Err(invalid("challenge does not match"));
+        }
+        let address = Address::from_str(&self.address).map_err(|_| invalid("address"))?;
+        let public_key =
+            common::PublicKey::from_str(&self.public_key).map_err(|_| invalid("public key"))?;
+        if matches!(address, Address::Implicit(_)) && Address::from(&public_key) != address {
+            return Err(invalid("address does not match the public key"));
+        }
+        let signature = hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| common::Signature::try_from_slice(&bytes).ok())
+            .ok_or_else(|| invalid("signature encoding"))?;
+
+        common::SigScheme::verify_signature(
+            &public_key,
+            &statement_hash(&self.address, &self.challenge),
+            &signature,
+        )
+        .map_err(|_| invalid("signature does not match"))?;
+
+        Ok((address, public_key))
+    }
+}
+
+/// Returns the trimmed challenge
+pub fn challenge(challenge: &str) -> Result<String, validate::FieldError> {
+    validate::string("challenge", challenge, MAX_CHALLENGE_LEN)
+}
+
+/// Checks that a single key of the established account can sign for it
+pub fn ensure_single_signer(account: &Account) -> Result<(), OwnershipError> {
+    if account.threshold > 1 {
+        return Err(OwnershipError::Multisig(
+            account.address.to_string(),
+            account.threshold,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that the key alone can sign for the established account
+///
+/// # Errors
+///
+/// Returns an error if the account has a threshold above one or the key is not one of its keys
+pub fn ensure_account_key(
+    account: &Account,
+    public_key: &common::PublicKey,
+) -> Result<(), OwnershipError> {
+    ensure_single_signer(account)?;
+    if !account.public_keys_map.pk_to_idx.contains_key(public_key) {
+        return Err(OwnershipError::InvalidProof(String::from(
+            "public key is not a key of the account",
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the statement the proof signs, as shown to the user before signing
+pub fn statement(address: &str, challenge: &str) -> String {
+    format!(
+        "{}\naddress:{}\nchallenge:{}",
+        OWNERSHIP_DOMAIN, address, challenge
+    )
+}
+
+fn statement_hash(address: &str, challenge: &str) -> Hash {
+    Hash::sha256(statement(address, challenge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::key::ed25519;
+    use wasm_bindgen_test::*;
+
+    const SECRET_KEY: &str = "1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93";
+    const OTHER: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    #[wasm_bindgen_test]
+    fn verifies_ownership_proofs() {
+        let key = common::SecretKey::Ed25519(ed25519::SecretKey::from_str(SECRET_KEY).unwrap());
+        let address = Address::from(&key.ref_to());
+        let proof = OwnershipProof::sign(&address, "withdrawal-42", &key);
+
+        assert_eq!(proof.verify("withdrawal-42").unwrap().0, address);
+        assert!(proof.verify("withdrawal-43").is_err());
+
+        // Key doesn't own the other implicit address
+        let proof = OwnershipProof::sign(&Address::from_str(OTHER).unwrap(), "withdrawal-42", &key);
+        assert_eq!(
+            proof.verify("withdrawal-42").unwrap_err(),
+            OwnershipError::InvalidProof(String::from("address does not match the public key"))
+        );
+    }
+}
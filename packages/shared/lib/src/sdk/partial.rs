@@ -0,0 +1,593 @@
+//! Partially signed txs - an envelope which carries a built tx, its signing data and the
+//! signatures collected so far between the devices of a multisig account, like a PSBT does for
+//! Bitcoin. Every signer adds their inner tx signatures with `Sdk::sign_partial_tx`, envelopes
+//! signed on different devices are merged, and once every account reached its threshold the
+//! envelope is finalized, the fee payer signs the wrapper and the tx is extracted for broadcast.
+use namada_sdk::address::Address;
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use namada_sdk::hash::Hash;
+use namada_sdk::key::{common, RefTo, SigScheme};
+use namada_sdk::signing::SigningTxData;
+use namada_sdk::time::DateTimeUtc;
+use namada_sdk::tx::{Authorization, Section, Signer, Tx};
+use serde::Serialize;
+use thiserror::Error;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use super::tx;
+use crate::utils::to_js_result;
+
+// Bumped on breaking changes of the envelope, older envelopes are rejected
+const VERSION: u8 = 1;
+const MAGIC: [u8; 4] = *b"pnt\xff";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PartialTxError {
+    #[error("Not a partially signed tx")]
+    InvalidEnvelope,
+    #[error("Unsupported partially signed tx version {0}, expected {VERSION}")]
+    UnsupportedVersion(u8),
+    #[error("Partially signed txs are of different txs")]
+    DifferentTx,
+    #[error("Partially signed tx is already finalized")]
+    Finalized,
+    #[error("Partially signed tx is not finalized yet")]
+    NotFinalized,
+    #[error("Key {0} is no signer of the tx")]
+    NotSigner(String),
+    #[error("Account {0} has {1} of {2} required signatures")]
+    MissingSignatures(String, usize, u8),
+    #[error("Wrapper of the tx is not signed by the fee payer yet")]
+    WrapperNotSigned,
+    #[error("Tx is not a wrapper")]
+    NotWrapper,
+    #[error("Signatures of {0} are not valid signatures of the tx")]
+    InvalidSignature(String),
+    #[error("Signing data of {0} has no account keys")]
+    MissingAccountKeys(String),
+}
+
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct PartiallySignedTx {
+    version: u8,
+    // Build result, i.e. the tx and its signing data. After finalization the tx contains the
+    // merged inner tx signatures.
+    tx: tx::Tx,
+    description: Option<String>,
+    created_at: String,
+    // Inner tx signatures collected so far, one per signer and set of signed sections
+    authorizations: Vec<Authorization>,
+    finalized: bool,
+    wrapper_signed: bool,
+}
+
+/// Signatures of an account, as listed to JS
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct PartialSigner {
+    // Account the inner tx is signed for, None for txs signed by public keys only
+    pub owner: Option<String>,
+    pub threshold: u8,
+    // Public keys which signed so far
+    pub signed_by: Vec<String>,
+    // Public keys which can still sign
+    pub pending: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct PartialTxInfo {
+    pub version: u8,
+    pub hash: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub signers: Vec<PartialSigner>,
+    pub fee_payer: String,
+    // True if every account reached its threshold
+    pub complete: bool,
+    pub finalized: bool,
+    pub wrapper_signed: bool,
+}
+
+// Whether the authorization signs for the account of the signing data, i.e. for its owner or
+// with public keys of its key map if it has no owner
+fn signs_for(authorization: &Authorization, signing_tx_data: &SigningTxData) -> bool {
+    match (&authorization.signer, &signing_tx_data.owner) {
+        (Signer::Address(signer), Some(owner)) => signer == owner,
+        (Signer::PubKeys(public_keys), None) => signing_tx_data
+            .account_public_keys_map
+            .as_ref()
+            .map_or(false, |pk_map| {
+                public_keys
+                    .iter()
+                    .all(|pk| pk_map.pk_to_idx.contains_key(pk))
+            }),
+        _ => false,
+    }
+}
+
+fn signer_name(owner: &Option<Address>) -> String {
+    owner
+        .as_ref()
+        .map_or_else(|| String::from("public keys"), Address::to_string)
+}
+
+// Checks that the authorization signs the raw header of the tx for one of its accounts, with
+// valid signatures of keys of the account
+fn verify_authorization(
+    authorization: &Authorization,
+    raw_header_hash: Hash,
+    signing_tx_data: &[SigningTxData],
+) -> Result<(), PartialTxError> {
+    let invalid = || {
+        PartialTxError::InvalidSignature(match &authorization.signer {
+            Signer::Address(address) => address.to_string(),
+            Signer::PubKeys(_) => String::from("public keys"),
+        })
+    };
+    if authorization.targets != [raw_header_hash] || authorization.signatures.is_empty() {
+        return Err(invalid());
+    }
+    let pk_map = signing_tx_data
+        .iter()
+        .find(|signing_tx_data| signs_for(authorization, signing_tx_data))
+        .and_then(|signing_tx_data| signing_tx_data.account_public_keys_map.as_ref())
+        .ok_or_else(invalid)?;
+
+    let raw_hash = authorization.get_raw_hash();
+    for (idx, signature) in &authorization.signatures {
+        // Signatures of an account are indexed by its key map, of public keys by their list
+        let public_key = match &authorization.signer {
+            Signer::Address(_) => pk_map.get_public_key_from_index(*idx),
+            Signer::PubKeys(public_keys) => public_keys.get(*idx as usize).cloned(),
+        };
+        let valid = public_key.map_or(false, |public_key| {
+            common::SigScheme::verify_signature(&public_key, &raw_hash, signature).is_ok()
+        });
+        if !valid {
+            return Err(invalid());
+        }
+    }
+
+    Ok(())
+}
+
+impl PartiallySignedTx {
+    /// Wraps a built tx into an envelope without signatures
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - build result of the tx
+    /// * `description` - note for the other signers, e.g. what the tx is for
+    /// * `now` - current time
+    pub fn new(tx: tx::Tx, description: Option<String>, now: DateTimeUtc) -> Self {
+        Self {
+            version: VERSION,
+            tx,
+            description,
+            created_at: now.to_rfc3339(),
+            authorizations: vec![],
+            finalized: false,
+            wrapper_signed: false,
+        }
+    }
+
+    /// Decodes an envelope
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are no envelope or of an unsupported version
+    pub fn decode(bytes: &[u8]) -> Result<Self, JsError> {
+        let envelope = bytes
+            .strip_prefix(&MAGIC)
+            .ok_or(PartialTxError::InvalidEnvelope)?;
+        let partial = Self::try_from_slice(envelope)?;
+        if partial.version != VERSION {
+            return Err(PartialTxError::UnsupportedVersion(partial.version).into());
+        }
+
+        Ok(partial)
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, JsError> {
+        Ok([MAGIC.to_vec(), borsh::to_vec(self)?].concat())
+    }
+
+    pub fn namada_tx(&self) -> Result<Tx, JsError> {
+        Ok(Tx::try_from_slice(&self.tx.tx_bytes())?)
+    }
+
+    fn wrapper_pk(tx: &Tx) -> Result<common::PublicKey, PartialTxError> {
+        tx.header
+            .wrapper()
+            .map(|wrapper| wrapper.pk)
+            .ok_or(PartialTxError::NotWrapper)
+    }
+
+    // Adds the signatures of the authorization to the matching one collected before, after
+    // checking them against the tx and the keys of its accounts
+    fn add_authorization(&mut self, authorization: Authorization) -> Result<(), JsError> {
+        verify_authorization(
+            &authorization,
+            self.namada_tx()?.raw_header_hash(),
+            &self.tx.signing_tx_data()?,
+        )?;
+
+        match self.authorizations.iter_mut().find(|collected| {
+            collected.signer == authorization.signer && collected.targets == authorization.targets
+        }) {
+            Some(collected) => collected.signatures.extend(authorization.signatures),
+            None => self.authorizations.push(authorization),
+        }
+
+        Ok(())
+    }
+
+    /// Signs the inner txs the key is a signer of, or the wrapper if the envelope is finalized
+    /// and the key is the fee payer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is no signer of the tx
+    pub fn sign(&mut self, key: &common::SecretKey) -> Result<(), JsError> {
+        let public_key = key.ref_to();
+        let mut namada_tx = self.namada_tx()?;
+
+        if self.finalized {
+            if Self::wrapper_pk(&namada_tx)? != public_key {
+                return Err(PartialTxError::NotSigner(public_key.to_string()).into());
+            }
+            namada_tx.sign_wrapper(key.clone());
+            self.tx.set_tx(&namada_tx)?;
+            self.wrapper_signed = true;
+            return Ok(());
+        }
+
+        let mut signed = false;
+        for signing_tx_data in self.tx.signing_tx_data()? {
+            let Some(pk_map) = signing_tx_data.account_public_keys_map.clone() else {
+                continue;
+            };
+            if !pk_map.pk_to_idx.contains_key(&public_key) {
+                continue;
+            }
+
+            let mut signed_tx = namada_tx.clone();
+            signed_tx.sign_raw(vec![key.clone()], pk_map, signing_tx_data.owner.clone());
+            if let Some(Section::Authorization(authorization)) = signed_tx.sections.last() {
+                self.add_authorization(authorization.clone())?;
+                signed = true;
+            }
+        }
+        if !signed {
+            return Err(PartialTxError::NotSigner(public_key.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Adds the signatures of an envelope of the same tx, e.g. signed on another device
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelopes are of different txs, one of them is finalized or
+    /// carries invalid signatures
+    pub fn merge(&mut self, other: PartiallySignedTx) -> Result<(), JsError> {
+        if self.finalized || other.finalized {
+            return Err(PartialTxError::Finalized.into());
+        }
+        if self.namada_tx()?.raw_header_hash() != other.namada_tx()?.raw_header_hash() {
+            return Err(PartialTxError::DifferentTx.into());
+        }
+
+        for authorization in other.authorizations {
+            self.add_authorization(authorization)?;
+        }
+        if self.description.is_none() {
+            self.description = other.description;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the collected signatures to the tx, after which only the fee payer signs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is finalized, an account misses signatures or has no
+    /// account keys
+    pub fn finalize(&mut self) -> Result<(), JsError> {
+        if self.finalized {
+            return Err(PartialTxError::Finalized.into());
+        }
+        for signer in self.signers()? {
+            if (signer.signed_by.len() as u8) < signer.threshold {
+                return Err(PartialTxError::MissingSignatures(
+                    signer.owner.unwrap_or_else(|| String::from("public keys")),
+                    signer.signed_by.len(),
+                    signer.threshold,
+                )
+                .into());
+            }
+        }
+
+        let mut namada_tx = self.namada_tx()?;
+        for authorization in &self.authorizations {
+            namada_tx.add_section(Section::Authorization(authorization.clone()));
+        }
+        self.tx.set_tx(&namada_tx)?;
+        self.finalized = true;
+
+        Ok(())
+    }
+
+    /// Returns the tx bytes to broadcast
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope is not finalized or the wrapper is not signed
+    pub fn extract(&self) -> Result<Vec<u8>, JsError> {
+        if !self.finalized {
+            return Err(PartialTxError::NotFinalized.into());
+        }
+        if !self.wrapper_signed {
+            return Err(PartialTxError::WrapperNotSigned.into());
+        }
+
+        Ok(self.tx.tx_bytes())
+    }
+
+    fn signers(&self) -> Result<Vec<PartialSigner>, JsError> {
+        let mut signers = vec![];
+
+        for signing_tx_data in self.tx.signing_tx_data()? {
+            // Without the keys it can't be told whether the account signed
+            let Some(pk_map) = &signing_tx_data.account_public_keys_map else {
+                return Err(PartialTxError::MissingAccountKeys(signer_name(
+                    &signing_tx_data.owner,
+                ))
+                .into());
+            };
+            let signed: Vec<u8> = self
+                .authorizations
+                .iter()
+                .filter(|authorization| signs_for(authorization, &signing_tx_data))
+                .flat_map(|authorization| authorization.signatures.keys().copied())
+                .collect();
+
+            let mut public_keys: Vec<(u8, String)> = pk_map
+                .pk_to_idx
+                .iter()
+                .map(|(pk, idx)| (*idx, pk.to_string()))
+                .collect();
+            public_keys.sort();
+            let (signed_by, pending): (Vec<_>, Vec<_>) = public_keys
+                .into_iter()
+                .partition(|(idx, _)| signed.contains(idx));
+
+            signers.push(PartialSigner {
+                owner: signing_tx_data.owner.as_ref().map(Address::to_string),
+                threshold: signing_tx_data.threshold,
+                signed_by: signed_by.into_iter().map(|(_, pk)| pk).collect(),
+                pending: pending.into_iter().map(|(_, pk)| pk).collect(),
+            });
+        }
+
+        Ok(signers)
+    }
+
+    pub fn info(&self) -> Result<PartialTxInfo, JsError> {
+        let namada_tx = self.namada_tx()?;
+        let signers = self.signers()?;
+
+        Ok(PartialTxInfo {
+            version: self.version,
+            hash: namada_tx
+                .wrapper_hash()
+                .ok_or(PartialTxError::NotWrapper)?
+                .to_string(),
+            description: self.description.clone(),
+            created_at: self.created_at.clone(),
+            complete: signers
+                .iter()
+                .all(|signer| signer.signed_by.len() >= signer.threshold as usize),
+            signers,
+            fee_payer: Self::wrapper_pk(&namada_tx)?.to_string(),
+            finalized: self.finalized,
+            wrapper_signed: self.wrapper_signed,
+        })
+    }
+}
+
+/// Wraps a built tx into a partially signed tx
+///
+/// # Arguments
+///
+/// * `tx` - Borsh serialized tx, as returned by the build functions
+/// * `description` - note for the other signers
+///
+/// # Errors
+///
+/// Returns an error if the tx can't be deserialized
+#[wasm_bindgen]
+pub fn create_partial_tx(tx: &[u8], description: Option<String>) -> Result<Vec<u8>, JsError> {
+    let tx: tx::Tx = borsh::from_slice(tx)?;
+
+    PartiallySignedTx::new(tx, description, DateTimeUtc::now()).encode()
+}
+
+/// Merges the signatures of partially signed txs of the same tx
+///
+/// # Errors
+///
+/// Returns an error if an envelope is invalid or finalized, or the envelopes are of different txs
+#[wasm_bindgen]
+pub fn merge_partial_txs(partial_txs: Vec<js_sys::Uint8Array>) -> Result<Vec<u8>, JsError> {
+    let mut envelopes = partial_txs
+        .iter()
+        .map(|partial_tx| PartiallySignedTx::decode(&partial_tx.to_vec()));
+    let mut merged = envelopes.next().ok_or(PartialTxError::InvalidEnvelope)??;
+    for envelope in envelopes {
+        merged.merge(envelope?)?;
+    }
+
+    merged.encode()
+}
+
+/// Adds the collected inner tx signatures to the tx, once every account reached its threshold
+///
+/// # Errors
+///
+/// Returns an error if the envelope is invalid or finalized, or an account misses signatures
+#[wasm_bindgen]
+pub fn finalize_partial_tx(partial_tx: &[u8]) -> Result<Vec<u8>, JsError> {
+    let mut partial_tx = PartiallySignedTx::decode(partial_tx)?;
+    partial_tx.finalize()?;
+
+    partial_tx.encode()
+}
+
+/// Returns the Borsh serialized tx of a finalized envelope with a signed wrapper, for broadcast
+///
+/// # Errors
+///
+/// Returns an error if the envelope is invalid, not finalized or the wrapper is not signed
+#[wasm_bindgen]
+pub fn extract_partial_tx(partial_tx: &[u8]) -> Result<Vec<u8>, JsError> {
+    PartiallySignedTx::decode(partial_tx)?.extract()
+}
+
+/// Returns the signers, collected signatures and state of a partially signed tx
+///
+/// # Errors
+///
+/// Returns an error if the envelope is invalid
+#[wasm_bindgen]
+pub fn inspect_partial_tx(partial_tx: &[u8]) -> Result<JsValue, JsError> {
+    to_js_result(PartiallySignedTx::decode(partial_tx)?.info()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use namada_sdk::account::AccountPublicKeysMap;
+    use namada_sdk::chain::ChainId;
+    use namada_sdk::key::{ed25519, SigScheme};
+    use namada_sdk::token::DenominatedAmount;
+    use namada_sdk::tx::data::{Fee, GasLimit};
+    use namada_sdk::tx::Data;
+    use rand::rngs::OsRng;
+    use wasm_bindgen_test::*;
+
+    use crate::sdk::args::WrapperTxMsg;
+    use crate::types::amount::StringAmount;
+
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+    const MULTISIG: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    fn secret_key() -> common::SecretKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng))
+    }
+
+    fn built_tx(signers: &[common::SecretKey], payer: &common::SecretKey) -> tx::Tx {
+        let mut namada_tx = Tx::new(ChainId::default(), None);
+        namada_tx.set_data(Data::new(vec![1]));
+        namada_tx.add_wrapper(
+            Fee {
+                amount_per_gas_unit: DenominatedAmount::from_str("0.000001").unwrap(),
+                token: Address::from_str(NAM).unwrap(),
+            },
+            payer.ref_to(),
+            GasLimit::from(50_000),
+        );
+        let signing_tx_data = SigningTxData {
+            owner: Some(Address::from_str(MULTISIG).unwrap()),
+            public_keys: vec![],
+            threshold: 2,
+            account_public_keys_map: Some(AccountPublicKeysMap::from_iter(
+                signers.iter().map(|key| key.ref_to()),
+            )),
+            fee_payer: payer.ref_to(),
+            shielded_hash: None,
+        };
+        let wrapper_tx_msg = WrapperTxMsg::new(
+            NAM.to_string(),
+            StringAmount::new(String::from("0.000001")),
+            String::from("50000"),
+            ChainId::default().to_string(),
+            Some(payer.ref_to().to_string()),
+            None,
+            None,
+            None,
+        );
+
+        tx::Tx::new(
+            namada_tx,
+            &borsh::to_vec(&wrapper_tx_msg).unwrap(),
+            vec![(signing_tx_data, None)],
+        )
+        .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn collects_signatures_across_devices() {
+        let (alice, bob, carol, payer) = (secret_key(), secret_key(), secret_key(), secret_key());
+        let tx = built_tx(&[alice.clone(), bob.clone()], &payer);
+        let envelope = PartiallySignedTx::new(tx, None, DateTimeUtc::now());
+        let envelope = PartiallySignedTx::decode(&envelope.encode().unwrap()).unwrap();
+
+        let mut on_alice = envelope.clone();
+        on_alice.sign(&alice).unwrap();
+        let mut on_bob = envelope.clone();
+        on_bob.sign(&bob).unwrap();
+        assert!(on_alice.sign(&carol).is_err());
+        assert!(on_alice.clone().finalize().is_err());
+
+        on_alice.merge(on_bob).unwrap();
+        assert_eq!(on_alice.authorizations.len(), 1);
+        assert!(on_alice.info().unwrap().complete);
+        assert!(on_alice.extract().is_err());
+
+        on_alice.finalize().unwrap();
+        assert!(on_alice.extract().is_err());
+        assert!(on_alice.sign(&alice).is_err());
+        on_alice.sign(&payer).unwrap();
+
+        let signed = Tx::try_from_slice(&on_alice.extract().unwrap()).unwrap();
+        assert_eq!(
+            signed.sections.len(),
+            envelope.namada_tx().unwrap().sections.len() + 2
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_signatures() {
+        let (alice, bob, mallory, payer) = (secret_key(), secret_key(), secret_key(), secret_key());
+        let tx = built_tx(&[alice.clone(), bob.clone()], &payer);
+        let mut envelope = PartiallySignedTx::new(tx, None, DateTimeUtc::now());
+        let mut on_alice = envelope.clone();
+        on_alice.sign(&alice).unwrap();
+
+        // Signature of a key outside the account under the index of alice
+        let mut forged = on_alice.authorizations[0].clone();
+        let signature = common::SigScheme::sign(&mallory, forged.get_raw_hash());
+        for collected in forged.signatures.values_mut() {
+            *collected = signature.clone();
+        }
+        let mut on_mallory = envelope.clone();
+        on_mallory.authorizations.push(forged);
+        assert!(envelope.merge(on_mallory).is_err());
+
+        // Signature of other sections than the raw header
+        let mut retargeted = on_alice.authorizations[0].clone();
+        retargeted.targets = vec![Hash::sha256(b"other")];
+        let mut on_other = envelope.clone();
+        on_other.authorizations.push(retargeted);
+        assert!(envelope.merge(on_other).is_err());
+
+        envelope.merge(on_alice).unwrap();
+        assert_eq!(envelope.info().unwrap().signers[0].signed_by.len(), 1);
+    }
+}
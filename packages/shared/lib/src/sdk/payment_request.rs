@@ -0,0 +1,204 @@
+//! Payment requests - `namada:<recipient>?token=<token>&amount=<amount>&memo=<memo>&expiry=<secs>`
+//! URIs merchants share as links or QR codes. The wallet parses them to pre-fill the transfer
+//! form, so only validated recipients, tokens and amounts reach it. Amounts are checked against
+//! the denomination of the token, which `Query` looks up on chain.
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::JsError;
+
+use super::names;
+use crate::types::amount::StringAmount;
+
+const SCHEME: &str = "namada:";
+
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct PaymentRequest {
+    // Transparent or shielded address
+    pub recipient: String,
+    pub token: String,
+    // Decimal amount, None lets the payer choose
+    pub amount: Option<String>,
+    pub memo: Option<String>,
+    // Seconds since the Unix epoch after which the request must not be paid
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub expiry: Option<u64>,
+}
+
+impl PaymentRequest {
+    pub fn token(&self) -> Result<Address, JsError> {
+        Address::from_str(&self.token)
+            .map_err(|_| JsError::new(&format!("Invalid token: {}", self.token)))
+    }
+
+    /// Validates the request, the amount can't have more decimal places than `denom`
+    pub fn validate(&self, denom: u8) -> Result<(), JsError> {
+        names::verify_address(&self.recipient)?;
+        self.token()?;
+        if let Some(amount) = &self.amount {
+            let amount = StringAmount::new(amount.clone())
+                .to_amount(denom)
+                .map_err(|_| {
+                    JsError::new(&format!(
+                        "Invalid amount: {}, the token has {} decimal places",
+                        amount, denom
+                    ))
+                })?;
+            if amount.is_zero() {
+                return Err(JsError::new("Amount must be greater than zero"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error if the request has an expiry at or before `now`
+    pub fn ensure_not_expired(&self, now: u64) -> Result<(), JsError> {
+        if self.expiry.is_some_and(|expiry| expiry <= now) {
+            return Err(JsError::new("Payment request expired"));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_uri(&self) -> String {
+        let mut params = vec![format!("token={}", self.token)];
+        if let Some(amount) = &self.amount {
+            params.push(format!("amount={}", amount));
+        }
+        if let Some(memo) = &self.memo {
+            params.push(format!("memo={}", percent_encode(memo)));
+        }
+        if let Some(expiry) = self.expiry {
+            params.push(format!("expiry={}", expiry));
+        }
+
+        format!("{}{}?{}", SCHEME, self.recipient, params.join("&"))
+    }
+
+    /// Parses the URI, the request still has to be validated against the token denomination
+    pub fn from_uri(uri: &str) -> Result<PaymentRequest, JsError> {
+        let rest = uri
+            .trim()
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| JsError::new("Payment request must start with namada:"))?;
+        let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut token = None;
+        let mut amount = None;
+        let mut memo = None;
+        let mut expiry = None;
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            match key {
+                "token" => token = Some(value.to_string()),
+                "amount" => amount = Some(value.to_string()),
+                "memo" => memo = Some(percent_decode(value)?),
+                "expiry" => {
+                    expiry = Some(
+                        value
+                            .parse::<u64>()
+                            .map_err(|_| JsError::new(&format!("Invalid expiry: {}", value)))?,
+                    )
+                }
+                // Unknown parameters are ignored, so newer requests still parse
+                _ => {}
+            }
+        }
+
+        Ok(PaymentRequest {
+            recipient: recipient.to_string(),
+            token: token.ok_or_else(|| JsError::new("Payment request has no token"))?,
+            amount,
+            memo,
+            expiry,
+        })
+    }
+}
+
+// Encodes everything but unreserved characters (RFC 3986)
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> Result<String, JsError> {
+    let invalid = || JsError::new(&format!("Invalid encoding: {}", value));
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value.get(i + 1..i + 3).ok_or_else(invalid)?;
+                decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    #[wasm_bindgen_test]
+    fn round_trips_uri() {
+        let request = PaymentRequest {
+            recipient: ALICE.to_string(),
+            token: NAM.to_string(),
+            amount: Some(String::from("10.5")),
+            memo: Some(String::from("Order #42 & more")),
+            expiry: Some(1_700_000_000),
+        };
+        let uri = request.to_uri();
+
+        assert!(uri.contains("memo=Order%20%2342%20%26%20more"));
+        assert_eq!(PaymentRequest::from_uri(&uri).unwrap(), request);
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_requests() {
+        let validate = |uri: String| PaymentRequest::from_uri(&uri).and_then(|r| r.validate(6));
+
+        assert!(validate(format!("bitcoin:{}?token={}", ALICE, NAM)).is_err());
+        assert!(validate(format!("namada:{}", ALICE)).is_err());
+        assert!(validate(format!("namada:{}?token={}&amount=0", ALICE, NAM)).is_err());
+        assert!(validate(format!("namada:alice?token={}", NAM)).is_err());
+        assert!(validate(format!("namada:{}?token={}&amount=1.5", ALICE, NAM)).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn checks_amount_against_denomination() {
+        let request =
+            PaymentRequest::from_uri(&format!("namada:{}?token={}&amount=0.0000001", ALICE, NAM))
+                .unwrap();
+
+        assert!(request.validate(6).is_err());
+        assert!(request.validate(8).is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+//! Notes spent by broadcast txs which are not confirmed yet. The shielded context is reloaded
+//! from storage before every build, so pending spends are kept in memory and applied after each
+//! load instead of being saved - a tx which never lands must not leave its notes spent.
+use std::collections::BTreeMap;
+
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::ibc::MsgTransfer;
+use namada_sdk::masp_primitives::sapling::Nullifier;
+use namada_sdk::token::Transfer;
+use namada_sdk::tx::{Section, Tx, TxCommitments};
+
+#[derive(Debug, Default)]
+pub struct PendingSpends {
+    // Nullifiers keyed by the wrapper hash of the tx spending them
+    by_tx: BTreeMap<String, Vec<Nullifier>>,
+}
+
+impl PendingSpends {
+    pub fn insert(&mut self, tx_hash: String, nullifiers: Vec<Nullifier>) {
+        if !nullifiers.is_empty() {
+            self.by_tx.insert(tx_hash, nullifiers);
+        }
+    }
+
+    /// Removes the spends of a tx once it is confirmed or rejected
+    pub fn remove(&mut self, tx_hash: &str) -> Vec<Nullifier> {
+        self.by_tx.remove(tx_hash).unwrap_or_default()
+    }
+
+    pub fn nullifiers(&self) -> impl Iterator<Item = &Nullifier> {
+        self.by_tx.values().flatten()
+    }
+}
+
+/// Returns nullifiers of the notes spent by the MASP sections of the tx
+pub fn spent_nullifiers(tx: &Tx) -> Vec<Nullifier> {
+    tx.sections
+        .iter()
+        .filter_map(|section| match section {
+            Section::MaspTx(masp_tx) => masp_tx.sapling_bundle(),
+            _ => None,
+        })
+        .flat_map(|bundle| bundle.shielded_spends.iter().map(|spend| spend.nullifier))
+        .collect()
+}
+
+/// Returns nullifiers of the notes spent by the MASP sections the inner txs refer to, e.g. of
+/// the inner txs which were applied
+///
+/// # Arguments
+///
+/// * `tx` - tx the inner txs are in
+/// * `cmts` - commitments of the inner txs
+pub fn inner_tx_nullifiers(tx: &Tx, cmts: &[TxCommitments]) -> Vec<Nullifier> {
+    cmts.iter()
+        .filter_map(|cmt| tx.data(cmt))
+        .filter_map(|data| {
+            let transfer = Transfer::try_from_slice(&data).ok().or_else(|| {
+                MsgTransfer::<Transfer>::try_from_slice(&data)
+                    .ok()
+                    .and_then(|msg| msg.transfer)
+            })?;
+            tx.get_masp_section(&transfer.shielded_section_hash?)
+        })
+        .filter_map(|masp_tx| masp_tx.sapling_bundle())
+        .flat_map(|bundle| bundle.shielded_spends.iter().map(|spend| spend.nullifier))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn tracks_spends_per_tx() {
+        let mut pending = PendingSpends::default();
+        pending.insert(String::from("a"), vec![Nullifier([1; 32])]);
+        pending.insert(
+            String::from("b"),
+            vec![Nullifier([2; 32]), Nullifier([3; 32])],
+        );
+        pending.insert(String::from("c"), vec![]);
+
+        assert_eq!(pending.nullifiers().count(), 3);
+        assert_eq!(pending.remove("a"), vec![Nullifier([1; 32])]);
+        assert!(pending.remove("c").is_empty());
+        assert_eq!(pending.nullifiers().count(), 2);
+    }
+}
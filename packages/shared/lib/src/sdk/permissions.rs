@@ -0,0 +1,125 @@
+//! Permissions - origin scoped capabilities granted to dapps.
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PermissionError {
+    #[error("Unknown capability: {0}")]
+    UnknownCapability(String),
+    #[error("Origin {0} is not permitted to {1}")]
+    NotPermitted(String, Capability),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    ReadAddresses,
+    Sign,
+    Submit,
+}
+
+impl FromStr for Capability {
+    type Err = PermissionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_addresses" => Ok(Capability::ReadAddresses),
+            "sign" => Ok(Capability::Sign),
+            "submit" => Ok(Capability::Submit),
+            _ => Err(PermissionError::UnknownCapability(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let capability = match self {
+            Capability::ReadAddresses => "read_addresses",
+            Capability::Sign => "sign",
+            Capability::Submit => "submit",
+        };
+        write!(f, "{}", capability)
+    }
+}
+
+/// Capabilities granted per origin, nothing is granted by default
+#[derive(Debug, Default)]
+pub struct PermissionRegistry {
+    grants: BTreeMap<String, BTreeSet<Capability>>,
+}
+
+impl PermissionRegistry {
+    /// Grants capabilities to the origin, in addition to the already granted ones
+    pub fn grant(&mut self, origin: String, capabilities: Vec<Capability>) {
+        self.grants.entry(origin).or_default().extend(capabilities);
+    }
+
+    /// Revokes all capabilities of the origin
+    pub fn revoke(&mut self, origin: &str) {
+        self.grants.remove(origin);
+    }
+
+    pub fn check(&self, origin: &str, capability: Capability) -> bool {
+        self.grants
+            .get(origin)
+            .map(|capabilities| capabilities.contains(&capability))
+            .unwrap_or_default()
+    }
+
+    /// Returns an error if the origin was not granted the capability
+    pub fn ensure(&self, origin: &str, capability: Capability) -> Result<(), PermissionError> {
+        if !self.check(origin, capability) {
+            return Err(PermissionError::NotPermitted(
+                origin.to_string(),
+                capability,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ORIGIN: &str = "https://dapp.example";
+
+    #[wasm_bindgen_test]
+    fn nothing_is_granted_by_default() {
+        let registry = PermissionRegistry::default();
+
+        assert!(!registry.check(ORIGIN, Capability::ReadAddresses));
+        assert_eq!(
+            registry.ensure(ORIGIN, Capability::Sign),
+            Err(PermissionError::NotPermitted(
+                ORIGIN.to_string(),
+                Capability::Sign
+            ))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_grant_and_revoke() {
+        let mut registry = PermissionRegistry::default();
+        registry.grant(ORIGIN.to_string(), vec![Capability::ReadAddresses]);
+        registry.grant(ORIGIN.to_string(), vec![Capability::Sign]);
+
+        assert!(registry.check(ORIGIN, Capability::ReadAddresses));
+        assert!(registry.check(ORIGIN, Capability::Sign));
+        assert!(!registry.check(ORIGIN, Capability::Submit));
+        assert!(!registry.check("https://other.example", Capability::Sign));
+
+        registry.revoke(ORIGIN);
+
+        assert!(!registry.check(ORIGIN, Capability::Sign));
+    }
+
+    #[wasm_bindgen_test]
+    fn can_parse_capability() {
+        assert_eq!(Capability::from_str("submit").unwrap(), Capability::Submit);
+        assert!(Capability::from_str("transfer").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Per account queue of txs. A tx built while an earlier tx of the same account is still in
+//! flight sees stale balances and revealed keys, so the second one waits until the first one is
+//! applied before it is built. The turn of a built tx is held until the tx is submitted and
+//! applied, or until it's abandoned: its signing fails or it's not submitted in time.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use namada_sdk::address::Address;
+use namada_sdk::hash::Hash;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::utils::sleep;
+
+// A built tx which is not submitted within this time is treated as abandoned
+pub const HELD_TURN_MS: u32 = 2 * 60 * 1000;
+
+/// Account txs are queued for
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Account {
+    Transparent(Address),
+    // Encoded pseudo extended key of the spending key
+    Shielded(String),
+}
+
+/// Turns of the accounts a tx is built for, the next txs of the accounts start once it's dropped
+#[derive(Debug, Default)]
+pub struct Turns(Vec<OwnedMutexGuard<()>>);
+
+#[derive(Debug, Default)]
+pub struct AccountQueue {
+    // Locks are kept for the session, there are only as many as accounts submitting txs
+    accounts: RefCell<BTreeMap<Account, Arc<Mutex<()>>>>,
+    // Turns of built txs by raw header hash, which stays the same when the tx is signed
+    held: Rc<RefCell<BTreeMap<Hash, Turns>>>,
+}
+
+impl AccountQueue {
+    /// Waits for the txs of the accounts queued before and returns the turns of the accounts
+    pub async fn enter(&self, accounts: impl IntoIterator<Item = Account>) -> Turns {
+        // Locked in order, so txs of overlapping accounts can't wait for each other
+        let accounts: BTreeSet<Account> = accounts.into_iter().collect();
+        let mut turns = vec![];
+        for account in accounts {
+            let lock = self
+                .accounts
+                .borrow_mut()
+                .entry(account)
+                .or_default()
+                .clone();
+            turns.push(lock.lock_owned().await);
+        }
+
+        Turns(turns)
+    }
+
+    /// Holds the turns until the tx is submitted, they are released after `HELD_TURN_MS` if it
+    /// isn't
+    ///
+    /// # Arguments
+    ///
+    /// * `tx_hash` - raw header hash of the built tx
+    /// * `turns` - turns of the accounts the tx was built for
+    pub fn hold(&self, tx_hash: Hash, turns: Turns) {
+        self.held.borrow_mut().insert(tx_hash, turns);
+
+        let held = self.held.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = sleep(HELD_TURN_MS).await;
+            held.borrow_mut().remove(&tx_hash);
+        });
+    }
+
+    /// Takes the held turns of the tx, to be dropped once it's applied or failed
+    pub fn take(&self, tx_hash: &Hash) -> Option<Turns> {
+        self.held.borrow_mut().remove(tx_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+    const BOB: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    fn account(address: &str) -> Account {
+        Account::Transparent(Address::from_str(address).unwrap())
+    }
+
+    fn is_free(queue: &AccountQueue, address: &str) -> bool {
+        queue.accounts.borrow()[&account(address)]
+            .try_lock()
+            .is_ok()
+    }
+
+    #[wasm_bindgen_test]
+    async fn queues_txs_of_same_account() {
+        let queue = AccountQueue::default();
+        let first = queue.enter([account(ALICE)]).await;
+
+        // Other accounts don't wait
+        drop(queue.enter([account(BOB)]).await);
+        assert!(!is_free(&queue, ALICE));
+
+        drop(first);
+        assert!(is_free(&queue, ALICE));
+    }
+
+    #[wasm_bindgen_test]
+    async fn queues_back_to_back_transfers() {
+        let queue = AccountQueue::default();
+        let first_hash = Hash::sha256(b"first");
+
+        // The first transfer is built, its turn is held until it's submitted
+        let first = queue.enter([account(ALICE)]).await;
+        queue.hold(first_hash, first);
+        assert!(!is_free(&queue, ALICE));
+
+        // Submitting takes the turn, the second transfer is built once the first is applied
+        let applying = queue.take(&first_hash).unwrap();
+        assert!(!is_free(&queue, ALICE));
+        assert!(queue.take(&first_hash).is_none());
+        drop(applying);
+        assert!(is_free(&queue, ALICE));
+
+        let second = queue
+            .enter([account(ALICE), account(BOB), account(ALICE)])
+            .await;
+        assert_eq!(second.0.len(), 2);
+    }
+}
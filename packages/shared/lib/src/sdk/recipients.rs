@@ -0,0 +1,221 @@
+//! Recipients import - parses and validates CSV or JSON lists of transfer recipients, used to
+//! build multi transfers for payroll and airdrops.
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use super::args::MultiTransferDataMsg;
+use crate::types::amount::StringAmount;
+use crate::utils::to_js_result;
+
+#[derive(Debug, Deserialize)]
+struct RecipientRow {
+    target: String,
+    token: String,
+    amount: String,
+}
+
+/// Problem found in a row of the recipients list
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct RecipientIssue {
+    // 1-based row number, header excluded
+    pub row: usize,
+    // One of "format", "target", "token", "amount", "duplicate"
+    pub field: String,
+    pub reason: String,
+}
+
+/// Issues found in the recipients list and the built tx, if there were no issues
+#[derive(Debug, Serialize, Tsify)]
+pub struct MultiTransferReport {
+    pub issues: Vec<RecipientIssue>,
+    // Borsh serialized tx
+    pub tx: Option<Vec<u8>>,
+}
+
+impl RecipientIssue {
+    fn new(row: usize, field: &str, reason: String) -> RecipientIssue {
+        RecipientIssue {
+            row,
+            field: field.to_string(),
+            reason,
+        }
+    }
+}
+
+// Parses `target,token,amount` lines, the header line is optional
+fn parse_csv(data: &str) -> Vec<Result<RecipientRow, RecipientIssue>> {
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|line| !line.starts_with("target"))
+        .enumerate()
+        .map(|(i, line)| {
+            let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+            match columns.as_slice() {
+                [target, token, amount] => Ok(RecipientRow {
+                    target: target.to_string(),
+                    token: token.to_string(),
+                    amount: amount.to_string(),
+                }),
+                _ => Err(RecipientIssue::new(
+                    i + 1,
+                    "format",
+                    format!("Expected 3 columns, found {}", columns.len()),
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Parses and validates recipients, given either as CSV(`target,token,amount`) or as a JSON
+/// array of `{ target, token, amount }`. Returns the transfers and the issues found, transfers
+/// should be built only if there are no issues.
+///
+/// # Arguments
+///
+/// * `data` - CSV or JSON list of recipients
+pub fn parse_recipients(data: &str) -> (Vec<MultiTransferDataMsg>, Vec<RecipientIssue>) {
+    let rows = if data.trim_start().starts_with('[') {
+        match serde_json::from_str::<Vec<RecipientRow>>(data) {
+            Ok(rows) => rows.into_iter().map(Ok).collect(),
+            Err(err) => {
+                return (
+                    vec![],
+                    vec![RecipientIssue::new(0, "format", err.to_string())],
+                )
+            }
+        }
+    } else {
+        parse_csv(data)
+    };
+
+    let mut transfers = vec![];
+    let mut issues = vec![];
+    let mut seen = BTreeSet::new();
+
+    for (i, row) in rows.into_iter().enumerate() {
+        let row_number = i + 1;
+        let row = match row {
+            Ok(row) => row,
+            Err(issue) => {
+                issues.push(issue);
+                continue;
+            }
+        };
+        let issues_before = issues.len();
+
+        if let Err(err) = Address::from_str(&row.target) {
+            issues.push(RecipientIssue::new(row_number, "target", err.to_string()));
+        }
+        if let Err(err) = Address::from_str(&row.token) {
+            issues.push(RecipientIssue::new(row_number, "token", err.to_string()));
+        }
+        match StringAmount::new(row.amount.clone()).to_denominated() {
+            Ok(amount) if amount.amount().is_zero() => issues.push(RecipientIssue::new(
+                row_number,
+                "amount",
+                String::from("Amount must be greater than zero"),
+            )),
+            Ok(_) => {}
+            Err(_) => issues.push(RecipientIssue::new(
+                row_number,
+                "amount",
+                format!("Invalid amount: {}", row.amount),
+            )),
+        }
+        if !seen.insert((row.target.clone(), row.token.clone())) {
+            issues.push(RecipientIssue::new(
+                row_number,
+                "duplicate",
+                format!("{} already receives {}", row.target, row.token),
+            ));
+        }
+
+        if issues.len() == issues_before {
+            transfers.push(MultiTransferDataMsg::new(
+                row.target,
+                row.token,
+                StringAmount::new(row.amount),
+            ));
+        }
+    }
+
+    (transfers, issues)
+}
+
+/// Validates a CSV or JSON list of recipients and returns the issues found, e.g.
+/// `[{ row: 2, field: "target", reason: "..." }]`
+#[wasm_bindgen]
+pub fn validate_recipients(data: &str) -> Result<JsValue, JsError> {
+    let (_, issues) = parse_recipients(data);
+
+    to_js_result(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    #[wasm_bindgen_test]
+    fn parses_csv_with_header() {
+        let data = format!("target,token,amount\n{},{},1.5\n", ALICE, NAM);
+        let (transfers, issues) = parse_recipients(&data);
+
+        assert_eq!(transfers.len(), 1);
+        assert!(issues.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn reports_invalid_rows() {
+        let data = format!(
+            "{alice},{nam},1\nbad,{nam},1\n{alice},{nam},2\n{alice},{nam},abc\n{alice},{nam}",
+            alice = ALICE,
+            nam = NAM
+        );
+        let (transfers, issues) = parse_recipients(&data);
+        let fields: Vec<(usize, &str)> = issues
+            .iter()
+            .map(|issue| (issue.row, issue.field.as_str()))
+            .collect();
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(
+            fields,
+            vec![
+                (2, "target"),
+                (3, "duplicate"),
+                (4, "amount"),
+                (4, "duplicate"),
+                (5, "format")
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_json() {
+        let data = format!(
+            r#"[{{ "target": "{}", "token": "{}", "amount": "0" }}]"#,
+            ALICE, NAM
+        );
+        let (transfers, issues) = parse_recipients(&data);
+
+        assert!(transfers.is_empty());
+        assert_eq!(
+            issues,
+            vec![RecipientIssue::new(
+                1,
+                "amount",
+                String::from("Amount must be greater than zero")
+            )]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Social recovery - an established account whose keys are the owner keys, e.g. of a device and
+//! an offline backup, and the keys of guardians. The account VP checks a single threshold, which
+//! is the owner threshold, so the owner signs alone while the guardians alone can never meet it.
+//! Each guardian signature stands in for a lost owner key: after losing up to
+//! `guardian_threshold` keys, `initiate_recovery` builds an account update replacing the keys,
+//! which the remaining owner keys and the guardians sign with `approve_recovery`.
+use std::collections::BTreeSet;
+
+use namada_sdk::key::common::PublicKey;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RecoveryError {
+    #[error("At least one owner key is required")]
+    NoOwners,
+    #[error("At least one guardian is required")]
+    NoGuardians,
+    #[error("Owner threshold must be between 1 and {0}")]
+    InvalidThreshold(usize),
+    #[error("Guardian threshold must be between 1 and {0}")]
+    InvalidGuardianThreshold(usize),
+    #[error("{0} guardians would meet the owner threshold {1} without the owner")]
+    GuardiansMeetThreshold(usize, u8),
+    #[error("Key {0} is listed more than once")]
+    DuplicateKey(String),
+}
+
+/// Keys and threshold of the account
+#[derive(Debug, PartialEq)]
+pub struct AccountKeys {
+    // Owner keys first, then the guardian keys
+    pub public_keys: Vec<PublicKey>,
+    pub threshold: u8,
+}
+
+/// Returns the keys and the threshold of the account after checking the thresholds
+///
+/// # Arguments
+///
+/// * `owners` - keys of the owner, e.g. of a device and an offline backup
+/// * `owner_threshold` - owner signatures required for any tx, the threshold of the account
+/// * `guardians` - keys of the guardians
+/// * `guardian_threshold` - owner keys which can be lost and replaced by guardian signatures
+///
+/// # Errors
+///
+/// Returns an error if a threshold can't be met, the guardians alone meet the owner threshold or
+/// a key is listed twice
+pub fn account_keys(
+    owners: Vec<PublicKey>,
+    owner_threshold: u8,
+    guardians: Vec<PublicKey>,
+    guardian_threshold: u8,
+) -> Result<AccountKeys, RecoveryError> {
+    if owners.is_empty() {
+        return Err(RecoveryError::NoOwners);
+    }
+    if guardians.is_empty() {
+        return Err(RecoveryError::NoGuardians);
+    }
+    if owner_threshold == 0 || usize::from(owner_threshold) > owners.len() {
+        return Err(RecoveryError::InvalidThreshold(owners.len()));
+    }
+    if guardian_threshold == 0 || usize::from(guardian_threshold) > guardians.len() {
+        return Err(RecoveryError::InvalidGuardianThreshold(guardians.len()));
+    }
+    if guardians.len() >= usize::from(owner_threshold) {
+        return Err(RecoveryError::GuardiansMeetThreshold(
+            guardians.len(),
+            owner_threshold,
+        ));
+    }
+
+    let public_keys: Vec<PublicKey> = owners.into_iter().chain(guardians).collect();
+    let mut seen = BTreeSet::new();
+    for key in &public_keys {
+        if !seen.insert(key.to_string()) {
+            return Err(RecoveryError::DuplicateKey(key.to_string()));
+        }
+    }
+
+    Ok(AccountKeys {
+        public_keys,
+        threshold: owner_threshold,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::key::{common, ed25519, RefTo, SigScheme};
+    use rand::rngs::OsRng;
+    use wasm_bindgen_test::*;
+
+    fn key() -> PublicKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng)).ref_to()
+    }
+
+    #[wasm_bindgen_test]
+    fn lists_owner_keys_first() {
+        let owners = vec![key(), key()];
+        let keys = account_keys(owners.clone(), 2, vec![key()], 1).unwrap();
+
+        assert_eq!(keys.public_keys.len(), 3);
+        assert_eq!(keys.public_keys[..2], owners[..]);
+        assert_eq!(keys.threshold, 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_setups() {
+        let owner = key();
+
+        assert_eq!(
+            account_keys(vec![owner.clone()], 1, vec![], 1),
+            Err(RecoveryError::NoGuardians)
+        );
+        assert_eq!(
+            account_keys(vec![owner.clone(), key()], 3, vec![key()], 1),
+            Err(RecoveryError::InvalidThreshold(2))
+        );
+        assert_eq!(
+            account_keys(vec![owner.clone(), key()], 2, vec![key(), key()], 2),
+            Err(RecoveryError::GuardiansMeetThreshold(2, 2))
+        );
+        assert_eq!(
+            account_keys(vec![key(), key(), key()], 3, vec![key()], 0),
+            Err(RecoveryError::InvalidGuardianThreshold(1))
+        );
+        assert_eq!(
+            account_keys(vec![owner.clone(), key()], 2, vec![owner.clone()], 1),
+            Err(RecoveryError::DuplicateKey(owner.to_string()))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn guardians_alone_cannot_meet_the_threshold() {
+        // A single owner key can't be recovered without the guardians meeting the threshold
+        assert_eq!(
+            account_keys(vec![key()], 1, vec![key()], 1),
+            Err(RecoveryError::GuardiansMeetThreshold(1, 1))
+        );
+        assert!(account_keys(vec![key(), key(), key()], 3, vec![key(), key()], 2).is_ok());
+    }
+}
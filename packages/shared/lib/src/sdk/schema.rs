@@ -0,0 +1,430 @@
+//! Borsh schemas of the binary blobs consumed and emitted by the Sdk, exported so payload
+//! inspectors and fuzzers can decode them without the Rust types. Values can be decoded to
+//! JSON with a schema, e.g. storage values returned by `Query::query_storage_value`.
+//! The wallet Store is not included, it is persisted as TOML rather than Borsh.
+use std::collections::BTreeMap;
+
+use gloo_utils::format::JsValueSerdeExt;
+use namada_sdk::borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use namada_sdk::borsh::BorshSchema;
+use namada_sdk::proof_of_stake::Epoch;
+use namada_sdk::token::Amount;
+use namada_sdk::token::Transfer;
+use namada_sdk::tx::data::pos::{Bond, ClaimRewards, Redelegation, Unbond, Withdraw};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use super::masp::checkpoint::TreeCheckpoint;
+use super::masp::compact::CompactBlock;
+use super::{args, signature, tx};
+use crate::types::amount::StringAmount;
+use crate::utils::to_js_result;
+
+/// Borsh definition of a declared type. Struct fields and enum variants reference other
+/// declarations, which are listed in the same schema.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchemaDefinition {
+    // Fixed size value, `size` in bytes
+    Primitive {
+        size: u8,
+    },
+    // Length prefix of `length_width` bytes, 0 for fixed length sequences
+    Sequence {
+        length_width: u8,
+        #[serde(with = "crate::types::number::u64_string")]
+        #[tsify(type = "string")]
+        min_length: u64,
+        #[serde(with = "crate::types::number::u64_string")]
+        #[tsify(type = "string")]
+        max_length: u64,
+        elements: String,
+    },
+    Tuple {
+        elements: Vec<String>,
+    },
+    // Vec of (discriminant, variant name, declaration)
+    Enum {
+        tag_width: u8,
+        variants: Vec<(i64, String, String)>,
+    },
+    // Vec of (field name, declaration), unnamed fields are named by their index
+    Struct {
+        fields: Vec<(String, String)>,
+    },
+}
+
+/// Schema of a type: its declaration and the definitions of all declarations it references
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct BorshSchemaInfo {
+    pub declaration: String,
+    pub definitions: BTreeMap<String, SchemaDefinition>,
+}
+
+impl From<&Definition> for SchemaDefinition {
+    fn from(definition: &Definition) -> SchemaDefinition {
+        match definition {
+            Definition::Primitive(size) => SchemaDefinition::Primitive { size: *size },
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => SchemaDefinition::Sequence {
+                length_width: *length_width,
+                min_length: *length_range.start(),
+                max_length: *length_range.end(),
+                elements: elements.clone(),
+            },
+            Definition::Tuple { elements } => SchemaDefinition::Tuple {
+                elements: elements.clone(),
+            },
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => SchemaDefinition::Enum {
+                tag_width: *tag_width,
+                variants: variants.clone(),
+            },
+            Definition::Struct { fields } => SchemaDefinition::Struct {
+                fields: match fields {
+                    Fields::NamedFields(fields) => fields.clone(),
+                    Fields::UnnamedFields(fields) => fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, declaration)| (i.to_string(), declaration.clone()))
+                        .collect(),
+                    Fields::Empty => vec![],
+                },
+            },
+        }
+    }
+}
+
+fn schema_of<T: BorshSchema>() -> BorshSchemaInfo {
+    let container = BorshSchemaContainer::for_type::<T>();
+
+    BorshSchemaInfo {
+        declaration: container.declaration().clone(),
+        definitions: container
+            .definitions()
+            .map(|(declaration, definition)| (declaration.clone(), definition.into()))
+            .collect(),
+    }
+}
+
+/// Returns Borsh schemas of all types passed to and returned from the Sdk, keyed by type name.
+/// Namada types are prefixed with "namada::", e.g. "namada::Tx" is the signed tx while "Tx" is
+/// the built tx returned by the build functions.
+#[wasm_bindgen]
+pub fn get_borsh_schemas() -> Result<JsValue, JsError> {
+    let schemas: BTreeMap<&str, BorshSchemaInfo> = BTreeMap::from([
+        ("namada::Tx", schema_of::<namada_sdk::tx::Tx>()),
+        ("namada::Transfer", schema_of::<Transfer>()),
+        ("namada::Bond", schema_of::<Bond>()),
+        ("namada::Unbond", schema_of::<Unbond>()),
+        ("namada::Withdraw", schema_of::<Withdraw>()),
+        ("namada::Redelegation", schema_of::<Redelegation>()),
+        ("namada::ClaimRewards", schema_of::<ClaimRewards>()),
+        (
+            "namada::Address",
+            schema_of::<namada_sdk::address::Address>(),
+        ),
+        ("namada::Amount", schema_of::<Amount>()),
+        ("namada::Epoch", schema_of::<Epoch>()),
+        ("Tx", schema_of::<tx::Tx>()),
+        ("TxDetails", schema_of::<tx::TxDetails>()),
+        ("TxResponse", schema_of::<tx::TxResponse>()),
+        ("SignatureMsg", schema_of::<signature::SignatureMsg>()),
+        ("StringAmount", schema_of::<StringAmount>()),
+        ("CompactBlock", schema_of::<CompactBlock>()),
+        ("TreeCheckpoint", schema_of::<TreeCheckpoint>()),
+        ("WrapperTxMsg", schema_of::<args::WrapperTxMsg>()),
+        ("RevealPkMsg", schema_of::<args::RevealPkMsg>()),
+        ("BondMsg", schema_of::<args::BondMsg>()),
+        ("UnbondMsg", schema_of::<args::UnbondMsg>()),
+        ("WithdrawMsg", schema_of::<args::WithdrawMsg>()),
+        ("RedelegateMsg", schema_of::<args::RedelegateMsg>()),
+        ("VoteProposalMsg", schema_of::<args::VoteProposalMsg>()),
+        ("ClaimRewardsMsg", schema_of::<args::ClaimRewardsMsg>()),
+        ("TransferMsg", schema_of::<args::TransferMsg>()),
+        (
+            "TransparentTransferMsg",
+            schema_of::<args::TransparentTransferMsg>(),
+        ),
+        ("MultiTransferMsg", schema_of::<args::MultiTransferMsg>()),
+        (
+            "MultiSourceTransferMsg",
+            schema_of::<args::MultiSourceTransferMsg>(),
+        ),
+        (
+            "ShieldedTransferMsg",
+            schema_of::<args::ShieldedTransferMsg>(),
+        ),
+        (
+            "ShieldingTransferMsg",
+            schema_of::<args::ShieldingTransferMsg>(),
+        ),
+        (
+            "UnshieldingTransferMsg",
+            schema_of::<args::UnshieldingTransferMsg>(),
+        ),
+        ("IbcTransferMsg", schema_of::<args::IbcTransferMsg>()),
+        (
+            "EthBridgeTransferMsg",
+            schema_of::<args::EthBridgeTransferMsg>(),
+        ),
+    ]);
+
+    to_js_result(schemas)
+}
+
+/// Decodes Borsh bytes to JSON following a schema
+struct Decoder<'a> {
+    definitions: &'a BTreeMap<String, SchemaDefinition>,
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("Unexpected end of data at byte {}", self.offset))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+
+        Ok(buf)
+    }
+
+    // Little endian unsigned integer of up to 16 bytes
+    fn uint(&mut self, width: usize) -> Result<u128, String> {
+        if width > 16 {
+            return Err(format!("Integer width {} exceeds 16 bytes", width));
+        }
+        let mut buf = [0u8; 16];
+        buf[..width].copy_from_slice(self.take(width)?);
+
+        Ok(u128::from_le_bytes(buf))
+    }
+
+    fn int(&mut self, width: usize) -> Result<i128, String> {
+        let value = self.uint(width)?;
+        let shift = 128 - width as u32 * 8;
+
+        Ok(((value << shift) as i128) >> shift)
+    }
+
+    fn decode(&mut self, declaration: &str) -> Result<Value, String> {
+        // Primitives are told apart by their declaration, the definition only has the size.
+        // Integers wider than 32 bits don't fit JS numbers and are returned as strings.
+        let primitive = match declaration {
+            "bool" => Some(json!(self.uint(1)? != 0)),
+            "u8" => Some(json!(self.uint(1)? as u64)),
+            "u16" => Some(json!(self.uint(2)? as u64)),
+            "u32" => Some(json!(self.uint(4)? as u64)),
+            "u64" => Some(json!(self.uint(8)?.to_string())),
+            "u128" => Some(json!(self.uint(16)?.to_string())),
+            "i8" => Some(json!(self.int(1)? as i64)),
+            "i16" => Some(json!(self.int(2)? as i64)),
+            "i32" => Some(json!(self.int(4)? as i64)),
+            "i64" => Some(json!(self.int(8)?.to_string())),
+            "i128" => Some(json!(self.int(16)?.to_string())),
+            "f32" => Some(json!(f32::from_le_bytes(self.array()?))),
+            "f64" => Some(json!(f64::from_le_bytes(self.array()?))),
+            "String" => {
+                let len = self.uint(4)? as usize;
+                let bytes = self.take(len)?;
+                Some(json!(
+                    String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())?
+                ))
+            }
+            "()" => Some(Value::Null),
+            _ => None,
+        };
+        if let Some(value) = primitive {
+            return Ok(value);
+        }
+
+        let definition = self
+            .definitions
+            .get(declaration)
+            .ok_or_else(|| format!("Missing definition of {}", declaration))?;
+
+        match definition {
+            SchemaDefinition::Primitive { size } => {
+                Ok(json!(hex::encode(self.take(*size as usize)?)))
+            }
+            SchemaDefinition::Sequence {
+                length_width,
+                min_length,
+                elements,
+                ..
+            } => {
+                let len = match length_width {
+                    0 => *min_length as usize,
+                    width => {
+                        let len = self.uint(*width as usize)?;
+                        // Namada types have no zero sized elements, so a sequence can't be
+                        // longer than the data left, which also bounds the loop below
+                        usize::try_from(len)
+                            .ok()
+                            .filter(|len| *len <= self.bytes.len() - self.offset)
+                            .ok_or_else(|| {
+                                format!(
+                                    "Sequence length {} exceeds the data at byte {}",
+                                    len, self.offset
+                                )
+                            })?
+                    }
+                };
+                if elements == "u8" {
+                    return Ok(json!(hex::encode(self.take(len)?)));
+                }
+                (0..len)
+                    .map(|_| self.decode(elements))
+                    .collect::<Result<Vec<Value>, String>>()
+                    .map(Value::Array)
+            }
+            SchemaDefinition::Tuple { elements } => elements
+                .iter()
+                .map(|element| self.decode(element))
+                .collect::<Result<Vec<Value>, String>>()
+                .map(Value::Array),
+            SchemaDefinition::Enum {
+                tag_width,
+                variants,
+            } => {
+                let tag = self.uint(*tag_width as usize)? as i64;
+                let (_, name, declaration) = variants
+                    .iter()
+                    .find(|(discriminant, _, _)| *discriminant == tag)
+                    .ok_or_else(|| format!("Unknown variant {} of {}", tag, declaration))?;
+                let value = self.decode(declaration)?;
+
+                Ok(json!({ name.clone(): value }))
+            }
+            SchemaDefinition::Struct { fields } => {
+                let mut object = serde_json::Map::new();
+                for (name, declaration) in fields {
+                    object.insert(name.clone(), self.decode(declaration)?);
+                }
+
+                Ok(Value::Object(object))
+            }
+        }
+    }
+}
+
+/// Decodes Borsh bytes to JSON following the schema. Byte sequences are hex encoded, integers
+/// wider than 32 bits are strings and enums are `{ [variant]: value }`.
+///
+/// # Errors
+///
+/// Returns an error describing where decoding failed, or if bytes are left after decoding
+pub fn decode_with_schema(bytes: &[u8], schema: &BorshSchemaInfo) -> Result<Value, String> {
+    let mut decoder = Decoder {
+        definitions: &schema.definitions,
+        bytes,
+        offset: 0,
+    };
+    let value = decoder.decode(&schema.declaration)?;
+    if decoder.offset != bytes.len() {
+        return Err(format!(
+            "{} bytes left after decoding {}",
+            bytes.len() - decoder.offset,
+            schema.declaration
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Decodes Borsh bytes, e.g. a storage value, with a schema returned by `get_borsh_schemas`
+///
+/// # Arguments
+///
+/// * `bytes` - Borsh encoded value
+/// * `schema` - schema of the value, `{ declaration, definitions }`
+///
+/// # Errors
+///
+/// Returns an error if the schema is invalid or the bytes don't match it
+#[wasm_bindgen]
+pub fn decode_borsh(bytes: &[u8], schema: JsValue) -> Result<JsValue, JsError> {
+    let schema: BorshSchemaInfo = schema.into_serde()?;
+    let value = decode_with_schema(bytes, &schema).map_err(|e| JsError::new(&e))?;
+
+    to_js_result(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn includes_referenced_definitions() {
+        let schema = schema_of::<args::WrapperTxMsg>();
+        let fields = match &schema.definitions[&schema.declaration] {
+            SchemaDefinition::Struct { fields } => fields.clone(),
+            _ => panic!("WrapperTxMsg should be a struct"),
+        };
+
+        assert_eq!(schema.declaration, "WrapperTxMsg");
+        assert_eq!(
+            fields[1],
+            (String::from("fee_amount"), String::from("StringAmount"))
+        );
+        assert_eq!(
+            schema.definitions["StringAmount"],
+            SchemaDefinition::Struct {
+                fields: vec![(String::from("0"), String::from("String"))]
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_decode_with_schema() {
+        let msg = args::VoteProposalMsg::new(String::from("voter"), 7, String::from("yay"));
+        let bytes = namada_sdk::borsh::to_vec(&msg).unwrap();
+
+        let value = decode_with_schema(&bytes, &schema_of::<args::VoteProposalMsg>()).unwrap();
+
+        assert_eq!(
+            value,
+            json!({ "signer": "voter", "proposal_id": "7", "vote": "yay" })
+        );
+        assert!(decode_with_schema(&bytes[1..], &schema_of::<args::VoteProposalMsg>()).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_widths() {
+        let schema = |length_width: u8| BorshSchemaInfo {
+            declaration: String::from("Bytes"),
+            definitions: BTreeMap::from([(
+                String::from("Bytes"),
+                SchemaDefinition::Sequence {
+                    length_width,
+                    min_length: 0,
+                    max_length: u64::MAX,
+                    elements: String::from("u8"),
+                },
+            )]),
+        };
+
+        assert!(decode_with_schema(&[0; 17], &schema(17)).is_err());
+        assert!(decode_with_schema(&[0xff; 4], &schema(4)).is_err());
+        assert_eq!(decode_with_schema(&[0; 4], &schema(4)).unwrap(), json!(""));
+    }
+}
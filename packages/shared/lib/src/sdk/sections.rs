@@ -0,0 +1,231 @@
+//! Low-level access to the sections of a tx, so integrators can assemble tx kinds this crate
+//! doesn't wrap yet. Changing the data or code of a signed tx invalidates its signatures, sign
+//! the tx after all sections are in place.
+use namada_sdk::borsh::{self, BorshDeserialize};
+use namada_sdk::hash::Hash;
+use namada_sdk::key::common::{PublicKey, Signature};
+use namada_sdk::tx::{Authorization, Code, Data, Section, Signer, Tx, TxCommitments};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use crate::utils::to_js_result;
+
+/// Section of a tx as listed to JS
+#[derive(Debug, PartialEq, Serialize, Tsify)]
+pub struct SectionInfo {
+    pub index: u32,
+    pub kind: String,
+    // Hash the header commitments and signatures refer to the section by
+    pub hash: String,
+}
+
+fn section_kind(section: &Section) -> &'static str {
+    match section {
+        Section::Data(_) => "data",
+        Section::ExtraData(_) => "extra_data",
+        Section::Code(_) => "code",
+        Section::Authorization(_) => "authorization",
+        Section::MaspBuilder(_) => "masp_builder",
+        Section::MaspTx(_) => "masp_tx",
+        Section::Header(_) => "header",
+    }
+}
+
+pub fn list_sections(tx: &Tx) -> Vec<SectionInfo> {
+    tx.sections
+        .iter()
+        .enumerate()
+        .map(|(index, section)| SectionInfo {
+            index: index as u32,
+            kind: section_kind(section).to_string(),
+            hash: section.get_hash().to_string(),
+        })
+        .collect()
+}
+
+/// Replaces the section at the index and points the header commitments which referred to the
+/// old section to the new one
+pub fn replace_section(tx: &mut Tx, index: usize, section: Section) -> Result<(), JsError> {
+    let old = tx
+        .sections
+        .get_mut(index)
+        .ok_or_else(|| JsError::new(&format!("Tx has no section {}", index)))?;
+    let old_hash = old.get_hash();
+    let new_hash = section.get_hash();
+    *old = section;
+
+    let replace = |hash: Hash| if hash == old_hash { new_hash } else { hash };
+    tx.header.batch = tx
+        .header
+        .batch
+        .iter()
+        .map(|commitments| TxCommitments {
+            code_hash: replace(commitments.code_hash),
+            data_hash: replace(commitments.data_hash),
+            memo_hash: replace(commitments.memo_hash),
+        })
+        .collect();
+
+    Ok(())
+}
+
+/// Returns the sections of the tx with their hashes, e.g.
+/// `[{ index: 0, kind: "data", hash: "A1B2..." }]`
+///
+/// # Errors
+///
+/// Returns an error if the tx can't be deserialized
+#[wasm_bindgen]
+pub fn get_tx_sections(tx_bytes: &[u8]) -> Result<JsValue, JsError> {
+    let tx = Tx::try_from_slice(tx_bytes)?;
+
+    to_js_result(list_sections(&tx))
+}
+
+/// Sets the data of the first commitment of the tx and returns the tx bytes
+#[wasm_bindgen]
+pub fn set_tx_data(tx_bytes: &[u8], data: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    let mut tx = Tx::try_from_slice(tx_bytes)?;
+    tx.set_data(Data::new(data));
+
+    Ok(borsh::to_vec(&tx)?)
+}
+
+/// Sets the wasm code of the first commitment of the tx and returns the tx bytes
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized tx
+/// * `code` - wasm code
+/// * `tag` - optional code tag, e.g. "tx_transfer.wasm"
+#[wasm_bindgen]
+pub fn set_tx_code(
+    tx_bytes: &[u8],
+    code: Vec<u8>,
+    tag: Option<String>,
+) -> Result<Vec<u8>, JsError> {
+    let mut tx = Tx::try_from_slice(tx_bytes)?;
+    tx.set_code(Code::new(code, tag));
+
+    Ok(borsh::to_vec(&tx)?)
+}
+
+/// Appends a borsh serialized section to the tx and returns the tx bytes
+#[wasm_bindgen]
+pub fn append_tx_section(tx_bytes: &[u8], section: &[u8]) -> Result<Vec<u8>, JsError> {
+    let mut tx = Tx::try_from_slice(tx_bytes)?;
+    tx.add_section(Section::try_from_slice(section)?);
+
+    Ok(borsh::to_vec(&tx)?)
+}
+
+/// Replaces the section at the index with a borsh serialized section and returns the tx bytes
+///
+/// # Errors
+///
+/// Returns an error if the tx or section can't be deserialized or there is no such section
+#[wasm_bindgen]
+pub fn replace_tx_section(tx_bytes: &[u8], index: u32, section: &[u8]) -> Result<Vec<u8>, JsError> {
+    let mut tx = Tx::try_from_slice(tx_bytes)?;
+    replace_section(&mut tx, index as usize, Section::try_from_slice(section)?)?;
+
+    Ok(borsh::to_vec(&tx)?)
+}
+
+/// Appends a signature made outside of the Sdk and returns the tx bytes
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized tx
+/// * `targets` - hex encoded hashes of the signed sections, as returned by `get_tx_sections`
+/// * `pubkey` - public key of the signer
+/// * `signature` - signature of the hash of `targets` and the signer, as returned by
+///   `get_signature_hash`
+///
+/// # Errors
+///
+/// Returns an error if the tx, a hash, the key or the signature can't be deserialized
+#[wasm_bindgen]
+pub fn append_tx_signature(
+    tx_bytes: &[u8],
+    targets: Vec<String>,
+    pubkey: String,
+    signature: String,
+) -> Result<Vec<u8>, JsError> {
+    let mut tx = Tx::try_from_slice(tx_bytes)?;
+    let authorization = authorization(targets, pubkey, Some(signature))?;
+    tx.add_section(Section::Authorization(authorization));
+
+    Ok(borsh::to_vec(&tx)?)
+}
+
+/// Returns the hex encoded hash a signer of the targets signs
+///
+/// # Arguments
+///
+/// * `targets` - hex encoded hashes of the signed sections
+/// * `pubkey` - public key of the signer
+#[wasm_bindgen]
+pub fn get_signature_hash(targets: Vec<String>, pubkey: String) -> Result<String, JsError> {
+    Ok(authorization(targets, pubkey, None)?
+        .get_raw_hash()
+        .to_string())
+}
+
+/// Returns the hex encoded hash of a borsh serialized section
+#[wasm_bindgen]
+pub fn get_section_hash(section: &[u8]) -> Result<String, JsError> {
+    Ok(Section::try_from_slice(section)?.get_hash().to_string())
+}
+
+fn authorization(
+    targets: Vec<String>,
+    pubkey: String,
+    signature: Option<String>,
+) -> Result<Authorization, JsError> {
+    let targets = targets
+        .iter()
+        .map(|target| Hash::from_str(target))
+        .collect::<Result<Vec<Hash>, _>>()?;
+    let mut signatures = BTreeMap::new();
+    if let Some(signature) = signature {
+        signatures.insert(0, Signature::from_str(&signature)?);
+    }
+
+    Ok(Authorization {
+        targets,
+        signer: Signer::PubKeys(vec![PublicKey::from_str(&pubkey)?]),
+        signatures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::tx::data::TxType;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn lists_and_replaces_sections() {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_data(Data::new(vec![1]));
+        tx.set_code(Code::new(vec![2], None));
+
+        let sections = list_sections(&tx);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].kind, "data");
+        assert_eq!(sections[1].kind, "code");
+        assert_eq!(sections[0].hash, tx.sections[0].get_hash().to_string());
+
+        replace_section(&mut tx, 0, Section::Data(Data::new(vec![3]))).unwrap();
+        assert_ne!(list_sections(&tx)[0].hash, sections[0].hash);
+        // The header refers to the new data
+        let commitments = tx.first_commitments().unwrap();
+        assert_eq!(commitments.data_hash, tx.sections[0].get_hash());
+        assert_eq!(commitments.code_hash, tx.sections[1].get_hash());
+        assert!(replace_section(&mut tx, 2, Section::Data(Data::new(vec![]))).is_err());
+    }
+}
@@ -0,0 +1,478 @@
+//! Session keys - temporary keys a dapp origin can sign low-value transfers with, without an
+//! approval popup for every action. The user funds the implicit address of the session key with
+//! the budget, which is the only on-chain grant, and the policy below limits what the key signs:
+//! transparent transfers of one token from the session address, with the fee paid by the session
+//! key in the same token, up to a per-tx amount, until the session expires. Session keys are held in memory only, `revoke_session` sweeps the funds
+//! left on the session address back to the user before the key is dropped.
+use std::collections::BTreeMap;
+
+use namada_sdk::address::Address;
+use namada_sdk::key::{common, RefTo};
+use namada_sdk::time::DateTimeUtc;
+use namada_sdk::token::{Amount, DenominatedAmount, Transfer};
+use namada_sdk::tx::data::WrapperTx;
+use serde::Serialize;
+use thiserror::Error;
+use tsify::Tsify;
+
+use super::transaction::TransactionKind;
+use crate::types::amount::StringAmount;
+
+// Sessions are meant for a play session, not as a permanent grant
+pub const MAX_SESSION_TTL_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SessionError {
+    #[error("No session key for origin {0}")]
+    NotFound(String),
+    #[error("Session of origin {0} expired")]
+    Expired(String),
+    #[error("Session ttl must be between 1 and {MAX_SESSION_TTL_SECS} seconds, got {0}")]
+    InvalidTtl(u64),
+    #[error("Session keys only sign transparent transfers")]
+    NotTransfer,
+    #[error("Session keys only sign transfers from {0}")]
+    WrongSource(String),
+    #[error("Session keys only sign transfers of {0}")]
+    WrongToken(String),
+    #[error("Session keys only sign txs they pay the fee of")]
+    WrongFeePayer,
+    #[error("Session keys only pay fees in {0}")]
+    WrongFeeToken(String),
+    #[error("Invalid fee: {0}")]
+    InvalidFee(String),
+    #[error("Transfer and fee of {0} exceed the limit of {1} per tx")]
+    OverLimit(String, String),
+    #[error("Transfer and fee of {0} exceed the remaining budget of {1}")]
+    OverBudget(String, String),
+}
+
+/// Public part of a session, returned to the host so it can ask the user to fund the address
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct SessionInfo {
+    pub origin: String,
+    // Implicit address of the session key, funded by the user with the budget
+    pub address: String,
+    pub public_key: String,
+    pub token: String,
+    pub max_per_tx: StringAmount,
+    pub budget: StringAmount,
+    pub spent: StringAmount,
+    pub expires_at: String,
+}
+
+struct Session {
+    key: common::SecretKey,
+    token: Address,
+    max_per_tx: DenominatedAmount,
+    budget: DenominatedAmount,
+    spent: DenominatedAmount,
+    expires_at: DateTimeUtc,
+}
+
+impl Session {
+    fn info(&self, origin: &str) -> SessionInfo {
+        let public_key = self.key.ref_to();
+
+        SessionInfo {
+            origin: origin.to_string(),
+            address: Address::from(&public_key).to_string(),
+            public_key: public_key.to_string(),
+            token: self.token.to_string(),
+            max_per_tx: StringAmount::from(self.max_per_tx),
+            budget: StringAmount::from(self.budget),
+            spent: StringAmount::from(self.spent),
+            expires_at: self.expires_at.to_rfc3339(),
+        }
+    }
+
+    // Returns the amount the inner txs transfer from the session address plus the fee of the
+    // whole gas limit
+    fn spending(
+        &self,
+        kinds: &[TransactionKind],
+        wrapper: &WrapperTx,
+    ) -> Result<DenominatedAmount, SessionError> {
+        let public_key = self.key.ref_to();
+        if wrapper.pk != public_key {
+            return Err(SessionError::WrongFeePayer);
+        }
+        if wrapper.fee.token != self.token {
+            return Err(SessionError::WrongFeeToken(self.token.to_string()));
+        }
+        let address = Address::from(&public_key);
+        let mut total = wrapper
+            .get_tx_fee()
+            .map_err(|err| SessionError::InvalidFee(err.to_string()))?;
+
+        for kind in kinds {
+            let TransactionKind::Transfer(Transfer {
+                sources,
+                shielded_section_hash: None,
+                ..
+            }) = kind
+            else {
+                return Err(SessionError::NotTransfer);
+            };
+            for (account, amount) in sources {
+                if account.owner != address {
+                    return Err(SessionError::WrongSource(address.to_string()));
+                }
+                if account.token != self.token {
+                    return Err(SessionError::WrongToken(self.token.to_string()));
+                }
+                total = total.checked_add(*amount).ok_or_else(|| {
+                    SessionError::OverBudget(amount.to_string(), self.remaining())
+                })?;
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn remaining(&self) -> String {
+        self.budget
+            .checked_sub(self.spent)
+            .map_or_else(|| String::from("0"), |remaining| remaining.to_string())
+    }
+}
+
+/// Session per dapp origin, a new session replaces the previous one of the origin
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: BTreeMap<String, Session>,
+}
+
+impl SessionRegistry {
+    /// Starts a session of the origin with a new key
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - dapp origin, e.g. "https://game.example"
+    /// * `key` - session key, generated by the caller
+    /// * `token` - the only token the session key transfers
+    /// * `max_per_tx` - highest amount a single tx transfers
+    /// * `budget` - highest amount all txs of the session transfer
+    /// * `ttl_secs` - seconds until the session expires
+    /// * `now` - current time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ttl is zero or longer than a day
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        &mut self,
+        origin: String,
+        key: common::SecretKey,
+        token: Address,
+        max_per_tx: DenominatedAmount,
+        budget: DenominatedAmount,
+        ttl_secs: u64,
+        now: DateTimeUtc,
+    ) -> Result<SessionInfo, SessionError> {
+        if ttl_secs == 0 || ttl_secs > MAX_SESSION_TTL_SECS {
+            return Err(SessionError::InvalidTtl(ttl_secs));
+        }
+
+        let session = Session {
+            key,
+            token,
+            max_per_tx,
+            budget,
+            spent: DenominatedAmount::native(Default::default()),
+            expires_at: DateTimeUtc(now.0 + chrono::Duration::seconds(ttl_secs as i64)),
+        };
+        let info = session.info(&origin);
+        self.sessions.insert(origin, session);
+
+        Ok(info)
+    }
+
+    /// Ends the session of the origin, its key signs nothing but the sweep afterwards
+    pub fn end(&mut self, origin: &str, now: DateTimeUtc) {
+        if let Some(session) = self.sessions.get_mut(origin) {
+            session.expires_at = DateTimeUtc(session.expires_at.0.min(now.0));
+        }
+    }
+
+    /// Returns the key and token of the session, expired or not, to sweep the funds left
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the origin has no session
+    pub fn sweep_key(&self, origin: &str) -> Result<(common::SecretKey, Address), SessionError> {
+        self.sessions
+            .get(origin)
+            .map(|session| (session.key.clone(), session.token.clone()))
+            .ok_or_else(|| SessionError::NotFound(origin.to_string()))
+    }
+
+    /// Drops the session and its key, once the funds left are swept
+    pub fn remove(&mut self, origin: &str) {
+        self.sessions.remove(origin);
+    }
+
+    /// Returns the sessions which didn't expire yet
+    pub fn sessions(&self, now: DateTimeUtc) -> Vec<SessionInfo> {
+        self.sessions
+            .iter()
+            .filter(|(_, session)| session.expires_at.0 > now.0)
+            .map(|(origin, session)| session.info(origin))
+            .collect()
+    }
+
+    /// Checks the inner txs against the policy of the session and records the spent amount
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - origin the tx is signed for
+    /// * `kinds` - decoded inner txs
+    /// * `wrapper` - wrapper of the tx, its fee counts against the limits
+    /// * `now` - current time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the origin has no session, the session expired, an inner tx is not a
+    /// transparent transfer of the session token from the session address, the fee is not paid
+    /// by the session key in the session token, or the transferred amount plus the fee exceeds
+    /// the per-tx limit or the remaining budget
+    pub fn authorize(
+        &mut self,
+        origin: &str,
+        kinds: &[TransactionKind],
+        wrapper: &WrapperTx,
+        now: DateTimeUtc,
+    ) -> Result<common::SecretKey, SessionError> {
+        let session = self
+            .sessions
+            .get_mut(origin)
+            .ok_or_else(|| SessionError::NotFound(origin.to_string()))?;
+        // Expired sessions keep their key until the funds left are swept
+        if session.expires_at.0 <= now.0 {
+            return Err(SessionError::Expired(origin.to_string()));
+        }
+
+        let amount = session.spending(kinds, wrapper)?;
+        if amount > session.max_per_tx {
+            return Err(SessionError::OverLimit(
+                amount.to_string(),
+                session.max_per_tx.to_string(),
+            ));
+        }
+        let spent = session
+            .spent
+            .checked_add(amount)
+            .filter(|spent| *spent <= session.budget)
+            .ok_or_else(|| SessionError::OverBudget(amount.to_string(), session.remaining()))?;
+        session.spent = spent;
+
+        Ok(session.key.clone())
+    }
+}
+
+/// Returns the amount a sweep transfers, the balance less the fee if the session key pays it in
+/// the swept token, or None if nothing is left
+///
+/// # Arguments
+///
+/// * `balance` - balance of the session address
+/// * `fee` - fee of the sweep if paid in the swept token, gas limit times the gas price
+pub fn sweep_amount(balance: Amount, fee: Option<Amount>) -> Option<Amount> {
+    balance
+        .checked_sub(fee.unwrap_or_default())
+        .filter(|amount| !amount.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use namada_sdk::key::{ed25519, SigScheme};
+    use namada_sdk::token::Account;
+    use namada_sdk::tx::data::{Fee, GasLimit};
+    use rand::rngs::OsRng;
+    use wasm_bindgen_test::*;
+
+    const ORIGIN: &str = "https://game.example";
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+    const OTHER: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    fn amount(amount: &str) -> DenominatedAmount {
+        DenominatedAmount::from_str(amount).unwrap()
+    }
+
+    fn key() -> common::SecretKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng))
+    }
+
+    // Wrapper with a gas limit of 50000, paying 0.05 at a fee of 0.000001 per gas unit
+    fn wrapper(key: &common::SecretKey, token: &str, amount_per_gas_unit: &str) -> WrapperTx {
+        WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: amount(amount_per_gas_unit),
+                token: Address::from_str(token).unwrap(),
+            },
+            key.ref_to(),
+            GasLimit::from(50_000),
+        )
+    }
+
+    fn transfer(source: &str, amount: &str) -> TransactionKind {
+        let account = |owner: &str| Account {
+            owner: Address::from_str(owner).unwrap(),
+            token: Address::from_str(NAM).unwrap(),
+        };
+
+        TransactionKind::Transfer(Transfer {
+            sources: BTreeMap::from([(account(source), self::amount(amount))]),
+            targets: BTreeMap::from([(account(OTHER), self::amount(amount))]),
+            shielded_section_hash: None,
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn authorizes_transfers_within_limits() {
+        let now = DateTimeUtc::now();
+        let key = key();
+        let fee = wrapper(&key, NAM, "0.000001");
+        let mut registry = SessionRegistry::default();
+        let info = registry
+            .create(
+                ORIGIN.to_string(),
+                key.clone(),
+                Address::from_str(NAM).unwrap(),
+                amount("1"),
+                amount("1.5"),
+                60,
+                now,
+            )
+            .unwrap();
+
+        assert!(registry
+            .authorize(ORIGIN, &[transfer(&info.address, "0.8")], &fee, now)
+            .is_ok());
+        assert!(matches!(
+            registry.authorize(ORIGIN, &[transfer(&info.address, "2")], &fee, now),
+            Err(SessionError::OverLimit(_, _))
+        ));
+        assert!(matches!(
+            registry.authorize(ORIGIN, &[transfer(&info.address, "0.8")], &fee, now),
+            Err(SessionError::OverBudget(_, _))
+        ));
+        assert!(matches!(
+            registry.authorize(ORIGIN, &[transfer(OTHER, "0.1")], &fee, now),
+            Err(SessionError::WrongSource(_))
+        ));
+        assert_eq!(
+            registry
+                .authorize("https://other.example", &[], &fee, now)
+                .unwrap_err(),
+            SessionError::NotFound(String::from("https://other.example"))
+        );
+
+        let later = DateTimeUtc(now.0 + chrono::Duration::seconds(61));
+        assert_eq!(
+            registry
+                .authorize(ORIGIN, &[transfer(&info.address, "0.1")], &fee, later)
+                .unwrap_err(),
+            SessionError::Expired(ORIGIN.to_string())
+        );
+        assert!(registry.sessions(later).is_empty());
+        assert!(registry.sweep_key(ORIGIN).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn counts_fees_against_limits() {
+        let now = DateTimeUtc::now();
+        let other_key = key();
+        let key = key();
+        let mut registry = SessionRegistry::default();
+        let info = registry
+            .create(
+                ORIGIN.to_string(),
+                key.clone(),
+                Address::from_str(NAM).unwrap(),
+                amount("1"),
+                amount("1.5"),
+                60,
+                now,
+            )
+            .unwrap();
+        let small = [transfer(&info.address, "0.1")];
+
+        // 0.1 transferred plus a fee of 50000 * 0.0001 = 5
+        assert!(matches!(
+            registry.authorize(ORIGIN, &small, &wrapper(&key, NAM, "0.0001"), now),
+            Err(SessionError::OverLimit(_, _))
+        ));
+        assert_eq!(
+            registry
+                .authorize(ORIGIN, &small, &wrapper(&key, OTHER, "0.000001"), now)
+                .unwrap_err(),
+            SessionError::WrongFeeToken(NAM.to_string())
+        );
+        assert_eq!(
+            registry
+                .authorize(ORIGIN, &small, &wrapper(&other_key, NAM, "0.000001"), now)
+                .unwrap_err(),
+            SessionError::WrongFeePayer
+        );
+
+        // 0.05 of fee each, the third tx exceeds the budget of 1.5
+        for _ in 0..2 {
+            let tx = [transfer(&info.address, "0.65")];
+            assert!(registry
+                .authorize(ORIGIN, &tx, &wrapper(&key, NAM, "0.000001"), now)
+                .is_ok());
+        }
+        assert!(matches!(
+            registry.authorize(ORIGIN, &small, &wrapper(&key, NAM, "0.000001"), now),
+            Err(SessionError::OverBudget(_, _))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn ended_sessions_only_sweep() {
+        let now = DateTimeUtc::now();
+        let key = key();
+        let fee = wrapper(&key, NAM, "0.000001");
+        let mut registry = SessionRegistry::default();
+        let info = registry
+            .create(
+                ORIGIN.to_string(),
+                key.clone(),
+                Address::from_str(NAM).unwrap(),
+                amount("1"),
+                amount("1.5"),
+                60,
+                now,
+            )
+            .unwrap();
+
+        registry.end(ORIGIN, now);
+        assert_eq!(
+            registry
+                .authorize(ORIGIN, &[transfer(&info.address, "0.1")], &fee, now)
+                .unwrap_err(),
+            SessionError::Expired(ORIGIN.to_string())
+        );
+        let (key, _) = registry.sweep_key(ORIGIN).unwrap();
+        assert_eq!(key.ref_to().to_string(), info.public_key);
+
+        registry.remove(ORIGIN);
+        assert!(registry.sweep_key(ORIGIN).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn sweeps_balance_less_fee() {
+        let balance = Amount::from_u64(1_000);
+        assert_eq!(sweep_amount(balance, None), Some(balance));
+        assert_eq!(
+            sweep_amount(balance, Some(Amount::from_u64(100))),
+            Some(Amount::from_u64(900))
+        );
+        assert_eq!(sweep_amount(balance, Some(balance)), None);
+        assert_eq!(sweep_amount(balance, Some(Amount::from_u64(2_000))), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Sign doc - canonical, human-readable representation of a transaction.
+//! Approval UIs and hardware wallets can display it and compare hashes to verify they show
+//! the same content.
+use gloo_utils::format::JsValueSerdeExt;
+use namada_sdk::borsh;
+use namada_sdk::hash::Hash;
+use namada_sdk::tx::{self, data::compute_inner_tx_hash, either::Either};
+use namada_sdk::uint::Uint;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
+
+use super::custom_tx::custom_tx_type_by_hash;
+use super::transaction::TransactionKind;
+use super::tx::wasm_hash_to_tx_type;
+use crate::types::query::WasmHash;
+use crate::utils::to_js_result;
+
+#[derive(Debug, Serialize)]
+pub struct SignDocFee {
+    token: String,
+    amount_per_gas_unit: String,
+    gas_limit: String,
+    payer: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignDocCommitment {
+    #[serde(rename = "type")]
+    tx_type: String,
+    hash: String,
+    memo: Option<String>,
+    data: serde_json::Value,
+}
+
+/// Content of the sign doc, fields are serialized in declaration order
+#[derive(Debug, Serialize)]
+pub struct SignDoc {
+    chain_id: String,
+    // Omitted when not bound, so hashes of unbound sign docs don't change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    genesis_hash: Option<String>,
+    expiration: Option<String>,
+    fee: SignDocFee,
+    commitments: Vec<SignDocCommitment>,
+}
+
+/// Sign doc of an off-chain statement, e.g. an address ownership proof
+#[derive(Debug, Serialize)]
+pub struct StatementSignDoc {
+    statement: String,
+}
+
+impl StatementSignDoc {
+    pub fn new(statement: String) -> StatementSignDoc {
+        StatementSignDoc { statement }
+    }
+
+    /// Returns the canonical JSON and its hash
+    pub fn canonical(&self) -> Result<SignDocResult, JsError> {
+        let doc = serde_json::to_string(self)?;
+        let hash = Hash::sha256(doc.as_bytes()).to_string();
+
+        Ok(SignDocResult { doc, hash })
+    }
+}
+
+/// Sign doc together with its hash, as returned to JS
+#[derive(Debug, Serialize, Tsify)]
+pub struct SignDocResult {
+    /// Canonical JSON of the sign doc
+    doc: String,
+    /// Hex encoded sha256 of `doc`
+    hash: String,
+}
+
+impl SignDoc {
+    pub fn from_tx(tx: &tx::Tx, wasm_hashes: &Vec<WasmHash>) -> Result<SignDoc, JsError> {
+        let wrapper = match &tx.header().tx_type {
+            tx::data::TxType::Wrapper(wrapper) => wrapper.clone(),
+            _ => return Err(JsError::new("Invalid transaction type!")),
+        };
+        let wrapper_hash = tx.wrapper_hash();
+
+        let fee = SignDocFee {
+            token: wrapper.fee.token.to_string(),
+            amount_per_gas_unit: wrapper.fee.amount_per_gas_unit.to_string(),
+            gas_limit: Uint::from(wrapper.gas_limit).to_string(),
+            payer: wrapper.pk.to_string(),
+        };
+
+        let mut commitments: Vec<SignDocCommitment> = vec![];
+        for cmt in tx.commitments() {
+            let memo = tx
+                .memo(cmt)
+                .map(|memo_bytes| String::from_utf8_lossy(&memo_bytes).to_string());
+            let hash = compute_inner_tx_hash(wrapper_hash.as_ref(), Either::Right(cmt)).to_string();
+            let tx_code_id = tx
+                .get_section(cmt.code_sechash())
+                .and_then(|s| s.code_sec())
+                .map(|s| String::from_utf8(subtle_encoding::hex::encode(s.code.hash().0)))
+                .transpose()?;
+            let tx_type = tx_code_id
+                .as_ref()
+                .and_then(|tx_code_id| wasm_hash_to_tx_type(tx_code_id, wasm_hashes));
+            let custom_tx = tx_code_id
+                .as_ref()
+                .and_then(|tx_code_id| custom_tx_type_by_hash(tx_code_id));
+            let tx_data = tx.data(cmt).unwrap_or_default();
+
+            let (tx_type, data) = match (tx_type, custom_tx) {
+                (Some(tx_type), _) => {
+                    let tx_kind = TransactionKind::decode(tx_type, &tx_data)?;
+
+                    (tx_type.name().to_string(), tx_kind.to_sign_doc_data())
+                }
+                (None, Some(custom_tx)) => (custom_tx.name.clone(), custom_tx.format(&tx_data)?),
+                (None, None) => (String::from("unknown"), serde_json::Value::Null),
+            };
+
+            commitments.push(SignDocCommitment {
+                tx_type,
+                hash,
+                memo,
+                data,
+            });
+        }
+
+        Ok(SignDoc {
+            chain_id: tx.header().chain_id.to_string(),
+            genesis_hash: None,
+            expiration: tx.header().expiration.map(|exp| exp.to_string()),
+            fee,
+            commitments,
+        })
+    }
+
+    /// Includes hash of the genesis block the tx is bound to
+    pub fn with_genesis_hash(self, genesis_hash: Option<String>) -> SignDoc {
+        SignDoc {
+            genesis_hash,
+            ..self
+        }
+    }
+
+    /// Returns the canonical JSON and its hash
+    pub fn canonical(&self) -> Result<SignDocResult, JsError> {
+        let doc = serde_json::to_string(self)?;
+        let hash = Hash::sha256(doc.as_bytes()).to_string();
+
+        Ok(SignDocResult { doc, hash })
+    }
+}
+
+/// Returns a canonical, human-readable JSON of what is being signed and its sha256 hash
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized tx
+/// * `wasm_hashes` - wasm hashes used to resolve tx types
+/// * `genesis_hash` - optional genesis hash the tx is bound to
+///
+/// # Errors
+///
+/// Returns an error if the tx can't be deserialized or is not a wrapper tx
+#[wasm_bindgen]
+pub fn get_sign_doc(
+    tx_bytes: &[u8],
+    wasm_hashes: JsValue,
+    genesis_hash: Option<String>,
+) -> Result<JsValue, JsError> {
+    let tx: tx::Tx = borsh::from_slice(tx_bytes)?;
+    let wasm_hashes: Vec<WasmHash> = wasm_hashes.into_serde()?;
+    let sign_doc = SignDoc::from_tx(&tx, &wasm_hashes)?
+        .with_genesis_hash(genesis_hash.map(|hash| hash.to_uppercase()));
+
+    to_js_result(sign_doc.canonical()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn sign_doc(amount: &str) -> SignDoc {
+        SignDoc {
+            chain_id: String::from("namada-test"),
+            genesis_hash: None,
+            expiration: None,
+            fee: SignDocFee {
+                token: String::from("tnam1"),
+                amount_per_gas_unit: String::from("0.000001"),
+                gas_limit: String::from("50000"),
+                payer: String::from("tpknam1"),
+            },
+            commitments: vec![SignDocCommitment {
+                tx_type: String::from("bond"),
+                hash: String::from("hash"),
+                memo: None,
+                data: serde_json::json!({ "amount": amount }),
+            }],
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn sign_doc_hash_is_deterministic() {
+        let first = sign_doc("1").canonical().unwrap();
+        let second = sign_doc("1").canonical().unwrap();
+        let other = sign_doc("2").canonical().unwrap();
+
+        assert_eq!(first.doc, second.doc);
+        assert_eq!(first.hash, second.hash);
+        assert_ne!(first.hash, other.hash);
+    }
+
+    #[wasm_bindgen_test]
+    fn genesis_hash_changes_sign_doc_hash() {
+        let unbound = sign_doc("1").canonical().unwrap();
+        let bound = sign_doc("1")
+            .with_genesis_hash(Some(String::from("ABCD")))
+            .canonical()
+            .unwrap();
+
+        assert!(!unbound.doc.contains("genesis_hash"));
+        assert!(bound.doc.contains("\"genesis_hash\":\"ABCD\""));
+        assert_ne!(unbound.hash, bound.hash);
+    }
+}
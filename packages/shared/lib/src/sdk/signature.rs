@@ -1,12 +1,13 @@
-use namada_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use namada_sdk::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_sdk::{
     key::common::{PublicKey, Signature},
     tx::{CompressedAuthorization, Section, Signer, Tx},
 };
 use std::collections::BTreeMap;
+use tsify::Tsify;
 use wasm_bindgen::JsError;
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct SignatureMsg {
     pub pubkey: Vec<u8>,
@@ -46,3 +47,34 @@ pub fn construct_signature_section(
 
     Ok(Section::Authorization(compressed_signature.expand(tx)))
 }
+
+/// Appends raw and wrapper signature sections described by `sig_msg` to the tx
+///
+/// # Arguments
+///
+/// * `tx` - A proto::Tx
+/// * `sig_msg` - signatures with the corresponding section indices
+///
+/// # Errors
+///
+/// Returns JsError if the signature sections can't be constructed
+pub fn append_signature(tx: &mut Tx, sig_msg: SignatureMsg) -> Result<(), JsError> {
+    let SignatureMsg {
+        pubkey,
+        raw_indices,
+        raw_signature,
+        wrapper_indices,
+        wrapper_signature,
+    } = sig_msg;
+
+    let raw_sig_section = construct_signature_section(&pubkey, &raw_indices, &raw_signature, tx)?;
+    tx.add_section(raw_sig_section);
+
+    let wrapper_sig_section =
+        construct_signature_section(&pubkey, &wrapper_indices, &wrapper_signature, tx)?;
+    tx.add_section(wrapper_sig_section);
+
+    tx.protocol_filter();
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Signing requirements - who has to sign a tx and who pays its fee, so multi-party coordination
+//! tools can collect the signatures of all parties before the tx is built for signing.
+use namada_sdk::address::Address;
+use namada_sdk::signing::SigningTxData;
+use namada_sdk::tx::data::WrapperTx;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::types::amount::StringAmount;
+
+/// Keys of an account an inner tx has to be signed with
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct SignerSet {
+    // Account the inner tx is signed for, None if only the fee payer signs
+    pub owner: Option<String>,
+    // Keys the SDK would sign with
+    pub public_keys: Vec<String>,
+    // Number of signatures the account requires
+    pub threshold: u8,
+    // All keys of the account, in the index order of the signatures
+    pub account_public_keys: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct SigningRequirements {
+    pub signers: Vec<SignerSet>,
+    // Public key the wrapper has to be signed with
+    pub fee_payer: String,
+    pub fee_payer_address: String,
+    // True if the fee payer is none of the inner tx signers and signs the wrapper on its own
+    pub fee_payer_signs_separately: bool,
+    pub fee_token: String,
+    pub fee_amount_per_gas_unit: StringAmount,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub gas_limit: u64,
+    // Fee amount per gas unit times the gas limit
+    pub max_fee: Option<StringAmount>,
+}
+
+fn signer_set(signing_tx_data: &SigningTxData) -> SignerSet {
+    let mut account_public_keys: Vec<(u8, String)> = signing_tx_data
+        .account_public_keys_map
+        .iter()
+        .flat_map(|pk_map| pk_map.pk_to_idx.iter())
+        .map(|(pk, idx)| (*idx, pk.to_string()))
+        .collect();
+    account_public_keys.sort();
+
+    SignerSet {
+        owner: signing_tx_data.owner.as_ref().map(Address::to_string),
+        public_keys: signing_tx_data
+            .public_keys
+            .iter()
+            .map(|pk| pk.to_string())
+            .collect(),
+        threshold: signing_tx_data.threshold,
+        account_public_keys: account_public_keys.into_iter().map(|(_, pk)| pk).collect(),
+    }
+}
+
+/// Returns the signing requirements of a built tx
+///
+/// # Arguments
+///
+/// * `signing_tx_data` - signing data of the inner txs
+/// * `wrapper` - wrapper header of the tx
+pub fn requirements(signing_tx_data: &[SigningTxData], wrapper: &WrapperTx) -> SigningRequirements {
+    let signers: Vec<SignerSet> = signing_tx_data.iter().map(signer_set).collect();
+    let fee_payer = wrapper.pk.to_string();
+    let fee_payer_signs_separately = !signers
+        .iter()
+        .any(|signers| signers.public_keys.contains(&fee_payer));
+
+    SigningRequirements {
+        signers,
+        fee_payer_address: Address::from(&wrapper.pk).to_string(),
+        fee_payer,
+        fee_payer_signs_separately,
+        fee_token: wrapper.fee.token.to_string(),
+        fee_amount_per_gas_unit: StringAmount::from(wrapper.fee.amount_per_gas_unit),
+        gas_limit: u64::from(wrapper.gas_limit),
+        max_fee: wrapper.get_tx_fee().ok().map(StringAmount::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use namada_sdk::account::AccountPublicKeysMap;
+    use namada_sdk::key::{common, ed25519, RefTo, SigScheme};
+    use namada_sdk::token::DenominatedAmount;
+    use namada_sdk::tx::data::{Fee, GasLimit};
+    use rand::rngs::OsRng;
+    use wasm_bindgen_test::*;
+
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    fn public_key() -> common::PublicKey {
+        common::SecretKey::Ed25519(ed25519::SigScheme::generate(&mut OsRng)).ref_to()
+    }
+
+    #[wasm_bindgen_test]
+    fn lists_signers_and_fee_payer() {
+        let (alice, bob, payer) = (public_key(), public_key(), public_key());
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount_per_gas_unit: DenominatedAmount::from_str("0.000001").unwrap(),
+                token: Address::from_str(NAM).unwrap(),
+            },
+            payer.clone(),
+            GasLimit::from(50_000),
+        );
+        let signing_tx_data = SigningTxData {
+            owner: Some(Address::from(&alice)),
+            public_keys: vec![alice.clone()],
+            threshold: 2,
+            account_public_keys_map: Some(AccountPublicKeysMap::from_iter(vec![
+                alice.clone(),
+                bob.clone(),
+            ])),
+            fee_payer: payer.clone(),
+            shielded_hash: None,
+        };
+
+        let required = requirements(&[signing_tx_data], &wrapper);
+        assert_eq!(required.signers[0].threshold, 2);
+        assert_eq!(required.signers[0].account_public_keys.len(), 2);
+        assert_eq!(required.fee_payer, payer.to_string());
+        assert!(required.fee_payer_signs_separately);
+        assert_eq!(required.gas_limit, 50_000);
+    }
+}
@@ -0,0 +1,128 @@
+//! Source-of-funds selection for users who split funds across transparent accounts. Sources are
+//! drained in priority order until the requested amount is met, each used source becoming one
+//! inner transfer of the batch. The fee is kept in the balance of the source paying it.
+use namada_sdk::address::Address;
+use namada_sdk::token::Amount;
+use wasm_bindgen::JsError;
+
+/// Picks the amount to pull from each source
+///
+/// # Arguments
+///
+/// * `balances` - (source, balance) pairs in priority order
+/// * `amount` - total amount to transfer
+/// * `fee_reserve` - fee payer and fee if the fee is paid in the transferred token
+///
+/// # Errors
+///
+/// Returns an error if the sources together don't hold the amount and the fee
+pub fn select_sources(
+    balances: Vec<(Address, Amount)>,
+    amount: Amount,
+    fee_reserve: Option<(Address, Amount)>,
+) -> Result<Vec<(Address, Amount)>, JsError> {
+    let mut remaining = amount;
+    let mut parts = vec![];
+
+    for (source, balance) in balances {
+        if remaining.is_zero() {
+            break;
+        }
+        let balance = match &fee_reserve {
+            Some((payer, fee)) if *payer == source => balance.checked_sub(*fee).unwrap_or_default(),
+            _ => balance,
+        };
+        if balance.is_zero() {
+            continue;
+        }
+        let pulled = std::cmp::min(balance, remaining);
+        remaining = remaining
+            .checked_sub(pulled)
+            .expect("Pulled amount should not exceed the remaining amount");
+        parts.push((source, pulled));
+    }
+
+    if !remaining.is_zero() {
+        return Err(JsError::new(&format!(
+            "Insufficient balance in sources, missing {} base units",
+            remaining
+        )));
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wasm_bindgen_test::*;
+
+    fn alice() -> Address {
+        Address::from_str("tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e").unwrap()
+    }
+
+    fn bob() -> Address {
+        Address::from_str("tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp").unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn pulls_sources_in_order() {
+        let balances = vec![
+            (alice(), Amount::from_u64(30)),
+            (bob(), Amount::from_u64(100)),
+        ];
+
+        assert_eq!(
+            select_sources(balances, Amount::from_u64(50), None).unwrap(),
+            vec![
+                (alice(), Amount::from_u64(30)),
+                (bob(), Amount::from_u64(20)),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn skips_unused_sources() {
+        let balances = vec![(alice(), Amount::zero()), (bob(), Amount::from_u64(100))];
+
+        assert_eq!(
+            select_sources(balances, Amount::from_u64(50), None).unwrap(),
+            vec![(bob(), Amount::from_u64(50))]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn keeps_fee_in_payer_balance() {
+        let balances = vec![
+            (alice(), Amount::from_u64(30)),
+            (bob(), Amount::from_u64(100)),
+        ];
+
+        assert_eq!(
+            select_sources(
+                balances.clone(),
+                Amount::from_u64(50),
+                Some((alice(), Amount::from_u64(10)))
+            )
+            .unwrap(),
+            vec![
+                (alice(), Amount::from_u64(20)),
+                (bob(), Amount::from_u64(30)),
+            ]
+        );
+        assert!(select_sources(
+            balances,
+            Amount::from_u64(130),
+            Some((bob(), Amount::from_u64(1)))
+        )
+        .is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_insufficient_balance() {
+        let balances = vec![(alice(), Amount::from_u64(30))];
+
+        assert!(select_sources(balances, Amount::from_u64(50), None).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+//! Sponsored txs - the wrapper fee is paid by a sponsor instead of the user. The user signs only
+//! the inner tx, the sponsor then signs the wrapper, so accounts without any balance can submit
+//! their first txs.
+use namada_sdk::key::common::PublicKey;
+use namada_sdk::tx::{data::TxType, Tx};
+use wasm_bindgen::JsError;
+
+/// Makes the sponsor the fee payer of the wrapper. Signatures of the wrapper become invalid,
+/// signatures of the inner tx don't sign the wrapper header and stay valid.
+pub fn set_fee_payer(tx: &mut Tx, sponsor: PublicKey) -> Result<(), JsError> {
+    match &mut tx.header.tx_type {
+        TxType::Wrapper(wrapper) => {
+            wrapper.pk = sponsor;
+            Ok(())
+        }
+        _ => Err(JsError::new("Invalid transaction type!")),
+    }
+}
+
+/// Ensures the sponsor is the fee payer of the wrapper
+pub fn ensure_fee_payer(tx: &Tx, sponsor: &PublicKey) -> Result<(), JsError> {
+    match &tx.header.tx_type {
+        TxType::Wrapper(wrapper) if &wrapper.pk == sponsor => Ok(()),
+        TxType::Wrapper(wrapper) => Err(JsError::new(&format!(
+            "Fee payer is {}, not the sponsor {}",
+            wrapper.pk, sponsor
+        ))),
+        _ => Err(JsError::new("Invalid transaction type!")),
+    }
+}
@@ -0,0 +1,121 @@
+//! Concurrent submission of signed txs. Txs of different fee payers don't depend on each other
+//! and are broadcast concurrently, while txs of the same fee payer are broadcast one after the
+//! other in the given order, as each of them changes the balance the next one pays fees from.
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+#[derive(Debug, Deserialize, Tsify)]
+pub struct SubmitRequest {
+    // Borsh serialized signed tx
+    pub tx_bytes: Vec<u8>,
+    // Borsh serialized WrapperTxMsg
+    pub wrapper_tx_msg: Vec<u8>,
+}
+
+/// Outcome of submitting one of the txs, `response` is the Borsh serialized TxResponse if the tx
+/// was applied, `error` is set otherwise
+#[derive(Debug, Serialize, Tsify)]
+pub struct SubmitResult {
+    pub index: u32,
+    pub response: Option<Vec<u8>>,
+    pub error: Option<String>,
+}
+
+impl SubmitResult {
+    pub fn new(index: usize, result: Result<Vec<u8>, String>) -> SubmitResult {
+        let (response, error) = match result {
+            Ok(response) => (Some(response), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        SubmitResult {
+            index: index as u32,
+            response,
+            error,
+        }
+    }
+}
+
+/// Groups indices of the txs by their fee payer, keeping the order within each group. Txs
+/// without a fee payer, e.g. ones that can't be decoded, get a group of their own.
+pub fn group_by_fee_payer(fee_payers: &[Option<String>]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<(Option<&String>, Vec<usize>)> = vec![];
+    for (index, fee_payer) in fee_payers.iter().enumerate() {
+        let group = fee_payer
+            .as_ref()
+            .and_then(|fee_payer| groups.iter().position(|(key, _)| *key == Some(fee_payer)));
+        match group {
+            Some(group) => groups[group].1.push(index),
+            None => groups.push((fee_payer.as_ref(), vec![index])),
+        }
+    }
+
+    groups.into_iter().map(|(_, indices)| indices).collect()
+}
+
+/// Polls all futures concurrently on the current task and returns their outputs in order
+pub async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<Pin<Box<F>>> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+
+    std::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (fut, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_some() {
+                continue;
+            }
+            match fut.as_mut().poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => pending = true,
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+
+    outputs
+        .into_iter()
+        .map(|output| output.expect("Future to be ready"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn groups_txs_by_fee_payer() {
+        let alice = Some(String::from("alice"));
+        let bob = Some(String::from("bob"));
+        let fee_payers = vec![alice.clone(), bob, None, alice, None];
+
+        assert_eq!(
+            group_by_fee_payer(&fee_payers),
+            vec![vec![0, 3], vec![1], vec![2], vec![4]]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn joins_in_order() {
+        let outputs = join_all(vec![
+            Box::pin(async {
+                crate::utils::sleep(20).await.unwrap();
+                1
+            }) as Pin<Box<dyn Future<Output = u32>>>,
+            Box::pin(async { 2 }),
+        ])
+        .await;
+
+        assert_eq!(outputs, vec![1, 2]);
+    }
+}
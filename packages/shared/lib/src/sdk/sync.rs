@@ -0,0 +1,383 @@
+//! Cloud sync - wallet metadata shared across the devices of a user through storage the user
+//! picks, e.g. a WebDAV server or an S3 bucket. Blobs are encrypted before they leave the device
+//! with a key derived from a secret only the devices of the user know, so the storage provider
+//! sees names and sizes, never the content.
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use zeroize::Zeroizing;
+
+use crate::rpc_client::{with_timeout, Headers};
+
+const SALT: &[u8] = b"namada-interface/sync/v1";
+const INFO: &[u8] = b"sync blob key";
+const NONCE_LEN: usize = 24;
+const NAME_PLACEHOLDER: &str = "{name}";
+// Blob listing the names of the other blobs, S3 presigned URLs can't list a bucket
+const INDEX_NAME: &str = "index";
+// Attempts to update the index while other devices change it
+const MAX_INDEX_ATTEMPTS: usize = 5;
+// Blobs of the metadata synced by the Sdk itself
+pub const ANNOTATIONS_BLOB: &str = "annotations";
+pub const NAMES_BLOB: &str = "names";
+// Public and viewing keys, only synced when asked for
+pub const WALLET_BLOB: &str = "wallet";
+// Secrets shorter than this are most likely a mistake, e.g. an empty PRF output
+pub const MIN_SECRET_LEN: usize = 32;
+pub const MAX_NAME_LEN: usize = 64;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SyncError {
+    #[error("Sync secret must be at least {MIN_SECRET_LEN} bytes, got {0}")]
+    SecretTooShort(usize),
+    #[error("Invalid blob name: {0}")]
+    InvalidName(String),
+    #[error("Blob {0} can't be decrypted, it was changed or encrypted with another secret")]
+    Decryption(String),
+    #[error("Sync index was changed by other devices {MAX_INDEX_ATTEMPTS} times, try again")]
+    IndexConflict,
+}
+
+/// Storage of encrypted blobs. Implementations only move bytes, encryption is done by
+/// `EncryptedSync` before blobs are passed to the backend.
+pub trait SyncBackend {
+    async fn put(&self, name: &str, blob: Vec<u8>) -> Result<(), JsError>;
+
+    /// Returns None if no blob of the name was stored
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, JsError>;
+
+    /// Returns names of the stored blobs
+    async fn list(&self) -> Result<Vec<String>, JsError>;
+}
+
+/// Returns the name if it's safe to use as a path segment
+///
+/// # Errors
+///
+/// Returns an error if the name is empty, too long or has other than `[a-z0-9_-]` characters
+pub fn validate_name(name: &str) -> Result<&str, SyncError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+
+    if !valid || name == INDEX_NAME {
+        return Err(SyncError::InvalidName(name.to_string()));
+    }
+
+    Ok(name)
+}
+
+/// Encrypts blobs with XChaCha20-Poly1305. The name is authenticated with the blob, so the
+/// storage provider can't swap the blobs of two names.
+pub struct SyncCipher {
+    key: Zeroizing<[u8; 32]>,
+}
+
+impl SyncCipher {
+    /// Derives the blob key from the secret with HKDF-SHA256
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret is shorter than 32 bytes
+    pub fn new(secret: &[u8]) -> Result<SyncCipher, SyncError> {
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(SyncError::SecretTooShort(secret.len()));
+        }
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(Some(SALT), secret)
+            .expand(INFO, key.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+
+        Ok(SyncCipher { key })
+    }
+
+    /// Returns the random nonce followed by the ciphertext
+    pub fn encrypt(&self, name: &str, plaintext: &[u8]) -> Vec<u8> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(self.key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .expect("Encryption of in memory blobs doesn't fail");
+
+        [nonce.to_vec(), ciphertext].concat()
+    }
+
+    pub fn decrypt(&self, name: &str, blob: &[u8]) -> Result<Vec<u8>, SyncError> {
+        if blob.len() < NONCE_LEN {
+            return Err(SyncError::Decryption(name.to_string()));
+        }
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+
+        XChaCha20Poly1305::new(self.key.as_ref().into())
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| SyncError::Decryption(name.to_string()))
+    }
+}
+
+/// Backend encrypting blobs before they are stored
+pub struct EncryptedSync<B: SyncBackend> {
+    backend: B,
+    cipher: SyncCipher,
+}
+
+impl<B: SyncBackend> EncryptedSync<B> {
+    pub fn new(backend: B, cipher: SyncCipher) -> EncryptedSync<B> {
+        EncryptedSync { backend, cipher }
+    }
+
+    pub async fn put(&self, name: &str, plaintext: &[u8]) -> Result<(), JsError> {
+        let name = validate_name(name)?;
+
+        self.backend
+            .put(name, self.cipher.encrypt(name, plaintext))
+            .await
+    }
+
+    pub async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, JsError> {
+        let name = validate_name(name)?;
+
+        match self.backend.get(name).await? {
+            Some(blob) => Ok(Some(self.cipher.decrypt(name, &blob)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<String>, JsError> {
+        self.backend.list().await
+    }
+}
+
+/// Stores blobs with HTTP PUT and GET, which WebDAV servers and S3 presigned URLs both accept.
+/// URLs are made from a template with a `{name}` placeholder, e.g.
+/// "https://dav.example/namada/{name}", or returned by the presign callback. The index is
+/// written with `If-Match` and `If-None-Match`, so the storage has to return ETags.
+pub struct HttpSyncBackend {
+    url_template: Option<String>,
+    // Called with the blob name and "GET" or "PUT", returns the URL or a promise of it
+    presign: Option<js_sys::Function>,
+    client: reqwest::Client,
+    // Timeout of the RPC client, requests to the storage are aborted after it too
+    timeout_ms: Arc<AtomicU32>,
+}
+
+impl HttpSyncBackend {
+    /// # Errors
+    ///
+    /// Returns an error if neither the template nor the callback is given, the template has no
+    /// `{name}` placeholder or a header is invalid
+    pub fn new(
+        url_template: Option<String>,
+        presign: Option<js_sys::Function>,
+        headers: Headers,
+        timeout_ms: Arc<AtomicU32>,
+    ) -> Result<HttpSyncBackend, JsError> {
+        match (&url_template, &presign) {
+            (None, None) => {
+                return Err(JsError::new(
+                    "Sync backend needs a URL template or a presign callback",
+                ))
+            }
+            (Some(url_template), _) if !url_template.contains(NAME_PLACEHOLDER) => {
+                return Err(JsError::new(&format!(
+                    "Sync URL template has no {}",
+                    NAME_PLACEHOLDER
+                )))
+            }
+            _ => {}
+        }
+
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        for (name, value) in headers.iter() {
+            default_headers.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes())?,
+                reqwest::header::HeaderValue::from_str(value)?,
+            );
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(default_headers)
+            .build()?;
+
+        Ok(HttpSyncBackend {
+            url_template,
+            presign,
+            client,
+            timeout_ms,
+        })
+    }
+
+    async fn url(&self, name: &str, method: &str) -> Result<String, JsError> {
+        let Some(presign) = &self.presign else {
+            let url_template = self
+                .url_template
+                .as_ref()
+                .expect("Checked by the constructor");
+            return Ok(url_template.replace(NAME_PLACEHOLDER, name));
+        };
+
+        let result = presign
+            .call2(&JsValue::NULL, &JsValue::from(name), &JsValue::from(method))
+            .map_err(|_| JsError::new("Presign callback failed"))?;
+        JsFuture::from(js_sys::Promise::resolve(&result))
+            .await
+            .map_err(|_| JsError::new("Presign callback failed"))?
+            .as_string()
+            .ok_or_else(|| JsError::new("Presign callback has to return a URL"))
+    }
+
+    fn timeout(&self) -> u32 {
+        self.timeout_ms.load(Ordering::Relaxed)
+    }
+
+    /// Stores the blob. With a precondition the blob is only stored if its version still
+    /// matches, returns false if it doesn't.
+    async fn put_blob(
+        &self,
+        name: &str,
+        blob: Vec<u8>,
+        precondition: Option<Precondition>,
+    ) -> Result<bool, JsError> {
+        let mut request = self.client.put(self.url(name, "PUT").await?).body(blob);
+        request = match precondition {
+            Some(Precondition::Matches(etag)) => request.header(reqwest::header::IF_MATCH, etag),
+            Some(Precondition::Absent) => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+            None => request,
+        };
+        let response = with_timeout(request.send(), self.timeout()).await??;
+        if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+
+        Ok(true)
+    }
+
+    /// Returns the blob and its ETag, if the storage returns one
+    async fn get_blob(&self, name: &str) -> Result<Option<(Vec<u8>, Option<String>)>, JsError> {
+        let request = self.client.get(self.url(name, "GET").await?);
+        // The body is read within the same timeout
+        let fetch = async {
+            let response = request.send().await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            let response = response.error_for_status()?;
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(String::from);
+            Ok::<_, reqwest::Error>(Some((response.bytes().await?.to_vec(), etag)))
+        };
+
+        Ok(with_timeout(fetch, self.timeout()).await??)
+    }
+
+    async fn index(&self) -> Result<(BTreeSet<String>, Precondition), JsError> {
+        match self.get_blob(INDEX_NAME).await? {
+            Some((index, Some(etag))) => {
+                Ok((serde_json::from_slice(&index)?, Precondition::Matches(etag)))
+            }
+            Some(_) => Err(JsError::new(
+                "Sync storage returns no ETag, the index can't be updated safely",
+            )),
+            None => Ok((BTreeSet::new(), Precondition::Absent)),
+        }
+    }
+}
+
+/// Condition on the stored version of a blob for a write to succeed
+enum Precondition {
+    // Blob still has the ETag it was read with
+    Matches(String),
+    // Blob doesn't exist yet
+    Absent,
+}
+
+impl SyncBackend for HttpSyncBackend {
+    async fn put(&self, name: &str, blob: Vec<u8>) -> Result<(), JsError> {
+        self.put_blob(name, blob, None).await?;
+
+        // Other devices may add names meanwhile, the index is only replaced if it's unchanged
+        // since it was read, otherwise it's read again
+        for _ in 0..MAX_INDEX_ATTEMPTS {
+            let (mut names, precondition) = self.index().await?;
+            if !names.insert(name.to_string()) {
+                return Ok(());
+            }
+            if self
+                .put_blob(INDEX_NAME, serde_json::to_vec(&names)?, Some(precondition))
+                .await?
+            {
+                return Ok(());
+            }
+        }
+
+        Err(SyncError::IndexConflict.into())
+    }
+
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>, JsError> {
+        Ok(self.get_blob(name).await?.map(|(blob, _)| blob))
+    }
+
+    async fn list(&self) -> Result<Vec<String>, JsError> {
+        match self.get_blob(INDEX_NAME).await? {
+            Some((index, _)) => Ok(serde_json::from_slice(&index)?),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn encrypts_blobs_bound_to_names() {
+        let cipher = SyncCipher::new(&[7; 32]).unwrap();
+        let blob = cipher.encrypt("annotations", b"labels");
+
+        assert_eq!(cipher.decrypt("annotations", &blob).unwrap(), b"labels");
+        assert!(cipher.decrypt("names", &blob).is_err());
+        assert!(SyncCipher::new(&[8; 32])
+            .unwrap()
+            .decrypt("annotations", &blob)
+            .is_err());
+        assert_eq!(
+            SyncCipher::new(&[7; 16]).err(),
+            Some(SyncError::SecretTooShort(16))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn validates_blob_names() {
+        assert!(validate_name("settings").is_ok());
+        assert!(validate_name("../keys").is_err());
+        assert!(validate_name(INDEX_NAME).is_err());
+    }
+}
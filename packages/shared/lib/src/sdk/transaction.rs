@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
+
 use namada_sdk::borsh::BorshSerializeExt;
-use namada_sdk::token::Transfer;
+use namada_sdk::token::{Account, DenominatedAmount, Transfer};
 
 use namada_sdk::governance::VoteProposalData;
 use namada_sdk::ibc::core::channel::types::timeout::TimeoutHeight;
@@ -9,6 +11,7 @@ use namada_sdk::{
     borsh::{self, BorshDeserialize},
     key::common::PublicKey,
 };
+use serde_json::json;
 use wasm_bindgen::JsError;
 
 use crate::sdk::{
@@ -34,36 +37,133 @@ pub enum TransactionKind {
 }
 
 impl TransactionKind {
-    pub fn from(tx_type: TxType, data: &[u8]) -> Self {
-        match tx_type {
-            TxType::Transfer => TransactionKind::Transfer(
-                Transfer::try_from_slice(data).expect("Cannot deserialize TransparentTransfer"),
-            ),
-            TxType::Bond => {
-                TransactionKind::Bond(Bond::try_from_slice(data).expect("Cannot deserialize Bond"))
+    /// Decodes the data of an inner tx, failing instead of panicking on malformed data, e.g. of
+    /// txs read from the chain
+    pub fn decode(tx_type: TxType, data: &[u8]) -> Result<Self, std::io::Error> {
+        Ok(match tx_type {
+            TxType::Transfer => TransactionKind::Transfer(Transfer::try_from_slice(data)?),
+            TxType::Bond => TransactionKind::Bond(Bond::try_from_slice(data)?),
+            TxType::Redelegate => {
+                TransactionKind::Redelegation(Redelegation::try_from_slice(data)?)
+            }
+            TxType::Unbond => TransactionKind::Unbond(Unbond::try_from_slice(data)?),
+            TxType::Withdraw => TransactionKind::Withdraw(Withdraw::try_from_slice(data)?),
+            TxType::VoteProposal => {
+                TransactionKind::ProposalVote(VoteProposalData::try_from_slice(data)?)
+            }
+            TxType::ClaimRewards => {
+                TransactionKind::ClaimRewards(ClaimRewards::try_from_slice(data)?)
             }
-            TxType::Redelegate => TransactionKind::Redelegation(
-                Redelegation::try_from_slice(data).expect("Cannot deserialize Redelegation"),
-            ),
-            TxType::Unbond => TransactionKind::Unbond(
-                Unbond::try_from_slice(data).expect("Cannot deserialize Unbond"),
-            ),
-            TxType::Withdraw => TransactionKind::Withdraw(
-                Withdraw::try_from_slice(data).expect("Cannot deserialize Withdraw"),
-            ),
-            TxType::VoteProposal => TransactionKind::ProposalVote(
-                VoteProposalData::try_from_slice(data).expect("Cannot deserialize VoteProposal"),
-            ),
-            TxType::ClaimRewards => TransactionKind::ClaimRewards(
-                ClaimRewards::try_from_slice(data).expect("Cannot deserialize ClaimRewards"),
-            ),
-            TxType::RevealPK => TransactionKind::RevealPk(
-                PublicKey::try_from_slice(data).expect("Cannot deserialize PublicKey"),
-            ),
-            TxType::IBCTransfer => TransactionKind::IbcTransfer(
-                MsgTransfer::try_from_slice(data).expect("Cannot deserialize MsgTransfer"),
-            ),
+            TxType::RevealPK => TransactionKind::RevealPk(PublicKey::try_from_slice(data)?),
+            TxType::IBCTransfer => TransactionKind::IbcTransfer(MsgTransfer::try_from_slice(data)?),
             _ => TransactionKind::Unknown,
+        })
+    }
+
+    // Returns addresses receiving funds, used for address screening
+    pub fn recipients(&self) -> Vec<String> {
+        match self {
+            TransactionKind::Transfer(Transfer { targets, .. }) => targets
+                .keys()
+                .map(|account| account.owner.to_string())
+                .collect(),
+            TransactionKind::IbcTransfer(MsgTransfer { message, .. }) => {
+                vec![message.packet_data.receiver.to_string()]
+            }
+            _ => vec![],
+        }
+    }
+
+    // Returns human-readable representation of the transaction arguments, used in sign docs
+    pub fn to_sign_doc_data(&self) -> serde_json::Value {
+        match self {
+            TransactionKind::Bond(Bond {
+                amount,
+                validator,
+                source,
+            }) => json!({
+                "source": source.as_ref().map(|addr| addr.to_string()),
+                "validator": validator.to_string(),
+                "amount": amount.to_string_native(),
+            }),
+            TransactionKind::Unbond(Unbond {
+                amount,
+                validator,
+                source,
+            }) => json!({
+                "source": source.as_ref().map(|addr| addr.to_string()),
+                "validator": validator.to_string(),
+                "amount": amount.to_string_native(),
+            }),
+            TransactionKind::Withdraw(Withdraw { validator, source }) => json!({
+                "source": source.as_ref().map(|addr| addr.to_string()),
+                "validator": validator.to_string(),
+            }),
+            TransactionKind::Redelegation(Redelegation {
+                owner,
+                src_validator,
+                dest_validator,
+                amount,
+            }) => json!({
+                "owner": owner.to_string(),
+                "src_validator": src_validator.to_string(),
+                "dest_validator": dest_validator.to_string(),
+                "amount": amount.to_string_native(),
+            }),
+            TransactionKind::RevealPk(public_key) => json!({
+                "public_key": public_key.to_string(),
+            }),
+            TransactionKind::Transfer(Transfer {
+                sources,
+                targets,
+                shielded_section_hash,
+            }) => {
+                let to_entries = |accounts: &BTreeMap<Account, DenominatedAmount>| {
+                    accounts
+                        .iter()
+                        .map(|(account, amount)| {
+                            json!({
+                                "owner": account.owner.to_string(),
+                                "token": account.token.to_string(),
+                                "amount": amount.to_string(),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                };
+
+                json!({
+                    "sources": to_entries(sources),
+                    "targets": to_entries(targets),
+                    "shielded_section_hash": shielded_section_hash.map(|hash| hash.to_string()),
+                })
+            }
+            TransactionKind::ProposalVote(VoteProposalData { id, vote, voter }) => json!({
+                "id": id,
+                "vote": vote.to_string(),
+                "voter": voter.to_string(),
+            }),
+            TransactionKind::ClaimRewards(ClaimRewards { validator, source }) => json!({
+                "source": source.as_ref().map(|addr| addr.to_string()),
+                "validator": validator.to_string(),
+            }),
+            TransactionKind::IbcTransfer(MsgTransfer { message, .. }) => {
+                let timeout_height = match message.timeout_height_on_b {
+                    TimeoutHeight::At(height) => Some(height.revision_height()),
+                    TimeoutHeight::Never => None,
+                };
+
+                json!({
+                    "sender": message.packet_data.sender.to_string(),
+                    "receiver": message.packet_data.receiver.to_string(),
+                    "token": message.packet_data.token.denom.to_string(),
+                    "amount": message.packet_data.token.amount.to_string(),
+                    "port_id": message.port_id_on_a.to_string(),
+                    "channel_id": message.chan_id_on_a.to_string(),
+                    "timeout_height": timeout_height,
+                    "memo": message.packet_data.memo.to_string(),
+                })
+            }
+            TransactionKind::Unknown => serde_json::Value::Null,
         }
     }
 
@@ -84,7 +184,7 @@ impl TransactionKind {
                 let bond = BondMsg::new(
                     source.clone().unwrap().to_string(),
                     validator.to_string(),
-                    amount.native_denominated().to_string(),
+                    amount.native_denominated().into(),
                 );
                 borsh::to_vec(&bond)?
             }
@@ -102,7 +202,7 @@ impl TransactionKind {
                 let unbond = UnbondMsg::new(
                     source.clone().unwrap().to_string(),
                     validator.to_string(),
-                    amount.native_denominated().to_string(),
+                    amount.native_denominated().into(),
                 );
                 borsh::to_vec(&unbond)?
             }
@@ -129,7 +229,7 @@ impl TransactionKind {
                     owner.to_string(),
                     src_validator.to_string(),
                     dest_validator.to_string(),
-                    amount.native_denominated().to_string(),
+                    amount.native_denominated().into(),
                 );
                 borsh::to_vec(&redelegation)?
             }
@@ -159,14 +259,14 @@ impl TransactionKind {
                 for (source, amount) in sources {
                     let owner = source.owner.to_string();
                     let token = source.token.to_string();
-                    let amount = amount.amount().native_denominated().to_string();
+                    let amount = amount.amount().native_denominated().into();
                     sources_data.push(TransferDataMsg::new(owner, token, amount))
                 }
 
                 for (target, amount) in targets {
                     let owner = target.owner.to_string();
                     let token = target.token.to_string();
-                    let amount = amount.amount().native_denominated().to_string();
+                    let amount = amount.amount().native_denominated().into();
                     targets_data.push(TransferDataMsg::new(owner, token, amount))
                 }
 
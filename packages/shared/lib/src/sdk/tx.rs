@@ -2,10 +2,11 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use gloo_utils::format::JsValueSerdeExt;
-use namada_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use namada_sdk::borsh::{self, BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_sdk::masp_primitives::transaction::components::sapling::builder::StoredBuildParams;
 use namada_sdk::masp_primitives::zip32::ExtendedFullViewingKey;
 use namada_sdk::signing::SigningTxData;
+use namada_sdk::token::Amount;
 use namada_sdk::tx::data::compute_inner_tx_hash;
 use namada_sdk::tx::either::Either;
 use namada_sdk::tx::{
@@ -14,14 +15,18 @@ use namada_sdk::tx::{
 };
 use namada_sdk::uint::Uint;
 use namada_sdk::{address::Address, key::common::PublicKey};
+use serde::Serialize;
+use tsify::Tsify;
 use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
 
 use super::args::WrapperTxMsg;
-use crate::sdk::transaction;
+use crate::sdk::{custom_tx, transaction};
+use crate::types::amount::StringAmount;
 use crate::types::query::WasmHash;
+use crate::utils::to_js_result;
 
 #[wasm_bindgen]
-#[derive(BorshSerialize, BorshDeserialize, Copy, Clone, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Copy, Clone, Debug)]
 #[borsh(crate = "namada_sdk::borsh", use_discriminant = true)]
 pub enum TxType {
     Bond = 1,
@@ -35,9 +40,31 @@ pub enum TxType {
     Redelegate = 9,
     Batch = 10,
     ClaimRewards = 11,
+    MultiTransfer = 12,
+    Custom = 13,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+impl TxType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TxType::Bond => "bond",
+            TxType::Unbond => "unbond",
+            TxType::Withdraw => "withdraw",
+            TxType::Transfer => "transfer",
+            TxType::IBCTransfer => "ibc_transfer",
+            TxType::EthBridgeTransfer => "eth_bridge_transfer",
+            TxType::RevealPK => "reveal_pk",
+            TxType::VoteProposal => "vote_proposal",
+            TxType::Redelegate => "redelegate",
+            TxType::Batch => "batch",
+            TxType::ClaimRewards => "claim_rewards",
+            TxType::MultiTransfer => "multi_transfer",
+            TxType::Custom => "custom",
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct SigningData {
     owner: Option<String>,
@@ -150,7 +177,7 @@ impl MaspSigningData {
 }
 
 /// Serializable Tx for exported build functions
-#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Clone, Debug, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct Tx {
     args: WrapperTxMsg,
@@ -186,6 +213,17 @@ impl Tx {
         self.bytes.clone()
     }
 
+    /// Replaces the tx, e.g. after adding signatures, keeping its signing data
+    pub fn set_tx(&mut self, tx: &tx::Tx) -> Result<(), JsError> {
+        self.hash = tx
+            .wrapper_hash()
+            .ok_or_else(|| JsError::new("Tx is not a wrapper"))?
+            .to_string();
+        self.bytes = borsh::to_vec(tx)?;
+
+        Ok(())
+    }
+
     pub fn signing_tx_data(&self) -> Result<Vec<SigningTxData>, JsError> {
         let mut signing_tx_data: Vec<SigningTxData> = vec![];
         for sd in self.signing_data.clone().iter() {
@@ -208,6 +246,11 @@ impl Tx {
 #[wasm_bindgen]
 pub fn get_inner_tx_hashes(tx_bytes: &[u8]) -> Result<Vec<String>, JsError> {
     let nam_tx: tx::Tx = borsh::from_slice(tx_bytes)?;
+
+    Ok(inner_tx_hashes(&nam_tx))
+}
+
+fn inner_tx_hashes(nam_tx: &tx::Tx) -> Vec<String> {
     let hash = nam_tx.wrapper_hash();
     let cmts = nam_tx.commitments();
     let mut inner_tx_hashes: Vec<String> = vec![];
@@ -217,10 +260,142 @@ pub fn get_inner_tx_hashes(tx_bytes: &[u8]) -> Result<Vec<String>, JsError> {
         inner_tx_hashes.push(inner_tx_hash.to_string());
     }
 
-    Ok(inner_tx_hashes)
+    inner_tx_hashes
 }
 
-pub fn wasm_hash_to_tx_type(wasm_hash: &str, wasm_hashes: &Vec<WasmHash>) -> Option<TxType> {
+/// Hashes of a tx as computed by the node
+#[derive(Serialize, Tsify)]
+pub struct TxHashes {
+    /// Wrapper tx hash, the hash explorers list the tx under
+    wrapper_hash: String,
+    /// Hashes of the inner txs, in commitment order
+    inner_hashes: Vec<String>,
+}
+
+/// Computes the wrapper and inner tx hashes the node will report for the tx, so they can be shown
+/// before broadcasting. The hashes don't change when the tx is signed.
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized tx
+///
+/// # Errors
+///
+/// Returns an error if the tx can't be deserialized or is not a wrapper tx
+#[wasm_bindgen]
+pub fn compute_tx_hash(tx_bytes: &[u8]) -> Result<JsValue, JsError> {
+    let nam_tx: tx::Tx = borsh::from_slice(tx_bytes)?;
+    let wrapper_hash = nam_tx
+        .wrapper_hash()
+        .ok_or_else(|| JsError::new("Invalid transaction type!"))?;
+
+    to_js_result(TxHashes {
+        wrapper_hash: wrapper_hash.to_string(),
+        inner_hashes: inner_tx_hashes(&nam_tx),
+    })
+}
+
+/// Claim of staking rewards and their bond back to the validator, to be reviewed before signing
+#[derive(Serialize, Tsify)]
+pub struct CompoundPreview {
+    pub delegator: String,
+    pub validator: String,
+    /// Claimed and re-bonded amount of the native token
+    pub rewards: StringAmount,
+    /// Borsh serialized batch of the claim and the bond
+    pub tx: Vec<u8>,
+}
+
+#[derive(Serialize, Tsify)]
+pub struct StakingBatchItem {
+    pub validator: String,
+    pub amount: StringAmount,
+}
+
+/// Unbonds or withdrawals of every validator of the source, summarized before submitting
+#[derive(Serialize, Tsify)]
+pub struct StakingBatch {
+    pub source: String,
+    /// "unbond" or "withdraw"
+    pub kind: String,
+    pub items: Vec<StakingBatchItem>,
+    pub total: StringAmount,
+    /// Borsh serialized batch of the txs
+    pub tx: Vec<u8>,
+}
+
+impl StakingBatch {
+    /// Summarizes the amounts per validator of the batch
+    pub fn new(
+        source: String,
+        kind: &str,
+        amounts: Vec<(Address, Amount)>,
+        tx: Vec<u8>,
+    ) -> Result<StakingBatch, JsError> {
+        let total = amounts
+            .iter()
+            .try_fold(Amount::zero(), |total, (_, amount)| {
+                total.checked_add(*amount)
+            })
+            .ok_or_else(|| JsError::new("Overflow when summing staked amounts"))?;
+        let items = amounts
+            .into_iter()
+            .map(|(validator, amount)| StakingBatchItem {
+                validator: validator.to_string(),
+                amount: StringAmount::native(amount),
+            })
+            .collect();
+
+        Ok(StakingBatch {
+            source,
+            kind: kind.to_string(),
+            items,
+            total: StringAmount::native(total),
+            tx,
+        })
+    }
+}
+
+/// Signed tx encoded for broadcasting through any node, e.g. with curl
+#[derive(Serialize, Tsify)]
+pub struct ExportedTx {
+    /// Hex encoded tx bytes, for `broadcast_tx_sync?tx=0x<hex>`
+    hex: String,
+    /// Base64 encoded tx bytes, for the JSON-RPC `broadcast_tx_sync` method
+    base64: String,
+    /// Wrapper tx hash
+    hash: String,
+    chain_id: String,
+    expiration: Option<String>,
+}
+
+/// Encodes a signed tx together with its metadata, so it can be broadcast when the configured
+/// RPC is down
+///
+/// # Arguments
+///
+/// * `tx_bytes` - borsh serialized signed tx
+///
+/// # Errors
+///
+/// Returns an error if the tx can't be deserialized or is not a wrapper tx
+#[wasm_bindgen]
+pub fn export_signed_tx(tx_bytes: &[u8]) -> Result<JsValue, JsError> {
+    let nam_tx: tx::Tx = borsh::from_slice(tx_bytes)?;
+    let hash = nam_tx
+        .wrapper_hash()
+        .ok_or_else(|| JsError::new("Invalid transaction type!"))?;
+
+    to_js_result(ExportedTx {
+        hex: hex::encode(tx_bytes),
+        base64: String::from_utf8(subtle_encoding::base64::encode(tx_bytes))?,
+        hash: hash.to_string(),
+        chain_id: nam_tx.header().chain_id.to_string(),
+        expiration: nam_tx.header().expiration.map(|exp| exp.to_string()),
+    })
+}
+
+pub fn code_path_to_tx_type(code_path: &str) -> Option<TxType> {
     let type_map: HashMap<String, TxType> = HashMap::from([
         (TX_TRANSFER_WASM.to_string(), TxType::Transfer),
         (TX_BOND_WASM.to_string(), TxType::Bond),
@@ -233,12 +408,16 @@ pub fn wasm_hash_to_tx_type(wasm_hash: &str, wasm_hashes: &Vec<WasmHash>) -> Opt
         (TX_IBC_WASM.to_string(), TxType::IBCTransfer),
     ]);
 
+    type_map.get(code_path).copied()
+}
+
+pub fn wasm_hash_to_tx_type(wasm_hash: &str, wasm_hashes: &Vec<WasmHash>) -> Option<TxType> {
     for wh in wasm_hashes {
         if wh.hash() == wasm_hash {
-            let tx_type = type_map.get(&wh.path());
+            let tx_type = code_path_to_tx_type(&wh.path());
 
-            if let Some(tx_type) = tx_type {
-                return Some(*tx_type);
+            if tx_type.is_some() {
+                return tx_type;
             }
         }
     }
@@ -253,7 +432,7 @@ pub fn deserialize_tx(tx_bytes: Vec<u8>, wasm_hashes: JsValue) -> Result<Vec<u8>
     Ok(borsh::to_vec(&tx)?)
 }
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct Commitment {
     tx_type: TxType,
@@ -263,7 +442,7 @@ pub struct Commitment {
     memo: Option<String>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TxDetails {
     wrapper_tx: WrapperTxMsg,
@@ -277,12 +456,14 @@ impl TxDetails {
 
         match tx.header().tx_type {
             tx::data::TxType::Wrapper(wrapper) => {
-                let fee_amount = wrapper.fee.amount_per_gas_unit.to_string();
+                let fee_amount = wrapper.fee.amount_per_gas_unit.into();
                 let gas_limit = Uint::from(wrapper.gas_limit).to_string();
                 let token = wrapper.fee.token.to_string();
 
-                let wrapper_tx =
-                    WrapperTxMsg::new(token, fee_amount, gas_limit, chain_id, None, None, None);
+                let expiration = tx.header().expiration.map(|exp| exp.to_string());
+                let wrapper_tx = WrapperTxMsg::new(
+                    token, fee_amount, gas_limit, chain_id, None, None, None, expiration,
+                );
                 let mut commitments: Vec<Commitment> = vec![];
                 let wasm_hashes: Vec<WasmHash> = wasm_hashes.into_serde().unwrap();
 
@@ -307,7 +488,7 @@ impl TxDetails {
                         if tx_type.is_some() {
                             let tx_type = tx_type.unwrap();
                             let tx_data = tx.data(cmt).unwrap_or_default();
-                            let tx_kind = transaction::TransactionKind::from(tx_type, &tx_data);
+                            let tx_kind = transaction::TransactionKind::decode(tx_type, &tx_data)?;
                             let data = tx_kind.to_bytes()?;
 
                             commitments.push(Commitment {
@@ -317,6 +498,19 @@ impl TxDetails {
                                 data,
                                 memo,
                             });
+                        } else if let Some(custom_tx) =
+                            custom_tx::custom_tx_type_by_hash(&tx_code_id)
+                        {
+                            let tx_data = tx.data(cmt).unwrap_or_default();
+                            let data = custom_tx.decode(&tx_data)?.to_bytes()?;
+
+                            commitments.push(Commitment {
+                                tx_type: TxType::Custom,
+                                hash,
+                                tx_code_id,
+                                data,
+                                memo,
+                            });
                         }
                     }
                 }
@@ -332,7 +526,7 @@ impl TxDetails {
 }
 
 #[wasm_bindgen]
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct BatchTxResult {
     hash: String,
@@ -347,7 +541,7 @@ impl BatchTxResult {
 
 /// Serializable response for process_tx calls
 #[wasm_bindgen]
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct TxResponse {
     code: String,
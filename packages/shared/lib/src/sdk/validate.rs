@@ -0,0 +1,168 @@
+//! Input validation - sanitization of the strings passed by the UI. Applied by the msg decoders in
+//! `args` and the wallet methods, so malformed input is rejected with the name of the field before
+//! it reaches the signer.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::chain::ChainId;
+use namada_sdk::key::common::PublicKey;
+use namada_sdk::PaymentAddress;
+use serde::Serialize;
+use thiserror::Error;
+use tsify::Tsify;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+// Bech32m addresses and keys are shorter, the limits only stop oversized input early
+pub const MAX_ADDRESS_LEN: usize = 128;
+pub const MAX_PUBLIC_KEY_LEN: usize = 128;
+pub const MAX_CHAIN_ID_LEN: usize = 50;
+pub const MAX_IBC_ID_LEN: usize = 128;
+pub const MAX_ALIAS_LEN: usize = 64;
+pub const MAX_MEMO_LEN: usize = 1024;
+
+/// Invalid field of the input. Crosses to JS as an Error with the message
+/// "Invalid {field}: {reason}", which the SDK parses back into the fields.
+#[derive(Debug, Error, PartialEq, Serialize, Tsify)]
+#[error("Invalid {field}: {reason}")]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: impl ToString) -> FieldError {
+        FieldError {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+// Trims the value and checks it's not empty, too long or containing control characters
+fn bounded<'a>(field: &str, value: &'a str, max_len: usize) -> Result<&'a str, FieldError> {
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Err(FieldError::new(field, "value is empty"));
+    }
+    if value.chars().count() > max_len {
+        return Err(FieldError::new(
+            field,
+            format!("value is longer than {} characters", max_len),
+        ));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(FieldError::new(field, "value contains control characters"));
+    }
+
+    Ok(value)
+}
+
+/// Parses a trimmed value of at most `max_len` characters
+pub fn parse<T>(field: &str, value: &str, max_len: usize) -> Result<T, FieldError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = bounded(field, value, max_len)?;
+
+    T::from_str(value).map_err(|err| FieldError::new(field, err))
+}
+
+/// Parses a transparent address, verifying its bech32m checksum
+pub fn address(field: &str, value: &str) -> Result<Address, FieldError> {
+    parse(field, value, MAX_ADDRESS_LEN)
+}
+
+/// Parses a shielded payment address, verifying its bech32m checksum
+pub fn payment_address(field: &str, value: &str) -> Result<PaymentAddress, FieldError> {
+    parse(field, value, MAX_ADDRESS_LEN)
+}
+
+pub fn public_key(field: &str, value: &str) -> Result<PublicKey, FieldError> {
+    parse(field, value, MAX_PUBLIC_KEY_LEN)
+}
+
+/// Returns the trimmed value, e.g. an address on another chain which can't be parsed here
+pub fn string(field: &str, value: &str, max_len: usize) -> Result<String, FieldError> {
+    Ok(bounded(field, value, max_len)?.to_string())
+}
+
+pub fn chain_id(field: &str, value: &str) -> Result<ChainId, FieldError> {
+    let value = bounded(field, value, MAX_CHAIN_ID_LEN)?;
+
+    Ok(ChainId(value.to_string()))
+}
+
+/// Returns the NFC normalized alias, so visually equal aliases are stored once
+pub fn alias(field: &str, value: &str) -> Result<String, FieldError> {
+    let value: String = value.nfc().collect();
+
+    Ok(bounded(field, &value, MAX_ALIAS_LEN)?.to_string())
+}
+
+/// Checks the memo. Memos are signed as given, so unlike other fields they are not trimmed or
+/// normalized, input which would need it is rejected. Memos can span lines.
+pub fn memo(field: &str, value: &str) -> Result<String, FieldError> {
+    if value.chars().count() > MAX_MEMO_LEN {
+        return Err(FieldError::new(
+            field,
+            format!("value is longer than {} characters", MAX_MEMO_LEN),
+        ));
+    }
+    if value
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Err(FieldError::new(field, "value contains control characters"));
+    }
+    if value.trim() != value {
+        return Err(FieldError::new(
+            field,
+            "value has leading or trailing whitespace",
+        ));
+    }
+    if !is_nfc(value) {
+        return Err(FieldError::new(field, "value is not NFC normalized"));
+    }
+
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const ALICE: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    #[wasm_bindgen_test]
+    fn validates_addresses() {
+        let padded = format!("  {}\n", ALICE);
+        assert_eq!(address("source", &padded).unwrap().to_string(), ALICE);
+
+        // Last character changed, checksum doesn't match
+        let tampered = format!("{}f", &ALICE[..ALICE.len() - 1]);
+        let err = address("source", &tampered).unwrap_err();
+        assert_eq!(err.field, "source");
+
+        assert!(address("target", "").is_err());
+        assert!(address("target", &"a".repeat(MAX_ADDRESS_LEN + 1)).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn normalizes_aliases_and_checks_memos() {
+        // "é" as "e" followed by a combining acute accent
+        assert_eq!(alias("alias", " cafe\u{301} ").unwrap(), "caf\u{e9}");
+        assert!(alias("alias", "a\u{0}b").is_err());
+        assert!(alias("alias", &"a".repeat(MAX_ALIAS_LEN + 1)).is_err());
+
+        assert_eq!(memo("memo", "line 1\nline 2").unwrap(), "line 1\nline 2");
+        assert!(memo("memo", "bell\u{7}").is_err());
+        // Memos are rejected rather than changed
+        assert!(memo("memo", " padded ").is_err());
+        assert!(memo("memo", "cafe\u{301}").is_err());
+        assert_eq!(memo("memo", "caf\u{e9}").unwrap(), "caf\u{e9}");
+    }
+}
@@ -0,0 +1,229 @@
+//! BIP44 paths of keys derived by the wallet, used to prevent deriving the same index twice.
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use namada_sdk::key::SchemeType;
+use namada_sdk::wallet::DerivationPath;
+use thiserror::Error;
+
+// Largest index of a path component, higher indices overlap with the hardened ones
+pub const MAX_INDEX: u32 = (1 << 31) - 1;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Bip44Error {
+    #[error("Invalid BIP44 path: {0}")]
+    InvalidPath(String),
+    #[error("Path {0} was already derived")]
+    AlreadyDerived(Bip44Path),
+}
+
+/// BIP44 path of a transparent key, m/44'/coin_type'/account'/change'/index'
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bip44Path {
+    pub coin_type: u32,
+    pub account: u32,
+    pub change: u32,
+    pub index: u32,
+}
+
+impl fmt::Display for Bip44Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "m/44'/{}'/{}'/{}'/{}'",
+            self.coin_type, self.account, self.change, self.index
+        )
+    }
+}
+
+impl FromStr for Bip44Path {
+    type Err = Bip44Error;
+
+    /// Parses the path whatever the notation of the hardened components, "'", "h" or "H", and
+    /// whether they are hardened, so paths stored by any scheme map to the same `Bip44Path`
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = || Bip44Error::InvalidPath(path.to_string());
+        let components = path
+            .strip_prefix("m/")
+            .ok_or_else(invalid)?
+            .split('/')
+            .map(|component| {
+                component
+                    .strip_suffix(['\'', 'h', 'H'])
+                    .unwrap_or(component)
+                    .parse::<u32>()
+                    .map_err(|_| invalid())
+            })
+            .collect::<Result<Vec<u32>, Bip44Error>>()?;
+
+        match components[..] {
+            [44, coin_type, account, change, index]
+                if components.iter().all(|component| *component <= MAX_INDEX) =>
+            {
+                Ok(Bip44Path {
+                    coin_type,
+                    account,
+                    change,
+                    index,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Bip44Path {
+    /// Returns the path in the notation of the scheme. SLIP-10 only derives hardened ed25519
+    /// keys, while secp256k1 keys follow BIP44 with unhardened change and index, e.g.
+    /// m/44'/60'/0'/0/0 of EVM wallets.
+    pub fn to_scheme_string(&self, scheme: SchemeType) -> String {
+        match scheme {
+            SchemeType::Secp256k1 => format!(
+                "m/44'/{}'/{}'/{}/{}",
+                self.coin_type, self.account, self.change, self.index
+            ),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns the derivation path of a key of the scheme
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a component doesn't fit 31 bits
+    pub fn derivation_path(&self, scheme: SchemeType) -> Result<DerivationPath, Bip44Error> {
+        let invalid = || Bip44Error::InvalidPath(self.to_scheme_string(scheme));
+        if [self.coin_type, self.account, self.change, self.index]
+            .iter()
+            .any(|component| *component > MAX_INDEX)
+        {
+            return Err(invalid());
+        }
+
+        DerivationPath::from_path_string(&self.to_scheme_string(scheme)).map_err(|_| invalid())
+    }
+}
+
+/// Set of paths derived so far
+#[derive(Debug, Default)]
+pub struct DerivedPaths {
+    paths: BTreeSet<Bip44Path>,
+}
+
+impl DerivedPaths {
+    /// Returns an error if the path was already derived
+    pub fn ensure_not_derived(&self, path: &Bip44Path) -> Result<(), Bip44Error> {
+        if self.paths.contains(path) {
+            return Err(Bip44Error::AlreadyDerived(*path));
+        }
+
+        Ok(())
+    }
+
+    /// Records a derived path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path was already derived
+    pub fn insert(&mut self, path: Bip44Path) -> Result<(), Bip44Error> {
+        if !self.paths.insert(path) {
+            return Err(Bip44Error::AlreadyDerived(path));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the lowest index which was not derived yet for the given coin type and account
+    pub fn next_unused_index(&self, coin_type: u32, account: u32) -> u32 {
+        let used = self
+            .paths
+            .iter()
+            .filter(|path| path.coin_type == coin_type && path.account == account)
+            .map(|path| path.index)
+            .collect::<BTreeSet<u32>>();
+
+        (0..)
+            .find(|index| !used.contains(index))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn can_parse_path() {
+        let path = Bip44Path::from_str("m/44'/877'/1'/0'/2'").unwrap();
+
+        assert_eq!(path.coin_type, 877);
+        assert_eq!(path.account, 1);
+        assert_eq!(path.index, 2);
+        assert_eq!(path.to_string(), "m/44'/877'/1'/0'/2'");
+        assert!(Bip44Path::from_str("m/44'/877'/0'").is_err());
+        assert!(Bip44Path::from_str("m/49'/877'/0'/0'/0'").is_err());
+        assert!(Bip44Path::from_str("m/44'/877'/0'/0'/2147483648'").is_err());
+        assert!(Bip44Path::from_str("m/44'/877'/1'/0'/2''").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn normalizes_hardened_notation() {
+        let path = Bip44Path::from_str("m/44'/877'/1'/0'/2'").unwrap();
+
+        for notation in [
+            "m/44h/877h/1h/0h/2h",
+            "m/44H/877H/1H/0H/2H",
+            "m/44'/877'/1'/0/2",
+        ] {
+            assert_eq!(Bip44Path::from_str(notation), Ok(path));
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn uses_scheme_notation() {
+        let path = Bip44Path::from_str("m/44'/60'/0'/0/3").unwrap();
+
+        assert_eq!(
+            path.to_scheme_string(SchemeType::Ed25519),
+            "m/44'/60'/0'/0'/3'"
+        );
+        assert_eq!(
+            path.to_scheme_string(SchemeType::Secp256k1),
+            "m/44'/60'/0'/0/3"
+        );
+        assert!(path.derivation_path(SchemeType::Secp256k1).is_ok());
+
+        let path = Bip44Path {
+            index: MAX_INDEX + 1,
+            ..path
+        };
+        assert!(path.derivation_path(SchemeType::Ed25519).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_find_next_unused_index() {
+        let mut paths = DerivedPaths::default();
+        for path in [
+            "m/44'/877'/0'/0'/0'",
+            "m/44'/877'/0'/0'/2'",
+            "m/44'/877'/1'/0'/1'",
+        ] {
+            paths.insert(Bip44Path::from_str(path).unwrap()).unwrap();
+        }
+
+        assert_eq!(paths.next_unused_index(877, 0), 1);
+        assert_eq!(paths.next_unused_index(877, 1), 0);
+        assert_eq!(paths.next_unused_index(118, 0), 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn cannot_derive_same_path_twice() {
+        let mut paths = DerivedPaths::default();
+        let path = Bip44Path::from_str("m/44'/877'/0'/0'/0'").unwrap();
+
+        assert!(paths.insert(path).is_ok());
+        assert_eq!(paths.insert(path), Err(Bip44Error::AlreadyDerived(path)));
+    }
+}
@@ -0,0 +1,366 @@
+//! Wallet descriptors - watch-only export of a wallet. A descriptor holds public keys, derivation
+//! paths, viewing keys and labels but never secrets, so another instance can import it to follow
+//! the balances and history of the whole wallet without being able to sign.
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::chain::BlockHeight;
+use namada_sdk::key::{common, PublicKeyHash};
+use namada_sdk::wallet::{alias::Alias, DerivationPath, Wallet, WalletIo};
+use namada_sdk::{ExtendedViewingKey, PaymentAddress};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tsify::Tsify;
+
+use super::Bip44Path;
+
+pub const DESCRIPTOR_VERSION: u8 = 1;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DescriptorError {
+    #[error("Unsupported descriptor version {0}, expected {1}")]
+    UnsupportedVersion(u8, u8),
+    #[error("Invalid descriptor entry {0}: {1}")]
+    InvalidEntry(String, String),
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Tsify)]
+pub struct WalletDescriptor {
+    pub version: u8,
+    pub keys: Vec<KeyDescriptor>,
+    // Established accounts, implicit addresses are derived from the keys
+    pub addresses: Vec<AddressDescriptor>,
+    pub viewing_keys: Vec<ViewingKeyDescriptor>,
+    pub payment_addresses: Vec<AddressDescriptor>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct KeyDescriptor {
+    pub label: String,
+    pub public_key: String,
+    pub address: String,
+    // BIP44 path, e.g. "m/44'/877'/0'/0'/1'", if the key was derived from a mnemonic
+    pub path: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct AddressDescriptor {
+    pub label: String,
+    pub address: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+pub struct ViewingKeyDescriptor {
+    pub label: String,
+    pub viewing_key: String,
+    // Height the key was created at, blocks before it are not synced
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub birthday: Option<u64>,
+}
+
+/// Labels of the imported entries, entries already in the wallet are skipped
+#[derive(Debug, Default, Serialize, Tsify)]
+pub struct ImportSummary {
+    pub imported: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Returns the descriptor of all public entries of the wallet, sorted by label
+pub fn export_descriptor<U: WalletIo>(wallet: &Wallet<U>) -> WalletDescriptor {
+    let mut keys: Vec<KeyDescriptor> = wallet
+        .get_public_keys()
+        .into_iter()
+        .map(|(label, public_key)| KeyDescriptor {
+            path: wallet
+                .find_path_by_pkh(&PublicKeyHash::from(&public_key))
+                .ok()
+                .map(|path| path.to_string()),
+            address: Address::from(&public_key).to_string(),
+            public_key: public_key.to_string(),
+            label,
+        })
+        .collect();
+    keys.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut addresses: Vec<AddressDescriptor> = wallet
+        .get_addresses()
+        .into_iter()
+        .filter(|(_, address)| !matches!(address, Address::Implicit(_)))
+        .map(|(label, address)| AddressDescriptor {
+            label,
+            address: address.to_string(),
+        })
+        .collect();
+    addresses.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut viewing_keys: Vec<ViewingKeyDescriptor> = wallet
+        .get_viewing_keys()
+        .into_iter()
+        .map(|(label, viewing_key)| ViewingKeyDescriptor {
+            birthday: wallet.find_birthday(&label).map(|height| height.0),
+            viewing_key: viewing_key.to_string(),
+            label,
+        })
+        .collect();
+    viewing_keys.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut payment_addresses: Vec<AddressDescriptor> = wallet
+        .get_payment_addrs()
+        .into_iter()
+        .map(|(label, payment_address)| AddressDescriptor {
+            label,
+            address: payment_address.to_string(),
+        })
+        .collect();
+    payment_addresses.sort_by(|a, b| a.label.cmp(&b.label));
+
+    WalletDescriptor {
+        version: DESCRIPTOR_VERSION,
+        keys,
+        addresses,
+        viewing_keys,
+        payment_addresses,
+    }
+}
+
+/// Returns the label, or if it's taken the label suffixed with the account and index of the path.
+/// Importing the same descriptor into the same wallet always results in the same labels.
+pub fn child_label(taken: &BTreeSet<String>, label: &str, path: Option<&Bip44Path>) -> String {
+    if !taken.contains(label) {
+        return label.to_string();
+    }
+
+    let base = match path {
+        Some(path) => format!("{}-{}-{}", label, path.account, path.index),
+        None => label.to_string(),
+    };
+    std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{}-{}", base, n)))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("Labels are not exhausted")
+}
+
+// Entries parsed before the wallet is changed, so an invalid descriptor imports nothing
+struct ParsedDescriptor {
+    keys: Vec<(String, common::PublicKey, Option<DerivationPath>)>,
+    addresses: Vec<(String, Address)>,
+    viewing_keys: Vec<(String, ExtendedViewingKey, Option<u64>)>,
+    payment_addresses: Vec<(String, PaymentAddress)>,
+}
+
+// Largest index of a derivation path component, higher bits mark hardened components
+const MAX_PATH_INDEX: u32 = (1 << 31) - 1;
+
+/// Parses the path as it was exported, keeping the hardened components. Indices which don't fit
+/// 31 bits are rejected instead of overflowing during derivation.
+fn parse_path(label: &str, path: &str) -> Result<DerivationPath, DescriptorError> {
+    let invalid = || DescriptorError::InvalidEntry(label.to_string(), path.to_string());
+    let components = path.strip_prefix("m/").ok_or_else(invalid)?;
+    for component in components.split('/') {
+        let index = component
+            .strip_suffix('\'')
+            .unwrap_or(component)
+            .parse::<u32>()
+            .map_err(|_| invalid())?;
+        if index > MAX_PATH_INDEX {
+            return Err(invalid());
+        }
+    }
+
+    DerivationPath::from_path_string(path).map_err(|_| invalid())
+}
+
+fn parse_entry<T: FromStr>(label: &str, value: &str) -> Result<T, DescriptorError> {
+    T::from_str(value)
+        .map_err(|_| DescriptorError::InvalidEntry(label.to_string(), value.to_string()))
+}
+
+impl WalletDescriptor {
+    fn parse(self) -> Result<ParsedDescriptor, DescriptorError> {
+        if self.version != DESCRIPTOR_VERSION {
+            return Err(DescriptorError::UnsupportedVersion(
+                self.version,
+                DESCRIPTOR_VERSION,
+            ));
+        }
+
+        let mut keys = vec![];
+        for key in self.keys {
+            let public_key: common::PublicKey = parse_entry(&key.label, &key.public_key)?;
+            if Address::from(&public_key).to_string() != key.address {
+                return Err(DescriptorError::InvalidEntry(key.label, key.address));
+            }
+            let path = key
+                .path
+                .map(|path| parse_path(&key.label, &path))
+                .transpose()?;
+            keys.push((key.label, public_key, path));
+        }
+
+        Ok(ParsedDescriptor {
+            keys,
+            addresses: self
+                .addresses
+                .into_iter()
+                .map(|a| {
+                    let address = parse_entry(&a.label, &a.address)?;
+                    Ok((a.label, address))
+                })
+                .collect::<Result<_, DescriptorError>>()?,
+            viewing_keys: self
+                .viewing_keys
+                .into_iter()
+                .map(|vk| {
+                    let viewing_key = parse_entry(&vk.label, &vk.viewing_key)?;
+                    Ok((vk.label, viewing_key, vk.birthday))
+                })
+                .collect::<Result<_, DescriptorError>>()?,
+            payment_addresses: self
+                .payment_addresses
+                .into_iter()
+                .map(|pa| {
+                    let address = parse_entry(&pa.label, &pa.address)?;
+                    Ok((pa.label, address))
+                })
+                .collect::<Result<_, DescriptorError>>()?,
+        })
+    }
+}
+
+/// Adds the public entries of the descriptor to the wallet. Entries already in the wallet are
+/// skipped, labels taken by other entries are replaced with `child_label`.
+///
+/// # Arguments
+///
+/// * `wallet` - Instance of a wallet struct.
+/// * `descriptor` - descriptor returned by `export_descriptor`
+///
+/// # Errors
+///
+/// Returns an error if the version is not supported or an entry is invalid
+pub fn import_descriptor<U: WalletIo>(
+    wallet: &mut Wallet<U>,
+    descriptor: WalletDescriptor,
+) -> Result<ImportSummary, DescriptorError> {
+    let parsed = descriptor.parse()?;
+    let mut summary = ImportSummary::default();
+
+    let public_keys = wallet.get_public_keys();
+    let mut taken: BTreeSet<String> = public_keys.keys().cloned().collect();
+    for (label, public_key, path) in parsed.keys {
+        if public_keys.values().any(|pk| pk == &public_key) {
+            summary.skipped.push(label);
+            continue;
+        }
+        // Only BIP44 paths have an account and index to label children with
+        let bip44_path = path
+            .as_ref()
+            .and_then(|path| Bip44Path::from_str(&path.to_string()).ok());
+        let label = child_label(&taken, &label, bip44_path.as_ref());
+        wallet.store_mut().insert_public_key::<U>(
+            Alias::from(label.clone()),
+            public_key,
+            path,
+            true,
+        );
+        taken.insert(label.clone());
+        summary.imported.push(label);
+    }
+
+    let addresses = wallet.get_addresses();
+    let mut taken: BTreeSet<String> = addresses.keys().cloned().collect();
+    for (label, address) in parsed.addresses {
+        if addresses.values().any(|a| a == &address) {
+            summary.skipped.push(label);
+            continue;
+        }
+        let label = child_label(&taken, &label, None);
+        wallet
+            .store_mut()
+            .insert_address::<U>(Alias::from(label.clone()), address, true);
+        taken.insert(label.clone());
+        summary.imported.push(label);
+    }
+
+    let viewing_keys = wallet.get_viewing_keys();
+    let mut taken: BTreeSet<String> = viewing_keys.keys().cloned().collect();
+    for (label, viewing_key, birthday) in parsed.viewing_keys {
+        if viewing_keys.values().any(|vk| vk == &viewing_key) {
+            summary.skipped.push(label);
+            continue;
+        }
+        let label = child_label(&taken, &label, None);
+        wallet.store_mut().insert_viewing_key::<U>(
+            Alias::from(label.clone()),
+            viewing_key,
+            birthday.map(BlockHeight),
+            None,
+            true,
+        );
+        taken.insert(label.clone());
+        summary.imported.push(label);
+    }
+
+    let payment_addresses = wallet.get_payment_addrs();
+    let mut taken: BTreeSet<String> = payment_addresses.keys().cloned().collect();
+    for (label, payment_address) in parsed.payment_addresses {
+        if payment_addresses.values().any(|pa| pa == &payment_address) {
+            summary.skipped.push(label);
+            continue;
+        }
+        let label = child_label(&taken, &label, None);
+        wallet.store_mut().insert_payment_addr::<U>(
+            Alias::from(label.clone()),
+            payment_address,
+            true,
+        );
+        taken.insert(label.clone());
+        summary.imported.push(label);
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn labels_children_deterministically() {
+        let taken = BTreeSet::from([String::from("alice"), String::from("alice-0-1")]);
+        let path = Bip44Path::from_str("m/44'/877'/0'/0'/1'").unwrap();
+
+        assert_eq!(child_label(&taken, "bob", Some(&path)), "bob");
+        assert_eq!(child_label(&taken, "alice", None), "alice-2");
+        assert_eq!(child_label(&taken, "alice", Some(&path)), "alice-0-1-2");
+
+        let path = Bip44Path::from_str("m/44'/877'/0'/0'/2'").unwrap();
+        assert_eq!(child_label(&taken, "alice", Some(&path)), "alice-0-2");
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_unknown_versions() {
+        let descriptor = WalletDescriptor {
+            version: DESCRIPTOR_VERSION + 1,
+            ..WalletDescriptor::default()
+        };
+
+        assert!(matches!(
+            descriptor.parse(),
+            Err(DescriptorError::UnsupportedVersion(2, 1))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn keeps_path_notation() {
+        let path = parse_path("alice", "m/44'/877'/0'/0/1").unwrap();
+        assert_eq!(path.to_string(), "m/44'/877'/0'/0/1");
+
+        assert!(parse_path("alice", "m/44'/877'/0'/0'/2147483648'").is_err());
+        assert!(parse_path("alice", "m/44'/877'/0'/0/4294967295").is_err());
+        assert!(parse_path("alice", "44'/877'/0'/0'/0'").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Key generation mixing host-provided entropy, e.g. WebAuthn PRF output, into the randomness
+//! of the platform. Both are combined with HKDF-SHA256, so the key stays unpredictable as long as
+//! either source is.
+use hkdf::Hkdf;
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::key::{common, ed25519, secp256k1, SchemeType};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+const SALT: &[u8] = b"namada-interface/keygen/v1";
+const INFO: &[u8] = b"ed25519 secret key";
+const SECP256K1_INFO: &[u8] = b"secp256k1 secret key";
+// Host entropy shorter than this is most likely a mistake, e.g. an empty PRF output
+pub const MIN_EXTRA_ENTROPY_LEN: usize = 16;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum EntropyError {
+    #[error("Extra entropy must be at least {MIN_EXTRA_ENTROPY_LEN} bytes, got {0}")]
+    TooShort(usize),
+}
+
+/// Derives a secret key of the scheme from the platform randomness and optional host entropy
+///
+/// # Arguments
+///
+/// * `os_entropy` - 32 random bytes of the platform
+/// * `extra_entropy` - optional entropy provided by the host
+/// * `scheme` - signing scheme of the key
+pub fn derive_secret_key(
+    os_entropy: &[u8; 32],
+    extra_entropy: Option<&[u8]>,
+    scheme: SchemeType,
+) -> Result<common::SecretKey, EntropyError> {
+    let mut ikm = Zeroizing::new(os_entropy.to_vec());
+    if let Some(extra_entropy) = extra_entropy {
+        if extra_entropy.len() < MIN_EXTRA_ENTROPY_LEN {
+            return Err(EntropyError::TooShort(extra_entropy.len()));
+        }
+        ikm.extend_from_slice(extra_entropy);
+    }
+
+    Ok(hkdf_secret_key(SALT, &ikm, scheme))
+}
+
+/// Expands the input key material into a secret key of the scheme with HKDF-SHA256. Any 32
+/// bytes are an ed25519 key, but secp256k1 keys have to be non-zero and below the curve order,
+/// so outputs outside of it are expanded again with a counter appended to the info. That
+/// happens with a probability of about 2^-128, so in practice the first output is the key.
+pub(super) fn hkdf_secret_key(salt: &[u8], ikm: &[u8], scheme: SchemeType) -> common::SecretKey {
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let expand = |info: &[u8]| {
+        let mut okm = Zeroizing::new([0u8; 32]);
+        hkdf.expand(info, okm.as_mut())
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        okm
+    };
+
+    match scheme {
+        SchemeType::Secp256k1 => (0u32..)
+            .find_map(|counter| {
+                let mut info = SECP256K1_INFO.to_vec();
+                if counter > 0 {
+                    info.extend_from_slice(&counter.to_be_bytes());
+                }
+                secp256k1::SecretKey::try_from_slice(expand(&info).as_ref()).ok()
+            })
+            .map(common::SecretKey::Secp256k1)
+            .expect("A secp256k1 key is found before the counter overflows"),
+        _ => common::SecretKey::Ed25519(
+            ed25519::SecretKey::try_from_slice(expand(INFO).as_ref())
+                .expect("Any 32 bytes are a valid ed25519 secret key"),
+        ),
+    }
+}
+
+/// Generates a secret key of the scheme, mixing in the host entropy if provided
+pub fn generate_secret_key(
+    extra_entropy: Option<&[u8]>,
+    scheme: SchemeType,
+) -> Result<common::SecretKey, EntropyError> {
+    let mut os_entropy = Zeroizing::new([0u8; 32]);
+    OsRng.fill_bytes(os_entropy.as_mut());
+
+    derive_secret_key(&os_entropy, extra_entropy, scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::key::RefTo;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn extra_entropy_changes_key() {
+        let os_entropy = [7u8; 32];
+        let public_key = |extra_entropy: Option<&[u8]>| {
+            derive_secret_key(&os_entropy, extra_entropy, SchemeType::Ed25519)
+                .unwrap()
+                .ref_to()
+        };
+
+        assert_eq!(public_key(None), public_key(None));
+        assert_ne!(public_key(None), public_key(Some(&[1u8; 32])));
+        assert!(matches!(
+            derive_secret_key(&os_entropy, None, SchemeType::Secp256k1).unwrap(),
+            common::SecretKey::Secp256k1(_)
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_short_extra_entropy() {
+        assert!(matches!(
+            derive_secret_key(&[7u8; 32], Some(&[]), SchemeType::Ed25519),
+            Err(EntropyError::TooShort(0))
+        ));
+    }
+}
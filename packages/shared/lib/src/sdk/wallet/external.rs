@@ -0,0 +1,238 @@
+//! Keys derived from a signature of an external wallet, e.g. Keplr or MetaMask, over a fixed
+//! message. Both wallets sign deterministically, so signing the message again restores the same
+//! key and no new seed phrase has to be backed up. The signature is verified to be of the
+//! message by the expected signer first, so a signature of anything else can't be used.
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use namada_sdk::key::{common, SchemeType};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use subtle_encoding::{base64, bech32};
+use thiserror::Error;
+
+use super::entropy::hkdf_secret_key;
+
+const SALT: &[u8] = b"namada-interface/external-signature/v1";
+/// Message the external wallet signs. Changing it changes every derived key.
+pub const EXTERNAL_SIGNATURE_MESSAGE: &str = concat!(
+    "Sign this message to log in to your Namada account.\n\n",
+    "This does not send a transaction or cost any fees. Anyone with this signature controls ",
+    "the Namada account, only sign it on sites you trust."
+);
+// r and s of an ECDSA signature, the recovery byte is dropped as wallets encode it differently
+const SIGNATURE_LEN: usize = 64;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExternalSignatureError {
+    #[error("Signature must be {SIGNATURE_LEN} or {} bytes, got {0}", SIGNATURE_LEN + 1)]
+    InvalidLength(usize),
+    #[error("Signature is empty")]
+    Empty,
+    #[error("Invalid signer {0}, expected an Ethereum or Cosmos address")]
+    InvalidSigner(String),
+    #[error("Signature is not of the message by {0}")]
+    InvalidSignature(String),
+}
+
+// Message hash MetaMask signs with `personal_sign`, see EIP-191
+fn eip191_hash(message: &str) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+
+    hasher.finalize().into()
+}
+
+// Message hash Keplr signs with `signArbitrary`, the sign doc of ADR-36
+fn adr36_hash(message: &str, signer: &str) -> [u8; 32] {
+    let data = String::from_utf8(base64::encode(message)).expect("Base64 is ASCII");
+    let sign_doc = format!(
+        concat!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+            r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":"{}","signer":"{}"}}}}],"#,
+            r#""sequence":"0"}}"#
+        ),
+        data, signer
+    );
+
+    Sha256::digest(sign_doc.as_bytes()).into()
+}
+
+fn eth_address(key: &VerifyingKey) -> Vec<u8> {
+    let point = key.to_encoded_point(false);
+    // The address is the hash of the uncompressed point without its tag byte
+    Keccak256::digest(&point.as_bytes()[1..])[12..].to_vec()
+}
+
+fn cosmos_address(key: &VerifyingKey) -> Vec<u8> {
+    let point = key.to_encoded_point(true);
+    Ripemd160::digest(Sha256::digest(point.as_bytes())).to_vec()
+}
+
+/// Checks that the signature is of `message` by `signer`. Ethereum signers, "0x" followed by
+/// the address, are checked as EIP-191 signatures and Cosmos signers, a bech32 address, as
+/// ADR-36 signatures. The key is recovered from the signature, every recovery id is tried if
+/// the recovery byte is missing.
+///
+/// # Errors
+///
+/// Returns an error if the signer is no address or the signature isn't of the message by it
+pub fn verify_external_signature(
+    message: &str,
+    signature: &[u8],
+    signer: &str,
+) -> Result<(), ExternalSignatureError> {
+    if signature.len() != SIGNATURE_LEN && signature.len() != SIGNATURE_LEN + 1 {
+        return Err(ExternalSignatureError::InvalidLength(signature.len()));
+    }
+    let invalid_signer = || ExternalSignatureError::InvalidSigner(signer.to_string());
+    let signer = signer.trim();
+    let (hash, expected, address): (_, _, fn(&VerifyingKey) -> Vec<u8>) =
+        match signer.strip_prefix("0x") {
+            Some(eth_signer) => (
+                eip191_hash(message),
+                hex::decode(eth_signer.to_lowercase()).map_err(|_| invalid_signer())?,
+                eth_address,
+            ),
+            None => (
+                adr36_hash(message, signer),
+                bech32::decode(signer).map_err(|_| invalid_signer())?.1,
+                cosmos_address,
+            ),
+        };
+    if expected.len() != 20 {
+        return Err(invalid_signer());
+    }
+
+    let invalid_signature = || ExternalSignatureError::InvalidSignature(signer.to_string());
+    let ecdsa_signature =
+        Signature::from_slice(&signature[..SIGNATURE_LEN]).map_err(|_| invalid_signature())?;
+    let recovery_ids = match signature.get(SIGNATURE_LEN) {
+        // Ethereum encodes the recovery id as 27 or 28
+        Some(byte) => vec![RecoveryId::from_byte(byte.checked_sub(27).unwrap_or(*byte))
+            .ok_or_else(invalid_signature)?],
+        None => (0..4).filter_map(RecoveryId::from_byte).collect(),
+    };
+
+    recovery_ids
+        .into_iter()
+        .filter_map(|id| VerifyingKey::recover_from_prehash(&hash, &ecdsa_signature, id).ok())
+        .any(|key| address(&key) == expected)
+        .then_some(())
+        .ok_or_else(invalid_signature)
+}
+
+/// Derives the key of the scheme from the signature of `EXTERNAL_SIGNATURE_MESSAGE` by `signer`
+///
+/// # Arguments
+///
+/// * `signature` - r, s and optionally the recovery byte of the signature
+/// * `signer` - Ethereum or Cosmos address of the external wallet
+/// * `scheme` - signing scheme of the derived key
+///
+/// # Errors
+///
+/// Returns an error if the signature doesn't have the length of an ECDSA signature, is zero or
+/// is not of the message by the signer
+pub fn derive_from_external_signature(
+    signature: &[u8],
+    signer: &str,
+    scheme: SchemeType,
+) -> Result<common::SecretKey, ExternalSignatureError> {
+    if signature.len() != SIGNATURE_LEN && signature.len() != SIGNATURE_LEN + 1 {
+        return Err(ExternalSignatureError::InvalidLength(signature.len()));
+    }
+    if signature[..SIGNATURE_LEN].iter().all(|byte| *byte == 0) {
+        return Err(ExternalSignatureError::Empty);
+    }
+    verify_external_signature(EXTERNAL_SIGNATURE_MESSAGE, signature, signer)?;
+    // The recovery byte is not part of the key material
+    let signature = &signature[..SIGNATURE_LEN];
+
+    Ok(hkdf_secret_key(SALT, signature, scheme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+    use namada_sdk::key::RefTo;
+    use wasm_bindgen_test::*;
+
+    fn signing_key(byte: u8) -> SigningKey {
+        SigningKey::from_bytes(&[byte; 32].into()).unwrap()
+    }
+
+    // Signature with the Ethereum recovery byte and the signer, as returned by MetaMask
+    fn eth_signature(key: &SigningKey, message: &str) -> (Vec<u8>, String) {
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&eip191_hash(message)).unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(27 + recovery_id.to_byte());
+
+        (
+            bytes,
+            format!("0x{}", hex::encode(eth_address(key.verifying_key()))),
+        )
+    }
+
+    #[wasm_bindgen_test]
+    fn derives_keys_deterministically() {
+        let (signature, signer) = eth_signature(&signing_key(7), EXTERNAL_SIGNATURE_MESSAGE);
+        let public_key = |signature: &[u8]| {
+            derive_from_external_signature(signature, &signer, SchemeType::Ed25519)
+                .unwrap()
+                .ref_to()
+        };
+        let (other_signature, other_signer) =
+            eth_signature(&signing_key(8), EXTERNAL_SIGNATURE_MESSAGE);
+
+        assert_eq!(public_key(&signature), public_key(&signature[..64]));
+        assert_ne!(
+            public_key(&signature),
+            derive_from_external_signature(&other_signature, &other_signer, SchemeType::Ed25519)
+                .unwrap()
+                .ref_to()
+        );
+        assert_eq!(
+            derive_from_external_signature(&[7u8; 32], &signer, SchemeType::Ed25519),
+            Err(ExternalSignatureError::InvalidLength(32))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_signatures_of_other_messages_or_signers() {
+        let key = signing_key(7);
+        let (signature, signer) = eth_signature(&key, EXTERNAL_SIGNATURE_MESSAGE);
+        let (other_message, _) = eth_signature(&key, "Sign in to example.com");
+        let (_, other_signer) = eth_signature(&signing_key(8), EXTERNAL_SIGNATURE_MESSAGE);
+
+        assert!(derive_from_external_signature(&signature, &signer, SchemeType::Ed25519).is_ok());
+        assert_eq!(
+            derive_from_external_signature(&other_message, &signer, SchemeType::Ed25519),
+            Err(ExternalSignatureError::InvalidSignature(signer.clone()))
+        );
+        assert_eq!(
+            derive_from_external_signature(&signature, &other_signer, SchemeType::Ed25519),
+            Err(ExternalSignatureError::InvalidSignature(other_signer))
+        );
+        assert_eq!(
+            derive_from_external_signature(&signature, "0x1234", SchemeType::Ed25519),
+            Err(ExternalSignatureError::InvalidSigner(String::from(
+                "0x1234"
+            )))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn verifies_cosmos_signatures() {
+        let key = signing_key(7);
+        let signer = bech32::encode("cosmos", cosmos_address(key.verifying_key()));
+        let (signature, _) = key
+            .sign_prehash_recoverable(&adr36_hash(EXTERNAL_SIGNATURE_MESSAGE, &signer))
+            .unwrap();
+        let signature = signature.to_bytes();
+
+        assert!(verify_external_signature(EXTERNAL_SIGNATURE_MESSAGE, &signature, &signer).is_ok());
+        assert!(verify_external_signature("Sign in to example.com", &signature, &signer).is_err());
+    }
+}
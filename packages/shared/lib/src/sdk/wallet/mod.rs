@@ -1,12 +1,112 @@
 use namada_sdk::{
-    key::common::SecretKey,
-    masp_primitives::zip32::ExtendedFullViewingKey,
-    wallet::{alias::Alias, Wallet, WalletIo},
+    borsh::BorshSerializeExt,
+    chain::BlockHeight,
+    key::{common::SecretKey, PublicKeyHash, SchemeType},
+    masp_primitives::zip32::{self, ChildIndex, ExtendedFullViewingKey},
+    wallet::{alias::Alias, derive_hd_secret_key, Wallet, WalletIo},
     ExtendedSpendingKey, ExtendedViewingKey, PaymentAddress,
 };
+use serde::Serialize;
 use std::str::FromStr;
+use tsify::Tsify;
 use zeroize::Zeroizing;
 
+use super::validate::FieldError;
+use crate::types::amount::StringAmount;
+use crate::types::key;
+
+mod bip44;
+mod descriptor;
+mod entropy;
+mod external;
+
+pub use bip44::{Bip44Error, Bip44Path, DerivedPaths};
+pub use descriptor::{export_descriptor, import_descriptor, WalletDescriptor};
+pub use entropy::generate_secret_key;
+pub use external::{derive_from_external_signature, EXTERNAL_SIGNATURE_MESSAGE};
+
+/// Account with on-chain activity found by the account discovery
+#[derive(Debug, Serialize, Tsify)]
+pub struct DiscoveredAccount {
+    pub path: String,
+    pub address: String,
+    pub public_key: String,
+    pub balance: StringAmount,
+}
+
+/// Derives secret key of the scheme from the seed and BIP44 path. ed25519 keys are derived with
+/// SLIP-10, secp256k1 keys with BIP32.
+///
+/// # Arguments
+///
+/// * `seed` - BIP39 seed
+/// * `path` - BIP44 path
+/// * `scheme` - signing scheme of the key
+///
+/// # Errors
+///
+/// Returns an error if a component of the path doesn't fit 31 bits
+pub fn derive_bip44_key(
+    seed: &[u8],
+    path: &Bip44Path,
+    scheme: SchemeType,
+) -> Result<SecretKey, Bip44Error> {
+    let path = path.derivation_path(scheme)?;
+
+    Ok(derive_hd_secret_key(scheme, seed, path))
+}
+
+/// Shielded account derived from a mnemonic
+#[derive(Debug, Serialize, Tsify)]
+pub struct ShieldedAccount {
+    pub path: String,
+    pub spending_key: String,
+    pub viewing_key: String,
+    pub payment_address: String,
+}
+
+/// Derives ZIP32 spending key from the seed, the same way as @namada/crypto does.
+/// ZIP32 master key is created from the ed25519 key at `transparent_path`, which lets
+/// a single mnemonic restore both transparent and shielded accounts.
+///
+/// # Arguments
+///
+/// * `seed` - BIP39 seed
+/// * `transparent_path` - BIP44 path of the key used as ZIP32 seed
+/// * `account` - ZIP32 account index, path is m/32'/coin_type'/account'
+///
+/// # Errors
+///
+/// Returns an error if the account or a component of the path doesn't fit 31 bits
+pub fn derive_zip32_key(
+    seed: &[u8],
+    transparent_path: &Bip44Path,
+    account: u32,
+) -> Result<ShieldedAccount, Bip44Error> {
+    if account > bip44::MAX_INDEX {
+        return Err(Bip44Error::InvalidPath(format!(
+            "m/32'/{}'/{}'",
+            transparent_path.coin_type, account
+        )));
+    }
+    let zip32_seed = match derive_bip44_key(seed, transparent_path, SchemeType::Ed25519)? {
+        SecretKey::Ed25519(sk) => sk.serialize_to_vec(),
+        _ => unreachable!("BIP44 keys are derived for the ed25519 scheme"),
+    };
+    let master = zip32::ExtendedSpendingKey::master(&zip32_seed);
+    let path = [32, transparent_path.coin_type, account].map(ChildIndex::Hardened);
+    let xsk = zip32::ExtendedSpendingKey::from_path(&master, &path);
+    let xfvk = ExtendedFullViewingKey::from(&xsk);
+    let payment_address: PaymentAddress = xfvk.default_address().1.into();
+
+    Ok(ShieldedAccount {
+        path: format!("m/32'/{}'/{}'", transparent_path.coin_type, account),
+        spending_key: ExtendedSpendingKey::from(xsk).to_string(),
+        viewing_key: ExtendedViewingKey::from(xfvk).to_string(),
+        payment_address: payment_address.to_string(),
+    })
+}
+
 #[cfg(feature = "web")]
 mod wallet_web;
 
@@ -42,13 +142,18 @@ pub fn add_spending_key<U: WalletIo>(wallet: &mut Wallet<U>, xsk: String, alias:
     }
 }
 
-pub fn add_viewing_key<U: WalletIo>(wallet: &mut Wallet<U>, xvk: String, alias: String) {
+pub fn add_viewing_key<U: WalletIo>(
+    wallet: &mut Wallet<U>,
+    xvk: String,
+    alias: String,
+    birthday: Option<u64>,
+) {
     let xvk = ExtendedViewingKey::from_str(&xvk).expect("XVK deserialization failed.");
     let alias = Alias::from(alias);
 
     if wallet
         .store_mut()
-        .insert_viewing_key::<U>(alias.clone(), xvk, None, None, true)
+        .insert_viewing_key::<U>(alias.clone(), xvk, birthday.map(BlockHeight), None, true)
         .is_none()
     {
         panic!("Action cancelled, no changes persisted.");
@@ -88,16 +193,68 @@ pub fn add_default_payment_address<U: WalletIo>(
     }
 }
 
+/// Checks that no wallet entry has the alias, so adding a key doesn't overwrite another one
+///
+/// # Errors
+///
+/// Returns an error if the alias is taken
+pub fn ensure_alias_free<U: WalletIo>(wallet: &Wallet<U>, alias: &str) -> Result<(), FieldError> {
+    if wallet.store().contains_alias(&Alias::from(alias)) {
+        return Err(FieldError::new(
+            "alias",
+            format!("{} already exists", alias),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the BIP44 paths of the keys stored in the wallet, other derivation paths are skipped
+pub fn derived_paths<U: WalletIo>(wallet: &Wallet<U>) -> DerivedPaths {
+    let mut paths = DerivedPaths::default();
+    for (_, public_key) in wallet.get_public_keys() {
+        let path = wallet
+            .find_path_by_pkh(&PublicKeyHash::from(&public_key))
+            .ok()
+            .and_then(|path| Bip44Path::from_str(&path.to_string()).ok());
+        if let Some(path) = path {
+            // Keys stored under several aliases have the same path
+            let _ = paths.insert(path);
+        }
+    }
+
+    paths
+}
+
+/// Adds keypair to the wallet, storing the derivation path alongside if provided. The path is
+/// stored in the notation of the scheme of the key.
+///
+/// # Arguments
+///
+/// * `wallet` - Instance of a wallet struct.
+/// * `secret_key` - secret key of either scheme.
+/// * `alias` - Keypair alias.
+/// * `password` - Optional password used to encrypt the secret key.
+/// * `path` - Optional BIP44 path the key was derived from.
+///
+/// # Errors
+///
+/// Returns an error if the path is invalid for the scheme of the key
 pub fn add_keypair<U: WalletIo>(
     wallet: &mut Wallet<U>,
-    secret_key: String,
+    secret_key: SecretKey,
     alias: String,
     password: Option<String>,
-) {
+    path: Option<Bip44Path>,
+) -> Result<(), Bip44Error> {
     let alias = Alias::from(alias);
-    let secret_key = SecretKey::from_str(&secret_key).expect("SecretKey deserialization failed.");
     let password = password.map(Zeroizing::new);
+    let path = path
+        .map(|path| path.derivation_path(key::scheme_of(&secret_key)))
+        .transpose()?;
     wallet
         .store_mut()
-        .insert_keypair::<U>(alias.clone(), secret_key, password, None, None, true);
+        .insert_keypair::<U>(alias, secret_key, password, None, path, false);
+
+    Ok(())
 }
@@ -0,0 +1,245 @@
+//! Staking - calculations shared by the staking related queries.
+//! Kept free of RPC calls so the results can be audited and tested in one place.
+use namada_sdk::dec::Dec;
+use namada_sdk::proof_of_stake::types::ValidatorState;
+use namada_sdk::token::Amount;
+
+use crate::types::query::{BondWarning, ProtocolParameters};
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+// Share of the total stake, in percent, above which a validator is considered over-delegated
+pub const OVER_DELEGATED_PERCENT: i128 = 10;
+// Part of the max commission change per epoch, in percent, a pending increase has to reach
+pub const COMMISSION_CHANGE_PERCENT: i128 = 90;
+
+/// Returns the epoch in which tokens unbonded at `unbond_epoch` become withdrawable
+///
+/// # Arguments
+///
+/// * `parameters` - protocol parameters
+/// * `unbond_epoch` - epoch in which the unbond was submitted
+pub fn withdraw_epoch(parameters: &ProtocolParameters, unbond_epoch: u64) -> u64 {
+    unbond_epoch
+        + parameters.pipeline_len
+        + parameters.unbonding_len
+        + parameters.cubic_slashing_window_length
+}
+
+/// Returns the average epoch duration in seconds, based on the number of epochs per year
+///
+/// # Arguments
+///
+/// * `parameters` - protocol parameters
+pub fn epoch_duration_secs(parameters: &ProtocolParameters) -> u64 {
+    SECONDS_PER_YEAR / parameters.epochs_per_year.max(1)
+}
+
+/// Returns the estimated unix timestamp(in seconds) at which `epoch` starts. If the epoch has
+/// already started, `now` is returned.
+///
+/// # Arguments
+///
+/// * `parameters` - protocol parameters, `parameters.epoch` is treated as the current epoch
+/// * `epoch` - future epoch
+/// * `now` - current unix timestamp in seconds
+pub fn epoch_time(parameters: &ProtocolParameters, epoch: u64, now: u64) -> u64 {
+    let remaining_epochs = epoch.saturating_sub(parameters.epoch);
+
+    now + remaining_epochs * epoch_duration_secs(parameters)
+}
+
+/// Returns the estimated unix timestamp(in seconds) at which tokens unbonded at `unbond_epoch`
+/// become withdrawable. If the tokens are already withdrawable, `now` is returned.
+///
+/// # Arguments
+///
+/// * `parameters` - protocol parameters, `parameters.epoch` is treated as the current epoch
+/// * `unbond_epoch` - epoch in which the unbond was submitted
+/// * `now` - current unix timestamp in seconds
+pub fn withdraw_time(parameters: &ProtocolParameters, unbond_epoch: u64, now: u64) -> u64 {
+    epoch_time(parameters, withdraw_epoch(parameters, unbond_epoch), now)
+}
+
+/// Returns the projected APR and reward per epoch of staking `amount` with a validator.
+/// Returns None on arithmetic overflow.
+///
+/// # Arguments
+///
+/// * `amount` - staked amount
+/// * `staking_rewards_rate` - yearly staking rewards rate of the network
+/// * `commission_rate` - commission rate of the validator
+/// * `epochs_per_year` - number of epochs per year
+pub fn staking_return(
+    amount: Dec,
+    staking_rewards_rate: Dec,
+    commission_rate: Dec,
+    epochs_per_year: u64,
+) -> Option<(Dec, Dec)> {
+    let apr = staking_rewards_rate.checked_mul(Dec::one().checked_sub(commission_rate)?)?;
+    let reward_per_epoch = amount
+        .checked_mul(apr)?
+        .checked_div(Dec::from(epochs_per_year.max(1)))?;
+
+    Some((apr, reward_per_epoch))
+}
+
+/// State of a validator relevant to bonding with it
+pub struct ValidatorRisk {
+    pub state: Option<ValidatorState>,
+    pub commission_rate: Dec,
+    // Commission rate at the pipeline epoch, when the bond becomes active
+    pub pipeline_commission_rate: Dec,
+    pub max_commission_change_per_epoch: Dec,
+    pub stake: Amount,
+    pub total_stake: Amount,
+}
+
+/// Returns the warnings a delegator should confirm before bonding `amount` to the validator.
+/// Returns None on arithmetic overflow.
+///
+/// # Arguments
+///
+/// * `risk` - state of the validator
+/// * `amount` - bonded amount
+pub fn bond_warnings(risk: &ValidatorRisk, amount: Amount) -> Option<Vec<BondWarning>> {
+    let mut warnings = vec![];
+
+    match risk.state {
+        Some(ValidatorState::Jailed) => warnings.push(BondWarning::new(
+            "jailed",
+            "Validator is jailed and earns no rewards until it is unjailed",
+        )),
+        Some(ValidatorState::Inactive) => warnings.push(BondWarning::new(
+            "inactive",
+            "Validator is inactive and earns no rewards",
+        )),
+        Some(ValidatorState::BelowThreshold) => warnings.push(BondWarning::new(
+            "inactive",
+            "Validator is below the stake threshold and earns no rewards",
+        )),
+        _ => {}
+    }
+
+    let increase = risk
+        .pipeline_commission_rate
+        .checked_sub(risk.commission_rate)
+        .unwrap_or_default();
+    let threshold = risk
+        .max_commission_change_per_epoch
+        .checked_mul(Dec::new(COMMISSION_CHANGE_PERCENT, 2)?)?;
+    if increase > Dec::zero() && increase >= threshold {
+        warnings.push(BondWarning::new(
+            "commission_change",
+            &format!(
+                "Commission rate increases from {} to {}, close to the max change per epoch",
+                risk.commission_rate, risk.pipeline_commission_rate
+            ),
+        ));
+    }
+
+    let stake = risk.stake.checked_add(amount)?;
+    let total_stake = risk.total_stake.checked_add(amount)?;
+    let max_stake = total_stake
+        .mul_floor(Dec::new(OVER_DELEGATED_PERCENT, 2)?)
+        .ok()?;
+    if stake > max_stake {
+        warnings.push(BondWarning::new(
+            "over_delegated",
+            &format!(
+                "Validator would hold more than {}% of the total stake",
+                OVER_DELEGATED_PERCENT
+            ),
+        ));
+    }
+
+    Some(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use wasm_bindgen_test::*;
+
+    fn parameters(epoch: u64) -> ProtocolParameters {
+        ProtocolParameters {
+            epoch,
+            epochs_per_year: 365,
+            min_epoch_duration: 86400,
+            max_block_gas: 0,
+            masp_epoch_multiplier: 1,
+            gas_cost: vec![],
+            pipeline_len: 2,
+            unbonding_len: 21,
+            cubic_slashing_window_length: 1,
+            max_validator_slots: 100,
+            max_inflation_rate: String::from("0.1"),
+            target_staked_ratio: String::from("0.6667"),
+            max_proposal_period: 27,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn can_compute_withdraw_epoch() {
+        assert_eq!(withdraw_epoch(&parameters(10), 10), 34);
+    }
+
+    #[wasm_bindgen_test]
+    fn can_compute_withdraw_time() {
+        let day = 86400;
+
+        assert_eq!(withdraw_time(&parameters(10), 10, 0), 24 * day);
+        assert_eq!(withdraw_time(&parameters(30), 10, 0), 4 * day);
+        // Already withdrawable
+        assert_eq!(withdraw_time(&parameters(40), 10, 100), 100);
+    }
+
+    #[wasm_bindgen_test]
+    fn can_compute_epoch_time() {
+        let day = 86400;
+
+        assert_eq!(epoch_time(&parameters(10), 12, 5), 5 + 2 * day);
+        assert_eq!(epoch_time(&parameters(10), 8, 5), 5);
+    }
+
+    #[wasm_bindgen_test]
+    fn can_compute_staking_return() {
+        let (apr, reward_per_epoch) = staking_return(
+            Dec::from(365),
+            Dec::from_str("0.1").unwrap(),
+            Dec::from_str("0.05").unwrap(),
+            365,
+        )
+        .unwrap();
+
+        assert_eq!(apr, Dec::from_str("0.095").unwrap());
+        assert_eq!(reward_per_epoch, Dec::from_str("0.095").unwrap());
+    }
+
+    #[wasm_bindgen_test]
+    fn can_compute_bond_warnings() {
+        let risk = ValidatorRisk {
+            state: Some(ValidatorState::Consensus),
+            commission_rate: Dec::from_str("0.05").unwrap(),
+            pipeline_commission_rate: Dec::from_str("0.05").unwrap(),
+            max_commission_change_per_epoch: Dec::from_str("0.01").unwrap(),
+            stake: Amount::native_whole(5),
+            total_stake: Amount::native_whole(100),
+        };
+        let warnings = bond_warnings(&risk, Amount::native_whole(1)).unwrap();
+        assert!(warnings.is_empty());
+
+        let risk = ValidatorRisk {
+            state: Some(ValidatorState::Jailed),
+            pipeline_commission_rate: Dec::from_str("0.06").unwrap(),
+            stake: Amount::native_whole(15),
+            ..risk
+        };
+        let kinds: Vec<String> = bond_warnings(&risk, Amount::native_whole(1))
+            .unwrap()
+            .into_iter()
+            .map(|warning| warning.kind)
+            .collect();
+        assert_eq!(kinds, vec!["jailed", "commission_change", "over_delegated"]);
+    }
+}
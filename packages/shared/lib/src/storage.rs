@@ -0,0 +1,90 @@
+//! Storage keys - builders of common storage keys for `Query::query_storage_value` and
+//! `Sdk::abci_query`, so integrators don't have to copy key formats from the ledger.
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::governance::storage::keys as governance_storage;
+use namada_sdk::proof_of_stake::storage_key::bond_key as pos_bond_key;
+use namada_sdk::proof_of_stake::types::BondId;
+use namada_sdk::state::Key;
+use namada_sdk::token::storage_key::balance_key as token_balance_key;
+use wasm_bindgen::prelude::*;
+
+/// Returns the key of the balance of `owner` in `token`
+///
+/// # Errors
+///
+/// Returns an error if any of the addresses is invalid
+#[wasm_bindgen]
+pub fn balance_key(token: &str, owner: &str) -> Result<String, JsError> {
+    let token = Address::from_str(token)?;
+    let owner = Address::from_str(owner)?;
+
+    Ok(token_balance_key(&token, &owner).to_string())
+}
+
+/// Returns the prefix of the bonds of `source` to `validator`, bonds are stored by epoch
+/// under it
+///
+/// # Errors
+///
+/// Returns an error if any of the addresses is invalid
+#[wasm_bindgen]
+pub fn bond_key(source: &str, validator: &str) -> Result<String, JsError> {
+    let bond_id = BondId {
+        source: Address::from_str(source)?,
+        validator: Address::from_str(validator)?,
+    };
+
+    Ok(pos_bond_key(&bond_id).to_string())
+}
+
+/// Returns the key of a field of a proposal
+///
+/// # Arguments
+///
+/// * `id` - id of the proposal
+/// * `field` - one of "content", "author", "type", "voting_start_epoch", "voting_end_epoch",
+///   "activation_epoch", "code", "funds"
+///
+/// # Errors
+///
+/// Returns an error if the field is unknown
+#[wasm_bindgen]
+pub fn proposal_key(id: u64, field: &str) -> Result<String, JsError> {
+    let key: Key = match field {
+        "content" => governance_storage::get_content_key(id),
+        "author" => governance_storage::get_author_key(id),
+        "type" => governance_storage::get_proposal_type_key(id),
+        "voting_start_epoch" => governance_storage::get_voting_start_epoch_key(id),
+        "voting_end_epoch" => governance_storage::get_voting_end_epoch_key(id),
+        "activation_epoch" => governance_storage::get_activation_epoch_key(id),
+        "code" => governance_storage::get_proposal_code_key(id),
+        "funds" => governance_storage::get_funds_key(id),
+        _ => return Err(JsError::new(&format!("Unknown proposal field: {}", field))),
+    };
+
+    Ok(key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+    const OWNER: &str = "tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e";
+
+    #[wasm_bindgen_test]
+    fn keys_can_be_parsed_back() {
+        let key = balance_key(NAM, OWNER).unwrap();
+        let parsed = Key::parse(&key).unwrap();
+
+        assert_eq!(parsed.to_string(), key);
+        assert!(key.contains(OWNER));
+        assert_ne!(
+            proposal_key(1, "content").unwrap(),
+            proposal_key(2, "content").unwrap()
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Token allowlist - tokens known to the interface. Unknown tokens and tokens whose address
+//! looks like a known one are flagged, so airdropped junk assets can't be used for phishing.
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use wasm_bindgen::prelude::*;
+
+// Number of characters compared at both ends of the address data
+const LOOK_ALIKE_CHARS: usize = 6;
+
+thread_local! {
+    // Shared by Query balance scans and Sdk transfer builders
+    static TOKEN_ALLOWLIST: RefCell<TokenAllowlist> = RefCell::new(TokenAllowlist::default());
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenStatus {
+    Known,
+    Unknown,
+    // Shares both ends of the address with the known token
+    LookAlike(String),
+}
+
+impl std::fmt::Display for TokenStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            TokenStatus::Known => "known",
+            TokenStatus::Unknown => "unknown",
+            TokenStatus::LookAlike(_) => "look_alike",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TokenAllowlist {
+    tokens: BTreeSet<String>,
+}
+
+impl TokenAllowlist {
+    pub fn new(tokens: Vec<String>) -> TokenAllowlist {
+        TokenAllowlist {
+            tokens: tokens.into_iter().collect(),
+        }
+    }
+
+    pub fn status(&self, address: &str) -> TokenStatus {
+        if self.tokens.contains(address) {
+            return TokenStatus::Known;
+        }
+
+        self.tokens
+            .iter()
+            .find(|token| looks_alike(token, address))
+            .map(|token| TokenStatus::LookAlike(token.clone()))
+            .unwrap_or(TokenStatus::Unknown)
+    }
+
+    /// Returns true if the token is known, or if no allowlist is set
+    pub fn allows(&self, address: &str) -> bool {
+        self.tokens.is_empty() || self.status(address) == TokenStatus::Known
+    }
+}
+
+// Compares the data part of bech32m addresses, e.g. "tnam1qxyz...abc"
+fn looks_alike(a: &str, b: &str) -> bool {
+    let data = |address: &str| {
+        address
+            .split_once('1')
+            .map(|(_, data)| data.as_bytes().to_vec())
+    };
+
+    match (data(a), data(b)) {
+        (Some(a), Some(b)) if a.len() == b.len() && a.len() > LOOK_ALIKE_CHARS * 2 => {
+            let end = a.len() - LOOK_ALIKE_CHARS;
+            a[..LOOK_ALIKE_CHARS] == b[..LOOK_ALIKE_CHARS] && a[end..] == b[end..]
+        }
+        _ => false,
+    }
+}
+
+/// Returns the status results are marked with, tokens are known if no allowlist is set
+pub fn token_status(address: &Address) -> TokenStatus {
+    let address = address.to_string();
+    TOKEN_ALLOWLIST.with(|allowlist| {
+        let allowlist = allowlist.borrow();
+        if allowlist.allows(&address) {
+            TokenStatus::Known
+        } else {
+            allowlist.status(&address)
+        }
+    })
+}
+
+/// Returns an error if an allowlist is set and the token is unknown or looks like a known token
+pub fn ensure_token_allowed(address: &Address) -> Result<(), JsError> {
+    let address = address.to_string();
+    let status = TOKEN_ALLOWLIST.with(|allowlist| {
+        let allowlist = allowlist.borrow();
+        (!allowlist.allows(&address)).then(|| allowlist.status(&address))
+    });
+
+    match status {
+        None => Ok(()),
+        Some(TokenStatus::LookAlike(token)) => Err(JsError::new(&format!(
+            "Token {} looks like known token {}",
+            address, token
+        ))),
+        Some(_) => Err(JsError::new(&format!(
+            "Token {} is not in the token allowlist",
+            address
+        ))),
+    }
+}
+
+/// Sets tokens known to the interface. Balance scans mark other tokens as unknown or look-alike
+/// and transfer builders reject them. An empty list allows all tokens.
+///
+/// # Arguments
+///
+/// * `tokens` - token addresses
+///
+/// # Errors
+///
+/// Returns an error if any of the addresses is invalid
+#[wasm_bindgen]
+pub fn set_token_allowlist(tokens: Vec<String>) -> Result<(), JsError> {
+    let tokens = tokens
+        .iter()
+        .map(|token| Address::from_str(token).map(|address| address.to_string()))
+        .collect::<Result<Vec<String>, _>>()?;
+
+    TOKEN_ALLOWLIST.with(|allowlist| *allowlist.borrow_mut() = TokenAllowlist::new(tokens));
+
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn is_known_token(address: &str) -> bool {
+    TOKEN_ALLOWLIST.with(|allowlist| allowlist.borrow().status(address) == TokenStatus::Known)
+}
+
+/// Returns one of "known", "unknown", "look_alike"
+#[wasm_bindgen]
+pub fn get_token_status(address: &str) -> String {
+    TOKEN_ALLOWLIST.with(|allowlist| allowlist.borrow().status(address).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const NAM: &str = "tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7";
+
+    #[wasm_bindgen_test]
+    fn flags_unknown_and_look_alike_tokens() {
+        let allowlist = TokenAllowlist::new(vec![NAM.to_string()]);
+
+        assert_eq!(allowlist.status(NAM), TokenStatus::Known);
+        assert_eq!(
+            allowlist.status("tnam1q9gr66zzzzzzzzzzzzzzzzzzzzzzzzzzzz3v6nu7"),
+            TokenStatus::LookAlike(NAM.to_string())
+        );
+        assert_eq!(
+            allowlist.status("tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e"),
+            TokenStatus::Unknown
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_allowlist_allows_all_tokens() {
+        let allowlist = TokenAllowlist::default();
+
+        assert!(allowlist.allows(NAM));
+        assert_eq!(allowlist.status(NAM), TokenStatus::Unknown);
+    }
+}
@@ -1,15 +1,12 @@
 use namada_sdk::borsh::BorshDeserialize;
 use namada_sdk::{
     address,
-    key::{
-        self,
-        common::{PublicKey, SecretKey},
-        PublicKeyHash, RefTo,
-    },
+    key::{common::PublicKey, PublicKeyHash, RefTo},
 };
-use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
+use crate::types::key;
+
 /// Helper function to bech32 encode a public key from bytes
 #[wasm_bindgen]
 pub fn public_key_to_bech32(bytes: Vec<u8>) -> Result<String, JsError> {
@@ -28,22 +25,29 @@ pub struct Address {
 #[wasm_bindgen]
 impl Address {
     /// Address helpers for wasm_bindgen
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - hex encoded secret key
+    /// * `scheme` - "ed25519" or "secp256k1", defaults to "ed25519"
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scheme is not supported or the key is invalid
     #[wasm_bindgen(constructor)]
-    pub fn new(secret: String) -> Address {
-        let private = SecretKey::Ed25519(
-            key::ed25519::SecretKey::from_str(&secret).expect("ed25519 encoding should not fail"),
-        );
+    pub fn new(secret: String, scheme: Option<String>) -> Result<Address, JsError> {
+        let private = key::secret_key(&secret, key::scheme(scheme.as_deref())?)?;
 
         #[allow(clippy::useless_conversion)]
         let public = PublicKey::from(private.ref_to());
         let hash = PublicKeyHash::from(&public);
         let implicit = address::Address::Implicit(address::ImplicitAddress::from(&public));
 
-        Address {
+        Ok(Address {
             implicit,
             public,
             hash,
-        }
+        })
     }
 
     pub fn implicit(&self) -> String {
@@ -68,7 +72,7 @@ mod tests {
     fn can_generate_implicit_address() {
         let secret =
             String::from("1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93");
-        let address = Address::new(secret);
+        let address = Address::new(secret, None).unwrap();
         let implicit = address.implicit();
 
         assert_eq!(implicit, "tnam1qpgk927uh2aqjs92yhycsh08n5yggvltn5nk92zp");
@@ -79,7 +83,7 @@ mod tests {
     fn can_return_correct_public_key() {
         let secret =
             String::from("1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93");
-        let address = Address::new(secret);
+        let address = Address::new(secret, None).unwrap();
         let public = address.public();
 
         assert_eq!(
@@ -93,7 +97,7 @@ mod tests {
     fn can_return_correct_public_key_hash() {
         let secret =
             String::from("1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93");
-        let address = Address::new(secret.clone());
+        let address = Address::new(secret.clone(), None).unwrap();
         let hash = address.hash();
 
         assert_eq!("5162ABDCBABA0940AA25C9885DE79D088433EB9D", hash);
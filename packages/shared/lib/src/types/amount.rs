@@ -0,0 +1,248 @@
+//! Amounts crossing the JS boundary. Amounts are passed as strings, so no precision is lost to
+//! f64. `StringAmount` is a decimal amount of the token, `BaseAmount` an integer of its base
+//! units which JS can convert to bigint. The arithmetic below takes decimal amounts only.
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use namada_sdk::borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use namada_sdk::token::{Amount, DenominatedAmount, Denomination};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// Decimal amount, e.g. "1.5", serialized the same way as a plain string
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Tsify,
+)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct StringAmount(String);
+
+impl StringAmount {
+    pub fn new(amount: String) -> StringAmount {
+        StringAmount(amount)
+    }
+
+    /// Formats an amount of the native token
+    pub fn native(amount: Amount) -> StringAmount {
+        StringAmount(amount.to_string_native())
+    }
+
+    /// Parses the amount, keeping the denomination of the string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid decimal amount
+    pub fn to_denominated(&self) -> Result<DenominatedAmount, JsError> {
+        DenominatedAmount::from_str(&self.0)
+            .map_err(|_| JsError::new(&format!("Invalid amount: {}", self.0)))
+    }
+
+    /// Parses the amount to base units of a token with `denom` decimal places
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid amount or has more decimal places
+    pub fn to_amount(&self, denom: u8) -> Result<Amount, JsError> {
+        Amount::from_str(&self.0, denom)
+            .map_err(|_| JsError::new(&format!("Invalid amount: {}", self.0)))
+    }
+}
+
+impl From<DenominatedAmount> for StringAmount {
+    fn from(amount: DenominatedAmount) -> StringAmount {
+        StringAmount(amount.to_string())
+    }
+}
+
+impl std::fmt::Display for StringAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Integer amount of base units, e.g. "1500000" for 1.5 of a token with 6 decimal places,
+/// serialized the same way as a plain string. Convert it with `amount_from_base_units` before
+/// passing it to the decimal arithmetic.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+    Clone,
+    Debug,
+    PartialEq,
+    Tsify,
+)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct BaseAmount(String);
+
+impl BaseAmount {
+    pub fn new(amount: String) -> BaseAmount {
+        BaseAmount(amount)
+    }
+
+    /// Parses the amount
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a valid integer amount
+    pub fn to_amount(&self) -> Result<Amount, JsError> {
+        Amount::from_str(&self.0, 0u8)
+            .map_err(|_| JsError::new(&format!("Invalid amount: {}", self.0)))
+    }
+}
+
+impl From<Amount> for BaseAmount {
+    fn from(amount: Amount) -> BaseAmount {
+        BaseAmount(amount.raw_amount().to_string())
+    }
+}
+
+impl std::fmt::Display for BaseAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn parse(amount: &str) -> Result<DenominatedAmount, JsError> {
+    StringAmount::new(amount.to_string()).to_denominated()
+}
+
+/// Adds two decimal amounts
+///
+/// # Errors
+///
+/// Returns an error if an amount is invalid or the result overflows
+#[wasm_bindgen]
+pub fn amount_add(a: &str, b: &str) -> Result<String, JsError> {
+    parse(a)?
+        .checked_add(parse(b)?)
+        .map(|amount| amount.to_string())
+        .ok_or_else(|| JsError::new("Amount overflow"))
+}
+
+/// Subtracts `b` from `a`
+///
+/// # Errors
+///
+/// Returns an error if an amount is invalid or `b` is greater than `a`
+#[wasm_bindgen]
+pub fn amount_sub(a: &str, b: &str) -> Result<String, JsError> {
+    parse(a)?
+        .checked_sub(parse(b)?)
+        .map(|amount| amount.to_string())
+        .ok_or_else(|| JsError::new("Amount underflow"))
+}
+
+/// Multiplies two decimal amounts
+///
+/// # Errors
+///
+/// Returns an error if an amount is invalid or the result overflows
+#[wasm_bindgen]
+pub fn amount_mul(a: &str, b: &str) -> Result<String, JsError> {
+    parse(a)?
+        .checked_mul(parse(b)?)
+        .map(|amount| amount.to_string())
+        .ok_or_else(|| JsError::new("Amount overflow"))
+}
+
+/// Compares two decimal amounts, returns -1, 0 or 1
+///
+/// # Errors
+///
+/// Returns an error if an amount is invalid
+#[wasm_bindgen]
+pub fn amount_cmp(a: &str, b: &str) -> Result<i8, JsError> {
+    let ordering = parse(a)?
+        .partial_cmp(&parse(b)?)
+        .ok_or_else(|| JsError::new("Amounts can't be compared"))?;
+
+    Ok(match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    })
+}
+
+/// Converts a decimal amount to an integer string of base units, which can be passed to
+/// BigInt
+///
+/// # Arguments
+///
+/// * `amount` - decimal amount, e.g. "1.5"
+/// * `denom` - decimal places of the token
+///
+/// # Errors
+///
+/// Returns an error if the amount is invalid or has more than `denom` decimal places
+#[wasm_bindgen]
+pub fn amount_to_base_units(amount: &str, denom: u8) -> Result<String, JsError> {
+    let amount = StringAmount::new(amount.to_string()).to_amount(denom)?;
+
+    Ok(amount.raw_amount().to_string())
+}
+
+/// Converts an integer string of base units to a decimal amount
+///
+/// # Arguments
+///
+/// * `base_units` - integer amount, e.g. "1500000"
+/// * `denom` - decimal places of the token
+///
+/// # Errors
+///
+/// Returns an error if `base_units` is not a valid integer
+#[wasm_bindgen]
+pub fn amount_from_base_units(base_units: &str, denom: u8) -> Result<String, JsError> {
+    let amount = StringAmount::new(base_units.to_string()).to_amount(0)?;
+
+    Ok(DenominatedAmount::new(amount, Denomination(denom)).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn checked_arithmetic_keeps_precision() {
+        assert_eq!(amount_add("0.1", "0.2").unwrap(), "0.3");
+        assert_eq!(amount_sub("1", "0.000001").unwrap(), "0.999999");
+        assert!(amount_sub("1", "2").is_err());
+        assert_eq!(amount_cmp("1.10", "1.1").unwrap(), 0);
+        assert_eq!(amount_cmp("2", "10").unwrap(), -1);
+    }
+
+    #[wasm_bindgen_test]
+    fn converts_base_units() {
+        assert_eq!(amount_to_base_units("1.5", 6).unwrap(), "1500000");
+        assert_eq!(
+            amount_to_base_units("123456789012345678901234567890", 6).unwrap(),
+            "123456789012345678901234567890000000"
+        );
+        assert!(amount_to_base_units("0.0000001", 6).is_err());
+        assert_eq!(amount_from_base_units("1500000", 6).unwrap(), "1.5");
+
+        let base = BaseAmount::from(Amount::from_u64(1_500_000));
+        assert_eq!(base.to_string(), "1500000");
+        assert_eq!(amount_from_base_units(&base.to_string(), 6).unwrap(), "1.5");
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_invalid_amounts() {
+        assert!(StringAmount::new(String::from("abc"))
+            .to_denominated()
+            .is_err());
+        assert!(amount_add("1", "").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Signing schemes - secret keys are ed25519 unless secp256k1 is selected, either explicitly or
+//! by the scheme tag of a key in the Namada encoding.
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::ethereum_events::EthAddress;
+use namada_sdk::key::{common, ed25519, secp256k1, RefTo, SchemeType};
+use serde::Serialize;
+use thiserror::Error;
+use tsify::Tsify;
+use zeroize::Zeroizing;
+
+pub const ED25519: &str = "ed25519";
+pub const SECP256K1: &str = "secp256k1";
+// Hex length of a raw 32 byte secret key, the Namada encoding has a leading scheme tag
+const RAW_KEY_HEX_LEN: usize = 64;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum KeyError {
+    #[error("Unsupported signing scheme {0}, expected {ED25519} or {SECP256K1}")]
+    UnsupportedScheme(String),
+    #[error("Invalid {0} secret key")]
+    InvalidKey(String),
+}
+
+/// Namada identity of an Ethereum key
+#[derive(Clone, Debug, PartialEq, Serialize, Tsify)]
+pub struct EthKey {
+    pub address: String,
+    pub public_key: String,
+    // Address of the key on Ethereum, e.g. the one shown by EVM wallets
+    pub eth_address: String,
+}
+
+/// Returns the scheme of the name, ed25519 if no name is given
+///
+/// # Errors
+///
+/// Returns an error if the scheme is neither ed25519 nor secp256k1
+pub fn scheme(name: Option<&str>) -> Result<SchemeType, KeyError> {
+    match name.map(|name| name.trim().to_lowercase()).as_deref() {
+        None | Some(ED25519) => Ok(SchemeType::Ed25519),
+        Some(SECP256K1) => Ok(SchemeType::Secp256k1),
+        Some(name) => Err(KeyError::UnsupportedScheme(name.to_string())),
+    }
+}
+
+/// Returns the scheme of the secret key
+pub fn scheme_of(key: &common::SecretKey) -> SchemeType {
+    match key {
+        common::SecretKey::Secp256k1(_) => SchemeType::Secp256k1,
+        _ => SchemeType::Ed25519,
+    }
+}
+
+pub fn scheme_name(scheme: SchemeType) -> &'static str {
+    match scheme {
+        SchemeType::Secp256k1 => SECP256K1,
+        _ => ED25519,
+    }
+}
+
+/// Parses a hex encoded raw secret key of the scheme
+///
+/// # Errors
+///
+/// Returns an error if the key is not a valid key of the scheme
+pub fn secret_key(key: &str, scheme: SchemeType) -> Result<common::SecretKey, KeyError> {
+    let invalid = || KeyError::InvalidKey(scheme_name(scheme).to_string());
+
+    match scheme {
+        SchemeType::Secp256k1 => secp256k1::SecretKey::from_str(key)
+            .map(common::SecretKey::Secp256k1)
+            .map_err(|_| invalid()),
+        _ => ed25519::SecretKey::from_str(key)
+            .map(common::SecretKey::Ed25519)
+            .map_err(|_| invalid()),
+    }
+}
+
+/// Parses a signing key, either a raw ed25519 key or a key of any scheme in the Namada encoding,
+/// i.e. prefixed with its scheme tag as returned by `SecretKey::to_string`
+///
+/// # Errors
+///
+/// Returns an error if the key is neither
+pub fn signing_key(key: &str) -> Result<common::SecretKey, KeyError> {
+    if key.len() == RAW_KEY_HEX_LEN {
+        return secret_key(key, SchemeType::Ed25519);
+    }
+
+    common::SecretKey::from_str(key).map_err(|_| KeyError::InvalidKey(String::from("encoded")))
+}
+
+/// Parses an Ethereum secret key, e.g. exported from an EVM wallet, as a Namada secp256k1 key
+///
+/// # Arguments
+///
+/// * `key` - hex encoded secret key, with or without the 0x prefix
+///
+/// # Errors
+///
+/// Returns an error if the key is not a valid secp256k1 key
+pub fn eth_key(key: &str) -> Result<(common::SecretKey, EthKey), KeyError> {
+    let key = Zeroizing::new(key.trim().to_lowercase());
+    let key = key.strip_prefix("0x").unwrap_or(key.as_str());
+    let secret_key = secp256k1::SecretKey::from_str(key)
+        .map_err(|_| KeyError::InvalidKey(SECP256K1.to_string()))?;
+    let eth_address = EthAddress::from(&secret_key.ref_to()).to_canonical();
+    let secret_key = common::SecretKey::Secp256k1(secret_key);
+    let public_key = secret_key.ref_to();
+
+    Ok((
+        secret_key,
+        EthKey {
+            address: Address::from(&public_key).to_string(),
+            public_key: public_key.to_string(),
+            eth_address,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const SECRET_KEY: &str = "1498b5467a63dffa2dc9d9e069caf075d16fc33fdd4c3b01bfadae6433767d93";
+
+    #[wasm_bindgen_test]
+    fn parses_keys_of_both_schemes() {
+        let ed25519 = secret_key(SECRET_KEY, scheme(None).unwrap()).unwrap();
+        let secp256k1 = secret_key(SECRET_KEY, scheme(Some("secp256k1")).unwrap()).unwrap();
+
+        assert!(matches!(ed25519, common::SecretKey::Ed25519(_)));
+        assert!(matches!(secp256k1, common::SecretKey::Secp256k1(_)));
+        assert_ne!(ed25519.ref_to(), secp256k1.ref_to());
+
+        // Raw keys are ed25519, encoded keys keep their scheme
+        assert_eq!(signing_key(SECRET_KEY).unwrap(), ed25519);
+        assert_eq!(signing_key(&secp256k1.to_string()).unwrap(), secp256k1);
+        assert_eq!(
+            scheme(Some("sr25519")),
+            Err(KeyError::UnsupportedScheme(String::from("sr25519")))
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn maps_eth_keys() {
+        let (secret_key, imported) =
+            eth_key("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+
+        assert!(matches!(secret_key, common::SecretKey::Secp256k1(_)));
+        assert_eq!(
+            imported.eth_address.to_lowercase(),
+            "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23"
+        );
+        assert_eq!(
+            imported.address,
+            Address::from(&secret_key.ref_to()).to_string()
+        );
+        let (_, upper) =
+            eth_key("0X4C0883A69102937D6231471B5DBB6204FE5129617082792AE468D01A3F362318").unwrap();
+        assert_eq!(upper.address, imported.address);
+        assert!(eth_key("0x1234").is_err());
+    }
+}
@@ -1,3 +1,6 @@
 pub mod address;
+pub mod amount;
+pub mod key;
 pub mod masp;
+pub mod number;
 pub mod query;
@@ -0,0 +1,89 @@
+//! Integers crossing the JS boundary. u64 values don't fit a JS number, so they are passed as
+//! decimal strings. Use with `#[serde(with = "...")]` and `#[tsify(type = "string")]`.
+//! Numbers are still accepted when deserializing, so values stored before stay readable.
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(u64),
+}
+
+impl StringOrNumber {
+    fn parse<E: de::Error>(self) -> Result<u64, E> {
+        match self {
+            StringOrNumber::String(value) => value.trim().parse().map_err(E::custom),
+            StringOrNumber::Number(value) => Ok(value),
+        }
+    }
+}
+
+/// u64 passed as a decimal string
+pub mod u64_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        StringOrNumber::deserialize(deserializer)?.parse()
+    }
+}
+
+/// Optional u64 passed as a decimal string or null
+pub mod option_u64_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u64>, D::Error> {
+        Option::<StringOrNumber>::deserialize(deserializer)?
+            .map(StringOrNumber::parse)
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use wasm_bindgen_test::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Heights {
+        #[serde(with = "u64_string")]
+        height: u64,
+        #[serde(default, with = "option_u64_string")]
+        birthday: Option<u64>,
+    }
+
+    #[wasm_bindgen_test]
+    fn keeps_u64_precision() {
+        let heights = Heights {
+            height: u64::MAX,
+            birthday: None,
+        };
+        let json = serde_json::to_string(&heights).unwrap();
+
+        assert_eq!(json, r#"{"height":"18446744073709551615","birthday":null}"#);
+        assert_eq!(serde_json::from_str::<Heights>(&json).unwrap(), heights);
+        // Numbers stored before are still read
+        assert_eq!(
+            serde_json::from_str::<Heights>(r#"{"height":7,"birthday":9}"#).unwrap(),
+            Heights {
+                height: 7,
+                birthday: Some(9)
+            }
+        );
+        assert!(serde_json::from_str::<Heights>(r#"{"height":"-1"}"#).is_err());
+    }
+}
@@ -1,21 +1,254 @@
 use namada_sdk::borsh::BorshSerialize;
+use namada_sdk::tendermint::merkle::proof::ProofOps;
 use serde::{Deserialize, Serialize};
+use tsify::Tsify;
 
-#[derive(BorshSerialize)]
+use crate::types::amount::{BaseAmount, StringAmount};
+
+#[derive(BorshSerialize, Tsify)]
 #[borsh(crate = "namada_sdk::borsh")]
 pub struct ProposalInfo {
+    #[tsify(type = "bigint")]
     pub id: u64,
     pub content: String,
     pub author: String,
+    #[tsify(type = "bigint")]
     pub start_epoch: u64,
+    #[tsify(type = "bigint")]
     pub end_epoch: u64,
+    #[tsify(type = "bigint")]
     pub grace_epoch: u64,
     pub tally_type: String,
     pub proposal_type: String,
     pub data: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(BorshSerialize, Tsify)]
+#[borsh(crate = "namada_sdk::borsh")]
+pub struct Proposals {
+    pub proposals: Vec<ProposalInfo>,
+}
+
+impl Proposals {
+    pub fn new(proposals: Vec<ProposalInfo>) -> Proposals {
+        Proposals { proposals }
+    }
+}
+
+/// Snapshot of the protocol parameters, valid for the given epoch
+#[derive(Clone, Debug, Serialize, Tsify)]
+pub struct ProtocolParameters {
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub epoch: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub epochs_per_year: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub min_epoch_duration: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub max_block_gas: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub masp_epoch_multiplier: u64,
+    // Vec of (token, minimum gas price)
+    pub gas_cost: Vec<(String, StringAmount)>,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub pipeline_len: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub unbonding_len: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub cubic_slashing_window_length: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub max_validator_slots: u64,
+    pub max_inflation_rate: String,
+    pub target_staked_ratio: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub max_proposal_period: u64,
+}
+
+/// Features enabled on the target chain
+#[derive(Debug, Serialize, Tsify)]
+pub struct ChainCapabilities {
+    // Names of the allowed tx types, e.g. "bond"
+    pub tx_types: Vec<String>,
+    pub masp: bool,
+    pub eth_bridge: bool,
+    // Hash of the vote proposal tx code, changes when governance is upgraded
+    pub governance_version: Option<String>,
+}
+
+/// Projected return of staking with a validator
+#[derive(Debug, Serialize, Tsify)]
+pub struct StakingReturn {
+    pub validator: String,
+    pub staking_rewards_rate: String,
+    pub inflation_rate: String,
+    pub commission_rate: String,
+    // Rewards rate after the validator commission
+    pub apr: String,
+    pub reward_per_epoch: StringAmount,
+}
+
+/// Risk of bonding with a validator the delegator should confirm
+#[derive(Debug, Serialize, Tsify)]
+pub struct BondWarning {
+    // One of "jailed", "inactive", "commission_change", "over_delegated"
+    pub kind: String,
+    pub message: String,
+}
+
+impl BondWarning {
+    pub fn new(kind: &str, message: &str) -> BondWarning {
+        BondWarning {
+            kind: kind.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Tsify)]
+pub struct BondPreflight {
+    pub validator: String,
+    pub warnings: Vec<BondWarning>,
+    // True if the UI should ask for an explicit confirmation
+    pub requires_confirmation: bool,
+}
+
+/// Native tokens of an address split into spendable and locked funds. Tokens are locked by
+/// PoS, as Namada has no vesting accounts.
+#[derive(Debug, Serialize, Tsify)]
+pub struct VestingSchedule {
+    pub address: String,
+    pub spendable: StringAmount,
+    // Bonded, unbonding and withdrawable tokens, slashes excluded
+    pub locked: StringAmount,
+    pub bonded: StringAmount,
+    pub unbonding: StringAmount,
+    // Unbonded tokens which can be withdrawn to the spendable balance
+    pub withdrawable: StringAmount,
+    pub slashed: StringAmount,
+    // Unbonds ordered by the epoch they become withdrawable in
+    pub unlocks: Vec<TokenUnlock>,
+}
+
+/// Unbonded tokens becoming withdrawable at `epoch`
+#[derive(Debug, Serialize, Tsify)]
+pub struct TokenUnlock {
+    pub validator: String,
+    pub amount: StringAmount,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub epoch: u64,
+    // Estimated unix timestamp in seconds
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub time: u64,
+}
+
+/// Shielded sync progress and notes of a viewing key
+#[derive(Debug, Serialize, Tsify)]
+pub struct KeySyncStatus {
+    pub viewing_key: String,
+    // Height the key was last synced to, None if it was not synced in this session
+    #[serde(default, with = "crate::types::number::option_u64_string")]
+    #[tsify(type = "string | null")]
+    pub synced_height: Option<u64>,
+    // The key is fully synced once synced_height reaches it
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub latest_height: u64,
+    pub notes: u32,
+    pub unspent_notes: u32,
+}
+
+/// Voting power of an address on a proposal
+#[derive(Debug, Serialize, Tsify)]
+pub struct VotingPower {
+    pub address: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub proposal_id: u64,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub epoch: u64,
+    pub is_validator: bool,
+    // Total stake of the validator, including delegations. Zero for non-validators.
+    pub validator_power: StringAmount,
+    // Vec of (validator, bonded amount) counted when voting as a delegator
+    pub delegated_power: Vec<(String, StringAmount)>,
+    pub total: StringAmount,
+}
+
+/// Operation of a Merkle proof, as returned by CometBFT, with hex encoded key and data
+#[derive(Debug, Serialize, Tsify)]
+pub struct ProofOp {
+    pub field_type: String,
+    pub key: String,
+    pub data: String,
+}
+
+impl ProofOp {
+    pub fn from_proof(proof: ProofOps) -> Vec<ProofOp> {
+        proof
+            .ops
+            .into_iter()
+            .map(|op| ProofOp {
+                field_type: op.field_type,
+                key: hex::encode(op.key),
+                data: hex::encode(op.data),
+            })
+            .collect()
+    }
+}
+
+/// Response of a raw ABCI query
+#[derive(Debug, Serialize, Tsify)]
+pub struct AbciQueryResult {
+    // Hex encoded response bytes
+    pub data: String,
+    pub info: String,
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    // Merkle proof of the response, None if not requested or not provided by the node
+    pub proof: Option<Vec<ProofOp>>,
+}
+
+/// Voting power of an address at the snapshot epoch of a proposal, as reported by the node.
+/// Only the snapshot epoch is proven, the voting power itself is not.
+#[derive(Debug, Serialize, Tsify)]
+pub struct VotingPowerStatement {
+    pub voting_power: VotingPower,
+    // Hex encoded sha256 of the JSON of voting_power
+    pub statement_hash: String,
+    // Height the snapshot epoch was read at
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    pub height: u64,
+    // Storage key of the proposal voting start epoch and its hex encoded borsh value
+    pub snapshot_key: String,
+    pub snapshot_value: String,
+    // Merkle proof of the snapshot value, None if the node did not provide one
+    pub snapshot_proof: Option<Vec<ProofOp>>,
+}
+
+/// Previously used payment address recovered by the diversifier scan
+#[derive(Debug, Serialize, Tsify)]
+pub struct ScannedPaymentAddress {
+    /// Hex encoded diversifier index
+    pub diversifier_index: String,
+    pub payment_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Tsify)]
 pub struct WasmHash {
     path: String,
     hash: String,
@@ -34,3 +267,16 @@ impl WasmHash {
         self.hash.clone()
     }
 }
+
+/// Holdings of a token summed over the accounts and viewing keys of a portfolio, in base units
+#[derive(Debug, Serialize, Tsify)]
+pub struct PortfolioEntry {
+    pub token: String,
+    // "known", or "unknown" / "look_alike" if the token is outside the token allowlist
+    pub status: String,
+    pub transparent: BaseAmount,
+    pub shielded: BaseAmount,
+    pub bonded: BaseAmount,
+    pub unbonding: BaseAmount,
+    pub total: BaseAmount,
+}
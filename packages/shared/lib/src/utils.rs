@@ -3,11 +3,16 @@ use js_sys::Uint8Array;
 use serde::Serialize;
 use std::fmt::Debug;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     pub fn log(s: &str);
+
+    // Available in window, workers and nodejs
+    #[wasm_bindgen(js_name = "setTimeout")]
+    fn set_timeout(handler: &js_sys::Function, timeout: u32) -> JsValue;
 }
 
 #[allow(dead_code)]
@@ -40,6 +45,20 @@ where
     }
 }
 
+/// Resolves after the given amount of milliseconds
+///
+/// # Arguments
+///
+/// * `ms` - Time to sleep in milliseconds
+pub async fn sleep(ms: u32) -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, ms);
+    });
+    JsFuture::from(promise).await?;
+
+    Ok(())
+}
+
 #[cfg(feature = "dev")]
 pub fn set_panic_hook() {
     web_sys::console::log_1(&"Set panic hook".into());
@@ -0,0 +1,624 @@
+//! Watchers - background polling loops which dispatch events only when the watched state changes.
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use namada_sdk::address::Address;
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::io::Client;
+use namada_sdk::masp_primitives::sapling::ViewingKey;
+use namada_sdk::proof_of_stake::types::ValidatorState;
+use namada_sdk::rpc::{
+    get_all_consensus_validators, get_token_balance, get_validator_state, query_commission_rate,
+    query_epoch,
+};
+use namada_sdk::tendermint_rpc::{query::Query as TmQuery, Order};
+use namada_sdk::token::{Amount, DenominatedAmount, Transfer};
+use namada_sdk::tx::Tx;
+use serde::Serialize;
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::query::{query_wasm_hashes, shielded_balance};
+use crate::rpc_client::HttpClient;
+use crate::sdk::events::EventDispatcher;
+use crate::sdk::history;
+use crate::sdk::transaction::TransactionKind;
+use crate::types::amount::BaseAmount;
+use crate::types::query::WasmHash;
+use crate::utils::{sleep, to_js_result};
+
+/// Handle to a running watcher, polling stops once `stop` is called
+#[wasm_bindgen]
+pub struct WatcherHandle {
+    stopped: Rc<Cell<bool>>,
+}
+
+#[wasm_bindgen]
+impl WatcherHandle {
+    pub fn stop(&self) {
+        self.stopped.set(true);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.get()
+    }
+}
+
+impl WatcherHandle {
+    pub(crate) fn new() -> (WatcherHandle, Rc<Cell<bool>>) {
+        let stopped = Rc::new(Cell::new(false));
+        let handle = WatcherHandle {
+            stopped: stopped.clone(),
+        };
+
+        (handle, stopped)
+    }
+}
+
+/// Polls transparent balances of given owners and tokens every `interval_ms` and dispatches
+/// `SdkEvents.BalanceChanged` for every balance that differs from the previous poll.
+/// Initial balances are dispatched with `previous` set to null.
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `owners` - addresses to watch
+/// * `tokens` - tokens to watch
+/// * `interval_ms` - polling interval in milliseconds
+pub fn start_balance_watcher(
+    client: HttpClient,
+    owners: Vec<Address>,
+    tokens: Vec<Address>,
+    interval_ms: u32,
+) -> WatcherHandle {
+    let (handle, stopped) = WatcherHandle::new();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let dispatcher = EventDispatcher::new();
+        let mut balances: BTreeMap<(Address, Address), String> = BTreeMap::new();
+
+        while !stopped.get() {
+            for owner in owners.iter() {
+                for token in tokens.iter() {
+                    // Failed queries are retried on the next tick
+                    let Ok(amount) = get_token_balance(&client, token, owner, None).await else {
+                        continue;
+                    };
+                    let current = amount.to_string();
+                    let key = (owner.clone(), token.clone());
+
+                    if balances.get(&key) != Some(&current) {
+                        let previous = balances.insert(key, current.clone());
+                        if let Err(err) = dispatcher.balance_changed(
+                            owner.to_string(),
+                            token.to_string(),
+                            previous,
+                            current,
+                        ) {
+                            web_sys::console::warn_1(&JsValue::from(err));
+                        }
+                    }
+                }
+            }
+
+            if sleep(interval_ms).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    handle
+}
+
+/// Change of a watched validator passed to the callback
+#[derive(Debug, Serialize, Tsify)]
+pub struct ValidatorChange {
+    validator: String,
+    // One of "commission", "state"
+    kind: String,
+    previous: String,
+    current: String,
+}
+
+fn validator_state_to_string(state: Option<ValidatorState>) -> String {
+    match state {
+        Some(ValidatorState::Consensus) => "consensus",
+        Some(ValidatorState::BelowCapacity) => "below_capacity",
+        Some(ValidatorState::BelowThreshold) => "below_threshold",
+        Some(ValidatorState::Inactive) => "inactive",
+        Some(ValidatorState::Jailed) => "jailed",
+        None => "unknown",
+    }
+    .to_string()
+}
+
+/// Polls commission rate and state of a validator every `interval_ms` and calls `callback`
+/// with a `ValidatorChange` whenever either of them changes, e.g. when the validator gets
+/// jailed or becomes inactive.
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `validator` - validator address
+/// * `callback` - JS function called with the change
+/// * `interval_ms` - polling interval in milliseconds
+pub fn start_validator_watcher(
+    client: HttpClient,
+    validator: Address,
+    callback: js_sys::Function,
+    interval_ms: u32,
+) -> WatcherHandle {
+    let (handle, stopped) = WatcherHandle::new();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut commission: Option<String> = None;
+        let mut state: Option<String> = None;
+
+        while !stopped.get() {
+            let mut changes = vec![];
+
+            if let Ok(pair) = query_commission_rate(&client, &validator, None).await {
+                let current = pair.commission_rate.unwrap_or_default().to_string();
+                if let Some(previous) = commission.replace(current.clone()) {
+                    if previous != current {
+                        changes.push(("commission", previous, current));
+                    }
+                }
+            }
+
+            if let Ok((validator_state, _)) = get_validator_state(&client, &validator, None).await {
+                let current = validator_state_to_string(validator_state);
+                if let Some(previous) = state.replace(current.clone()) {
+                    if previous != current {
+                        changes.push(("state", previous, current));
+                    }
+                }
+            }
+
+            for (kind, previous, current) in changes {
+                let change = ValidatorChange {
+                    validator: validator.to_string(),
+                    kind: kind.to_string(),
+                    previous,
+                    current,
+                };
+                notify(&callback, change);
+            }
+
+            if sleep(interval_ms).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    handle
+}
+
+/// Change of the consensus validator set at an epoch boundary passed to the callback
+#[derive(Debug, Serialize, Tsify)]
+pub struct ValidatorSetChange {
+    #[serde(with = "crate::types::number::u64_string")]
+    #[tsify(type = "string")]
+    epoch: u64,
+    // Validators which joined the consensus set
+    entered: Vec<String>,
+    // Validators which left the consensus set
+    left: Vec<String>,
+}
+
+/// Returns validators in `current` but not in `previous` and the ones in `previous` only
+pub fn validator_set_diff(
+    previous: &BTreeSet<Address>,
+    current: &BTreeSet<Address>,
+) -> (Vec<Address>, Vec<Address>) {
+    let entered = current.difference(previous).cloned().collect();
+    let left = previous.difference(current).cloned().collect();
+
+    (entered, left)
+}
+
+/// Polls the epoch every `interval_ms` and, when a new epoch starts, compares the consensus
+/// validator set with the one of the previous epoch. `callback` is called with a
+/// `ValidatorSetChange` if any validator entered or left the set. The set of the epoch the
+/// watcher starts in is only recorded.
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `callback` - JS function called with the change
+/// * `interval_ms` - polling interval in milliseconds
+pub fn start_validator_set_watcher(
+    client: HttpClient,
+    callback: js_sys::Function,
+    interval_ms: u32,
+) -> WatcherHandle {
+    let (handle, stopped) = WatcherHandle::new();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut last: Option<(u64, BTreeSet<Address>)> = None;
+
+        while !stopped.get() {
+            // Failed queries are retried on the next tick
+            if let Ok(epoch) = query_epoch(&client).await {
+                let is_new_epoch = last.as_ref().map_or(true, |(last, _)| *last != epoch.0);
+                let validators = if is_new_epoch {
+                    get_all_consensus_validators(&client, epoch).await.ok()
+                } else {
+                    None
+                };
+
+                if let Some(validators) = validators {
+                    let current: BTreeSet<Address> = validators
+                        .into_iter()
+                        .map(|validator| validator.address)
+                        .collect();
+
+                    if let Some((_, previous)) = last.replace((epoch.0, current.clone())) {
+                        let (entered, left) = validator_set_diff(&previous, &current);
+                        if !entered.is_empty() || !left.is_empty() {
+                            let change = ValidatorSetChange {
+                                epoch: epoch.0,
+                                entered: entered.iter().map(Address::to_string).collect(),
+                                left: left.iter().map(Address::to_string).collect(),
+                            };
+                            notify(&callback, change);
+                        }
+                    }
+                }
+            }
+
+            if sleep(interval_ms).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    handle
+}
+
+// Txs fetched per page when searching the new blocks for incoming funds
+const INCOMING_PAGE_SIZE: u8 = 100;
+
+/// Incoming funds passed to the callback
+#[derive(Debug, Serialize, Tsify)]
+pub struct IncomingTransfer {
+    // Address or viewing key which received the funds
+    owner: String,
+    token: String,
+    // Received amount in base units
+    amount: BaseAmount,
+    // One of "transparent", "shielded"
+    kind: String,
+    // Hash of the tx, null for shielded funds as they are found by the sync
+    hash: Option<String>,
+}
+
+/// Returns the funds received by `owners` in an inner tx as (owner, token, amount in base units).
+/// Funds sent back in the same transfer are subtracted, other tokens and amounts below
+/// `min_amount` are left out.
+pub fn received_funds(
+    kind: &TransactionKind,
+    owners: &[Address],
+    tokens: &[Address],
+    min_amount: Amount,
+) -> Vec<(Address, Address, Amount)> {
+    let TransactionKind::Transfer(Transfer {
+        sources, targets, ..
+    }) = kind
+    else {
+        return vec![];
+    };
+
+    targets
+        .iter()
+        .filter(|(account, _)| owners.contains(&account.owner) && tokens.contains(&account.token))
+        .filter_map(|(account, amount)| {
+            let sent = sources
+                .get(account)
+                .map(DenominatedAmount::amount)
+                .unwrap_or_default();
+            let received = amount.amount().checked_sub(sent)?;
+            (!received.is_zero() && received >= min_amount)
+                .then(|| (account.owner.clone(), account.token.clone(), received))
+        })
+        .collect()
+}
+
+// Searches the applied txs of the blocks after `from_height` up to `to_height` for funds received
+// by `owners`
+async fn transparent_incoming(
+    client: &HttpClient,
+    (from_height, to_height): (u64, u64),
+    owners: &[Address],
+    tokens: &[Address],
+    min_amount: Amount,
+    wasm_hashes: &Vec<WasmHash>,
+) -> Result<Vec<IncomingTransfer>, JsError> {
+    let query = TmQuery::from_str(&format!(
+        "tx.height > {} AND tx.height <= {}",
+        from_height, to_height
+    ))?;
+    let mut incoming = vec![];
+    let mut page = 1;
+    loop {
+        let response = client
+            .tx_search(
+                query.clone(),
+                false,
+                page,
+                INCOMING_PAGE_SIZE,
+                Order::Ascending,
+            )
+            .await?;
+
+        for tx_response in &response.txs {
+            let tx = match Tx::try_from_slice(&tx_response.tx) {
+                Ok(tx) if tx_response.tx_result.code.is_ok() => tx,
+                _ => continue,
+            };
+            let applied = tx_response
+                .tx_result
+                .events
+                .iter()
+                .filter(|event| event.kind == "tx/applied")
+                .flat_map(|event| event.attributes.iter())
+                .find(|attribute| attribute.key_str().ok() == Some("batch"))
+                .and_then(|attribute| attribute.value_str().ok())
+                .map(history::applied_inner_txs)
+                .unwrap_or_default();
+
+            for (_, kind, _) in history::applied_kinds(&tx, &applied, wasm_hashes) {
+                for (owner, token, amount) in received_funds(&kind, owners, tokens, min_amount) {
+                    incoming.push(IncomingTransfer {
+                        owner: owner.to_string(),
+                        token: token.to_string(),
+                        amount: BaseAmount::from(amount),
+                        kind: String::from("transparent"),
+                        hash: Some(tx_response.hash.to_string()),
+                    });
+                }
+            }
+        }
+
+        let fetched = page as u64 * u64::from(INCOMING_PAGE_SIZE);
+        if response.txs.is_empty() || fetched >= u64::from(response.total_count) {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(incoming)
+}
+
+// Calls the callback with the serialized value, errors are logged as the watcher keeps running
+fn notify<T: Serialize>(callback: &js_sys::Function, value: T) {
+    let result = to_js_result(value)
+        .map_err(JsValue::from)
+        .and_then(|value| callback.call1(&JsValue::NULL, &value));
+    if let Err(err) = result {
+        web_sys::console::warn_1(&err);
+    }
+}
+
+/// Tracks balances and reports increases of at least `min_amount`. The first balance of an
+/// owner and token is only recorded, so funds received before watching are not reported.
+#[derive(Debug, Default)]
+pub struct IncomingTracker {
+    balances: BTreeMap<(String, Address), Amount>,
+    min_amount: Amount,
+}
+
+impl IncomingTracker {
+    pub fn new(min_amount: Amount) -> IncomingTracker {
+        IncomingTracker {
+            balances: BTreeMap::new(),
+            min_amount,
+        }
+    }
+
+    /// Records the balance and returns the received amount if it grew by at least `min_amount`
+    pub fn observe(&mut self, owner: &str, token: &Address, balance: Amount) -> Option<Amount> {
+        let previous = self
+            .balances
+            .insert((owner.to_string(), token.clone()), balance)?;
+        let received = balance.checked_sub(previous)?;
+
+        (!received.is_zero() && received >= self.min_amount).then_some(received)
+    }
+}
+
+/// Every `interval_ms`, searches the txs applied in the new blocks for transfers to `owners` and
+/// diffs the shielded balances of `viewing_keys`, calling `callback` with an `IncomingTransfer`
+/// for every receipt of at least `min_amount`. Blocks before the watcher started are skipped.
+/// Shielded balances are computed from the stored shielded context, so they only change after
+/// `shielded_sync`. Failed queries are retried on the next tick and callback errors are logged.
+///
+/// # Arguments
+///
+/// * `client` - RPC client
+/// * `owners` - addresses to watch
+/// * `viewing_keys` - encoded viewing keys and their viewing keys to watch
+/// * `tokens` - tokens to watch
+/// * `min_amount` - smallest received amount reported, in base units
+/// * `callback` - JS function called with the incoming transfer
+/// * `interval_ms` - polling interval in milliseconds
+pub fn start_incoming_watcher(
+    client: HttpClient,
+    owners: Vec<Address>,
+    viewing_keys: Vec<(String, ViewingKey)>,
+    tokens: Vec<Address>,
+    min_amount: Amount,
+    callback: js_sys::Function,
+    interval_ms: u32,
+) -> WatcherHandle {
+    let (handle, stopped) = WatcherHandle::new();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut tracker = IncomingTracker::new(min_amount);
+        let mut wasm_hashes = vec![];
+        // Last block searched for transparent funds
+        let mut searched_height: Option<u64> = None;
+
+        while !stopped.get() {
+            let mut incoming = vec![];
+
+            if !owners.is_empty() && wasm_hashes.is_empty() {
+                match query_wasm_hashes(&client).await {
+                    Ok(hashes) => wasm_hashes = hashes,
+                    Err(err) => web_sys::console::warn_1(&JsValue::from(err)),
+                }
+            }
+            // Transfers can't be recognized without the wasm hashes, the search waits for them
+            if !owners.is_empty() && !wasm_hashes.is_empty() {
+                match client.status().await {
+                    Ok(status) => {
+                        let latest = status.sync_info.latest_block_height.value();
+                        match searched_height {
+                            Some(searched) if searched < latest => {
+                                match transparent_incoming(
+                                    &client,
+                                    (searched, latest),
+                                    &owners,
+                                    &tokens,
+                                    min_amount,
+                                    &wasm_hashes,
+                                )
+                                .await
+                                {
+                                    Ok(transfers) => {
+                                        incoming.extend(transfers);
+                                        searched_height = Some(latest);
+                                    }
+                                    Err(err) => web_sys::console::warn_1(&JsValue::from(err)),
+                                }
+                            }
+                            Some(_) => {}
+                            None => searched_height = Some(latest),
+                        }
+                    }
+                    Err(err) => web_sys::console::warn_1(&JsValue::from(JsError::from(err))),
+                }
+            }
+
+            for (xvk, viewing_key) in viewing_keys.iter() {
+                let balances = match shielded_balance(&client, viewing_key, &tokens).await {
+                    Ok(balances) => balances,
+                    Err(err) => {
+                        web_sys::console::warn_1(&JsValue::from(err));
+                        continue;
+                    }
+                };
+                let balances: BTreeMap<Address, Amount> = balances.into_iter().collect();
+                for token in tokens.iter() {
+                    // Tokens without notes have a zero balance
+                    let balance = balances.get(token).copied().unwrap_or_default();
+                    if let Some(amount) = tracker.observe(xvk, token, balance) {
+                        incoming.push(IncomingTransfer {
+                            owner: xvk.clone(),
+                            token: token.to_string(),
+                            amount: BaseAmount::from(amount),
+                            kind: String::from("shielded"),
+                            hash: None,
+                        });
+                    }
+                }
+            }
+
+            for transfer in incoming {
+                notify(&callback, transfer);
+            }
+
+            if sleep(interval_ms).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use namada_sdk::token::Account;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn reports_increases_above_minimum() {
+        let nam = Address::from_str("tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7").unwrap();
+        let mut tracker = IncomingTracker::new(Amount::from_u64(10));
+
+        assert_eq!(tracker.observe("alice", &nam, Amount::from_u64(100)), None);
+        assert_eq!(tracker.observe("alice", &nam, Amount::from_u64(105)), None);
+        assert_eq!(
+            tracker.observe("alice", &nam, Amount::from_u64(125)),
+            Some(Amount::from_u64(20))
+        );
+        // Spending is not incoming
+        assert_eq!(tracker.observe("alice", &nam, Amount::from_u64(50)), None);
+        assert_eq!(tracker.observe("bob", &nam, Amount::from_u64(50)), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn finds_received_funds() {
+        let nam = Address::from_str("tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7").unwrap();
+        let alice = Address::from_str("tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e").unwrap();
+        let bob = Address::from_str("tnam1q8ctk7tr337f85dw69q0rsrggasxjjf5jq2s2wph").unwrap();
+        let account = |owner: &Address| Account {
+            owner: owner.clone(),
+            token: nam.clone(),
+        };
+        let amount = |amount: u64| DenominatedAmount::native(Amount::from_u64(amount));
+        let transfer = |sources, targets| {
+            TransactionKind::Transfer(Transfer {
+                sources: BTreeMap::from(sources),
+                targets: BTreeMap::from(targets),
+                shielded_section_hash: None,
+            })
+        };
+        let owners = [alice.clone()];
+        let tokens = [nam.clone()];
+
+        let received = transfer(
+            [(account(&bob), amount(50))],
+            [(account(&alice), amount(50))],
+        );
+        assert_eq!(
+            received_funds(&received, &owners, &tokens, Amount::from_u64(10)),
+            vec![(alice.clone(), nam.clone(), Amount::from_u64(50))]
+        );
+        assert_eq!(
+            received_funds(&received, &owners, &tokens, Amount::from_u64(100)),
+            vec![]
+        );
+        assert_eq!(
+            received_funds(&received, &owners, &[], Amount::zero()),
+            vec![]
+        );
+
+        // Funds sent back to the same account are not received
+        let to_self = transfer(
+            [(account(&alice), amount(50))],
+            [(account(&alice), amount(50))],
+        );
+        assert_eq!(
+            received_funds(&to_self, &owners, &tokens, Amount::zero()),
+            vec![]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn can_diff_validator_sets() {
+        let a = Address::from_str("tnam1q9gr66cvu4hrzm0sd5kmlnjje82gs3xlfg3v6nu7").unwrap();
+        let b = Address::from_str("tnam1qxgfw7myv4dh0qna4hq0xdg6lx77fzl7dcem8h7e").unwrap();
+        let c = Address::from_str("tnam1q8ctk7tr337f85dw69q0rsrggasxjjf5jq2s2wph").unwrap();
+
+        let previous = BTreeSet::from([a.clone(), b.clone()]);
+        let current = BTreeSet::from([b, c.clone()]);
+
+        assert_eq!(validator_set_diff(&previous, &current), (vec![c], vec![a]));
+        assert_eq!(validator_set_diff(&current, &current), (vec![], vec![]));
+    }
+}
@@ -1,7 +1,7 @@
 /* eslint-disable @typescript-eslint/no-unused-vars */
-import { field } from "@dao-xyz/borsh";
+import { field, vec } from "@dao-xyz/borsh";
 import BigNumber from "bignumber.js";
-import { BondProps } from "../types";
+import { BondProps, BondSplitDataProps, BondSplitProps } from "../types";
 import { BigNumberSerializer } from "./utils";
 
 export class BondMsgValue {
@@ -18,3 +18,30 @@ export class BondMsgValue {
     Object.assign(this, data);
   }
 }
+
+export class BondSplitDataMsgValue {
+  @field({ type: "string" })
+  validator!: string;
+
+  @field(BigNumberSerializer)
+  amount!: BigNumber;
+
+  constructor(data: BondSplitDataProps) {
+    Object.assign(this, data);
+  }
+}
+
+export class BondSplitMsgValue {
+  @field({ type: "string" })
+  source!: string;
+
+  @field({ type: vec(BondSplitDataMsgValue) })
+  bonds!: BondSplitDataMsgValue[];
+
+  constructor({ source, bonds }: BondSplitProps) {
+    Object.assign(this, {
+      source,
+      bonds: bonds.map((bondProps) => new BondSplitDataMsgValue(bondProps)),
+    });
+  }
+}
@@ -0,0 +1,17 @@
+import { field, vec } from "@dao-xyz/borsh";
+import { CustomTxProps } from "../types";
+
+export class CustomTxMsgValue {
+  @field({ type: "string" })
+  name!: string;
+
+  @field({ type: vec("u8") })
+  data!: Uint8Array;
+
+  @field({ type: "string" })
+  display!: string;
+
+  constructor(data: CustomTxProps) {
+    Object.assign(this, data);
+  }
+}
@@ -1,8 +1,10 @@
 export * from "./batchTxResult";
 export * from "./bond";
 export * from "./claimRewards";
+export * from "./customTx";
 export * from "./ethBridgeTransfer";
 export * from "./ibcTransfer";
+export * from "./recovery";
 export * from "./redelegate";
 export * from "./revealPk";
 export * from "./signature";
@@ -17,14 +19,23 @@ export * from "./withdraw";
 export * from "./wrapperTx";
 
 import { BatchTxResultMsgValue } from "./batchTxResult";
-import { BondMsgValue } from "./bond";
+import {
+  BondMsgValue,
+  BondSplitDataMsgValue,
+  BondSplitMsgValue,
+} from "./bond";
 import { ClaimRewardsMsgValue } from "./claimRewards";
+import { CustomTxMsgValue } from "./customTx";
 import { EthBridgeTransferMsgValue } from "./ethBridgeTransfer";
 import { IbcTransferMsgValue } from "./ibcTransfer";
+import { GuardianAccountMsgValue, RecoveryMsgValue } from "./recovery";
 import { RedelegateMsgValue } from "./redelegate";
 import { RevealPkMsgValue } from "./revealPk";
 import { SignatureMsgValue } from "./signature";
 import {
+  MultiSourceTransferMsgValue,
+  MultiTransferDataMsgValue,
+  MultiTransferMsgValue,
   ShieldedTransferDataMsgValue,
   ShieldedTransferMsgValue,
   ShieldingTransferDataMsgValue,
@@ -50,10 +61,15 @@ export type Schema =
   | IbcTransferMsgValue
   | SignatureMsgValue
   | BondMsgValue
+  | BondSplitMsgValue
+  | BondSplitDataMsgValue
   | UnbondMsgValue
   | VoteProposalMsgValue
   | ClaimRewardsMsgValue
   | WithdrawMsgValue
+  | MultiSourceTransferMsgValue
+  | MultiTransferMsgValue
+  | MultiTransferDataMsgValue
   | ShieldedTransferMsgValue
   | ShieldedTransferDataMsgValue
   | ShieldingTransferMsgValue
@@ -69,6 +85,9 @@ export type Schema =
   | UnshieldingTransferMsgValue
   | WrapperTxMsgValue
   | RedelegateMsgValue
+  | GuardianAccountMsgValue
+  | RecoveryMsgValue
   | CommitmentMsgValue
   | TxDetailsMsgValue
-  | RevealPkMsgValue;
+  | RevealPkMsgValue
+  | CustomTxMsgValue;
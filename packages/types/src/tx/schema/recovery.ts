@@ -0,0 +1,42 @@
+/* eslint-disable @typescript-eslint/no-unused-vars */
+import { field, vec } from "@dao-xyz/borsh";
+import { GuardianAccountProps, RecoveryProps } from "../types";
+
+export class GuardianAccountMsgValue {
+  @field({ type: vec("string") })
+  ownerPublicKeys!: string[];
+
+  @field({ type: "u8" })
+  ownerThreshold!: number;
+
+  @field({ type: vec("string") })
+  guardianPublicKeys!: string[];
+
+  @field({ type: "u8" })
+  guardianThreshold!: number;
+
+  constructor(data: GuardianAccountProps) {
+    Object.assign(this, data);
+  }
+}
+
+export class RecoveryMsgValue {
+  @field({ type: "string" })
+  account!: string;
+
+  @field({ type: vec("string") })
+  ownerPublicKeys!: string[];
+
+  @field({ type: "u8" })
+  ownerThreshold!: number;
+
+  @field({ type: vec("string") })
+  guardianPublicKeys!: string[];
+
+  @field({ type: "u8" })
+  guardianThreshold!: number;
+
+  constructor(data: RecoveryProps) {
+    Object.assign(this, data);
+  }
+}
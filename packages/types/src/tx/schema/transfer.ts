@@ -2,6 +2,9 @@
 import { field, option, vec } from "@dao-xyz/borsh";
 import BigNumber from "bignumber.js";
 import {
+  MultiSourceTransferProps,
+  MultiTransferDataProps,
+  MultiTransferProps,
   ShieldedTransferDataProps,
   ShieldedTransferProps,
   ShieldingTransferDataProps,
@@ -47,6 +50,63 @@ export class TransparentTransferMsgValue {
   }
 }
 
+/**
+ * Multi Transfer schemas
+ */
+export class MultiTransferDataMsgValue {
+  @field({ type: "string" })
+  target!: string;
+
+  @field({ type: "string" })
+  token!: string;
+
+  @field(BigNumberSerializer)
+  amount!: BigNumber;
+
+  constructor(data: MultiTransferDataProps) {
+    Object.assign(this, data);
+  }
+}
+
+export class MultiTransferMsgValue {
+  @field({ type: "string" })
+  source!: string;
+
+  @field({ type: vec(MultiTransferDataMsgValue) })
+  data!: MultiTransferDataMsgValue[];
+
+  constructor({ source, data }: MultiTransferProps) {
+    Object.assign(this, {
+      source,
+      data: data.map(
+        (transferProps) => new MultiTransferDataMsgValue(transferProps)
+      ),
+    });
+  }
+}
+
+/**
+ * Multi Source Transfer schema
+ */
+export class MultiSourceTransferMsgValue {
+  // Source accounts in priority order
+  @field({ type: vec("string") })
+  sources!: string[];
+
+  @field({ type: "string" })
+  target!: string;
+
+  @field({ type: "string" })
+  token!: string;
+
+  @field(BigNumberSerializer)
+  amount!: BigNumber;
+
+  constructor(data: MultiSourceTransferProps) {
+    Object.assign(this, data);
+  }
+}
+
 /**
  * Shielded Transfer schemas
  */
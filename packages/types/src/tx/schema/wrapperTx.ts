@@ -26,6 +26,10 @@ export class WrapperTxMsgValue {
   @field({ type: option("bool") })
   force?: boolean;
 
+  // RFC3339 datetime
+  @field({ type: option("string") })
+  expiration?: string;
+
   constructor(data: WrapperTxProps) {
     Object.assign(this, data);
   }
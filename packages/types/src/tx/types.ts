@@ -1,9 +1,17 @@
 import {
   BatchTxResultMsgValue,
   BondMsgValue,
+  BondSplitDataMsgValue,
+  BondSplitMsgValue,
   ClaimRewardsMsgValue,
+  CustomTxMsgValue,
   EthBridgeTransferMsgValue,
+  GuardianAccountMsgValue,
   IbcTransferMsgValue,
+  MultiSourceTransferMsgValue,
+  MultiTransferDataMsgValue,
+  MultiTransferMsgValue,
+  RecoveryMsgValue,
   RedelegateMsgValue,
   ShieldedTransferDataMsgValue,
   ShieldedTransferMsgValue,
@@ -27,8 +35,13 @@ import { RevealPkMsgValue } from "./schema/revealPk";
 
 export type BatchTxResultProps = BatchTxResultMsgValue;
 export type BondProps = BondMsgValue;
+export type BondSplitProps = BondSplitMsgValue;
+export type BondSplitDataProps = BondSplitDataMsgValue;
 export type EthBridgeTransferProps = EthBridgeTransferMsgValue;
 export type IbcTransferProps = IbcTransferMsgValue;
+export type MultiSourceTransferProps = MultiSourceTransferMsgValue;
+export type MultiTransferProps = MultiTransferMsgValue;
+export type MultiTransferDataProps = MultiTransferDataMsgValue;
 export type RedelegateProps = RedelegateMsgValue;
 export type SignatureProps = SignatureMsgValue;
 export type ShieldedTransferProps = ShieldedTransferMsgValue;
@@ -46,6 +59,9 @@ export type SigningDataProps = SigningDataMsgValue;
 export type UnbondProps = UnbondMsgValue;
 export type VoteProposalProps = VoteProposalMsgValue;
 export type ClaimRewardsProps = ClaimRewardsMsgValue;
+export type CustomTxProps = CustomTxMsgValue;
+export type GuardianAccountProps = GuardianAccountMsgValue;
+export type RecoveryProps = RecoveryMsgValue;
 export type WithdrawProps = WithdrawMsgValue;
 export type WrapperTxProps = WrapperTxMsgValue;
 export type RevealPkProps = RevealPkMsgValue;
@@ -60,7 +76,8 @@ export type SupportedTxProps =
   | VoteProposalProps
   | ClaimRewardsProps
   | TransferProps
-  | RevealPkProps;
+  | RevealPkProps
+  | CustomTxProps;
 
 export type CommitmentDetailProps = SupportedTxProps & {
   txType: unknown;